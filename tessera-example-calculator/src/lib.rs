@@ -97,6 +97,7 @@ pub fn desktop_main() -> anyhow::Result<()> {
         TesseraConfig {
             window_title: "Calculator".to_string(),
             sample_count: 1,
+            ..Default::default()
         },
     )
     .unwrap_or_else(|e| error!("App failed to run: {e}"));
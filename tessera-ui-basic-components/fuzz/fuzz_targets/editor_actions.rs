@@ -0,0 +1,172 @@
+//! Drives a [`TextEditorState`] with random edits, caret moves, selections and IME input,
+//! checking after every step that the carets and selection stay on the content and, at the
+//! end, that undoing every step restores the original text.
+//!
+//! Run with `cargo fuzz run editor_actions` from `tessera-ui-basic-components`.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use glyphon::{Action, Cursor, Edit, cosmic_text::Motion};
+use libfuzzer_sys::fuzz_target;
+use tessera_ui::{Dp, winit::event::Ime};
+use tessera_ui_basic_components::{pipelines::TextConstraint, text_editor::TextEditorState};
+
+#[derive(Arbitrary, Debug)]
+enum Step {
+    Insert(char),
+    Enter,
+    Backspace,
+    Delete,
+    Indent,
+    Unindent,
+    Move(Move),
+    Select { start: (u8, u8), end: (u8, u8) },
+    AddCaret(u8, u8),
+    SelectAll,
+    ExpandSelection,
+    Preedit(String),
+    Commit(String),
+    Paste(String),
+    DuplicateLines,
+    MoveLines(bool),
+    ToggleComment,
+    Layout(u16),
+    Undo,
+    Redo,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Move {
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PreviousWord,
+    NextWord,
+    BufferStart,
+    BufferEnd,
+}
+
+impl From<Move> for Motion {
+    fn from(motion: Move) -> Self {
+        match motion {
+            Move::Left => Motion::Left,
+            Move::Right => Motion::Right,
+            Move::Up => Motion::Up,
+            Move::Down => Motion::Down,
+            Move::Home => Motion::Home,
+            Move::End => Motion::End,
+            Move::PreviousWord => Motion::PreviousWord,
+            Move::NextWord => Motion::NextWord,
+            Move::BufferStart => Motion::BufferStart,
+            Move::BufferEnd => Motion::BufferEnd,
+        }
+    }
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    text: String,
+    steps: Vec<Step>,
+}
+
+fuzz_target!(|input: Input| {
+    let mut state = TextEditorState::new(Dp(14.0), None);
+    state.set_history_depth(usize::MAX);
+    state.set_line_comment(Some("//".to_string()));
+    state.editor_mut().insert_string(&input.text, None);
+    state.clear_history();
+    let initial = state.raw_text();
+
+    for step in input.steps {
+        apply(&mut state, step);
+        check_carets(&state);
+    }
+
+    // Drop any composition, it only becomes content once committed
+    state.apply_ime(Ime::Commit(String::new()));
+    // Steps ending on an undo leave redoable edits, which the final redo brings back
+    while state.can_redo() {
+        state.redo();
+    }
+    let edited = state.raw_text();
+    while state.can_undo() {
+        state.undo();
+    }
+    assert_eq!(state.raw_text(), initial, "undoing every step");
+    while state.can_redo() {
+        state.redo();
+    }
+    assert_eq!(state.raw_text(), edited, "redoing every step");
+    check_carets(&state);
+});
+
+fn apply(state: &mut TextEditorState, step: Step) {
+    let cursor = |(line, index): (u8, u8)| Cursor::new(line as usize, index as usize);
+    match step {
+        Step::Insert(c) => state.apply_action(Action::Insert(c)),
+        Step::Enter => state.apply_action(Action::Enter),
+        Step::Backspace => state.apply_action(Action::Backspace),
+        Step::Delete => state.apply_action(Action::Delete),
+        Step::Indent => state.apply_action(Action::Indent),
+        Step::Unindent => state.apply_action(Action::Unindent),
+        Step::Move(motion) => state.apply_action(Action::Motion(motion.into())),
+        Step::Select { start, end } => state.select_range(cursor(start), cursor(end)),
+        Step::AddCaret(line, index) => state.add_caret(cursor((line, index))),
+        Step::SelectAll => state.select_all(),
+        Step::ExpandSelection => {
+            state.expand_selection();
+        }
+        Step::Preedit(text) => state.apply_ime(Ime::Preedit(text, None)),
+        Step::Commit(text) => state.apply_ime(Ime::Commit(text)),
+        Step::Paste(text) => {
+            state.insert_text(&text);
+        }
+        Step::DuplicateLines => {
+            state.duplicate_lines();
+        }
+        Step::MoveLines(up) => {
+            state.move_lines(up);
+        }
+        Step::ToggleComment => {
+            state.toggle_comment();
+        }
+        Step::Layout(width) => {
+            state.text_data(TextConstraint {
+                max_width: Some(width as f32),
+                max_height: None,
+                max_lines: None,
+            });
+        }
+        Step::Undo => {
+            state.undo();
+        }
+        Step::Redo => {
+            state.redo();
+        }
+    }
+}
+
+/// Asserts that every caret sits on a character boundary of an existing line and that the
+/// selection runs forward within the content.
+fn check_carets(state: &TextEditorState) {
+    let text = state.raw_text();
+    state.editor().with_buffer(|buffer| {
+        let carets = state.secondary_carets().iter().map(|caret| caret.cursor);
+        for cursor in carets.chain([state.editor().cursor()]) {
+            let line = buffer.lines.get(cursor.line).expect("caret past the last line");
+            assert!(
+                line.text().is_char_boundary(cursor.index),
+                "caret {cursor:?} off a character boundary of {:?}",
+                line.text()
+            );
+        }
+    });
+    if let Some(selection) = state.selection_range() {
+        assert!(selection.start.offset <= selection.end.offset);
+        assert!(selection.end.offset <= text.len());
+    }
+}
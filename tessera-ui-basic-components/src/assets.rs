@@ -0,0 +1,515 @@
+//! Loading of images, fonts and text files bundled with an application.
+//!
+//! An [`AssetService`] looks assets up by a relative path such as `"icons/close.png"` in a
+//! list of [`AssetProvider`]s: bytes embedded into the binary with [`EmbeddedAssets`], a
+//! directory on disk with [`DirectoryAssets`], or any custom source. The first provider that
+//! has a path wins, so a directory added before the embedded assets can override them, e.g.
+//! with a user theme.
+//!
+//! Loaded bytes and decoded images are cached until [evicted](AssetService::evict). Large
+//! assets can be loaded on a background thread with [`AssetService::load_image_async`],
+//! whose [`AssetHandle`] a component polls every frame.
+//!
+//! The service set with [`set_global`] is the one [`ImageSource::Asset`] resolves paths with,
+//! so images can be named the same way wherever they are used. It reads from the working
+//! directory until one is set.
+//!
+//! # Example
+//!
+//! ```
+//! use tessera_ui_basic_components::assets::{self, AssetService, EmbeddedAssets};
+//!
+//! let service = AssetService::new().with_provider(
+//!     EmbeddedAssets::new()
+//!         // Usually `include_bytes!("../assets/themes/dark.toml")`
+//!         .with_file("themes/dark.toml", b"background = \"#1e1e1e\""),
+//! );
+//! assets::set_global(service);
+//!
+//! let theme = assets::global().load_text("themes/dark.toml").unwrap();
+//! assert!(theme.starts_with("background"));
+//! assert!(assets::global().load_bytes("themes/light.toml").is_err());
+//! ```
+//!
+//! [`ImageSource::Asset`]: crate::image::ImageSource::Asset
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use parking_lot::{Mutex, RwLock};
+
+use crate::{
+    image::{ImageData, decode_image},
+    pipelines::write_font_system,
+};
+
+/// The service [`global`] returns, `None` until the first call or [`set_global`].
+static GLOBAL: RwLock<Option<Arc<AssetService>>> = RwLock::new(None);
+
+/// A source of asset bytes, looked up by the path of the asset.
+pub trait AssetProvider: Send + Sync {
+    /// Returns the bytes of the asset at `path`, or `Ok(None)` if this provider does not
+    /// have it so the next one is asked.
+    fn load(&self, path: &str) -> io::Result<Option<Arc<[u8]>>>;
+}
+
+/// Assets embedded into the binary, usually with `include_bytes!`.
+///
+/// # Example
+/// ```
+/// use tessera_ui_basic_components::assets::{AssetProvider, EmbeddedAssets};
+///
+/// let icons = EmbeddedAssets::from_files([
+///     ("icons/close.svg", b"<svg/>".as_slice()),
+///     ("icons/open.svg", b"<svg></svg>".as_slice()),
+/// ]);
+/// assert_eq!(icons.load("icons/close.svg").unwrap().as_deref(), Some(b"<svg/>".as_slice()));
+/// assert!(icons.load("icons/save.svg").unwrap().is_none());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EmbeddedAssets {
+    files: HashMap<String, &'static [u8]>,
+}
+
+impl EmbeddedAssets {
+    /// Creates an empty set of embedded assets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the embedded assets of `files`, pairs of a path and the bytes of the file.
+    pub fn from_files(files: impl IntoIterator<Item = (&'static str, &'static [u8])>) -> Self {
+        let files = files
+            .into_iter()
+            .map(|(path, bytes)| (normalize(path), bytes))
+            .collect();
+        Self { files }
+    }
+
+    /// Adds the file at `path` with the contents `bytes`.
+    pub fn with_file(mut self, path: &str, bytes: &'static [u8]) -> Self {
+        self.files.insert(normalize(path), bytes);
+        self
+    }
+}
+
+impl AssetProvider for EmbeddedAssets {
+    fn load(&self, path: &str) -> io::Result<Option<Arc<[u8]>>> {
+        Ok(self
+            .files
+            .get(&normalize(path))
+            .map(|bytes| Arc::from(*bytes)))
+    }
+}
+
+/// Assets read from a directory on disk.
+///
+/// Paths are resolved below the directory; paths leading out of it, absolute ones or those
+/// with `..`, are not found.
+#[derive(Clone, Debug)]
+pub struct DirectoryAssets {
+    root: PathBuf,
+}
+
+impl DirectoryAssets {
+    /// Creates a provider reading the assets below `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Returns the directory the assets are read from.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl AssetProvider for DirectoryAssets {
+    fn load(&self, path: &str) -> io::Result<Option<Arc<[u8]>>> {
+        let relative = Path::new(path);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Ok(None);
+        }
+        match fs::read(self.root.join(relative)) {
+            Ok(bytes) => Ok(Some(bytes.into())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Error returned when an asset cannot be loaded.
+#[derive(Clone, Debug)]
+pub enum AssetError {
+    /// No provider has an asset at the path.
+    NotFound(String),
+    /// A provider failed to read the asset at the path.
+    Io(String, Arc<io::Error>),
+    /// The asset at the path is not a valid image, font or UTF-8 text.
+    Invalid(String, String),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "asset `{path}` not found"),
+            Self::Io(path, err) => write!(f, "failed to read asset `{path}`: {err}"),
+            Self::Invalid(path, reason) => write!(f, "invalid asset `{path}`: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(_, err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Looks assets up in a list of [`AssetProvider`]s and caches them. See the
+/// [module documentation](self).
+#[derive(Default)]
+pub struct AssetService {
+    providers: Vec<Arc<dyn AssetProvider>>,
+    bytes: Mutex<HashMap<String, Arc<[u8]>>>,
+    images: Mutex<HashMap<String, Arc<ImageData>>>,
+    /// The number of faces of each font loaded into the font system.
+    fonts: Mutex<HashMap<String, usize>>,
+    /// Handles of the assets being loaded in the background, by path.
+    pending_bytes: Mutex<HashMap<String, AssetHandle<Arc<[u8]>>>>,
+    pending_images: Mutex<HashMap<String, AssetHandle<Arc<ImageData>>>>,
+}
+
+impl AssetService {
+    /// Creates a service without providers, which finds no assets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `provider` after the ones added before, so it is asked for the paths they do not
+    /// have.
+    pub fn with_provider(mut self, provider: impl AssetProvider + 'static) -> Self {
+        self.providers.push(Arc::new(provider));
+        self
+    }
+
+    /// Returns the bytes of the asset at `path` from the first provider that has it.
+    pub fn load_bytes(&self, path: &str) -> Result<Arc<[u8]>, AssetError> {
+        let path = normalize(path);
+        if let Some(bytes) = self.bytes.lock().get(&path) {
+            return Ok(bytes.clone());
+        }
+        for provider in &self.providers {
+            let loaded = provider
+                .load(&path)
+                .map_err(|err| AssetError::Io(path.clone(), Arc::new(err)))?;
+            if let Some(bytes) = loaded {
+                self.bytes.lock().insert(path, bytes.clone());
+                return Ok(bytes);
+            }
+        }
+        Err(AssetError::NotFound(path))
+    }
+
+    /// Returns the asset at `path` as UTF-8 text, e.g. a syntax definition or a color theme.
+    pub fn load_text(&self, path: &str) -> Result<String, AssetError> {
+        let bytes = self.load_bytes(path)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|err| AssetError::Invalid(normalize(path), err.to_string()))
+    }
+
+    /// Returns the asset at `path` decoded as a PNG or JPEG image, ready for the
+    /// [`image`](crate::image::image) component.
+    pub fn load_image(&self, path: &str) -> Result<Arc<ImageData>, AssetError> {
+        let path = normalize(path);
+        if let Some(image) = self.images.lock().get(&path) {
+            return Ok(image.clone());
+        }
+        let bytes = self.load_bytes(&path)?;
+        let image = decode_image(&bytes)
+            .map(Arc::new)
+            .map_err(|err| AssetError::Invalid(path.clone(), err.to_string()))?;
+        self.images.lock().insert(path, image.clone());
+        Ok(image)
+    }
+
+    /// Loads the font file at `path` into the font system, after which text can use its
+    /// family. Returns the number of faces it contains.
+    ///
+    /// A font is only loaded once, later calls return its faces without adding them again.
+    /// Evicting it or clearing the cache leaves it in the font system.
+    pub fn load_font(&self, path: &str) -> Result<usize, AssetError> {
+        let path = normalize(path);
+        let mut fonts = self.fonts.lock();
+        if let Some(&faces) = fonts.get(&path) {
+            return Ok(faces);
+        }
+        let bytes = self.load_bytes(&path)?;
+        let mut font_system = write_font_system();
+        let database = font_system.db_mut();
+        let before = database.len();
+        database.load_font_data(bytes.to_vec());
+        match database.len() - before {
+            0 => Err(AssetError::Invalid(path, "no font faces found".to_string())),
+            faces => {
+                fonts.insert(path, faces);
+                Ok(faces)
+            }
+        }
+    }
+
+    /// Starts decoding the image at `path` on a background thread, or returns it at once if
+    /// it is cached.
+    ///
+    /// While the image is loading, further calls for it return the same handle rather than
+    /// starting another thread.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tessera_ui_basic_components::assets;
+    ///
+    /// let photo = assets::global().load_image_async("photos/cover.jpg");
+    /// // Every frame
+    /// match photo.get() {
+    ///     Some(Ok(image)) => { /* draw it */ }
+    ///     Some(Err(err)) => eprintln!("{err}"),
+    ///     None => { /* show a placeholder */ }
+    /// }
+    /// ```
+    pub fn load_image_async(self: &Arc<Self>, path: &str) -> AssetHandle<Arc<ImageData>> {
+        if let Some(image) = self.images.lock().get(&normalize(path)) {
+            return AssetHandle::ready(Ok(image.clone()));
+        }
+        self.spawn(
+            path,
+            |service| &service.pending_images,
+            |service, path| service.load_image(path),
+        )
+    }
+
+    /// Starts reading the asset at `path` on a background thread, or returns it at once if
+    /// it is cached. Like [`Self::load_image_async`], an asset is only read once at a time.
+    pub fn load_bytes_async(self: &Arc<Self>, path: &str) -> AssetHandle<Arc<[u8]>> {
+        if let Some(bytes) = self.bytes.lock().get(&normalize(path)) {
+            return AssetHandle::ready(Ok(bytes.clone()));
+        }
+        self.spawn(
+            path,
+            |service| &service.pending_bytes,
+            |service, path| service.load_bytes(path),
+        )
+    }
+
+    /// Drops the cached bytes and image of the asset at `path`, so the next load reads it
+    /// again.
+    pub fn evict(&self, path: &str) {
+        let path = normalize(path);
+        self.bytes.lock().remove(&path);
+        self.images.lock().remove(&path);
+    }
+
+    /// Drops all cached assets.
+    pub fn clear_cache(&self) {
+        self.bytes.lock().clear();
+        self.images.lock().clear();
+    }
+
+    /// Loads the asset at `path` with `load` on a new thread, unless it is in `pending`
+    /// already, and returns its handle.
+    fn spawn<T: Clone + Send + 'static>(
+        self: &Arc<Self>,
+        path: &str,
+        pending: fn(&AssetService) -> &Mutex<HashMap<String, AssetHandle<T>>>,
+        load: fn(&AssetService, &str) -> Result<T, AssetError>,
+    ) -> AssetHandle<T> {
+        let path = normalize(path);
+        let mut pending_handles = pending(self).lock();
+        if let Some(handle) = pending_handles.get(&path) {
+            return handle.clone();
+        }
+        let handle = AssetHandle {
+            result: Arc::new(Mutex::new(None)),
+        };
+        pending_handles.insert(path.clone(), handle.clone());
+        let (service, result) = (self.clone(), handle.result.clone());
+        std::thread::spawn(move || {
+            *result.lock() = Some(load(&service, &path));
+            // Loaded assets are cached now, failed ones can be tried again
+            pending(&service).lock().remove(&path);
+        });
+        handle
+    }
+}
+
+/// An asset being loaded in the background, see [`AssetService::load_image_async`].
+#[derive(Clone)]
+pub struct AssetHandle<T> {
+    result: Arc<Mutex<Option<Result<T, AssetError>>>>,
+}
+
+impl<T: Clone> AssetHandle<T> {
+    fn ready(result: Result<T, AssetError>) -> Self {
+        Self {
+            result: Arc::new(Mutex::new(Some(result))),
+        }
+    }
+
+    /// Returns the loaded asset or the error loading it, or `None` while it is loading.
+    pub fn get(&self) -> Option<Result<T, AssetError>> {
+        self.result.lock().clone()
+    }
+
+    /// Returns `true` once the asset is loaded or failed to load.
+    pub fn is_done(&self) -> bool {
+        self.result.lock().is_some()
+    }
+}
+
+/// Returns the service [`ImageSource::Asset`](crate::image::ImageSource::Asset) and the
+/// application look assets up with: the one set with [`set_global`], or else one reading
+/// from the working directory.
+pub fn global() -> Arc<AssetService> {
+    if let Some(service) = GLOBAL.read().as_ref() {
+        return service.clone();
+    }
+    GLOBAL
+        .write()
+        .get_or_insert_with(|| {
+            Arc::new(AssetService::new().with_provider(DirectoryAssets::new(".")))
+        })
+        .clone()
+}
+
+/// Makes `service` the one [`global`] returns.
+pub fn set_global(service: AssetService) {
+    *GLOBAL.write() = Some(Arc::new(service));
+}
+
+/// Returns `path` with forward slashes and without leading `./` or `/`, so the same asset is
+/// found and cached under one key however it is written.
+fn normalize(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut path = path.as_str();
+    loop {
+        if let Some(rest) = path.strip_prefix("./") {
+            path = rest;
+        } else if let Some(rest) = path.strip_prefix('/') {
+            path = rest;
+        } else {
+            return path.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_providers_in_order() {
+        let service = AssetService::new()
+            .with_provider(EmbeddedAssets::new().with_file("theme.toml", b"override"))
+            .with_provider(
+                EmbeddedAssets::new()
+                    .with_file("theme.toml", b"default")
+                    .with_file("./icons\\close.svg", b"<svg/>"),
+            );
+        assert_eq!(service.load_text("theme.toml").unwrap(), "override");
+        assert_eq!(&*service.load_bytes("/icons/close.svg").unwrap(), b"<svg/>");
+        assert!(matches!(
+            service.load_bytes("missing.png"),
+            Err(AssetError::NotFound(path)) if path == "missing.png"
+        ));
+    }
+
+    #[test]
+    fn test_font_loaded_once() {
+        let font = {
+            let font_system = write_font_system();
+            let database = font_system.db();
+            let face = database.faces().next().expect("a system font");
+            database
+                .with_face_data(face.id, |data, _| data.to_vec())
+                .unwrap()
+        };
+        let root = std::env::temp_dir().join("tessera-assets-font-test");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("font.ttf"), font).unwrap();
+        fs::write(root.join("broken.ttf"), b"not a font").unwrap();
+        let service = AssetService::new().with_provider(DirectoryAssets::new(&root));
+
+        let face_count = || write_font_system().db().len();
+        let before = face_count();
+        let faces = service.load_font("font.ttf").unwrap();
+        assert!(faces > 0);
+        assert_eq!(face_count(), before + faces);
+        service.evict("font.ttf");
+        assert_eq!(service.load_font("./font.ttf").unwrap(), faces);
+        assert_eq!(face_count(), before + faces);
+
+        assert!(matches!(
+            service.load_font("broken.ttf"),
+            Err(AssetError::Invalid(path, _)) if path == "broken.ttf"
+        ));
+        assert!(service.load_font("broken.ttf").is_err());
+    }
+
+    /// A provider that takes a while to read and counts its reads.
+    struct SlowAssets(std::sync::atomic::AtomicUsize);
+
+    impl AssetProvider for SlowAssets {
+        fn load(&self, _path: &str) -> io::Result<Option<Arc<[u8]>>> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            Ok(Some(Arc::from(b"data".as_slice())))
+        }
+    }
+
+    #[test]
+    fn test_pending_load_is_shared() {
+        let provider = Arc::new(SlowAssets(Default::default()));
+        let mut service = AssetService::new();
+        service.providers.push(provider.clone());
+        let service = Arc::new(service);
+
+        let first = service.load_bytes_async("big.bin");
+        let second = service.load_bytes_async("./big.bin");
+        assert!(Arc::ptr_eq(&first.result, &second.result));
+        while !first.is_done() {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(&*second.get().unwrap().unwrap(), b"data");
+        assert_eq!(provider.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Once loaded, the cached bytes are returned without a thread
+        assert!(service.load_bytes_async("big.bin").is_done());
+    }
+
+    #[test]
+    fn test_directory_stays_below_root() {
+        let root = std::env::temp_dir().join("tessera-assets-test");
+        fs::create_dir_all(root.join("icons")).unwrap();
+        fs::write(root.join("icons/close.svg"), b"<svg/>").unwrap();
+        let assets = DirectoryAssets::new(root.join("icons"));
+
+        assert_eq!(
+            assets.load("close.svg").unwrap().as_deref(),
+            Some(b"<svg/>".as_slice())
+        );
+        assert!(assets.load("open.svg").unwrap().is_none());
+        assert!(assets.load("../icons/close.svg").unwrap().is_none());
+        assert!(
+            assets
+                .load(root.join("icons/close.svg").to_str().unwrap())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -89,7 +89,7 @@ use std::{
 };
 
 use parking_lot::{Mutex, RwLock};
-use tessera_ui::{Color, DimensionValue, tessera};
+use tessera_ui::{Color, DimensionValue, clock, tessera};
 
 use crate::{
     RippleState,
@@ -282,13 +282,13 @@ impl BottomNavBarState {
         if self.selected != index {
             self.previous_selected = self.selected;
             self.selected = index;
-            self.anim_start_time = Some(Instant::now());
+            self.anim_start_time = Some(clock::now());
         }
     }
 
     fn animation_progress(&mut self) -> Option<f32> {
         if let Some(start_time) = self.anim_start_time {
-            let elapsed = start_time.elapsed();
+            let elapsed = clock::elapsed(start_time);
             if elapsed < ANIMATION_DURATION {
                 Some(animation::easing(
                     elapsed.as_secs_f32() / ANIMATION_DURATION.as_secs_f32(),
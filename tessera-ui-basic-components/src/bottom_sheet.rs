@@ -87,7 +87,7 @@ use std::{
 
 use derive_builder::Builder;
 use parking_lot::RwLock;
-use tessera_ui::{Color, DimensionValue, Dp, Px, PxPosition, tessera, winit};
+use tessera_ui::{Color, DimensionValue, Dp, Px, PxPosition, clock, tessera, winit};
 
 use crate::{
     animation,
@@ -163,9 +163,9 @@ impl BottomSheetProviderState {
     pub fn open(&mut self) {
         if !self.is_open {
             self.is_open = true;
-            let mut timer = Instant::now();
+            let mut timer = clock::now();
             if let Some(old_timer) = self.timer {
-                let elapsed = old_timer.elapsed();
+                let elapsed = clock::elapsed(old_timer);
                 if elapsed < ANIM_TIME {
                     timer += ANIM_TIME - elapsed;
                 }
@@ -181,9 +181,9 @@ impl BottomSheetProviderState {
     pub fn close(&mut self) {
         if self.is_open {
             self.is_open = false;
-            let mut timer = Instant::now();
+            let mut timer = clock::now();
             if let Some(old_timer) = self.timer {
-                let elapsed = old_timer.elapsed();
+                let elapsed = clock::elapsed(old_timer);
                 if elapsed < ANIM_TIME {
                     timer += ANIM_TIME - elapsed;
                 }
@@ -198,7 +198,7 @@ fn calc_progress_from_timer(timer: Option<&Instant>) -> f32 {
     let raw = match timer {
         None => 1.0,
         Some(t) => {
-            let elapsed = t.elapsed();
+            let elapsed = clock::elapsed(*t);
             if elapsed >= ANIM_TIME {
                 1.0
             } else {
@@ -444,7 +444,7 @@ pub fn bottom_sheet_provider(
     let (is_open, timer_opt) = snapshot_state(&state);
 
     // Fast exit when nothing to render.
-    if !(is_open || timer_opt.is_some_and(|t| t.elapsed() < ANIM_TIME)) {
+    if !(is_open || timer_opt.is_some_and(|t| clock::elapsed(t) < ANIM_TIME)) {
         return;
     }
 
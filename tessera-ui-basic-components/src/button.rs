@@ -14,8 +14,9 @@
 //!
 //! # Features
 //! - Customizable appearance: color, shape, border, padding, ripple, hover
+//! - Visual states: hovered, pressed, focused and disabled
 //! - Flexible sizing: explicit width/height or content-based
-//! - Event handling: on_click callback
+//! - Event handling: on_click callback, also from Enter or Space while focused
 //! - Composable: can wrap any child component
 //! - Builder and fluent APIs for ergonomic usage
 //!
@@ -40,8 +41,21 @@ pub struct ButtonArgs {
     #[builder(default = "Color::new(0.2, 0.5, 0.8, 1.0)")]
     pub color: Color,
     /// The hover color of the button (RGBA). If None, no hover effect is applied.
+    /// A button focused with the keyboard is drawn in it as well.
     #[builder(default)]
     pub hover_color: Option<Color>,
+    /// The color of the button while it is held down (RGBA). If None, pressing it only
+    /// shows the ripple.
+    #[builder(default)]
+    pub pressed_color: Option<Color>,
+    /// Whether the button is disabled: it is drawn in `disabled_color` and ignores clicks,
+    /// hover and the keyboard. The child is not greyed out, see
+    /// [`disabled`](crate::disabled::disabled) for that.
+    #[builder(default = "false")]
+    pub disabled: bool,
+    /// The color of the disabled button (RGBA). If None, `color` at 38% of its opacity.
+    #[builder(default)]
+    pub disabled_color: Option<Color>,
     /// The shape of the button.
     #[builder(
         default = "Shape::RoundedRectangle { top_left: Dp(25.0), top_right: Dp(25.0), bottom_right: Dp(25.0), bottom_left: Dp(25.0), g2_k_value: 3.0 }"
@@ -62,6 +76,13 @@ pub struct ButtonArgs {
     /// The ripple color (RGB) for the button.
     #[builder(default = "Color::from_rgb(1.0, 1.0, 1.0)")]
     pub ripple_color: Color,
+    /// Whether a press starts the ripple animation.
+    #[builder(default = "true")]
+    pub ripple: bool,
+    /// Whether the button takes the keyboard focus, so it can be reached with
+    /// [`focus_next`](tessera_ui::focus_state::focus_next) and clicked with Enter or Space.
+    #[builder(default = "true")]
+    pub focusable: bool,
     /// Width of the border. If > 0, an outline will be drawn.
     #[builder(default = "Dp(0.0)")]
     pub border_width: Dp,
@@ -143,29 +164,16 @@ pub fn button(args: impl Into<ButtonArgs>, ripple_state: Arc<RippleState>, child
 
 /// Create surface arguments based on button configuration
 fn create_surface_args(args: &ButtonArgs) -> crate::surface::SurfaceArgs {
-    let style = if args.border_width.to_pixels_f32() > 0.0 {
-        crate::surface::SurfaceStyle::FilledOutlined {
-            fill_color: args.color,
-            border_color: args.border_color.unwrap_or(args.color),
-            border_width: args.border_width,
-        }
-    } else {
-        crate::surface::SurfaceStyle::Filled { color: args.color }
-    };
-
-    let hover_style = if let Some(hover_color) = args.hover_color {
-        let style = if args.border_width.to_pixels_f32() > 0.0 {
+    let style_of = |color: Color| {
+        if args.border_width.to_pixels_f32() > 0.0 {
             crate::surface::SurfaceStyle::FilledOutlined {
-                fill_color: hover_color,
-                border_color: args.border_color.unwrap_or(hover_color),
+                fill_color: color,
+                border_color: args.border_color.unwrap_or(color),
                 border_width: args.border_width,
             }
         } else {
-            crate::surface::SurfaceStyle::Filled { color: hover_color }
-        };
-        Some(style)
-    } else {
-        None
+            crate::surface::SurfaceStyle::Filled { color }
+        }
     };
 
     let mut builder = SurfaceArgsBuilder::default();
@@ -175,17 +183,29 @@ fn create_surface_args(args: &ButtonArgs) -> crate::surface::SurfaceArgs {
         builder = builder.shadow(shadow);
     }
 
-    // Set on_click handler if available
-    if let Some(on_click) = args.on_click.clone() {
-        builder = builder.on_click(on_click);
+    if args.disabled {
+        // Without on_click the surface takes no clicks, hover or focus
+        let color = args
+            .disabled_color
+            .unwrap_or(args.color.with_alpha(args.color.a * 0.38));
+        builder = builder.style(style_of(color));
+    } else {
+        // Set on_click handler if available
+        if let Some(on_click) = args.on_click.clone() {
+            builder = builder.on_click(on_click);
+        }
+        builder = builder
+            .style(style_of(args.color))
+            .hover_style(args.hover_color.map(style_of))
+            .pressed_style(args.pressed_color.map(style_of));
     }
 
     builder
-        .style(style)
-        .hover_style(hover_style)
         .shape(args.shape)
         .padding(args.padding)
         .ripple_color(args.ripple_color)
+        .ripple(args.ripple)
+        .focusable(args.focusable)
         .width(args.width)
         .height(args.height)
         .build()
@@ -243,6 +263,16 @@ impl ButtonArgs {
         self
     }
 
+    pub fn with_pressed_color(mut self, pressed_color: Color) -> Self {
+        self.pressed_color = Some(pressed_color);
+        self
+    }
+
+    pub fn with_disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
     pub fn with_padding(mut self, padding: Dp) -> Self {
         self.padding = padding;
         self
@@ -268,6 +298,11 @@ impl ButtonArgs {
         self
     }
 
+    pub fn with_ripple(mut self, ripple: bool) -> Self {
+        self.ripple = ripple;
+        self
+    }
+
     pub fn with_border(mut self, width: Dp, color: Option<Color>) -> Self {
         self.border_width = width;
         self.border_color = color;
@@ -22,7 +22,12 @@ use std::{
 
 use derive_builder::Builder;
 use parking_lot::RwLock;
-use tessera_ui::{Color, DimensionValue, Dp, tessera};
+use tessera_ui::{
+    Color, DimensionValue, Dp, clock,
+    haptics::{self, HapticFeedback},
+    sound::{self, Cue},
+    tessera, theme,
+};
 
 use crate::{
     RippleState,
@@ -61,10 +66,14 @@ pub struct CheckboxArgs {
     #[builder(default = "Color::new(0.8, 0.8, 0.8, 1.0)")]
     pub color: Color,
 
-    #[builder(default = "Color::new(0.6, 0.7, 0.9, 1.0)")]
+    /// Defaults to the [accent color](tessera_ui::theme::accent_color), or light blue without
+    /// one.
+    #[builder(default = "theme::accent_or(Color::new(0.6, 0.7, 0.9, 1.0))")]
     pub checked_color: Color,
 
-    #[builder(default = "Color::from_rgb_u8(119, 72, 146)")]
+    /// Defaults to black or white, whichever stands out more on the
+    /// [accent color](tessera_ui::theme::accent_color), or purple without one.
+    #[builder(default = "theme::on_accent_color().unwrap_or(Color::from_rgb_u8(119, 72, 146))")]
     pub checkmark_color: Color,
 
     #[builder(default = "5.0")]
@@ -116,13 +125,13 @@ impl CheckmarkState {
     /// Toggle checked state and start animation
     pub fn toggle(&mut self) {
         self.checked = !self.checked;
-        self.last_toggle_time = Some(Instant::now());
+        self.last_toggle_time = Some(clock::now());
     }
 
     /// Update progress based on elapsed time
     pub fn update_progress(&mut self) {
         if let Some(start) = self.last_toggle_time {
-            let elapsed = start.elapsed();
+            let elapsed = clock::elapsed(start);
             let fraction =
                 (elapsed.as_secs_f32() / CHECKMARK_ANIMATION_DURATION.as_secs_f32()).min(1.0);
             self.progress = if self.checked {
@@ -195,6 +204,8 @@ pub fn checkbox(args: impl Into<CheckboxArgs>, state: Arc<CheckboxState>) {
         let on_toggle = args.on_toggle.clone();
         Arc::new(move || {
             state.checkmark.write().toggle();
+            sound::play(Cue::Toggle);
+            haptics::perform(HapticFeedback::Click);
             on_toggle(state.checkmark.read().checked);
         })
     };
@@ -0,0 +1,460 @@
+//! Read-only display of a code snippet.
+//!
+//! [`code_block`] renders code line by line with optional line numbers, a background behind
+//! selected line ranges and a "Copy" label that puts the code on the clipboard. It has no
+//! editing state, cursor or focus, which makes it much cheaper than a [`crate::text_editor`]
+//! for snippets that are only displayed, e.g. in documentation or rendered markdown.
+//!
+//! The code is drawn in a single color, or colored by a tree-sitter grammar set in
+//! [`CodeBlockArgs::syntax_highlighting`] with the `tree-sitter` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use tessera_ui_basic_components::code_block::{code_block, CodeBlockArgsBuilder};
+//!
+//! code_block(
+//!     CodeBlockArgsBuilder::default()
+//!         .code("fn main() {\n    println!(\"Hello\");\n}")
+//!         .emphasized_lines(vec![2..=2])
+//!         .build()
+//!         .unwrap(),
+//! );
+//! ```
+use std::ops::RangeInclusive;
+#[cfg(feature = "tree-sitter")]
+use std::{
+    num::NonZero,
+    ops::Range,
+    sync::{Arc, OnceLock},
+};
+
+use derive_builder::Builder;
+#[cfg(feature = "tree-sitter")]
+use parking_lot::Mutex;
+use tessera_ui::{
+    Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp, PressKeyEventType, Px,
+    PxPosition, tessera, winit::window::CursorIcon,
+};
+
+#[cfg(feature = "tree-sitter")]
+use crate::{
+    pipelines::TextSpan,
+    text_edit_core::{SyntaxHighlighting, SyntaxTree},
+};
+use crate::{
+    shape_def::Shape,
+    surface::{SurfaceArgsBuilder, surface},
+    text::{TextArgsBuilder, text},
+};
+
+/// Gap between the code and the copy label.
+const COPY_LABEL_GAP: Dp = Dp(12.0);
+
+/// Arguments for the [`code_block`] component.
+#[derive(Builder, Clone)]
+#[builder(pattern = "owned")]
+pub struct CodeBlockArgs {
+    /// The code to display. Lines are separated by `\n`.
+    #[builder(setter(into))]
+    pub code: String,
+    /// Color of the code.
+    #[builder(default = "Color::new(0.15, 0.15, 0.15, 1.0)")]
+    pub color: Color,
+    /// Font size of the code and line numbers.
+    #[builder(default = "Dp(14.0)")]
+    pub size: Dp,
+    /// Shows a line number in front of every line.
+    #[builder(default = "true")]
+    pub line_numbers: bool,
+    /// Color of the line numbers.
+    #[builder(default = "Color::new(0.55, 0.55, 0.55, 1.0)")]
+    pub line_number_color: Color,
+    /// Line ranges drawn on the emphasis color, using the 1-based numbers shown in the gutter.
+    #[builder(default)]
+    pub emphasized_lines: Vec<RangeInclusive<usize>>,
+    /// Background of emphasized lines.
+    #[builder(default = "Color::new(1.0, 0.85, 0.3, 0.3)")]
+    pub emphasis_color: Color,
+    /// Shows a "Copy" label in the top right corner that copies the code to the clipboard.
+    #[builder(default = "true")]
+    pub copy_button: bool,
+    /// Color of the copy label.
+    #[builder(default = "Color::new(0.4745, 0.5255, 0.7961, 1.0)")]
+    pub copy_button_color: Color,
+    /// Background of the whole block.
+    #[builder(default = "Color::new(0.96, 0.96, 0.96, 1.0)")]
+    pub background_color: Color,
+    /// Padding between the background edge and the code.
+    #[builder(default = "Dp(8.0)")]
+    pub padding: Dp,
+    /// Width of the block. Defaults to `Wrap`.
+    #[builder(default = "DimensionValue::WRAP", setter(into))]
+    pub width: DimensionValue,
+    /// Tree-sitter grammar and colors the code is highlighted with. Defaults to `None`, which
+    /// draws all code in [`Self::color`].
+    #[cfg(feature = "tree-sitter")]
+    #[builder(default)]
+    pub syntax_highlighting: Option<Arc<SyntaxHighlighting>>,
+}
+
+impl From<String> for CodeBlockArgs {
+    fn from(val: String) -> Self {
+        CodeBlockArgsBuilder::default().code(val).build().unwrap()
+    }
+}
+
+impl From<&str> for CodeBlockArgs {
+    fn from(val: &str) -> Self {
+        CodeBlockArgsBuilder::default().code(val).build().unwrap()
+    }
+}
+
+impl CodeBlockArgs {
+    fn is_emphasized(&self, line_number: usize) -> bool {
+        self.emphasized_lines
+            .iter()
+            .any(|range| range.contains(&line_number))
+    }
+}
+
+/// Displays read-only code with optional line numbers, emphasized lines and a copy label.
+///
+/// # Example
+/// ```
+/// use tessera_ui_basic_components::code_block::code_block;
+///
+/// code_block("let answer = 42;");
+/// ```
+#[tessera]
+pub fn code_block(args: impl Into<CodeBlockArgs>) {
+    let args: CodeBlockArgs = args.into();
+    surface(
+        SurfaceArgsBuilder::default()
+            .style(args.background_color.into())
+            .shape(Shape::RoundedRectangle {
+                top_left: Dp(4.0),
+                top_right: Dp(4.0),
+                bottom_right: Dp(4.0),
+                bottom_left: Dp(4.0),
+                g2_k_value: 3.0,
+            })
+            .padding(args.padding)
+            .width(args.width)
+            .build()
+            .unwrap(),
+        None,
+        move || code_block_lines(args),
+    );
+}
+
+/// The lines of a code block. For every line the children are, in order: the emphasis
+/// background if the line is emphasized, the line number if enabled, and the code. The copy
+/// label, if enabled, is the last child.
+#[tessera]
+fn code_block_lines(args: CodeBlockArgs) {
+    let line_height = Dp(args.size.0 * 1.4);
+    let lines: Vec<String> = args.code.lines().map(str::to_string).collect();
+    #[cfg(feature = "tree-sitter")]
+    let colors = args
+        .syntax_highlighting
+        .as_ref()
+        .map(|highlighting| line_colors(highlighting, &args.code));
+
+    for (i, line) in lines.iter().enumerate() {
+        if args.is_emphasized(i + 1) {
+            surface(
+                SurfaceArgsBuilder::default()
+                    .style(args.emphasis_color.into())
+                    .shape(Shape::RECTANGLE)
+                    .width(DimensionValue::FILLED)
+                    .height(DimensionValue::FILLED)
+                    .build()
+                    .unwrap(),
+                None,
+                || {},
+            );
+        }
+        if args.line_numbers {
+            text(
+                TextArgsBuilder::default()
+                    .text((i + 1).to_string())
+                    .color(args.line_number_color)
+                    .size(args.size)
+                    .line_height(line_height)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        let code = TextArgsBuilder::default()
+            .text(line.clone())
+            .color(args.color)
+            .size(args.size)
+            .line_height(line_height);
+        #[cfg(feature = "tree-sitter")]
+        let code = match colors.as_ref().and_then(|colors| colors.get(i)) {
+            Some(colors) if !colors.is_empty() => code.spans(line_spans(line, colors)),
+            _ => code,
+        };
+        text(code.build().unwrap());
+    }
+    if args.copy_button {
+        copy_label(args.code.clone(), args.copy_button_color, args.size);
+    }
+
+    measure(Box::new(move |input| {
+        let max_width = match input.parent_constraint.width {
+            DimensionValue::Fixed(w) => Some(w),
+            DimensionValue::Wrap { max, .. } => max,
+            DimensionValue::Fill { max, .. } => max,
+        };
+        let wrap = DimensionValue::Wrap {
+            min: None,
+            max: None,
+        };
+        let gutter_gap = Dp(12.0).to_px();
+
+        // Children of each line: (emphasis, line number, code)
+        let mut children = input.children_ids.iter().copied();
+        let mut rows = Vec::with_capacity(lines.len());
+        for i in 0..lines.len() {
+            let emphasis = args.is_emphasized(i + 1).then(|| children.next()).flatten();
+            let number = args.line_numbers.then(|| children.next()).flatten();
+            let code = children.next();
+            rows.push((emphasis, number, code));
+        }
+        let copy = children.next();
+        let copy_size = match copy {
+            Some(copy) => Some(input.measure_child(copy, &Constraint::new(wrap, wrap))?),
+            None => None,
+        };
+        let copy_width = copy_size.map(|size| size.width);
+
+        let mut gutter_width = Px(0);
+        for &(_, number, _) in &rows {
+            if let Some(number) = number {
+                let size = input.measure_child(number, &Constraint::new(wrap, wrap))?;
+                gutter_width = gutter_width.max(size.width + gutter_gap);
+            }
+        }
+
+        // The code keeps clear of the copy label, which stays in the top right corner
+        let reserved = reserved_width(gutter_width, copy_width);
+        let code_constraint = Constraint::new(
+            DimensionValue::Wrap {
+                min: None,
+                max: max_width.map(|w| (w - reserved).max(Px(0))),
+            },
+            wrap,
+        );
+        let mut width = reserved;
+        let mut heights = Vec::with_capacity(rows.len());
+        for &(_, _, code) in &rows {
+            let size = match code {
+                Some(code) => input.measure_child(code, &code_constraint)?,
+                None => ComputedData::ZERO,
+            };
+            width = width.max(reserved + size.width);
+            heights.push(size.height);
+        }
+        if let Some(max_width) = max_width
+            && matches!(input.parent_constraint.width, DimensionValue::Fill { .. })
+        {
+            width = max_width;
+        }
+
+        let mut y = Px(0);
+        for (&(emphasis, number, code), &height) in rows.iter().zip(&heights) {
+            if let Some(emphasis) = emphasis {
+                let constraint =
+                    Constraint::new(DimensionValue::Fixed(width), DimensionValue::Fixed(height));
+                input.measure_child(emphasis, &constraint)?;
+                input.place_child(emphasis, PxPosition::new(Px(0), y));
+            }
+            if let Some(number) = number {
+                input.place_child(number, PxPosition::new(Px(0), y));
+            }
+            if let Some(code) = code {
+                input.place_child(code, PxPosition::new(gutter_width, y));
+            }
+            y += height;
+        }
+
+        if let (Some(copy), Some(size)) = (copy, copy_size) {
+            y = y.max(size.height);
+            input.place_child(copy, PxPosition::new(width - size.width, Px(0)));
+        }
+
+        Ok(ComputedData { width, height: y })
+    }));
+}
+
+/// Returns the width next to the code taken by a gutter of `gutter_width` and, if shown, a
+/// copy label of `copy_width` with the gap before it.
+fn reserved_width(gutter_width: Px, copy_width: Option<Px>) -> Px {
+    gutter_width + copy_width.map_or(Px(0), |width| width + COPY_LABEL_GAP.to_px())
+}
+
+/// Colored byte ranges of every line of a code block, see [`SyntaxTree::line_spans`].
+#[cfg(feature = "tree-sitter")]
+type LineColors = Vec<Vec<(Range<usize>, Color)>>;
+
+/// A code block has no state to keep its parse tree in, so the colors of recently shown
+/// code are kept here instead of parsing it again every frame.
+#[cfg(feature = "tree-sitter")]
+type ColorCache = lru::LruCache<(usize, String), (Arc<SyntaxHighlighting>, Arc<LineColors>)>;
+
+#[cfg(feature = "tree-sitter")]
+static COLOR_CACHE: OnceLock<Mutex<ColorCache>> = OnceLock::new();
+
+/// Returns the colors `highlighting` gives the lines of `code`.
+#[cfg(feature = "tree-sitter")]
+fn line_colors(highlighting: &Arc<SyntaxHighlighting>, code: &str) -> Arc<LineColors> {
+    let cache =
+        COLOR_CACHE.get_or_init(|| Mutex::new(lru::LruCache::new(NonZero::new(32).unwrap())));
+    let key = (Arc::as_ptr(highlighting) as usize, code.to_string());
+    // The address only identifies the grammar while the cached one is alive
+    if let Some((cached, colors)) = cache.lock().get(&key)
+        && Arc::ptr_eq(cached, highlighting)
+    {
+        return colors.clone();
+    }
+    let mut syntax = SyntaxTree::new(highlighting.clone());
+    syntax.update_text(code);
+    let colors: Arc<LineColors> = Arc::new(
+        (0..code.lines().count())
+            .map(|line| syntax.line_spans(line))
+            .collect(),
+    );
+    cache
+        .lock()
+        .put(key, (highlighting.clone(), colors.clone()));
+    colors
+}
+
+/// Splits `line` into spans in the colors of the byte ranges `colors`, where later ranges
+/// are drawn over earlier ones like nested nodes over their parents. Text outside of them
+/// keeps the color of the whole text.
+#[cfg(feature = "tree-sitter")]
+fn line_spans(line: &str, colors: &[(Range<usize>, Color)]) -> Vec<TextSpan> {
+    let mut byte_colors = vec![None; line.len()];
+    for (range, color) in colors {
+        let range = range.start.min(line.len())..range.end.min(line.len());
+        byte_colors[range].fill(Some(*color));
+    }
+    let mut spans: Vec<TextSpan> = Vec::new();
+    let mut start = 0;
+    for end in (1..=line.len()).filter(|&end| line.is_char_boundary(end)) {
+        let color = byte_colors[start];
+        if end < line.len() && byte_colors[end] == color {
+            continue;
+        }
+        let span = TextSpan::new(&line[start..end]);
+        spans.push(match color {
+            Some(color) => span.with_color(color),
+            None => span,
+        });
+        start = end;
+    }
+    spans
+}
+
+/// Clickable label that copies `code` to the clipboard.
+#[tessera]
+fn copy_label(code: String, color: Color, size: Dp) {
+    text(
+        TextArgsBuilder::default()
+            .text("Copy".to_string())
+            .color(color)
+            .size(size)
+            .build()
+            .unwrap(),
+    );
+
+    input_handler(Box::new(move |input| {
+        let size = input.computed_data;
+        let is_cursor_in = input.cursor_position_rel.is_some_and(|pos| {
+            pos.x.0 >= 0 && pos.x.0 < size.width.0 && pos.y.0 >= 0 && pos.y.0 < size.height.0
+        });
+        if !is_cursor_in {
+            return;
+        }
+        input.requests.cursor_icon = CursorIcon::Pointer;
+
+        let clicked = input.cursor_events.iter().any(|e| {
+            matches!(
+                e.content,
+                CursorEventContent::Pressed(PressKeyEventType::Left)
+            )
+        });
+        if clicked {
+            input.clipboard.set_text(&code);
+            input.cursor_events.clear();
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserved_width() {
+        assert_eq!(reserved_width(Px(20), None), Px(20));
+        let gap = COPY_LABEL_GAP.to_px();
+        assert_eq!(reserved_width(Px(20), Some(Px(30))), Px(50) + gap);
+        assert_eq!(reserved_width(Px(0), Some(Px(30))), Px(30) + gap);
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn test_line_spans() {
+        let red = Color::from_rgb_u8(255, 0, 0);
+        let blue = Color::from_rgb_u8(0, 0, 255);
+        let texts = |spans: Vec<TextSpan>| {
+            spans
+                .into_iter()
+                .map(|span| (span.text, span.color))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            texts(line_spans("let x = 1;", &[(0..3, red), (8..9, blue)])),
+            vec![
+                ("let".to_string(), Some(red)),
+                (" x = ".to_string(), None),
+                ("1".to_string(), Some(blue)),
+                (";".to_string(), None),
+            ]
+        );
+        // A nested range is drawn over the one around it
+        assert_eq!(
+            texts(line_spans("\"a\\nb\"", &[(0..6, red), (2..4, blue)])),
+            vec![
+                ("\"a".to_string(), Some(red)),
+                ("\\n".to_string(), Some(blue)),
+                ("b\"".to_string(), Some(red)),
+            ]
+        );
+        assert_eq!(texts(line_spans("ä", &[])), vec![("ä".to_string(), None)]);
+        assert!(line_spans("", &[]).is_empty());
+    }
+
+    #[cfg(feature = "tree-sitter")]
+    #[test]
+    fn test_line_colors() {
+        let number = Color::from_rgb_u8(0, 0, 255);
+        let highlighting = Arc::new(
+            SyntaxHighlighting::new(
+                tree_sitter_json::LANGUAGE,
+                tree_sitter_json::HIGHLIGHTS_QUERY,
+                &[("number", number)],
+            )
+            .unwrap(),
+        );
+        let code = "{\n  \"a\": 12\n}";
+        let colors = line_colors(&highlighting, code);
+        assert_eq!(*colors, vec![vec![], vec![(7..9, number)], vec![]]);
+        // The same code is only parsed once
+        assert!(Arc::ptr_eq(&colors, &line_colors(&highlighting, code)));
+    }
+}
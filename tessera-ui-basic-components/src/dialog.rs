@@ -95,7 +95,7 @@ use std::{
 
 use derive_builder::Builder;
 use parking_lot::RwLock;
-use tessera_ui::{Color, DimensionValue, Dp, tessera, winit};
+use tessera_ui::{Color, DimensionValue, Dp, clock, tessera, winit};
 
 use crate::{
     alignment::Alignment,
@@ -114,7 +114,7 @@ const ANIM_TIME: Duration = Duration::from_millis(300);
 /// Placing this here reduces inline complexity inside the component body.
 fn compute_dialog_progress(timer_opt: Option<Instant>) -> f32 {
     timer_opt.as_ref().map_or(1.0, |timer| {
-        let elapsed = timer.elapsed();
+        let elapsed = clock::elapsed(*timer);
         if elapsed >= ANIM_TIME {
             1.0
         } else {
@@ -179,9 +179,9 @@ impl DialogProviderState {
             // Already opened, no action needed
         } else {
             self.is_open = true; // Mark as open
-            let mut timer = Instant::now();
+            let mut timer = clock::now();
             if let Some(old_timer) = self.timer {
-                let elapsed = old_timer.elapsed();
+                let elapsed = clock::elapsed(old_timer);
                 if elapsed < ANIM_TIME {
                     // If we are still in the middle of an animation
                     timer += ANIM_TIME - elapsed; // We need to 'catch up' the timer
@@ -195,9 +195,9 @@ impl DialogProviderState {
     pub fn close(&mut self) {
         if self.is_open {
             self.is_open = false; // Mark as closed
-            let mut timer = Instant::now();
+            let mut timer = clock::now();
             if let Some(old_timer) = self.timer {
-                let elapsed = old_timer.elapsed();
+                let elapsed = clock::elapsed(old_timer);
                 if elapsed < ANIM_TIME {
                     // If we are still in the middle of an animation
                     timer += ANIM_TIME - elapsed; // We need to 'catch up' the timer
@@ -380,7 +380,7 @@ pub fn dialog_provider(
         (guard.is_open, guard.timer)
     };
 
-    let is_animating = timer_opt.is_some_and(|t| t.elapsed() < ANIM_TIME);
+    let is_animating = timer_opt.is_some_and(|t| clock::elapsed(t) < ANIM_TIME);
 
     if is_open || is_animating {
         let progress = animation::easing(compute_dialog_progress(timer_opt));
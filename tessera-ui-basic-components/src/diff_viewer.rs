@@ -0,0 +1,651 @@
+//! A read-only view of the differences between two versions of a text.
+//!
+//! [`diff_viewer`] shows the lines of a [`DiffViewerState`], built either by comparing two
+//! texts line by line or by parsing a unified diff such as the output of `git diff`.
+//!
+//! - [`DiffLayout::SideBySide`] shows the old lines on the left and the new ones on the
+//!   right, with empty filler rows keeping unchanged lines aligned
+//! - [`DiffLayout::Inline`] shows the removed lines above the added lines that replace them
+//! - Added and removed lines get a tinted background, and the words that changed inside a
+//!   replaced line a stronger one
+//! - With the `tree-sitter` feature, [`DiffViewerState::set_syntax_highlighting`] colors the
+//!   code with the same grammars as the [`crate::text_editor`]
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use parking_lot::RwLock;
+//! use tessera_ui_basic_components::diff_viewer::{DiffViewerState, diff_viewer};
+//!
+//! let state = DiffViewerState::from_texts("let x = 1;\nlet y = 2;", "let x = 3;\nlet y = 2;");
+//! assert_eq!((state.added_count(), state.removed_count()), (1, 1));
+//! let state = Arc::new(RwLock::new(state));
+//! // diff_viewer(Default::default(), state.clone());
+//! ```
+
+mod diff;
+
+use std::sync::Arc;
+
+use derive_builder::Builder;
+use parking_lot::RwLock;
+use tessera_ui::{Color, ComputedData, DimensionValue, Dp, Px, PxPosition, tessera};
+
+#[cfg(feature = "tree-sitter")]
+use crate::text_edit_core::{SyntaxHighlighting, SyntaxTree};
+use crate::{
+    pipelines::{TextCommand, TextData, write_font_system},
+    scrollable::{ScrollableArgsBuilder, ScrollableState, scrollable},
+    selection_highlight_rect::selection_highlight_rect,
+    surface::{SurfaceArgsBuilder, surface},
+    text_edit_core::RectDef,
+};
+
+pub use diff::{DiffLine, DiffLineKind};
+
+/// How a [`diff_viewer`] arranges the old and new lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DiffLayout {
+    /// The old text on the left and the new text on the right.
+    #[default]
+    SideBySide,
+    /// A single column, removed lines above the added lines that replace them.
+    Inline,
+}
+
+/// Which version of the text a pane row shows a line of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Old,
+    New,
+    /// Both versions, in the single pane of [`DiffLayout::Inline`].
+    Both,
+}
+
+/// A row of a pane: the index of a [`DiffLine`], or `None` for a filler row standing for a
+/// line that only the other pane has.
+type Row = Option<usize>;
+
+/// The laid out rows of one pane and the backgrounds drawn behind them.
+struct Pane {
+    rows: Vec<Row>,
+    buffer: glyphon::Buffer,
+    /// Byte length of the line number prefix of every row.
+    prefix_len: usize,
+    rects: Vec<(RectDef, Color)>,
+}
+
+/// The lines of a [`diff_viewer`] and where the view is scrolled.
+pub struct DiffViewerState {
+    lines: Vec<DiffLine>,
+    scrollable_state: Arc<ScrollableState>,
+    /// The laid out panes, or empty when they have to be laid out again
+    panes: Vec<Pane>,
+    /// Layout, font size, line height and colors the panes were built with
+    pane_style: Option<(DiffLayout, f32, f32, Color, Color, Color)>,
+    #[cfg(feature = "tree-sitter")]
+    syntax: Option<(SyntaxTree, SyntaxTree)>,
+}
+
+impl Default for DiffViewerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiffViewerState {
+    /// Creates a state without lines.
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            scrollable_state: Arc::new(ScrollableState::new()),
+            panes: Vec::new(),
+            pane_style: None,
+            #[cfg(feature = "tree-sitter")]
+            syntax: None,
+        }
+    }
+
+    /// Creates a state showing the differences between `old` and `new`.
+    pub fn from_texts(old: &str, new: &str) -> Self {
+        let mut state = Self::new();
+        state.set_texts(old, new);
+        state
+    }
+
+    /// Creates a state showing the unified diff `diff`. See [`Self::set_unified_diff`].
+    pub fn from_unified_diff(diff: &str) -> Self {
+        let mut state = Self::new();
+        state.set_unified_diff(diff);
+        state
+    }
+
+    /// Shows the differences between `old` and `new`, computed line by line. Every line of
+    /// both texts is shown, unchanged ones included.
+    pub fn set_texts(&mut self, old: &str, new: &str) {
+        self.set_lines(diff::diff_texts(old, new));
+    }
+
+    /// Shows the hunks of the unified diff `diff`, as printed by `diff -u` or `git diff`.
+    /// File headers are skipped and the `@@` header of each hunk is shown as a separator
+    /// row. Only the lines of the hunks are known, so syntax highlighting may be off for
+    /// code whose start lies outside of them.
+    pub fn set_unified_diff(&mut self, diff: &str) {
+        self.set_lines(diff::parse_unified(diff));
+    }
+
+    fn set_lines(&mut self, lines: Vec<DiffLine>) {
+        self.lines = lines;
+        self.panes.clear();
+    }
+
+    /// Returns the shown lines, in order.
+    pub fn lines(&self) -> &[DiffLine] {
+        &self.lines
+    }
+
+    /// Returns the number of added lines.
+    pub fn added_count(&self) -> usize {
+        self.count(DiffLineKind::Added)
+    }
+
+    /// Returns the number of removed lines.
+    pub fn removed_count(&self) -> usize {
+        self.count(DiffLineKind::Removed)
+    }
+
+    fn count(&self, kind: DiffLineKind) -> usize {
+        self.lines.iter().filter(|line| line.kind == kind).count()
+    }
+
+    /// Returns the syntax highlighting of the code, if any.
+    #[cfg(feature = "tree-sitter")]
+    pub fn syntax_highlighting(&self) -> Option<&Arc<SyntaxHighlighting>> {
+        self.syntax.as_ref().map(|(old, _)| old.highlighting())
+    }
+
+    /// Colors the code of both versions with `highlighting`, or removes the colors with
+    /// `None`. Each version is parsed on its own, so constructs that only one of them has
+    /// are colored correctly.
+    #[cfg(feature = "tree-sitter")]
+    pub fn set_syntax_highlighting(&mut self, highlighting: Option<Arc<SyntaxHighlighting>>) {
+        self.syntax = highlighting.map(|highlighting| {
+            (
+                SyntaxTree::new(highlighting.clone()),
+                SyntaxTree::new(highlighting),
+            )
+        });
+        self.panes.clear();
+    }
+
+    /// Returns the rows of every pane of `layout`.
+    fn pane_rows(&self, layout: DiffLayout) -> Vec<(Side, Vec<Row>)> {
+        if layout == DiffLayout::Inline {
+            return vec![(Side::Both, (0..self.lines.len()).map(Some).collect())];
+        }
+        let (mut old, mut new) = (Vec::new(), Vec::new());
+        let mut i = 0;
+        while i < self.lines.len() {
+            let removed = self.run_len(i, DiffLineKind::Removed);
+            let added = self.run_len(i + removed, DiffLineKind::Added);
+            if removed + added == 0 {
+                old.push(Some(i));
+                new.push(Some(i));
+                i += 1;
+                continue;
+            }
+            for row in 0..removed.max(added) {
+                old.push((row < removed).then_some(i + row));
+                new.push((row < added).then_some(i + removed + row));
+            }
+            i += removed + added;
+        }
+        vec![(Side::Old, old), (Side::New, new)]
+    }
+
+    /// Returns the number of consecutive lines of `kind` from line `start`.
+    fn run_len(&self, start: usize, kind: DiffLineKind) -> usize {
+        self.lines[start..]
+            .iter()
+            .take_while(|line| line.kind == kind)
+            .count()
+    }
+
+    /// Returns the colored byte ranges of the code of line `index`, relative to its text.
+    #[cfg(feature = "tree-sitter")]
+    fn syntax_spans(&self, index: usize) -> Vec<(std::ops::Range<usize>, Color)> {
+        let line = &self.lines[index];
+        let Some((old, new)) = &self.syntax else {
+            return Vec::new();
+        };
+        // Each tree holds the lines of its version, in order, so the row of a line in it is
+        // the number of lines of that version before it
+        let version_row = |removed: bool| {
+            self.lines[..index]
+                .iter()
+                .filter(|earlier| match earlier.kind {
+                    DiffLineKind::Unchanged => true,
+                    DiffLineKind::Removed => removed,
+                    DiffLineKind::Added => !removed,
+                    DiffLineKind::Hunk => false,
+                })
+                .count()
+        };
+        match line.kind {
+            DiffLineKind::Removed => old.line_spans(version_row(true)),
+            DiffLineKind::Added | DiffLineKind::Unchanged => new.line_spans(version_row(false)),
+            DiffLineKind::Hunk => Vec::new(),
+        }
+    }
+
+    /// Parses the code of both versions for highlighting.
+    #[cfg(feature = "tree-sitter")]
+    fn update_syntax(&mut self) {
+        let Some((old, new)) = &mut self.syntax else {
+            return;
+        };
+        let version = |removed: bool| {
+            let lines = self.lines.iter().filter(|line| match line.kind {
+                DiffLineKind::Unchanged => true,
+                DiffLineKind::Removed => removed,
+                DiffLineKind::Added => !removed,
+                DiffLineKind::Hunk => false,
+            });
+            lines
+                .map(|line| line.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        old.update_text(&version(true));
+        new.update_text(&version(false));
+    }
+
+    /// Lays out the panes if the lines or the style changed since the last call.
+    fn layout_panes(&mut self, args: &DiffViewerArgs) {
+        let line_height = args.line_height.unwrap_or(Dp(args.size.0 * 1.3));
+        let style = (
+            args.layout,
+            args.size.to_pixels_f32(),
+            line_height.to_pixels_f32(),
+            args.color,
+            args.line_number_color,
+            args.hunk_color,
+        );
+        if self.pane_style == Some(style) && !self.panes.is_empty() {
+            return;
+        }
+        self.pane_style = Some(style);
+        #[cfg(feature = "tree-sitter")]
+        self.update_syntax();
+
+        let number_width = self
+            .lines
+            .iter()
+            .flat_map(|line| line.old_number.into_iter().chain(line.new_number))
+            .max()
+            .unwrap_or(0)
+            .to_string()
+            .len();
+        let mut panes = Vec::new();
+        for (side, rows) in self.pane_rows(args.layout) {
+            let mut buffer = glyphon::Buffer::new(
+                &mut write_font_system(),
+                glyphon::Metrics::new(style.1, style.2),
+            );
+            buffer.set_wrap(&mut write_font_system(), glyphon::Wrap::None);
+            buffer.set_size(&mut write_font_system(), None, None);
+            let prefix_len = match side {
+                Side::Both => 2 * (number_width + 1) + 2,
+                Side::Old | Side::New => number_width + 1,
+            };
+            buffer.lines = rows
+                .iter()
+                .map(|&row| self.buffer_line(row, side, number_width, args))
+                .collect();
+            buffer.shape_until_scroll(&mut write_font_system(), false);
+            panes.push(Pane {
+                rows,
+                buffer,
+                prefix_len,
+                rects: Vec::new(),
+            });
+        }
+        self.panes = panes;
+    }
+
+    /// Builds the laid out form of `row` in a pane of `side`: the line numbers, then the
+    /// line colored by the syntax highlighting.
+    fn buffer_line(
+        &self,
+        row: Row,
+        side: Side,
+        number_width: usize,
+        args: &DiffViewerArgs,
+    ) -> glyphon::BufferLine {
+        let attrs = glyphon::Attrs::new()
+            .family(glyphon::fontdb::Family::Monospace)
+            .color(glyphon_color(args.color));
+        let mut attrs_list = glyphon::AttrsList::new(&attrs);
+        let Some(index) = row else {
+            return glyphon::BufferLine::new(
+                String::new(),
+                glyphon::cosmic_text::LineEnding::None,
+                attrs_list,
+                glyphon::Shaping::Advanced,
+            );
+        };
+        let line = &self.lines[index];
+        let number =
+            |number: Option<usize>| number.map_or_else(String::new, |number| number.to_string());
+        let mut text = match side {
+            Side::Old => format!("{:>number_width$} ", number(line.old_number)),
+            Side::New => format!("{:>number_width$} ", number(line.new_number)),
+            Side::Both => format!(
+                "{:>number_width$} {:>number_width$} {} ",
+                number(line.old_number),
+                number(line.new_number),
+                match line.kind {
+                    DiffLineKind::Added => '+',
+                    DiffLineKind::Removed => '-',
+                    DiffLineKind::Unchanged | DiffLineKind::Hunk => ' ',
+                }
+            ),
+        };
+        let prefix_len = text.len();
+        attrs_list.add_span(
+            0..prefix_len,
+            &attrs.clone().color(glyphon_color(args.line_number_color)),
+        );
+        text.push_str(&line.text);
+        if line.kind == DiffLineKind::Hunk {
+            attrs_list.add_span(
+                prefix_len..text.len(),
+                &attrs.clone().color(glyphon_color(args.hunk_color)),
+            );
+        }
+        #[cfg(feature = "tree-sitter")]
+        for (range, color) in self.syntax_spans(index) {
+            attrs_list.add_span(
+                prefix_len + range.start..prefix_len + range.end,
+                &attrs.clone().color(glyphon_color(color)),
+            );
+        }
+        glyphon::BufferLine::new(
+            text,
+            glyphon::cosmic_text::LineEnding::None,
+            attrs_list,
+            glyphon::Shaping::Advanced,
+        )
+    }
+
+    /// Computes the backgrounds of the rows of pane `index` and of the words that changed
+    /// in them.
+    fn compute_pane_rects(&self, index: usize, args: &DiffViewerArgs) -> Vec<(RectDef, Color)> {
+        let Some(pane) = self.panes.get(index) else {
+            return Vec::new();
+        };
+        let width = pane_size(&pane.buffer)[0];
+        let mut backgrounds = Vec::new();
+        let mut highlights = Vec::new();
+        for run in pane.buffer.layout_runs() {
+            let row_rect = |x: f32, width: f32| RectDef {
+                x: Px(x as i32),
+                y: Px(run.line_top as i32),
+                width: Px(width as i32),
+                height: Px(run.line_height as i32),
+            };
+            let Some(&row) = pane.rows.get(run.line_i) else {
+                continue;
+            };
+            let Some(line) = row.map(|index| &self.lines[index]) else {
+                backgrounds.push((row_rect(0.0, width), args.filler_color));
+                continue;
+            };
+            let (background, highlight) = match line.kind {
+                DiffLineKind::Unchanged => continue,
+                DiffLineKind::Hunk => {
+                    backgrounds.push((row_rect(0.0, width), args.hunk_background_color));
+                    continue;
+                }
+                DiffLineKind::Added => (args.added_background_color, args.added_highlight_color),
+                DiffLineKind::Removed => {
+                    (args.removed_background_color, args.removed_highlight_color)
+                }
+            };
+            backgrounds.push((row_rect(0.0, width), background));
+            for range in &line.changes {
+                let start = glyphon::Cursor::new(run.line_i, pane.prefix_len + range.start);
+                let end = glyphon::Cursor::new(run.line_i, pane.prefix_len + range.end);
+                if let Some((x, width)) = run.highlight(start, end) {
+                    highlights.push((row_rect(x, width), highlight));
+                }
+            }
+        }
+        // Word highlights are drawn over the row backgrounds
+        backgrounds.extend(highlights);
+        backgrounds
+    }
+}
+
+/// Returns the width and height of the laid out lines of `buffer`.
+fn pane_size(buffer: &glyphon::Buffer) -> [f32; 2] {
+    buffer
+        .layout_runs()
+        .fold([0.0, 0.0], |[width, height], run| {
+            [
+                width.max(run.line_w),
+                height.max(run.line_top + run.line_height),
+            ]
+        })
+}
+
+fn glyphon_color(color: Color) -> glyphon::Color {
+    glyphon::Color::rgba(
+        (color.r * 255.0) as u8,
+        (color.g * 255.0) as u8,
+        (color.b * 255.0) as u8,
+        (color.a * 255.0) as u8,
+    )
+}
+
+/// Arguments for the [`diff_viewer`] component.
+#[derive(Builder, Clone)]
+#[builder(pattern = "owned")]
+pub struct DiffViewerArgs {
+    /// Width of the view. Defaults to `Fill`.
+    #[builder(default = "DimensionValue::FILLED", setter(into))]
+    pub width: DimensionValue,
+    /// Height of the view. Defaults to `Fill`.
+    #[builder(default = "DimensionValue::FILLED", setter(into))]
+    pub height: DimensionValue,
+    /// How the old and new lines are arranged.
+    #[builder(default)]
+    pub layout: DiffLayout,
+    /// Font size of the lines.
+    #[builder(default = "Dp(13.0)")]
+    pub size: Dp,
+    /// Line height. Defaults to 1.3 × the font size.
+    #[builder(default, setter(strip_option))]
+    pub line_height: Option<Dp>,
+    /// Color of text that the syntax highlighting does not color.
+    #[builder(default = "Color::new(0.15, 0.15, 0.15, 1.0)")]
+    pub color: Color,
+    /// Color of the line numbers and of the `+`/`-` markers.
+    #[builder(default = "Color::new(0.55, 0.55, 0.55, 1.0)")]
+    pub line_number_color: Color,
+    /// Background of the view.
+    #[builder(default = "Color::new(0.97, 0.97, 0.97, 1.0)")]
+    pub background_color: Color,
+    /// Background of added lines.
+    #[builder(default = "Color::new(0.2, 0.75, 0.3, 0.15)")]
+    pub added_background_color: Color,
+    /// Background of removed lines.
+    #[builder(default = "Color::new(0.9, 0.25, 0.25, 0.15)")]
+    pub removed_background_color: Color,
+    /// Background of the changed words of added lines, drawn over the line background.
+    #[builder(default = "Color::new(0.2, 0.75, 0.3, 0.3)")]
+    pub added_highlight_color: Color,
+    /// Background of the changed words of removed lines, drawn over the line background.
+    #[builder(default = "Color::new(0.9, 0.25, 0.25, 0.3)")]
+    pub removed_highlight_color: Color,
+    /// Color of the `@@` headers of hunks.
+    #[builder(default = "Color::new(0.35, 0.45, 0.75, 1.0)")]
+    pub hunk_color: Color,
+    /// Background of the `@@` headers of hunks.
+    #[builder(default = "Color::new(0.35, 0.45, 0.75, 0.1)")]
+    pub hunk_background_color: Color,
+    /// Background of the filler rows of [`DiffLayout::SideBySide`].
+    #[builder(default = "Color::new(0.5, 0.5, 0.5, 0.08)")]
+    pub filler_color: Color,
+    /// Space between the panes of [`DiffLayout::SideBySide`].
+    #[builder(default = "Dp(16.0)")]
+    pub pane_gap: Dp,
+    /// Padding between the edge of the view and the lines.
+    #[builder(default = "Dp(6.0)")]
+    pub padding: Dp,
+}
+
+impl Default for DiffViewerArgs {
+    fn default() -> Self {
+        DiffViewerArgsBuilder::default().build().unwrap()
+    }
+}
+
+/// Shows the lines of a [`DiffViewerState`] in a scrollable area. See the
+/// [module documentation](self) for what it supports.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use parking_lot::RwLock;
+/// use tessera_ui_basic_components::diff_viewer::{
+///     DiffLayout, DiffViewerArgsBuilder, DiffViewerState, diff_viewer,
+/// };
+///
+/// let diff = "@@ -1,2 +1,2 @@\n-hello\n+hello, world\n unchanged\n";
+/// let state = Arc::new(RwLock::new(DiffViewerState::from_unified_diff(diff)));
+/// let args = DiffViewerArgsBuilder::default()
+///     .layout(DiffLayout::Inline)
+///     .build()
+///     .unwrap();
+/// // diff_viewer(args, state.clone());
+/// ```
+#[tessera]
+pub fn diff_viewer(args: impl Into<DiffViewerArgs>, state: Arc<RwLock<DiffViewerState>>) {
+    let args: DiffViewerArgs = args.into();
+    let scrollable_state = state.read().scrollable_state.clone();
+    surface(
+        SurfaceArgsBuilder::default()
+            .style(args.background_color.into())
+            .width(args.width)
+            .height(args.height)
+            .padding(args.padding)
+            .build()
+            .unwrap(),
+        None,
+        move || {
+            scrollable(
+                ScrollableArgsBuilder::default()
+                    .width(DimensionValue::FILLED)
+                    .height(DimensionValue::FILLED)
+                    .horizontal(true)
+                    .build()
+                    .unwrap(),
+                scrollable_state,
+                move || diff_panes(args, state),
+            );
+        },
+    );
+}
+
+/// The panes of a [`diff_viewer`], placed next to each other.
+#[tessera]
+fn diff_panes(args: DiffViewerArgs, state: Arc<RwLock<DiffViewerState>>) {
+    let pane_count = match args.layout {
+        DiffLayout::SideBySide => 2,
+        DiffLayout::Inline => 1,
+    };
+    let gap = args.pane_gap;
+    for index in 0..pane_count {
+        diff_pane(args.clone(), state.clone(), index);
+    }
+
+    measure(Box::new(move |input| {
+        let mut x = Px(0);
+        let mut height = Px(0);
+        for (i, &child) in input.children_ids.iter().enumerate() {
+            if i > 0 {
+                x += gap.into();
+            }
+            let size = input.measure_child(child, input.parent_constraint)?;
+            input.place_child(child, PxPosition::new(x, Px(0)));
+            x += size.width;
+            height = height.max(size.height);
+        }
+        Ok(ComputedData { width: x, height })
+    }));
+}
+
+/// One pane of a [`diff_viewer`], with one child per row background and changed word.
+#[tessera]
+fn diff_pane(args: DiffViewerArgs, state: Arc<RwLock<DiffViewerState>>, index: usize) {
+    let rects = state
+        .read()
+        .panes
+        .get(index)
+        .map_or_else(Vec::new, |pane| pane.rects.clone());
+    for (rect, color) in &rects {
+        selection_highlight_rect(rect.width, rect.height, *color);
+    }
+
+    measure(Box::new(move |input| {
+        let mut state = state.write();
+        state.layout_panes(&args);
+
+        // The number of background children was decided from the rects stored last pass
+        let rects = state.compute_pane_rects(index, &args);
+        for (&child, (rect, _)) in input.children_ids.iter().zip(&rects) {
+            input.measure_child(child, input.parent_constraint)?;
+            input.place_child(child, PxPosition::new(rect.x, rect.y));
+        }
+        let Some(pane) = state.panes.get_mut(index) else {
+            return Ok(ComputedData::ZERO);
+        };
+        pane.rects = rects;
+
+        let [width, height] = pane_size(&pane.buffer);
+        input.metadata_mut().push_draw_command(TextCommand {
+            data: TextData::from_buffer(pane.buffer.clone()),
+        });
+        Ok(ComputedData {
+            width: Px(width as i32),
+            height: Px(height as i32),
+        })
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_side_by_side_rows_align_unchanged_lines() {
+        let state = DiffViewerState::from_texts("a\nb\nc\nd", "a\nB\nB2\nd\ne");
+        let rows = state.pane_rows(DiffLayout::SideBySide);
+        let text = |row: &Row| row.map(|index| state.lines()[index].text.as_str());
+        let old: Vec<_> = rows[0].1.iter().map(text).collect();
+        let new: Vec<_> = rows[1].1.iter().map(text).collect();
+        assert_eq!(old, [Some("a"), Some("b"), Some("c"), Some("d"), None]);
+        assert_eq!(
+            new,
+            [Some("a"), Some("B"), Some("B2"), Some("d"), Some("e")]
+        );
+        assert_eq!(rows[0].0, Side::Old);
+
+        let inline = state.pane_rows(DiffLayout::Inline);
+        assert_eq!(inline.len(), 1);
+        assert_eq!(inline[0].1.len(), state.lines().len());
+    }
+}
@@ -0,0 +1,459 @@
+//! Line and word differences between two texts, and parsing of unified diffs.
+
+use std::{iter, ops::Range};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What a [`DiffLine`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DiffLineKind {
+    /// A line of both texts.
+    Unchanged,
+    /// A line only in the new text.
+    Added,
+    /// A line only in the old text.
+    Removed,
+    /// The `@@ -1,4 +1,5 @@` header of a hunk of a unified diff.
+    Hunk,
+}
+
+/// A line of a diff.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffLine {
+    /// What the line is.
+    pub kind: DiffLineKind,
+    /// Number of the line in the old text, counting from 1, or `None` if it is not in it.
+    pub old_number: Option<usize>,
+    /// Number of the line in the new text, counting from 1, or `None` if it is not in it.
+    pub new_number: Option<usize>,
+    /// The line, without its `+`/`-` marker and line ending.
+    pub text: String,
+    /// Byte ranges of `text` that differ from the line it replaces or was replaced by.
+    pub changes: Vec<Range<usize>>,
+}
+
+impl DiffLine {
+    fn new(
+        kind: DiffLineKind,
+        old_number: Option<usize>,
+        new_number: Option<usize>,
+        text: &str,
+    ) -> Self {
+        Self {
+            kind,
+            old_number,
+            new_number,
+            text: text.to_string(),
+            changes: Vec::new(),
+        }
+    }
+}
+
+/// One step of an edit script.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Most edits searched for from each end of a range before its old side is taken as replaced
+/// by the new one as a whole. Bounds the time spent on texts with little in common.
+const MAX_EDITS: usize = 1024;
+
+/// Returns the shortest edit script turning `old` into `new`, with the linear space
+/// refinement of Myers' algorithm. Within every run of changes the deletions come first.
+fn edit_script<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Op> {
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    diff_range(old, new, &mut ops);
+    for run in ops.split_mut(|&op| op == Op::Equal) {
+        run.sort_by_key(|&op| op == Op::Insert);
+    }
+    ops
+}
+
+/// Appends the edit script turning `old` into `new` to `ops`. The common prefix and suffix
+/// are equal; what is left is split at the middle snake of its shortest edit script and both
+/// halves are diffed in turn.
+fn diff_range<T: PartialEq>(old: &[T], new: &[T], ops: &mut Vec<Op>) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+    ops.extend(iter::repeat_n(Op::Equal, prefix));
+    match middle_snake(old, new) {
+        // Both sides are non-empty and differ at both ends, so each half is shorter
+        Some(snake) => {
+            diff_range(&old[..snake.x], &new[..snake.y], ops);
+            ops.extend(iter::repeat_n(Op::Equal, snake.len));
+            diff_range(
+                &old[snake.x + snake.len..],
+                &new[snake.y + snake.len..],
+                ops,
+            );
+        }
+        None => {
+            ops.extend(iter::repeat_n(Op::Delete, old.len()));
+            ops.extend(iter::repeat_n(Op::Insert, new.len()));
+        }
+    }
+    ops.extend(iter::repeat_n(Op::Equal, suffix));
+}
+
+/// A run of equal items starting at `old[x]` and `new[y]`.
+#[derive(Debug, PartialEq, Eq)]
+struct Snake {
+    x: usize,
+    y: usize,
+    len: usize,
+}
+
+/// Returns the middle snake of the shortest edit script turning `old` into `new`: the snake
+/// where the furthest reaching paths searched from the start and from the end meet. Returns
+/// `None` if either side is empty, which needs no search, or if the script has more than
+/// twice [`MAX_EDITS`] edits.
+///
+/// Only the furthest reaching x of each diagonal is kept for both directions, so the search
+/// takes space linear in the lengths. Backwards, x counts from the end of `old`.
+fn middle_snake<T: PartialEq>(old: &[T], new: &[T]) -> Option<Snake> {
+    if old.is_empty() || new.is_empty() {
+        return None;
+    }
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let delta = n - m;
+    let max = ((n + m + 1) / 2).min(MAX_EDITS as isize);
+    let offset = max + 1;
+    let index = |k: isize| (offset + k) as usize;
+    let mut forward = vec![0isize; 2 * offset as usize + 1];
+    let mut backward = vec![0isize; 2 * offset as usize + 1];
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let start = furthest_x(&forward, index, k, d);
+            let (mut x, mut y) = (start, start - k);
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[index(k)] = x;
+            // The backward search of the last round covers diagonals up to d - 1 away
+            let back_k = delta - k;
+            if delta % 2 != 0 && back_k.abs() < d && x + backward[index(back_k)] >= n {
+                return Some(Snake {
+                    x: start as usize,
+                    y: (start - k) as usize,
+                    len: (x - start) as usize,
+                });
+            }
+        }
+        for k in (-d..=d).step_by(2) {
+            let start = furthest_x(&backward, index, k, d);
+            let (mut x, mut y) = (start, start - k);
+            while x < n && y < m && old[(n - x - 1) as usize] == new[(m - y - 1) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[index(k)] = x;
+            let forward_k = delta - k;
+            if delta % 2 == 0 && forward_k.abs() <= d && x + forward[index(forward_k)] >= n {
+                return Some(Snake {
+                    x: (n - x) as usize,
+                    y: (m - y) as usize,
+                    len: (x - start) as usize,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Returns where the path of `d` edits on diagonal `k` starts: one step down from diagonal
+/// `k + 1` or right from `k - 1` in the furthest reaching `xs` of the last round, whichever
+/// is further.
+fn furthest_x(xs: &[isize], index: impl Fn(isize) -> usize, k: isize, d: isize) -> isize {
+    if k == -d || (k != d && xs[index(k - 1)] < xs[index(k + 1)]) {
+        xs[index(k + 1)]
+    } else {
+        xs[index(k - 1)] + 1
+    }
+}
+
+/// Returns the lines of the difference between `old` and `new`, with the changed words of
+/// replaced lines marked.
+pub(super) fn diff_texts(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    for op in edit_script(&old_lines, &new_lines) {
+        match op {
+            Op::Equal => {
+                let line = DiffLine::new(
+                    DiffLineKind::Unchanged,
+                    Some(i + 1),
+                    Some(j + 1),
+                    new_lines[j],
+                );
+                lines.push(line);
+                i += 1;
+                j += 1;
+            }
+            Op::Delete => {
+                let line = DiffLine::new(DiffLineKind::Removed, Some(i + 1), None, old_lines[i]);
+                lines.push(line);
+                i += 1;
+            }
+            Op::Insert => {
+                let line = DiffLine::new(DiffLineKind::Added, None, Some(j + 1), new_lines[j]);
+                lines.push(line);
+                j += 1;
+            }
+        }
+    }
+    mark_changes(&mut lines);
+    lines
+}
+
+/// Parses a unified diff, as printed by `diff -u` or `git diff`, into its lines. File
+/// headers are skipped; hunk headers are kept as [`DiffLineKind::Hunk`] lines.
+pub(super) fn parse_unified(diff: &str) -> Vec<DiffLine> {
+    let (mut old, mut new) = (1, 1);
+    let mut in_hunk = false;
+    let mut lines = Vec::new();
+    for line in diff.lines() {
+        if let Some((old_start, new_start)) = hunk_starts(line) {
+            (old, new) = (old_start, new_start);
+            in_hunk = true;
+            lines.push(DiffLine::new(DiffLineKind::Hunk, None, None, line));
+            continue;
+        }
+        if !in_hunk {
+            continue;
+        }
+        let (marker, text) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+        match marker {
+            "+" => {
+                lines.push(DiffLine::new(DiffLineKind::Added, None, Some(new), text));
+                new += 1;
+            }
+            "-" => {
+                lines.push(DiffLine::new(DiffLineKind::Removed, Some(old), None, text));
+                old += 1;
+            }
+            " " | "" => {
+                let line = DiffLine::new(DiffLineKind::Unchanged, Some(old), Some(new), text);
+                lines.push(line);
+                old += 1;
+                new += 1;
+            }
+            // "\ No newline at end of file"
+            "\\" => {}
+            // The headers of the next file
+            _ => in_hunk = false,
+        }
+    }
+    mark_changes(&mut lines);
+    lines
+}
+
+/// Returns the first old and new line numbers of the hunk header `line`, e.g. `(3, 4)` for
+/// `@@ -3,7 +4,8 @@ fn main() {`.
+fn hunk_starts(line: &str) -> Option<(usize, usize)> {
+    let mut ranges = line.strip_prefix("@@ ")?.split_whitespace();
+    let start = |range: &str, sign: char| -> Option<usize> {
+        let range = range.strip_prefix(sign)?;
+        range.split(',').next()?.parse().ok()
+    };
+    let old = start(ranges.next()?, '-')?;
+    let new = start(ranges.next()?, '+')?;
+    // An empty side starts at line 0, so the lines of the other one count from 1
+    Some((old.max(1), new.max(1)))
+}
+
+/// Marks the changed words of every removed line followed by added lines, pairing the
+/// removed and added lines of a run in order. Pairs without any word in common are left
+/// unmarked, as the whole line changed.
+fn mark_changes(lines: &mut [DiffLine]) {
+    let mut i = 0;
+    while i < lines.len() {
+        let removed = lines[i..]
+            .iter()
+            .take_while(|line| line.kind == DiffLineKind::Removed)
+            .count();
+        let added = lines[i + removed..]
+            .iter()
+            .take_while(|line| line.kind == DiffLineKind::Added)
+            .count();
+        for pair in 0..removed.min(added) {
+            let (old, new) = lines.split_at_mut(i + removed);
+            let (old, new) = (&mut old[i + pair], &mut new[pair]);
+            (old.changes, new.changes) = word_changes(&old.text, &new.text);
+        }
+        i += (removed + added).max(1);
+    }
+}
+
+/// Returns the byte ranges of the words of `old` and of `new` that differ.
+fn word_changes(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let old_words: Vec<(usize, &str)> = old.split_word_bound_indices().collect();
+    let new_words: Vec<(usize, &str)> = new.split_word_bound_indices().collect();
+    let old_texts: Vec<&str> = old_words.iter().map(|&(_, word)| word).collect();
+    let new_texts: Vec<&str> = new_words.iter().map(|&(_, word)| word).collect();
+    let ops = edit_script(&old_texts, &new_texts);
+
+    let (mut old_changes, mut new_changes) = (Vec::new(), Vec::new());
+    let mut common = false;
+    let (mut i, mut j) = (0, 0);
+    for op in ops {
+        match op {
+            Op::Equal => {
+                common |= !old_texts[i].trim().is_empty();
+                i += 1;
+                j += 1;
+            }
+            Op::Delete => {
+                push_range(&mut old_changes, old_words[i]);
+                i += 1;
+            }
+            Op::Insert => {
+                push_range(&mut new_changes, new_words[j]);
+                j += 1;
+            }
+        }
+    }
+    if !common {
+        return (Vec::new(), Vec::new());
+    }
+    (old_changes, new_changes)
+}
+
+/// Adds the range of `word` at its start offset to `ranges`, joining it to the last range
+/// if they touch.
+fn push_range(ranges: &mut Vec<Range<usize>>, (start, word): (usize, &str)) {
+    let end = start + word.len();
+    match ranges.last_mut() {
+        Some(last) if last.end == start => last.end = end,
+        _ => ranges.push(start..end),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(lines: &[DiffLine]) -> Vec<(DiffLineKind, Option<usize>, Option<usize>)> {
+        lines
+            .iter()
+            .map(|line| (line.kind, line.old_number, line.new_number))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_texts() {
+        use DiffLineKind::*;
+        let lines = diff_texts(
+            "fn main() {\n    let x = 1;\n    old();\n}",
+            "fn main() {\n    let x = 2;\n}\nfn new() {}",
+        );
+        assert_eq!(
+            kinds(&lines),
+            [
+                (Unchanged, Some(1), Some(1)),
+                (Removed, Some(2), None),
+                (Removed, Some(3), None),
+                (Added, None, Some(2)),
+                (Unchanged, Some(4), Some(3)),
+                (Added, None, Some(4)),
+            ]
+        );
+        // "1" became "2" in the first pair
+        assert_eq!(lines[1].changes, vec![(12..13)]);
+        assert_eq!(lines[3].changes, vec![(12..13)]);
+        // The second removed line has no partner
+        assert!(lines[2].changes.is_empty());
+        assert!(diff_texts("", "").is_empty());
+    }
+
+    #[test]
+    fn test_parse_unified() {
+        use DiffLineKind::*;
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10,3 +10,3 @@ mod tests;
+ use std::sync::Arc;
+-use foo::Bar;
++use foo::Baz;
+\\ No newline at end of file
+";
+        let lines = parse_unified(diff);
+        assert_eq!(
+            kinds(&lines),
+            [
+                (Hunk, None, None),
+                (Unchanged, Some(10), Some(10)),
+                (Removed, Some(11), None),
+                (Added, None, Some(11)),
+            ]
+        );
+        assert_eq!(lines[1].text, "use std::sync::Arc;");
+        assert_eq!(lines[3].changes, vec![(9..12)]);
+        assert_eq!(hunk_starts("@@ -0,0 +1 @@"), Some((1, 1)));
+    }
+
+    /// Applies `ops` to `old`, taking the inserted items from `new`.
+    fn apply<T: Clone + PartialEq + std::fmt::Debug>(ops: &[Op], old: &[T], new: &[T]) -> Vec<T> {
+        let (mut i, mut j) = (0, 0);
+        let mut result = Vec::new();
+        for op in ops {
+            match op {
+                Op::Equal => {
+                    assert_eq!(old[i], new[j]);
+                    result.push(old[i].clone());
+                    i += 1;
+                    j += 1;
+                }
+                Op::Delete => i += 1,
+                Op::Insert => {
+                    result.push(new[j].clone());
+                    j += 1;
+                }
+            }
+        }
+        assert_eq!((i, j), (old.len(), new.len()));
+        result
+    }
+
+    #[test]
+    fn test_edit_script() {
+        let cases = [
+            ("abcabba", "cbabac", 5),
+            ("", "abc", 3),
+            ("abc", "", 3),
+            ("abc", "abc", 0),
+            ("kitten", "sitting", 5),
+            ("a", "b", 2),
+            ("xaxbx", "ab", 3),
+        ];
+        for (old, new, edits) in cases {
+            let (old, new): (Vec<char>, Vec<char>) = (old.chars().collect(), new.chars().collect());
+            let ops = edit_script(&old, &new);
+            assert_eq!(apply(&ops, &old, &new), new);
+            assert_eq!(ops.iter().filter(|&&op| op != Op::Equal).count(), edits);
+        }
+
+        // Past the limit the differing middle is replaced as a whole
+        let old: Vec<usize> = (0..3 * MAX_EDITS).collect();
+        let mut new: Vec<usize> = old.iter().map(|i| i + 3 * MAX_EDITS).collect();
+        new[0] = 0;
+        let ops = edit_script(&old, &new);
+        assert_eq!(apply(&ops, &old, &new), new);
+        assert_eq!(ops[0], Op::Equal);
+        assert!(ops[1..3 * MAX_EDITS].iter().all(|&op| op == Op::Delete));
+    }
+}
@@ -0,0 +1,156 @@
+//! Provides the [`disabled`] wrapper, which makes any subtree non-interactive and greys it out.
+//!
+//! Components do not need disabled styling of their own: wrapping them in [`disabled`] skips
+//! the input handlers of everything inside and covers it with a translucent overlay, so every
+//! disabled control in an application looks the same.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use tessera_ui_basic_components::{
+//!     button::{button, ButtonArgs},
+//!     disabled::{disabled, DisabledArgs},
+//!     text::text,
+//!     RippleState,
+//! };
+//!
+//! let ripple_state = Arc::new(RippleState::new());
+//! let can_submit = false;
+//! disabled(
+//!     DisabledArgs {
+//!         disabled: !can_submit,
+//!         ..Default::default()
+//!     },
+//!     move || {
+//!         button(ButtonArgs::default(), ripple_state, || text("Submit".to_string()));
+//!     },
+//! );
+//! ```
+
+use derive_builder::Builder;
+use tessera_ui::{Color, ComputedData, Constraint, DimensionValue, PxPosition, tessera};
+
+use crate::{
+    pos_misc::is_position_in_shape,
+    shape_def::Shape,
+    surface::{SurfaceArgs, SurfaceArgsBuilder, surface},
+};
+
+/// Arguments for the [`disabled`] component.
+#[derive(Clone, Debug, Builder)]
+#[builder(pattern = "owned")]
+pub struct DisabledArgs {
+    /// Whether the child is disabled. While `false` the child is shown and behaves as usual,
+    /// so the wrapper can stay in place as the condition changes.
+    #[builder(default = "true")]
+    pub disabled: bool,
+    /// Color of the overlay covering the disabled child, which fades its colors toward gray.
+    #[builder(default = "Color::new(0.5, 0.5, 0.5, 0.5)")]
+    pub overlay_color: Color,
+    /// Shape of the overlay, which should match the shape of the child, e.g. the shape of a
+    /// rounded button.
+    #[builder(default)]
+    pub shape: Shape,
+}
+
+impl Default for DisabledArgs {
+    fn default() -> Self {
+        DisabledArgsBuilder::default().build().unwrap()
+    }
+}
+
+/// A wrapper that disables its child.
+///
+/// While [`DisabledArgs::disabled`] is set, the input handlers of the child and all of its
+/// descendants are skipped, so it cannot be clicked, hovered, focused by clicking or typed
+/// into, and cursor events over it do not reach the components behind it either. The child
+/// keeps its size and is covered with [`DisabledArgs::overlay_color`].
+///
+/// # Arguments
+///
+/// * `args` - Whether the child is disabled and how it is greyed out.
+/// * `child` - The content to disable.
+#[tessera]
+pub fn disabled(args: DisabledArgs, child: impl FnOnce()) {
+    child();
+    let (is_disabled, shape) = (args.disabled, args.shape);
+    if is_disabled {
+        surface(overlay_args(&args), None, || {});
+    }
+
+    measure(Box::new(move |input| {
+        if is_disabled {
+            input.disable_input();
+        }
+        let child_id = input.children_ids[0];
+        let size = input.measure_child(child_id, input.parent_constraint)?;
+        input.place_child(child_id, PxPosition::ZERO);
+        // The overlay covers exactly the child
+        if let Some(&overlay_id) = input.children_ids.get(1) {
+            let constraint = Constraint::new(
+                DimensionValue::Fixed(size.width),
+                DimensionValue::Fixed(size.height),
+            );
+            input.measure_child(overlay_id, &constraint)?;
+            input.place_child(overlay_id, PxPosition::ZERO);
+        }
+        Ok(ComputedData {
+            width: size.width,
+            height: size.height,
+        })
+    }));
+
+    input_handler(Box::new(move |mut input| {
+        let is_cursor_inside = input
+            .cursor_position_rel
+            .is_some_and(|pos| is_position_in_shape(shape, input.computed_data, pos));
+        if is_disabled && is_cursor_inside {
+            input.block_cursor();
+        }
+    }));
+}
+
+/// Returns the arguments of the overlay greying out a disabled child.
+fn overlay_args(args: &DisabledArgs) -> SurfaceArgs {
+    SurfaceArgsBuilder::default()
+        .style(args.overlay_color.into())
+        .shape(args.shape)
+        .width(DimensionValue::FILLED)
+        .height(DimensionValue::FILLED)
+        .build()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use tessera_ui::testing;
+
+    use super::*;
+    use crate::{
+        spacer::{SpacerArgs, spacer},
+        surface::SurfaceStyle,
+    };
+
+    #[test]
+    fn test_overlay_only_while_disabled() {
+        for (is_disabled, overlays) in [(true, 1), (false, 0)] {
+            let args = DisabledArgs {
+                disabled: is_disabled,
+                ..Default::default()
+            };
+            let tree = testing::compose(|| disabled(args, || spacer(SpacerArgs::fill_both())));
+            assert_eq!(tree.find_all_by_role("spacer").len(), 1);
+            assert_eq!(tree.find_all_by_role("surface").len(), overlays);
+        }
+    }
+
+    #[test]
+    fn test_overlay_is_translucent() {
+        let SurfaceStyle::Filled { color } = overlay_args(&DisabledArgs::default()).style else {
+            panic!("the overlay is a plain fill");
+        };
+        // The child shows through, faded
+        assert!(color.a > 0.0 && color.a < 1.0);
+    }
+}
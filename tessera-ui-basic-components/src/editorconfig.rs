@@ -0,0 +1,432 @@
+//! Loading of [EditorConfig](https://editorconfig.org) settings for text editors.
+//!
+//! [`EditorConfig::for_path`] reads the `.editorconfig` files that apply to a file, from its
+//! directory up to the first file marked `root = true`, and resolves the properties the
+//! [`crate::text_editor`] understands. The result can be applied to [`TextEditorArgs`] and to
+//! the [`SaveOptions`] of a [`crate::text_editor::TextEditorState`].
+//!
+//! Only available with the `editorconfig` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use tessera_ui_basic_components::{
+//!     editorconfig::EditorConfig,
+//!     text_editor::{SaveOptions, TextEditorArgs},
+//! };
+//!
+//! let config = EditorConfig::for_path("src/main.rs").unwrap();
+//! let args = config.apply_to_args(TextEditorArgs::simple());
+//! let save_options = config.apply_to_save_options(SaveOptions::default());
+//! ```
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::text_editor::{SaveOptions, TextEditorArgs};
+
+/// Whether indentation uses tab characters or spaces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// Indent with tab characters.
+    Tab,
+    /// Indent with spaces.
+    Space,
+}
+
+/// The EditorConfig properties that apply to one file. Unset properties are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditorConfig {
+    /// `indent_style`
+    pub indent_style: Option<IndentStyle>,
+    /// `indent_size`, in columns. `indent_size = tab` resolves to the tab width.
+    pub indent_size: Option<usize>,
+    /// `tab_width`, in columns. Defaults to the indent size.
+    pub tab_width: Option<usize>,
+    /// `trim_trailing_whitespace`
+    pub trim_trailing_whitespace: Option<bool>,
+    /// `insert_final_newline`
+    pub insert_final_newline: Option<bool>,
+    /// `max_line_length`, in columns. `off` resolves to `None`.
+    pub max_line_length: Option<usize>,
+}
+
+impl EditorConfig {
+    /// Resolves the settings for the file at `path` from the `.editorconfig` files in its
+    /// directory and all parent directories, up to the first one with `root = true`.
+    ///
+    /// Missing files are skipped; a directory without any gives the default, empty config.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file being edited. It does not need to exist.
+    pub fn for_path(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = std::path::absolute(path.as_ref())?;
+        let mut files: Vec<(PathBuf, String)> = Vec::new();
+        for dir in path.ancestors().skip(1) {
+            let contents = match fs::read_to_string(dir.join(".editorconfig")) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            let is_root = parse(&contents).0;
+            files.push((dir.to_path_buf(), contents));
+            if is_root {
+                break;
+            }
+        }
+
+        let mut properties = Properties::default();
+        // Closer files take precedence, so they are applied last
+        for (dir, contents) in files.iter().rev() {
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let relative: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            properties.apply_file(contents, &relative.join("/"));
+        }
+        Ok(properties.resolve())
+    }
+
+    /// Resolves the settings for `relative_path` from the contents of a single
+    /// `.editorconfig` file in the same directory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::editorconfig::{EditorConfig, IndentStyle};
+    ///
+    /// let config = EditorConfig::from_str_for(
+    ///     "[*.{rs,toml}]\nindent_style = space\nindent_size = 4\n",
+    ///     "src/lib.rs",
+    /// );
+    /// assert_eq!(config.indent_style, Some(IndentStyle::Space));
+    /// assert_eq!(config.indent_size, Some(4));
+    /// ```
+    pub fn from_str_for(contents: &str, relative_path: &str) -> Self {
+        let mut properties = Properties::default();
+        properties.apply_file(contents, relative_path);
+        properties.resolve()
+    }
+
+    /// Applies the indentation settings to editor arguments, and `max_line_length` as the only
+    /// ruler.
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - The arguments to update. Settings that are not configured are kept.
+    pub fn apply_to_args(&self, mut args: TextEditorArgs) -> TextEditorArgs {
+        match self.indent_style {
+            Some(IndentStyle::Space) => {
+                args.insert_spaces = true;
+                if let Some(size) = self.indent_size {
+                    args.tab_width = size;
+                }
+            }
+            Some(IndentStyle::Tab) => {
+                args.insert_spaces = false;
+                if let Some(width) = self.tab_width {
+                    args.tab_width = width;
+                }
+            }
+            None => {
+                if let Some(size) = self.indent_size {
+                    args.tab_width = size;
+                }
+            }
+        }
+        if let Some(max_line_length) = self.max_line_length {
+            args.rulers = vec![max_line_length];
+        }
+        args
+    }
+
+    /// Applies the whitespace settings to the clean-up done on save.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options to update. Settings that are not configured are kept.
+    pub fn apply_to_save_options(&self, mut options: SaveOptions) -> SaveOptions {
+        if let Some(trim) = self.trim_trailing_whitespace {
+            options.trim_trailing_whitespace = trim;
+        }
+        if let Some(final_newline) = self.insert_final_newline {
+            options.ensure_final_newline = final_newline;
+        }
+        options
+    }
+}
+
+/// Raw property values, lowercased, as set by the matching sections.
+#[derive(Default)]
+struct Properties {
+    indent_style: Option<String>,
+    indent_size: Option<String>,
+    tab_width: Option<String>,
+    trim_trailing_whitespace: Option<String>,
+    insert_final_newline: Option<String>,
+    max_line_length: Option<String>,
+}
+
+impl Properties {
+    fn apply_file(&mut self, contents: &str, relative_path: &str) {
+        for (pattern, pairs) in parse(contents).1 {
+            if !section_matches(&pattern, relative_path) {
+                continue;
+            }
+            for (key, value) in pairs {
+                let slot = match key.as_str() {
+                    "indent_style" => &mut self.indent_style,
+                    "indent_size" => &mut self.indent_size,
+                    "tab_width" => &mut self.tab_width,
+                    "trim_trailing_whitespace" => &mut self.trim_trailing_whitespace,
+                    "insert_final_newline" => &mut self.insert_final_newline,
+                    "max_line_length" => &mut self.max_line_length,
+                    _ => continue,
+                };
+                // `unset` removes a value set by an earlier section or file
+                *slot = (value != "unset").then_some(value);
+            }
+        }
+    }
+
+    fn resolve(self) -> EditorConfig {
+        let number = |value: &Option<String>| value.as_deref().and_then(|v| v.parse().ok());
+        let flag = |value: &Option<String>| match value.as_deref() {
+            Some("true") => Some(true),
+            Some("false") => Some(false),
+            _ => None,
+        };
+
+        let tab_width = number(&self.tab_width);
+        let indent_size = match self.indent_size.as_deref() {
+            Some("tab") => tab_width,
+            _ => number(&self.indent_size),
+        };
+        EditorConfig {
+            indent_style: match self.indent_style.as_deref() {
+                Some("tab") => Some(IndentStyle::Tab),
+                Some("space") => Some(IndentStyle::Space),
+                _ => None,
+            },
+            indent_size: indent_size.or(tab_width),
+            tab_width: tab_width.or(indent_size),
+            trim_trailing_whitespace: flag(&self.trim_trailing_whitespace),
+            insert_final_newline: flag(&self.insert_final_newline),
+            max_line_length: number(&self.max_line_length),
+        }
+    }
+}
+
+type Section = (String, Vec<(String, String)>);
+
+/// Parses an `.editorconfig` file into its `root` flag and its sections in order.
+fn parse(contents: &str) -> (bool, Vec<Section>) {
+    let mut is_root = false;
+    let mut sections: Vec<Section> = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(pattern) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((pattern.to_string(), Vec::new()));
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = value.trim().to_lowercase();
+        match sections.last_mut() {
+            Some((_, pairs)) => pairs.push((key, value)),
+            None if key == "root" => is_root = value == "true",
+            None => {}
+        }
+    }
+    (is_root, sections)
+}
+
+/// Returns `true` if the section `pattern` applies to `path`, which is relative to the
+/// directory of the `.editorconfig` file and uses `/` separators.
+fn section_matches(pattern: &str, path: &str) -> bool {
+    // Patterns without a slash match the file name in any directory
+    let pattern = if pattern.contains('/') {
+        pattern.strip_prefix('/').unwrap_or(pattern).to_string()
+    } else {
+        format!("**/{pattern}")
+    };
+    let path: Vec<char> = path.chars().collect();
+    expand_braces(&pattern).iter().any(|alternative| {
+        let alternative: Vec<char> = alternative.chars().collect();
+        // `**/` also matches no directory at all
+        glob_match(&alternative, &path)
+            || alternative.starts_with(&['*', '*', '/']) && glob_match(&alternative[3..], &path)
+    })
+}
+
+/// Expands `{a,b}` alternatives and `{1..3}` number ranges into plain glob patterns.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let Some(open) = chars.iter().position(|&c| c == '{') else {
+        return vec![pattern.to_string()];
+    };
+    let mut depth = 0;
+    let mut close = None;
+    let mut commas = Vec::new();
+    for (i, &c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(i);
+                    break;
+                }
+            }
+            ',' if depth == 1 => commas.push(i),
+            _ => {}
+        }
+    }
+    let Some(close) = close else {
+        return vec![pattern.to_string()];
+    };
+
+    let prefix: String = chars[..open].iter().collect();
+    let suffix: String = chars[close + 1..].iter().collect();
+    let inner: String = chars[open + 1..close].iter().collect();
+    let alternatives: Vec<String> = if !commas.is_empty() {
+        let mut bounds = vec![open];
+        bounds.extend(&commas);
+        bounds.push(close);
+        bounds
+            .windows(2)
+            .map(|w| chars[w[0] + 1..w[1]].iter().collect())
+            .collect()
+    } else if let Some((from, to)) = inner
+        .split_once("..")
+        .and_then(|(a, b)| Some((a.parse::<i64>().ok()?, b.parse::<i64>().ok()?)))
+    {
+        (from.min(to)..=from.max(to))
+            .map(|n| n.to_string())
+            .collect()
+    } else {
+        // A brace without alternatives is literal
+        vec![format!("{{{inner}}}")]
+    };
+
+    alternatives
+        .into_iter()
+        .flat_map(|alternative| expand_braces(&format!("{prefix}{alternative}{suffix}")))
+        .collect()
+}
+
+/// Matches `text` against a glob with `*`, `**`, `?`, `[...]` and `\` escapes.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            (0..=text.len()).any(|i| glob_match(&pattern[2..], &text[i..]))
+        }
+        Some('*') => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| glob_match(&pattern[1..], &text[i..])),
+        Some('?') => text
+            .first()
+            .is_some_and(|&c| c != '/' && glob_match(&pattern[1..], &text[1..])),
+        Some('[') => match pattern.iter().skip(1).position(|&c| c == ']') {
+            Some(len) => {
+                let class = &pattern[1..=len];
+                let (negated, class) = match class.first() {
+                    Some('!') => (true, &class[1..]),
+                    _ => (false, class),
+                };
+                text.first().is_some_and(|&c| {
+                    c != '/'
+                        && class_contains(class, c) != negated
+                        && glob_match(&pattern[len + 2..], &text[1..])
+                })
+            }
+            None => text.first() == Some(&'[') && glob_match(&pattern[1..], &text[1..]),
+        },
+        Some('\\') if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && glob_match(&pattern[2..], &text[1..])
+        }
+        Some(&c) => text.first() == Some(&c) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Returns `true` if `c` is in a character class such as `a-z_`.
+fn class_contains(class: &[char], c: char) -> bool {
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == '-' {
+            if class[i] <= c && c <= class[i + 2] {
+                return true;
+            }
+            i += 3;
+        } else {
+            if class[i] == c {
+                return true;
+            }
+            i += 1;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_section_matches() {
+        assert!(section_matches("*", "src/main.rs"));
+        assert!(section_matches("*.rs", "main.rs"));
+        assert!(section_matches("*.{rs,toml}", "crates/a/Cargo.toml"));
+        assert!(!section_matches("*.{rs,toml}", "README.md"));
+        assert!(section_matches("src/*.rs", "src/lib.rs"));
+        assert!(!section_matches("src/*.rs", "src/a/lib.rs"));
+        assert!(section_matches("src/**.rs", "src/a/lib.rs"));
+        assert!(section_matches("/Makefile", "Makefile"));
+        assert!(section_matches("file[0-9].txt", "file7.txt"));
+        assert!(!section_matches("file[!0-9].txt", "file7.txt"));
+        assert!(section_matches("v{1..3}.md", "v2.md"));
+        assert!(!section_matches("v{1..3}.md", "v4.md"));
+    }
+
+    #[test]
+    fn test_later_sections_override_and_unset() {
+        let contents = "\
+root = true
+
+[*]
+indent_style = tab
+tab_width = 8
+trim_trailing_whitespace = true
+insert_final_newline = true
+
+[*.py]
+indent_style = space
+indent_size = 4
+insert_final_newline = unset
+max_line_length = 88
+";
+        let python = EditorConfig::from_str_for(contents, "tool/run.py");
+        assert_eq!(python.indent_style, Some(IndentStyle::Space));
+        assert_eq!(python.indent_size, Some(4));
+        assert_eq!(python.tab_width, Some(8));
+        assert_eq!(python.trim_trailing_whitespace, Some(true));
+        assert_eq!(python.insert_final_newline, None);
+        assert_eq!(python.max_line_length, Some(88));
+
+        let other = EditorConfig::from_str_for(contents, "Makefile");
+        assert_eq!(other.indent_style, Some(IndentStyle::Tab));
+        assert_eq!(other.indent_size, Some(8));
+        assert!(parse(contents).0);
+    }
+}
@@ -0,0 +1,533 @@
+//! Long text that is collapsed to a few lines until the user asks to see the rest.
+//!
+//! The [`expandable_text`] component shows the first [`ExpandableTextArgs::collapsed_lines`]
+//! lines of its text, fades out the last visible line and offers a "Show more" toggle below it.
+//! Clicking the toggle animates the height to the full text, after which the toggle reads
+//! "Show less" and collapses the text again. Text that already fits is shown as-is, without a
+//! fade or toggle.
+//!
+//! The expanded flag and animation progress live in [`ExpandableTextState`], which the
+//! application keeps alive between frames.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use parking_lot::RwLock;
+//! use tessera_ui::Color;
+//! use tessera_ui_basic_components::expandable_text::{
+//!     expandable_text, ExpandableTextArgsBuilder, ExpandableTextState,
+//! };
+//!
+//! let state = Arc::new(RwLock::new(ExpandableTextState::new(false)));
+//!
+//! expandable_text(
+//!     ExpandableTextArgsBuilder::default()
+//!         .text("A long description that only needs to be read in full on demand...")
+//!         .collapsed_lines(2)
+//!         .fade_color(Color::WHITE) // match the background behind the text
+//!         .build()
+//!         .unwrap(),
+//!     state.clone(),
+//! );
+//! assert!(!state.read().is_expanded());
+//! ```
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use derive_builder::Builder;
+use parking_lot::RwLock;
+use tessera_ui::{
+    Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp, PressKeyEventType, Px,
+    PxPosition, clock, tessera, winit::window::CursorIcon,
+};
+
+use crate::{
+    animation,
+    pipelines::{ShapeCommand, TextConstraint, TextData},
+    text::{TextArgsBuilder, text},
+};
+
+/// Number of bands the fade over the last collapsed line is drawn with.
+const FADE_STEPS: usize = 8;
+
+/// State of an [`expandable_text`]: whether it is expanded and how far the animation has got.
+///
+/// # Example
+/// ```
+/// use tessera_ui_basic_components::expandable_text::ExpandableTextState;
+///
+/// let mut state = ExpandableTextState::new(false);
+/// state.toggle();
+/// assert!(state.is_expanded());
+/// ```
+pub struct ExpandableTextState {
+    expanded: bool,
+    progress: f32,
+    /// The progress when the toggle was last clicked, which the animation starts from.
+    toggle_progress: f32,
+    last_toggle_time: Option<Instant>,
+    overflows: bool,
+    /// The full and collapsed heights of the last layout and what they depend on.
+    heights: Option<(LayoutKey, f32, f32)>,
+}
+
+/// What the full and collapsed heights of an [`expandable_text`] depend on.
+#[derive(Clone, Debug, PartialEq)]
+struct LayoutKey {
+    text: String,
+    max_width: Option<Px>,
+    size: Dp,
+    line_height: Dp,
+    collapsed_lines: usize,
+}
+
+impl Default for ExpandableTextState {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl ExpandableTextState {
+    /// Creates a new state, starting expanded if `expanded` is `true`.
+    pub fn new(expanded: bool) -> Self {
+        Self {
+            expanded,
+            progress: if expanded { 1.0 } else { 0.0 },
+            toggle_progress: 0.0,
+            last_toggle_time: None,
+            overflows: false,
+            heights: None,
+        }
+    }
+
+    /// Switches between collapsed and expanded, animating the change.
+    ///
+    /// Toggling during the animation turns it around from the current height.
+    pub fn toggle(&mut self) {
+        self.expanded = !self.expanded;
+        self.toggle_progress = self.progress;
+        self.last_toggle_time = Some(clock::now());
+    }
+
+    /// Returns whether the full text is shown, or being animated towards.
+    pub fn is_expanded(&self) -> bool {
+        self.expanded
+    }
+
+    /// Returns whether the text had more lines than fit while collapsed at the last layout.
+    ///
+    /// The toggle is only shown when this is `true`.
+    pub fn overflows(&self) -> bool {
+        self.overflows
+    }
+
+    fn update_progress(&mut self, duration: Duration) {
+        let Some(last_toggle_time) = self.last_toggle_time else {
+            return;
+        };
+        // A full animation takes `duration`, a reversed one only the way back
+        let step = clock::elapsed(last_toggle_time).as_secs_f32() / duration.as_secs_f32();
+        self.progress = if self.expanded {
+            (self.toggle_progress + step).min(1.0)
+        } else {
+            (self.toggle_progress - step).max(0.0)
+        };
+    }
+
+    /// Returns the full and collapsed heights of the text, calling `layout` with the line limit
+    /// only when `key` differs from the last layout.
+    fn heights(&mut self, key: LayoutKey, layout: impl Fn(Option<usize>) -> f32) -> (f32, f32) {
+        if let Some((_, full, collapsed)) = self.heights.as_ref().filter(|(last, ..)| *last == key)
+        {
+            return (*full, *collapsed);
+        }
+        let full = layout(None);
+        let collapsed = layout(Some(key.collapsed_lines));
+        self.overflows = full > collapsed;
+        self.heights = Some((key, full, collapsed));
+        (full, collapsed)
+    }
+}
+
+/// Arguments for the [`expandable_text`] component.
+///
+/// # Example
+/// ```
+/// use tessera_ui_basic_components::expandable_text::ExpandableTextArgsBuilder;
+///
+/// let args = ExpandableTextArgsBuilder::default()
+///     .text("Release notes...")
+///     .collapsed_lines(4)
+///     .show_more_label("Read more")
+///     .build()
+///     .unwrap();
+/// assert_eq!(args.show_less_label, "Show less");
+/// ```
+#[derive(Builder, Clone)]
+#[builder(pattern = "owned")]
+pub struct ExpandableTextArgs {
+    /// The text to display.
+    #[builder(setter(into))]
+    pub text: String,
+    /// Color of the text.
+    #[builder(default = "Color::BLACK")]
+    pub color: Color,
+    /// Font size of the text and the toggle.
+    #[builder(default = "Dp(25.0)")]
+    pub size: Dp,
+    /// Optional line height override, defaults to 1.2 × `size`.
+    #[builder(default, setter(strip_option))]
+    pub line_height: Option<Dp>,
+    /// Number of lines shown while collapsed.
+    #[builder(default = "3")]
+    pub collapsed_lines: usize,
+    /// Color the last collapsed line fades into; usually the background color.
+    #[builder(default = "Color::WHITE")]
+    pub fade_color: Color,
+    /// Label of the toggle while collapsed.
+    #[builder(default = "\"Show more\".to_string()", setter(into))]
+    pub show_more_label: String,
+    /// Label of the toggle while expanded.
+    #[builder(default = "\"Show less\".to_string()", setter(into))]
+    pub show_less_label: String,
+    /// Color of the toggle label.
+    #[builder(default = "Color::new(0.4745, 0.5255, 0.7961, 1.0)")]
+    pub toggle_color: Color,
+    /// Duration of the height animation.
+    #[builder(default = "Duration::from_millis(250)")]
+    pub animation_duration: Duration,
+    /// Called with the new expanded flag whenever the toggle is clicked.
+    #[builder(default, setter(strip_option))]
+    pub on_toggle: Option<Arc<dyn Fn(bool) + Send + Sync>>,
+}
+
+impl From<String> for ExpandableTextArgs {
+    fn from(val: String) -> Self {
+        ExpandableTextArgsBuilder::default()
+            .text(val)
+            .build()
+            .unwrap()
+    }
+}
+
+impl From<&str> for ExpandableTextArgs {
+    fn from(val: &str) -> Self {
+        ExpandableTextArgsBuilder::default()
+            .text(val)
+            .build()
+            .unwrap()
+    }
+}
+
+/// Text clamped to a number of lines, with a fade and a "Show more"/"Show less" toggle.
+///
+/// # Arguments
+///
+/// - `args`: Text, styling and labels, see [`ExpandableTextArgs`].
+/// - `state`: Shared [`ExpandableTextState`] holding the expanded flag.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+/// use parking_lot::RwLock;
+/// use tessera_ui_basic_components::expandable_text::{expandable_text, ExpandableTextState};
+///
+/// let state = Arc::new(RwLock::new(ExpandableTextState::default()));
+/// expandable_text("Lorem ipsum dolor sit amet, consectetur adipiscing elit...", state);
+/// ```
+#[tessera]
+pub fn expandable_text(
+    args: impl Into<ExpandableTextArgs>,
+    state: Arc<RwLock<ExpandableTextState>>,
+) {
+    let args: ExpandableTextArgs = args.into();
+    let (expanded, overflows) = {
+        let state = state.read();
+        (state.expanded, state.overflows)
+    };
+
+    expandable_text_body(args.clone(), state.clone());
+
+    // Whether the text overflows is only known after layout, so the toggle follows a frame later.
+    if overflows {
+        let label = if expanded {
+            args.show_less_label.clone()
+        } else {
+            args.show_more_label.clone()
+        };
+        expandable_text_toggle(
+            label,
+            args.toggle_color,
+            args.size,
+            state.clone(),
+            args.on_toggle.clone(),
+        );
+    }
+
+    let duration = args.animation_duration;
+    input_handler(Box::new(move |_| {
+        state.write().update_progress(duration);
+    }));
+
+    measure(Box::new(move |input| {
+        let child_constraint = Constraint::new(
+            input.parent_constraint.width,
+            DimensionValue::Wrap {
+                min: None,
+                max: None,
+            },
+        );
+
+        let body_id = input.children_ids[0];
+        let body_size = input.measure_child(body_id, &child_constraint)?;
+        input.place_child(body_id, PxPosition::ZERO);
+
+        let mut width = body_size.width;
+        let mut height = body_size.height;
+        if let Some(&toggle_id) = input.children_ids.get(1) {
+            let toggle_size = input.measure_child(toggle_id, &child_constraint)?;
+            input.place_child(toggle_id, PxPosition::new(Px(0), height));
+            width = width.max(toggle_size.width);
+            height += toggle_size.height;
+        }
+
+        Ok(ComputedData { width, height })
+    }));
+}
+
+/// The text itself, clipped to the animated height, with the fade on top of it.
+#[tessera]
+fn expandable_text_body(args: ExpandableTextArgs, state: Arc<RwLock<ExpandableTextState>>) {
+    let (progress, overflows) = {
+        let state = state.read();
+        (state.progress, state.overflows)
+    };
+    let line_height = args.line_height.unwrap_or(Dp(args.size.0 * 1.2));
+
+    // At rest the text is limited to the collapsed lines; while animating it is laid out in
+    // full and clipped by this component instead.
+    let mut text_args = TextArgsBuilder::default()
+        .text(args.text.clone())
+        .color(args.color)
+        .size(args.size)
+        .line_height(line_height);
+    if progress <= 0.0 {
+        text_args = text_args.max_lines(args.collapsed_lines);
+    }
+    text(text_args.build().unwrap());
+
+    if overflows && progress < 1.0 {
+        let alpha = args.fade_color.a * (1.0 - animation::easing(progress));
+        for step in 1..=FADE_STEPS {
+            fade_band(
+                args.fade_color
+                    .with_alpha(alpha * step as f32 / FADE_STEPS as f32),
+            );
+        }
+    }
+
+    measure(Box::new(move |input| {
+        input.enable_clipping();
+
+        let max_width = match input.parent_constraint.width {
+            DimensionValue::Fixed(w) => Some(w),
+            DimensionValue::Wrap { max, .. } => max,
+            DimensionValue::Fill { max, .. } => max,
+        };
+        let layout = |max_lines| {
+            TextData::new(
+                args.text.clone(),
+                args.color,
+                args.size.to_pixels_f32(),
+                line_height.to_pixels_f32(),
+                TextConstraint {
+                    max_width: max_width.map(|px| px.to_f32()),
+                    max_height: None,
+                    max_lines,
+                },
+            )
+        };
+        let key = LayoutKey {
+            text: args.text.clone(),
+            max_width,
+            size: args.size,
+            line_height,
+            collapsed_lines: args.collapsed_lines,
+        };
+        let (full_height, collapsed_height) = state
+            .write()
+            .heights(key, |max_lines| layout(max_lines).size[1] as f32);
+
+        let eased = animation::easing(progress);
+        let height =
+            Px((collapsed_height + (full_height - collapsed_height) * eased).round() as i32);
+
+        let text_id = input.children_ids[0];
+        let text_size = input.measure_child(
+            text_id,
+            &Constraint::new(
+                input.parent_constraint.width,
+                DimensionValue::Wrap {
+                    min: None,
+                    max: None,
+                },
+            ),
+        )?;
+        input.place_child(text_id, PxPosition::ZERO);
+
+        // The fade covers the bottom line of whatever is currently visible.
+        let bands = &input.children_ids[1..];
+        if !bands.is_empty() {
+            let band_height = Px((line_height.to_pixels_f32() / FADE_STEPS as f32).ceil() as i32);
+            let band_constraint = Constraint::new(
+                DimensionValue::Fixed(text_size.width),
+                DimensionValue::Fixed(band_height),
+            );
+            let top = height - band_height * FADE_STEPS as i32;
+            for (i, &band_id) in bands.iter().enumerate() {
+                input.measure_child(band_id, &band_constraint)?;
+                input.place_child(
+                    band_id,
+                    PxPosition::new(Px(0), top + band_height * i as i32),
+                );
+            }
+        }
+
+        Ok(ComputedData {
+            width: text_size.width,
+            height,
+        })
+    }));
+}
+
+/// One horizontal band of the fade, filling the fixed size its parent gives it.
+#[tessera]
+fn fade_band(color: Color) {
+    measure(Box::new(move |input| {
+        let fixed = |dimension| match dimension {
+            DimensionValue::Fixed(px) => px,
+            _ => Px(0),
+        };
+        input.metadata_mut().push_draw_command(ShapeCommand::Rect {
+            color,
+            corner_radii: glam::Vec4::ZERO.into(),
+            g2_k_value: 3.0,
+            shadow: None,
+        });
+        Ok(ComputedData {
+            width: fixed(input.parent_constraint.width),
+            height: fixed(input.parent_constraint.height),
+        })
+    }));
+}
+
+/// The clickable "Show more"/"Show less" label.
+#[tessera]
+fn expandable_text_toggle(
+    label: String,
+    color: Color,
+    size: Dp,
+    state: Arc<RwLock<ExpandableTextState>>,
+    on_toggle: Option<Arc<dyn Fn(bool) + Send + Sync>>,
+) {
+    text(
+        TextArgsBuilder::default()
+            .text(label)
+            .color(color)
+            .size(size)
+            .build()
+            .unwrap(),
+    );
+
+    input_handler(Box::new(move |input| {
+        let size = input.computed_data;
+        let is_cursor_in = input.cursor_position_rel.is_some_and(|pos| {
+            pos.x.0 >= 0 && pos.x.0 < size.width.0 && pos.y.0 >= 0 && pos.y.0 < size.height.0
+        });
+        if !is_cursor_in {
+            return;
+        }
+        input.requests.cursor_icon = CursorIcon::Pointer;
+
+        let clicked = input.cursor_events.iter().any(|e| {
+            matches!(
+                e.content,
+                CursorEventContent::Pressed(PressKeyEventType::Left)
+            )
+        });
+        if clicked {
+            state.write().toggle();
+            if let Some(on_toggle) = &on_toggle {
+                on_toggle(state.read().expanded);
+            }
+            input.cursor_events.clear();
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DURATION: Duration = Duration::from_millis(200);
+
+    fn key(text: &str, max_width: i32) -> LayoutKey {
+        LayoutKey {
+            text: text.to_string(),
+            max_width: Some(Px(max_width)),
+            size: Dp(16.0),
+            line_height: Dp(20.0),
+            collapsed_lines: 2,
+        }
+    }
+
+    #[test]
+    fn test_toggle_reverses_from_current_progress() {
+        let clock = clock::manual();
+        let mut state = ExpandableTextState::new(false);
+
+        state.toggle();
+        clock.advance(DURATION / 4);
+        state.update_progress(DURATION);
+        assert_eq!(state.progress, 0.25);
+
+        // Collapsing again goes back from a quarter, taking a quarter of the time
+        state.toggle();
+        assert!(!state.is_expanded());
+        clock.advance(DURATION / 8);
+        state.update_progress(DURATION);
+        assert_eq!(state.progress, 0.125);
+        clock.advance(DURATION);
+        state.update_progress(DURATION);
+        assert_eq!(state.progress, 0.0);
+
+        state.toggle();
+        clock.advance(DURATION * 2);
+        state.update_progress(DURATION);
+        assert_eq!(state.progress, 1.0);
+    }
+
+    #[test]
+    fn test_heights_are_cached_per_text_and_width() {
+        let layouts = std::cell::Cell::new(0);
+        let layout = |max_lines: Option<usize>| {
+            layouts.set(layouts.get() + 1);
+            if max_lines.is_some() { 40.0 } else { 100.0 }
+        };
+        let mut state = ExpandableTextState::default();
+
+        assert_eq!(state.heights(key("text", 300), layout), (100.0, 40.0));
+        assert!(state.overflows());
+        assert_eq!(layouts.get(), 2);
+        assert_eq!(state.heights(key("text", 300), layout), (100.0, 40.0));
+        assert_eq!(layouts.get(), 2);
+
+        state.heights(key("text", 200), layout);
+        assert_eq!(layouts.get(), 4);
+        state.heights(key("other", 200), layout);
+        assert_eq!(layouts.get(), 6);
+    }
+}
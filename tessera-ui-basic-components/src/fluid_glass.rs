@@ -19,7 +19,7 @@ use tessera_ui::{
 use crate::{
     padding_utils::remove_padding_from_dimension,
     pipelines::{blur::command::DualBlurCommand, contrast::ContrastCommand, mean::MeanCommand},
-    pos_misc::is_position_in_component,
+    pos_misc::HitTest,
     ripple_state::RippleState,
     shape_def::Shape,
 };
@@ -52,6 +52,10 @@ pub struct FluidGlassArgs {
         default = "Shape::RoundedRectangle { top_left: Dp(25.0), top_right: Dp(25.0), bottom_right: Dp(25.0), bottom_left: Dp(25.0), g2_k_value: 3.0 }"
     )]
     pub shape: Shape,
+    /// The region that takes clicks. Defaults to [`HitTest::Shape`], so the transparent
+    /// corners of rounded and elliptical glass do not.
+    #[builder(default)]
+    pub hit_test: HitTest,
     /// The radius for the background blur effect. A value of `0.0` disables the blur.
     #[builder(default = "0.0")]
     pub blur_radius: f32,
@@ -118,6 +122,7 @@ impl PartialEq for FluidGlassArgs {
     fn eq(&self, other: &Self) -> bool {
         self.tint_color == other.tint_color
             && self.shape == other.shape
+            && self.hit_test == other.hit_test
             && self.blur_radius == other.blur_radius
             && self.dispersion_height == other.dispersion_height
             && self.chroma_multiplier == other.chroma_multiplier
@@ -175,7 +180,7 @@ fn handle_click_state(
     let size = input.computed_data;
     let cursor_pos_option = input.cursor_position_rel;
     let is_cursor_in = cursor_pos_option
-        .map(|pos| is_position_in_component(size, pos))
+        .map(|pos| args.hit_test.contains(args.shape, size, pos))
         .unwrap_or(false);
 
     if is_cursor_in {
@@ -208,11 +213,11 @@ fn handle_click_state(
     }
 }
 
-fn handle_block_input(input: &mut tessera_ui::InputHandlerInput) {
+fn handle_block_input(args: &FluidGlassArgs, input: &mut tessera_ui::InputHandlerInput) {
     let size = input.computed_data;
     let cursor_pos_option = input.cursor_position_rel;
     let is_cursor_in = cursor_pos_option
-        .map(|pos| is_position_in_component(size, pos))
+        .map(|pos| args.hit_test.contains(args.shape, size, pos))
         .unwrap_or(false);
 
     if is_cursor_in {
@@ -372,9 +377,10 @@ pub fn fluid_glass(
             );
         }));
     } else if args.block_input {
+        let args_for_handler = args.clone();
         input_handler(Box::new(move |mut input: tessera_ui::InputHandlerInput| {
             // Delegate to extracted helper for input blocking behavior.
-            handle_block_input(&mut input);
+            handle_block_input(&args_for_handler, &mut input);
         }));
     }
 }
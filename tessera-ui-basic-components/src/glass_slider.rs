@@ -17,7 +17,10 @@ use derive_builder::Builder;
 use parking_lot::RwLock;
 use tessera_ui::{
     Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp, Px, PxPosition,
-    focus_state::Focus, tessera, winit::window::CursorIcon,
+    focus_state::Focus,
+    haptics::{self, HapticFeedback},
+    tessera,
+    winit::window::CursorIcon,
 };
 
 use crate::{
@@ -261,6 +264,7 @@ pub fn glass_slider(args: impl Into<GlassSliderArgs>, state: Arc<RwLock<GlassSli
         if disabled {
             return;
         }
+        state.read().focus.join_traversal();
 
         let is_in_component =
             cursor_within_component(input.cursor_position_rel, &input.computed_data);
@@ -279,6 +283,9 @@ pub fn glass_slider(args: impl Into<GlassSliderArgs>, state: Arc<RwLock<GlassSli
         if let Some(v) = process_cursor_events(&mut state.write(), &input, width_f)
             && (v - args.value).abs() > f32::EPSILON
         {
+            if v == 0.0 || v == 1.0 {
+                haptics::perform(HapticFeedback::Tick);
+            }
             on_change(v);
         }
     }));
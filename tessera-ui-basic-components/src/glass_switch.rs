@@ -17,7 +17,11 @@ use derive_builder::Builder;
 use parking_lot::RwLock;
 use tessera_ui::{
     Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp, PressKeyEventType,
-    PxPosition, tessera, winit::window::CursorIcon,
+    PxPosition, clock,
+    haptics::{self, HapticFeedback},
+    sound::{self, Cue},
+    tessera,
+    winit::window::CursorIcon,
 };
 
 use crate::{
@@ -54,7 +58,7 @@ impl GlassSwitchState {
     /// Toggles the switch state.
     pub fn toggle(&mut self) {
         self.checked = !self.checked;
-        self.last_toggle_time = Some(Instant::now());
+        self.last_toggle_time = Some(clock::now());
     }
 
     /// Returns whether the switch is currently checked.
@@ -120,7 +124,7 @@ fn interpolate_color(off: Color, on: Color, progress: f32) -> Color {
 fn update_progress_from_state(state: Arc<RwLock<GlassSwitchState>>) {
     let last_toggle_time = state.read().last_toggle_time;
     if let Some(last_toggle_time) = last_toggle_time {
-        let elapsed = last_toggle_time.elapsed();
+        let elapsed = clock::elapsed(last_toggle_time);
         let fraction = (elapsed.as_secs_f32() / ANIMATION_DURATION.as_secs_f32()).min(1.0);
         let checked = state.read().checked;
         state.write().progress = if checked { fraction } else { 1.0 - fraction };
@@ -173,6 +177,8 @@ fn handle_input_events(
     if pressed && is_cursor_in {
         // If internal state exists, toggle it and use the toggled value.
         state.write().toggle();
+        sound::play(Cue::Toggle);
+        haptics::perform(HapticFeedback::Click);
         on_toggle(state.read().checked);
     }
 }
@@ -1,6 +1,6 @@
 //! This module provides the `image` component and related utilities for rendering images in Tessera UI.
 //!
-//! It supports loading image data from file paths or raw bytes, decoding them into a format suitable for GPU rendering,
+//! It supports loading image data from file paths, raw bytes or the [assets](crate::assets) of the application, decoding them into a format suitable for GPU rendering,
 //! and displaying them as part of the UI component tree. The main entry point is the [`image()`] component, which can be
 //! sized explicitly or use the intrinsic dimensions of the image. Image data should be loaded and decoded outside the
 //! main UI loop for optimal performance, using [`load_image_from_source`].
@@ -8,17 +8,17 @@
 //! Typical use cases include displaying static images, icons, or dynamically loaded pictures in UI layouts.
 //! The module is designed to integrate seamlessly with Tessera's stateless component model and rendering pipeline.
 
-use std::sync::Arc;
+use std::{io, sync::Arc};
 
 use derive_builder::Builder;
 use image::GenericImageView;
 use tessera_ui::{ComputedData, Constraint, DimensionValue, Px, tessera};
 
-use crate::pipelines::image::ImageCommand;
+use crate::{assets::AssetError, pipelines::image::ImageCommand};
 
 pub use crate::pipelines::image::ImageData;
 
-/// Specifies the source for image data, which can be either a file path or raw bytes.
+/// Specifies the source for image data: a file path, raw bytes or an asset.
 ///
 /// This enum is used by [`load_image_from_source`] to load image data from different sources.
 #[derive(Clone, Debug)]
@@ -27,6 +27,9 @@ pub enum ImageSource {
     Path(String),
     /// Load image from a byte slice. The data is wrapped in an `Arc` for efficient sharing.
     Bytes(Arc<[u8]>),
+    /// Load image from the path of an asset, looked up with the
+    /// [global asset service](crate::assets::global), which caches the decoded image.
+    Asset(String),
 }
 
 /// Decodes an image from a given [`ImageSource`].
@@ -47,14 +50,33 @@ pub enum ImageSource {
 pub fn load_image_from_source(source: &ImageSource) -> Result<ImageData, image::ImageError> {
     let decoded = match source {
         ImageSource::Path(path) => image::open(path)?,
-        ImageSource::Bytes(bytes) => image::load_from_memory(bytes)?,
+        ImageSource::Bytes(bytes) => return decode_image(bytes),
+        ImageSource::Asset(path) => {
+            return match crate::assets::global().load_image(path) {
+                Ok(image) => Ok(ImageData::clone(&image)),
+                Err(AssetError::NotFound(path)) => Err(image::ImageError::IoError(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("asset `{path}` not found"),
+                ))),
+                Err(err) => Err(image::ImageError::IoError(io::Error::other(err))),
+            };
+        }
     };
+    Ok(image_data(decoded))
+}
+
+/// Decodes the image file `bytes`, in any format the `image` crate is built with.
+pub(crate) fn decode_image(bytes: &[u8]) -> Result<ImageData, image::ImageError> {
+    image::load_from_memory(bytes).map(image_data)
+}
+
+fn image_data(decoded: image::DynamicImage) -> ImageData {
     let (width, height) = decoded.dimensions();
-    Ok(ImageData {
+    ImageData {
         data: Arc::new(decoded.to_rgba8().into_raw()),
         width,
         height,
-    })
+    }
 }
 
 /// Arguments for the `image` component.
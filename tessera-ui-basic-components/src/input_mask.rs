@@ -0,0 +1,309 @@
+//! Input masks that format editor content as the user types.
+//!
+//! An [`InputMask`] turns the raw characters typed by the user (for example the digits of a
+//! phone number) into a formatted value with separators, and maps the caret so that it stays
+//! after the same raw character when separators are inserted or removed.
+//!
+//! Masks are meant for single-line editors; line breaks are never part of the raw value.
+//! Attach a mask with [`crate::text_editor::TextEditorArgs::input_mask`] and read the
+//! unformatted value back with [`crate::text_editor::TextEditorState::raw_text`].
+//!
+//! # Example
+//!
+//! ```
+//! use tessera_ui_basic_components::input_mask::InputMask;
+//!
+//! let mask = InputMask::phone();
+//! assert_eq!(mask.format("5551234567"), "(555) 123-4567");
+//! assert_eq!(mask.raw("(555) 123-4567"), "5551234567");
+//! ```
+
+/// Describes how raw input is formatted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputMask {
+    /// A fixed pattern of placeholders and literal characters.
+    ///
+    /// `#` accepts a digit, `A` accepts a letter and `*` accepts any letter or digit.
+    /// Every other character is a literal that is inserted automatically.
+    /// Input beyond the last placeholder is dropped.
+    Pattern(String),
+    /// A currency amount: digits grouped in thousands, with an optional fraction.
+    Currency {
+        /// Prefix placed before the amount, e.g. `"$"`.
+        symbol: String,
+        /// Character used between groups of three digits.
+        group_separator: char,
+        /// Character separating the fraction.
+        decimal_separator: char,
+        /// Maximum number of fraction digits. `0` disables the fraction.
+        decimals: usize,
+    },
+}
+
+impl InputMask {
+    /// US phone number, formatted as `(555) 123-4567`.
+    pub fn phone() -> Self {
+        Self::Pattern("(###) ###-####".to_string())
+    }
+
+    /// Card number in groups of four digits, formatted as `1234 5678 9012 3456`.
+    pub fn credit_card() -> Self {
+        Self::Pattern("#### #### #### ####".to_string())
+    }
+
+    /// Dollar amount with two decimals, formatted as `$1,234.56`.
+    pub fn currency() -> Self {
+        Self::Currency {
+            symbol: "$".to_string(),
+            group_separator: ',',
+            decimal_separator: '.',
+            decimals: 2,
+        }
+    }
+
+    /// Extracts the raw value from `text`, dropping separators and any character the mask rejects.
+    pub fn raw(&self, text: &str) -> String {
+        let (formatted, _) = self.format_with_caret(text, 0);
+        match self {
+            InputMask::Pattern(pattern) => formatted
+                .chars()
+                .zip(pattern.chars())
+                .filter(|(_, slot)| is_placeholder(*slot))
+                .map(|(c, _)| c)
+                .collect(),
+            InputMask::Currency {
+                symbol,
+                group_separator,
+                ..
+            } => formatted
+                .strip_prefix(symbol.as_str())
+                .unwrap_or(&formatted)
+                .chars()
+                .filter(|c| c != group_separator)
+                .collect(),
+        }
+    }
+
+    /// Formats `text`, which may be raw or already (partially) formatted.
+    pub fn format(&self, text: &str) -> String {
+        self.format_with_caret(text, 0).0
+    }
+
+    /// Formats `text` and maps a caret byte offset in `text` to a byte offset in the result.
+    ///
+    /// The caret stays directly after the last accepted character that preceded it.
+    pub fn format_with_caret(&self, text: &str, caret: usize) -> (String, usize) {
+        match self {
+            InputMask::Pattern(pattern) => {
+                format_pattern(pattern, pattern_input(pattern, text, caret))
+            }
+            InputMask::Currency {
+                symbol,
+                group_separator,
+                decimal_separator,
+                decimals,
+            } => {
+                // Each accepted character together with whether it was typed before the caret.
+                let candidates = text
+                    .char_indices()
+                    .filter(|(_, c)| c.is_ascii_digit() || self.is_decimal_separator(*c))
+                    .map(|(index, c)| (c, index < caret));
+                format_currency(
+                    symbol,
+                    *group_separator,
+                    *decimal_separator,
+                    *decimals,
+                    candidates,
+                )
+            }
+        }
+    }
+
+    fn is_decimal_separator(&self, c: char) -> bool {
+        matches!(self, InputMask::Currency { decimal_separator, decimals, .. } if *decimals > 0 && c == *decimal_separator)
+    }
+}
+
+fn is_placeholder(slot: char) -> bool {
+    matches!(slot, '#' | 'A' | '*')
+}
+
+fn slot_accepts(slot: char, c: char) -> bool {
+    match slot {
+        '#' => c.is_ascii_digit(),
+        'A' => c.is_alphabetic(),
+        '*' => c.is_alphanumeric(),
+        _ => false,
+    }
+}
+
+/// Returns the input characters of `text` for `pattern`, each with whether it was typed
+/// before `caret`.
+///
+/// The text is walked against the pattern by position, so characters standing where the
+/// pattern has the same literal are separators even if a placeholder would accept them, like
+/// the `1` of `+1 (###) ###-####`. Other characters fill the next placeholder that accepts
+/// them, or are dropped.
+fn pattern_input(pattern: &str, text: &str, caret: usize) -> Vec<(char, bool)> {
+    let mut slots = pattern.chars().peekable();
+    let mut input = Vec::new();
+    for (index, c) in text.char_indices() {
+        if slots
+            .next_if(|slot| *slot == c && !is_placeholder(*slot))
+            .is_some()
+        {
+            continue;
+        }
+        while slots.next_if(|slot| !is_placeholder(*slot)).is_some() {}
+        if slots.next_if(|slot| slot_accepts(*slot, c)).is_some() {
+            input.push((c, index < caret));
+        }
+    }
+    input
+}
+
+fn format_pattern(
+    pattern: &str,
+    candidates: impl IntoIterator<Item = (char, bool)>,
+) -> (String, usize) {
+    let mut candidates = candidates.into_iter().peekable();
+    let mut formatted = String::new();
+    let mut caret = 0;
+
+    for slot in pattern.chars() {
+        if !is_placeholder(slot) {
+            // Literals only appear once there is input to follow them.
+            if candidates.peek().is_none() {
+                break;
+            }
+            formatted.push(slot);
+            continue;
+        }
+
+        // Skip input that does not fit this slot.
+        let Some((c, before_caret)) = candidates.find(|(c, _)| slot_accepts(slot, *c)) else {
+            break;
+        };
+        formatted.push(c);
+        if before_caret {
+            caret = formatted.len();
+        }
+    }
+
+    (formatted, caret)
+}
+
+fn format_currency(
+    symbol: &str,
+    group_separator: char,
+    decimal_separator: char,
+    decimals: usize,
+    candidates: impl Iterator<Item = (char, bool)>,
+) -> (String, usize) {
+    let mut integer: Vec<(char, bool)> = Vec::new();
+    let mut fraction: Option<Vec<(char, bool)>> = None;
+    for (c, before_caret) in candidates {
+        match &mut fraction {
+            None if c == decimal_separator => fraction = Some(Vec::new()),
+            None if c.is_ascii_digit() => integer.push((c, before_caret)),
+            Some(digits) if c.is_ascii_digit() && digits.len() < decimals => {
+                digits.push((c, before_caret))
+            }
+            _ => {}
+        }
+    }
+
+    // Leading zeros carry no value, but keep one so "0.5" stays readable.
+    let leading_zeros = integer
+        .iter()
+        .take_while(|(c, _)| *c == '0')
+        .count()
+        .min(integer.len().saturating_sub(1));
+    integer.drain(..leading_zeros);
+
+    let mut formatted = String::new();
+    if integer.is_empty() && fraction.is_none() {
+        return (formatted, 0);
+    }
+    formatted.push_str(symbol);
+    let mut caret = formatted.len();
+
+    for (i, (c, before_caret)) in integer.iter().enumerate() {
+        let remaining = integer.len() - i;
+        if i > 0 && remaining.is_multiple_of(3) {
+            formatted.push(group_separator);
+        }
+        formatted.push(*c);
+        if *before_caret {
+            caret = formatted.len();
+        }
+    }
+
+    if let Some(digits) = fraction {
+        if integer.is_empty() {
+            formatted.push('0');
+        }
+        formatted.push(decimal_separator);
+        for (c, before_caret) in digits {
+            formatted.push(c);
+            if before_caret {
+                caret = formatted.len();
+            }
+        }
+    }
+
+    (formatted, caret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_formats_partial_input() {
+        let mask = InputMask::phone();
+        assert_eq!(mask.format(""), "");
+        assert_eq!(mask.format("555"), "(555");
+        assert_eq!(mask.format("5551"), "(555) 1");
+        assert_eq!(mask.format("555123456789"), "(555) 123-4567");
+        assert_eq!(mask.format("55a5"), "(555");
+    }
+
+    #[test]
+    fn test_pattern_keeps_caret_after_same_raw_char() {
+        let mask = InputMask::credit_card();
+        // User typed a digit in the middle of "1234 5678", caret after the inserted '9'.
+        let (formatted, caret) = mask.format_with_caret("12349 5678", 5);
+        assert_eq!(formatted, "1234 9567 8");
+        assert_eq!(&formatted[..caret], "1234 9");
+    }
+
+    #[test]
+    fn test_currency_grouping_and_raw_value() {
+        let mask = InputMask::currency();
+        assert_eq!(mask.format("1234567"), "$1,234,567");
+        assert_eq!(mask.format("$1,234.5678"), "$1,234.56");
+        assert_eq!(mask.format(".5"), "$0.5");
+        assert_eq!(mask.format("007"), "$7");
+        assert_eq!(mask.raw("$1,234.56"), "1234.56");
+
+        let (formatted, caret) = mask.format_with_caret("$1,2345", 7);
+        assert_eq!(formatted, "$12,345");
+        assert_eq!(caret, formatted.len());
+    }
+
+    #[test]
+    fn test_pattern_with_digit_literals() {
+        let mask = InputMask::Pattern("+1 (###) ###-####".to_string());
+        assert_eq!(mask.format("5551234567"), "+1 (555) 123-4567");
+        // The country code is a separator, not the first digit
+        assert_eq!(mask.format("+1 (555) 123-4567"), "+1 (555) 123-4567");
+        assert_eq!(mask.raw("+1 (555) 123-4567"), "5551234567");
+        assert_eq!(mask.raw("+1 (555"), "555");
+
+        // A digit typed in the middle shifts the rest, with the caret after it
+        let (formatted, caret) = mask.format_with_caret("+1 (555) 1239-4567", 13);
+        assert_eq!(formatted, "+1 (555) 123-9456");
+        assert_eq!(&formatted[..caret], "+1 (555) 123-9");
+    }
+}
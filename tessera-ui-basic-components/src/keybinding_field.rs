@@ -0,0 +1,398 @@
+//! A field showing the key binding of an editor command, which records a new one when clicked.
+//!
+//! ## Usage
+//!
+//! Settings pages that let users change the shortcuts of a
+//! [`text_editor`](crate::text_editor::text_editor). The [`EditorKeymap`] is shared with the
+//! editor, which is given a copy of it through
+//! [`TextEditorArgs::keymap`](crate::text_editor::TextEditorArgs::keymap) every frame.
+//!
+//! ## Recording
+//!
+//! Clicking the field, or pressing Enter or Space while it has the focus, starts recording:
+//! the modifiers held are shown as they are pressed, and the next chord replaces the bindings
+//! of the command in the keymap. A chord that [conflicts](EditorKeymap::conflicts) with the
+//! binding of another command is only written back once it is pressed a second time, which
+//! removes that binding. Escape, or moving the focus away, stops recording without a change.
+
+use std::sync::Arc;
+
+use derive_builder::Builder;
+use parking_lot::RwLock;
+use tessera_ui::{
+    Color, CursorEventContent, DimensionValue, Dp, Focus, PressKeyEventType,
+    key_chord::{KeyChord, format_modifiers},
+    tessera, theme,
+    winit::{
+        keyboard::{Key, ModifiersState, NamedKey},
+        window::CursorIcon,
+    },
+};
+
+use crate::{
+    shape_def::Shape,
+    surface::{SurfaceArgsBuilder, SurfaceStyle, surface},
+    text::{TextArgsBuilder, text},
+    text_editor::{EditorCommand, EditorKeymap},
+};
+
+/// The state of a [`keybinding_field`]: its focus and the chord being recorded.
+///
+/// # Example
+///
+/// ```
+/// use tessera_ui_basic_components::keybinding_field::KeybindingFieldState;
+///
+/// let mut state = KeybindingFieldState::new();
+/// state.start_recording();
+/// assert!(state.is_recording());
+/// state.stop_recording();
+/// assert!(!state.is_recording());
+/// ```
+#[derive(Default)]
+pub struct KeybindingFieldState {
+    focus: Focus,
+    recording: bool,
+    /// Modifiers held while recording, shown until a key is pressed with them.
+    held: ModifiersState,
+    /// A recorded chord bound to another command, written back if it is pressed again.
+    conflict: Option<KeyChord>,
+}
+
+impl KeybindingFieldState {
+    /// Creates a state that is not recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` while the field waits for a chord.
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Focuses the field and waits for the next chord.
+    pub fn start_recording(&mut self) {
+        self.focus.request_focus();
+        self.recording = true;
+        self.held = ModifiersState::empty();
+        self.conflict = None;
+    }
+
+    /// Stops waiting for a chord, leaving the binding unchanged.
+    pub fn stop_recording(&mut self) {
+        self.recording = false;
+        self.conflict = None;
+    }
+
+    /// Records `chord` for `command`, returning `true` if it was written back to `keymap`.
+    fn press(
+        &mut self,
+        chord: KeyChord,
+        command: EditorCommand,
+        keymap: &mut EditorKeymap,
+    ) -> bool {
+        if chord == KeyChord::named(NamedKey::Escape, ModifiersState::empty()) {
+            self.stop_recording();
+            return false;
+        }
+        let clashes = keymap
+            .conflicts(std::slice::from_ref(&chord))
+            .iter()
+            .any(|&(_, bound)| bound != command);
+        if clashes && self.conflict.as_ref() != Some(&chord) {
+            self.conflict = Some(chord);
+            return false;
+        }
+        keymap.rebind(command, [chord]);
+        self.stop_recording();
+        true
+    }
+
+    /// Returns the text of the field for `command` bound in `keymap`.
+    fn label(&self, command: EditorCommand, keymap: &EditorKeymap) -> String {
+        if !self.recording {
+            let sequences: Vec<String> = keymap
+                .sequences_for(command)
+                .into_iter()
+                .map(|sequence| {
+                    let chords: Vec<String> = sequence.iter().map(KeyChord::to_string).collect();
+                    chords.join(" ")
+                })
+                .collect();
+            return match sequences.is_empty() {
+                true => "Unbound".to_string(),
+                false => sequences.join(", "),
+            };
+        }
+        match &self.conflict {
+            Some(chord) if self.held.is_empty() => {
+                let commands: Vec<String> = keymap
+                    .conflicts(std::slice::from_ref(chord))
+                    .into_iter()
+                    .filter(|&(_, bound)| bound != command)
+                    .map(|(_, bound)| format!("{bound:?}"))
+                    .collect();
+                format!(
+                    "{chord} is used by {}, press it again to replace",
+                    commands.join(", ")
+                )
+            }
+            _ if self.held.is_empty() => "Press a key".to_string(),
+            _ => format_modifiers(self.held),
+        }
+    }
+}
+
+/// Arguments of the [`keybinding_field`] component.
+#[derive(Builder, Clone)]
+#[builder(pattern = "owned")]
+pub struct KeybindingFieldArgs {
+    /// The command whose binding the field shows and records.
+    pub command: EditorCommand,
+    /// Called with the recorded chord after it was written back to the keymap, e.g. to save
+    /// the keymap.
+    #[builder(default, setter(strip_option))]
+    pub on_change: Option<Arc<dyn Fn(KeyChord) + Send + Sync>>,
+    /// Width of the field.
+    #[builder(default = "DimensionValue::Fixed(Dp(200.0).into())", setter(into))]
+    pub width: DimensionValue,
+    /// Font size of the binding.
+    #[builder(default = "Dp(14.0)")]
+    pub text_size: Dp,
+    /// Color of the binding.
+    #[builder(default = "Color::BLACK")]
+    pub text_color: Color,
+    /// Background color of the field.
+    #[builder(default = "Color::new(0.95, 0.95, 0.95, 1.0)")]
+    pub color: Color,
+    /// Border color of the field while it does not record.
+    #[builder(default = "Color::new(0.55, 0.55, 0.55, 1.0)")]
+    pub border_color: Color,
+    /// Border color of the field while it records. Defaults to the
+    /// [accent color](tessera_ui::theme::accent_color), or blue without one.
+    #[builder(default = "theme::accent_or(Color::new(0.2, 0.5, 0.8, 1.0))")]
+    pub recording_border_color: Color,
+    /// Border color of the field while the recorded chord conflicts with another binding.
+    #[builder(default = "Color::new(0.85, 0.25, 0.2, 1.0)")]
+    pub conflict_border_color: Color,
+}
+
+impl From<EditorCommand> for KeybindingFieldArgs {
+    fn from(command: EditorCommand) -> Self {
+        KeybindingFieldArgsBuilder::default()
+            .command(command)
+            .build()
+            .unwrap()
+    }
+}
+
+/// A field showing the binding of an editor command in `keymap`, which records the next chord
+/// pressed as its new binding when clicked. See the [module documentation](self).
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use parking_lot::RwLock;
+/// use tessera_ui_basic_components::{
+///     keybinding_field::{KeybindingFieldArgsBuilder, KeybindingFieldState, keybinding_field},
+///     text_editor::{EditorCommand, EditorKeymap, TextEditorArgs},
+/// };
+///
+/// let keymap = Arc::new(RwLock::new(EditorKeymap::default()));
+/// let state = Arc::new(RwLock::new(KeybindingFieldState::new()));
+/// keybinding_field(
+///     KeybindingFieldArgsBuilder::default()
+///         .command(EditorCommand::ToggleComment)
+///         .on_change(Arc::new(|chord| println!("Comment with {chord}")))
+///         .build()
+///         .unwrap(),
+///     state,
+///     keymap.clone(),
+/// );
+///
+/// // The editor picks up the recorded binding from the shared keymap
+/// let args = TextEditorArgs::simple().with_keymap(keymap.read().clone());
+/// ```
+#[tessera]
+pub fn keybinding_field(
+    args: impl Into<KeybindingFieldArgs>,
+    state: Arc<RwLock<KeybindingFieldState>>,
+    keymap: Arc<RwLock<EditorKeymap>>,
+) {
+    let args: KeybindingFieldArgs = args.into();
+    let label = state.read().label(args.command, &keymap.read());
+    let border_color = match &*state.read() {
+        state if state.conflict.is_some() => args.conflict_border_color,
+        state if state.recording => args.recording_border_color,
+        _ => args.border_color,
+    };
+    surface(
+        SurfaceArgsBuilder::default()
+            .style(SurfaceStyle::FilledOutlined {
+                fill_color: args.color,
+                border_color,
+                border_width: Dp(1.0),
+            })
+            .shape(Shape::RoundedRectangle {
+                top_left: Dp(4.0),
+                top_right: Dp(4.0),
+                bottom_right: Dp(4.0),
+                bottom_left: Dp(4.0),
+                g2_k_value: 3.0,
+            })
+            .padding(Dp(8.0))
+            .width(args.width)
+            .build()
+            .unwrap(),
+        None,
+        move || {
+            text(
+                TextArgsBuilder::default()
+                    .text(label)
+                    .color(args.text_color)
+                    .size(args.text_size)
+                    .build()
+                    .unwrap(),
+            )
+        },
+    );
+
+    input_handler(Box::new(move |input| {
+        let size = input.computed_data;
+        let is_cursor_in = input.cursor_position_rel.is_some_and(|pos| {
+            pos.x.0 >= 0 && pos.x.0 < size.width.0 && pos.y.0 >= 0 && pos.y.0 < size.height.0
+        });
+        if is_cursor_in {
+            input.requests.cursor_icon = CursorIcon::Pointer;
+        }
+        let clicked = is_cursor_in
+            && input.cursor_events.iter().any(|event| {
+                matches!(
+                    event.content,
+                    CursorEventContent::Pressed(PressKeyEventType::Left)
+                )
+            });
+
+        let mut state = state.write();
+        state.focus.join_traversal();
+        if !state.recording {
+            let key_count = input.keyboard_events.len();
+            if state.focus.is_focused() {
+                input.keyboard_events.retain(|event| {
+                    !(event.state.is_pressed()
+                        && matches!(
+                            event.logical_key,
+                            Key::Named(NamedKey::Enter | NamedKey::Space)
+                        ))
+                });
+            }
+            let activated = state.focus.take_activation();
+            if clicked || activated || input.keyboard_events.len() < key_count {
+                state.start_recording();
+            }
+            return;
+        }
+        if !state.focus.is_focused() {
+            state.stop_recording();
+            return;
+        }
+
+        // The field takes every key while recording, so none reaches the components around it
+        let modifiers = input.key_modifiers;
+        state.held = modifiers;
+        for event in input.keyboard_events.drain(..) {
+            let Some(chord) = KeyChord::from_event(&event, modifiers) else {
+                continue;
+            };
+            let written = state.press(chord.clone(), args.command, &mut keymap.write());
+            if written && let Some(on_change) = &args.on_change {
+                on_change(chord);
+            }
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctrl(c: char) -> KeyChord {
+        KeyChord::character(c, ModifiersState::CONTROL)
+    }
+
+    #[test]
+    fn test_press_writes_back_the_chord() {
+        let mut keymap =
+            EditorKeymap::empty().with_binding([ctrl('/')], EditorCommand::ToggleComment);
+        let mut state = KeybindingFieldState::new();
+        assert_eq!(state.label(EditorCommand::ToggleComment, &keymap), "Ctrl+/");
+
+        state.start_recording();
+        assert_eq!(
+            state.label(EditorCommand::ToggleComment, &keymap),
+            "Press a key"
+        );
+        state.held = ModifiersState::CONTROL | ModifiersState::SHIFT;
+        assert_eq!(
+            state.label(EditorCommand::ToggleComment, &keymap),
+            "Ctrl+Shift+"
+        );
+
+        assert!(state.press(ctrl('m'), EditorCommand::ToggleComment, &mut keymap));
+        assert!(!state.is_recording());
+        assert_eq!(
+            keymap.sequences_for(EditorCommand::ToggleComment),
+            vec![&[ctrl('m')][..]]
+        );
+        assert_eq!(state.label(EditorCommand::ToggleComment, &keymap), "Ctrl+M");
+    }
+
+    #[test]
+    fn test_conflicting_chord_needs_a_second_press() {
+        let mut keymap = EditorKeymap::empty()
+            .with_binding([ctrl('c')], EditorCommand::Copy)
+            .with_binding([ctrl('/')], EditorCommand::ToggleComment);
+        let mut state = KeybindingFieldState::new();
+        state.start_recording();
+
+        assert!(!state.press(ctrl('c'), EditorCommand::ToggleComment, &mut keymap));
+        assert!(state.is_recording());
+        assert_eq!(
+            state.label(EditorCommand::ToggleComment, &keymap),
+            "Ctrl+C is used by Copy, press it again to replace"
+        );
+        assert_eq!(keymap.command_for(&[ctrl('c')]), Some(EditorCommand::Copy));
+
+        // Another chord is recorded as the new conflict or binding instead
+        assert!(state.press(ctrl('m'), EditorCommand::ToggleComment, &mut keymap));
+        state.start_recording();
+        assert!(!state.press(ctrl('c'), EditorCommand::ToggleComment, &mut keymap));
+        assert!(state.press(ctrl('c'), EditorCommand::ToggleComment, &mut keymap));
+        assert_eq!(
+            keymap.command_for(&[ctrl('c')]),
+            Some(EditorCommand::ToggleComment)
+        );
+        assert_eq!(state.label(EditorCommand::Copy, &keymap), "Unbound");
+    }
+
+    #[test]
+    fn test_escape_cancels_recording() {
+        let mut keymap =
+            EditorKeymap::empty().with_binding([ctrl('/')], EditorCommand::ToggleComment);
+        let mut state = KeybindingFieldState::new();
+        state.start_recording();
+        let escape = KeyChord::named(NamedKey::Escape, ModifiersState::empty());
+        assert!(!state.press(escape, EditorCommand::ToggleComment, &mut keymap));
+        assert!(!state.is_recording());
+        assert_eq!(
+            keymap.command_for(&[ctrl('/')]),
+            Some(EditorCommand::ToggleComment)
+        );
+
+        // The chord already bound to the command is no conflict
+        state.start_recording();
+        assert!(state.press(ctrl('/'), EditorCommand::ToggleComment, &mut keymap));
+        assert_eq!(keymap.bindings().len(), 1);
+    }
+}
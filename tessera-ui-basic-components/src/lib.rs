@@ -57,27 +57,41 @@ mod padding_utils;
 mod selection_highlight_rect;
 
 pub mod alignment;
+pub mod assets;
 pub mod bottom_sheet;
 pub mod boxed;
 pub mod button;
 pub mod checkbox;
 mod checkmark;
+pub mod code_block;
 pub mod column;
 pub mod dialog;
+pub mod diff_viewer;
+pub mod disabled;
+#[cfg(feature = "editorconfig")]
+pub mod editorconfig;
+pub mod expandable_text;
 pub mod fluid_glass;
 pub mod glass_button;
 pub mod glass_progress;
 pub mod glass_slider;
 pub mod glass_switch;
 pub mod image;
+pub mod input_mask;
+pub mod keybinding_field;
+pub mod log_view;
+pub mod magnifier;
+pub mod outline;
 pub mod pipelines;
 pub mod pos_misc;
 pub mod progress;
+pub mod recovery;
 pub mod ripple_state;
 pub use ripple_state::RippleState;
 pub mod bottom_nav_bar;
 pub mod row;
 pub mod scrollable;
+pub mod selectable_text;
 pub mod shape_def;
 pub mod side_bar;
 pub mod slider;
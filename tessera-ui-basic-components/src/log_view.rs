@@ -0,0 +1,565 @@
+//! A view of an append-only stream of log lines.
+//!
+//! [`log_view`] shows the lines pushed into a [`LogViewState`]. The state keeps at most a fixed
+//! number of lines, dropping the oldest ones as new ones arrive, and lays out only the lines
+//! that were added since the last frame, so it stays cheap for long-running streams.
+//!
+//! - ANSI color and bold escape sequences are drawn and removed from the text
+//! - Every line gets a [`LogLevel`], detected from its text or inherited from the line before,
+//!   and [`LogViewState::set_min_level`] hides the less severe ones
+//! - [`LogViewState::set_search_query`] highlights the occurrences of a string
+//! - While following the tail, the view sticks to the newest line until the user scrolls up
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use parking_lot::RwLock;
+//! use tessera_ui_basic_components::log_view::{LogLevel, LogViewState, log_view};
+//!
+//! let state = Arc::new(RwLock::new(LogViewState::new(5_000)));
+//! state.write().push("\x1b[32mINFO\x1b[0m server started");
+//! state.write().push("WARN disk almost full");
+//! state.write().set_min_level(Some(LogLevel::Warning));
+//! assert_eq!(state.read().shown_line_count(), 1);
+//! // log_view(Default::default(), state.clone());
+//! ```
+
+mod ansi;
+
+use std::{collections::VecDeque, ops::Range, sync::Arc};
+
+use derive_builder::Builder;
+use parking_lot::RwLock;
+use tessera_ui::{Color, ComputedData, DimensionValue, Dp, Px, PxPosition, tessera};
+
+use crate::{
+    log_view::ansi::{AnsiStyle, parse_ansi},
+    pipelines::{TextCommand, TextData, write_font_system},
+    scrollable::{ScrollableArgsBuilder, ScrollableState, scrollable},
+    selection_highlight_rect::selection_highlight_rect,
+    surface::{SurfaceArgsBuilder, surface},
+    text_edit_core::RectDef,
+};
+
+/// Number of lines a [`LogViewState`] keeps by default.
+pub const DEFAULT_MAX_LINES: usize = 10_000;
+
+/// How severe a log line is, from least to most severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// Step by step tracing of what the program does.
+    Trace,
+    /// Details for debugging.
+    Debug,
+    /// Normal operation.
+    Info,
+    /// Something unexpected that the program recovered from.
+    Warning,
+    /// A failure.
+    Error,
+}
+
+impl LogLevel {
+    /// Detects the level of a line from the first of its first few words that names one,
+    /// e.g. `ERROR`, `[warn]` or `Info:`, ignoring case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::log_view::LogLevel;
+    ///
+    /// assert_eq!(LogLevel::detect("2024-01-01 12:00:00 [WARN] low memory"), Some(LogLevel::Warning));
+    /// assert_eq!(LogLevel::detect("    at main.rs:12"), None);
+    /// ```
+    pub fn detect(line: &str) -> Option<Self> {
+        line.split_whitespace().take(4).find_map(|word| {
+            let word = word.trim_matches(|c: char| !c.is_alphabetic());
+            match word.to_ascii_lowercase().as_str() {
+                "trace" => Some(LogLevel::Trace),
+                "debug" => Some(LogLevel::Debug),
+                "info" => Some(LogLevel::Info),
+                "warn" | "warning" => Some(LogLevel::Warning),
+                "error" | "err" | "fatal" | "critical" => Some(LogLevel::Error),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// One stored line, without its escape sequences.
+#[derive(Clone, Debug)]
+struct LogLine {
+    text: String,
+    spans: Vec<(Range<usize>, AnsiStyle)>,
+    level: Option<LogLevel>,
+}
+
+/// The lines of a [`log_view`], how they are filtered and where the view is scrolled.
+pub struct LogViewState {
+    lines: VecDeque<LogLine>,
+    max_lines: usize,
+    /// Style left open by the last pushed line
+    ansi_style: AnsiStyle,
+    min_level: Option<LogLevel>,
+    search_query: String,
+    follow_tail: bool,
+    scrollable_state: Arc<ScrollableState>,
+    /// The shown lines laid out for drawing, or `None` when they have to be laid out again
+    buffer: Option<glyphon::Buffer>,
+    /// Number of lines at the start of the buffer that were dropped from `lines` since it
+    /// was last drawn, removed from it all at once
+    dropped_lines: usize,
+    /// Font size, line height and color the buffer was built with
+    buffer_style: (f32, f32, Color),
+    /// What to draw for the buffer, or `None` when it changed since it was last drawn
+    text_data: Option<TextData>,
+    match_rects: Vec<RectDef>,
+    /// Whether the buffer or the search query changed since `match_rects` were computed
+    match_rects_stale: bool,
+}
+
+impl Default for LogViewState {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_LINES)
+    }
+}
+
+impl LogViewState {
+    /// Creates an empty state that keeps at most `max_lines` lines, and at least one.
+    pub fn new(max_lines: usize) -> Self {
+        let scrollable_state = Arc::new(ScrollableState::new());
+        scrollable_state.scroll_to_end();
+        Self {
+            lines: VecDeque::new(),
+            max_lines: max_lines.max(1),
+            ansi_style: AnsiStyle::default(),
+            min_level: None,
+            search_query: String::new(),
+            follow_tail: true,
+            scrollable_state,
+            buffer: None,
+            dropped_lines: 0,
+            buffer_style: (0.0, 0.0, Color::TRANSPARENT),
+            text_data: None,
+            match_rects: Vec::new(),
+            match_rects_stale: true,
+        }
+    }
+
+    /// Appends `text`, one line per line of it. The level of each line is detected with
+    /// [`LogLevel::detect`]; lines without one, such as the frames of a stack trace, take the
+    /// level of the line before.
+    pub fn push(&mut self, text: &str) {
+        for line in text.lines() {
+            let (text, spans) = parse_ansi(line, &mut self.ansi_style);
+            let level =
+                LogLevel::detect(&text).or_else(|| self.lines.back().and_then(|line| line.level));
+            self.push_line(LogLine { text, spans, level });
+        }
+    }
+
+    /// Appends `text`, one line per line of it, all with the given level.
+    pub fn push_with_level(&mut self, text: &str, level: LogLevel) {
+        for line in text.lines() {
+            let (text, spans) = parse_ansi(line, &mut self.ansi_style);
+            self.push_line(LogLine {
+                text,
+                spans,
+                level: Some(level),
+            });
+        }
+    }
+
+    fn push_line(&mut self, line: LogLine) {
+        if self.lines.len() == self.max_lines
+            && let Some(removed) = self.lines.pop_front()
+            && self.is_shown(&removed)
+            && self.buffer.is_some()
+        {
+            self.dropped_lines += 1;
+            self.invalidate_layout();
+        }
+        if self.is_shown(&line)
+            && let Some(buffer) = &mut self.buffer
+        {
+            buffer.lines.push(buffer_line(&line, self.buffer_style.2));
+            self.invalidate_layout();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Removes all lines.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.ansi_style = AnsiStyle::default();
+        self.discard_buffer();
+    }
+
+    /// Drops the laid out lines, so they are laid out again from `lines`.
+    fn discard_buffer(&mut self) {
+        self.buffer = None;
+        self.dropped_lines = 0;
+        self.invalidate_layout();
+    }
+
+    /// Marks what is drawn as out of date with the buffer.
+    fn invalidate_layout(&mut self) {
+        self.text_data = None;
+        self.match_rects_stale = true;
+    }
+
+    /// Returns the number of stored lines, including hidden ones.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns `true` if no lines are stored.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns the stored lines, oldest first, without their escape sequences.
+    pub fn lines(&self) -> impl Iterator<Item = (&str, Option<LogLevel>)> {
+        self.lines
+            .iter()
+            .map(|line| (line.text.as_str(), line.level))
+    }
+
+    /// Returns the number of lines that pass the level filter.
+    pub fn shown_line_count(&self) -> usize {
+        self.lines.iter().filter(|line| self.is_shown(line)).count()
+    }
+
+    /// Returns the least severe level that is shown, or `None` if all lines are.
+    pub fn min_level(&self) -> Option<LogLevel> {
+        self.min_level
+    }
+
+    /// Hides lines less severe than `level`. Lines without a level are always shown.
+    pub fn set_min_level(&mut self, level: Option<LogLevel>) {
+        if self.min_level != level {
+            self.min_level = level;
+            self.discard_buffer();
+        }
+    }
+
+    fn is_shown(&self, line: &LogLine) -> bool {
+        line.level
+            .zip(self.min_level)
+            .is_none_or(|(level, min)| level >= min)
+    }
+
+    /// Returns the highlighted search string. It is empty when nothing is highlighted.
+    pub fn search_query(&self) -> &str {
+        &self.search_query
+    }
+
+    /// Highlights every occurrence of `query` in the shown lines, case-sensitively. An empty
+    /// query removes the highlights.
+    pub fn set_search_query(&mut self, query: impl Into<String>) {
+        let query = query.into();
+        if self.search_query != query {
+            self.search_query = query;
+            self.match_rects_stale = true;
+        }
+    }
+
+    /// Returns the indices among the shown lines of the lines that contain the search query.
+    pub fn matching_lines(&self) -> Vec<usize> {
+        if self.search_query.is_empty() {
+            return Vec::new();
+        }
+        self.lines
+            .iter()
+            .filter(|line| self.is_shown(line))
+            .enumerate()
+            .filter(|(_, line)| line.text.contains(&self.search_query))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns `true` while the view follows new lines, i.e. following is on and the user has
+    /// not scrolled away from the end.
+    pub fn follow_tail(&self) -> bool {
+        self.follow_tail && self.scrollable_state.is_at_end()
+    }
+
+    /// Turns following new lines on, scrolling to the newest one, or off.
+    pub fn set_follow_tail(&mut self, follow: bool) {
+        self.follow_tail = follow;
+        if follow {
+            self.scrollable_state.scroll_to_end();
+        }
+    }
+
+    /// Lays out the lines added since the last call and returns what to draw.
+    fn text_data(&mut self, size: f32, line_height: f32, color: Color) -> TextData {
+        if self.buffer_style != (size, line_height, color) {
+            self.buffer_style = (size, line_height, color);
+            self.discard_buffer();
+        }
+        if let Some(text_data) = &self.text_data {
+            return text_data.clone();
+        }
+        if let Some(buffer) = &mut self.buffer {
+            buffer.lines.drain(..self.dropped_lines);
+        }
+        self.dropped_lines = 0;
+        let buffer = self.buffer.get_or_insert_with(|| {
+            let mut buffer = glyphon::Buffer::new(
+                &mut write_font_system(),
+                glyphon::Metrics::new(size, line_height),
+            );
+            buffer.set_wrap(&mut write_font_system(), glyphon::Wrap::None);
+            buffer.set_size(&mut write_font_system(), None, None);
+            buffer.lines = self
+                .lines
+                .iter()
+                .filter(|line| {
+                    line.level
+                        .zip(self.min_level)
+                        .is_none_or(|(level, min)| level >= min)
+                })
+                .map(|line| buffer_line(line, color))
+                .collect();
+            buffer
+        });
+        buffer.shape_until_scroll(&mut write_font_system(), false);
+        let text_data = TextData::from_buffer(buffer.clone());
+        self.text_data = Some(text_data.clone());
+        text_data
+    }
+
+    /// Returns the highlights of the search query in the laid out lines, computing them
+    /// again if the lines or the query changed.
+    fn match_rects(&mut self) -> &[RectDef] {
+        if self.match_rects_stale {
+            self.match_rects = self.compute_match_rects();
+            self.match_rects_stale = false;
+        }
+        &self.match_rects
+    }
+
+    /// Computes the highlights of the search query in the laid out lines.
+    fn compute_match_rects(&self) -> Vec<RectDef> {
+        let (Some(buffer), false) = (&self.buffer, self.search_query.is_empty()) else {
+            return Vec::new();
+        };
+        let mut rects = Vec::new();
+        for run in buffer.layout_runs() {
+            for (start, found) in run.text.match_indices(&self.search_query) {
+                let start_cursor = glyphon::Cursor::new(run.line_i, start);
+                let end_cursor = glyphon::Cursor::new(run.line_i, start + found.len());
+                if let Some((x, width)) = run.highlight(start_cursor, end_cursor) {
+                    rects.push(RectDef {
+                        x: Px(x as i32),
+                        y: Px(run.line_top as i32),
+                        width: Px(width as i32),
+                        height: Px(run.line_height as i32),
+                    });
+                }
+            }
+        }
+        rects
+    }
+}
+
+fn glyphon_color(color: Color) -> glyphon::Color {
+    glyphon::Color::rgba(
+        (color.r * 255.0) as u8,
+        (color.g * 255.0) as u8,
+        (color.b * 255.0) as u8,
+        (color.a * 255.0) as u8,
+    )
+}
+
+/// Builds the laid out form of `line`, drawn in `color` where no escape sequence colors it.
+fn buffer_line(line: &LogLine, color: Color) -> glyphon::BufferLine {
+    let attrs = glyphon::Attrs::new()
+        .family(glyphon::fontdb::Family::SansSerif)
+        .color(glyphon_color(color));
+    let mut attrs_list = glyphon::AttrsList::new(&attrs);
+    for (range, style) in &line.spans {
+        let mut span = attrs.clone();
+        if let Some(color) = style.color {
+            span = span.color(glyphon_color(color));
+        }
+        if style.bold {
+            span = span.weight(glyphon::Weight::BOLD);
+        }
+        attrs_list.add_span(range.clone(), &span);
+    }
+    glyphon::BufferLine::new(
+        line.text.clone(),
+        glyphon::cosmic_text::LineEnding::None,
+        attrs_list,
+        glyphon::Shaping::Advanced,
+    )
+}
+
+/// Arguments for the [`log_view`] component.
+#[derive(Builder, Clone)]
+#[builder(pattern = "owned")]
+pub struct LogViewArgs {
+    /// Width of the view. Defaults to `Fill`.
+    #[builder(default = "DimensionValue::FILLED", setter(into))]
+    pub width: DimensionValue,
+    /// Height of the view. Defaults to `Fill`.
+    #[builder(default = "DimensionValue::FILLED", setter(into))]
+    pub height: DimensionValue,
+    /// Font size of the lines.
+    #[builder(default = "Dp(13.0)")]
+    pub size: Dp,
+    /// Line height. Defaults to 1.3 × the font size.
+    #[builder(default, setter(strip_option))]
+    pub line_height: Option<Dp>,
+    /// Color of text that no escape sequence colors.
+    #[builder(default = "Color::new(0.15, 0.15, 0.15, 1.0)")]
+    pub color: Color,
+    /// Background of the view.
+    #[builder(default = "Color::new(0.97, 0.97, 0.97, 1.0)")]
+    pub background_color: Color,
+    /// Background of the occurrences of the search query.
+    #[builder(default = "Color::new(1.0, 0.8, 0.0, 0.35)")]
+    pub search_highlight_color: Color,
+    /// Padding between the edge of the view and the lines.
+    #[builder(default = "Dp(6.0)")]
+    pub padding: Dp,
+}
+
+impl Default for LogViewArgs {
+    fn default() -> Self {
+        LogViewArgsBuilder::default().build().unwrap()
+    }
+}
+
+/// Shows the lines of a [`LogViewState`] in a scrollable area. See the
+/// [module documentation](self) for what it supports.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use parking_lot::RwLock;
+/// use tessera_ui_basic_components::log_view::{LogViewArgsBuilder, LogViewState, log_view};
+/// use tessera_ui::Dp;
+///
+/// let state = Arc::new(RwLock::new(LogViewState::default()));
+/// let args = LogViewArgsBuilder::default().size(Dp(12.0)).build().unwrap();
+/// // log_view(args, state.clone());
+/// ```
+#[tessera]
+pub fn log_view(args: impl Into<LogViewArgs>, state: Arc<RwLock<LogViewState>>) {
+    let args: LogViewArgs = args.into();
+    let (scrollable_state, follow_tail) = {
+        let guard = state.read();
+        (guard.scrollable_state.clone(), guard.follow_tail)
+    };
+    surface(
+        SurfaceArgsBuilder::default()
+            .style(args.background_color.into())
+            .width(args.width)
+            .height(args.height)
+            .padding(args.padding)
+            .build()
+            .unwrap(),
+        None,
+        move || {
+            scrollable(
+                ScrollableArgsBuilder::default()
+                    .width(DimensionValue::FILLED)
+                    .height(DimensionValue::FILLED)
+                    .horizontal(true)
+                    .stick_to_end(follow_tail)
+                    .build()
+                    .unwrap(),
+                scrollable_state,
+                move || log_lines(args, state),
+            );
+        },
+    );
+}
+
+/// The laid out lines of a [`log_view`], with one child per search highlight.
+#[tessera]
+fn log_lines(args: LogViewArgs, state: Arc<RwLock<LogViewState>>) {
+    let match_rects = state.read().match_rects.clone();
+    for rect in &match_rects {
+        selection_highlight_rect(rect.width, rect.height, args.search_highlight_color);
+    }
+
+    measure(Box::new(move |input| {
+        let line_height = args.line_height.unwrap_or(Dp(args.size.0 * 1.3));
+        let mut state = state.write();
+        let text_data = state.text_data(
+            args.size.to_pixels_f32(),
+            line_height.to_pixels_f32(),
+            args.color,
+        );
+
+        // The number of highlight children was decided from the rects stored last pass
+        for (&child, rect) in input.children_ids.iter().zip(state.match_rects()) {
+            input.measure_child(child, input.parent_constraint)?;
+            input.place_child(child, PxPosition::new(rect.x, rect.y));
+        }
+
+        let size = ComputedData {
+            width: Px(text_data.size[0] as i32),
+            height: Px(text_data.size[1] as i32),
+        };
+        input
+            .metadata_mut()
+            .push_draw_command(TextCommand { data: text_data });
+        Ok(size)
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_keeps_max_lines_and_inherits_levels() {
+        let mut state = LogViewState::new(3);
+        state.push("ERROR boom\n    at main.rs:1\nINFO ok");
+        state.push("DEBUG next");
+        let lines: Vec<_> = state.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                ("    at main.rs:1", Some(LogLevel::Error)),
+                ("INFO ok", Some(LogLevel::Info)),
+                ("DEBUG next", Some(LogLevel::Debug)),
+            ]
+        );
+
+        state.set_min_level(Some(LogLevel::Info));
+        assert_eq!(state.shown_line_count(), 2);
+        state.set_search_query("ok");
+        assert_eq!(state.matching_lines(), [1]);
+    }
+
+    #[test]
+    fn test_layout_is_reused_until_lines_change() {
+        let mut state = LogViewState::new(2);
+        assert!(state.follow_tail());
+        state.push("INFO one\nINFO two");
+        state.text_data(13.0, 17.0, Color::BLACK);
+        assert!(state.text_data.is_some());
+        state.set_search_query("two");
+        assert_eq!(state.match_rects().len(), 1);
+        state.set_search_query("two");
+        assert!(!state.match_rects_stale);
+
+        // Dropped lines leave the buffer together on the next layout
+        state.push("INFO three\nINFO four");
+        assert!(state.text_data.is_none());
+        assert_eq!(state.dropped_lines, 2);
+        state.text_data(13.0, 17.0, Color::BLACK);
+        let buffer = state.buffer.as_ref().unwrap();
+        let texts: Vec<_> = buffer.lines.iter().map(|line| line.text()).collect();
+        assert_eq!(texts, ["INFO three", "INFO four"]);
+        assert!(state.match_rects().is_empty());
+    }
+}
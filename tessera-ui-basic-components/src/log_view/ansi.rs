@@ -0,0 +1,157 @@
+//! Parsing of the ANSI escape sequences that color terminal output.
+
+use std::ops::Range;
+
+use tessera_ui::Color;
+
+/// Style set by the SGR ("select graphic rendition") escape sequences.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct AnsiStyle {
+    /// Foreground color, or `None` for the default text color.
+    pub(crate) color: Option<Color>,
+    pub(crate) bold: bool,
+}
+
+/// The 16 standard and bright terminal colors.
+const PALETTE: [Color; 16] = [
+    Color::from_rgb_u8(0, 0, 0),
+    Color::from_rgb_u8(205, 49, 49),
+    Color::from_rgb_u8(13, 188, 121),
+    Color::from_rgb_u8(229, 229, 16),
+    Color::from_rgb_u8(36, 114, 200),
+    Color::from_rgb_u8(188, 63, 188),
+    Color::from_rgb_u8(17, 168, 205),
+    Color::from_rgb_u8(229, 229, 229),
+    Color::from_rgb_u8(102, 102, 102),
+    Color::from_rgb_u8(241, 76, 76),
+    Color::from_rgb_u8(35, 209, 139),
+    Color::from_rgb_u8(245, 245, 67),
+    Color::from_rgb_u8(59, 142, 234),
+    Color::from_rgb_u8(214, 112, 214),
+    Color::from_rgb_u8(41, 184, 219),
+    Color::from_rgb_u8(255, 255, 255),
+];
+
+/// Returns color `index` of the 256 color palette: the 16 terminal colors, a 6×6×6 cube and
+/// 24 grays.
+fn palette_256(index: u8) -> Color {
+    match index {
+        0..=15 => PALETTE[index as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = index - 16;
+            Color::from_rgb_u8(level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            Color::from_rgb_u8(gray, gray, gray)
+        }
+    }
+}
+
+/// Applies the parameters of one SGR sequence, e.g. `1;31` of `ESC[1;31m`, to `style`.
+fn apply_sgr(style: &mut AnsiStyle, params: &str) {
+    let mut codes = params
+        .split(';')
+        .map(|code| code.parse::<u8>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        match code {
+            0 => *style = AnsiStyle::default(),
+            1 => style.bold = true,
+            22 => style.bold = false,
+            30..=37 => style.color = Some(PALETTE[(code - 30) as usize]),
+            90..=97 => style.color = Some(PALETTE[(code - 90 + 8) as usize]),
+            39 => style.color = None,
+            38 => match codes.next() {
+                Some(5) => style.color = codes.next().map(palette_256),
+                Some(2) => {
+                    let (r, g, b) = (codes.next(), codes.next(), codes.next());
+                    style.color = Some(Color::from_rgb_u8(
+                        r.unwrap_or(0),
+                        g.unwrap_or(0),
+                        b.unwrap_or(0),
+                    ));
+                }
+                _ => {}
+            },
+            // Backgrounds, underlines and the like are not drawn
+            _ => {}
+        }
+    }
+}
+
+/// Removes the escape sequences from `line`, returning the plain text and the byte ranges of
+/// it that have a non-default style.
+///
+/// `style` is the style in effect at the start of the line and is updated to the one at its
+/// end, since terminal output may color several lines with one sequence.
+pub(crate) fn parse_ansi(
+    line: &str,
+    style: &mut AnsiStyle,
+) -> (String, Vec<(Range<usize>, AnsiStyle)>) {
+    let mut text = String::with_capacity(line.len());
+    let mut spans: Vec<(Range<usize>, AnsiStyle)> = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            let start = text.len();
+            text.push(c);
+            if *style != AnsiStyle::default() {
+                match spans.last_mut() {
+                    Some((range, last)) if range.end == start && last == style => {
+                        range.end = text.len();
+                    }
+                    _ => spans.push((start..text.len(), *style)),
+                }
+            }
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_none() {
+            // A two character sequence such as `ESC c`
+            chars.next();
+            continue;
+        }
+        let mut params = String::new();
+        for c in chars.by_ref() {
+            if ('\x40'..='\x7e').contains(&c) {
+                if c == 'm' {
+                    apply_sgr(style, &params);
+                }
+                break;
+            }
+            params.push(c);
+        }
+    }
+    (text, spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ansi() {
+        let mut style = AnsiStyle::default();
+        let (text, spans) = parse_ansi("\x1b[1;31mERROR\x1b[0m failed \x1b[2K", &mut style);
+        assert_eq!(text, "ERROR failed ");
+        assert_eq!(
+            spans,
+            [(
+                0..5,
+                AnsiStyle {
+                    color: Some(PALETTE[1]),
+                    bold: true
+                }
+            )]
+        );
+        assert_eq!(style, AnsiStyle::default());
+
+        // An unterminated color carries over to the next line
+        let (_, spans) = parse_ansi("\x1b[38;5;196mred", &mut style);
+        assert_eq!(spans[0].1.color, Some(Color::from_rgb_u8(255, 0, 0)));
+        let (text, spans) = parse_ansi("still red\x1b[39m", &mut style);
+        assert_eq!(text, "still red");
+        assert_eq!(spans[0].0, 0..9);
+        assert_eq!(style, AnsiStyle::default());
+    }
+}
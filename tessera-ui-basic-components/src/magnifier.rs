@@ -0,0 +1,447 @@
+//! A lens showing a zoomed view of the content under the pointer.
+//!
+//! [`magnifier`] lays out its content as usual and, while the lens is shown, draws the area
+//! around the pointer scaled up by [`MagnifierArgs::zoom`] on top of it. The lens samples the
+//! rendered scene, so it works over any content: it helps low-vision users read small text,
+//! and lets touch users see where the caret lands when selecting text in a
+//! [`text_editor`](crate::text_editor::text_editor).
+//!
+//! [`MagnifierArgs::offset`] moves the lens up from the pointer, so a finger does not cover it.
+//! The lens stays inside the content, near its edges it moves aside but still shows the area
+//! around the pointer.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use parking_lot::RwLock;
+//! use tessera_ui::Dp;
+//! use tessera_ui_basic_components::{
+//!     magnifier::{
+//!         LONG_PRESS_DELAY, MagnifierArgsBuilder, MagnifierState, MagnifierTrigger, magnifier,
+//!     },
+//!     text_editor::{TextEditorArgs, TextEditorState, text_editor},
+//! };
+//!
+//! let lens_state = Arc::new(RwLock::new(MagnifierState::new()));
+//! let editor_state = Arc::new(RwLock::new(TextEditorState::new(Dp(16.0), None)));
+//! let args = MagnifierArgsBuilder::default()
+//!     .trigger(MagnifierTrigger::LongPress(LONG_PRESS_DELAY))
+//!     .offset(Dp(64.0))
+//!     .build()
+//!     .unwrap();
+//! magnifier(args, lens_state, move || {
+//!     text_editor(TextEditorArgs::default(), editor_state);
+//! });
+//! ```
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use derive_builder::Builder;
+use parking_lot::RwLock;
+use tessera_ui::{
+    BarrierRequirement, Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp,
+    PressKeyEventType, Px, PxPosition, PxSize, clock, renderer::DrawCommand, tessera,
+};
+
+/// A common delay for [`MagnifierTrigger::LongPress`].
+pub const LONG_PRESS_DELAY: Duration = Duration::from_millis(500);
+
+/// How far the pointer may move while held before a long press is given up.
+const LONG_PRESS_SLOP: Dp = Dp(8.0);
+
+/// The outline of the lens of a [`magnifier`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MagnifierShape {
+    /// A circle.
+    #[default]
+    Circle,
+    /// A square.
+    Rectangle,
+}
+
+/// When a [`magnifier`] shows its lens.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MagnifierTrigger {
+    /// Whenever the pointer is over the content.
+    #[default]
+    Hover,
+    /// While the pointer or a finger is pressed, e.g. while dragging a selection.
+    Press,
+    /// Once the pointer or a finger has been held still for the given time, until it is
+    /// released. Moving before that gives up the long press, so scrolling does not show it.
+    LongPress(Duration),
+}
+
+/// Arguments for the [`magnifier`] component.
+#[derive(Builder, Clone, Debug, PartialEq)]
+#[builder(pattern = "owned")]
+pub struct MagnifierArgs {
+    /// How many times the lens enlarges the content.
+    #[builder(default = "2.0")]
+    pub zoom: f32,
+    /// The width and height of the lens.
+    #[builder(default = "Dp(120.0)")]
+    pub size: Dp,
+    /// The outline of the lens.
+    #[builder(default)]
+    pub shape: MagnifierShape,
+    /// How far the lens is moved up from the pointer. The lens still shows the area around
+    /// the pointer.
+    #[builder(default = "Dp(0.0)")]
+    pub offset: Dp,
+    /// The width of the border around the lens. `Dp(0.0)` draws none.
+    #[builder(default = "Dp(2.0)")]
+    pub border_width: Dp,
+    /// The color of the border around the lens.
+    #[builder(default = "Color::GRAY")]
+    pub border_color: Color,
+    /// When the lens is shown.
+    #[builder(default)]
+    pub trigger: MagnifierTrigger,
+}
+
+impl Default for MagnifierArgs {
+    fn default() -> Self {
+        MagnifierArgsBuilder::default().build().unwrap()
+    }
+}
+
+/// Where the pointer of a [`magnifier`] is.
+#[derive(Clone, Debug, Default)]
+pub struct MagnifierState {
+    pointer: Option<PxPosition>,
+    press: Option<Press>,
+    /// The size of the content at the last frame, which the lens stays inside.
+    size: PxSize,
+}
+
+/// A press on the content of a [`magnifier`].
+#[derive(Clone, Copy, Debug)]
+struct Press {
+    time: Instant,
+    origin: PxPosition,
+    /// Whether the pointer moved away before a long press showed the lens.
+    cancelled: bool,
+}
+
+impl MagnifierState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the point the lens magnifies relative to the content, or `None` while the
+    /// lens is hidden.
+    pub fn focus(&self, trigger: MagnifierTrigger) -> Option<PxPosition> {
+        self.pointer
+            .filter(|_| self.is_shown(trigger, clock::now()))
+    }
+
+    fn is_shown(&self, trigger: MagnifierTrigger, now: Instant) -> bool {
+        match trigger {
+            MagnifierTrigger::Hover => true,
+            MagnifierTrigger::Press => self.press.is_some(),
+            MagnifierTrigger::LongPress(delay) => self.press.is_some_and(|press| {
+                !press.cancelled && now.saturating_duration_since(press.time) >= delay
+            }),
+        }
+    }
+
+    fn contains(&self, pos: &PxPosition) -> bool {
+        pos.x >= Px(0) && pos.y >= Px(0) && pos.x < self.size.width && pos.y < self.size.height
+    }
+
+    fn press(&mut self, pointer: PxPosition, time: Instant) {
+        self.pointer = Some(pointer);
+        self.press = Some(Press {
+            time,
+            origin: pointer,
+            cancelled: false,
+        });
+    }
+
+    fn release(&mut self) {
+        self.press = None;
+    }
+
+    /// Follows the pointer, `None` when it left the window.
+    fn move_pointer(&mut self, pointer: Option<PxPosition>, trigger: MagnifierTrigger) {
+        let shown = self.is_shown(trigger, clock::now());
+        if let Some(press) = self.press.as_mut().filter(|_| !shown) {
+            let slop = Px::from(LONG_PRESS_SLOP).0 as i64;
+            press.cancelled |= pointer.is_none_or(|pos| {
+                let dx = (pos.x - press.origin.x).0 as i64;
+                let dy = (pos.y - press.origin.y).0 as i64;
+                dx * dx + dy * dy > slop * slop
+            });
+        }
+
+        let size = self.size;
+        self.pointer = match pointer {
+            // A drag that leaves the content keeps the lens at its edge
+            Some(pos) if self.press.is_some() => Some(PxPosition {
+                x: pos.x.max(Px(0)).min(size.width),
+                y: pos.y.max(Px(0)).min(size.height),
+            }),
+            pos => pos.filter(|pos| self.contains(pos)),
+        };
+    }
+
+    /// Returns where the lens of `args` goes relative to the content, and where the
+    /// magnified point is relative to the center of the lens, or `None` while it is hidden.
+    fn lens(&self, args: &MagnifierArgs) -> Option<(PxPosition, PxPosition)> {
+        let focus = self.focus(args.trigger)?;
+        Some(lens_placement(
+            focus,
+            args.size.into(),
+            args.offset.into(),
+            self.size,
+        ))
+    }
+}
+
+/// Places a lens of `lens` pixels `offset` above `focus`, moved inside `content` where it
+/// would stick out, as far as the content is larger than the lens. Returns the position of
+/// the lens and where `focus` is relative to its center.
+fn lens_placement(
+    focus: PxPosition,
+    lens: Px,
+    offset: Px,
+    content: PxSize,
+) -> (PxPosition, PxPosition) {
+    let clamp = |value: Px, extent: Px| value.min(extent - lens).max(Px(0));
+    let position = PxPosition {
+        x: clamp(focus.x - lens / 2, content.width),
+        y: clamp(focus.y - lens / 2 - offset, content.height),
+    };
+    let sample = PxPosition {
+        x: focus.x - position.x - lens / 2,
+        y: focus.y - position.y - lens / 2,
+    };
+    (position, sample)
+}
+
+/// The lens drawn by a [`magnifier`], sampling the scene below it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MagnifierCommand {
+    /// How many times the lens enlarges the scene.
+    pub zoom: f32,
+    /// The outline of the lens.
+    pub shape: MagnifierShape,
+    /// Where the magnified point is relative to the center of the lens.
+    pub offset: PxPosition,
+    /// The width of the border.
+    pub border_width: Px,
+    /// The color of the border.
+    pub border_color: Color,
+}
+
+impl DrawCommand for MagnifierCommand {
+    fn barrier(&self) -> Option<BarrierRequirement> {
+        // The lens shows a smaller area than it covers, centered `offset` from its own center
+        Some(BarrierRequirement::PaddedLocal {
+            top: (-self.offset.y).max(Px(0)),
+            right: self.offset.x.max(Px(0)),
+            bottom: self.offset.y.max(Px(0)),
+            left: (-self.offset.x).max(Px(0)),
+        })
+    }
+}
+
+#[tessera]
+fn magnifier_lens(args: MagnifierArgs, offset: PxPosition) {
+    measure(Box::new(move |input| {
+        let size: Px = args.size.into();
+        input.metadata_mut().push_draw_command(MagnifierCommand {
+            zoom: args.zoom.max(1.0),
+            shape: args.shape,
+            offset,
+            border_width: args.border_width.into(),
+            border_color: args.border_color,
+        });
+        Ok(ComputedData {
+            width: size,
+            height: size,
+        })
+    }));
+}
+
+/// Shows `content` with a lens that zooms into the area under the pointer, see the
+/// [module docs](self).
+///
+/// The lens does not take any input, the pointer events reach the content as usual.
+#[tessera]
+pub fn magnifier(
+    args: impl Into<MagnifierArgs>,
+    state: Arc<RwLock<MagnifierState>>,
+    content: impl FnOnce(),
+) {
+    let args: MagnifierArgs = args.into();
+    content();
+    let lens = state.read().lens(&args);
+    if let Some((_, offset)) = lens {
+        magnifier_lens(args.clone(), offset);
+    }
+
+    let lens_size: Px = args.size.into();
+    measure(Box::new(move |input| {
+        let content_id = input.children_ids[0];
+        let size = input.measure_child(content_id, input.parent_constraint)?;
+        input.place_child(content_id, PxPosition::ZERO);
+
+        if let (Some(&lens_id), Some((position, _))) = (input.children_ids.get(1), lens) {
+            let constraint = Constraint::new(
+                DimensionValue::Fixed(lens_size),
+                DimensionValue::Fixed(lens_size),
+            );
+            input.measure_child(lens_id, &constraint)?;
+            input.place_child(lens_id, position);
+        }
+        Ok(size)
+    }));
+
+    input_handler(Box::new(move |input| {
+        let mut state = state.write();
+        state.size = PxSize {
+            width: input.computed_data.width,
+            height: input.computed_data.height,
+        };
+        for event in input.cursor_events.iter() {
+            match event.content {
+                CursorEventContent::Pressed(PressKeyEventType::Left) => {
+                    match input.cursor_position_rel.filter(|pos| state.contains(pos)) {
+                        Some(pos) => state.press(pos, event.timestamp),
+                        None => state.release(),
+                    }
+                }
+                CursorEventContent::Released(PressKeyEventType::Left) => state.release(),
+                _ => {}
+            }
+        }
+        state.move_pointer(input.cursor_position_rel, args.trigger);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: i32, y: i32) -> PxPosition {
+        PxPosition { x: Px(x), y: Px(y) }
+    }
+
+    fn state(width: i32, height: i32) -> MagnifierState {
+        MagnifierState {
+            size: PxSize {
+                width: Px(width),
+                height: Px(height),
+            },
+            ..MagnifierState::new()
+        }
+    }
+
+    #[test]
+    fn test_lens_placement() {
+        let content = PxSize {
+            width: Px(400),
+            height: Px(300),
+        };
+        // Centered on the focus, moved up by the offset
+        assert_eq!(
+            lens_placement(pos(200, 150), Px(100), Px(0), content),
+            (pos(150, 100), pos(0, 0))
+        );
+        assert_eq!(
+            lens_placement(pos(200, 150), Px(100), Px(60), content),
+            (pos(150, 40), pos(0, 60))
+        );
+    }
+
+    #[test]
+    fn test_lens_placement_clamps_at_edges() {
+        let content = PxSize {
+            width: Px(400),
+            height: Px(300),
+        };
+        // Top left: the lens stays inside, still magnifying the focus
+        assert_eq!(
+            lens_placement(pos(10, 20), Px(100), Px(60), content),
+            (pos(0, 0), pos(-40, -30))
+        );
+        // Bottom right
+        assert_eq!(
+            lens_placement(pos(395, 290), Px(100), Px(0), content),
+            (pos(300, 200), pos(45, 40))
+        );
+        // Content smaller than the lens keeps it at the top left
+        let small = PxSize {
+            width: Px(80),
+            height: Px(40),
+        };
+        assert_eq!(
+            lens_placement(pos(70, 30), Px(100), Px(60), small),
+            (pos(0, 0), pos(20, -20))
+        );
+    }
+
+    #[test]
+    fn test_press_trigger() {
+        let mut state = state(400, 300);
+        state.move_pointer(Some(pos(50, 50)), MagnifierTrigger::Press);
+        assert_eq!(state.focus(MagnifierTrigger::Hover), Some(pos(50, 50)));
+        assert_eq!(state.focus(MagnifierTrigger::Press), None);
+
+        state.press(pos(50, 50), clock::now());
+        assert_eq!(state.focus(MagnifierTrigger::Press), Some(pos(50, 50)));
+        // Dragging out of the content keeps the lens at its edge
+        state.move_pointer(Some(pos(-20, 500)), MagnifierTrigger::Press);
+        assert_eq!(state.focus(MagnifierTrigger::Press), Some(pos(0, 300)));
+
+        state.release();
+        assert_eq!(state.focus(MagnifierTrigger::Press), None);
+        state.move_pointer(Some(pos(-20, 500)), MagnifierTrigger::Press);
+        assert_eq!(state.focus(MagnifierTrigger::Hover), None);
+    }
+
+    #[test]
+    fn test_long_press_trigger() {
+        let clock = clock::manual();
+        let trigger = MagnifierTrigger::LongPress(LONG_PRESS_DELAY);
+        let mut state = state(400, 300);
+
+        state.press(pos(50, 50), clock::now());
+        assert_eq!(state.focus(trigger), None);
+        clock.advance(LONG_PRESS_DELAY / 2);
+        // Small movements while waiting are part of holding still
+        state.move_pointer(Some(pos(52, 51)), trigger);
+        assert_eq!(state.focus(trigger), None);
+        clock.advance(LONG_PRESS_DELAY / 2);
+        assert_eq!(state.focus(trigger), Some(pos(52, 51)));
+
+        // Once shown, the lens follows a drag until released
+        state.move_pointer(Some(pos(200, 100)), trigger);
+        assert_eq!(state.focus(trigger), Some(pos(200, 100)));
+        state.release();
+        assert_eq!(state.focus(trigger), None);
+    }
+
+    #[test]
+    fn test_long_press_cancelled_by_moving() {
+        let clock = clock::manual();
+        let trigger = MagnifierTrigger::LongPress(LONG_PRESS_DELAY);
+        let mut state = state(400, 300);
+
+        state.press(pos(50, 50), clock::now());
+        clock.advance(LONG_PRESS_DELAY / 2);
+        state.move_pointer(Some(pos(50, 120)), trigger);
+        clock.advance(LONG_PRESS_DELAY);
+        assert_eq!(state.focus(trigger), None);
+
+        // The next press starts over
+        state.release();
+        state.press(pos(50, 120), clock::now());
+        clock.advance(LONG_PRESS_DELAY);
+        assert_eq!(state.focus(trigger), Some(pos(50, 120)));
+    }
+}
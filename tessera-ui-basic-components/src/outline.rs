@@ -0,0 +1,441 @@
+//! A panel listing the symbols of a document, such as its types and functions.
+//!
+//! The application supplies the symbol tree, e.g. from a language server or a tree-sitter
+//! query, with [`OutlineState::set_symbols`]. [`outline`] shows it indented by depth and
+//! highlights the innermost symbol containing the caret of the editor; clicking a symbol
+//! moves the caret to its start.
+//!
+//! The highlight follows the caret through
+//! [`TextEditorArgs::on_caret_move`](crate::text_editor::TextEditorArgs::on_caret_move),
+//! which [`caret_listener`] returns a callback for.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use glyphon::Cursor;
+//! use parking_lot::RwLock;
+//! use tessera_ui::Dp;
+//! use tessera_ui_basic_components::{
+//!     outline::{OutlineState, OutlineSymbol, SymbolKind, caret_listener, outline},
+//!     text_editor::{TextEditorArgs, TextEditorState, TextRange},
+//! };
+//!
+//! let range = |start, end| TextRange {
+//!     start: Cursor::new(start, 0),
+//!     end: Cursor::new(end, 0),
+//! };
+//! let symbols = vec![
+//!     OutlineSymbol::new("Point", SymbolKind::Type, range(0, 9)).with_children(vec![
+//!         OutlineSymbol::new("new", SymbolKind::Method, range(1, 4)),
+//!         OutlineSymbol::new("length", SymbolKind::Method, range(5, 8)),
+//!     ]),
+//! ];
+//! let state = Arc::new(RwLock::new(OutlineState::new()));
+//! state.write().set_symbols(symbols);
+//!
+//! let editor = Arc::new(RwLock::new(TextEditorState::new(Dp(14.0), None)));
+//! let args = TextEditorArgs::simple().with_on_caret_move(caret_listener(state.clone()));
+//! // text_editor(args, editor.clone());
+//! // outline(Default::default(), state.clone(), editor.clone());
+//! ```
+
+use std::sync::Arc;
+
+use derive_builder::Builder;
+use glyphon::Cursor;
+use parking_lot::RwLock;
+use tessera_ui::{Color, DimensionValue, Dp, tessera};
+
+use crate::{
+    column::{ColumnArgs, column},
+    row::{RowArgs, row},
+    scrollable::{ScrollableArgsBuilder, ScrollableState, scrollable},
+    shape_def::Shape,
+    spacer::{SpacerArgsBuilder, spacer},
+    surface::{SurfaceArgsBuilder, SurfaceStyle, surface},
+    text::{TextArgsBuilder, text},
+    text_edit_core::{TextEditorState, TextPosition, TextRange},
+};
+
+/// What a symbol of an [`outline`] is, shown as a short tag before its name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// A module, namespace or package.
+    Module,
+    /// A struct, class, enum, trait or interface.
+    Type,
+    /// A free function.
+    Function,
+    /// A function belonging to a type.
+    Method,
+    /// A field or property of a type.
+    Field,
+    /// A constant or static.
+    Constant,
+    /// A variable.
+    Variable,
+    /// Anything else, e.g. a heading of a Markdown document.
+    Other,
+}
+
+impl SymbolKind {
+    /// Returns the tag shown before the names of symbols of this kind.
+    pub fn tag(self) -> &'static str {
+        match self {
+            SymbolKind::Module => "mod",
+            SymbolKind::Type => "type",
+            SymbolKind::Function => "fn",
+            SymbolKind::Method => "fn",
+            SymbolKind::Field => "field",
+            SymbolKind::Constant => "const",
+            SymbolKind::Variable => "let",
+            SymbolKind::Other => "•",
+        }
+    }
+}
+
+/// A symbol of the document and the symbols nested in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutlineSymbol {
+    /// Name shown in the outline.
+    pub name: String,
+    /// What the symbol is.
+    pub kind: SymbolKind,
+    /// The text the symbol spans, e.g. a whole function with its body. Clicking the symbol
+    /// moves the caret to its start.
+    pub range: TextRange,
+    /// Symbols inside this one, in document order.
+    pub children: Vec<OutlineSymbol>,
+}
+
+impl OutlineSymbol {
+    /// Creates a symbol without children.
+    pub fn new(name: impl Into<String>, kind: SymbolKind, range: TextRange) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            range,
+            children: Vec::new(),
+        }
+    }
+
+    /// Sets the symbols nested in this one.
+    pub fn with_children(mut self, children: Vec<OutlineSymbol>) -> Self {
+        self.children = children;
+        self
+    }
+
+    fn contains(&self, cursor: Cursor) -> bool {
+        let at = (cursor.line, cursor.index);
+        (self.range.start.line, self.range.start.index) <= at
+            && at <= (self.range.end.line, self.range.end.index)
+    }
+}
+
+/// The symbols of an [`outline`] and the caret they are highlighted for.
+pub struct OutlineState {
+    symbols: Vec<OutlineSymbol>,
+    caret: Option<Cursor>,
+    scrollable_state: Arc<ScrollableState>,
+}
+
+impl Default for OutlineState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutlineState {
+    /// Creates a state without symbols.
+    pub fn new() -> Self {
+        Self {
+            symbols: Vec::new(),
+            caret: None,
+            scrollable_state: Arc::new(ScrollableState::new()),
+        }
+    }
+
+    /// Returns the top-level symbols.
+    pub fn symbols(&self) -> &[OutlineSymbol] {
+        &self.symbols
+    }
+
+    /// Replaces the symbol tree, e.g. after the document was parsed again.
+    pub fn set_symbols(&mut self, symbols: Vec<OutlineSymbol>) {
+        self.symbols = symbols;
+    }
+
+    /// Sets the caret position whose symbol is highlighted.
+    pub fn set_caret(&mut self, caret: TextPosition) {
+        self.caret = Some(caret.into());
+    }
+
+    /// Returns the indices leading from the top level to the innermost symbol containing the
+    /// caret, or `None` if no symbol contains it. Of overlapping siblings the first wins.
+    pub fn current_path(&self) -> Option<Vec<usize>> {
+        let caret = self.caret?;
+        let mut path = Vec::new();
+        let mut symbols = &self.symbols;
+        while let Some((index, symbol)) = symbols
+            .iter()
+            .enumerate()
+            .find(|(_, symbol)| symbol.contains(caret))
+        {
+            path.push(index);
+            symbols = &symbol.children;
+        }
+        (!path.is_empty()).then_some(path)
+    }
+
+    /// Returns the innermost symbol containing the caret.
+    pub fn current_symbol(&self) -> Option<&OutlineSymbol> {
+        let path = self.current_path()?;
+        let mut symbols = &self.symbols;
+        let mut symbol = None;
+        for index in path {
+            let found = &symbols[index];
+            symbols = &found.children;
+            symbol = Some(found);
+        }
+        symbol
+    }
+
+    /// Returns the rows of the outline: every symbol with its depth, in document order, and
+    /// whether it is the current one.
+    fn rows(&self) -> Vec<OutlineRow> {
+        fn push_rows(
+            symbols: &[OutlineSymbol],
+            depth: usize,
+            current: Option<&[usize]>,
+            rows: &mut Vec<OutlineRow>,
+        ) {
+            for (index, symbol) in symbols.iter().enumerate() {
+                let current = current.filter(|path| path.first() == Some(&index));
+                rows.push(OutlineRow {
+                    label: format!("{} {}", symbol.kind.tag(), symbol.name),
+                    depth,
+                    start: symbol.range.start,
+                    current: current.is_some_and(|path| path.len() == 1),
+                });
+                push_rows(
+                    &symbol.children,
+                    depth + 1,
+                    current.map(|path| &path[1..]),
+                    rows,
+                );
+            }
+        }
+
+        let current = self.current_path();
+        let mut rows = Vec::new();
+        push_rows(&self.symbols, 0, current.as_deref(), &mut rows);
+        rows
+    }
+}
+
+/// Returns a callback for
+/// [`TextEditorArgs::on_caret_move`](crate::text_editor::TextEditorArgs::on_caret_move) that
+/// keeps the highlight of `state` on the symbol of the caret.
+pub fn caret_listener(state: Arc<RwLock<OutlineState>>) -> Arc<dyn Fn(TextPosition) + Send + Sync> {
+    Arc::new(move |caret| state.write().set_caret(caret))
+}
+
+/// One shown symbol.
+#[derive(Clone)]
+struct OutlineRow {
+    label: String,
+    depth: usize,
+    start: Cursor,
+    current: bool,
+}
+
+/// Arguments for the [`outline`] component.
+#[derive(Builder, Clone)]
+#[builder(pattern = "owned")]
+pub struct OutlineArgs {
+    /// Width of the panel. Defaults to `Fill`.
+    #[builder(default = "DimensionValue::FILLED", setter(into))]
+    pub width: DimensionValue,
+    /// Height of the panel. Defaults to `Fill`.
+    #[builder(default = "DimensionValue::FILLED", setter(into))]
+    pub height: DimensionValue,
+    /// Font size of the symbol names.
+    #[builder(default = "Dp(13.0)")]
+    pub size: Dp,
+    /// Indentation of each level of nesting.
+    #[builder(default = "Dp(12.0)")]
+    pub indent: Dp,
+    /// Color of the symbol names.
+    #[builder(default = "Color::new(0.15, 0.15, 0.15, 1.0)")]
+    pub color: Color,
+    /// Background of the panel.
+    #[builder(default = "Color::new(0.97, 0.97, 0.97, 1.0)")]
+    pub background_color: Color,
+    /// Background of the symbol containing the caret.
+    #[builder(default = "Color::new(0.2, 0.5, 0.8, 0.25)")]
+    pub highlight_color: Color,
+    /// Background of the symbol under the mouse pointer.
+    #[builder(default = "Color::new(0.0, 0.0, 0.0, 0.06)")]
+    pub hover_color: Color,
+    /// Padding between the edge of the panel and the symbols.
+    #[builder(default = "Dp(6.0)")]
+    pub padding: Dp,
+}
+
+impl Default for OutlineArgs {
+    fn default() -> Self {
+        OutlineArgsBuilder::default().build().unwrap()
+    }
+}
+
+/// Shows the symbols of an [`OutlineState`] in a scrollable panel, highlighting the one
+/// containing the caret. Clicking a symbol moves the caret of `editor` to its start, scrolls
+/// it to the middle of the view and gives the editor the focus. See the
+/// [module documentation](self) for an example.
+#[tessera]
+pub fn outline(
+    args: impl Into<OutlineArgs>,
+    state: Arc<RwLock<OutlineState>>,
+    editor: Arc<RwLock<TextEditorState>>,
+) {
+    let args: OutlineArgs = args.into();
+    let (rows, scrollable_state) = {
+        let guard = state.read();
+        (guard.rows(), guard.scrollable_state.clone())
+    };
+    surface(
+        SurfaceArgsBuilder::default()
+            .style(args.background_color.into())
+            .width(args.width)
+            .height(args.height)
+            .padding(args.padding)
+            .build()
+            .unwrap(),
+        None,
+        move || {
+            scrollable(
+                ScrollableArgsBuilder::default()
+                    .width(DimensionValue::FILLED)
+                    .height(DimensionValue::FILLED)
+                    .build()
+                    .unwrap(),
+                scrollable_state,
+                move || {
+                    column(ColumnArgs::default(), |scope| {
+                        for entry in rows {
+                            let args = args.clone();
+                            let state = state.clone();
+                            let editor = editor.clone();
+                            scope.child(move || outline_row(args, entry, state, editor));
+                        }
+                    });
+                },
+            );
+        },
+    );
+}
+
+/// One symbol of the [`outline`].
+#[tessera]
+fn outline_row(
+    args: OutlineArgs,
+    entry: OutlineRow,
+    state: Arc<RwLock<OutlineState>>,
+    editor: Arc<RwLock<TextEditorState>>,
+) {
+    let background = if entry.current {
+        args.highlight_color
+    } else {
+        Color::TRANSPARENT
+    };
+    let start = entry.start;
+    surface(
+        SurfaceArgsBuilder::default()
+            .style(SurfaceStyle::Filled { color: background })
+            .hover_style(Some(SurfaceStyle::Filled {
+                color: if entry.current {
+                    args.highlight_color
+                } else {
+                    args.hover_color
+                },
+            }))
+            .shape(Shape::RECTANGLE)
+            .width(DimensionValue::FILLED)
+            .padding(Dp(3.0))
+            .on_click(Arc::new(move || {
+                let mut editor = editor.write();
+                editor.go_to(start.line, start.index);
+                editor.focus_handler().request_focus();
+                state.write().set_caret(editor.caret());
+            }))
+            .build()
+            .unwrap(),
+        None,
+        move || {
+            row(RowArgs::default(), |scope| {
+                let indent = Dp(args.indent.0 * entry.depth as f64);
+                scope.child(move || {
+                    spacer(SpacerArgsBuilder::default().width(indent).build().unwrap())
+                });
+                scope.child(move || {
+                    text(
+                        TextArgsBuilder::default()
+                            .text(entry.label)
+                            .color(args.color)
+                            .size(args.size)
+                            .build()
+                            .unwrap(),
+                    )
+                });
+            });
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: usize, end: usize) -> TextRange {
+        TextRange {
+            start: Cursor::new(start, 0),
+            end: Cursor::new(end, 0),
+        }
+    }
+
+    #[test]
+    fn test_current_symbol_is_innermost() {
+        let mut state = OutlineState::new();
+        state.set_symbols(vec![
+            OutlineSymbol::new("main", SymbolKind::Function, range(0, 2)),
+            OutlineSymbol::new("Point", SymbolKind::Type, range(4, 12)).with_children(vec![
+                OutlineSymbol::new("x", SymbolKind::Field, range(5, 6)),
+                OutlineSymbol::new("length", SymbolKind::Method, range(8, 11)),
+            ]),
+        ]);
+        assert_eq!(state.current_path(), None);
+
+        let caret = |line| TextPosition {
+            line,
+            column: 2,
+            offset: 0,
+        };
+        state.set_caret(caret(9));
+        assert_eq!(state.current_path(), Some(vec![1, 1]));
+        assert_eq!(
+            state.current_symbol().map(|s| s.name.as_str()),
+            Some("length")
+        );
+        let current: Vec<_> = state.rows().iter().map(|row| row.current).collect();
+        assert_eq!(current, [false, false, false, true]);
+
+        state.set_caret(caret(7));
+        assert_eq!(
+            state.current_symbol().map(|s| s.name.as_str()),
+            Some("Point")
+        );
+        state.set_caret(caret(3));
+        assert_eq!(state.current_symbol(), None);
+    }
+}
@@ -2,6 +2,7 @@ pub mod blur;
 pub mod checkmark;
 pub mod contrast;
 pub(crate) mod fluid_glass;
+pub(crate) mod magnifier;
 pub mod mean;
 mod pos_misc;
 pub mod shape;
@@ -13,7 +14,10 @@ pub mod image;
 pub use checkmark::{CheckmarkCommand, CheckmarkPipeline};
 pub use shape::{RippleProps, ShadowProps, ShapeCommand};
 pub use simple_rect::{SimpleRectCommand, SimpleRectPipeline};
-pub use text::{TextCommand, TextConstraint, TextData, read_font_system, write_font_system};
+pub use text::{
+    FontFeatures, TextCommand, TextConstraint, TextData, TextSpan, read_font_system,
+    write_font_system,
+};
 
 pub fn register_pipelines(app: &mut tessera_ui::renderer::WgpuApp) {
     let simple_rect_pipeline =
@@ -37,6 +41,10 @@ pub fn register_pipelines(app: &mut tessera_ui::renderer::WgpuApp) {
     // Register image pipeline
     let image_pipeline = image::ImagePipeline::new(&app.gpu, &app.config, app.sample_count);
     app.register_draw_pipeline(image_pipeline);
+    // Register magnifier pipeline
+    let magnifier_pipeline =
+        magnifier::MagnifierPipeline::new(&app.gpu, &app.config, app.sample_count);
+    app.register_draw_pipeline(magnifier_pipeline);
     // Register blur pipeline
     let blur_pipeline = blur::pipeline::BlurPipeline::new(&app.gpu);
     app.register_compute_pipeline(blur_pipeline);
@@ -7,7 +7,11 @@ use std::{
 use encase::{ShaderType, UniformBuffer};
 use glam::Vec4;
 use tessera_ui::{
-    DrawCommand, PxPosition, PxSize, px::PxRect, renderer::drawer::DrawablePipeline, wgpu,
+    DrawCommand, PxPosition, PxSize,
+    gpu_memory::{self, GpuMemoryCategory},
+    px::PxRect,
+    renderer::drawer::DrawablePipeline,
+    wgpu,
 };
 
 #[derive(Debug, Clone)]
@@ -69,12 +73,15 @@ impl DrawCommand for ImageCommand {
 #[derive(ShaderType)]
 struct ImageUniforms {
     rect: Vec4,
-    is_bgra: u32,
 }
 
 struct ImageResources {
     bind_group: wgpu::BindGroup,
     uniform_buffer: wgpu::Buffer,
+    /// Size of the texture.
+    bytes: u64,
+    /// The draw call that last used the image.
+    last_used: u64,
 }
 
 /// Pipeline for rendering images in UI components.
@@ -88,6 +95,8 @@ pub struct ImagePipeline {
     pipeline: wgpu::RenderPipeline,
     bind_group_layout: wgpu::BindGroupLayout,
     resources: HashMap<ImageData, ImageResources>,
+    /// Number of draw calls so far, to find the least recently drawn images.
+    draws: u64,
 }
 
 impl ImagePipeline {
@@ -174,6 +183,7 @@ impl ImagePipeline {
             pipeline,
             bind_group_layout,
             resources: HashMap::new(),
+            draws: 0,
         }
     }
 
@@ -185,9 +195,37 @@ impl ImagePipeline {
         config: &wgpu::SurfaceConfiguration,
         data: &ImageData,
     ) -> &ImageResources {
-        self.resources.entry(data.clone()).or_insert_with(|| {
+        let resources = self.resources.entry(data.clone()).or_insert_with(|| {
             Self::create_image_resources(device, queue, config, &self.bind_group_layout, data)
-        })
+        });
+        resources.last_used = self.draws;
+        resources
+    }
+
+    /// Drops the least recently drawn images not used by the current draw call until the
+    /// images fit their [`gpu_memory`] budget, and records what is left.
+    fn evict_to_budget(&mut self) {
+        let mut bytes: u64 = self.resources.values().map(|r| r.bytes).sum();
+        if let Some(budget) = gpu_memory::budget(GpuMemoryCategory::Images)
+            && bytes > budget
+        {
+            let mut unused: Vec<_> = self
+                .resources
+                .iter()
+                .filter(|(_, resources)| resources.last_used < self.draws)
+                .map(|(data, resources)| (resources.last_used, data.clone()))
+                .collect();
+            unused.sort_by_key(|(last_used, _)| *last_used);
+            for (_, data) in unused {
+                if bytes <= budget {
+                    break;
+                }
+                if let Some(resources) = self.resources.remove(&data) {
+                    bytes -= resources.bytes;
+                }
+            }
+        }
+        gpu_memory::record_usage(GpuMemoryCategory::Images, "image pipeline", bytes);
     }
 
     /// Compute the ImageUniforms for a given command size and position.
@@ -207,15 +245,7 @@ impl ImagePipeline {
         ]
         .into();
 
-        let is_bgra = matches!(
-            config.format,
-            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
-        );
-
-        ImageUniforms {
-            rect,
-            is_bgra: if is_bgra { 1 } else { 0 },
-        }
+        ImageUniforms { rect }
     }
 
     // Create GPU resources for an image. Kept as a single helper to avoid duplicating
@@ -237,7 +267,7 @@ impl ImagePipeline {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: config.format,
+            format: image_format(config.format),
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             label: Some("diffuse_texture"),
             view_formats: &[],
@@ -300,10 +330,25 @@ impl ImagePipeline {
         ImageResources {
             bind_group: diffuse_bind_group,
             uniform_buffer,
+            bytes: gpu_memory::texture_bytes(&diffuse_texture),
+            last_used: 0,
         }
     }
 }
 
+/// Returns the format image textures get on a surface of `surface_format`.
+///
+/// Decoded images are sRGB-encoded RGBA. Sampling them from an sRGB texture decodes them to
+/// the linear values sRGB and float surfaces expect, while a surface without sRGB encoding is
+/// given the bytes unchanged.
+fn image_format(surface_format: wgpu::TextureFormat) -> wgpu::TextureFormat {
+    if surface_format.is_srgb() || surface_format == wgpu::TextureFormat::Rgba16Float {
+        wgpu::TextureFormat::Rgba8UnormSrgb
+    } else {
+        wgpu::TextureFormat::Rgba8Unorm
+    }
+}
+
 impl DrawablePipeline<ImageCommand> for ImagePipeline {
     fn draw(
         &mut self,
@@ -316,6 +361,7 @@ impl DrawablePipeline<ImageCommand> for ImagePipeline {
         _clip_rect: Option<PxRect>,
     ) {
         render_pass.set_pipeline(&self.pipeline);
+        self.draws += 1;
 
         for (command, size, start_pos) in commands {
             // Use the extracted helper to obtain or create GPU resources.
@@ -331,5 +377,6 @@ impl DrawablePipeline<ImageCommand> for ImagePipeline {
             render_pass.set_bind_group(0, &resources.bind_group, &[]);
             render_pass.draw(0..6, 0..1);
         }
+        self.evict_to_budget();
     }
 }
@@ -0,0 +1,272 @@
+use encase::{ShaderType, StorageBuffer};
+use glam::{Vec2, Vec4};
+use tessera_ui::{PxPosition, PxSize, px::PxRect, renderer::DrawablePipeline, wgpu};
+
+use crate::magnifier::{MagnifierCommand, MagnifierShape};
+
+pub const MAX_CONCURRENT_LENSES: usize = 16;
+
+#[derive(ShaderType, Clone, Copy, Debug, Default)]
+struct LensUniforms {
+    border_color: Vec4,
+    rect_uv_bounds: Vec4,
+    rect_size_px: Vec2,
+    focus_uv: Vec2,
+    screen_size: Vec2,
+    zoom: f32,
+    shape_type: f32,
+    border_width: f32,
+}
+
+#[derive(ShaderType)]
+struct LensInstances {
+    #[shader(size(runtime))]
+    instances: Vec<LensUniforms>,
+}
+
+/// Draws the lenses of [`crate::magnifier::magnifier`] by sampling the scene texture around
+/// their focus.
+pub(crate) struct MagnifierPipeline {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+}
+
+impl MagnifierPipeline {
+    pub fn new(gpu: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32) -> Self {
+        let shader = gpu.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Magnifier Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("magnifier/magnifier.wgsl").into()),
+        });
+
+        // Linear filtering keeps zoomed text smooth instead of blocky
+        let sampler = gpu.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let bind_group_layout = gpu.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("magnifier_bind_group_layout"),
+        });
+
+        let pipeline_layout = gpu.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Magnifier Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = gpu.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Magnifier Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+        }
+    }
+
+    fn build_instance(
+        command: &MagnifierCommand,
+        size: &PxSize,
+        start_pos: &PxPosition,
+        config: &wgpu::SurfaceConfiguration,
+    ) -> LensUniforms {
+        let screen_size = Vec2::new(config.width as f32, config.height as f32);
+        LensUniforms {
+            border_color: command.border_color.to_array().into(),
+            rect_uv_bounds: lens_uv_bounds(size, start_pos, screen_size),
+            rect_size_px: [size.width.to_f32(), size.height.to_f32()].into(),
+            focus_uv: focus_uv(size, start_pos, command.offset, screen_size),
+            screen_size,
+            zoom: command.zoom,
+            shape_type: match command.shape {
+                MagnifierShape::Circle => 0.0,
+                MagnifierShape::Rectangle => 1.0,
+            },
+            border_width: command.border_width.to_f32(),
+        }
+    }
+}
+
+/// Returns the left, top, right and bottom of the lens at `start_pos` in UV coordinates of a
+/// screen of `screen_size` pixels.
+fn lens_uv_bounds(size: &PxSize, start_pos: &PxPosition, screen_size: Vec2) -> Vec4 {
+    let start = Vec2::new(start_pos.x.to_f32(), start_pos.y.to_f32());
+    let end = start + Vec2::new(size.width.to_f32(), size.height.to_f32());
+    Vec4::new(
+        start.x / screen_size.x,
+        start.y / screen_size.y,
+        end.x / screen_size.x,
+        end.y / screen_size.y,
+    )
+}
+
+/// Returns the magnified point of the lens at `start_pos` in UV coordinates, its center moved
+/// by `offset`.
+fn focus_uv(size: &PxSize, start_pos: &PxPosition, offset: PxPosition, screen_size: Vec2) -> Vec2 {
+    let center = Vec2::new(
+        start_pos.x.to_f32() + size.width.to_f32() / 2.0,
+        start_pos.y.to_f32() + size.height.to_f32() / 2.0,
+    );
+    (center + Vec2::new(offset.x.to_f32(), offset.y.to_f32())) / screen_size
+}
+
+impl DrawablePipeline<MagnifierCommand> for MagnifierPipeline {
+    fn draw(
+        &mut self,
+        gpu: &wgpu::Device,
+        queue: &wgpu::Queue,
+        config: &wgpu::SurfaceConfiguration,
+        render_pass: &mut wgpu::RenderPass<'_>,
+        commands: &[(&MagnifierCommand, PxSize, PxPosition)],
+        scene_texture_view: &wgpu::TextureView,
+        _clip_rect: Option<PxRect>,
+    ) {
+        if commands.is_empty() {
+            return;
+        }
+        let instances = LensInstances {
+            instances: commands
+                .iter()
+                .take(MAX_CONCURRENT_LENSES)
+                .map(|(command, size, pos)| Self::build_instance(command, size, pos, config))
+                .collect(),
+        };
+        let instance_count = instances.instances.len() as u32;
+
+        let mut buffer_content = StorageBuffer::new(Vec::<u8>::new());
+        if buffer_content.write(&instances).is_err() {
+            return;
+        }
+        let buffer = gpu.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Magnifier Storage Buffer"),
+            size: buffer_content.as_ref().len() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        queue.write_buffer(&buffer, 0, buffer_content.as_ref());
+
+        let bind_group = gpu.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(scene_texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+            label: Some("magnifier_bind_group"),
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..6, 0..instance_count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tessera_ui::Px;
+
+    use super::*;
+
+    const SCREEN: Vec2 = Vec2::new(1000.0, 500.0);
+
+    fn lens() -> (PxSize, PxPosition) {
+        (
+            PxSize::new(Px(200), Px(100)),
+            PxPosition::new(Px(100), Px(50)),
+        )
+    }
+
+    #[test]
+    fn test_lens_uv_bounds() {
+        let (size, start_pos) = lens();
+        let bounds = lens_uv_bounds(&size, &start_pos, SCREEN);
+        assert!(bounds.abs_diff_eq(Vec4::new(0.1, 0.1, 0.3, 0.3), 1e-6));
+    }
+
+    #[test]
+    fn test_focus_uv_is_the_center_moved_by_the_offset() {
+        let (size, start_pos) = lens();
+        let centered = focus_uv(&size, &start_pos, PxPosition::ZERO, SCREEN);
+        assert!(centered.abs_diff_eq(Vec2::new(0.2, 0.2), 1e-6));
+
+        let offset = PxPosition::new(Px(-50), Px(25));
+        let moved = focus_uv(&size, &start_pos, offset, SCREEN);
+        assert!(moved.abs_diff_eq(Vec2::new(0.15, 0.25), 1e-6));
+    }
+}
@@ -0,0 +1,84 @@
+struct LensUniforms {
+    // vec4s
+    border_color: vec4<f32>,
+    rect_uv_bounds: vec4<f32>,
+
+    // vec2s
+    rect_size_px: vec2<f32>,
+    focus_uv: vec2<f32>,
+    screen_size: vec2<f32>,
+
+    // f32s
+    zoom: f32,
+    shape_type: f32, // 0.0 for a circle, 1.0 for a rectangle
+    border_width: f32,
+};
+
+struct LensInstances {
+    instances: array<LensUniforms>,
+};
+
+@group(0) @binding(0) var<storage, read> uniforms: LensInstances;
+@group(0) @binding(1) var t_scene: texture_2d<f32>;
+@group(0) @binding(2) var s_scene: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>, // Local UV [0, 1]
+    @location(1) @interpolate(flat) instance_index: u32,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32
+) -> VertexOutput {
+    let instance = uniforms.instances[instance_index];
+    let rect_uv_min = instance.rect_uv_bounds.xy;
+    let rect_uv_max = instance.rect_uv_bounds.zw;
+
+    let local_uvs = array<vec2<f32>, 4>(
+        vec2(0.0, 0.0), // Top-left
+        vec2(0.0, 1.0), // Bottom-left
+        vec2(1.0, 1.0), // Bottom-right
+        vec2(1.0, 0.0)  // Top-right
+    );
+    let indices = array<u32, 6>(0, 1, 2, 0, 2, 3);
+    let local_uv = local_uvs[indices[vertex_index]];
+    let global_uv = rect_uv_min + local_uv * (rect_uv_max - rect_uv_min);
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(global_uv.x * 2.0 - 1.0, -(global_uv.y * 2.0 - 1.0), 0.0, 1.0);
+    out.uv = local_uv;
+    out.instance_index = instance_index;
+    return out;
+}
+
+// Signed distance from the outline of the lens, negative inside.
+fn sdf_lens(p: vec2<f32>, half_size: vec2<f32>, shape_type: f32) -> f32 {
+    let circle = length(p) - min(half_size.x, half_size.y);
+    let q = abs(p) - half_size;
+    let rectangle = length(max(q, vec2(0.0))) + min(max(q.x, q.y), 0.0);
+    return select(circle, rectangle, shape_type == 1.0);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let instance = uniforms.instances[in.instance_index];
+    let half_size = instance.rect_size_px * 0.5;
+    let p = in.uv * instance.rect_size_px - half_size;
+    let sd = sdf_lens(p, half_size, instance.shape_type);
+
+    // The center of the lens shows the focus, the rest is scaled up around it
+    let sample_uv = instance.focus_uv + p / instance.zoom / instance.screen_size;
+    let scene = textureSample(t_scene, s_scene, sample_uv);
+
+    let border = select(
+        0.0,
+        clamp(sd + instance.border_width + 0.5, 0.0, 1.0) * instance.border_color.a,
+        instance.border_width > 0.0
+    );
+    let color = mix(scene.rgb, instance.border_color.rgb, border);
+    let alpha = clamp(0.5 - sd, 0.0, 1.0);
+    return vec4<f32>(color, alpha);
+}
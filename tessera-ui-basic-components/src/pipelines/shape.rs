@@ -25,6 +25,7 @@ use glam::{Vec2, Vec4};
 use lru::LruCache;
 use tessera_ui::{
     Color, Px, PxPosition, PxSize,
+    gpu_memory::{self, GpuMemoryCategory},
     px::PxRect,
     renderer::DrawablePipeline,
     wgpu::{self, include_wgsl, util::DeviceExt},
@@ -95,6 +96,8 @@ pub struct ShapePipeline {
     cache_transform_bind_group_layout: wgpu::BindGroupLayout,
     cached_pipeline: wgpu::RenderPipeline,
     cache: LruCache<ShapeCacheKey, Arc<ShapeCacheEntry>>,
+    /// Size of all textures in `cache`.
+    cache_bytes: u64,
     render_format: wgpu::TextureFormat,
 }
 
@@ -307,6 +310,7 @@ impl ShapePipeline {
             cache: LruCache::new(
                 NonZeroUsize::new(SHAPE_CACHE_CAPACITY).expect("shape cache capacity must be > 0"),
             ),
+            cache_bytes: 0,
             render_format: config.format,
         }
     }
@@ -324,7 +328,24 @@ impl ShapePipeline {
         }
 
         let entry = Arc::new(self.build_cache_entry(gpu, gpu_queue, command, size));
-        _ = self.cache.put(key, entry.clone());
+        self.cache_bytes += entry.bytes;
+        if let Some((_, evicted)) = self.cache.push(key, entry.clone()) {
+            self.cache_bytes -= evicted.bytes;
+        }
+        // Entries drawn this frame are kept alive by their `Arc` until the frame is submitted,
+        // so any of them may be dropped from the cache
+        if let Some(budget) = gpu_memory::budget(GpuMemoryCategory::ShapeCache) {
+            while self.cache_bytes > budget
+                && let Some((_, evicted)) = self.cache.pop_lru()
+            {
+                self.cache_bytes -= evicted.bytes;
+            }
+        }
+        gpu_memory::record_usage(
+            GpuMemoryCategory::ShapeCache,
+            "shape pipeline",
+            self.cache_bytes,
+        );
         Some(entry)
     }
 
@@ -474,6 +495,7 @@ impl ShapePipeline {
         });
 
         ShapeCacheEntry {
+            bytes: gpu_memory::texture_bytes(&cache_texture),
             _texture: cache_texture,
             _view: cache_view,
             texture_bind_group,
@@ -664,6 +686,7 @@ struct ShapeCacheKey {
 }
 
 struct ShapeCacheEntry {
+    bytes: u64,
     _texture: wgpu::Texture,
     _view: wgpu::TextureView,
     texture_bind_group: wgpu::BindGroup,
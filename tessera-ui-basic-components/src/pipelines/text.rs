@@ -10,14 +10,23 @@
 //! This module is intended for integration into custom UI components and rendering flows that require flexible and robust text display.
 
 mod command;
+mod features;
+mod span;
 
-use std::{num::NonZero, sync::OnceLock};
+use std::{collections::HashSet, num::NonZero, sync::OnceLock};
 
 use glyphon::fontdb;
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
-use tessera_ui::{Color, DrawablePipeline, PxPosition, PxSize, px::PxRect, wgpu};
+use tessera_ui::{
+    Color, DrawablePipeline, PxPosition, PxSize,
+    gpu_memory::{self, GpuMemoryCategory},
+    px::PxRect,
+    wgpu,
+};
 
 pub use command::{TextCommand, TextConstraint};
+pub use features::FontFeatures;
+pub use span::TextSpan;
 
 /// It costs a lot to create a glyphon font system, so we use a static one
 /// to share it every where and avoid creating it multiple times.
@@ -28,18 +37,19 @@ static TEXT_DATA_CACHE: OnceLock<RwLock<lru::LruCache<LruKey, TextData>>> = Once
 
 #[derive(PartialEq)]
 struct LruKey {
-    text: String,
+    spans: Vec<TextSpan>,
     color: Color,
     size: f32,
     line_height: f32,
     constraint: TextConstraint,
+    features: FontFeatures,
 }
 
 impl Eq for LruKey {}
 
 impl std::hash::Hash for LruKey {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.text.hash(state);
+        self.spans.hash(state);
         self.color.r.to_bits().hash(state);
         self.color.g.to_bits().hash(state);
         self.color.b.to_bits().hash(state);
@@ -47,6 +57,7 @@ impl std::hash::Hash for LruKey {
         self.size.to_bits().hash(state);
         self.line_height.to_bits().hash(state);
         self.constraint.hash(state);
+        self.features.hash(state);
     }
 }
 
@@ -56,6 +67,29 @@ fn write_lru_cache() -> RwLockWriteGuard<'static, lru::LruCache<LruKey, TextData
         .write()
 }
 
+/// Id of the custom glyph that draws a solid rectangle, for the underlines, strikethroughs and
+/// backgrounds of [`TextSpan`]s.
+const RECT_GLYPH: glyphon::CustomGlyphId = 0;
+
+/// Rasterizes the custom glyphs of text areas.
+fn rasterize_custom_glyph(
+    request: glyphon::RasterizeCustomGlyphRequest,
+) -> Option<glyphon::RasterizedCustomGlyph> {
+    (request.id == RECT_GLYPH).then(|| glyphon::RasterizedCustomGlyph {
+        data: vec![u8::MAX; request.width as usize * request.height as usize],
+        content_type: glyphon::ContentType::Mask,
+    })
+}
+
+fn glyphon_color(color: Color) -> glyphon::Color {
+    glyphon::Color::rgba(
+        (color.r * 255.0) as u8,
+        (color.g * 255.0) as u8,
+        (color.b * 255.0) as u8,
+        (color.a * 255.0) as u8,
+    )
+}
+
 #[cfg(target_os = "android")]
 fn init_font_system() -> RwLock<glyphon::FontSystem> {
     let mut font_system = glyphon::FontSystem::new();
@@ -105,7 +139,6 @@ pub struct GlyphonTextRender {
     /// Glyphon font atlas, a heavy-weight, shared resource.
     atlas: glyphon::TextAtlas,
     /// Glyphon cache, a heavy-weight, shared resource.
-    #[allow(unused)]
     cache: glyphon::Cache,
     /// Glyphon viewport, holds screen-size related buffers.
     viewport: glyphon::Viewport,
@@ -115,6 +148,38 @@ pub struct GlyphonTextRender {
     msaa: wgpu::MultisampleState,
     /// Glyphon text renderer, responsible for rendering text.
     renderer: glyphon::TextRenderer,
+    /// Glyphs drawn since the atlas was created.
+    atlas_glyphs: AtlasEstimate,
+}
+
+/// Side of a new glyphon atlas texture, which doubles whenever it is full.
+const INITIAL_ATLAS_SIDE: u64 = 256;
+
+/// Estimate of the memory of the glyph atlas, whose size glyphon does not expose.
+///
+/// Each glyph is counted as a square of its font size in the one-byte mask atlas. The color
+/// atlas for emoji is assumed to stay at its initial size.
+#[derive(Default)]
+struct AtlasEstimate {
+    glyphs: HashSet<glyphon::cosmic_text::CacheKey>,
+    texels: u64,
+}
+
+impl AtlasEstimate {
+    fn insert(&mut self, key: glyphon::cosmic_text::CacheKey) {
+        if self.glyphs.insert(key) {
+            let size = f32::from_bits(key.font_size_bits).ceil() as u64;
+            self.texels += size * size;
+        }
+    }
+
+    fn bytes(&self) -> u64 {
+        let mut side = INITIAL_ATLAS_SIDE;
+        while side * side < self.texels {
+            side *= 2;
+        }
+        side * side + INITIAL_ATLAS_SIDE * INITIAL_ATLAS_SIDE * 4
+    }
 }
 
 impl GlyphonTextRender {
@@ -149,8 +214,50 @@ impl GlyphonTextRender {
             swash_cache,
             msaa,
             renderer,
+            atlas_glyphs: AtlasEstimate::default(),
         }
     }
+
+    /// Accounts for the glyphs of `commands` in the atlas estimate. If that is over the
+    /// [`gpu_memory`] budget, the atlas is replaced by an empty one, so it only grows back to
+    /// the size of the glyphs still in use.
+    fn update_atlas_usage(
+        &mut self,
+        gpu: &wgpu::Device,
+        gpu_queue: &wgpu::Queue,
+        config: &wgpu::SurfaceConfiguration,
+        commands: &[(&TextCommand, PxSize, PxPosition)],
+    ) {
+        let mut drawn = AtlasEstimate::default();
+        for (command, _size, start_pos) in commands {
+            for area in command.data.text_areas(*start_pos) {
+                for run in area.buffer.layout_runs() {
+                    for glyph in run.glyphs {
+                        drawn.insert(glyph.physical((area.left, area.top), area.scale).cache_key);
+                    }
+                }
+            }
+        }
+
+        let mut atlas_glyphs = std::mem::take(&mut self.atlas_glyphs);
+        for &key in &drawn.glyphs {
+            atlas_glyphs.insert(key);
+        }
+        let over_budget = gpu_memory::budget(GpuMemoryCategory::GlyphAtlas)
+            .is_some_and(|budget| atlas_glyphs.bytes() > budget);
+        if over_budget && drawn.bytes() < atlas_glyphs.bytes() {
+            // Text drawn earlier this frame keeps the old atlas alive until it is submitted
+            self.atlas = glyphon::TextAtlas::new(gpu, gpu_queue, &self.cache, config.format);
+            self.renderer = glyphon::TextRenderer::new(&mut self.atlas, gpu, self.msaa, None);
+            atlas_glyphs = drawn;
+        }
+        gpu_memory::record_usage(
+            GpuMemoryCategory::GlyphAtlas,
+            "text pipeline",
+            atlas_glyphs.bytes(),
+        );
+        self.atlas_glyphs = atlas_glyphs;
+    }
 }
 
 impl DrawablePipeline<TextCommand> for GlyphonTextRender {
@@ -167,6 +274,7 @@ impl DrawablePipeline<TextCommand> for GlyphonTextRender {
         if commands.is_empty() {
             return;
         }
+        self.update_atlas_usage(gpu, gpu_queue, config, commands);
 
         self.viewport.update(
             gpu_queue,
@@ -178,10 +286,10 @@ impl DrawablePipeline<TextCommand> for GlyphonTextRender {
 
         let text_areas = commands
             .iter()
-            .map(|(command, _size, start_pos)| command.data.text_area(*start_pos));
+            .flat_map(|(command, _size, start_pos)| command.data.text_areas(*start_pos));
 
         self.renderer
-            .prepare(
+            .prepare_with_custom(
                 gpu,
                 gpu_queue,
                 &mut write_font_system(),
@@ -189,6 +297,7 @@ impl DrawablePipeline<TextCommand> for GlyphonTextRender {
                 &self.viewport,
                 text_areas,
                 &mut self.swash_cache,
+                rasterize_custom_glyph,
             )
             .unwrap();
 
@@ -208,6 +317,7 @@ impl DrawablePipeline<TextCommand> for GlyphonTextRender {
 ///
 /// - `text_buffer`: The glyphon text buffer.
 /// - `size`: The size of the text area [width, height].
+/// - `decorations`: Underlines, strikethroughs and backgrounds of [`TextSpan`]s.
 ///
 /// # Example
 ///
@@ -218,7 +328,7 @@ impl DrawablePipeline<TextCommand> for GlyphonTextRender {
 /// use tessera_ui_basic_components::pipelines::text::TextConstraint;
 ///
 /// let color = Color::from_rgb(1.0, 1.0, 1.0);
-/// let constraint = TextConstraint { max_width: Some(200.0), max_height: Some(50.0) };
+/// let constraint = TextConstraint { max_width: Some(200.0), max_height: Some(50.0), max_lines: None };
 /// let data = TextData::new("Hello".to_string(), color, 16.0, 1.2, constraint);
 /// ```
 #[derive(Debug, Clone, PartialEq)]
@@ -227,6 +337,10 @@ pub struct TextData {
     text_buffer: glyphon::Buffer,
     /// text area size
     pub size: [u32; 2],
+    /// rectangles drawn with the text, see [`TextSpan::is_decorated`]
+    decorations: Vec<glyphon::CustomGlyph>,
+    /// rows drawn lower than they are laid out: their top and bottom, and how much lower
+    row_offsets: Vec<(f32, f32, f32)>,
 }
 
 impl TextData {
@@ -244,14 +358,69 @@ impl TextData {
         size: f32,
         line_height: f32,
         constraint: TextConstraint,
+    ) -> Self {
+        Self::with_font_features(
+            text,
+            color,
+            size,
+            line_height,
+            constraint,
+            &FontFeatures::default(),
+        )
+    }
+
+    /// Prepares text data for rendering, shaped with the OpenType `features`.
+    ///
+    /// # Parameters
+    /// - `text`: The text string.
+    /// - `color`: The text color.
+    /// - `size`: Font size.
+    /// - `line_height`: Line height.
+    /// - `constraint`: Text constraint for layout.
+    /// - `features`: Font features such as ligatures, see [`FontFeatures`].
+    pub fn with_font_features(
+        text: String,
+        color: Color,
+        size: f32,
+        line_height: f32,
+        constraint: TextConstraint,
+        features: &FontFeatures,
+    ) -> Self {
+        Self::with_spans(
+            vec![TextSpan::new(text)],
+            color,
+            size,
+            line_height,
+            constraint,
+            features,
+        )
+    }
+
+    /// Prepares text data for rendering `spans` in one layout, each in its own style.
+    ///
+    /// # Parameters
+    /// - `spans`: The styled runs of the text, see [`TextSpan`].
+    /// - `color`: The text color of spans without their own.
+    /// - `size`: Font size of spans without their own.
+    /// - `line_height`: Line height at `size`; spans of other sizes scale it.
+    /// - `constraint`: Text constraint for layout.
+    /// - `features`: Font features such as ligatures, see [`FontFeatures`].
+    pub fn with_spans(
+        spans: Vec<TextSpan>,
+        color: Color,
+        size: f32,
+        line_height: f32,
+        constraint: TextConstraint,
+        features: &FontFeatures,
     ) -> Self {
         // Check cache first
         let key = LruKey {
-            text: text.clone(),
+            spans,
             color,
             size,
             line_height,
             constraint: constraint.clone(),
+            features: features.clone(),
         };
         if let Some(cache) = write_lru_cache().get(&key) {
             return cache.clone();
@@ -262,24 +431,46 @@ impl TextData {
             &mut write_font_system(),
             glyphon::Metrics::new(size, line_height),
         );
-        let color = glyphon::Color::rgba(
-            (color.r * 255.0) as u8,
-            (color.g * 255.0) as u8,
-            (color.b * 255.0) as u8,
-            (color.a * 255.0) as u8,
-        );
         text_buffer.set_wrap(&mut write_font_system(), glyphon::Wrap::Glyph);
         text_buffer.set_size(
             &mut write_font_system(),
             constraint.max_width,
             constraint.max_height,
         );
-        text_buffer.set_text(
+        let attrs = glyphon::Attrs::new()
+            .family(fontdb::Family::SansSerif)
+            .color(glyphon_color(color))
+            .font_features(features.to_glyphon());
+        let span_attrs: Vec<glyphon::Attrs> = key
+            .spans
+            .iter()
+            .map(|span| {
+                let mut span_attrs = attrs.clone();
+                if let Some(color) = span.color {
+                    span_attrs = span_attrs.color(glyphon_color(color));
+                }
+                if let Some(weight) = span.weight {
+                    span_attrs = span_attrs.weight(glyphon::Weight(weight));
+                }
+                if span.italic {
+                    span_attrs = span_attrs.style(glyphon::Style::Italic);
+                }
+                if let Some(span_size) = span.size {
+                    let span_size = span_size.to_pixels_f32();
+                    let span_line_height = line_height * span_size / size;
+                    span_attrs =
+                        span_attrs.metrics(glyphon::Metrics::new(span_size, span_line_height));
+                }
+                span_attrs
+            })
+            .collect();
+        text_buffer.set_rich_text(
             &mut write_font_system(),
-            &text,
-            &glyphon::Attrs::new()
-                .family(fontdb::Family::SansSerif)
-                .color(color),
+            key.spans
+                .iter()
+                .zip(span_attrs)
+                .map(|(span, span_attrs)| (span.text.as_str(), span_attrs)),
+            &attrs,
             glyphon::Shaping::Advanced,
             None,
         );
@@ -289,17 +480,26 @@ impl TextData {
         let mut run_width: f32 = 0.0;
         // Calculate total height including descender for the last line
         let metrics = text_buffer.metrics();
-        let num_lines = text_buffer.layout_runs().count() as f32;
+        let max_lines = constraint.max_lines.unwrap_or(usize::MAX);
+        // Lines with larger spans are taller than the buffer's line height
+        let lines_height: f32 = text_buffer
+            .layout_runs()
+            .take(max_lines)
+            .map(|run| run.line_height)
+            .sum();
         let descent_amount = (metrics.line_height - metrics.font_size).max(0.0);
-        let total_height = num_lines * metrics.line_height + descent_amount;
-        for run in text_buffer.layout_runs() {
+        let total_height = lines_height + descent_amount;
+        for run in text_buffer.layout_runs().take(max_lines) {
             // Take the max. width of all lines.
             run_width = run_width.max(run.line_w);
         }
+        let decorations = span_decorations(&text_buffer, &key.spans, color, size, max_lines);
         // build text data
         let result = Self {
             text_buffer,
             size: [run_width as u32, total_height.ceil() as u32],
+            decorations,
+            row_offsets: Vec::new(),
         };
         // Insert into cache
         write_lru_cache().put(key, result.clone());
@@ -310,9 +510,10 @@ impl TextData {
     pub fn from_buffer(text_buffer: glyphon::Buffer) -> Self {
         // Calculate total height including descender for the last line
         let metrics = text_buffer.metrics();
-        let num_lines = text_buffer.layout_runs().count() as f32;
+        // Lines can override the buffer's line height, e.g. folded lines in the editor
+        let lines_height: f32 = text_buffer.layout_runs().map(|run| run.line_height).sum();
         let descent_amount = (metrics.line_height - metrics.font_size).max(0.0);
-        let total_height = num_lines * metrics.line_height + descent_amount;
+        let total_height = lines_height + descent_amount;
         // Calculate text bounds
         let mut run_width: f32 = 0.0;
         for run in text_buffer.layout_runs() {
@@ -323,9 +524,24 @@ impl TextData {
         Self {
             text_buffer,
             size: [run_width as u32, total_height.ceil() as u32],
+            decorations: Vec::new(),
+            row_offsets: Vec::new(),
         }
     }
 
+    /// Draws each row from `top` to `bottom` of the layout `offset` pixels lower, e.g. the
+    /// text of editor lines made taller for a lens row above it. The rows are given in
+    /// layout order.
+    pub(crate) fn with_row_offsets(mut self, row_offsets: Vec<(f32, f32, f32)>) -> Self {
+        self.row_offsets = row_offsets;
+        self
+    }
+
+    /// Returns the laid out text buffer, e.g. to hit-test or select in it.
+    pub(crate) fn buffer(&self) -> &glyphon::Buffer {
+        &self.text_buffer
+    }
+
     /// Get the glyphon text area from the text data
     fn text_area(&'_ self, start_pos: PxPosition) -> glyphon::TextArea<'_> {
         let bounds = glyphon::TextBounds {
@@ -336,12 +552,104 @@ impl TextData {
         };
         glyphon::TextArea {
             buffer: &self.text_buffer,
-            left: start_pos.x.to_f32(),
+            // Vertical scroll is part of the layout, horizontal scroll is applied here
+            left: start_pos.x.to_f32() - self.text_buffer.scroll().horizontal,
             top: start_pos.y.to_f32(),
             scale: 1.0,
             bounds,
             default_color: glyphon::Color::rgb(0, 0, 0), // Black by default
-            custom_glyphs: &[],
+            custom_glyphs: &self.decorations,
+        }
+    }
+
+    /// Returns the text areas drawing the text data: one, or one clipped to each row with an
+    /// offset and to each part between them.
+    fn text_areas(&'_ self, start_pos: PxPosition) -> Vec<glyphon::TextArea<'_>> {
+        let area = self.text_area(start_pos);
+        if self.row_offsets.is_empty() {
+            return vec![area];
+        }
+        let clipped = |top: i32, bottom: i32, offset: f32| glyphon::TextArea {
+            top: area.top + offset,
+            bounds: glyphon::TextBounds {
+                top: top.max(area.bounds.top),
+                bottom: bottom.min(area.bounds.bottom),
+                ..area.bounds
+            },
+            ..area.clone()
+        };
+        let mut areas = Vec::with_capacity(self.row_offsets.len() * 2 + 1);
+        let mut top = area.bounds.top;
+        for &(row_top, row_bottom, offset) in &self.row_offsets {
+            let row_top = start_pos.y.raw() + row_top.round() as i32;
+            let row_bottom = start_pos.y.raw() + row_bottom.round() as i32;
+            areas.push(clipped(top, row_top, 0.0));
+            areas.push(clipped(row_top, row_bottom, offset));
+            top = row_bottom;
+        }
+        areas.push(clipped(top, area.bounds.bottom, 0.0));
+        areas.retain(|area| area.bounds.top < area.bounds.bottom);
+        areas
+    }
+}
+
+/// Returns the rectangles drawn with the first `max_lines` lines of `buffer` for the
+/// backgrounds, underlines and strikethroughs of the `spans` it was laid out from. Spans
+/// without a color or size of their own are set in `color` and `size`.
+fn span_decorations(
+    buffer: &glyphon::Buffer,
+    spans: &[TextSpan],
+    color: Color,
+    size: f32,
+    max_lines: usize,
+) -> Vec<glyphon::CustomGlyph> {
+    if !spans.iter().any(TextSpan::is_decorated) {
+        return Vec::new();
+    }
+    let span_ranges = span::span_ranges(spans);
+    let line_ranges = span::line_ranges(buffer);
+
+    let rect = |left: f32, top: f32, width: f32, height: f32, color: Color| glyphon::CustomGlyph {
+        id: RECT_GLYPH,
+        left,
+        top,
+        width,
+        height,
+        color: Some(glyphon_color(color)),
+        snap_to_physical_pixel: true,
+        metadata: 0,
+    };
+    // Backgrounds come first so the lines are drawn over them
+    let (mut backgrounds, mut lines) = (Vec::new(), Vec::new());
+    for run in buffer.layout_runs().take(max_lines) {
+        for (span, range) in spans.iter().zip(&span_ranges) {
+            if !span.is_decorated() {
+                continue;
+            }
+            let Some(on_line) = span::span_on_line(range, &line_ranges[run.line_i]) else {
+                continue;
+            };
+            let start = glyphon::Cursor::new(run.line_i, on_line.start);
+            let end = glyphon::Cursor::new(run.line_i, on_line.end);
+            let Some((x, width)) = run.highlight(start, end).filter(|&(_, w)| w > 0.0) else {
+                continue;
+            };
+            if let Some(background) = span.background {
+                backgrounds.push(rect(x, run.line_top, width, run.line_height, background));
+            }
+            let span_size = span.size.map_or(size, |size| size.to_pixels_f32());
+            let thickness = (span_size / 14.0).max(1.0);
+            let color = span.color.unwrap_or(color);
+            if span.underline {
+                let top = run.line_y + span_size * 0.1;
+                lines.push(rect(x, top, width, thickness, color));
+            }
+            if span.strikethrough {
+                let top = run.line_y - span_size * 0.3;
+                lines.push(rect(x, top, width, thickness, color));
+            }
         }
     }
+    backgrounds.extend(lines);
+    backgrounds
 }
@@ -23,6 +23,9 @@ pub struct TextConstraint {
     /// Maximum height of the text
     /// If None, it will be calculated by the text renderer
     pub max_height: Option<f32>,
+    /// Maximum number of lines to show
+    /// Lines past the limit are laid out but not counted in the size, so they are not drawn
+    pub max_lines: Option<usize>,
 }
 
 impl std::hash::Hash for TextConstraint {
@@ -37,6 +40,7 @@ impl std::hash::Hash for TextConstraint {
         } else {
             0u32.hash(state); // Hash a constant for None
         }
+        self.max_lines.hash(state);
     }
 }
 
@@ -45,5 +49,6 @@ impl TextConstraint {
     pub const NONE: Self = Self {
         max_width: None,
         max_height: None,
+        max_lines: None,
     };
 }
@@ -0,0 +1,111 @@
+use glyphon::cosmic_text;
+
+/// OpenType features applied when shaping text, e.g. to turn the ligatures of a code font
+/// on or off, pick a stylistic set or draw zeros slashed.
+///
+/// Features that are not set keep the default of the font, which usually has ligatures on.
+///
+/// # Example
+///
+/// ```
+/// use tessera_ui_basic_components::pipelines::FontFeatures;
+///
+/// // Plain text with `fi` and `->` drawn as separate characters
+/// let plain = FontFeatures::new().with_ligatures(false);
+/// // A code font with its ligatures, the alternates of its third stylistic set and a
+/// // slashed zero
+/// let code = FontFeatures::new()
+///     .with_ligatures(true)
+///     .with_stylistic_set(3)
+///     .with_slashed_zero(true);
+/// assert_eq!(code.feature(b"ss03"), Some(1));
+/// assert_eq!(plain.feature(b"zero"), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FontFeatures {
+    /// Feature tags with their values, in the order they were first set.
+    settings: Vec<([u8; 4], u32)>,
+}
+
+impl FontFeatures {
+    /// Creates settings that keep the defaults of the font.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the feature `tag`, e.g. `b"liga"`, to `value`, replacing an earlier value. Most
+    /// features are turned off with `0` and on with `1`; features choosing among alternates
+    /// take the number of the alternate.
+    pub fn with_feature(mut self, tag: &[u8; 4], value: u32) -> Self {
+        match self.settings.iter_mut().find(|(set, _)| set == tag) {
+            Some((_, old)) => *old = value,
+            None => self.settings.push((*tag, value)),
+        }
+        self
+    }
+
+    /// Turns the standard (`liga`) and contextual (`clig`) ligatures and the contextual
+    /// alternates (`calt`) on or off. Code fonts such as Fira Code draw their ligatures of
+    /// operators like `->` and `!=` with contextual alternates.
+    pub fn with_ligatures(self, enabled: bool) -> Self {
+        let value = u32::from(enabled);
+        self.with_feature(b"liga", value)
+            .with_feature(b"clig", value)
+            .with_feature(b"calt", value)
+    }
+
+    /// Turns the stylistic set `set` (`ss01` to `ss20`) on. Other numbers are ignored.
+    pub fn with_stylistic_set(self, set: u8) -> Self {
+        if !(1..=20).contains(&set) {
+            return self;
+        }
+        let [tens, ones] = [b'0' + set / 10, b'0' + set % 10];
+        self.with_feature(&[b's', b's', tens, ones], 1)
+    }
+
+    /// Turns the slashed or dotted zero (`zero`) on or off.
+    pub fn with_slashed_zero(self, enabled: bool) -> Self {
+        self.with_feature(b"zero", u32::from(enabled))
+    }
+
+    /// Returns the value `tag` is set to, or `None` if it keeps the default of the font.
+    pub fn feature(&self, tag: &[u8; 4]) -> Option<u32> {
+        self.settings
+            .iter()
+            .find(|(set, _)| set == tag)
+            .map(|&(_, value)| value)
+    }
+
+    /// Returns `true` if no feature is set.
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+    }
+
+    /// Returns the settings in the form the text shaper takes.
+    pub(crate) fn to_glyphon(&self) -> cosmic_text::FontFeatures {
+        let mut features = cosmic_text::FontFeatures::new();
+        for (tag, value) in &self.settings {
+            features.set(cosmic_text::FeatureTag::new(tag), *value);
+        }
+        features
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_replace_earlier_values() {
+        let features = FontFeatures::new()
+            .with_ligatures(true)
+            .with_stylistic_set(12)
+            .with_stylistic_set(21)
+            .with_feature(b"calt", 0);
+        assert_eq!(features.feature(b"liga"), Some(1));
+        assert_eq!(features.feature(b"calt"), Some(0));
+        assert_eq!(features.feature(b"ss12"), Some(1));
+        assert_eq!(features.settings.len(), 4);
+        assert!(FontFeatures::new().is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+use std::ops::Range;
+
+use tessera_ui::{Color, Dp};
+
+/// A run of text with its own style, laid out together with the spans around it.
+///
+/// Attributes that are not set fall back to those of the whole text, e.g. the color and size
+/// of [`TextArgs`](crate::text::TextArgs).
+///
+/// # Example
+///
+/// ```
+/// use tessera_ui::Color;
+/// use tessera_ui_basic_components::pipelines::TextSpan;
+///
+/// // "Build failed: 3 errors" with the count in bold red on a light background
+/// let spans = vec![
+///     TextSpan::new("Build failed: "),
+///     TextSpan::new("3 errors")
+///         .with_color(Color::new(0.8, 0.1, 0.1, 1.0))
+///         .with_weight(700)
+///         .with_background(Color::new(1.0, 0.9, 0.9, 1.0)),
+/// ];
+/// assert!(!spans[0].is_decorated());
+/// assert!(spans[1].is_decorated());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextSpan {
+    /// The text of the span; it may break the line with `\n`.
+    pub text: String,
+    /// Color of the text.
+    pub color: Option<Color>,
+    /// Font weight, from 100 (thin) over 400 (normal) and 700 (bold) to 900 (black).
+    pub weight: Option<u16>,
+    /// Whether the text is set in italics.
+    pub italic: bool,
+    /// Whether a line is drawn under the text.
+    pub underline: bool,
+    /// Whether a line is drawn through the text.
+    pub strikethrough: bool,
+    /// Font size. The line height is scaled with it.
+    pub size: Option<Dp>,
+    /// Color drawn behind the text, across the height of its line.
+    pub background: Option<Color>,
+}
+
+impl TextSpan {
+    /// Creates a span of `text` in the style of the whole text.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the color of the text.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the font weight, e.g. `700` for bold.
+    pub fn with_weight(mut self, weight: u16) -> Self {
+        self.weight = Some(weight);
+        self
+    }
+
+    /// Sets whether the text is set in italics.
+    pub fn with_italic(mut self, italic: bool) -> Self {
+        self.italic = italic;
+        self
+    }
+
+    /// Sets whether a line is drawn under the text.
+    pub fn with_underline(mut self, underline: bool) -> Self {
+        self.underline = underline;
+        self
+    }
+
+    /// Sets whether a line is drawn through the text.
+    pub fn with_strikethrough(mut self, strikethrough: bool) -> Self {
+        self.strikethrough = strikethrough;
+        self
+    }
+
+    /// Sets the font size.
+    pub fn with_size(mut self, size: Dp) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Sets the color drawn behind the text.
+    pub fn with_background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Returns `true` if anything is drawn around the glyphs of the span: an underline, a
+    /// strikethrough or a background.
+    pub fn is_decorated(&self) -> bool {
+        self.underline || self.strikethrough || self.background.is_some()
+    }
+}
+
+impl std::hash::Hash for TextSpan {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let hash_color = |color: Option<Color>, state: &mut H| {
+            color
+                .map(|c| [c.r, c.g, c.b, c.a].map(f32::to_bits))
+                .hash(state)
+        };
+        self.text.hash(state);
+        hash_color(self.color, state);
+        self.weight.hash(state);
+        self.italic.hash(state);
+        self.underline.hash(state);
+        self.strikethrough.hash(state);
+        self.size.map(|size| size.0.to_bits()).hash(state);
+        hash_color(self.background, state);
+    }
+}
+
+/// Returns the byte range of each of `spans` in the text they make up together.
+pub(super) fn span_ranges(spans: &[TextSpan]) -> Vec<Range<usize>> {
+    let mut offset = 0;
+    spans
+        .iter()
+        .map(|span| {
+            let start = offset;
+            offset += span.text.len();
+            start..offset
+        })
+        .collect()
+}
+
+/// Returns the byte range of the text of each line of `buffer` in the whole text, without its
+/// line ending.
+pub(super) fn line_ranges(buffer: &glyphon::Buffer) -> Vec<Range<usize>> {
+    let mut offset = 0;
+    buffer
+        .lines
+        .iter()
+        .map(|line| {
+            let start = offset;
+            offset += line.text().len() + line.ending().as_str().len();
+            start..start + line.text().len()
+        })
+        .collect()
+}
+
+/// Returns the part of the text at `span` that is on the line at `line`, in bytes from the
+/// start of the line, or `None` if the span is not on the line.
+pub(super) fn span_on_line(span: &Range<usize>, line: &Range<usize>) -> Option<Range<usize>> {
+    if span.is_empty() || span.end <= line.start || span.start >= line.end {
+        return None;
+    }
+    Some(span.start.max(line.start) - line.start..span.end.min(line.end) - line.start)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pipelines::write_font_system;
+
+    use super::*;
+
+    fn buffer(text: &str) -> glyphon::Buffer {
+        let mut buffer =
+            glyphon::Buffer::new(&mut write_font_system(), glyphon::Metrics::new(14.0, 20.0));
+        buffer.set_text(
+            &mut write_font_system(),
+            text,
+            &glyphon::Attrs::new(),
+            glyphon::Shaping::Advanced,
+            None,
+        );
+        buffer
+    }
+
+    #[test]
+    fn test_span_ranges_count_bytes() {
+        // 'é' and 'ö' are two bytes each
+        let spans = [
+            TextSpan::new("héllo "),
+            TextSpan::new("wörld\nnext"),
+            TextSpan::new(""),
+            TextSpan::new("!"),
+        ];
+        assert_eq!(span_ranges(&spans), vec![0..7, 7..18, 18..18, 18..19]);
+    }
+
+    #[test]
+    fn test_line_ranges_skip_line_endings() {
+        assert_eq!(
+            line_ranges(&buffer("héllo wörld\nnext!")),
+            vec![0..13, 14..19]
+        );
+        assert_eq!(line_ranges(&buffer("a\r\nb")), vec![0..1, 3..4]);
+    }
+
+    #[test]
+    fn test_span_on_line() {
+        let spans = [
+            TextSpan::new("héllo "),
+            TextSpan::new("wörld\nnext"),
+            TextSpan::new(""),
+            TextSpan::new("!"),
+        ];
+        let text: String = spans.iter().map(|span| span.text.as_str()).collect();
+        let lines = line_ranges(&buffer(&text));
+        let on_line =
+            |span: usize, line: usize| span_on_line(&span_ranges(&spans)[span], &lines[line]);
+
+        assert_eq!(on_line(0, 0), Some(0..7));
+        assert_eq!(on_line(0, 1), None);
+        // A span across a line break is cut into a part on each line
+        assert_eq!(on_line(1, 0), Some(7..13));
+        assert_eq!(on_line(1, 1), Some(0..4));
+        // Several spans share the second line, and an empty one is on none
+        assert_eq!(on_line(2, 1), None);
+        assert_eq!(on_line(3, 0), None);
+        assert_eq!(on_line(3, 1), Some(4..5));
+    }
+}
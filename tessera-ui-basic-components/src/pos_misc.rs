@@ -1,9 +1,64 @@
 //! Convenience utilities for testing whether a cursor/point falls inside a
 //! component or a rectangle. Functions operate using Px units and simple
 //! inclusive comparisons on edges.
+//!
+//! Components drawn with a [`Shape`] use [`HitTest`] to decide which part of
+//! their bounds takes the cursor, so the transparent corners of round buttons
+//! do not.
+
+use std::sync::Arc;
 
 use tessera_ui::{ComputedData, Px, PxPosition};
 
+use crate::shape_def::Shape;
+
+/// The region of a component that accepts the cursor, e.g. for hovering and clicking.
+#[derive(Clone, Default)]
+pub enum HitTest {
+    /// The [`Shape`] the component is drawn with.
+    #[default]
+    Shape,
+    /// The whole rectangular bounds of the component.
+    Bounds,
+    /// A custom region, e.g. one half of a diagonal split handle. It is called with the size
+    /// of the component and a position relative to its top-left corner.
+    Custom(Arc<dyn Fn(ComputedData, PxPosition) -> bool + Send + Sync>),
+}
+
+impl HitTest {
+    /// Returns true if `position` is inside the region of a component of the given `size`,
+    /// drawn with `shape`.
+    pub fn contains(&self, shape: Shape, size: ComputedData, position: PxPosition) -> bool {
+        match self {
+            HitTest::Shape => is_position_in_shape(shape, size, position),
+            HitTest::Bounds => is_position_in_component(size, position),
+            HitTest::Custom(contains) => {
+                is_position_in_component(size, position) && contains(size, position)
+            }
+        }
+    }
+}
+
+impl PartialEq for HitTest {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (HitTest::Shape, HitTest::Shape) | (HitTest::Bounds, HitTest::Bounds) => true,
+            (HitTest::Custom(a), HitTest::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Debug for HitTest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HitTest::Shape => f.write_str("Shape"),
+            HitTest::Bounds => f.write_str("Bounds"),
+            HitTest::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
 /// Returns true if `position` is inside a component of the given `size`.
 /// The component is assumed to be located at the origin (0, 0).
 ///
@@ -35,6 +90,55 @@ pub fn is_position_in_rect(
     within_x && within_y
 }
 
+/// Returns true if `position` is inside `shape` drawn over a component of the given `size`.
+/// The component is assumed to be located at the origin (0, 0).
+///
+/// Rounded corners are tested as circular arcs, which is close to the smoother
+/// corners they are drawn with.
+pub fn is_position_in_shape(shape: Shape, size: ComputedData, position: PxPosition) -> bool {
+    if !is_position_in_component(size, position) {
+        return false;
+    }
+    let (width, height) = (size.width.to_f32(), size.height.to_f32());
+    let (x, y) = (position.x.to_f32(), position.y.to_f32());
+    let radii = match shape {
+        Shape::Ellipse => {
+            let (rx, ry) = (width / 2.0, height / 2.0);
+            if rx <= 0.0 || ry <= 0.0 {
+                return false;
+            }
+            let (dx, dy) = ((x - rx) / rx, (y - ry) / ry);
+            return dx * dx + dy * dy <= 1.0;
+        }
+        Shape::RoundedRectangle {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+            ..
+        } => [top_left, top_right, bottom_right, bottom_left].map(|r| r.to_pixels_f32()),
+        Shape::HorizontalCapsule => [height / 2.0; 4],
+        Shape::VerticalCapsule => [width / 2.0; 4],
+    };
+    let max_radius = width.min(height) / 2.0;
+    let [top_left, top_right, bottom_right, bottom_left] = radii.map(|r| r.min(max_radius));
+    // Only the square of each corner that is cut off can be outside the shape
+    let (left, top) = (x < width / 2.0, y < height / 2.0);
+    let (corner, cx, cy) = match (left, top) {
+        (true, true) => (top_left, top_left, top_left),
+        (false, true) => (top_right, width - top_right, top_right),
+        (false, false) => (bottom_right, width - bottom_right, height - bottom_right),
+        (true, false) => (bottom_left, bottom_left, height - bottom_left),
+    };
+    let in_corner_x = if left { x < cx } else { x > cx };
+    let in_corner_y = if top { y < cy } else { y > cy };
+    if !(in_corner_x && in_corner_y) {
+        return true;
+    }
+    let (dx, dy) = (x - cx, y - cy);
+    dx * dx + dy * dy <= corner * corner
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,4 +162,40 @@ mod tests {
             PxPosition::new(Px(50), Px(75))
         ));
     }
+
+    #[test]
+    fn test_is_position_in_shape() {
+        let size = ComputedData {
+            width: Px(100),
+            height: Px(50),
+        };
+        let at = |x, y| PxPosition::new(Px(x), Px(y));
+        let rounded = Shape::rounded_rectangle(tessera_ui::Dp(10.0));
+        assert!(is_position_in_shape(rounded, size, at(50, 25)));
+        assert!(is_position_in_shape(rounded, size, at(5, 20)));
+        // The corners are cut off
+        assert!(!is_position_in_shape(rounded, size, at(1, 1)));
+        assert!(!is_position_in_shape(rounded, size, at(99, 49)));
+        assert!(is_position_in_shape(Shape::RECTANGLE, size, at(1, 1)));
+
+        assert!(is_position_in_shape(Shape::Ellipse, size, at(50, 1)));
+        assert!(!is_position_in_shape(Shape::Ellipse, size, at(5, 5)));
+        assert!(!is_position_in_shape(
+            Shape::HorizontalCapsule,
+            size,
+            at(2, 2)
+        ));
+        assert!(is_position_in_shape(
+            Shape::HorizontalCapsule,
+            size,
+            at(30, 2)
+        ));
+
+        let left_half = HitTest::Custom(Arc::new(|size: ComputedData, pos: PxPosition| {
+            pos.x < size.width / 2
+        }));
+        assert!(left_half.contains(Shape::RECTANGLE, size, at(10, 10)));
+        assert!(!left_half.contains(Shape::RECTANGLE, size, at(60, 10)));
+        assert!(HitTest::Bounds.contains(Shape::Ellipse, size, at(1, 1)));
+    }
 }
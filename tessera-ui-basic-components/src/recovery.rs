@@ -0,0 +1,313 @@
+//! Crash recovery for the unsaved changes of text editors.
+//!
+//! A [`RecoveryJournal`] writes the content of every editor buffer that changed since it was
+//! last saved to a recovery directory, at most once per [interval](RecoveryJournal::with_interval).
+//! Saving or closing a buffer removes its journal file, so the files left in the directory
+//! at the next launch are the buffers whose changes were lost. [`RecoveryJournal::recoverable`]
+//! lists them, and [`RecoveredBuffer::restore`] puts one back into an editor.
+//!
+//! Buffers are told apart by an id chosen by the application, usually the path of the
+//! edited file. Journal files are named after a hash of the id, so ids of any length make
+//! valid file names, and keep the id itself in their header.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use tessera_ui::Dp;
+//! use tessera_ui_basic_components::{recovery::RecoveryJournal, text_editor::TextEditorState};
+//!
+//! let mut journal = RecoveryJournal::new("/home/me/.cache/my-editor/recovery");
+//! let mut state = TextEditorState::new(Dp(14.0), None);
+//!
+//! // At launch, offer what the last session lost
+//! for buffer in journal.recoverable().unwrap() {
+//!     if buffer.id == "notes.txt" {
+//!         buffer.restore(&mut state);
+//!     }
+//! }
+//!
+//! // Every frame, e.g. in the component showing the editor
+//! journal.update("notes.txt", &state).unwrap();
+//!
+//! // After writing the file
+//! journal.mark_saved("notes.txt", &state).unwrap();
+//! ```
+use std::{
+    collections::HashMap,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use tessera_ui::clock;
+
+use crate::text_editor::TextEditorState;
+
+/// First line of every journal file, with the version of the format.
+const HEADER: &str = "tessera-recovery 1";
+
+/// Extension of the journal files.
+const EXTENSION: &str = "recovery";
+
+/// Journals the unsaved content of editor buffers to a recovery directory. See the
+/// [module documentation](self).
+pub struct RecoveryJournal {
+    dir: PathBuf,
+    interval: Duration,
+    buffers: HashMap<String, JournaledBuffer>,
+}
+
+/// What the journal knows about one buffer.
+struct JournaledBuffer {
+    /// When the content was last looked at.
+    checked: Instant,
+    /// Hash of the content that was last saved or written to the journal.
+    hash: u64,
+}
+
+impl RecoveryJournal {
+    /// Creates a journal writing to `dir`, which is created when the first file is written.
+    /// Changes are journaled at most every 5 seconds.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            interval: Duration::from_secs(5),
+            buffers: HashMap::new(),
+        }
+    }
+
+    /// Sets how long the journal waits after looking at a buffer before it looks again.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the directory the journal files are written to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Journals the content of the buffer `id` shown in `state` if it changed since it was
+    /// last saved or journaled, and the interval has passed since the last look. Cheap to
+    /// call every frame.
+    ///
+    /// The content at the first update of a buffer counts as saved, so a file that was just
+    /// opened is not journaled until it is edited.
+    pub fn update(&mut self, id: &str, state: &TextEditorState) -> io::Result<()> {
+        let now = clock::now();
+        let Some(buffer) = self.buffers.get_mut(id) else {
+            let hash = content_hash(&state.content());
+            let buffer = JournaledBuffer { checked: now, hash };
+            self.buffers.insert(id.to_string(), buffer);
+            return Ok(());
+        };
+        if now.duration_since(buffer.checked) < self.interval {
+            return Ok(());
+        }
+        buffer.checked = now;
+        let content = state.content();
+        let hash = content_hash(&content);
+        if hash == buffer.hash {
+            return Ok(());
+        }
+        let (line, column) = state.cursor_position();
+        write_atomically(&self.dir, id, &encode(id, line, column, &content))?;
+        buffer.hash = hash;
+        Ok(())
+    }
+
+    /// Records the content of `state` as the saved version of the buffer `id` and removes
+    /// its journal file.
+    pub fn mark_saved(&mut self, id: &str, state: &TextEditorState) -> io::Result<()> {
+        let buffer = JournaledBuffer {
+            checked: clock::now(),
+            hash: content_hash(&state.content()),
+        };
+        self.buffers.insert(id.to_string(), buffer);
+        remove_file(&self.dir.join(file_name(id)))
+    }
+
+    /// Forgets the buffer `id` and removes its journal file, e.g. when it is closed without
+    /// saving or its recovered content was declined.
+    pub fn discard(&mut self, id: &str) -> io::Result<()> {
+        self.buffers.remove(id);
+        remove_file(&self.dir.join(file_name(id)))
+    }
+
+    /// Returns the buffers left in the recovery directory, sorted by id. Files that are not
+    /// journal files are skipped; a missing directory has none.
+    pub fn recoverable(&self) -> io::Result<Vec<RecoveredBuffer>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut buffers = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_none_or(|extension| extension != EXTENSION)
+            {
+                continue;
+            }
+            let contents = fs::read_to_string(&path)?;
+            let Some(journal) = decode(&contents) else {
+                continue;
+            };
+            buffers.push(RecoveredBuffer {
+                id: journal.id.to_string(),
+                text: journal.content.to_string(),
+                line: journal.line,
+                column: journal.column,
+                modified: fs::metadata(&path)?.modified()?,
+            });
+        }
+        buffers.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(buffers)
+    }
+}
+
+/// The unsaved content of a buffer found in the recovery directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecoveredBuffer {
+    /// The id the buffer was journaled under.
+    pub id: String,
+    /// The content when it was last journaled.
+    pub text: String,
+    /// Line of the cursor.
+    pub line: usize,
+    /// Byte column of the cursor within its line.
+    pub column: usize,
+    /// When the content was journaled.
+    pub modified: SystemTime,
+}
+
+impl RecoveredBuffer {
+    /// Replaces the content of `state` with the recovered one as a single undo step, so
+    /// undoing goes back to the saved version, and puts the cursor where it was.
+    ///
+    /// The journal file stays until the buffer is saved or discarded.
+    pub fn restore(&self, state: &mut TextEditorState) {
+        state.replace_content(&self.text);
+        state.set_cursor(self.line, self.column);
+    }
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the name of the journal file of the buffer `id`: the FNV-1a hash of the id in
+/// hex, which has the same short length for any id and, unlike [`DefaultHasher`], is the
+/// same in every build so the next launch finds the file again.
+fn file_name(id: &str) -> String {
+    let hash = id.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}.{EXTENSION}")
+}
+
+/// The contents of a journal file.
+#[derive(Debug, PartialEq, Eq)]
+struct Journal<'a> {
+    id: &'a str,
+    line: usize,
+    column: usize,
+    content: &'a str,
+}
+
+/// Returns the contents of a journal file for `content` of the buffer `id` with the cursor
+/// at `line` and `column`. The byte length of the id comes before it, so it may contain line
+/// breaks.
+fn encode(id: &str, line: usize, column: usize, content: &str) -> String {
+    format!("{HEADER}\n{line} {column} {}\n{id}\n{content}", id.len())
+}
+
+/// Returns the journal in the file `contents`, or `None` if it is not in the format
+/// [`encode`] writes.
+fn decode(contents: &str) -> Option<Journal<'_>> {
+    let rest = contents.strip_prefix(HEADER)?.strip_prefix('\n')?;
+    let (numbers, rest) = rest.split_once('\n')?;
+    let mut numbers = numbers.split(' ').map(|number| number.parse().ok());
+    let (line, column, id_len) = (numbers.next()??, numbers.next()??, numbers.next()??);
+    if numbers.next().is_some() {
+        return None;
+    }
+    let id = rest.get(..id_len)?;
+    let content = rest.get(id_len..)?.strip_prefix('\n')?;
+    Some(Journal {
+        id,
+        line,
+        column,
+        content,
+    })
+}
+
+/// Writes `contents` to the journal file of `id` in `dir` through a temporary file, so a
+/// crash while writing leaves the previous version intact.
+fn write_atomically(dir: &Path, id: &str, contents: &str) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(file_name(id));
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, &path)
+}
+
+/// Removes the file at `path` if it exists.
+fn remove_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_names() {
+        let long = "a/".repeat(1000);
+        for id in [
+            "notes.txt",
+            "/home/me/src/main.rs",
+            "Untitled 2",
+            "über/ß",
+            &long,
+        ] {
+            let name = file_name(id);
+            assert_eq!(name.len(), 16 + 1 + EXTENSION.len());
+            assert!(name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'.'));
+        }
+        assert_ne!(file_name("notes.txt"), file_name("notes.txt "));
+        // Stable across builds, unlike the standard hasher
+        assert_eq!(file_name(""), format!("cbf29ce484222325.{EXTENSION}"));
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let contents = encode("über/ß.txt", 3, 7, "first\nsecond\n\nlast");
+        let journal = Journal {
+            id: "über/ß.txt",
+            line: 3,
+            column: 7,
+            content: "first\nsecond\n\nlast",
+        };
+        assert_eq!(decode(&contents), Some(journal));
+
+        let journal = Journal {
+            id: "two\nlines",
+            line: 0,
+            column: 0,
+            content: "",
+        };
+        assert_eq!(decode(&encode("two\nlines", 0, 0, "")), Some(journal));
+        assert_eq!(decode("some other file"), None);
+        assert_eq!(decode(&format!("{HEADER}\n1 2 40\nshort\n")), None);
+    }
+}
@@ -4,7 +4,20 @@
 //!
 //! Other components composed from those, such as [`crate::button::button`], also leverage it to provide ripple effects.
 
-use std::sync::atomic;
+use std::{
+    sync::{OnceLock, atomic},
+    time::Instant,
+};
+
+use tessera_ui::{clock, focus_state::Focus};
+
+/// The time the animation start times are counted from.
+static ORIGIN: OnceLock<Instant> = OnceLock::new();
+
+/// Returns the milliseconds since [`ORIGIN`] by the [frame clock](clock).
+fn clock_millis() -> u64 {
+    clock::elapsed(*ORIGIN.get_or_init(clock::now)).as_millis() as u64
+}
 
 /// `RippleState` manages the animation and hover state for ripple effects in interactive UI components.
 /// It is designed to be shared across components using `Arc<RippleState>`, enabling coordinated animation and hover feedback.
@@ -32,7 +45,8 @@ use std::sync::atomic;
 pub struct RippleState {
     /// Whether the ripple animation is currently active.
     pub is_animating: atomic::AtomicBool,
-    /// The animation start time, stored as milliseconds since the Unix epoch.
+    /// The animation start time, stored as milliseconds of the [frame clock](clock) since the
+    /// first ripple.
     pub start_time: atomic::AtomicU64,
     /// The X coordinate of the click position, stored as fixed-point (multiplied by 1000).
     pub click_pos_x: atomic::AtomicI32,
@@ -40,6 +54,11 @@ pub struct RippleState {
     pub click_pos_y: atomic::AtomicI32,
     /// Whether the pointer is currently hovering over the component.
     pub is_hovered: atomic::AtomicBool,
+    /// Whether the component is held down by the pointer.
+    pub is_pressed: atomic::AtomicBool,
+    /// Keyboard focus of the component, used by [focusable](crate::surface::SurfaceArgs::focusable)
+    /// surfaces.
+    pub focus: Focus,
 }
 
 impl Default for RippleState {
@@ -64,6 +83,8 @@ impl RippleState {
             click_pos_x: atomic::AtomicI32::new(0),
             click_pos_y: atomic::AtomicI32::new(0),
             is_hovered: atomic::AtomicBool::new(false),
+            is_pressed: atomic::AtomicBool::new(false),
+            focus: Focus::new(),
         }
     }
 
@@ -80,10 +101,7 @@ impl RippleState {
     /// state.start_animation([0.5, 0.5]);
     /// ```
     pub fn start_animation(&self, click_pos: [f32; 2]) {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
+        let now = clock_millis();
 
         self.start_time.store(now, atomic::Ordering::SeqCst);
         self.click_pos_x
@@ -117,10 +135,7 @@ impl RippleState {
             return None;
         }
 
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
+        let now = clock_millis();
         let start = self.start_time.load(atomic::Ordering::SeqCst);
         let elapsed_ms = now.saturating_sub(start);
         let progress = (elapsed_ms as f32) / 600.0; // 600ms animation
@@ -165,4 +180,45 @@ impl RippleState {
     pub fn is_hovered(&self) -> bool {
         self.is_hovered.load(atomic::Ordering::SeqCst)
     }
+
+    /// Sets whether the component is held down by the pointer.
+    ///
+    /// # Arguments
+    ///
+    /// * `pressed` - `true` from the press until the release, `false` otherwise.
+    pub fn set_pressed(&self, pressed: bool) {
+        self.is_pressed.store(pressed, atomic::Ordering::SeqCst);
+    }
+
+    /// Returns whether the component is held down by the pointer.
+    ///
+    /// # Example
+    /// ```
+    /// use tessera_ui_basic_components::ripple_state::RippleState;
+    /// let state = RippleState::new();
+    /// state.set_pressed(true);
+    /// assert!(state.is_pressed());
+    /// ```
+    pub fn is_pressed(&self) -> bool {
+        self.is_pressed.load(atomic::Ordering::SeqCst)
+    }
+
+    /// Ends the hover and the press and gives up the keyboard focus, for a component that
+    /// stopped taking input, e.g. because it was disabled while held down or focused.
+    ///
+    /// # Example
+    /// ```
+    /// use tessera_ui_basic_components::ripple_state::RippleState;
+    /// let state = RippleState::new();
+    /// state.set_pressed(true);
+    /// state.focus.request_focus();
+    /// state.release();
+    /// assert!(!state.is_pressed());
+    /// assert!(!state.focus.is_focused());
+    /// ```
+    pub fn release(&self) {
+        self.set_hovered(false);
+        self.set_pressed(false);
+        self.focus.unfocus();
+    }
 }
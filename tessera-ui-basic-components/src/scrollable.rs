@@ -14,13 +14,13 @@
 //!
 //! # Example
 //! See [`scrollable()`] for usage details and code samples.
-mod scrollbar;
+pub(crate) mod scrollbar;
 use std::{sync::Arc, time::Instant};
 
 use derive_builder::Builder;
 use parking_lot::RwLock;
 use tessera_ui::{
-    Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp, Px, PxPosition,
+    Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp, Px, PxPosition, clock,
     tessera,
 };
 
@@ -68,6 +68,15 @@ pub struct ScrollableArgs {
     /// The layout of the scrollbar relative to the content.
     #[builder(default = "ScrollBarLayout::Alongside")]
     pub scrollbar_layout: ScrollBarLayout,
+    /// Chat-style stacking for vertical scrolling.
+    ///
+    /// When `true`, content shorter than the viewport sits at the bottom, and the view
+    /// follows newly added content for as long as the user stays scrolled to the end.
+    /// Scrolling up releases the pin; use [`ScrollableState::has_unseen_content`] and
+    /// [`ScrollableState::scroll_to_end`] to offer a "jump to latest" action.
+    /// Defaults to `false`.
+    #[builder(default = "false")]
+    pub stick_to_end: bool,
 }
 
 /// Defines the behavior of the scrollbar visibility.
@@ -116,6 +125,42 @@ impl ScrollableState {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Returns `true` while the view is pinned to the end of the content.
+    ///
+    /// Only meaningful for scrollables created with [`ScrollableArgs::stick_to_end`].
+    pub fn is_at_end(&self) -> bool {
+        self.inner.read().pinned_to_end
+    }
+
+    /// Returns `true` if content grew below the viewport after the user scrolled away
+    /// from the end, e.g. to show an unread badge on a "jump to latest" button.
+    pub fn has_unseen_content(&self) -> bool {
+        self.inner.read().unseen_content
+    }
+
+    /// Scrolls to the end of the content and pins the view there again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::scrollable::ScrollableState;
+    ///
+    /// let state = Arc::new(ScrollableState::new());
+    /// // Typically called from the on_click of a "jump to latest" button.
+    /// if state.has_unseen_content() || !state.is_at_end() {
+    ///     state.scroll_to_end();
+    /// }
+    /// assert!(state.is_at_end());
+    /// ```
+    pub fn scroll_to_end(&self) {
+        let mut inner = self.inner.write();
+        let end = inner.end_position();
+        inner.set_target_position(end);
+        inner.pinned_to_end = true;
+        inner.unseen_content = false;
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -130,6 +175,12 @@ struct ScrollableStateInner {
     visible_size: ComputedData,
     /// Last frame time for delta time calculation
     last_frame_time: Option<Instant>,
+    /// Whether the scrollable was laid out with `stick_to_end`
+    stick_to_end: bool,
+    /// Whether the view follows the end of the content (`stick_to_end` only)
+    pinned_to_end: bool,
+    /// Whether content was added below while not pinned (`stick_to_end` only)
+    unseen_content: bool,
 }
 
 impl Default for ScrollableStateInner {
@@ -147,13 +198,27 @@ impl ScrollableStateInner {
             child_size: ComputedData::ZERO,
             visible_size: ComputedData::ZERO,
             last_frame_time: None,
+            stick_to_end: false,
+            pinned_to_end: false,
+            unseen_content: false,
+        }
+    }
+
+    /// The vertical position at which the end of the child is aligned with the end of the viewport.
+    fn end_position(&self) -> PxPosition {
+        PxPosition {
+            x: self.target_position.x,
+            y: self
+                .visible_size
+                .height
+                .saturating_sub(self.child_size.height),
         }
     }
 
     /// Updates the scroll position based on time-based interpolation
     /// Returns true if the position changed (needs redraw)
     fn update_scroll_position(&mut self, smoothing: f32) -> bool {
-        let current_time = Instant::now();
+        let current_time = clock::now();
 
         // Calculate delta time
         let delta_time = if let Some(last_time) = self.last_frame_time {
@@ -204,6 +269,18 @@ impl ScrollableStateInner {
     fn set_target_position(&mut self, target: PxPosition) {
         self.target_position = target;
     }
+
+    /// Scrolls to `target` on behalf of the user, who releases the pin by scrolling away
+    /// from the end and re-pins the view by scrolling back to it.
+    pub(crate) fn scroll_to(&mut self, target: PxPosition) {
+        self.set_target_position(target);
+        if self.stick_to_end {
+            self.pinned_to_end = target.y <= self.end_position().y;
+            if self.pinned_to_end {
+                self.unseen_content = false;
+            }
+        }
+    }
 }
 
 /// A container that makes its content scrollable when it exceeds the container's size.
@@ -549,8 +626,33 @@ fn scrollable_inner(
             // Measure the child with child constraint
             let child_node_id = input.children_ids[0]; // Scrollable should have exactly one child
             let child_measurement = input.measure_child(child_node_id, &child_constraint)?;
+
+            // Calculate the size of the scrollable area using helpers to reduce inline branching
+            let width = resolve_dimension(merged_constraint.width, child_measurement.width);
+            let height = resolve_dimension(merged_constraint.height, child_measurement.height);
+            let computed_data = ComputedData { width, height };
+
             // Update the child position and size in the state
-            state.write().child_size = child_measurement;
+            {
+                let mut state_guard = state.write();
+                let grew = child_measurement.height > state_guard.child_size.height;
+                state_guard.child_size = child_measurement;
+                state_guard.visible_size = computed_data;
+                // A chat-style view starts out pinned to the end
+                let stick_to_end = args.stick_to_end && args.vertical;
+                if stick_to_end && !state_guard.stick_to_end {
+                    state_guard.pinned_to_end = true;
+                }
+                state_guard.stick_to_end = stick_to_end;
+                if args.stick_to_end && args.vertical {
+                    if state_guard.pinned_to_end {
+                        let end = state_guard.end_position();
+                        state_guard.set_target_position(end);
+                    } else if grew {
+                        state_guard.unseen_content = true;
+                    }
+                }
+            }
 
             // Update scroll position based on time and get current position for rendering
             let current_child_position = {
@@ -562,14 +664,6 @@ fn scrollable_inner(
             // Place child at current interpolated position
             input.place_child(child_node_id, current_child_position);
 
-            // Calculate the size of the scrollable area using helpers to reduce inline branching
-            let width = resolve_dimension(merged_constraint.width, child_measurement.width);
-            let height = resolve_dimension(merged_constraint.height, child_measurement.height);
-
-            // Pack the size into ComputedData
-            let computed_data = ComputedData { width, height };
-            // Update the visible size in the state
-            state.write().visible_size = computed_data;
             // Return the size of the scrollable area
             Ok(computed_data)
         }));
@@ -614,23 +708,24 @@ fn scrollable_inner(
                     &input.computed_data,
                     args.vertical,
                     args.horizontal,
+                    args.stick_to_end,
                 );
 
                 // Set constrained target position
-                state_guard.set_target_position(constrained_target);
+                state_guard.scroll_to(constrained_target);
 
                 // Update scroll activity for AutoHide behavior
                 if matches!(args.scrollbar_behavior, ScrollBarBehavior::AutoHide) {
                     // Update vertical scrollbar state if vertical scrolling is enabled
                     if args.vertical {
                         let mut scrollbar_state = scrollbar_state_v.write();
-                        scrollbar_state.last_scroll_activity = Some(std::time::Instant::now());
+                        scrollbar_state.last_scroll_activity = Some(clock::now());
                         scrollbar_state.should_be_visible = true;
                     }
                     // Update horizontal scrollbar state if horizontal scrolling is enabled
                     if args.horizontal {
                         let mut scrollbar_state = scrollbar_state_h.write();
-                        scrollbar_state.last_scroll_activity = Some(std::time::Instant::now());
+                        scrollbar_state.last_scroll_activity = Some(clock::now());
                         scrollbar_state.should_be_visible = true;
                     }
                 }
@@ -646,6 +741,7 @@ fn scrollable_inner(
                 &input.computed_data,
                 args.vertical,
                 args.horizontal,
+                args.stick_to_end,
             );
             state.write().set_target_position(constrained_position);
 
@@ -674,12 +770,23 @@ fn constrain_axis(pos: Px, child_len: Px, container_len: Px) -> Px {
     }
 }
 
+/// Like [`constrain_axis`], but content shorter than the container is aligned to its end.
+fn constrain_axis_to_end(pos: Px, child_len: Px, container_len: Px) -> Px {
+    let end = container_len.saturating_sub(child_len);
+    if child_len <= container_len {
+        end
+    } else {
+        pos.min(Px::ZERO).max(end)
+    }
+}
+
 fn constrain_position(
     position: PxPosition,
     child_size: &ComputedData,
     container_size: &ComputedData,
     vertical_scrollable: bool,
     horizontal_scrollable: bool,
+    stick_to_end: bool,
 ) -> PxPosition {
     let x = if horizontal_scrollable {
         constrain_axis(position.x, child_size.width, container_size.width)
@@ -687,7 +794,9 @@ fn constrain_position(
         Px::ZERO
     };
 
-    let y = if vertical_scrollable {
+    let y = if vertical_scrollable && stick_to_end {
+        constrain_axis_to_end(position.y, child_size.height, container_size.height)
+    } else if vertical_scrollable {
         constrain_axis(position.y, child_size.height, container_size.height)
     } else {
         Px::ZERO
@@ -695,3 +804,59 @@ fn constrain_position(
 
     PxPosition { x, y }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constrain_axis_to_end_aligns_short_content_to_the_end() {
+        assert_eq!(constrain_axis_to_end(Px(0), Px(30), Px(100)), Px(70));
+        assert_eq!(constrain_axis_to_end(Px(-20), Px(30), Px(100)), Px(70));
+        assert_eq!(constrain_axis_to_end(Px(0), Px(100), Px(100)), Px(0));
+    }
+
+    #[test]
+    fn test_constrain_axis_to_end_clamps_long_content() {
+        assert_eq!(constrain_axis_to_end(Px(10), Px(300), Px(100)), Px(0));
+        assert_eq!(constrain_axis_to_end(Px(-50), Px(300), Px(100)), Px(-50));
+        assert_eq!(constrain_axis_to_end(Px(-250), Px(300), Px(100)), Px(-200));
+    }
+
+    fn chat_state() -> ScrollableStateInner {
+        let mut state = ScrollableStateInner::new();
+        state.stick_to_end = true;
+        state.pinned_to_end = true;
+        state.child_size = ComputedData {
+            width: Px(100),
+            height: Px(300),
+        };
+        state.visible_size = ComputedData {
+            width: Px(100),
+            height: Px(100),
+        };
+        state
+    }
+
+    #[test]
+    fn test_scrolling_away_from_the_end_releases_the_pin() {
+        let mut state = chat_state();
+        state.unseen_content = true;
+        state.scroll_to(PxPosition::new(Px(0), Px(-120)));
+        assert!(!state.pinned_to_end);
+        assert!(state.unseen_content);
+        state.scroll_to(PxPosition::new(Px(0), Px(-200)));
+        assert!(state.pinned_to_end);
+        assert!(!state.unseen_content);
+    }
+
+    #[test]
+    fn test_scrolling_without_stick_to_end_never_pins() {
+        let mut state = chat_state();
+        state.stick_to_end = false;
+        state.pinned_to_end = false;
+        state.scroll_to(PxPosition::new(Px(0), Px(-200)));
+        assert!(!state.pinned_to_end);
+        assert!(!ScrollableStateInner::new().pinned_to_end);
+    }
+}
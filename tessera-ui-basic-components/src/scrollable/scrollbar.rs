@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use parking_lot::RwLock;
 use tessera_ui::{
-    Color, Constraint, CursorEventContent, Dp, PressKeyEventType, Px, PxPosition, tessera,
+    Color, Constraint, CursorEventContent, Dp, PressKeyEventType, Px, PxPosition, clock, tessera,
 };
 
 use crate::{
@@ -11,7 +11,27 @@ use crate::{
     surface::{SurfaceArgsBuilder, surface},
 };
 
-#[derive(Clone, Debug)]
+/// The scroll position driven by a scrollbar.
+///
+/// Positions are offsets of the content, so they are zero or negative.
+pub(crate) trait ScrollTarget: Send + Sync {
+    /// Returns the position the content is being scrolled to.
+    fn target_position(&self) -> PxPosition;
+    /// Scrolls the content to `position`, as the user dragging or clicking the scrollbar.
+    fn set_target_position(&self, position: PxPosition);
+}
+
+impl ScrollTarget for RwLock<ScrollableStateInner> {
+    fn target_position(&self) -> PxPosition {
+        self.read().target_position
+    }
+
+    fn set_target_position(&self, position: PxPosition) {
+        self.write().scroll_to(position);
+    }
+}
+
+#[derive(Clone)]
 pub struct ScrollBarArgs {
     /// The total size of the scrollable content.
     pub total: Px,
@@ -21,8 +41,8 @@ pub struct ScrollBarArgs {
     pub offset: Px,
     /// The thickness of the scrollbar
     pub thickness: Dp,
-    /// The scrollable's state, used for interaction.
-    pub state: Arc<RwLock<ScrollableStateInner>>,
+    /// The scroll position the scrollbar reads and drives.
+    pub state: Arc<dyn ScrollTarget>,
     /// The behavior of the scrollbar visibility.
     pub scrollbar_behavior: ScrollBarBehavior,
     /// The color of the scrollbar track.
@@ -74,12 +94,15 @@ fn calculate_target_pos_v(
 
     let content_scrollable_range = total - visible;
     if content_scrollable_range <= Px::ZERO {
-        return PxPosition::ZERO;
+        return PxPosition {
+            x: fallback.x,
+            y: Px::ZERO,
+        };
     }
 
     let new_target_y = Px::from_f32(-progress * content_scrollable_range.to_f32());
     PxPosition {
-        x: Px::ZERO, // Vertical scrollbar doesn't affect X
+        x: fallback.x, // Vertical scrollbar doesn't affect X
         y: new_target_y,
     }
 }
@@ -105,13 +128,16 @@ fn calculate_target_pos_h(
 
     let content_scrollable_range = total - visible;
     if content_scrollable_range <= Px::ZERO {
-        return PxPosition::ZERO;
+        return PxPosition {
+            x: Px::ZERO,
+            y: fallback.y,
+        };
     }
 
     let new_target_x = Px::from_f32(-progress * content_scrollable_range.to_f32());
     PxPosition {
         x: new_target_x,
-        y: Px::ZERO, // Horizontal scrollbar doesn't affect Y
+        y: fallback.y, // Horizontal scrollbar doesn't affect Y
     }
 }
 
@@ -125,7 +151,7 @@ fn compute_thumb_color(state_lock: &Arc<RwLock<ScrollBarState>>, args: &ScrollBa
         (args.thumb_hover_color, args.thumb_color)
     };
     let progress = if let Some(instant) = state.hover_instant {
-        (instant.elapsed().as_secs_f32() / 0.2).min(1.0)
+        (clock::elapsed(instant).as_secs_f32() / 0.2).min(1.0)
     } else {
         0.0
     };
@@ -245,7 +271,7 @@ fn handle_autohide_if_needed(args: &ScrollBarArgs, state: &Arc<RwLock<ScrollBarS
         let mut state_guard = state.write();
         if let Some(last_activity) = state_guard.last_scroll_activity {
             // Hide scrollbar after 2 seconds of inactivity
-            if last_activity.elapsed().as_secs_f32() > 2.0 {
+            if clock::elapsed(last_activity).as_secs_f32() > 2.0 {
                 state_guard.should_be_visible = false;
             }
         }
@@ -256,7 +282,7 @@ fn handle_autohide_if_needed(args: &ScrollBarArgs, state: &Arc<RwLock<ScrollBarS
 fn mark_scroll_activity(state: &Arc<RwLock<ScrollBarState>>, behavior: &ScrollBarBehavior) {
     if matches!(*behavior, ScrollBarBehavior::AutoHide) {
         let mut state_guard = state.write();
-        state_guard.last_scroll_activity = Some(std::time::Instant::now());
+        state_guard.last_scroll_activity = Some(clock::now());
         state_guard.should_be_visible = true;
     }
 }
@@ -354,7 +380,7 @@ fn update_drag_vertical(
 ) {
     if let Some(cursor_pos) = input.cursor_position_rel {
         let new_target_pos = calculate_target(cursor_pos.y);
-        args.state.write().set_target_position(new_target_pos);
+        args.state.set_target_position(new_target_pos);
         mark_scroll_activity(state, &args.scrollbar_behavior);
     } else {
         // Cursor left window: stop dragging.
@@ -367,11 +393,11 @@ fn update_hover_state(is_on_thumb: bool, state: &Arc<RwLock<ScrollBarState>>) {
     if is_on_thumb && !state.read().is_hovered {
         let mut state_guard = state.write();
         state_guard.is_hovered = true;
-        state_guard.hover_instant = Some(std::time::Instant::now());
+        state_guard.hover_instant = Some(clock::now());
     } else if !is_on_thumb && state.read().is_hovered {
         let mut state_guard = state.write();
         state_guard.is_hovered = false;
-        state_guard.hover_instant = Some(std::time::Instant::now());
+        state_guard.hover_instant = Some(clock::now());
     }
 }
 
@@ -386,7 +412,7 @@ fn handle_state_v(
     handle_autohide_if_needed(&args, &state);
 
     // Capture current target position once to avoid locking inside helper on every call.
-    let fallback_pos = args.state.read().target_position;
+    let fallback_pos = args.state.target_position();
     let calculate_target_pos = |cursor_y: Px| -> PxPosition {
         calculate_target_pos_v(
             cursor_y,
@@ -439,7 +465,7 @@ fn handle_state_v(
         if is_on_track_v(cursor_pos, args.thickness.to_px(), track_height) {
             // Jump to the clicked position
             let new_target_pos = calculate_target_pos(cursor_pos.y);
-            args.state.write().set_target_position(new_target_pos);
+            args.state.set_target_position(new_target_pos);
         }
     }
 }
@@ -454,7 +480,7 @@ fn update_drag_horizontal(
 ) {
     if let Some(cursor_pos) = input.cursor_position_rel {
         let new_target_pos = calculate_target(cursor_pos.x);
-        args.state.write().set_target_position(new_target_pos);
+        args.state.set_target_position(new_target_pos);
         mark_scroll_activity(state, &args.scrollbar_behavior);
     } else {
         // Cursor left window: stop dragging.
@@ -473,7 +499,7 @@ fn handle_state_h(
     handle_autohide_if_needed(&args, &state);
 
     // Capture current target position once to avoid locking inside helper on every call.
-    let fallback_pos = args.state.read().target_position;
+    let fallback_pos = args.state.target_position();
     let calculate_target_pos = |cursor_x: Px| -> PxPosition {
         calculate_target_pos_h(
             cursor_x,
@@ -526,7 +552,7 @@ fn handle_state_h(
         if is_on_track_h(cursor_pos, args.thickness.to_px(), track_width) {
             // Jump to the clicked position
             let new_target_pos = calculate_target_pos(cursor_pos.x);
-            args.state.write().set_target_position(new_target_pos);
+            args.state.set_target_position(new_target_pos);
         }
     }
 }
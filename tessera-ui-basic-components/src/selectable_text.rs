@@ -0,0 +1,454 @@
+//! Read-only text that can be selected and copied.
+//!
+//! The [`selectable_text`] component lays out its text like [`text`](crate::text::text) does,
+//! but lets the user select it with the mouse: dragging selects a range, a double click
+//! selects a word and a triple click a line. Ctrl+C (Cmd+C on macOS) copies the selection
+//! and Ctrl+A selects all of the text. Selections spanning several lines are highlighted
+//! line by line, the same way the text editor highlights them.
+//!
+//! The selection lives in [`SelectableTextState`], which the application keeps alive between
+//! frames.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::Arc;
+//! use parking_lot::RwLock;
+//! use tessera_ui_basic_components::selectable_text::{
+//!     selectable_text, SelectableTextArgsBuilder, SelectableTextState,
+//! };
+//!
+//! let state = Arc::new(RwLock::new(SelectableTextState::new()));
+//!
+//! selectable_text(
+//!     SelectableTextArgsBuilder::default()
+//!         .text("Error 0x80070005: access denied")
+//!         .build()
+//!         .unwrap(),
+//!     state.clone(),
+//! );
+//! assert_eq!(state.read().selected_text(), None);
+//! ```
+use std::{sync::Arc, time::Instant};
+
+use derive_builder::Builder;
+use glyphon::{Action, Cursor, Edit, cosmic_text::Selection};
+use parking_lot::RwLock;
+use tessera_ui::{
+    Color, ComputedData, CursorEventContent, DimensionValue, Dp, PressKeyEventType, Px, PxPosition,
+    focus_state::Focus, key_chord::KeyChord, tessera, winit,
+};
+
+use crate::{
+    pipelines::{FontFeatures, TextCommand, TextConstraint, TextData, write_font_system},
+    pos_misc::is_position_in_component,
+    selection_highlight_rect::selection_highlight_rect,
+    text_edit_core::{ClickType, RectDef, compute_selection_rects, default_selection_color},
+};
+
+/// Longest time between the clicks of a double or triple click.
+const MULTI_CLICK_MS: u128 = 500;
+
+/// Farthest the clicks of a double or triple click may be apart, in pixels along both axes.
+const MULTI_CLICK_DISTANCE: u32 = 5;
+
+/// State of a [`selectable_text`]: the selection and the clicks and drag making it.
+///
+/// # Example
+/// ```
+/// use tessera_ui_basic_components::selectable_text::SelectableTextState;
+///
+/// let mut state = SelectableTextState::new();
+/// // Nothing is laid out yet, so there is nothing to select
+/// state.select_all();
+/// assert_eq!(state.selected_text(), None);
+/// ```
+pub struct SelectableTextState {
+    editor: glyphon::Editor<'static>,
+    text: String,
+    focus: Focus,
+    last_click: Option<(Instant, PxPosition)>,
+    click_count: usize,
+    is_dragging: bool,
+    selection_rects: Vec<RectDef>,
+}
+
+impl Default for SelectableTextState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SelectableTextState {
+    /// Creates a new state without a selection.
+    pub fn new() -> Self {
+        let buffer =
+            glyphon::Buffer::new(&mut write_font_system(), glyphon::Metrics::new(25.0, 30.0));
+        Self {
+            editor: glyphon::Editor::new(buffer),
+            text: String::new(),
+            focus: Focus::new(),
+            last_click: None,
+            click_count: 0,
+            is_dragging: false,
+            selection_rects: Vec::new(),
+        }
+    }
+
+    /// Returns the selected text, or `None` if nothing is selected.
+    pub fn selected_text(&self) -> Option<String> {
+        self.editor
+            .copy_selection()
+            .filter(|selected| !selected.is_empty())
+    }
+
+    /// Selects all of the text.
+    pub fn select_all(&mut self) {
+        self.editor.set_cursor(Cursor::new(0, 0));
+        self.editor
+            .set_selection(Selection::Normal(Cursor::new(0, 0)));
+        // Moving to the end extends the selection over the full text
+        self.editor.action(
+            &mut write_font_system(),
+            Action::Motion(glyphon::cosmic_text::Motion::BufferEnd),
+        );
+    }
+
+    /// Removes the selection.
+    pub fn clear_selection(&mut self) {
+        self.editor.set_selection(Selection::None);
+    }
+
+    /// Takes over the layout of `text` from the last measure. The selection is kept while the
+    /// text stays the same, e.g. when only the width changed, and dropped otherwise.
+    fn set_layout(&mut self, text: &str, buffer: &glyphon::Buffer) {
+        if self.text == text {
+            self.editor.with_buffer_mut(|own| *own = buffer.clone());
+        } else {
+            self.editor = glyphon::Editor::new(buffer.clone());
+            self.text = text.to_string();
+            self.is_dragging = false;
+        }
+    }
+
+    /// Counts a click at `position` towards a double or triple click.
+    fn click_type(&mut self, position: PxPosition, timestamp: Instant) -> ClickType {
+        let repeated = self.last_click.is_some_and(|(time, last)| {
+            let distance = (position.x - last.x).abs() + (position.y - last.y).abs();
+            timestamp.duration_since(time).as_millis() <= MULTI_CLICK_MS
+                && distance <= MULTI_CLICK_DISTANCE
+        });
+        self.click_count = if repeated {
+            self.click_count % 3 + 1
+        } else {
+            1
+        };
+        self.last_click = Some((timestamp, position));
+        match self.click_count {
+            2 => ClickType::Double,
+            3 => ClickType::Triple,
+            _ => ClickType::Single,
+        }
+    }
+}
+
+/// Arguments for the [`selectable_text`] component.
+///
+/// # Example
+/// ```
+/// use tessera_ui::Color;
+/// use tessera_ui_basic_components::selectable_text::SelectableTextArgsBuilder;
+///
+/// let args = SelectableTextArgsBuilder::default()
+///     .text("Build 2024.11.3 (a1b2c3d)")
+///     .selection_color(Color::new(1.0, 0.8, 0.0, 0.4))
+///     .build()
+///     .unwrap();
+/// assert_eq!(args.color, Color::BLACK);
+/// ```
+#[derive(Builder, Clone)]
+#[builder(pattern = "owned")]
+pub struct SelectableTextArgs {
+    /// The text to display.
+    #[builder(setter(into))]
+    pub text: String,
+    /// Color of the text.
+    #[builder(default = "Color::BLACK")]
+    pub color: Color,
+    /// Font size of the text.
+    #[builder(default = "Dp(25.0)")]
+    pub size: Dp,
+    /// Optional line height override, defaults to 1.2 × `size`.
+    #[builder(default, setter(strip_option))]
+    pub line_height: Option<Dp>,
+    /// OpenType features to shape the text with, see
+    /// [`TextArgs::font_features`](crate::text::TextArgs::font_features).
+    #[builder(default)]
+    pub font_features: FontFeatures,
+    /// Color of the selection highlight, drawn over the text. Defaults to the
+    /// [accent color](tessera_ui::theme::accent_color), translucent.
+    #[builder(default = "default_selection_color()")]
+    pub selection_color: Color,
+}
+
+impl From<String> for SelectableTextArgs {
+    fn from(val: String) -> Self {
+        SelectableTextArgsBuilder::default()
+            .text(val)
+            .build()
+            .unwrap()
+    }
+}
+
+impl From<&str> for SelectableTextArgs {
+    fn from(val: &str) -> Self {
+        SelectableTextArgsBuilder::default()
+            .text(val)
+            .build()
+            .unwrap()
+    }
+}
+
+/// Text the user can select and copy, but not edit.
+///
+/// Scroll events are left to the components around it, so the text can be placed in a
+/// [`scrollable`](crate::scrollable::scrollable).
+///
+/// # Arguments
+///
+/// - `args`: Text and styling, see [`SelectableTextArgs`].
+/// - `state`: Shared [`SelectableTextState`] holding the selection.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+/// use parking_lot::RwLock;
+/// use tessera_ui_basic_components::selectable_text::{selectable_text, SelectableTextState};
+///
+/// let state = Arc::new(RwLock::new(SelectableTextState::default()));
+/// selectable_text("Copy me: 4F2A-99C1", state);
+/// ```
+#[tessera]
+pub fn selectable_text(
+    args: impl Into<SelectableTextArgs>,
+    state: Arc<RwLock<SelectableTextState>>,
+) {
+    let args: SelectableTextArgs = args.into();
+
+    // The highlights of the selection laid out last frame, drawn over the text
+    let selection_rects = state.read().selection_rects.clone();
+    for rect in &selection_rects {
+        selection_highlight_rect(rect.width, rect.height, args.selection_color);
+    }
+
+    {
+        let state = state.clone();
+        input_handler(Box::new(move |input| {
+            state.read().focus.join_traversal();
+            let size = input.computed_data;
+            let cursor_pos = input.cursor_position_rel;
+            let is_cursor_inside =
+                cursor_pos.is_some_and(|pos| is_position_in_component(size, pos));
+            if is_cursor_inside {
+                input.requests.cursor_icon = winit::window::CursorIcon::Text;
+            }
+
+            for event in input.cursor_events.iter() {
+                match event.content {
+                    CursorEventContent::Pressed(PressKeyEventType::Left) => {
+                        let mut state = state.write();
+                        let Some(pos) = cursor_pos.filter(|_| is_cursor_inside) else {
+                            // A click elsewhere drops the selection
+                            state.clear_selection();
+                            continue;
+                        };
+                        state.focus.request_focus();
+                        let (x, y) = (pos.x.0, pos.y.0);
+                        let action = match state.click_type(pos, event.timestamp) {
+                            ClickType::Single if input.key_modifiers.shift_key() => {
+                                Action::Drag { x, y }
+                            }
+                            ClickType::Single => Action::Click { x, y },
+                            ClickType::Double => Action::DoubleClick { x, y },
+                            ClickType::Triple => Action::TripleClick { x, y },
+                        };
+                        state.editor.action(&mut write_font_system(), action);
+                        state.is_dragging = true;
+                    }
+                    CursorEventContent::Released(_) => state.write().is_dragging = false,
+                    _ => {}
+                }
+            }
+
+            // Dragging goes on outside the text, up to its edges
+            if state.read().is_dragging
+                && let Some(pos) = cursor_pos
+            {
+                let x = pos.x.0.clamp(0, size.width.0);
+                let y = pos.y.0.clamp(0, size.height.0);
+                state
+                    .write()
+                    .editor
+                    .action(&mut write_font_system(), Action::Drag { x, y });
+            }
+
+            if !state.read().focus.is_focused() {
+                return;
+            }
+            let modifiers = input.key_modifiers;
+            let clipboard = &mut *input.clipboard;
+            input.keyboard_events.retain(|event| {
+                let Some(chord) = KeyChord::from_event(event, modifiers) else {
+                    return true;
+                };
+                if !(chord.modifiers.control_key() || chord.modifiers.super_key()) {
+                    return true;
+                }
+                let winit::keyboard::Key::Character(c) = &chord.key else {
+                    return true;
+                };
+                match c.as_str() {
+                    "c" => {
+                        if let Some(selected) = state.read().selected_text() {
+                            clipboard.set_text(&selected);
+                        }
+                        false
+                    }
+                    "a" => {
+                        state.write().select_all();
+                        false
+                    }
+                    _ => true,
+                }
+            });
+        }));
+    }
+
+    measure(Box::new(move |input| {
+        let max_width: Option<Px> = match input.parent_constraint.width {
+            DimensionValue::Fixed(w) => Some(w),
+            DimensionValue::Wrap { max, .. } => max,
+            DimensionValue::Fill { max, .. } => max,
+        };
+        let line_height = args.line_height.unwrap_or(Dp(args.size.0 * 1.2));
+
+        let text_data = TextData::with_font_features(
+            args.text.clone(),
+            args.color,
+            args.size.to_pixels_f32(),
+            line_height.to_pixels_f32(),
+            TextConstraint {
+                max_width: max_width.map(|px| px.to_f32()),
+                max_height: None,
+                max_lines: None,
+            },
+            &args.font_features,
+        );
+
+        let mut state = state.write();
+        state.set_layout(&args.text, text_data.buffer());
+        // The number of highlights was decided from the rects stored last pass
+        let rects = compute_selection_rects(&state.editor, &[]);
+        for (&child_id, rect) in input.children_ids.iter().zip(&rects) {
+            input.measure_child(child_id, input.parent_constraint)?;
+            input.place_child(child_id, PxPosition::new(rect.x, rect.y));
+        }
+        state.selection_rects = rects;
+
+        let size = text_data.size;
+        input
+            .metadata_mut()
+            .push_draw_command(TextCommand { data: text_data });
+
+        Ok(ComputedData {
+            width: size[0].into(),
+            height: size[1].into(),
+        })
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Returns a state laid out with `text` on one line.
+    fn laid_out(text: &str) -> SelectableTextState {
+        let mut state = SelectableTextState::new();
+        let font_system = &mut write_font_system();
+        let mut buffer = glyphon::Buffer::new(font_system, glyphon::Metrics::new(14.0, 18.0));
+        buffer.set_text(
+            font_system,
+            text,
+            &glyphon::Attrs::new(),
+            glyphon::Shaping::Advanced,
+            None,
+        );
+        state.set_layout(text, &buffer);
+        state
+    }
+
+    /// Returns the position in the middle of the character at byte `index` of the first line.
+    fn position_of(state: &SelectableTextState, index: usize) -> PxPosition {
+        state.editor.with_buffer(|buffer| {
+            let run = buffer.layout_runs().next().unwrap();
+            let glyph = run
+                .glyphs
+                .iter()
+                .find(|glyph| glyph.start == index)
+                .unwrap();
+            PxPosition::new(
+                Px((glyph.x + glyph.w / 2.0) as i32),
+                Px((run.line_top + run.line_height / 2.0) as i32),
+            )
+        })
+    }
+
+    #[test]
+    fn test_click_type() {
+        let mut state = SelectableTextState::new();
+        let start = Instant::now();
+        let at = |x| PxPosition::new(Px(x), Px(10));
+        assert_eq!(state.click_type(at(10), start), ClickType::Single);
+        let soon = start + Duration::from_millis(200);
+        assert_eq!(state.click_type(at(12), soon), ClickType::Double);
+        let later = soon + Duration::from_millis(200);
+        assert_eq!(state.click_type(at(14), later), ClickType::Triple);
+        // A fourth click starts over
+        let last = later + Duration::from_millis(200);
+        assert_eq!(state.click_type(at(14), last), ClickType::Single);
+
+        // Too slow or too far apart
+        let slow = last + Duration::from_millis(600);
+        assert_eq!(state.click_type(at(14), slow), ClickType::Single);
+        let far = slow + Duration::from_millis(100);
+        assert_eq!(state.click_type(at(30), far), ClickType::Single);
+    }
+
+    #[test]
+    fn test_multi_click_selection() {
+        let text = "copy this word";
+        let mut state = laid_out(text);
+        let PxPosition { x, y } = position_of(&state, 7);
+        let font_system = &mut write_font_system();
+
+        let (x, y) = (x.0, y.0);
+        state
+            .editor
+            .action(font_system, Action::DoubleClick { x, y });
+        assert_eq!(state.selected_text().as_deref(), Some("this"));
+
+        state
+            .editor
+            .action(font_system, Action::TripleClick { x, y });
+        assert_eq!(state.selected_text().as_deref(), Some(text));
+
+        // Laying out the same text again keeps the selection
+        let buffer = state.editor.with_buffer(|buffer| buffer.clone());
+        state.set_layout(text, &buffer);
+        assert_eq!(state.selected_text().as_deref(), Some(text));
+        state.set_layout("other text", &buffer);
+        assert_eq!(state.selected_text(), None);
+    }
+}
@@ -18,7 +18,10 @@ use derive_builder::Builder;
 use parking_lot::RwLock;
 use tessera_ui::{
     Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp, InputHandlerInput,
-    MeasureInput, MeasurementError, Px, PxPosition, focus_state::Focus, tessera,
+    MeasureInput, MeasurementError, Px, PxPosition,
+    focus_state::Focus,
+    haptics::{self, HapticFeedback},
+    tessera,
     winit::window::CursorIcon,
 };
 
@@ -114,6 +117,7 @@ fn handle_slider_state(
     if args.disabled {
         return;
     }
+    state.read().focus.join_traversal();
 
     let is_in_component = cursor_within_component(input.cursor_position_rel, &input.computed_data);
 
@@ -173,6 +177,10 @@ fn notify_on_change(new_value: Option<f32>, args: &SliderArgs) {
     if let Some(v) = new_value
         && (v - args.value).abs() > f32::EPSILON
     {
+        // Dragging into either end of the track ticks once
+        if v == 0.0 || v == 1.0 {
+            haptics::perform(HapticFeedback::Tick);
+        }
         (args.on_change)(v);
     }
 }
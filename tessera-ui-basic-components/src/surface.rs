@@ -11,25 +11,36 @@
 //!
 //! The surface can be configured for both static and interactive scenarios, with support for
 //! hover and click callbacks, making it suitable for a wide range of UI composition needs.
+//! Changes of the style, e.g. on hover or focus, can be animated with a [`StyleTransition`].
 
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use derive_builder::Builder;
+use parking_lot::Mutex;
 use tessera_ui::{
     Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp, GestureState,
-    PressKeyEventType, Px, PxPosition, PxSize, tessera, winit::window::CursorIcon,
+    PressKeyEventType, Px, PxPosition, PxSize, clock, tessera,
+    winit::{
+        event::KeyEvent,
+        keyboard::{Key, NamedKey},
+        window::CursorIcon,
+    },
 };
 
 use crate::{
+    animation,
     padding_utils::remove_padding_from_dimension,
     pipelines::{RippleProps, ShadowProps, ShapeCommand, SimpleRectCommand},
-    pos_misc::is_position_in_component,
+    pos_misc::HitTest,
     ripple_state::RippleState,
     shape_def::Shape,
 };
 
 /// Defines the visual style of the surface (fill, outline, or both).
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum SurfaceStyle {
     /// A solid color fill.
     Filled { color: Color },
@@ -51,6 +62,158 @@ impl Default for SurfaceStyle {
     }
 }
 
+impl SurfaceStyle {
+    /// Interpolates between `self` and `other` by `t` in `[0.0, 1.0]`.
+    ///
+    /// Styles of different kinds are blended as [`SurfaceStyle::FilledOutlined`], with the
+    /// missing fill or outline fading in or out.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        if t <= 0.0 {
+            return self.clone();
+        }
+        if t >= 1.0 {
+            return other.clone();
+        }
+        let lerp_dp = |a: Dp, b: Dp| Dp(a.0 + (b.0 - a.0) * f64::from(t));
+        match (self, other) {
+            (Self::Filled { color: a }, Self::Filled { color: b }) => Self::Filled {
+                color: a.lerp(b, t),
+            },
+            (
+                Self::Outlined {
+                    color: a,
+                    width: a_width,
+                },
+                Self::Outlined {
+                    color: b,
+                    width: b_width,
+                },
+            ) => Self::Outlined {
+                color: a.lerp(b, t),
+                width: lerp_dp(*a_width, *b_width),
+            },
+            _ => {
+                let (a_fill, a_border, a_width) = self.parts();
+                let (b_fill, b_border, b_width) = other.parts();
+                // A missing fill or border is the other one's, fully transparent
+                let lerp_color = |a: Option<Color>, b: Option<Color>| match (a, b) {
+                    (Some(a), Some(b)) => a.lerp(&b, t),
+                    (Some(a), None) => a.lerp(&a.with_alpha(0.0), t),
+                    (None, Some(b)) => b.with_alpha(0.0).lerp(&b, t),
+                    (None, None) => Color::TRANSPARENT,
+                };
+                Self::FilledOutlined {
+                    fill_color: lerp_color(a_fill, b_fill),
+                    border_color: lerp_color(a_border, b_border),
+                    border_width: lerp_dp(a_width, b_width),
+                }
+            }
+        }
+    }
+
+    /// Returns the fill color, border color and border width, if the style has them.
+    fn parts(&self) -> (Option<Color>, Option<Color>, Dp) {
+        match *self {
+            Self::Filled { color } => (Some(color), None, Dp(0.0)),
+            Self::Outlined { color, width } => (None, Some(color), width),
+            Self::FilledOutlined {
+                fill_color,
+                border_color,
+                border_width,
+            } => (Some(fill_color), Some(border_color), border_width),
+        }
+    }
+}
+
+/// Animates a surface from one style to the next over a fixed duration, instead of switching
+/// at once, e.g. between its normal and hover styles.
+///
+/// It remembers the style it is animating towards, so create one per surface and pass the same
+/// one in [`SurfaceArgs::style_transition`] every frame.
+///
+/// # Example
+///
+/// ```
+/// use std::{sync::Arc, time::Duration};
+/// use tessera_ui::Color;
+/// use tessera_ui_basic_components::surface::{StyleTransition, SurfaceArgsBuilder, SurfaceStyle};
+///
+/// let transition = Arc::new(StyleTransition::new(Duration::from_millis(150)));
+/// let args = SurfaceArgsBuilder::default()
+///     .style(SurfaceStyle::Filled { color: Color::WHITE })
+///     .style_transition(transition)
+///     .build()
+///     .unwrap();
+/// ```
+pub struct StyleTransition {
+    duration: Duration,
+    animation: Mutex<Option<StyleAnimation>>,
+}
+
+struct StyleAnimation {
+    from: SurfaceStyle,
+    to: SurfaceStyle,
+    start: Instant,
+}
+
+impl StyleAnimation {
+    fn style_at(&self, now: Instant, duration: Duration) -> SurfaceStyle {
+        let progress = if duration.is_zero() {
+            1.0
+        } else {
+            now.saturating_duration_since(self.start).as_secs_f32() / duration.as_secs_f32()
+        };
+        self.from.lerp(&self.to, animation::easing(progress))
+    }
+}
+
+impl StyleTransition {
+    /// Creates a transition that takes `duration` to reach a new style.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            animation: Mutex::new(None),
+        }
+    }
+
+    /// Returns how long a change of style takes.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the style to draw now on the way to `target`.
+    ///
+    /// The first style is shown as is. A different target starts a new animation from the
+    /// style currently shown, so interrupted transitions do not jump.
+    pub fn style(&self, target: &SurfaceStyle) -> SurfaceStyle {
+        self.style_at(target, clock::now())
+    }
+
+    fn style_at(&self, target: &SurfaceStyle, now: Instant) -> SurfaceStyle {
+        let mut animation = self.animation.lock();
+        match animation.as_mut() {
+            None => {
+                *animation = Some(StyleAnimation {
+                    from: target.clone(),
+                    to: target.clone(),
+                    start: now,
+                });
+                target.clone()
+            }
+            Some(current) => {
+                if current.to != *target {
+                    *current = StyleAnimation {
+                        from: current.style_at(now, self.duration),
+                        to: target.clone(),
+                        start: now,
+                    };
+                }
+                current.style_at(now, self.duration)
+            }
+        }
+    }
+}
+
 impl From<Color> for SurfaceStyle {
     fn from(color: Color) -> Self {
         SurfaceStyle::Filled { color }
@@ -69,10 +232,25 @@ pub struct SurfaceArgs {
     #[builder(default)]
     pub hover_style: Option<SurfaceStyle>,
 
+    /// Optional style to apply while the surface is held down, from the press until the
+    /// release. Like `hover_style`, it needs `on_click` and a `RippleState`.
+    #[builder(default)]
+    pub pressed_style: Option<SurfaceStyle>,
+
+    /// Animates changes of the style, including to and from `hover_style`, instead of
+    /// switching at once.
+    #[builder(default, setter(strip_option))]
+    pub style_transition: Option<Arc<StyleTransition>>,
+
     /// Geometric outline of the surface (rounded rectangle / ellipse / capsule variants).
     #[builder(default)]
     pub shape: Shape,
 
+    /// Region that takes hover and clicks. Defaults to [`HitTest::Shape`], so the transparent
+    /// corners of rounded and elliptical surfaces do not.
+    #[builder(default)]
+    pub hit_test: HitTest,
+
     /// Optional shadow/elevation style. When present it is passed through to the shape pipeline.
     #[builder(default, setter(strip_option))]
     pub shadow: Option<ShadowProps>,
@@ -101,6 +279,18 @@ pub struct SurfaceArgs {
     #[builder(default = "Color::from_rgb(1.0, 1.0, 1.0)")]
     pub ripple_color: Color,
 
+    /// Whether a press starts the ripple animation. Without it a `RippleState` only tracks
+    /// the hover and pressed states.
+    #[builder(default = "true")]
+    pub ripple: bool,
+
+    /// Makes an interactive surface with a `RippleState` take the keyboard focus: it joins
+    /// the focus traversal, is focused by clicking it, shows `hover_style` while focused and
+    /// is clicked with Enter or Space or by [activating](tessera_ui::focus_state::activate_focused)
+    /// it.
+    #[builder(default = "false")]
+    pub focusable: bool,
+
     /// If true, all input events inside the surface bounds are blocked (stop propagation),
     /// after (optionally) handling its own click logic.
     #[builder(default = "false")]
@@ -426,8 +616,10 @@ fn compute_surface_size(
 /// * Interaction (`input_handler`) phase (only when `on_click` is `Some`):
 ///   - Tracks cursor containment
 ///   - Sets hover state on provided `RippleState`
-///   - Starts ripple animation on mouse press
+///   - Sets the pressed state from mouse press to release, for `pressed_style`
+///   - Starts ripple animation on mouse press, unless `ripple` is off
 ///   - Invokes `on_click` on mouse release inside bounds
+///   - If `focusable`, takes the focus on press and invokes `on_click` on Enter or Space
 ///   - Optionally blocks further event propagation if `block_input` is true
 /// * Non‑interactive variant only blocks events if `block_input` and cursor inside.
 ///
@@ -517,16 +709,13 @@ pub fn surface(args: SurfaceArgs, ripple_state: Option<Arc<RippleState>>, child:
             }
         };
 
-        let is_hovered = ripple_state_for_measure
+        let effective_style =
+            interaction_style(&args_measure_clone, ripple_state_for_measure.as_deref());
+        let transitioned_style = args_measure_clone
+            .style_transition
             .as_ref()
-            .map(|state| state.is_hovered())
-            .unwrap_or(false);
-
-        let effective_style = if is_hovered && args_measure_clone.hover_style.is_some() {
-            args_measure_clone.hover_style.as_ref().unwrap()
-        } else {
-            &args_measure_clone.style
-        };
+            .map(|transition| transition.style(effective_style));
+        let effective_style = transitioned_style.as_ref().unwrap_or(effective_style);
 
         let padding_px: Px = args_measure_clone.padding.into();
         let (width, height) =
@@ -559,11 +748,27 @@ pub fn surface(args: SurfaceArgs, ripple_state: Option<Arc<RippleState>>, child:
             let size = input.computed_data;
             let cursor_pos_option = input.cursor_position_rel;
             let is_cursor_in_surface = cursor_pos_option
-                .map(|pos| is_position_in_component(size, pos))
+                .map(|pos| {
+                    args_for_handler
+                        .hit_test
+                        .contains(args_for_handler.shape, size, pos)
+                })
                 .unwrap_or(false);
 
             if let Some(ref state) = state_for_handler {
                 state.set_hovered(is_cursor_in_surface);
+                if args_for_handler.focusable {
+                    state.focus.join_traversal();
+                }
+                // A release anywhere ends the press, also after dragging off the surface
+                if input.cursor_events.iter().any(|event| {
+                    matches!(
+                        event.content,
+                        CursorEventContent::Released(PressKeyEventType::Left)
+                    )
+                }) {
+                    state.set_pressed(false);
+                }
             }
 
             if is_cursor_in_surface && args_for_handler.on_click.is_some() {
@@ -598,10 +803,16 @@ pub fn surface(args: SurfaceArgs, ripple_state: Option<Arc<RippleState>>, child:
                     && let (Some(cursor_pos), Some(state)) =
                         (cursor_pos_option, state_for_handler.as_ref())
                 {
-                    let normalized_x = (cursor_pos.x.to_f32() / size.width.to_f32()) - 0.5;
-                    let normalized_y = (cursor_pos.y.to_f32() / size.height.to_f32()) - 0.5;
-
-                    state.start_animation([normalized_x, normalized_y]);
+                    state.set_pressed(true);
+                    if args_for_handler.focusable {
+                        state.focus.request_focus();
+                    }
+                    if args_for_handler.ripple {
+                        let normalized_x = (cursor_pos.x.to_f32() / size.width.to_f32()) - 0.5;
+                        let normalized_y = (cursor_pos.y.to_f32() / size.height.to_f32()) - 0.5;
+
+                        state.start_animation([normalized_x, normalized_y]);
+                    }
                 }
 
                 if !release_events.is_empty()
@@ -614,13 +825,34 @@ pub fn surface(args: SurfaceArgs, ripple_state: Option<Arc<RippleState>>, child:
                     input.block_all();
                 }
             }
+
+            if args_for_handler.focusable
+                && let Some(ref state) = state_for_handler
+                && take_keyboard_click(state, input.keyboard_events)
+                && let Some(ref on_click) = args_for_handler.on_click
+            {
+                if args_for_handler.ripple {
+                    state.start_animation([0.0, 0.0]);
+                }
+                on_click();
+            }
         }));
     } else {
+        let state_for_handler = ripple_state;
         input_handler(Box::new(move |mut input| {
+            // Without on_click the surface takes no input, e.g. while a button is disabled, so
+            // it lets go of a press, hover or focus it still had
+            if let Some(ref state) = state_for_handler {
+                state.release();
+            }
             let size = input.computed_data;
             let cursor_pos_option = input.cursor_position_rel;
             let is_cursor_in_surface = cursor_pos_option
-                .map(|pos| is_position_in_component(size, pos))
+                .map(|pos| {
+                    args_for_handler
+                        .hit_test
+                        .contains(args_for_handler.shape, size, pos)
+                })
                 .unwrap_or(false);
             if args_for_handler.block_input && is_cursor_in_surface {
                 input.block_all();
@@ -628,3 +860,152 @@ pub fn surface(args: SurfaceArgs, ripple_state: Option<Arc<RippleState>>, child:
         }));
     }
 }
+
+/// Returns the style of `args` for the hover, press and focus recorded in `ripple_state`.
+fn interaction_style<'a>(
+    args: &'a SurfaceArgs,
+    ripple_state: Option<&RippleState>,
+) -> &'a SurfaceStyle {
+    let is_hovered = ripple_state
+        .is_some_and(|state| state.is_hovered() || (args.focusable && state.focus.is_focused()));
+    let is_pressed = ripple_state.is_some_and(RippleState::is_pressed);
+    match (&args.pressed_style, &args.hover_style) {
+        (Some(pressed_style), _) if is_pressed => pressed_style,
+        (_, Some(hover_style)) if is_hovered => hover_style,
+        _ => &args.style,
+    }
+}
+
+/// Returns `true` if the surface of `state` has the focus and Enter or Space was pressed or
+/// it was [activated](tessera_ui::focus_state::activate_focused), taking the keys out of
+/// `keyboard_events`.
+fn take_keyboard_click(state: &RippleState, keyboard_events: &mut Vec<KeyEvent>) -> bool {
+    if !state.focus.is_focused() {
+        return false;
+    }
+    let key_count = keyboard_events.len();
+    keyboard_events
+        .retain(|event| !(event.state.is_pressed() && is_activation_key(&event.logical_key)));
+    let activated = state.focus.take_activation();
+    keyboard_events.len() < key_count || activated
+}
+
+/// Returns `true` for the keys that click a focused surface.
+fn is_activation_key(key: &Key) -> bool {
+    matches!(key, Key::Named(NamedKey::Enter | NamedKey::Space))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_transition() {
+        let white = SurfaceStyle::Filled {
+            color: Color::WHITE,
+        };
+        let black = SurfaceStyle::Filled {
+            color: Color::BLACK,
+        };
+        let transition = StyleTransition::new(Duration::from_millis(100));
+        let start = Instant::now();
+        assert_eq!(transition.style_at(&white, start), white);
+
+        // Halfway the eased progress is exactly one half
+        let half = SurfaceStyle::Filled {
+            color: Color::WHITE.lerp(&Color::BLACK, 0.5),
+        };
+        assert_eq!(transition.style_at(&black, start), white);
+        let halfway = start + Duration::from_millis(50);
+        assert_eq!(transition.style_at(&black, halfway), half);
+        assert_eq!(
+            transition.style_at(&black, start + Duration::from_millis(100)),
+            black
+        );
+
+        // Going back midway starts from the style shown
+        let transition = StyleTransition::new(Duration::from_millis(100));
+        transition.style_at(&white, start);
+        transition.style_at(&black, start);
+        assert_eq!(transition.style_at(&white, halfway), half);
+
+        let outline = SurfaceStyle::Outlined {
+            color: Color::BLACK,
+            width: Dp(2.0),
+        };
+        assert_eq!(
+            white.lerp(&outline, 0.5),
+            SurfaceStyle::FilledOutlined {
+                fill_color: Color::WHITE.lerp(&Color::WHITE.with_alpha(0.0), 0.5),
+                border_color: Color::BLACK.with_alpha(0.0).lerp(&Color::BLACK, 0.5),
+                border_width: Dp(1.0),
+            }
+        );
+    }
+
+    #[test]
+    fn test_interaction_style() {
+        let filled = |color| SurfaceStyle::Filled { color };
+        let args = SurfaceArgsBuilder::default()
+            .style(filled(Color::WHITE))
+            .hover_style(Some(filled(Color::GREEN)))
+            .pressed_style(Some(filled(Color::BLACK)))
+            .build()
+            .unwrap();
+        let state = RippleState::new();
+        assert_eq!(interaction_style(&args, None), &filled(Color::WHITE));
+        assert_eq!(
+            interaction_style(&args, Some(&state)),
+            &filled(Color::WHITE)
+        );
+
+        state.set_hovered(true);
+        assert_eq!(
+            interaction_style(&args, Some(&state)),
+            &filled(Color::GREEN)
+        );
+        // The press wins over the hover until the release
+        state.set_pressed(true);
+        assert_eq!(
+            interaction_style(&args, Some(&state)),
+            &filled(Color::BLACK)
+        );
+        state.set_pressed(false);
+        assert_eq!(
+            interaction_style(&args, Some(&state)),
+            &filled(Color::GREEN)
+        );
+
+        // Disabling a held down surface drops both
+        state.set_pressed(true);
+        state.release();
+        assert_eq!(
+            interaction_style(&args, Some(&state)),
+            &filled(Color::WHITE)
+        );
+    }
+
+    #[test]
+    fn test_keyboard_click() {
+        assert!(is_activation_key(&Key::Named(NamedKey::Enter)));
+        assert!(is_activation_key(&Key::Named(NamedKey::Space)));
+        assert!(!is_activation_key(&Key::Named(NamedKey::Tab)));
+        assert!(!is_activation_key(&Key::Character("a".into())));
+
+        let state = RippleState::new();
+        tessera_ui::focus_state::activate_focused();
+        assert!(!take_keyboard_click(&state, &mut Vec::new()));
+
+        // Activating the focused surface clicks it once
+        state.focus.request_focus();
+        assert!(tessera_ui::focus_state::activate_focused());
+        assert!(take_keyboard_click(&state, &mut Vec::new()));
+        assert!(!take_keyboard_click(&state, &mut Vec::new()));
+
+        // A disabled surface gives the focus up
+        state.release();
+        assert!(!state.focus.is_focused());
+        tessera_ui::focus_state::activate_focused();
+        assert!(!take_keyboard_click(&state, &mut Vec::new()));
+    }
+}
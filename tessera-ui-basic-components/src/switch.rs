@@ -25,7 +25,11 @@ use derive_builder::Builder;
 use parking_lot::RwLock;
 use tessera_ui::{
     Color, ComputedData, Constraint, CursorEventContent, DimensionValue, Dp, PressKeyEventType,
-    PxPosition, tessera, winit::window::CursorIcon,
+    PxPosition, clock,
+    haptics::{self, HapticFeedback},
+    sound::{self, Cue},
+    tessera, theme,
+    winit::window::CursorIcon,
 };
 
 use crate::{
@@ -90,7 +94,7 @@ impl SwitchState {
     /// Toggles the checked state and updates the animation timestamp.
     pub fn toggle(&mut self) {
         self.checked = !self.checked;
-        self.last_toggle_time = Some(Instant::now());
+        self.last_toggle_time = Some(clock::now());
     }
 
     /// Returns whether the switch is currently checked.
@@ -145,7 +149,9 @@ pub struct SwitchArgs {
     #[builder(default = "Color::new(0.8, 0.8, 0.8, 1.0)")]
     pub track_color: Color,
 
-    #[builder(default = "Color::new(0.6, 0.7, 0.9, 1.0)")]
+    /// Defaults to the [accent color](tessera_ui::theme::accent_color), or light blue without
+    /// one.
+    #[builder(default = "theme::accent_or(Color::new(0.6, 0.7, 0.9, 1.0))")]
     pub track_checked_color: Color,
 
     #[builder(default = "Color::WHITE")]
@@ -164,7 +170,7 @@ impl Default for SwitchArgs {
 fn update_progress_from_state(state: &Arc<RwLock<SwitchState>>) {
     let last_toggle_time = state.read().last_toggle_time;
     if let Some(last_toggle_time) = last_toggle_time {
-        let elapsed = last_toggle_time.elapsed();
+        let elapsed = clock::elapsed(last_toggle_time);
         let fraction = (elapsed.as_secs_f32() / ANIMATION_DURATION.as_secs_f32()).min(1.0);
         let checked = state.read().checked;
         state.write().progress = if checked { fraction } else { 1.0 - fraction };
@@ -203,6 +209,8 @@ fn handle_input_events_switch(
         {
             state.write().toggle();
             let new_state = state.read().checked;
+            sound::play(Cue::Toggle);
+            haptics::perform(HapticFeedback::Click);
             on_toggle(new_state);
         }
     }
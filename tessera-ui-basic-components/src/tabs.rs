@@ -47,7 +47,7 @@ use std::{
 use derive_builder::Builder;
 use parking_lot::RwLock;
 use tessera_ui::{
-    Color, ComputedData, Constraint, DimensionValue, Dp, MeasurementError, Px, PxPosition,
+    Color, ComputedData, Constraint, DimensionValue, Dp, MeasurementError, Px, PxPosition, clock,
     place_node, tessera,
 };
 
@@ -133,7 +133,7 @@ impl TabsState {
         if self.active_tab != index {
             self.prev_active_tab = self.active_tab;
             self.active_tab = index;
-            self.last_switch_time = Some(Instant::now());
+            self.last_switch_time = Some(clock::now());
             let eased_progress = animation::easing(self.progress);
             self.indicator_from_width = Px((self.indicator_from_width.0 as f32
                 + (self.indicator_to_width.0 - self.indicator_from_width.0) as f32 * eased_progress)
@@ -344,7 +344,7 @@ where
     input_handler(Box::new(move |_| {
         let last_switch_time = state_clone.read().last_switch_time;
         if let Some(last_switch_time) = last_switch_time {
-            let elapsed = last_switch_time.elapsed();
+            let elapsed = clock::elapsed(last_switch_time);
             let fraction = (elapsed.as_secs_f32() / ANIMATION_DURATION.as_secs_f32()).min(1.0);
             state_clone.write().progress = fraction;
         }
@@ -1,13 +1,13 @@
 //! Text component module for Tessera UI.
 //!
-//! This module provides the [`text`] component and its configuration types for rendering styled, single-style text within the Tessera UI framework.
+//! This module provides the [`text`] component and its configuration types for rendering styled text, in one style or mixing the styles of [`TextSpan`]s, within the Tessera UI framework.
 //! It is designed for displaying static or dynamic text content with customizable color, font size, and line height, supporting Unicode and DPI scaling.
 //!
 //! Typical use cases include labels, headings, captions, and any UI element requiring straightforward text rendering.
 //! The component is stateless and integrates with Tessera's layout and rendering systems, automatically adapting to parent constraints and device pixel density.
 //!
 //! The builder-pattern [`TextArgs`] struct allows ergonomic and flexible configuration, with sensible defaults for most properties.
-//! Conversions from `String` and `&str` are supported for convenience.
+//! Conversions from `String`, `&str` and `Vec<TextSpan>` are supported for convenience.
 //!
 //! # Examples
 //!
@@ -35,6 +35,11 @@ use tessera_ui::{Color, ComputedData, DimensionValue, Dp, Px, tessera};
 
 use crate::pipelines::{TextCommand, TextConstraint, TextData};
 
+/// OpenType features of a [`text`], see [`TextArgs::font_features`].
+pub use crate::pipelines::FontFeatures;
+/// A styled run of a [`text`], see [`TextArgs::spans`].
+pub use crate::pipelines::TextSpan;
+
 /// Configuration arguments for the `text` component.
 ///
 /// `TextArgs` defines the visual properties and content for rendering text in the Tessera UI framework.
@@ -46,6 +51,9 @@ use crate::pipelines::{TextCommand, TextConstraint, TextData};
 /// - `color`: Text color (defaults to black)
 /// - `size`: Font size in density-independent pixels (defaults to 25.0 dp)
 /// - `line_height`: Optional line height override (defaults to 1.2 × font size)
+/// - `max_lines`: Optional limit on the number of lines shown (defaults to unlimited)
+/// - `font_features`: OpenType features such as ligatures (defaults to those of the font)
+/// - `spans`: Styled runs drawn instead of `text` (defaults to none)
 ///
 /// # Builder Pattern
 ///
@@ -157,6 +165,68 @@ pub struct TextArgs {
     /// ```
     #[builder(default, setter(strip_option))]
     pub line_height: Option<Dp>,
+
+    /// Optional limit on the number of visible lines.
+    ///
+    /// Text that wraps onto more lines than this is cut off after the last allowed line,
+    /// and the component only takes up the height of the lines that are shown.
+    ///
+    /// # Example
+    /// ```
+    /// use tessera_ui_basic_components::text::TextArgsBuilder;
+    ///
+    /// let preview = TextArgsBuilder::default()
+    ///     .text("A long paragraph that should only show its first two lines...".to_string())
+    ///     .max_lines(2)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(preview.max_lines, Some(2));
+    /// ```
+    #[builder(default, setter(strip_option))]
+    pub max_lines: Option<usize>,
+
+    /// OpenType features to shape the text with.
+    ///
+    /// Defaults to none set, which keeps the defaults of the font. Turning ligatures off
+    /// keeps e.g. `fi` or `->` as separate characters in plain UI text.
+    ///
+    /// # Example
+    /// ```
+    /// use tessera_ui_basic_components::text::{FontFeatures, TextArgsBuilder};
+    ///
+    /// let label = TextArgsBuilder::default()
+    ///     .text("office -> file".to_string())
+    ///     .font_features(FontFeatures::new().with_ligatures(false))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[builder(default)]
+    pub font_features: FontFeatures,
+
+    /// Runs of text with their own styles, laid out together in place of `text`.
+    ///
+    /// Defaults to none, which draws `text` in a single style. Attributes a span leaves
+    /// unset are taken from these arguments, so spans mixing styles in a label only need to
+    /// set what differs.
+    ///
+    /// # Example
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text::{TextArgsBuilder, TextSpan};
+    ///
+    /// let price = TextArgsBuilder::default()
+    ///     .text(String::new())
+    ///     .spans(vec![
+    ///         TextSpan::new("$49").with_strikethrough(true),
+    ///         TextSpan::new(" $29")
+    ///             .with_weight(700)
+    ///             .with_color(Color::new(0.1, 0.6, 0.2, 1.0)),
+    ///     ])
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[builder(default)]
+    pub spans: Vec<TextSpan>,
 }
 
 /// Converts a [`String`] into [`TextArgs`] using the builder pattern.
@@ -195,6 +265,29 @@ impl From<&str> for TextArgs {
     }
 }
 
+/// Converts styled spans into [`TextArgs`] drawing them, see [`TextArgs::spans`].
+///
+/// # Example
+/// ```
+/// use tessera_ui_basic_components::text::{TextArgs, TextSpan};
+///
+/// let args: TextArgs = vec![
+///     TextSpan::new("Press "),
+///     TextSpan::new("Ctrl+S").with_weight(700),
+///     TextSpan::new(" to save"),
+/// ]
+/// .into();
+/// ```
+impl From<Vec<TextSpan>> for TextArgs {
+    fn from(spans: Vec<TextSpan>) -> Self {
+        TextArgsBuilder::default()
+            .text(String::new())
+            .spans(spans)
+            .build()
+            .unwrap()
+    }
+}
+
 /// Basic text component.
 ///
 /// # Example
@@ -228,15 +321,22 @@ pub fn text(args: impl Into<TextArgs>) {
 
         let line_height = text_args.line_height.unwrap_or(Dp(text_args.size.0 * 1.2));
 
-        let text_data = TextData::new(
-            text_args.text.clone(),
+        let spans = if text_args.spans.is_empty() {
+            vec![TextSpan::new(text_args.text.clone())]
+        } else {
+            text_args.spans.clone()
+        };
+        let text_data = TextData::with_spans(
+            spans,
             text_args.color,
             text_args.size.to_pixels_f32(),
             line_height.to_pixels_f32(),
             TextConstraint {
                 max_width: max_width.map(|px| px.to_f32()),
                 max_height: max_height.map(|px| px.to_f32()),
+                max_lines: text_args.max_lines,
             },
+            &text_args.font_features,
         );
 
         let size = text_data.size;
@@ -14,9 +14,48 @@
 //! Most applications should interact with [`TextEditorState`] for state management and [`text_edit_core()`]
 //! for rendering and layout within a component tree.
 
+mod auto_close;
+mod block_selection;
+mod brackets;
+mod breakpoints;
+mod change;
+mod color_scheme;
+mod comment;
+mod completion;
+mod context_menu;
 mod cursor;
+mod diagnostics;
+mod fold;
+mod go_to_line;
+mod gutter;
+mod history;
+mod indent;
+mod inlay_hint;
+mod input_handler;
+mod jump_list;
+mod keymap;
+mod language;
+mod lens_row;
+mod link;
+mod multi_cursor;
+mod position;
+mod preedit;
+mod rich_copy;
+mod ruler;
+mod save;
+mod search;
+mod spelling;
+mod structure;
+#[cfg(feature = "tree-sitter")]
+mod syntax;
+mod viewport;
+mod whitespace;
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    ops::RangeInclusive,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use glyphon::{
     Cursor, Edit,
@@ -24,19 +63,99 @@ use glyphon::{
 };
 use parking_lot::RwLock;
 use tessera_ui::{
-    Clipboard, Color, ComputedData, DimensionValue, Dp, Px, PxPosition, focus_state::Focus,
-    tessera, winit,
+    Clipboard, Color, ComputedData, DimensionValue, Dp, Px, PxPosition, PxSize, clock,
+    focus_state::Focus, key_chord::ChordMatch, speech, tessera, theme, winit,
 };
 use winit::keyboard::NamedKey;
 
 use crate::{
-    pipelines::{TextCommand, TextConstraint, TextData, write_font_system},
+    input_mask::InputMask,
+    pipelines::{FontFeatures, TextCommand, TextConstraint, TextData, write_font_system},
+    scrollable::scrollbar::{ScrollBarState, ScrollTarget},
     selection_highlight_rect::selection_highlight_rect,
-    text_edit_core::cursor::CURSOR_WIDRH,
+    surface::StyleTransition,
+    text_edit_core::{
+        auto_close::{AUTO_CLOSE_PAIRS, closer_for, shift_closers},
+        brackets::find_bracket_pair,
+        breakpoints::{breakpoint_annotations, breakpoint_menu_entries},
+        change::EditWindow,
+        comment::{shift_index, toggle_comment_edits},
+        completion::{ActiveCompletion, prefix_start},
+        context_menu::menu_entries,
+        cursor::CURSOR_WIDRH,
+        diagnostics::{UNDERLINE_HEIGHT, diagnostic_index_at, diagnostic_underline},
+        history::{Carets, DEFAULT_HISTORY_DEPTH, EditDelta, EditHistory, EditKind, EditSnapshot},
+        indent::{DEFAULT_TAB_WIDTH, dedent_len, newline_indent, spaces_to_tab_stop},
+        inlay_hint::{
+            DEFAULT_INLAY_HINT_COLOR, compute_inlay_hint_rects, hint_width, inlay_hint_label,
+            spaced_char,
+        },
+        link::{LINK_UNDERLINE_HEIGHT, find_links, find_pattern_links},
+        multi_cursor::{insert_at_carets, word_at},
+        preedit::{ComposedLayout, PREEDIT_UNDERLINE_HEIGHT, Preedit, preedit_caret},
+        save::{map_index, normalize_line},
+        search::{counter_label, find_matches, next_match, previous_match},
+        spelling::{SPELLING_COLOR, SpellChecker},
+    },
+};
+
+pub(crate) use brackets::DEFAULT_BRACKET_PAIRS;
+pub use breakpoints::Breakpoint;
+pub(crate) use breakpoints::BreakpointCommand;
+pub use change::TextChange;
+pub use color_scheme::EditorColorScheme;
+pub(crate) use completion::is_word_char;
+pub use completion::{CompletionItem, CompletionRequest};
+pub use context_menu::ContextMenuItem;
+pub(crate) use context_menu::{ContextMenuChoice, ContextMenuEntry, EditCommand};
+pub use diagnostics::{Diagnostic, DiagnosticSeverity};
+pub(crate) use gutter::LINE_NUMBER_GAP;
+pub use gutter::{GutterAnnotation, GutterMark, VisualRow};
+pub use inlay_hint::InlayHint;
+pub use input_handler::{
+    EditorInputConfig, EditorInputConfigBuilder, EditorInputHook, EditorKeyHook,
+    editor_input_handler,
 };
+pub use keymap::{EditorCommand, EditorKeymap};
+pub use language::{LANGUAGES, Language};
+pub use lens_row::LensRow;
+pub use link::LinkPattern;
+pub use multi_cursor::Caret;
+pub(crate) use multi_cursor::apply_to_carets;
+pub use position::TextPosition;
+pub use save::SaveOptions;
+pub use search::TextRange;
+#[cfg(feature = "hunspell")]
+pub use spelling::HunspellProvider;
+pub use spelling::SpellProvider;
+#[cfg(feature = "tree-sitter")]
+pub use syntax::SyntaxHighlighting;
+#[cfg(feature = "tree-sitter")]
+pub(crate) use syntax::SyntaxTree;
+
+/// Factor by which one zoom step grows or shrinks the text.
+pub(crate) const ZOOM_STEP: f32 = 1.1;
+
+/// Characters after which a new line gets an extra indent level by default.
+pub(crate) const DEFAULT_INDENT_AFTER: [char; 3] = ['{', '[', '('];
+
+/// Default color of breakpoints without a condition.
+pub(crate) const DEFAULT_BREAKPOINT_COLOR: Color = Color::new(0.85, 0.2, 0.2, 1.0);
+/// Default color of breakpoints with a condition.
+pub(crate) const DEFAULT_CONDITIONAL_BREAKPOINT_COLOR: Color = Color::new(0.95, 0.55, 0.1, 1.0);
+/// Default color of the highlight of the line a debugger stopped at.
+const DEFAULT_EXECUTION_LINE_COLOR: Color = Color::new(1.0, 0.85, 0.2, 0.3);
+
+/// Returns the default selection color: the [accent color](theme::accent_color), translucent,
+/// or light blue without one.
+pub(crate) fn default_selection_color() -> Color {
+    theme::accent_color().map_or(Color::new(0.5, 0.7, 1.0, 0.4), |accent| {
+        accent.with_alpha(0.4)
+    })
+}
 
 /// Definition of a rectangular selection highlight
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 /// Defines a rectangular region for text selection highlighting.
 ///
 /// Used internally to represent the geometry of a selection highlight in pixel coordinates.
@@ -65,6 +184,53 @@ pub enum ClickType {
     Triple,
 }
 
+/// How the editor breaks lines that are wider than the available width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    /// Lines are never broken; the content scrolls horizontally instead.
+    None,
+    /// Lines are broken between words. A word wider than the editor is broken at a glyph.
+    Word,
+    /// Lines are broken at any glyph.
+    #[default]
+    Glyph,
+}
+
+/// The font family the editor content is drawn in.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum FontFamily {
+    /// The default proportional font without serifs.
+    #[default]
+    SansSerif,
+    /// The default proportional font with serifs.
+    Serif,
+    /// The default fixed-width font, for code.
+    Monospace,
+    /// An installed or loaded font family, by name, e.g. `"Fira Code"`.
+    Name(String),
+}
+
+impl FontFamily {
+    fn to_glyphon(&self) -> glyphon::fontdb::Family<'_> {
+        match self {
+            FontFamily::SansSerif => glyphon::fontdb::Family::SansSerif,
+            FontFamily::Serif => glyphon::fontdb::Family::Serif,
+            FontFamily::Monospace => glyphon::fontdb::Family::Monospace,
+            FontFamily::Name(name) => glyphon::fontdb::Family::Name(name),
+        }
+    }
+}
+
+impl WrapMode {
+    fn to_glyphon(self) -> glyphon::Wrap {
+        match self {
+            WrapMode::None => glyphon::Wrap::None,
+            WrapMode::Word => glyphon::Wrap::WordOrGlyph,
+            WrapMode::Glyph => glyphon::Wrap::Glyph,
+        }
+    }
+}
+
 /// Core text editing state, shared between components
 /// Core state for text editing, including content, selection, cursor, and interaction state.
 ///
@@ -72,10 +238,21 @@ pub enum ClickType {
 /// It is designed to be shared between UI components via an `Arc<RwLock<TextEditorState>>`.
 pub struct TextEditorState {
     line_height: Px,
+    // Zoom
+    base_metrics: glyphon::Metrics,
+    font_size: Dp,
+    zoom: f32,
+    zoom_range: (f32, f32),
+    zoom_changed: bool,
     pub(crate) editor: glyphon::Editor<'static>,
     blink_timer: Instant,
     focus_handler: Focus,
     pub(crate) selection_color: Color,
+    /// Color of the text and of the caret, black without one.
+    text_color: Option<Color>,
+    caret_color: Option<Color>,
+    #[cfg(feature = "tree-sitter")]
+    syntax: Option<syntax::SyntaxTree>,
     pub(crate) current_selection_rects: Vec<RectDef>,
     // Click tracking for double/triple click detection
     last_click_time: Option<Instant>,
@@ -83,7 +260,137 @@ pub struct TextEditorState {
     click_count: u32,
     is_dragging: bool,
     // For IME
-    pub(crate) preedit_string: Option<String>,
+    preedit: Option<Preedit>,
+    /// The layout the editor was last drawn from while composing.
+    composed: Option<ComposedLayout>,
+    pub(crate) current_preedit_rects: Vec<RectDef>,
+    // Undo/redo
+    history: EditHistory,
+    changed_from: Option<String>,
+    // Find/replace
+    search_pattern: Option<String>,
+    search_matches: Vec<TextRange>,
+    pub(crate) search_highlight_color: Color,
+    current_match_color: Color,
+    pub(crate) current_search_rects: Vec<(RectDef, Color)>,
+    input_mask: Option<InputMask>,
+    change_text: bool,
+    read_only: bool,
+    // Multi-cursor
+    pub(crate) secondary_carets: Vec<Caret>,
+    /// Line and buffer x where the block selection being dragged started.
+    block_anchor: Option<(usize, f32)>,
+    jumps: jump_list::JumpList,
+    /// Text typed into the open go-to-line popup.
+    go_to_line: Option<String>,
+    go_to_line_enabled: bool,
+    /// Selections before each expansion and the selection it produced, newest last.
+    selection_history: Vec<(SelectionState, SelectionState)>,
+    wrap_mode: WrapMode,
+    font_family: FontFamily,
+    font_features: FontFeatures,
+    // Bracket matching
+    bracket_pairs: Vec<(char, char)>,
+    pub(crate) bracket_highlight_color: Color,
+    pub(crate) current_bracket_rects: Vec<RectDef>,
+    folds: Vec<(usize, usize)>,
+    /// Number of lines when `folds` was last known to match the content.
+    fold_line_count: usize,
+    pub(crate) fold_marker_color: Color,
+    /// The visible fold markers, with the header line of their fold.
+    pub(crate) current_fold_marker_rects: Vec<(RectDef, usize)>,
+    hovered_fold: Option<usize>,
+    pub(crate) current_line_color: Option<Color>,
+    pub(crate) current_line_rects: Vec<(RectDef, Color)>,
+    rulers: Vec<usize>,
+    ruler_color: Color,
+    overflow_color: Option<Color>,
+    /// Advance width of one column and the font size it was measured at.
+    column_width: (f32, f32),
+    pub(crate) current_ruler_rects: Vec<RectDef>,
+    // Gutter
+    gutter_annotations: Vec<GutterAnnotation>,
+    // Debugging
+    breakpoints: Vec<Breakpoint>,
+    /// Colors of breakpoints without and with a condition
+    breakpoint_colors: (Color, Color),
+    execution_line: Option<usize>,
+    execution_line_color: Color,
+    /// Line of the gutter the context menu was opened on, with whether it offers conditions
+    breakpoint_menu: Option<(usize, bool)>,
+    /// Width of the strip left of the text, zero without a gutter.
+    gutter_width: Px,
+    /// Width of the line numbers at the left of the gutter, zero without them.
+    line_number_width: Px,
+    pub(crate) current_gutter_rects: Vec<(RectDef, GutterAnnotation)>,
+    pub(crate) current_line_numbers: Vec<VisualRow>,
+    pub(crate) current_wrap_indicators: Vec<RectDef>,
+    show_whitespace: bool,
+    pub(crate) whitespace_color: Color,
+    pub(crate) current_whitespace_rects: Vec<RectDef>,
+    // Diagnostics
+    diagnostics: Vec<Diagnostic>,
+    pub(crate) current_diagnostic_rects: Vec<(RectDef, Color)>,
+    hovered_diagnostic: Option<usize>,
+    // Spell checking
+    spell_checker: Option<SpellChecker>,
+    misspellings: Vec<TextRange>,
+    /// The misspelled word the context menu was opened on, with its suggestions
+    spelling_suggestions: Option<(TextRange, Vec<String>)>,
+    /// Lines with a lens row above them and the height of the row, sorted by line
+    lens_rows: Vec<(usize, f32)>,
+    // Inlay hints
+    inlay_hints: Vec<InlayHint>,
+    inlay_hint_color: Color,
+    /// Room and left padding of each hint, with the font metrics they were measured with
+    inlay_hint_widths: Option<(glyphon::Metrics, Vec<(f32, f32)>)>,
+    pub(crate) current_inlay_hint_rects: Vec<(RectDef, String)>,
+    // Links
+    hovered_link: Option<TextRange>,
+    link_patterns: Vec<LinkPattern>,
+    link_color: Color,
+    pub(crate) current_link_rects: Vec<RectDef>,
+    save_options: SaveOptions,
+    completion: Option<ActiveCompletion>,
+    // Auto-indent
+    auto_indent: bool,
+    indent_after: Vec<char>,
+    // Auto-close
+    auto_close: bool,
+    /// Closers inserted by auto-close, which typing the same character moves over.
+    auto_closers: Vec<Cursor>,
+    tab_width: usize,
+    insert_spaces: bool,
+    line_comment: Option<String>,
+    language: Option<&'static Language>,
+    keymap: EditorKeymap,
+    /// Distance from the edge of the editor component to the text area: padding and border.
+    text_offset: Px,
+    // Overtype
+    overwrite: bool,
+    overwrite_changed: bool,
+    /// Primary caret last passed to the `on_caret_move` callback
+    reported_caret: Option<Cursor>,
+    copy_as_html: bool,
+    // Text field mode
+    single_line: bool,
+    submit_requested: bool,
+    // Context menu
+    context_menu: Option<PxPosition>,
+    context_menu_choice: Option<ContextMenuChoice>,
+    // Animation of the border and background on focus changes
+    focus_transition: Option<Arc<StyleTransition>>,
+    // Size of the whole document as of the last layout
+    content_height: Px,
+    scroll_offset: Px,
+    viewport_height: Px,
+    line_heights: Vec<f32>,
+    /// Lines kept visible above and below the caret.
+    scroll_margin: usize,
+    /// Caret and focus as of the last time the view followed the caret.
+    followed_caret: (Cursor, bool),
+    pub(crate) scrollbar_state_v: Arc<RwLock<ScrollBarState>>,
+    pub(crate) scrollbar_state_h: Arc<RwLock<ScrollBarState>>,
 }
 
 impl TextEditorState {
@@ -94,7 +401,7 @@ impl TextEditorState {
     /// * `size` - Font size in Dp.
     /// * `line_height` - Optional line height in Dp. If `None`, uses 1.2x the font size.
     pub fn new(size: Dp, line_height: Option<Dp>) -> Self {
-        Self::with_selection_color(size, line_height, Color::new(0.5, 0.7, 1.0, 0.4))
+        Self::with_selection_color(size, line_height, default_selection_color())
     }
 
     /// Creates a new `TextEditorState` with a custom selection highlight color.
@@ -105,26 +412,130 @@ impl TextEditorState {
     /// * `line_height` - Optional line height in Dp.
     /// * `selection_color` - Color used for selection highlight.
     pub fn with_selection_color(size: Dp, line_height: Option<Dp>, selection_color: Color) -> Self {
-        let final_line_height = line_height.unwrap_or(Dp(size.0 * 1.2));
-        let line_height_px: Px = final_line_height.into();
-        let mut buffer = glyphon::Buffer::new(
-            &mut write_font_system(),
-            glyphon::Metrics::new(size.to_pixels_f32(), line_height_px.to_f32()),
-        );
-        buffer.set_wrap(&mut write_font_system(), glyphon::Wrap::Glyph);
+        let metrics = font_metrics(size, line_height);
+        let line_height_px = Px(metrics.line_height as i32);
+        let mut buffer = glyphon::Buffer::new(&mut write_font_system(), metrics);
+        buffer.set_wrap(&mut write_font_system(), WrapMode::default().to_glyphon());
+        buffer.set_tab_width(&mut write_font_system(), DEFAULT_TAB_WIDTH as u16);
         let editor = glyphon::Editor::new(buffer);
         Self {
             line_height: line_height_px,
+            base_metrics: metrics,
+            font_size: size,
+            zoom: 1.0,
+            zoom_range: (0.5, 3.0),
+            zoom_changed: false,
             editor,
-            blink_timer: Instant::now(),
+            blink_timer: clock::now(),
             focus_handler: Focus::new(),
             selection_color,
+            text_color: None,
+            caret_color: None,
+            #[cfg(feature = "tree-sitter")]
+            syntax: None,
             current_selection_rects: Vec::new(),
             last_click_time: None,
             last_click_position: None,
             click_count: 0,
             is_dragging: false,
-            preedit_string: None,
+            preedit: None,
+            composed: None,
+            current_preedit_rects: Vec::new(),
+            history: EditHistory::new(DEFAULT_HISTORY_DEPTH),
+            changed_from: None,
+            search_pattern: None,
+            search_matches: Vec::new(),
+            search_highlight_color: Color::new(1.0, 0.8, 0.0, 0.35),
+            current_match_color: Color::new(1.0, 0.55, 0.0, 0.5),
+            current_search_rects: Vec::new(),
+            input_mask: None,
+            change_text: false,
+            read_only: false,
+            secondary_carets: Vec::new(),
+            jumps: jump_list::JumpList::default(),
+            go_to_line: None,
+            go_to_line_enabled: false,
+            selection_history: Vec::new(),
+            block_anchor: None,
+            wrap_mode: WrapMode::default(),
+            font_family: FontFamily::default(),
+            font_features: FontFeatures::default(),
+            bracket_pairs: DEFAULT_BRACKET_PAIRS.to_vec(),
+            bracket_highlight_color: Color::new(0.5, 0.5, 0.5, 0.35),
+            current_bracket_rects: Vec::new(),
+            folds: Vec::new(),
+            fold_line_count: 1,
+            fold_marker_color: Color::new(0.5, 0.5, 0.5, 0.5),
+            current_fold_marker_rects: Vec::new(),
+            hovered_fold: None,
+            current_line_color: None,
+            current_line_rects: Vec::new(),
+            rulers: Vec::new(),
+            ruler_color: Color::new(0.5, 0.5, 0.5, 0.25),
+            overflow_color: None,
+            column_width: (0.0, 0.0),
+            current_ruler_rects: Vec::new(),
+            gutter_annotations: Vec::new(),
+            breakpoints: Vec::new(),
+            breakpoint_colors: (
+                DEFAULT_BREAKPOINT_COLOR,
+                DEFAULT_CONDITIONAL_BREAKPOINT_COLOR,
+            ),
+            execution_line: None,
+            execution_line_color: DEFAULT_EXECUTION_LINE_COLOR,
+            breakpoint_menu: None,
+            gutter_width: Px(0),
+            line_number_width: Px(0),
+            current_gutter_rects: Vec::new(),
+            current_line_numbers: Vec::new(),
+            current_wrap_indicators: Vec::new(),
+            show_whitespace: false,
+            whitespace_color: Color::new(0.5, 0.5, 0.5, 0.5),
+            current_whitespace_rects: Vec::new(),
+            diagnostics: Vec::new(),
+            current_diagnostic_rects: Vec::new(),
+            hovered_diagnostic: None,
+            spell_checker: None,
+            misspellings: Vec::new(),
+            spelling_suggestions: None,
+            lens_rows: Vec::new(),
+            inlay_hints: Vec::new(),
+            inlay_hint_color: DEFAULT_INLAY_HINT_COLOR,
+            inlay_hint_widths: None,
+            current_inlay_hint_rects: Vec::new(),
+            hovered_link: None,
+            link_patterns: Vec::new(),
+            link_color: Color::new(0.2, 0.5, 0.95, 1.0),
+            current_link_rects: Vec::new(),
+            save_options: SaveOptions::default(),
+            completion: None,
+            auto_indent: false,
+            indent_after: DEFAULT_INDENT_AFTER.to_vec(),
+            auto_close: false,
+            auto_closers: Vec::new(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            insert_spaces: true,
+            line_comment: None,
+            language: None,
+            keymap: EditorKeymap::default(),
+            text_offset: Px(0),
+            overwrite: false,
+            overwrite_changed: false,
+            reported_caret: None,
+            copy_as_html: false,
+            single_line: false,
+            submit_requested: false,
+            context_menu: None,
+            context_menu_choice: None,
+            focus_transition: None,
+            content_height: Px(0),
+            scroll_offset: Px(0),
+            viewport_height: Px(0),
+            line_heights: Vec::new(),
+            scroll_margin: 0,
+            followed_caret: (Cursor::default(), false),
+            scrollbar_state_v: Arc::default(),
+            scrollbar_state_h: Arc::default(),
         }
     }
 
@@ -133,28 +544,389 @@ impl TextEditorState {
         self.line_height
     }
 
+    /// Returns the font size, before zooming.
+    pub fn font_size(&self) -> Dp {
+        self.font_size
+    }
+
+    /// Sets the font size and line height, which the [zoom](Self::zoom) is applied to.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Font size in Dp.
+    /// * `line_height` - Optional line height in Dp. If `None`, uses 1.2x the font size.
+    pub fn set_font_size(&mut self, size: Dp, line_height: Option<Dp>) {
+        let metrics = font_metrics(size, line_height);
+        if metrics == self.base_metrics {
+            return;
+        }
+        self.font_size = size;
+        self.base_metrics = metrics;
+        let metrics = metrics.scale(self.zoom);
+        self.line_height = Px(metrics.line_height.round() as i32);
+        self.editor.with_buffer_mut(|buffer| {
+            buffer.set_metrics(&mut write_font_system(), metrics);
+        });
+    }
+
+    /// Returns the font family of the content.
+    pub fn font_family(&self) -> &FontFamily {
+        &self.font_family
+    }
+
+    /// Sets the font family of the content.
+    ///
+    /// # Arguments
+    ///
+    /// * `family` - The new font family.
+    pub fn set_font_family(&mut self, family: FontFamily) {
+        self.font_family = family;
+        // Columns are measured in the editor font
+        self.column_width = (0.0, 0.0);
+    }
+
+    /// Returns the OpenType features the content is shaped with.
+    pub fn font_features(&self) -> &FontFeatures {
+        &self.font_features
+    }
+
+    /// Sets the OpenType features the content is shaped with, e.g. to turn the ligatures of
+    /// a code font on.
+    ///
+    /// # Arguments
+    ///
+    /// * `features` - The new features. Features left unset keep the defaults of the font.
+    pub fn set_font_features(&mut self, features: FontFeatures) {
+        self.font_features = features;
+        self.column_width = (0.0, 0.0);
+    }
+
+    /// Returns the zoom factor applied to the font size and line height.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Sets the zoom factor, clamped to [`Self::zoom_range`]. `1.0` is the size the state was
+    /// created with.
+    ///
+    /// The first visible line stays at the top of the view.
+    ///
+    /// # Arguments
+    ///
+    /// * `zoom` - The new zoom factor.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.apply_zoom(zoom, 0.0);
+    }
+
+    /// Returns the smallest and largest allowed zoom factor.
+    pub fn zoom_range(&self) -> (f32, f32) {
+        self.zoom_range
+    }
+
+    /// Sets the smallest and largest allowed zoom factor, and clamps the current zoom to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The smallest zoom factor. Defaults to `0.5`.
+    /// * `max` - The largest zoom factor. Defaults to `3.0`.
+    pub fn set_zoom_range(&mut self, min: f32, max: f32) {
+        self.zoom_range = (min.min(max), max.max(min));
+        self.apply_zoom(self.zoom, 0.0);
+    }
+
+    /// Zooms in response to user input, keeping the content at `anchor_y` pixels below the
+    /// top of the view in place.
+    pub(crate) fn zoom_at(&mut self, zoom: f32, anchor_y: f32) {
+        if self.apply_zoom(zoom, anchor_y) {
+            self.zoom_changed = true;
+        }
+    }
+
+    fn apply_zoom(&mut self, zoom: f32, anchor_y: f32) -> bool {
+        let (min, max) = self.zoom_range;
+        let zoom = zoom.clamp(min, max);
+        if zoom == self.zoom {
+            return false;
+        }
+        let ratio = zoom / self.zoom;
+        self.zoom = zoom;
+
+        let metrics = self.base_metrics.scale(zoom);
+        self.line_height = Px(metrics.line_height.round() as i32);
+        self.editor.with_buffer_mut(|buffer| {
+            let mut scroll = buffer.scroll();
+            // Offsets from the top of the first visible line scale with the text
+            scroll.vertical = (scroll.vertical + anchor_y) * ratio - anchor_y;
+            scroll.horizontal *= ratio;
+            buffer.set_metrics(&mut write_font_system(), metrics);
+            buffer.set_scroll(scroll);
+        });
+        true
+    }
+
+    /// Returns `true` once after user input, e.g. Ctrl+wheel, changed the zoom.
+    pub(crate) fn take_zoom_changed(&mut self) -> bool {
+        std::mem::take(&mut self.zoom_changed)
+    }
+
     /// Returns the current text buffer as `TextData`, applying the given layout constraints.
     ///
     /// # Arguments
     ///
     /// * `constraint` - Layout constraints for text rendering.
     pub fn text_data(&mut self, constraint: TextConstraint) -> TextData {
-        self.editor.with_buffer_mut(|buffer| {
+        // Replacing the content resets the attributes of every line, so folded lines are
+        // collapsed and text past the first ruler is recolored again before each layout.
+        self.sync_folds();
+        // Inlay hints are made room for by spacing the character before them wider, in em
+        let metrics = self.editor.with_buffer(|buffer| buffer.metrics());
+        let font_size = metrics.font_size;
+        let mut hint_spacing: Vec<(Cursor, f32)> = Vec::new();
+        for (position, (width, _)) in self.inlay_hint_layout() {
+            match hint_spacing.last_mut() {
+                Some((last, spacing)) if *last == position => *spacing += width / font_size,
+                _ => hint_spacing.push((position, width / font_size)),
+            }
+        }
+        let (folds, lens_rows) = (&self.folds, &self.lens_rows);
+        let (family, features) = (&self.font_family, &self.font_features);
+        let tab_width = self.tab_width;
+        let overflow = self
+            .overflow_color
+            .zip(self.rulers.first())
+            .map(|(color, &limit)| {
+                (
+                    editor_attrs(family, features).color(glyphon_color(color)),
+                    limit,
+                )
+            });
+        let text_attrs = match self.text_color {
+            Some(color) => editor_attrs(family, features).color(glyphon_color(color)),
+            None => editor_attrs(family, features),
+        };
+        // Only the edited part of the content is parsed again
+        #[cfg(feature = "tree-sitter")]
+        if let Some(syntax) = &mut self.syntax {
+            self.editor.with_buffer(|buffer| syntax.update(buffer));
+        }
+        #[cfg(feature = "tree-sitter")]
+        let syntax = self.syntax.as_ref();
+        // Only lines around the visible area are styled and shaped, the rest of a long
+        // document is laid out once it is scrolled into view.
+        let style_lines = |buffer: &mut glyphon::Buffer, window: std::ops::Range<usize>| {
+            let mut changed = false;
+            for (i, line) in buffer
+                .lines
+                .iter_mut()
+                .enumerate()
+                .take(window.end)
+                .skip(window.start)
+            {
+                let attrs_list = if fold::is_hidden(folds, i) {
+                    cosmic_text::AttrsList::new(&folded_attrs())
+                } else {
+                    let mut attrs_list = cosmic_text::AttrsList::new(&text_attrs);
+                    #[cfg(feature = "tree-sitter")]
+                    for (range, color) in syntax.map_or_else(Vec::new, |s| s.line_spans(i)) {
+                        attrs_list.add_span(range, &text_attrs.clone().color(glyphon_color(color)));
+                    }
+                    if let Some((attrs, limit)) = &overflow
+                        && let Some(start) = ruler::overflow_start(line.text(), *limit, tab_width)
+                    {
+                        attrs_list.add_span(start..line.text().len(), attrs);
+                    }
+                    if let Ok(row) = lens_rows.binary_search_by_key(&i, |&(line, _)| line) {
+                        lens_row::make_room(
+                            &mut attrs_list,
+                            line.text(),
+                            metrics,
+                            lens_rows[row].1,
+                        );
+                    }
+                    let first = hint_spacing.partition_point(|(position, _)| position.line < i);
+                    for &(position, spacing) in hint_spacing[first..]
+                        .iter()
+                        .take_while(|(position, _)| position.line == i)
+                    {
+                        if let Some(range) = spaced_char(line.text(), position.index) {
+                            let spaced = cosmic_text::AttrsOwned::new(
+                                &attrs_list.get_span(range.start).letter_spacing(spacing),
+                            );
+                            attrs_list.add_span(range, &spaced.as_attrs());
+                        }
+                    }
+                    attrs_list
+                };
+                changed |= line.set_attrs_list(attrs_list);
+            }
+            if changed {
+                buffer.set_redraw(true);
+            }
+        };
+        let mut styled = self.editor.with_buffer_mut(|buffer| {
             buffer.set_size(
                 &mut write_font_system(),
                 constraint.max_width,
                 constraint.max_height,
             );
-            buffer.shape_until_scroll(&mut write_font_system(), false);
+            let window = viewport::shaped_window(buffer);
+            style_lines(buffer, window.clone());
+            window
         });
+        // After the caret moved or the editor got the focus, the view follows the caret. It
+        // stays where it was scrolled to otherwise.
+        let caret = (self.editor.cursor(), self.focus_handler.is_focused());
+        if caret.0 != self.followed_caret.0 || (caret.1 && !self.followed_caret.1) {
+            let margin = self.scroll_margin;
+            self.editor
+                .with_buffer_mut(|buffer| viewport::follow_cursor(buffer, caret.0, margin));
+        }
+        self.followed_caret = caret;
+        // Shaping may scroll to the cursor, e.g. after a jump to the end of the document, so
+        // lines that came into view are styled and shaped again.
+        loop {
+            if self.wrap_mode == WrapMode::None {
+                // Shaping up to the cursor also scrolls horizontally to keep it in view.
+                self.editor.shape_as_needed(&mut write_font_system(), false);
+            } else {
+                self.editor.with_buffer_mut(|buffer| {
+                    buffer.shape_until_scroll(&mut write_font_system(), false);
+                });
+            }
+            let window = self.editor.with_buffer(viewport::shaped_window);
+            if window.start >= styled.start && window.end <= styled.end {
+                break;
+            }
+            self.editor
+                .with_buffer_mut(|buffer| style_lines(buffer, window.clone()));
+            styled = styled.start.min(window.start)..styled.end.max(window.end);
+        }
+
+        if !self.rulers.is_empty() && self.column_width.0 != font_size {
+            let metrics = self.editor.with_buffer(|buffer| buffer.metrics());
+            self.column_width = (
+                font_size,
+                ruler::measure_column_width(metrics, &self.font_family, &self.font_features),
+            );
+        }
 
-        let text_buffer = match self.editor.buffer_ref() {
-            glyphon::cosmic_text::BufferRef::Owned(buffer) => buffer.clone(),
-            glyphon::cosmic_text::BufferRef::Borrowed(buffer) => (**buffer).to_owned(),
-            glyphon::cosmic_text::BufferRef::Arc(buffer) => (**buffer).clone(),
+        let folds = &self.folds;
+        self.line_heights = self
+            .editor
+            .with_buffer_mut(|buffer| viewport::line_heights(buffer, styled, folds));
+        let (content_height, scroll_offset) = self
+            .editor
+            .with_buffer(|buffer| content_metrics(buffer, &self.line_heights));
+        self.content_height = Px(content_height.ceil() as i32);
+        self.scroll_offset = Px(scroll_offset.round() as i32);
+        self.viewport_height = match constraint.max_height {
+            Some(max_height) => Px(max_height.min(content_height).ceil() as i32),
+            None => self.content_height,
         };
 
-        TextData::from_buffer(text_buffer)
+        // A composition is shown in a copy of the buffer, never in the content itself
+        self.composed = self.preedit.as_ref().map(|preedit| {
+            ComposedLayout::new(
+                owned_buffer(&self.editor),
+                self.editor.cursor(),
+                preedit,
+                &mut write_font_system(),
+            )
+        });
+        let row_offsets = self.layout_editor().with_buffer(lens_row::row_offsets);
+        TextData::from_buffer(owned_buffer(self.layout_editor())).with_row_offsets(row_offsets)
+    }
+
+    /// Returns the height of the whole content, including lines scrolled out of view.
+    ///
+    /// Like [`Self::scroll_offset`] and [`Self::viewport_height`], this reflects the last layout
+    /// of the editor, so it lags one frame behind edits. Outer scroll containers and scrollbars
+    /// can use it to represent the length of the document rather than the visible part.
+    pub fn content_height(&self) -> Px {
+        self.content_height
+    }
+
+    /// Returns how far the content is scrolled down, from the top of the first line to the
+    /// top of the visible area.
+    pub fn scroll_offset(&self) -> Px {
+        self.scroll_offset
+    }
+
+    /// Returns the height of the visible part of the content.
+    pub fn viewport_height(&self) -> Px {
+        self.viewport_height
+    }
+
+    /// Returns how lines wider than the editor are broken.
+    pub fn wrap_mode(&self) -> WrapMode {
+        self.wrap_mode
+    }
+
+    /// Sets how lines wider than the editor are broken.
+    ///
+    /// With [`WrapMode::None`] the content scrolls horizontally to follow the cursor and can be
+    /// scrolled with a horizontal wheel or Shift+wheel.
+    ///
+    /// # Arguments
+    ///
+    /// * `wrap_mode` - The new wrap mode.
+    pub fn set_wrap_mode(&mut self, wrap_mode: WrapMode) {
+        self.wrap_mode = wrap_mode;
+        self.editor.with_buffer_mut(|buffer| {
+            buffer.set_wrap(&mut write_font_system(), wrap_mode.to_glyphon());
+            if wrap_mode != WrapMode::None {
+                let mut scroll = buffer.scroll();
+                scroll.horizontal = 0.0;
+                buffer.set_scroll(scroll);
+            }
+        });
+    }
+
+    /// Returns the width of the widest visible line, including room for the cursor at its
+    /// end, and the width of the visible area.
+    pub(crate) fn horizontal_extent(&self) -> (Px, Px) {
+        self.editor.with_buffer(|buffer| {
+            let widest = buffer
+                .layout_runs()
+                .map(|run| run.line_w)
+                .fold(0.0, f32::max)
+                + CURSOR_WIDRH.to_pixels_f32();
+            let visible = buffer.size().0.unwrap_or(widest);
+            (Px(widest.ceil() as i32), Px(visible as i32))
+        })
+    }
+
+    /// Returns how far the content is scrolled to the left, in pixels.
+    ///
+    /// This is always zero unless wrapping is off.
+    pub fn horizontal_scroll(&self) -> Px {
+        self.editor
+            .with_buffer(|buffer| Px(buffer.scroll().horizontal as i32))
+    }
+
+    /// Scrolls the content horizontally by `pixels`, clamped to the widest visible line.
+    ///
+    /// Has no effect unless wrapping is off.
+    ///
+    /// # Arguments
+    ///
+    /// * `pixels` - Distance to scroll, positive values scroll towards the end of the lines.
+    pub fn scroll_horizontally(&mut self, pixels: f32) {
+        if self.wrap_mode != WrapMode::None {
+            return;
+        }
+        self.editor.with_buffer_mut(|buffer| {
+            let widest = buffer
+                .layout_runs()
+                .map(|run| run.line_w)
+                .fold(0.0, f32::max);
+            let visible = buffer.size().0.unwrap_or(widest);
+            let mut scroll = buffer.scroll();
+            scroll.horizontal = (scroll.horizontal + pixels)
+                .min(widest + CURSOR_WIDRH.to_pixels_f32() - visible)
+                .max(0.0);
+            buffer.set_scroll(scroll);
+        });
     }
 
     /// Returns a reference to the internal focus handler.
@@ -184,7 +956,7 @@ impl TextEditorState {
 
     /// Resets the blink timer to the current instant.
     pub fn update_blink_timer(&mut self) {
-        self.blink_timer = Instant::now();
+        self.blink_timer = clock::now();
     }
 
     /// Returns the current selection highlight color.
@@ -267,6 +1039,44 @@ impl TextEditorState {
     /// Stops the current drag operation.
     pub fn stop_drag(&mut self) {
         self.is_dragging = false;
+        self.block_anchor = None;
+    }
+
+    /// Starts a block selection at `x` and `y` in the buffer, e.g. on Alt+click.
+    ///
+    /// Dragging with [`Self::extend_block_selection`] then selects the same columns on every
+    /// line up to the cursor, with a caret on each of them, so typing edits all the rows.
+    pub(crate) fn start_block_selection(&mut self, x: i32, y: i32) {
+        let Some(cursor) = self
+            .editor
+            .with_buffer(|buffer| buffer.hit(x as f32, y as f32))
+        else {
+            return;
+        };
+        self.secondary_carets.clear();
+        self.block_anchor = Some((cursor.line, x as f32));
+        self.extend_block_selection(x, y);
+    }
+
+    /// Extends the block selection being dragged to `x` and `y` in the buffer.
+    pub(crate) fn extend_block_selection(&mut self, x: i32, y: i32) {
+        let Some(anchor) = self.block_anchor else {
+            return;
+        };
+        let carets = self.editor.with_buffer(|buffer| {
+            let head = buffer.hit(x as f32, y as f32)?;
+            block_selection::block_carets(buffer, anchor, (head.line, x as f32), &self.folds)
+        });
+        if let Some((primary, others)) = carets {
+            self.editor.set_selection(primary.selection);
+            self.editor.set_cursor(primary.cursor);
+            self.secondary_carets = others;
+        }
+    }
+
+    /// Returns `true` while a block selection is being dragged.
+    pub(crate) fn is_block_selecting(&self) -> bool {
+        self.block_anchor.is_some()
     }
 
     /// Returns the last click position, if any.
@@ -283,162 +1093,3713 @@ impl TextEditorState {
         self.last_click_position = Some(position);
     }
 
-    /// Map keyboard events to text editing actions
-    /// Maps a keyboard event to a list of text editing actions for the editor.
+    /// Returns the maximum number of undo steps kept for this editor.
+    pub fn history_depth(&self) -> usize {
+        self.history.max_depth()
+    }
+
+    /// Sets the maximum number of undo steps kept for this editor.
     ///
-    /// This function translates keyboard input (including modifiers) into editing actions
-    /// such as character insertion, deletion, navigation, and clipboard operations.
+    /// Reducing the depth drops the oldest steps. A depth of `0` disables undo.
     ///
     /// # Arguments
     ///
-    /// * `key_event` - The keyboard event to map.
-    /// * `key_modifiers` - The current keyboard modifier state.
-    /// * `clipboard` - Mutable reference to the clipboard for clipboard operations.
-    ///
-    /// # Returns
+    /// * `depth` - The new maximum number of undo steps.
+    pub fn set_history_depth(&mut self, depth: usize) {
+        self.history.set_max_depth(depth);
+    }
+
+    /// Returns `true` if there is an edit that can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    /// Returns `true` if there is an undone edit that can be redone.
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Reverts the last edit, restoring the content, cursor and selection from before it.
     ///
-    /// An optional vector of `glyphon::Action` to be applied to the editor.
-    pub fn map_key_event_to_action(
-        &mut self,
-        key_event: winit::event::KeyEvent,
-        key_modifiers: winit::keyboard::ModifiersState,
-        clipboard: &mut Clipboard,
-    ) -> Option<Vec<glyphon::Action>> {
-        let editor = &mut self.editor;
+    /// Returns `true` if an edit was undone.
+    pub fn undo(&mut self) -> bool {
+        self.clear_preedit();
+        match self.history.undo() {
+            Some(step) => {
+                self.restore(|text| step.revert(text), step.before);
+                true
+            }
+            None => false,
+        }
+    }
 
-        match key_event.state {
-            winit::event::ElementState::Pressed => {}
-            winit::event::ElementState::Released => return None,
+    /// Re-applies the last undone edit.
+    ///
+    /// Returns `true` if an edit was redone.
+    pub fn redo(&mut self) -> bool {
+        self.clear_preedit();
+        match self.history.redo() {
+            Some(step) => {
+                self.restore(|text| step.apply(text), step.after);
+                true
+            }
+            None => false,
         }
+    }
 
-        match key_event.logical_key {
-            winit::keyboard::Key::Named(named_key) => match named_key {
-                NamedKey::Backspace => Some(vec![glyphon::Action::Backspace]),
-                NamedKey::Delete => Some(vec![glyphon::Action::Delete]),
-                NamedKey::Enter => Some(vec![glyphon::Action::Enter]),
-                NamedKey::Escape => Some(vec![glyphon::Action::Escape]),
-                NamedKey::Tab => Some(vec![glyphon::Action::Insert(' '); 4]),
-                NamedKey::ArrowLeft => {
-                    if key_modifiers.control_key() {
-                        editor.set_selection(Selection::None);
+    /// Clears the undo and redo history, e.g. after loading a new document.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
 
-                        Some(vec![glyphon::Action::Motion(cosmic_text::Motion::LeftWord)])
-                    } else {
-                        // if we have selected text, we need to clear it and not perform any action
-                        if editor.selection_bounds().is_some() {
-                            editor.set_selection(Selection::None);
+    /// Captures the current content, cursor and selection for the history, ending any
+    /// composition without committing it first, as every edit does.
+    pub(crate) fn snapshot(&mut self) -> EditSnapshot {
+        EditSnapshot {
+            text: editor_content(&self.editor),
+            cursor: self.editor.cursor(),
+            selection: self.editor.selection(),
+        }
+    }
 
-                            return None;
-                        }
+    /// Returns the cursor and selection of the primary caret.
+    pub(crate) fn carets(&self) -> Carets {
+        Carets {
+            cursor: self.editor.cursor(),
+            selection: self.editor.selection(),
+        }
+    }
 
-                        Some(vec![glyphon::Action::Motion(cosmic_text::Motion::Left)])
-                    }
-                }
-                NamedKey::ArrowRight => {
-                    if key_modifiers.control_key() {
-                        editor.set_selection(Selection::None);
+    /// Returns the lines holding a caret or the anchor of its selection, which are the ones
+    /// an action at the carets edits.
+    pub(crate) fn caret_lines(&self) -> RangeInclusive<usize> {
+        let primary = Caret {
+            cursor: self.editor.cursor(),
+            selection: self.editor.selection(),
+        };
+        let lines = std::iter::once(primary)
+            .chain(self.secondary_carets.iter().copied())
+            .flat_map(|caret| {
+                let anchor = match caret.selection {
+                    Selection::Normal(c) | Selection::Line(c) | Selection::Word(c) => c,
+                    Selection::None => caret.cursor,
+                };
+                [caret.cursor.line, anchor.line]
+            });
+        let (first, last) = lines.fold((usize::MAX, 0), |(first, last), line| {
+            (first.min(line), last.max(line))
+        });
+        first..=last
+    }
 
-                        Some(vec![glyphon::Action::Motion(
-                            cosmic_text::Motion::RightWord,
-                        )])
-                    } else {
-                        if editor.selection_bounds().is_some() {
-                            editor.set_selection(Selection::None);
+    /// Describes `edit` for the `on_change` callback, with the whole content if
+    /// [`Self::set_change_text`] asks for it.
+    pub(crate) fn text_change(&self, edit: &EditDelta) -> TextChange {
+        let mut change =
+            TextChange::from_edit(edit, cursor_to_offset(&self.editor, self.editor.cursor()));
+        if self.change_text {
+            change.text = Some(editor_content(&self.editor));
+        }
+        change
+    }
 
-                            return None;
-                        }
+    /// Records `edit` of `kind`, made with the carets at `before` while the content had
+    /// `old_line_count` lines, in the history. Without an edit the action only moved the
+    /// carets, which ends the current group without adding a step.
+    pub(crate) fn record_edit(
+        &mut self,
+        edit: Option<EditDelta>,
+        before: Carets,
+        old_line_count: usize,
+        kind: EditKind,
+    ) {
+        let Some(edit) = edit else {
+            self.history.break_group();
+            return;
+        };
+        let cursor_after = self.editor.cursor();
+        let edited_lines = {
+            let anchor = match before.selection {
+                Selection::Normal(c) | Selection::Line(c) | Selection::Word(c) => c.line,
+                Selection::None => before.cursor.line,
+            };
+            (
+                anchor.min(before.cursor.line),
+                anchor.max(before.cursor.line),
+            )
+        };
+        self.history.record(edit, before, kind, cursor_after);
+        self.refresh_search();
 
-                        Some(vec![glyphon::Action::Motion(cosmic_text::Motion::Right)])
-                    }
-                }
-                NamedKey::ArrowUp => {
-                    // if we are on the first line, we move the cursor to the beginning of the line
-                    if editor.cursor().line == 0 {
-                        editor.set_cursor(Cursor::new(0, 0));
+        let line_count = self.editor.with_buffer(|buffer| buffer.lines.len());
+        if line_count != old_line_count {
+            // Joining lines also touches the line before the edit
+            fold::shift_folds(
+                &mut self.folds,
+                edited_lines.0.saturating_sub(1),
+                edited_lines.1,
+                line_count as isize - old_line_count as isize,
+            );
+        }
+        self.fold_line_count = line_count;
+    }
 
-                        return None;
-                    }
+    /// Records the change of the content since `before` was taken as one undo step that
+    /// can't be merged with others, leaving the cursor at `cursor_after`.
+    fn record_change(&mut self, before: EditSnapshot, cursor_after: Cursor) {
+        if let Some(edit) = EditDelta::between(&before.text, &editor_content(&self.editor)) {
+            self.history
+                .record(edit, before.carets(), EditKind::Other, cursor_after);
+        }
+    }
 
-                    Some(vec![glyphon::Action::Motion(cosmic_text::Motion::Up)])
-                }
-                NamedKey::ArrowDown => {
-                    let last_line_index =
-                        editor.with_buffer(|buffer| buffer.lines.len().saturating_sub(1));
+    /// Returns the content as it was before the state itself changed it, e.g. by
+    /// [`Self::undo`] or [`Self::replace_all`] rather than by an input action, once after the
+    /// change.
+    pub(crate) fn take_content_changed(&mut self) -> Option<String> {
+        self.changed_from.take()
+    }
 
-                    // if we are on the last line, we move the cursor to the end of the line
-                    if editor.cursor().line >= last_line_index {
-                        let last_col =
-                            editor.with_buffer(|buffer| buffer.lines[last_line_index].text().len());
+    /// Returns `true` if one of the `take_*_changed` methods has a change to report, or
+    /// [`Self::take_caret_moved`] a move if `caret` is set, so the input handler only locks
+    /// the state for writing when it has something to take.
+    pub(crate) fn has_pending_changes(&self, caret: bool) -> bool {
+        self.changed_from.is_some()
+            || self.zoom_changed
+            || self.overwrite_changed
+            || (caret
+                && self.preedit.is_none()
+                && self.reported_caret != Some(self.editor.cursor()))
+    }
 
-                        editor.set_cursor(Cursor::new(last_line_index, last_col));
-                        return None;
-                    }
+    /// Remembers `before` as the content to report the pending changes against.
+    fn mark_changed(&mut self, before: &str) {
+        if self.changed_from.is_none() {
+            self.changed_from = Some(before.to_string());
+        }
+    }
 
-                    Some(vec![glyphon::Action::Motion(cosmic_text::Motion::Down)])
-                }
-                NamedKey::Home => Some(vec![glyphon::Action::Motion(cosmic_text::Motion::Home)]),
-                NamedKey::End => Some(vec![glyphon::Action::Motion(cosmic_text::Motion::End)]),
-                NamedKey::Space => Some(vec![glyphon::Action::Insert(' ')]),
-                _ => None,
-            },
+    /// Rewrites the content with `edit` and puts the carets at `carets`, for undo and redo.
+    fn restore(&mut self, edit: impl FnOnce(&mut String), carets: Carets) {
+        let before = editor_content(&self.editor);
+        let mut text = before.clone();
+        edit(&mut text);
+        self.mark_changed(&before);
+        self.set_text_reactive(&text);
+        // The content may end in a line break without an empty line after it, which a
+        // caret of the history may still be on
+        let cursor = self.clamped_cursor(carets.cursor);
+        self.editor.set_cursor(cursor);
+        let selection = match carets.selection {
+            Selection::None => Selection::None,
+            Selection::Normal(anchor) => Selection::Normal(self.clamped_cursor(anchor)),
+            Selection::Line(anchor) => Selection::Line(self.clamped_cursor(anchor)),
+            Selection::Word(anchor) => Selection::Word(self.clamped_cursor(anchor)),
+        };
+        self.editor.set_selection(selection);
+        self.secondary_carets.clear();
+        self.refresh_search();
+    }
+
+    /// Searches the content for `pattern` and returns all matches.
+    ///
+    /// The search is literal and case-sensitive, and matches do not span lines. The matches
+    /// are kept up to date as the content changes and are highlighted with the search
+    /// highlight color until [`Self::clear_search`] is called or an empty pattern is searched.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The text to search for.
+    pub fn find(&mut self, pattern: &str) -> &[TextRange] {
+        self.search_pattern = (!pattern.is_empty()).then(|| pattern.to_string());
+        self.refresh_search();
+        &self.search_matches
+    }
+
+    /// Returns the matches of the last [`Self::find`].
+    pub fn search_matches(&self) -> &[TextRange] {
+        &self.search_matches
+    }
+
+    /// Returns the pattern of the last [`Self::find`], or `None` while no search is active.
+    pub fn search_pattern(&self) -> Option<&str> {
+        self.search_pattern.as_deref()
+    }
+
+    /// Returns the index in [`Self::search_matches`] of the match that is selected, e.g. by
+    /// [`Self::find_next`].
+    pub fn current_match(&self) -> Option<usize> {
+        let (start, end) = self.editor.selection_bounds()?;
+        self.search_matches
+            .iter()
+            .position(|&range| range == TextRange { start, end })
+    }
+
+    /// Returns the text of the search counter shown in the editor, e.g. `3 of 12`, or `None`
+    /// while no search is active.
+    pub fn search_counter(&self) -> Option<String> {
+        self.search_pattern
+            .as_ref()
+            .map(|_| counter_label(self.current_match(), self.search_matches.len()))
+    }
+
+    /// Selects the next match after the cursor, wrapping around at the end of the content.
+    ///
+    /// Returns the selected match, or `None` if there are no matches.
+    pub fn find_next(&mut self) -> Option<TextRange> {
+        let found = next_match(&self.search_matches, self.editor.cursor())?;
+        self.select_match(found);
+        Some(found)
+    }
+
+    /// Selects the previous match before the cursor, wrapping around at the start of the content.
+    ///
+    /// Returns the selected match, or `None` if there are no matches.
+    pub fn find_previous(&mut self) -> Option<TextRange> {
+        let found = previous_match(&self.search_matches, self.editor.cursor())?;
+        self.select_match(found);
+        Some(found)
+    }
+
+    /// Replaces the currently selected match with `replacement` and selects the next match.
+    ///
+    /// If the selection is not a match, this only moves to the next match, so repeated calls
+    /// step through the content like a typical "Replace" button.
+    ///
+    /// Returns `true` if a replacement was made.
+    ///
+    /// # Arguments
+    ///
+    /// * `replacement` - The text to insert in place of the match.
+    pub fn replace(&mut self, replacement: &str) -> bool {
+        let selected = self
+            .editor
+            .selection_bounds()
+            .map(|(start, end)| TextRange { start, end });
+        let is_match = selected.is_some_and(|range| self.search_matches.contains(&range));
+
+        if is_match {
+            let before = self.snapshot();
+            self.editor.delete_selection();
+            let at = self.editor.cursor();
+            let cursor = insert_text_at(&mut self.editor, at, replacement);
+            self.editor.set_cursor(cursor);
+            self.mark_changed(&before.text);
+            self.record_change(before, self.editor.cursor());
+            self.refresh_search();
+        }
+
+        self.find_next();
+        is_match
+    }
+
+    /// Replaces every match with `replacement` as a single undo step.
+    ///
+    /// Returns the number of replacements made.
+    ///
+    /// # Arguments
+    ///
+    /// * `replacement` - The text to insert in place of each match.
+    pub fn replace_all(&mut self, replacement: &str) -> usize {
+        let count = self.search_matches.len();
+        let Some(pattern) = self.search_pattern.clone() else {
+            return 0;
+        };
+        if count == 0 {
+            return 0;
+        }
+
+        let before = self.snapshot();
+        let new_text = before.text.replace(&pattern, replacement);
+        self.set_text_reactive(&new_text);
+        self.mark_changed(&before.text);
+        self.record_change(before, self.editor.cursor());
+        self.refresh_search();
+        count
+    }
+
+    /// Replaces the whole content with `text` as a single undo step, e.g. to restore a
+    /// [recovered](crate::recovery::RecoveredBuffer) version of it. The cursor is kept where
+    /// it was, moved back onto the new content if needed.
+    pub fn replace_content(&mut self, text: &str) {
+        let before = self.snapshot();
+        if before.text == text {
+            return;
+        }
+        self.set_text_reactive(text);
+        let cursor = self.clamped_cursor(before.cursor);
+        self.editor.set_cursor(cursor);
+        self.editor.set_selection(Selection::None);
+        self.secondary_carets.clear();
+        self.mark_changed(&before.text);
+        self.record_change(before, cursor);
+        self.refresh_search();
+    }
+
+    /// Stops highlighting search matches.
+    pub fn clear_search(&mut self) {
+        self.search_pattern = None;
+        self.search_matches.clear();
+    }
+
+    /// Returns the color used to highlight search matches.
+    pub fn search_highlight_color(&self) -> Color {
+        self.search_highlight_color
+    }
+
+    /// Sets the color used to highlight search matches.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new search highlight color.
+    pub fn set_search_highlight_color(&mut self, color: Color) {
+        self.search_highlight_color = color;
+    }
+
+    /// Returns the color used to highlight the [current match](Self::current_match).
+    pub fn current_match_color(&self) -> Color {
+        self.current_match_color
+    }
+
+    /// Sets the color used to highlight the [current match](Self::current_match), beneath the
+    /// selection.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new current match color.
+    pub fn set_current_match_color(&mut self, color: Color) {
+        self.current_match_color = color;
+    }
+
+    /// Returns the color of the highlight behind the line of the cursor, if it is highlighted.
+    pub fn current_line_color(&self) -> Option<Color> {
+        self.current_line_color
+    }
+
+    /// Sets the color of the full-width highlight behind the line of the cursor, shown while
+    /// the editor has focus, or `None` to turn the highlight off.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new current line color.
+    pub fn set_current_line_color(&mut self, color: Option<Color>) {
+        self.current_line_color = color;
+    }
+
+    /// Returns the color of the text, or `None` for the default black.
+    pub fn text_color(&self) -> Option<Color> {
+        self.text_color
+    }
+
+    /// Sets the color of the text, or goes back to black with `None`.
+    pub fn set_text_color(&mut self, color: Option<Color>) {
+        self.text_color = color;
+    }
+
+    /// Returns the color of the carets, or `None` for the default black.
+    pub fn caret_color(&self) -> Option<Color> {
+        self.caret_color
+    }
+
+    /// Sets the color of the carets, or goes back to black with `None`.
+    pub fn set_caret_color(&mut self, color: Option<Color>) {
+        self.caret_color = color;
+    }
+
+    /// Returns the grammar the content is highlighted with, if any.
+    #[cfg(feature = "tree-sitter")]
+    pub fn syntax_highlighting(&self) -> Option<&Arc<SyntaxHighlighting>> {
+        self.syntax.as_ref().map(syntax::SyntaxTree::highlighting)
+    }
+
+    /// Highlights the content with a tree-sitter grammar, or turns highlighting off with
+    /// `None`. The content is parsed again as a whole.
+    #[cfg(feature = "tree-sitter")]
+    pub fn set_syntax_highlighting(&mut self, highlighting: Option<Arc<SyntaxHighlighting>>) {
+        self.syntax = highlighting.map(syntax::SyntaxTree::new);
+    }
+
+    /// Returns the bracket pairs that are matched around the cursor.
+    pub fn bracket_pairs(&self) -> &[(char, char)] {
+        &self.bracket_pairs
+    }
+
+    /// Sets the bracket pairs that are matched around the cursor, as `(open, close)`.
+    ///
+    /// Defaults to `()`, `[]` and `{}`. An empty list turns bracket matching off.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairs` - The new bracket pairs.
+    pub fn set_bracket_pairs(&mut self, pairs: Vec<(char, char)>) {
+        self.bracket_pairs = pairs;
+    }
+
+    /// Returns the color used to highlight a matching bracket pair.
+    pub fn bracket_highlight_color(&self) -> Color {
+        self.bracket_highlight_color
+    }
+
+    /// Sets the color used to highlight a matching bracket pair.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new bracket highlight color.
+    pub fn set_bracket_highlight_color(&mut self, color: Color) {
+        self.bracket_highlight_color = color;
+    }
+
+    /// Returns the bracket next to the cursor and its matching bracket, if there is one.
+    ///
+    /// The bracket directly after the cursor takes precedence over the one before it.
+    pub fn matching_brackets(&self) -> Option<(TextRange, TextRange)> {
+        let cursor = self.editor.cursor();
+        self.editor.with_buffer(|buffer| {
+            let lines: Vec<&str> = buffer.lines.iter().map(|line| line.text()).collect();
+            find_bracket_pair(&lines, cursor, &self.bracket_pairs)
+        })
+    }
+
+    /// Returns the folded regions as `(header, last)` line pairs, sorted by header.
+    ///
+    /// The header line of a fold stays visible with a marker after it, the lines after it up
+    /// to and including `last` are hidden.
+    pub fn folds(&self) -> &[(usize, usize)] {
+        &self.folds
+    }
+
+    /// Returns `true` if `line` is hidden inside a folded region.
+    pub fn is_line_hidden(&self, line: usize) -> bool {
+        fold::is_hidden(&self.folds, line)
+    }
+
+    /// Folds the lines after `header` up to and including `last`.
+    ///
+    /// A fold that already starts at `header` is replaced. Carets on the hidden lines move to
+    /// the end of the header line. Edits that add or remove lines inside a fold open it.
+    ///
+    /// Returns `false` if the range is empty or reaches past the last line.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The line that stays visible.
+    /// * `last` - The last line to hide.
+    pub fn fold_lines(&mut self, header: usize, last: usize) -> bool {
+        self.sync_folds();
+        if last <= header || last >= self.fold_line_count {
+            return false;
+        }
+        self.folds.retain(|&(h, _)| h != header);
+        let at = self.folds.partition_point(|&(h, _)| h < header);
+        self.folds.insert(at, (header, last));
+
+        let folds = &self.folds;
+        self.secondary_carets
+            .retain(|caret| !fold::is_hidden(folds, caret.cursor.line));
+        let cursor = self.editor.cursor();
+        if self.is_line_hidden(cursor.line) {
+            let header = self.visible_line_before(cursor.line);
+            let end = self.line_len(header);
+            self.editor.set_selection(Selection::None);
+            self.editor.set_cursor(Cursor::new(header, end));
+        }
+        true
+    }
+
+    /// Folds the lines after `line` that are indented deeper than it.
+    ///
+    /// Returns `false` if the line does not start an indented block.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The header line of the block.
+    pub fn fold_indented(&mut self, line: usize) -> bool {
+        let last = self.editor.with_buffer(|buffer| {
+            let lines: Vec<&str> = buffer.lines.iter().map(|line| line.text()).collect();
+            fold::indented_block_end(&lines, line, self.tab_width)
+        });
+        last.is_some_and(|last| self.fold_lines(line, last))
+    }
+
+    /// Opens the fold that starts at `line` and every fold hiding `line`.
+    ///
+    /// Returns `true` if a fold was opened.
+    pub fn unfold(&mut self, line: usize) -> bool {
+        let count = self.folds.len();
+        self.folds.retain(|&(header, _)| header != line);
+        self.reveal_line(line);
+        self.folds.len() != count
+    }
+
+    /// Opens all folds.
+    pub fn unfold_all(&mut self) {
+        self.folds.clear();
+    }
+
+    /// Opens the fold that starts at `line`, or folds its indented block if there is none.
+    ///
+    /// Returns `true` if a fold was opened or closed.
+    pub fn toggle_fold(&mut self, line: usize) -> bool {
+        if self.folds.iter().any(|&(header, _)| header == line) {
+            self.unfold(line)
+        } else {
+            self.fold_indented(line)
+        }
+    }
+
+    /// Returns the fold whose marker the mouse pointer is over, as a `(header, last)` pair.
+    pub fn hovered_fold(&self) -> Option<(usize, usize)> {
+        let header = self.hovered_fold?;
+        self.folds.iter().copied().find(|&(h, _)| h == header)
+    }
+
+    /// Returns the header line of the fold whose marker is at `position`, relative to the
+    /// text area.
+    pub(crate) fn fold_marker_at(&self, position: PxPosition) -> Option<usize> {
+        self.current_fold_marker_rects
+            .iter()
+            .find(|(rect, _)| {
+                position.x >= rect.x
+                    && position.x < rect.x + rect.width
+                    && position.y >= rect.y
+                    && position.y < rect.y + rect.height
+            })
+            .map(|&(_, header)| header)
+    }
+
+    /// Updates the fold marker under the mouse pointer, given the pointer position relative to
+    /// the text area. Returns `true` if it changed.
+    pub(crate) fn update_hovered_fold(&mut self, position: Option<PxPosition>) -> bool {
+        let hovered = position.and_then(|pos| self.fold_marker_at(pos));
+        let changed = hovered != self.hovered_fold;
+        self.hovered_fold = hovered;
+        changed
+    }
+
+    /// Returns the hidden lines of the hovered fold, cut off after `max_lines` lines.
+    pub(crate) fn fold_preview(&self, max_lines: usize) -> Option<String> {
+        let (header, last) = self.hovered_fold()?;
+        let mut preview = self.editor.with_buffer(|buffer| {
+            let lines = buffer
+                .lines
+                .get(header + 1..=last.min(header + max_lines))?;
+            Some(
+                lines
+                    .iter()
+                    .map(|line| line.text())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        })?;
+        if last - header > max_lines {
+            preview.push_str("\n…");
+        }
+        Some(preview)
+    }
+
+    /// Returns where the preview of the hovered fold is placed relative to the text area:
+    /// below the header line, at the start of the marker.
+    pub(crate) fn fold_preview_anchor(&self) -> Option<PxPosition> {
+        let header = self.hovered_fold?;
+        let (rect, _) = self
+            .current_fold_marker_rects
+            .iter()
+            .find(|&&(_, h)| h == header)?;
+        // The marker sits a quarter line below the top of the header line
+        Some(PxPosition::new(
+            rect.x,
+            rect.y - rect.height / 2 + self.line_height,
+        ))
+    }
+
+    /// Returns the color of the marker drawn after the header line of a fold.
+    pub fn fold_marker_color(&self) -> Color {
+        self.fold_marker_color
+    }
+
+    /// Sets the color of the marker drawn after the header line of a fold.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new fold marker color.
+    pub fn set_fold_marker_color(&mut self, color: Color) {
+        self.fold_marker_color = color;
+    }
+
+    /// Moves the cursor off the hidden lines it was moved onto by `action`.
+    ///
+    /// Vertical motions are repeated so the cursor keeps its column, other motions jump past
+    /// the fold in the direction the cursor moved from `before`.
+    pub(crate) fn skip_folded_lines(&mut self, before: Cursor, action: &glyphon::Action) {
+        if let glyphon::Action::Motion(
+            cosmic_text::Motion::Up
+            | cosmic_text::Motion::Down
+            | cosmic_text::Motion::PageUp
+            | cosmic_text::Motion::PageDown,
+        ) = action
+        {
+            while self.is_line_hidden(self.editor.cursor().line) {
+                let cursor = self.editor.cursor();
+                self.editor.action(&mut write_font_system(), *action);
+                if self.editor.cursor() == cursor {
+                    break;
+                }
+            }
+        }
+
+        let cursor = self.editor.cursor();
+        if !self.is_line_hidden(cursor.line) {
+            return;
+        }
+        let forward = (cursor.line, cursor.index) > (before.line, before.index);
+        let mut next = cursor.line;
+        while self.is_line_hidden(next) {
+            next += 1;
+        }
+        let target = if forward && next < self.fold_line_count {
+            Cursor::new(next, 0)
+        } else {
+            let header = self.visible_line_before(cursor.line);
+            Cursor::new(header, self.line_len(header))
+        };
+        self.editor.set_cursor(target);
+    }
+
+    /// Forgets the folds if the number of lines changed without [`Self::record_edit`] seeing
+    /// it, e.g. when the content was replaced.
+    fn sync_folds(&mut self) {
+        let line_count = self.editor.with_buffer(|buffer| buffer.lines.len());
+        if line_count != self.fold_line_count {
+            self.folds.clear();
+            self.fold_line_count = line_count;
+        }
+    }
+
+    /// Opens every fold hiding `line`.
+    fn reveal_line(&mut self, line: usize) {
+        self.folds
+            .retain(|&(header, last)| !(header < line && line <= last));
+    }
+
+    /// Returns the closest line at or before `line` that is not hidden.
+    fn visible_line_before(&self, mut line: usize) -> usize {
+        while line > 0 && self.is_line_hidden(line) {
+            line -= 1;
+        }
+        line
+    }
+
+    fn line_len(&self, line: usize) -> usize {
+        self.editor
+            .with_buffer(|buffer| buffer.lines.get(line).map_or(0, |l| l.text().len()))
+    }
+
+    /// Returns the columns at which ruler lines are drawn, in ascending order.
+    pub fn rulers(&self) -> &[usize] {
+        &self.rulers
+    }
+
+    /// Sets the columns at which vertical ruler lines are drawn behind the text, e.g. a
+    /// maximum line length of 80 or 100.
+    ///
+    /// Columns are as wide as the digit `0` in the editor font, so rulers line up with the
+    /// text in monospace fonts. An empty list removes the rulers.
+    ///
+    /// # Arguments
+    ///
+    /// * `columns` - The ruler columns, in any order.
+    pub fn set_rulers(&mut self, mut columns: Vec<usize>) {
+        columns.sort_unstable();
+        columns.dedup();
+        self.rulers = columns;
+    }
+
+    /// Returns the color of the ruler lines.
+    pub fn ruler_color(&self) -> Color {
+        self.ruler_color
+    }
+
+    /// Sets the color of the ruler lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new ruler color.
+    pub fn set_ruler_color(&mut self, color: Color) {
+        self.ruler_color = color;
+    }
+
+    /// Returns the color of text beyond the first ruler, if it is recolored.
+    pub fn overflow_color(&self) -> Option<Color> {
+        self.overflow_color
+    }
+
+    /// Sets the color of text beyond the first ruler, or `None` to draw it like other text.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new overflow color.
+    pub fn set_overflow_color(&mut self, color: Option<Color>) {
+        self.overflow_color = color;
+    }
+
+    /// Computes the ruler lines for a text area of the given size, scrolled like the text.
+    pub(crate) fn ruler_rects(&self, width: Px, height: Px) -> Vec<RectDef> {
+        let scroll_x = self.horizontal_scroll();
+        self.rulers
+            .iter()
+            .filter_map(|&column| {
+                let x = Px((column as f32 * self.column_width.1).round() as i32) - scroll_x;
+                (x >= Px(0) && x < width).then_some(RectDef {
+                    x,
+                    y: Px(0),
+                    width: ruler::RULER_WIDTH,
+                    height,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the marks drawn in the gutter.
+    pub fn gutter_annotations(&self) -> &[GutterAnnotation] {
+        &self.gutter_annotations
+    }
+
+    /// Sets the marks drawn in the gutter, e.g. breakpoints or changed lines. Marks of lines
+    /// that do not exist are not drawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `annotations` - One mark per entry, several may share a line.
+    pub fn set_gutter_annotations(&mut self, annotations: Vec<GutterAnnotation>) {
+        self.gutter_annotations = annotations;
+    }
+
+    /// Returns the breakpoints, in the order they were added.
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Replaces the breakpoints, e.g. with the ones a debugger confirmed. Breakpoints of lines
+    /// that do not exist are not drawn.
+    ///
+    /// Lines are not moved by later edits, so the owner should send fresh breakpoints after
+    /// the content changes.
+    pub fn set_breakpoints(&mut self, breakpoints: Vec<Breakpoint>) {
+        self.breakpoints = breakpoints;
+    }
+
+    /// Returns the breakpoint of `line`, if any.
+    pub fn breakpoint_at(&self, line: usize) -> Option<&Breakpoint> {
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| breakpoint.line == line)
+    }
+
+    /// Removes the breakpoint of `line`, or adds an enabled one without a condition if there
+    /// is none, as clicking the gutter does in breakpoint mode. Returns `true` if a breakpoint
+    /// was added.
+    pub fn toggle_breakpoint(&mut self, line: usize) -> bool {
+        let count = self.breakpoints.len();
+        self.breakpoints
+            .retain(|breakpoint| breakpoint.line != line);
+        if self.breakpoints.len() == count {
+            self.breakpoints.push(Breakpoint::new(line));
+            return true;
+        }
+        false
+    }
+
+    /// Enables or disables the breakpoint of `line`. Returns `false` if there is none.
+    pub fn set_breakpoint_enabled(&mut self, line: usize, enabled: bool) -> bool {
+        self.breakpoint_mut(line)
+            .map(|breakpoint| breakpoint.enabled = enabled)
+            .is_some()
+    }
+
+    /// Sets the condition of the breakpoint of `line`, or removes it with `None`. Returns
+    /// `false` if there is no breakpoint.
+    pub fn set_breakpoint_condition(&mut self, line: usize, condition: Option<String>) -> bool {
+        self.breakpoint_mut(line)
+            .map(|breakpoint| breakpoint.condition = condition)
+            .is_some()
+    }
+
+    fn breakpoint_mut(&mut self, line: usize) -> Option<&mut Breakpoint> {
+        self.breakpoints
+            .iter_mut()
+            .find(|breakpoint| breakpoint.line == line)
+    }
+
+    /// Returns the colors of the breakpoints without and with a condition.
+    pub(crate) fn breakpoint_colors(&self) -> (Color, Color) {
+        self.breakpoint_colors
+    }
+
+    /// Sets the colors of the breakpoints without and with a condition.
+    pub(crate) fn set_breakpoint_colors(&mut self, colors: (Color, Color)) {
+        self.breakpoint_colors = colors;
+    }
+
+    /// Returns the line the debugger stopped at, if any.
+    pub fn execution_line(&self) -> Option<usize> {
+        self.execution_line
+    }
+
+    /// Sets the line the debugger stopped at, highlighted across the editor and marked in
+    /// the gutter independently of the caret, or `None` while the program runs. A fold hiding
+    /// the line is opened.
+    ///
+    /// The view does not follow; call [`Self::go_to`] as well to bring the line into view.
+    pub fn set_execution_line(&mut self, line: Option<usize>) {
+        if let Some(line) = line {
+            self.reveal_line(line);
+        }
+        self.execution_line = line;
+    }
+
+    /// Returns the color of the highlight of the execution line.
+    pub fn execution_line_color(&self) -> Color {
+        self.execution_line_color
+    }
+
+    /// Sets the color of the highlight of the execution line. Its gutter mark is drawn in
+    /// the same color, opaque.
+    pub fn set_execution_line_color(&mut self, color: Color) {
+        self.execution_line_color = color;
+    }
+
+    /// Opens the breakpoint menu of the gutter line `line` at `position`, relative to the text
+    /// area, offering the condition entries if `conditions` is `true`.
+    pub(crate) fn open_breakpoint_menu(
+        &mut self,
+        position: PxPosition,
+        line: usize,
+        conditions: bool,
+    ) {
+        self.open_context_menu(position);
+        self.spelling_suggestions = None;
+        self.breakpoint_menu = Some((line, conditions));
+    }
+
+    /// Returns the gutter line the chosen breakpoint command applies to.
+    pub(crate) fn breakpoint_menu_line(&self) -> Option<usize> {
+        self.breakpoint_menu.map(|(line, _)| line)
+    }
+
+    /// Returns the width of the gutter, zero without one.
+    pub(crate) fn gutter_width(&self) -> Px {
+        self.gutter_width
+    }
+
+    /// Sets the width of the gutter, zero to hide it.
+    pub(crate) fn set_gutter_width(&mut self, width: Px) {
+        self.gutter_width = width;
+    }
+
+    /// Returns the width of the line numbers in the gutter, including the gap after them.
+    pub(crate) fn line_number_width(&self) -> Px {
+        self.line_number_width
+    }
+
+    /// Sets the width of the line numbers in the gutter, zero to hide them.
+    pub(crate) fn set_line_number_width(&mut self, width: Px) {
+        self.line_number_width = width;
+    }
+
+    /// Returns the rows of the current layout, which map the rows of wrapped lines back to
+    /// their line. Only the rows in view are laid out.
+    pub fn visual_rows(&self) -> Vec<VisualRow> {
+        self.editor.with_buffer(gutter::layout_rows)
+    }
+
+    /// Computes the marks of the gutter for the current layout, right of the line numbers.
+    pub(crate) fn gutter_rects(&self) -> Vec<(RectDef, GutterAnnotation)> {
+        let width = self.gutter_width - self.line_number_width;
+        if width <= Px(0) {
+            return Vec::new();
+        }
+        let (color, conditional_color) = self.breakpoint_colors;
+        let annotations: Vec<GutterAnnotation> =
+            self.gutter_annotations
+                .iter()
+                .copied()
+                .chain(breakpoint_annotations(
+                    &self.breakpoints,
+                    color,
+                    conditional_color,
+                ))
+                .chain(self.execution_line.map(|line| {
+                    GutterAnnotation::bar(line, self.execution_line_color.with_alpha(1.0))
+                }))
+                .collect();
+        let mut rects = gutter::gutter_rects(&self.visual_rows(), &annotations, width, &self.folds);
+        for (rect, _) in &mut rects {
+            rect.x += self.line_number_width;
+        }
+        rects
+    }
+
+    /// Returns the rows that show their line number in the current layout.
+    pub(crate) fn line_number_rows(&self) -> Vec<VisualRow> {
+        if self.line_number_width <= Px(0) {
+            return Vec::new();
+        }
+        gutter::line_number_rows(&self.visual_rows(), &self.folds)
+    }
+
+    /// Computes the wrap indicators of the continuation rows in the gutter. They line up
+    /// with the right edge of the line numbers, or sit in the middle of the gutter without
+    /// them.
+    pub(crate) fn wrap_indicator_rects(&self) -> Vec<RectDef> {
+        if self.gutter_width <= Px(0) || self.wrap_mode == WrapMode::None {
+            return Vec::new();
+        }
+        let (width, _) = gutter::WRAP_INDICATOR_SIZE;
+        let x = if self.line_number_width > Px(0) {
+            self.line_number_width - LINE_NUMBER_GAP - width
+        } else {
+            (self.gutter_width - width) / 2
+        };
+        gutter::wrap_indicator_rects(&self.visual_rows(), x.max(Px(0)), &self.folds)
+    }
+
+    /// Returns the line next to `position` if it is in the gutter, relative to the editor
+    /// component.
+    pub(crate) fn gutter_line_at(&self, position: PxPosition) -> Option<usize> {
+        let pos = self.text_area_position(position);
+        if pos.x >= Px(0) || pos.x < -self.gutter_width || pos.y < Px(0) {
+            return None;
+        }
+        gutter::line_at_y(&self.visual_rows(), pos.y.to_f32(), &self.folds)
+    }
+
+    /// Returns `true` if spaces, tabs and line endings are marked.
+    pub fn show_whitespace(&self) -> bool {
+        self.show_whitespace
+    }
+
+    /// Sets whether spaces are marked with dots, tabs with arrows and line endings with
+    /// pilcrows.
+    ///
+    /// # Arguments
+    ///
+    /// * `show` - `true` to draw the marks.
+    pub fn set_show_whitespace(&mut self, show: bool) {
+        self.show_whitespace = show;
+    }
+
+    /// Returns the color of the whitespace marks.
+    pub fn whitespace_color(&self) -> Color {
+        self.whitespace_color
+    }
+
+    /// Sets the color of the whitespace marks.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new whitespace color.
+    pub fn set_whitespace_color(&mut self, color: Color) {
+        self.whitespace_color = color;
+    }
+
+    /// Returns the clean-up steps applied by [`Self::prepare_save`].
+    pub fn save_options(&self) -> SaveOptions {
+        self.save_options
+    }
+
+    /// Sets the clean-up steps applied by [`Self::prepare_save`].
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The clean-up steps for this document.
+    pub fn set_save_options(&mut self, options: SaveOptions) {
+        self.save_options = options;
+    }
+
+    /// Applies the [save options](Self::save_options) to the content and returns the text to
+    /// write, e.g. from the host's "Save" command.
+    ///
+    /// The cursor and selection stay on the same characters. If the clean-up changes the
+    /// content it is a single undo step and `on_change` is called once; otherwise nothing is
+    /// touched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Dp;
+    /// use tessera_ui_basic_components::text_editor::{SaveOptions, TextEditorState};
+    ///
+    /// let mut state = TextEditorState::new(Dp(14.0), None);
+    /// state.set_save_options(SaveOptions {
+    ///     trim_trailing_whitespace: true,
+    ///     ensure_final_newline: true,
+    ///     ..SaveOptions::default()
+    /// });
+    /// # let _ = || {
+    /// std::fs::write("notes.txt", state.prepare_save()).unwrap();
+    /// # };
+    /// ```
+    pub fn prepare_save(&mut self) -> String {
+        let before = self.snapshot();
+        let options = self.save_options;
+        let (tab_width, insert_spaces) = (self.tab_width, self.insert_spaces);
+
+        let (old_lines, new_lines, mut text) = self.editor.with_buffer(|buffer| {
+            let old: Vec<String> = buffer.lines.iter().map(|l| l.text().to_string()).collect();
+            let new: Vec<String> = old
+                .iter()
+                .map(|line| normalize_line(line, &options, tab_width, insert_spaces))
+                .collect();
+            let text = new
+                .iter()
+                .zip(&buffer.lines)
+                .map(|(line, buffer_line)| line.clone() + buffer_line.ending().as_str())
+                .collect::<String>();
+            (old, new, text)
+        });
+        if options.ensure_final_newline && !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        if text == before.text {
+            return text;
+        }
+
+        let map = |c: Cursor| match (old_lines.get(c.line), new_lines.get(c.line)) {
+            (Some(old), Some(new)) => Cursor {
+                index: map_index(old, new, c.index),
+                ..c
+            },
+            _ => c,
+        };
+        let cursor = map(before.cursor);
+        let selection = match before.selection {
+            Selection::Normal(c) => Selection::Normal(map(c)),
+            Selection::Line(c) => Selection::Line(map(c)),
+            Selection::Word(c) => Selection::Word(map(c)),
+            Selection::None => Selection::None,
+        };
+        self.set_text_reactive(&text);
+        self.editor.set_cursor(cursor);
+        self.editor.set_selection(selection);
+
+        self.mark_changed(&before.text);
+        self.record_change(before, cursor);
+        self.secondary_carets.clear();
+        self.refresh_search();
+        text
+    }
+
+    /// Returns the diagnostics underlined in the content.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Replaces the diagnostics underlined in the content.
+    ///
+    /// Ranges are not moved by later edits, so the owner should send fresh diagnostics after
+    /// the content changes, as a language server does.
+    ///
+    /// # Arguments
+    ///
+    /// * `diagnostics` - The new diagnostics. An empty list removes all underlines.
+    pub fn set_diagnostics(&mut self, diagnostics: Vec<Diagnostic>) {
+        self.diagnostics = diagnostics;
+        self.hovered_diagnostic = None;
+    }
+
+    /// Returns the diagnostic covering `cursor`, preferring the most severe one.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - The position to look up, e.g. [`glyphon::Editor::cursor`].
+    pub fn diagnostic_at(&self, cursor: Cursor) -> Option<&Diagnostic> {
+        diagnostic_index_at(&self.diagnostics, cursor).map(|i| &self.diagnostics[i])
+    }
+
+    /// Returns the diagnostic under the mouse pointer, if any.
+    pub fn hovered_diagnostic(&self) -> Option<&Diagnostic> {
+        self.hovered_diagnostic.map(|i| &self.diagnostics[i])
+    }
+
+    /// Updates the hovered diagnostic from a pointer position relative to the text area.
+    ///
+    /// Returns `true` if a different diagnostic, or none, is hovered now.
+    pub(crate) fn update_hovered_diagnostic(&mut self, position: Option<PxPosition>) -> bool {
+        let hovered = position.and_then(|pos| {
+            let x = (pos.x + self.horizontal_scroll()).to_f32();
+            let cursor = self
+                .editor
+                .with_buffer(|buffer| buffer.hit(x, pos.y.to_f32()))?;
+            diagnostic_index_at(&self.diagnostics, cursor)
+        });
+        let changed = hovered != self.hovered_diagnostic;
+        self.hovered_diagnostic = hovered;
+        changed
+    }
+
+    /// Returns the provider checking the spelling of the visible words, if any.
+    pub fn spell_provider(&self) -> Option<&Arc<dyn SpellProvider>> {
+        self.spell_checker.as_ref().map(SpellChecker::provider)
+    }
+
+    /// Sets the provider checking the spelling of the visible words, or turns spell checking
+    /// off with `None`.
+    ///
+    /// Misspelled words are underlined like error diagnostics, and right-clicking one lists
+    /// the suggestions of the provider at the top of the context menu.
+    pub fn set_spell_provider(&mut self, provider: Option<Arc<dyn SpellProvider>>) {
+        self.spell_checker = provider.map(SpellChecker::new);
+        self.misspellings.clear();
+        self.spelling_suggestions = None;
+    }
+
+    /// Returns the misspelled words of the lines shown when the editor was last laid out.
+    pub fn misspellings(&self) -> &[TextRange] {
+        &self.misspellings
+    }
+
+    /// Checks the spelling of the laid out lines.
+    fn refresh_misspellings(&mut self) {
+        let Some(checker) = &mut self.spell_checker else {
+            return;
+        };
+        self.misspellings = self.editor.with_buffer(|buffer| {
+            let mut lines: Vec<usize> = buffer.layout_runs().map(|run| run.line_i).collect();
+            lines.dedup();
+            checker.misspellings(
+                lines
+                    .into_iter()
+                    .map(|line| (line, buffer.lines[line].text())),
+            )
+        });
+    }
+
+    /// Returns the misspelled word at `position`, relative to the text area, with the
+    /// suggestions for it.
+    fn spelling_suggestions_at(&self, position: PxPosition) -> Option<(TextRange, Vec<String>)> {
+        let checker = self.spell_checker.as_ref()?;
+        let x = (position.x + self.horizontal_scroll()).to_f32();
+        self.editor.with_buffer(|buffer| {
+            let cursor = buffer.hit(x, position.y.to_f32())?;
+            let range = *self.misspellings.iter().find(|range| {
+                range.start.line == cursor.line
+                    && range.start.index <= cursor.index
+                    && cursor.index <= range.end.index
+            })?;
+            let word = &buffer.lines[range.start.line].text()[range.start.index..range.end.index];
+            Some((range, checker.suggest(word)))
+        })
+    }
+
+    /// Replaces the misspelled word the context menu was opened on with its suggestion
+    /// `index`, as one undo step. Returns `false` if there is no such suggestion.
+    pub(crate) fn accept_spelling_suggestion(&mut self, index: usize) -> bool {
+        let Some((range, suggestions)) = self.spelling_suggestions.take() else {
+            return false;
+        };
+        let Some(replacement) = suggestions.get(index) else {
+            return false;
+        };
+        if self.read_only {
+            return false;
+        }
+
+        let before = self.snapshot();
+        self.editor.delete_range(range.start, range.end);
+        let cursor = insert_text_at(&mut self.editor, range.start, replacement);
+        self.editor.set_cursor(cursor);
+        self.editor.set_selection(Selection::None);
+        self.misspellings
+            .retain(|misspelling| *misspelling != range);
+
+        self.mark_changed(&before.text);
+        self.record_change(before, cursor);
+        self.refresh_search();
+        true
+    }
+
+    /// Returns the inlay hints shown in the content, sorted by position.
+    pub fn inlay_hints(&self) -> &[InlayHint] {
+        &self.inlay_hints
+    }
+
+    /// Replaces the inlay hints shown in the content. Hints at the same position are shown
+    /// in the given order.
+    ///
+    /// Positions are not moved by later edits, so the owner should send fresh hints after
+    /// the content changes, as a language server does.
+    ///
+    /// # Arguments
+    ///
+    /// * `hints` - The new hints. An empty list removes all hints.
+    pub fn set_inlay_hints(&mut self, mut hints: Vec<InlayHint>) {
+        hints.sort_by_key(|hint| (hint.position.line, hint.position.index));
+        self.inlay_hints = hints;
+        self.inlay_hint_widths = None;
+    }
+
+    /// Returns the color of inlay hint labels.
+    pub fn inlay_hint_color(&self) -> Color {
+        self.inlay_hint_color
+    }
+
+    /// Sets the color of inlay hint labels.
+    pub fn set_inlay_hint_color(&mut self, color: Color) {
+        self.inlay_hint_color = color;
+    }
+
+    /// Makes room for the lens rows of the editor: each `(line, height)` pushes that line and
+    /// the ones after it down by `height` pixels. The rows are laid out by
+    /// [`text_editor`](crate::text_editor::text_editor), see
+    /// [`TextEditorArgs::lens_rows`](crate::text_editor::TextEditorArgs::lens_rows).
+    pub(crate) fn set_lens_rows(&mut self, mut rows: Vec<(usize, f32)>) {
+        rows.sort_by_key(|&(line, _)| line);
+        rows.dedup_by_key(|&mut (line, _)| line);
+        self.lens_rows = rows;
+    }
+
+    /// Returns the top of each lens row in the last layout, relative to the text area, with
+    /// the line it is above. Rows of lines that were not laid out are left out.
+    pub(crate) fn lens_row_tops(&self) -> Vec<(usize, f32)> {
+        self.layout_editor().with_buffer(lens_row::lens_row_tops)
+    }
+
+    /// Returns the buffer position of every inlay hint with the room and left padding of
+    /// its label, measuring the labels again if the font changed.
+    fn inlay_hint_layout(&mut self) -> Vec<(Cursor, (f32, f32))> {
+        let metrics = self.editor.with_buffer(|buffer| buffer.metrics());
+        if self
+            .inlay_hint_widths
+            .as_ref()
+            .is_none_or(|(measured, _)| *measured != metrics)
+        {
+            let widths = self
+                .inlay_hints
+                .iter()
+                .map(|hint| hint_width(hint, metrics))
+                .collect();
+            self.inlay_hint_widths = Some((metrics, widths));
+        }
+        let widths = self
+            .inlay_hint_widths
+            .as_ref()
+            .map_or(&[][..], |(_, widths)| widths.as_slice());
+        self.inlay_hints
+            .iter()
+            .zip(widths)
+            .map(|(hint, &width)| (hint.position, width))
+            .collect()
+    }
+
+    /// Returns the URL, file path or match of a [link pattern](Self::set_link_patterns) at
+    /// `cursor`, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - The position to look up, e.g. [`glyphon::Editor::cursor`].
+    pub fn link_at(&self, cursor: Cursor) -> Option<String> {
+        self.link_range_at(cursor)
+            .and_then(|range| self.range_text(range))
+    }
+
+    /// Returns the link that is underlined because the mouse pointer is over it
+    /// while Ctrl is held.
+    pub fn hovered_link(&self) -> Option<String> {
+        self.hovered_link.and_then(|range| self.range_text(range))
+    }
+
+    /// Updates the hovered link from a pointer position relative to the text area, or `None`
+    /// when links should not react to the pointer. Returns `true` if it changed.
+    pub(crate) fn update_hovered_link(&mut self, position: Option<PxPosition>) -> bool {
+        let hovered = position.and_then(|pos| {
+            let x = (pos.x + self.horizontal_scroll()).to_f32();
+            let cursor = self
+                .editor
+                .with_buffer(|buffer| buffer.hit(x, pos.y.to_f32()))?;
+            self.link_range_at(cursor)
+        });
+        let changed = hovered != self.hovered_link;
+        self.hovered_link = hovered;
+        changed
+    }
+
+    /// Returns the range of the link containing `cursor`.
+    fn link_range_at(&self, cursor: Cursor) -> Option<TextRange> {
+        self.editor.with_buffer(|buffer| {
+            let line = buffer.lines.get(cursor.line)?.text();
+            let range = find_links(line)
+                .into_iter()
+                .chain(find_pattern_links(line, &self.link_patterns))
+                .find(|range| range.contains(&cursor.index))?;
+            Some(TextRange {
+                start: Cursor::new(cursor.line, range.start),
+                end: Cursor::new(cursor.line, range.end),
+            })
+        })
+    }
+
+    /// Returns the text of a range within one line, or `None` if the line has changed so much
+    /// that the range no longer fits.
+    fn range_text(&self, range: TextRange) -> Option<String> {
+        self.editor.with_buffer(|buffer| {
+            let line = buffer.lines.get(range.start.line)?.text();
+            line.get(range.start.index..range.end.index)
+                .map(str::to_string)
+        })
+    }
+
+    /// Returns the patterns detected as links on top of URLs and file paths.
+    pub fn link_patterns(&self) -> &[LinkPattern] {
+        &self.link_patterns
+    }
+
+    /// Sets the patterns detected as links on top of URLs and file paths, e.g. issue numbers.
+    /// Where a pattern overlaps a URL or path, the URL or path wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - The link patterns, earlier ones winning over later ones.
+    pub fn set_link_patterns(&mut self, patterns: Vec<LinkPattern>) {
+        self.link_patterns = patterns;
+        self.hovered_link = None;
+    }
+
+    /// Returns the color of the underline of a hovered link.
+    pub fn link_color(&self) -> Color {
+        self.link_color
+    }
+
+    /// Sets the color of the underline of a hovered link.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new link color.
+    pub fn set_link_color(&mut self, color: Color) {
+        self.link_color = color;
+    }
+
+    /// Returns the items of the completion popup, or an empty slice if it is closed.
+    pub fn completion_items(&self) -> &[CompletionItem] {
+        self.completion
+            .as_ref()
+            .map_or(&[], |completion| completion.items.as_slice())
+    }
+
+    /// Returns the index of the highlighted completion item while the popup is open.
+    pub fn selected_completion(&self) -> Option<usize> {
+        self.completion
+            .as_ref()
+            .map(|completion| completion.selected)
+    }
+
+    /// Closes the completion popup without inserting anything.
+    pub fn close_completion(&mut self) {
+        self.completion = None;
+    }
+
+    /// Asks `provider` for completions of the word before the cursor, opening the popup if it
+    /// returns any and closing it otherwise.
+    pub(crate) fn update_completion(
+        &mut self,
+        provider: &(dyn Fn(CompletionRequest) -> Vec<CompletionItem> + Send + Sync),
+    ) {
+        let cursor = self.editor.cursor();
+        let start = self.editor.with_buffer(|buffer| {
+            let line = buffer.lines.get(cursor.line)?.text();
+            Some((
+                prefix_start(line, cursor.index),
+                line[..cursor.index].to_string(),
+            ))
+        });
+        let multi_caret =
+            !self.secondary_carets.is_empty() || self.editor.selection_bounds().is_some();
+        let Some((start, line)) = start.filter(|(start, _)| *start < cursor.index && !multi_caret)
+        else {
+            self.completion = None;
+            return;
+        };
+
+        let items = provider(CompletionRequest {
+            prefix: line[start..].to_string(),
+            cursor,
+        });
+        self.completion = (!items.is_empty()).then(|| ActiveCompletion {
+            items,
+            selected: 0,
+            start: Cursor::new(cursor.line, start),
+        });
+    }
+
+    /// Handles the keys that navigate the open completion popup. Returns `true` if the key was
+    /// used by the popup and must not reach the editor.
+    pub(crate) fn handle_completion_key(&mut self, key_event: &winit::event::KeyEvent) -> bool {
+        let Some(completion) = &mut self.completion else {
+            return false;
+        };
+        if key_event.state != winit::event::ElementState::Pressed {
+            return false;
+        }
+        let len = completion.items.len();
+        match key_event.logical_key {
+            winit::keyboard::Key::Named(NamedKey::ArrowDown) => {
+                completion.selected = (completion.selected + 1) % len;
+            }
+            winit::keyboard::Key::Named(NamedKey::ArrowUp) => {
+                completion.selected = (completion.selected + len - 1) % len;
+            }
+            winit::keyboard::Key::Named(NamedKey::Enter | NamedKey::Tab) => {
+                let selected = completion.selected;
+                self.accept_completion(selected);
+            }
+            winit::keyboard::Key::Named(NamedKey::Escape) => self.completion = None,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Replaces the typed prefix with the completion item at `index` and closes the popup.
+    ///
+    /// Returns `true` if an item was inserted.
+    pub(crate) fn accept_completion(&mut self, index: usize) -> bool {
+        let Some(completion) = self.completion.take() else {
+            return false;
+        };
+        let Some(item) = completion.items.get(index) else {
+            return false;
+        };
+        if self.read_only {
+            return false;
+        }
+
+        let before = self.snapshot();
+        self.editor.delete_range(completion.start, before.cursor);
+        let cursor = insert_text_at(&mut self.editor, completion.start, &item.insert_text);
+        self.editor.set_cursor(cursor);
+        self.editor.set_selection(Selection::None);
+
+        self.mark_changed(&before.text);
+        self.record_change(before, cursor);
+        self.refresh_search();
+        true
+    }
+
+    /// Returns the distance from the edge of the editor component to the text area.
+    pub(crate) fn text_offset(&self) -> Px {
+        self.text_offset
+    }
+
+    /// Sets the distance from the edge of the editor component to the text area.
+    pub(crate) fn set_text_offset(&mut self, offset: Px) {
+        self.text_offset = offset;
+    }
+
+    /// Returns the top-left corner of the text area, relative to the editor component: inside
+    /// the padding, the border and the gutter.
+    pub(crate) fn text_origin(&self) -> PxPosition {
+        PxPosition::new(self.text_offset + self.gutter_width, self.text_offset)
+    }
+
+    /// Converts `position`, relative to the editor component, to one relative to the text area.
+    pub(crate) fn text_area_position(&self, position: PxPosition) -> PxPosition {
+        let origin = self.text_origin();
+        PxPosition::new(position.x - origin.x, position.y - origin.y)
+    }
+
+    /// Returns the text position under `position`, relative to the top-left corner of the
+    /// [`text_editor`](crate::text_editor::text_editor) component like the cursor position of
+    /// an input handler, or `None` outside the text area.
+    ///
+    /// Scrolling, padding, the border and the gutter are accounted for. Points right of a line or below
+    /// the last one map to the nearest position on it, like a click there.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - A point in the editor component, e.g. where a tooltip was requested.
+    pub fn hit_test(&self, position: PxPosition) -> Option<Cursor> {
+        let pos = self.text_area_position(position);
+        if pos.x < Px(0) || pos.y < Px(0) {
+            return None;
+        }
+        let x = (pos.x + self.horizontal_scroll()).to_f32();
+        self.editor
+            .with_buffer(|buffer| buffer.hit(x, pos.y.to_f32()))
+    }
+
+    /// Returns where the caret would be drawn at `cursor`: the top-left corner of its line
+    /// box, relative to the editor component like [`Self::hit_test`].
+    ///
+    /// Returns `None` if the line does not exist or is hidden in a fold. The position may lie
+    /// outside the component while `cursor` is scrolled out of view.
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - The text position, e.g. the start of a [`TextRange`] to anchor a widget to.
+    pub fn position_of(&self, cursor: Cursor) -> Option<PxPosition> {
+        if self.is_line_hidden(cursor.line) {
+            return None;
+        }
+        let (x, y) = self.editor.with_buffer(|buffer| {
+            let line_height = buffer.metrics().line_height;
+            let mut line_end = None;
+            for run in buffer.layout_runs().filter(|run| run.line_i == cursor.line) {
+                let (top, _) = lens_row::text_row(&run, line_height);
+                for glyph in run.glyphs {
+                    if (glyph.start..glyph.end).contains(&cursor.index) {
+                        let x = if glyph.level.is_rtl() {
+                            glyph.x + glyph.w
+                        } else {
+                            glyph.x
+                        };
+                        return Some((x, top));
+                    }
+                }
+                let end = run.glyphs.last().map_or(0, |glyph| glyph.end);
+                if cursor.index >= end {
+                    line_end = Some((run.line_w, top));
+                }
+            }
+            line_end
+        })?;
+        let origin = self.text_origin();
+        Some(PxPosition::new(
+            Px(x.round() as i32) - self.horizontal_scroll() + origin.x,
+            Px(y.round() as i32) + origin.y,
+        ))
+    }
+
+    /// Returns where the primary caret is drawn, like `Edit::cursor_position`, but below the
+    /// lens row above its line, if any.
+    fn caret_position(&self) -> Option<(i32, i32)> {
+        let (x, y) = self.editor.cursor_position()?;
+        let line = self.editor.cursor().line;
+        let top = self.editor.with_buffer(|buffer| {
+            let line_height = buffer.metrics().line_height;
+            buffer
+                .layout_runs()
+                .find(|run| run.line_i == line && run.line_top as i32 == y)
+                .map_or(y, |run| lens_row::text_row(&run, line_height).0 as i32)
+        });
+        Some((x, top))
+    }
+
+    /// Returns where the completion popup is anchored: below the cursor, relative to the text
+    /// area.
+    pub(crate) fn completion_anchor(&self) -> Option<PxPosition> {
+        let (x, y) = self.caret_position()?;
+        Some(PxPosition::new(
+            Px(x) - self.horizontal_scroll(),
+            Px(y) + self.line_height,
+        ))
+    }
+
+    /// Returns the position and size of the caret, relative to the text area, which the IME
+    /// candidate window is placed next to.
+    pub(crate) fn ime_cursor_area(&self) -> Option<(PxPosition, PxSize)> {
+        let (x, y) = match &self.composed {
+            Some(composed) => cursor_pixel_position(&composed.editor, composed.caret())?,
+            None => self.caret_position()?,
+        };
+        let position = PxPosition::new(Px(x) - self.horizontal_scroll(), Px(y));
+        Some((
+            position,
+            PxSize::new(CURSOR_WIDRH.to_px(), self.line_height),
+        ))
+    }
+
+    /// Returns the text being composed with an input method, shown at the caret but not part
+    /// of the content until it is committed.
+    pub fn preedit(&self) -> Option<&str> {
+        self.preedit.as_ref().map(|preedit| preedit.text.as_str())
+    }
+
+    /// Shows `text` being composed at the primary caret, underlined, in place of the previous
+    /// composition. The caret is put at the end of `cursor`, a byte range within `text` as
+    /// reported by the input method. An empty text ends the composition.
+    ///
+    /// The composed text is laid out with the content but kept out of its buffer, so it is
+    /// not part of the content, the undo history or the change notifications, and edits made
+    /// meanwhile never see it.
+    pub(crate) fn set_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        if text.is_empty() {
+            self.preedit = None;
+            return;
+        }
+        // Line breaks are shown as spaces, both are a single byte
+        let text = text.replace(['\n', '\r'], " ");
+        self.editor.set_selection(Selection::None);
+        let caret = preedit_caret(&text, cursor);
+        self.preedit = Some(Preedit { text, caret });
+    }
+
+    /// Ends the composition without committing it.
+    pub(crate) fn clear_preedit(&mut self) {
+        self.preedit = None;
+    }
+
+    /// Returns the content.
+    pub(crate) fn content(&self) -> String {
+        editor_content(&self.editor)
+    }
+
+    /// Returns the editor the text was last laid out in: with the composition, if any,
+    /// inserted at the caret.
+    fn layout_editor(&self) -> &glyphon::Editor<'static> {
+        self.composed
+            .as_ref()
+            .map_or(&self.editor, |composed| &composed.editor)
+    }
+
+    /// Returns where `cursor` of the content is in [`Self::layout_editor`]. At the
+    /// composition it goes after it if `after`.
+    fn layout_cursor(&self, cursor: Cursor, after: bool) -> Cursor {
+        self.composed
+            .as_ref()
+            .map_or(cursor, |composed| composed.cursor(cursor, after))
+    }
+
+    /// Returns where `range` of the content is in [`Self::layout_editor`].
+    fn layout_range(&self, range: TextRange) -> TextRange {
+        self.composed
+            .as_ref()
+            .map_or(range, |composed| composed.range(range))
+    }
+
+    /// Opens the context menu at `position`, relative to the text area, as a right-click does.
+    pub fn open_context_menu(&mut self, position: PxPosition) {
+        self.completion = None;
+        self.spelling_suggestions = self.spelling_suggestions_at(position);
+        self.breakpoint_menu = None;
+        self.context_menu = Some(position);
+    }
+
+    /// Closes the context menu without running any of its entries.
+    pub fn close_context_menu(&mut self) {
+        self.context_menu = None;
+    }
+
+    /// Returns `true` while the context menu is open.
+    pub fn is_context_menu_open(&self) -> bool {
+        self.context_menu.is_some()
+    }
+
+    /// Returns where the open context menu is anchored, relative to the text area.
+    pub(crate) fn context_menu_anchor(&self) -> Option<PxPosition> {
+        self.context_menu
+    }
+
+    /// Returns the entries of the context menu, followed by the `custom` ones.
+    pub(crate) fn context_menu_entries(&self, custom: &[ContextMenuItem]) -> Vec<ContextMenuEntry> {
+        if let Some((line, conditions)) = self.breakpoint_menu {
+            return breakpoint_menu_entries(self.breakpoint_at(line), conditions);
+        }
+        let has_selection = self.editor.selection_bounds().is_some();
+        let suggestions = self
+            .spelling_suggestions
+            .as_ref()
+            .map(|(_, suggestions)| suggestions.as_slice());
+        menu_entries(has_selection, self.read_only, suggestions, custom)
+    }
+
+    /// Closes the context menu, keeping `choice` until the editor runs it.
+    pub(crate) fn choose_context_menu_entry(&mut self, choice: ContextMenuChoice) {
+        self.context_menu = None;
+        self.context_menu_choice = Some(choice);
+    }
+
+    /// Returns the context menu entry clicked since the last call.
+    pub(crate) fn take_context_menu_choice(&mut self) -> Option<ContextMenuChoice> {
+        self.context_menu_choice.take()
+    }
+
+    /// Returns the transition animating the editor surface between its unfocused and focused
+    /// styles over `duration`, kept across frames.
+    pub(crate) fn focus_transition(&mut self, duration: Duration) -> Arc<StyleTransition> {
+        match &self.focus_transition {
+            Some(transition) if transition.duration() == duration => transition.clone(),
+            _ => self
+                .focus_transition
+                .insert(Arc::new(StyleTransition::new(duration)))
+                .clone(),
+        }
+    }
+
+    /// Runs a clipboard `command` on the selection, returning the actions that edit the content.
+    pub(crate) fn clipboard_actions(
+        &mut self,
+        command: EditCommand,
+        clipboard: &mut Clipboard,
+    ) -> Option<Vec<glyphon::Action>> {
+        match command {
+            EditCommand::Copy => {
+                if let Some(text) = self.editor.copy_selection() {
+                    self.copy_to_clipboard(&text, clipboard);
+                }
+                None
+            }
+            EditCommand::Cut => {
+                let text = self.editor.copy_selection()?;
+                self.copy_to_clipboard(&text, clipboard);
+                // Use Backspace action to delete selection
+                Some(vec![glyphon::Action::Backspace])
+            }
+            EditCommand::Paste => {
+                if !self.read_only
+                    && let Some(text) = clipboard.get_text()
+                {
+                    self.insert_text(&text);
+                }
+                None
+            }
+            EditCommand::SelectAll => {
+                self.select_all();
+                None
+            }
+        }
+    }
+
+    /// Puts the selected `text` on `clipboard`, with an HTML version next to it if
+    /// [`Self::copy_as_html`] is on.
+    fn copy_to_clipboard(&self, text: &str, clipboard: &mut Clipboard) {
+        match self.selection_html() {
+            Some(html) if self.copy_as_html => clipboard.set_html(&html, text),
+            _ => clipboard.set_text(text),
+        }
+    }
+
+    /// Returns the selection of the primary caret as an HTML fragment in the colors the
+    /// text is drawn in, or `None` if nothing is selected.
+    pub fn selection_html(&self) -> Option<String> {
+        let (start, end) = self.editor.selection_bounds()?;
+        self.editor.with_buffer(|buffer| {
+            let lines: Vec<_> = (start.line..=end.line)
+                .filter_map(|i| {
+                    let line = buffer.lines.get(i)?;
+                    let from = if i == start.line { start.index } else { 0 };
+                    let to = if i == end.line {
+                        end.index
+                    } else {
+                        line.text().len()
+                    };
+                    let mut segments = rich_copy::colored_segments(line, from..to);
+                    // Folded lines are drawn transparent
+                    if fold::is_hidden(&self.folds, i) {
+                        segments.iter_mut().for_each(|(_, color)| *color = None);
+                    }
+                    Some(segments)
+                })
+                .collect();
+            Some(rich_copy::html_fragment(&lines))
+        })
+    }
+
+    /// Returns `true` if copying puts an HTML version of the selection on the clipboard.
+    pub fn copy_as_html(&self) -> bool {
+        self.copy_as_html
+    }
+
+    /// Sets whether copying and cutting put an HTML version of the selection, see
+    /// [`Self::selection_html`], on the clipboard next to the plain text, so rich text
+    /// targets keep its colors.
+    ///
+    /// # Arguments
+    ///
+    /// * `copy_as_html` - `true` to add the HTML version.
+    pub fn set_copy_as_html(&mut self, copy_as_html: bool) {
+        self.copy_as_html = copy_as_html;
+    }
+
+    /// Selects the whole content, leaving the cursor at its end.
+    pub fn select_all(&mut self) {
+        self.editor.set_cursor(Cursor::new(0, 0));
+        self.editor
+            .set_selection(Selection::Normal(Cursor::new(0, 0)));
+        // Moving to the end extends the selection over the full document
+        self.editor.action(
+            &mut write_font_system(),
+            glyphon::Action::Motion(cosmic_text::Motion::BufferEnd),
+        );
+    }
+
+    /// Returns the line and the byte index within that line of the cursor.
+    pub fn cursor_position(&self) -> (usize, usize) {
+        let cursor = self.editor.cursor();
+        (cursor.line, cursor.index)
+    }
+
+    /// Returns the position of the primary caret.
+    pub fn caret(&self) -> TextPosition {
+        self.position_of_cursor(self.editor.cursor())
+    }
+
+    /// Returns the selection of the primary caret from its first to its last position, or
+    /// `None` if nothing is selected.
+    ///
+    /// Pass the ends to [`Self::select_range`] to restore it.
+    pub fn selection_range(&self) -> Option<std::ops::Range<TextPosition>> {
+        let (start, end) = self.editor.selection_bounds()?;
+        Some(self.position_of_cursor(start)..self.position_of_cursor(end))
+    }
+
+    /// Returns the position of `cursor`, moved onto the content like in [`Self::set_cursor`].
+    pub fn position_of_cursor(&self, cursor: Cursor) -> TextPosition {
+        let cursor = self.clamped_cursor(cursor);
+        TextPosition {
+            line: cursor.line,
+            column: cursor.index,
+            offset: cursor_to_offset(&self.editor, cursor),
+        }
+    }
+
+    /// Returns the position at byte `column` of `line`, moved onto the content.
+    pub fn position_at(&self, line: usize, column: usize) -> TextPosition {
+        self.position_of_cursor(Cursor::new(line, column))
+    }
+
+    /// Returns the position at the byte `offset` into the content, moved onto the content.
+    pub fn position_at_offset(&self, offset: usize) -> TextPosition {
+        self.position_of_cursor(offset_to_cursor(&self.editor, offset))
+    }
+
+    /// Returns the column of `position` in UTF-16 code units, as language servers count
+    /// them by default.
+    pub fn utf16_column(&self, position: TextPosition) -> usize {
+        self.editor.with_buffer(|buffer| {
+            buffer.lines.get(position.line).map_or(0, |line| {
+                position::utf16_column(line.text(), position.column)
+            })
+        })
+    }
+
+    /// Returns the position `utf16_column` UTF-16 code units into `line`, e.g. from a
+    /// language server, moved onto the content.
+    pub fn position_at_utf16(&self, line: usize, utf16_column: usize) -> TextPosition {
+        let column = self.editor.with_buffer(|buffer| {
+            buffer.lines.get(line).map_or(usize::MAX, |text| {
+                position::byte_column(text.text(), utf16_column)
+            })
+        });
+        self.position_at(line, column)
+    }
+
+    /// Moves the cursor to byte `column` of `line`, dropping the selection and any secondary
+    /// carets, and scrolls it into view.
+    ///
+    /// Positions past the end of the content or of the line are moved back onto it, and a
+    /// fold hiding the line is opened.
+    pub fn set_cursor(&mut self, line: usize, column: usize) {
+        let cursor = self.clamped_cursor(Cursor::new(line, column));
+        self.reveal_line(cursor.line);
+        self.secondary_carets.clear();
+        self.editor.set_selection(Selection::None);
+        self.editor.set_cursor(cursor);
+    }
+
+    /// Selects the text from `start` to `end`, leaving the cursor at `end` and scrolling it
+    /// into view. `end` may come before `start` to select backwards.
+    ///
+    /// Both ends are clamped onto the content like in [`Self::set_cursor`].
+    pub fn select_range(&mut self, start: Cursor, end: Cursor) {
+        let (start, end) = (self.clamped_cursor(start), self.clamped_cursor(end));
+        self.reveal_line(start.line);
+        self.reveal_line(end.line);
+        self.secondary_carets.clear();
+        self.editor.set_selection(Selection::Normal(start));
+        self.editor.set_cursor(end);
+    }
+
+    /// Returns the selected text of the primary caret, or `None` if nothing is selected.
+    pub fn selected_text(&self) -> Option<String> {
+        self.editor.copy_selection()
+    }
+
+    /// Reads the selection aloud with [`tessera_ui::speech`], or the line of the cursor
+    /// without a selection, e.g. to proofread it or for users who cannot see the screen.
+    ///
+    /// Returns `false` if there was nothing to read.
+    pub fn speak(&self) -> bool {
+        let text = self.selected_text().unwrap_or_else(|| {
+            let line = self.editor.cursor().line;
+            self.editor.with_buffer(|buffer| {
+                buffer
+                    .lines
+                    .get(line)
+                    .map_or_else(String::new, |line| line.text().to_string())
+            })
+        });
+        if text.trim().is_empty() {
+            return false;
+        }
+        speech::speak(&text);
+        true
+    }
+
+    /// Returns how many lines are kept visible above and below the caret.
+    pub fn scroll_margin(&self) -> usize {
+        self.scroll_margin
+    }
+
+    /// Sets how many lines are kept visible above and below the caret when the view follows
+    /// it, like the `scrolloff` of Vim. A margin of more than half the view keeps the caret
+    /// centered.
+    pub fn set_scroll_margin(&mut self, lines: usize) {
+        self.scroll_margin = lines;
+    }
+
+    /// Scrolls so that `line` is at the top of the view, or as far as the content allows,
+    /// without moving the cursor. A fold hiding the line is opened.
+    pub fn scroll_to_line(&mut self, line: usize) {
+        let line = line.min(
+            self.editor
+                .with_buffer(|buffer| buffer.lines.len().saturating_sub(1)),
+        );
+        self.reveal_line(line);
+        self.editor.with_buffer_mut(|buffer| {
+            let mut scroll = buffer.scroll();
+            scroll.line = line;
+            scroll.vertical = 0.0;
+            buffer.set_scroll(scroll);
+        });
+    }
+
+    /// Moves the cursor to the start of `line` like [`Self::set_cursor`] and scrolls the line
+    /// to the middle of the view, or as close to it as the content allows.
+    pub fn scroll_and_place_cursor_at_line(&mut self, line: usize) {
+        self.go_to(line, 0);
+    }
+
+    /// Moves the cursor to byte `column` of `line` like [`Self::set_cursor`] and scrolls the
+    /// line to the middle of the view.
+    ///
+    /// The position left is recorded in the jump list, see [`Self::jump_back`].
+    pub fn go_to(&mut self, line: usize, column: usize) {
+        let from = self.editor.cursor();
+        self.set_cursor(line, column);
+        self.record_jump_from(from, 1);
+        let line = self.editor.cursor().line;
+        self.editor.with_buffer_mut(|buffer| {
+            let line_height = buffer.metrics().line_height;
+            let view_height = buffer.size().1.unwrap_or(line_height);
+            let mut scroll = buffer.scroll();
+            scroll.line = line;
+            // Shaping moves a negative offset onto the lines above
+            scroll.vertical = -((view_height - line_height) / 2.0).max(0.0);
+            buffer.set_scroll(scroll);
+        });
+    }
+
+    /// Returns `true` if the go-to-line command opens the popup.
+    pub(crate) fn go_to_line_enabled(&self) -> bool {
+        self.go_to_line_enabled
+    }
+
+    /// Records the position of the primary caret in the jump list, e.g. before moving it to
+    /// the definition of a symbol. Go-to-line, search matches and clicks far away record
+    /// the position they leave by themselves.
+    pub fn record_jump(&mut self) {
+        self.jumps.push(self.editor.cursor());
+    }
+
+    /// Moves the caret back to the position it last jumped away from, returning `false` at
+    /// the oldest recorded position.
+    pub fn jump_back(&mut self) -> bool {
+        let current = self.editor.cursor();
+        let Some(cursor) = self.jumps.back(current) else {
+            return false;
+        };
+        self.set_cursor(cursor.line, cursor.index);
+        true
+    }
+
+    /// Moves the caret forward again after [`Self::jump_back`], returning `false` at the
+    /// newest position.
+    pub fn jump_forward(&mut self) -> bool {
+        let Some(cursor) = self.jumps.forward() else {
+            return false;
+        };
+        self.set_cursor(cursor.line, cursor.index);
+        true
+    }
+
+    /// Records `from` in the jump list if the primary caret has since moved at least
+    /// `min_lines` lines away from it.
+    pub(crate) fn record_jump_from(&mut self, from: Cursor, min_lines: usize) {
+        if self.editor.cursor().line.abs_diff(from.line) >= min_lines.max(1) {
+            self.jumps.push(from);
+        }
+    }
+
+    /// Sets whether the go-to-line command opens the popup.
+    pub(crate) fn set_go_to_line_enabled(&mut self, enabled: bool) {
+        self.go_to_line_enabled = enabled;
+        if !enabled {
+            self.go_to_line = None;
+        }
+    }
+
+    /// Returns the text typed into the go-to-line popup, or `None` while it is closed.
+    pub(crate) fn go_to_line_input(&self) -> Option<&str> {
+        self.go_to_line.as_deref()
+    }
+
+    /// Handles the keys typed into the open go-to-line popup: digits and `:` are typed,
+    /// Enter jumps to the typed `line[:column]` and Escape closes it. Returns `true` if the
+    /// key was used by the popup and must not reach the editor.
+    pub(crate) fn handle_go_to_line_key(&mut self, key_event: &winit::event::KeyEvent) -> bool {
+        let Some(input) = &mut self.go_to_line else {
+            return false;
+        };
+        if key_event.state != winit::event::ElementState::Pressed {
+            return false;
+        }
+        match &key_event.logical_key {
+            winit::keyboard::Key::Named(NamedKey::Enter) => {
+                let target = go_to_line::parse_target(input);
+                self.go_to_line = None;
+                if let Some((line, column)) = target {
+                    let column = column.map_or(0, |column| {
+                        self.editor.with_buffer(|buffer| {
+                            buffer.lines.get(line).map_or(0, |text| {
+                                go_to_line::char_column_index(text.text(), column)
+                            })
+                        })
+                    });
+                    self.go_to(line, column);
+                }
+            }
+            winit::keyboard::Key::Named(NamedKey::Escape) => self.go_to_line = None,
+            winit::keyboard::Key::Named(NamedKey::Backspace) => {
+                input.pop();
+            }
+            winit::keyboard::Key::Character(text) => {
+                input.extend(text.chars().filter(|c| c.is_ascii_digit() || *c == ':'));
+            }
+            _ => {}
+        }
+        // Everything else is swallowed too, the popup has the keyboard while it is open
+        true
+    }
+
+    /// Returns `cursor` moved onto the content: past the last line to the end of the
+    /// content, and onto the end of its line and a character boundary.
+    fn clamped_cursor(&self, cursor: Cursor) -> Cursor {
+        self.editor.with_buffer(|buffer| {
+            let last = buffer.lines.len().saturating_sub(1);
+            let line_i = cursor.line.min(last);
+            let text = buffer.lines.get(line_i).map_or("", |line| line.text());
+            let mut index = match cursor.line > last {
+                true => text.len(),
+                false => cursor.index.min(text.len()),
+            };
+            while !text.is_char_boundary(index) {
+                index -= 1;
+            }
+            Cursor::new(line_i, index)
+        })
+    }
+
+    /// Returns `true` if a new line copies the indentation of the line it was split from.
+    pub fn auto_indent(&self) -> bool {
+        self.auto_indent
+    }
+
+    /// Makes Enter copy the leading whitespace of the current line into the new line.
+    ///
+    /// The line break and the indentation are a single undo step.
+    ///
+    /// # Arguments
+    ///
+    /// * `auto_indent` - Whether new lines are indented automatically.
+    pub fn set_auto_indent(&mut self, auto_indent: bool) {
+        self.auto_indent = auto_indent;
+    }
+
+    /// Returns `true` if typing an opening bracket or quote inserts its closer.
+    pub fn auto_close(&self) -> bool {
+        self.auto_close
+    }
+
+    /// Makes typing `(`, `[`, `{`, `"` or `'` insert the matching closer after the cursor.
+    ///
+    /// Typing the closer in front of one inserted this way moves over it instead of adding
+    /// another one.
+    ///
+    /// # Arguments
+    ///
+    /// * `auto_close` - Whether brackets and quotes are closed automatically.
+    pub fn set_auto_close(&mut self, auto_close: bool) {
+        self.auto_close = auto_close;
+        self.auto_closers.clear();
+    }
+
+    /// Returns the action to apply for `action` with auto-close, which moves over an
+    /// auto-inserted closer instead of typing it again, and the closer to insert after it.
+    pub(crate) fn auto_close_action(
+        &mut self,
+        action: glyphon::Action,
+    ) -> (glyphon::Action, Option<char>) {
+        let has_selection = self.editor.selection() != Selection::None
+            || self
+                .secondary_carets
+                .iter()
+                .any(|caret| caret.selection != Selection::None);
+        let typed = match action {
+            glyphon::Action::Insert(c) if self.auto_close && c != '\n' && !has_selection => c,
+            _ => {
+                // The remembered positions only follow typing
+                if is_editing_action(&action) {
+                    self.auto_closers.clear();
+                }
+                return (action, None);
+            }
+        };
+
+        let carets = self.caret_cursors();
+        let char_at = |cursor: Cursor| {
+            self.editor.with_buffer(|buffer| {
+                let line = buffer.lines.get(cursor.line)?.text();
+                Some((
+                    line.get(..cursor.index)?.chars().next_back(),
+                    line.get(cursor.index..)?.chars().next(),
+                ))
+            })
+        };
+        let overtypes = carets.iter().all(|&cursor| {
+            self.auto_closers.contains(&cursor)
+                && char_at(cursor).is_some_and(|(_, after)| after == Some(typed))
+        });
+        if overtypes {
+            self.auto_closers.retain(|closer| !carets.contains(closer));
+            return (glyphon::Action::Motion(cosmic_text::Motion::Right), None);
+        }
+
+        shift_closers(&mut self.auto_closers, &carets, typed.len_utf8());
+        let (before, after) = char_at(self.editor.cursor()).unwrap_or_default();
+        (action, closer_for(&AUTO_CLOSE_PAIRS, typed, before, after))
+    }
+
+    /// Remembers the `closer` just inserted after every caret.
+    pub(crate) fn remember_auto_closers(&mut self, closer: char) {
+        let carets = self.caret_cursors();
+        shift_closers(&mut self.auto_closers, &carets, closer.len_utf8());
+        self.auto_closers.extend(carets);
+    }
+
+    /// Returns the cursors of the primary and every secondary caret.
+    fn caret_cursors(&self) -> Vec<Cursor> {
+        std::iter::once(self.editor.cursor())
+            .chain(self.secondary_carets.iter().map(|caret| caret.cursor))
+            .collect()
+    }
+
+    /// Returns the characters after which auto-indent adds an extra indent level.
+    pub fn indent_after(&self) -> &[char] {
+        &self.indent_after
+    }
+
+    /// Sets the characters after which auto-indent adds an extra indent level.
+    ///
+    /// Defaults to `{`, `[` and `(`. Has no effect unless auto-indent is on.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - Characters that open an indented block.
+    pub fn set_indent_after(&mut self, chars: Vec<char>) {
+        self.indent_after = chars;
+    }
+
+    /// Returns the number of columns per indent level.
+    pub fn tab_width(&self) -> usize {
+        self.tab_width
+    }
+
+    /// Sets the number of columns per indent level.
+    ///
+    /// This is the number of spaces Tab inserts when [`Self::insert_spaces`] is on, the most
+    /// spaces Shift+Tab removes, and the width a tab character is drawn with. Defaults to 4.
+    ///
+    /// # Arguments
+    ///
+    /// * `tab_width` - Columns per indent level, at least 1.
+    pub fn set_tab_width(&mut self, tab_width: usize) {
+        let tab_width = tab_width.max(1);
+        if tab_width != self.tab_width {
+            self.tab_width = tab_width;
+            let width = u16::try_from(tab_width).unwrap_or(u16::MAX);
+            self.editor.set_tab_width(&mut write_font_system(), width);
+        }
+    }
+
+    /// Returns `true` if Tab inserts spaces rather than a tab character.
+    pub fn insert_spaces(&self) -> bool {
+        self.insert_spaces
+    }
+
+    /// Makes Tab and auto-indent use spaces or tab characters.
+    ///
+    /// # Arguments
+    ///
+    /// * `insert_spaces` - `true` for spaces up to the next tab stop, `false` for `\t`.
+    pub fn set_insert_spaces(&mut self, insert_spaces: bool) {
+        self.insert_spaces = insert_spaces;
+    }
+
+    /// Returns `true` if typed characters replace the character after the cursor.
+    pub fn overwrite(&self) -> bool {
+        self.overwrite
+    }
+
+    /// Switches between insert and overwrite (overtype) mode. The Insert key toggles it.
+    ///
+    /// In overwrite mode a typed character replaces the one after the cursor, except at the end
+    /// of a line, and the cursor is drawn as a block over that character.
+    ///
+    /// # Arguments
+    ///
+    /// * `overwrite` - `true` for overwrite mode, `false` for insert mode.
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.overwrite = overwrite;
+    }
+
+    /// Returns `true` once after the Insert key toggled overwrite mode.
+    pub(crate) fn take_overwrite_changed(&mut self) -> bool {
+        std::mem::take(&mut self.overwrite_changed)
+    }
+
+    /// Returns the position of the primary caret once after it moved, by user input or by a
+    /// call such as [`Self::go_to`], and on the first call. A caret moving within a
+    /// composition is not reported.
+    pub(crate) fn take_caret_moved(&mut self) -> Option<TextPosition> {
+        let cursor = self.editor.cursor();
+        if self.preedit.is_some() || self.reported_caret == Some(cursor) {
+            return None;
+        }
+        self.reported_caret = Some(cursor);
+        Some(self.caret())
+    }
+
+    /// Returns `true` if the editor behaves as a single-line text field.
+    pub fn is_single_line(&self) -> bool {
+        self.single_line
+    }
+
+    /// Turns the editor into a single-line text field or back into a multi-line editor.
+    ///
+    /// In single-line mode Enter submits instead of breaking the line, Tab inserts nothing and
+    /// line breaks in pasted text become spaces. Content set programmatically is not changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `single_line` - Whether the editor is a single-line text field.
+    pub fn set_single_line(&mut self, single_line: bool) {
+        self.single_line = single_line;
+    }
+
+    /// Returns `true` once after Enter was pressed in single-line mode.
+    pub(crate) fn take_submit_requested(&mut self) -> bool {
+        std::mem::take(&mut self.submit_requested)
+    }
+
+    /// Prepares every caret for a movement. With `extend`, carets without a selection start
+    /// one where they are, so the movement selects the text it passes. Otherwise selections
+    /// are dropped.
+    fn anchor_selections(&mut self, extend: bool) {
+        let anchored = |selection: Selection, cursor: Cursor| match selection {
+            Selection::None if extend => Selection::Normal(cursor),
+            _ if !extend => Selection::None,
+            selection => selection,
+        };
+        let selection = anchored(self.editor.selection(), self.editor.cursor());
+        self.editor.set_selection(selection);
+        for caret in &mut self.secondary_carets {
+            caret.selection = anchored(caret.selection, caret.cursor);
+        }
+    }
+
+    /// In overwrite mode, selects the character after every caret without a selection, so that
+    /// inserting `action`'s character replaces it.
+    pub(crate) fn select_overwritten(&mut self, action: &glyphon::Action) {
+        if !self.overwrite || !matches!(action, glyphon::Action::Insert(c) if *c != '\n') {
+            return;
+        }
+        let next_char = |editor: &glyphon::Editor, cursor: Cursor| {
+            editor.with_buffer(|buffer| {
+                let line = buffer.lines.get(cursor.line)?.text();
+                let c = line.get(cursor.index..)?.chars().next()?;
+                Some(Cursor::new(cursor.line, cursor.index + c.len_utf8()))
+            })
+        };
+
+        if self.editor.selection() == Selection::None {
+            let cursor = self.editor.cursor();
+            if let Some(end) = next_char(&self.editor, cursor) {
+                self.editor.set_selection(Selection::Normal(cursor));
+                self.editor.set_cursor(end);
+            }
+        }
+        for caret in &mut self.secondary_carets {
+            if caret.selection == Selection::None
+                && let Some(end) = next_char(&self.editor, caret.cursor)
+            {
+                caret.selection = Selection::Normal(caret.cursor);
+                caret.cursor = end;
+            }
+        }
+    }
+
+    /// Returns the text inserted for one indent level.
+    fn indent_unit(&self) -> String {
+        if self.insert_spaces {
+            " ".repeat(self.tab_width)
+        } else {
+            "\t".to_string()
+        }
+    }
+
+    /// Returns the first and last line touched by the selection, or the cursor line without
+    /// one. A selection that ends at the start of a line does not touch that line.
+    fn selected_lines(&self) -> (usize, usize) {
+        let cursor = self.editor.cursor();
+        let (start, end) = self.editor.selection_bounds().unwrap_or((cursor, cursor));
+        let last_line = if end.line > start.line && end.index == 0 {
+            end.line - 1
+        } else {
+            end.line
+        };
+        (start.line, last_line)
+    }
+
+    /// Duplicates the selected text after the selection and selects the copy, or without a
+    /// selection duplicates the cursor line below it and moves the cursor to the copy.
+    ///
+    /// The change is a single undo step. Returns `true` if the content changed.
+    pub fn duplicate_lines(&mut self) -> bool {
+        let before = self.snapshot();
+        let cursor = self.editor.cursor();
+        let new_cursor = if let Some((_, end)) = self.editor.selection_bounds() {
+            let Some(copy) = self.editor.copy_selection() else {
+                return false;
+            };
+            let copy_end = insert_text_at(&mut self.editor, end, &copy);
+            self.break_inserted_lines(end.line, copy_end.line);
+            self.editor.set_selection(Selection::Normal(end));
+            copy_end
+        } else {
+            let line = self
+                .editor
+                .with_buffer(|buffer| buffer.lines.get(cursor.line).map(|l| l.text().to_string()));
+            let Some(line) = line.filter(|_| !self.single_line) else {
+                return false;
+            };
+            let line_end = Cursor::new(cursor.line, line.len());
+            insert_text_at(&mut self.editor, line_end, &format!("\n{line}"));
+            self.break_inserted_lines(cursor.line, cursor.line + 1);
+            Cursor::new(cursor.line + 1, cursor.index)
+        };
+        self.editor.set_cursor(new_cursor);
+
+        self.mark_changed(&before.text);
+        self.record_change(before, new_cursor);
+        self.secondary_carets.clear();
+        self.refresh_search();
+        true
+    }
+
+    /// Ends the lines from `first` to before `last` with a line break where they have none.
+    ///
+    /// Text inserted into a line gets its ending on every line, so text inserted into the last
+    /// line, which has none, otherwise loses its line breaks.
+    fn break_inserted_lines(&mut self, first: usize, last: usize) {
+        self.editor.with_buffer_mut(|buffer| {
+            for line in buffer.lines.iter_mut().take(last).skip(first) {
+                if line.ending() == cosmic_text::LineEnding::None {
+                    line.set_ending(cosmic_text::LineEnding::default());
+                }
+            }
+        });
+    }
+
+    /// Moves the lines touched by the selection, or the cursor line without one, up or down by
+    /// one line, keeping the cursor and selection on them.
+    ///
+    /// The change is a single undo step. Returns `true` if the lines moved, which they do not
+    /// past the start or end of the content.
+    ///
+    /// # Arguments
+    ///
+    /// * `up` - `true` to move the lines up, `false` to move them down.
+    pub fn move_lines(&mut self, up: bool) -> bool {
+        let (first, last) = self.selected_lines();
+        let line_text = |editor: &glyphon::Editor, line_i: usize| {
+            editor.with_buffer(|buffer| buffer.lines.get(line_i).map(|l| l.text().to_string()))
+        };
+        let (lo, hi) = if up {
+            (first.saturating_sub(1), last)
+        } else {
+            (first, last + 1)
+        };
+        if (up && first == 0) || line_text(&self.editor, hi).is_none() {
+            return false;
+        }
+        // The lines swap content but the line endings stay in place, so that the last line
+        // keeps having none
+        let endings: Vec<_> = self.editor.with_buffer(|buffer| {
+            buffer.lines[lo..=hi]
+                .iter()
+                .map(|line| line.ending())
+                .collect()
+        });
+        let before = self.snapshot();
+        if up {
+            let above = lo;
+            let Some(moved_over) = line_text(&self.editor, above) else {
+                return false;
+            };
+            // Take out the line above and put it back below the moved lines
+            self.editor
+                .delete_range(Cursor::new(above, 0), Cursor::new(first, 0));
+            let Some(last_text) = line_text(&self.editor, last - 1) else {
+                return false;
+            };
+            insert_text_at(
+                &mut self.editor,
+                Cursor::new(last - 1, last_text.len()),
+                &format!("\n{moved_over}"),
+            );
+        } else {
+            let (Some(last_text), Some(moved_over)) = (
+                line_text(&self.editor, last),
+                line_text(&self.editor, last + 1),
+            ) else {
+                return false;
+            };
+            // Take out the line below and put it back above the moved lines
+            self.editor.delete_range(
+                Cursor::new(last, last_text.len()),
+                Cursor::new(last + 1, moved_over.len()),
+            );
+            insert_text_at(
+                &mut self.editor,
+                Cursor::new(first, 0),
+                &format!("{moved_over}\n"),
+            );
+        }
+        self.editor.with_buffer_mut(|buffer| {
+            for (line, ending) in buffer.lines[lo..=hi].iter_mut().zip(endings) {
+                line.set_ending(ending);
+            }
+        });
+
+        // Carets on the moved lines move along, a selection ending at the start of the line
+        // after them keeps ending right after them
+        let (new_first, new_last) = if up {
+            (first - 1, last - 1)
+        } else {
+            (first + 1, last + 1)
+        };
+        let shift = |c: Cursor| {
+            let moved = if (first..=last).contains(&c.line) {
+                Cursor::new(c.line + new_first - first, c.index)
+            } else if c.line == last + 1 && c.index == 0 {
+                match line_text(&self.editor, new_last + 1) {
+                    Some(_) => Cursor::new(new_last + 1, 0),
+                    None => Cursor::new(new_last, usize::MAX),
+                }
+            } else {
+                c
+            };
+            self.clamped_cursor(moved)
+        };
+        let new_cursor = shift(before.cursor);
+        let new_selection = match before.selection {
+            Selection::Normal(c) => Selection::Normal(shift(c)),
+            Selection::Line(c) => Selection::Line(shift(c)),
+            Selection::Word(c) => Selection::Word(shift(c)),
+            Selection::None => Selection::None,
+        };
+        self.editor.set_cursor(new_cursor);
+        self.editor.set_selection(new_selection);
+
+        self.mark_changed(&before.text);
+        self.record_change(before, new_cursor);
+        self.secondary_carets.clear();
+        self.refresh_search();
+        true
+    }
+
+    /// Returns the key bindings of the editor commands.
+    pub fn keymap(&self) -> &EditorKeymap {
+        &self.keymap
+    }
+
+    /// Sets the key bindings of the editor commands, dropping a partly typed sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `keymap` - The bindings, e.g. [`EditorKeymap::default`] with some changed.
+    pub fn set_keymap(&mut self, keymap: EditorKeymap) {
+        self.keymap = keymap;
+    }
+
+    /// Runs `command` as if its key was pressed, returning the actions it leaves to the
+    /// caller like [`Self::map_key_event_to_action`].
+    ///
+    /// Commands that edit the text do nothing in a read-only editor.
+    pub fn run_command(
+        &mut self,
+        command: EditorCommand,
+        clipboard: &mut Clipboard,
+    ) -> Option<Vec<glyphon::Action>> {
+        let edits = matches!(
+            command,
+            EditorCommand::Undo
+                | EditorCommand::Redo
+                | EditorCommand::ToggleComment
+                | EditorCommand::DuplicateLines
+                | EditorCommand::MoveLinesUp
+                | EditorCommand::MoveLinesDown
+                | EditorCommand::ToggleOverwrite
+        );
+        if edits && self.read_only {
+            return None;
+        }
+        match command {
+            EditorCommand::Copy => return self.clipboard_actions(EditCommand::Copy, clipboard),
+            EditorCommand::Cut => return self.clipboard_actions(EditCommand::Cut, clipboard),
+            EditorCommand::Paste => return self.clipboard_actions(EditCommand::Paste, clipboard),
+            EditorCommand::SelectAll => self.select_all(),
+            EditorCommand::Undo => {
+                self.undo();
+            }
+            EditorCommand::Redo => {
+                self.redo();
+            }
+            EditorCommand::AddNextOccurrence => {
+                self.add_next_occurrence();
+            }
+            EditorCommand::NavigateBack => {
+                self.jump_back();
+            }
+            EditorCommand::NavigateForward => {
+                self.jump_forward();
+            }
+            EditorCommand::GoToLine => {
+                if self.go_to_line_enabled {
+                    self.go_to_line = Some(String::new());
+                }
+            }
+            EditorCommand::ExpandSelection => {
+                self.expand_selection();
+            }
+            EditorCommand::ShrinkSelection => {
+                self.shrink_selection();
+            }
+            EditorCommand::ZoomIn => self.zoom_at(self.zoom * ZOOM_STEP, 0.0),
+            EditorCommand::ZoomOut => self.zoom_at(self.zoom / ZOOM_STEP, 0.0),
+            EditorCommand::ZoomReset => self.zoom_at(1.0, 0.0),
+            EditorCommand::Fold => {
+                self.fold_indented(self.editor.cursor().line);
+            }
+            EditorCommand::Unfold => {
+                self.unfold(self.editor.cursor().line);
+            }
+            EditorCommand::ToggleComment => {
+                self.toggle_comment();
+            }
+            EditorCommand::DuplicateLines => {
+                self.duplicate_lines();
+            }
+            EditorCommand::MoveLinesUp => {
+                self.move_lines(true);
+            }
+            EditorCommand::MoveLinesDown => {
+                self.move_lines(false);
+            }
+            EditorCommand::FindNext => {
+                self.find_next();
+            }
+            EditorCommand::FindPrevious => {
+                self.find_previous();
+            }
+            EditorCommand::ToggleOverwrite => {
+                self.overwrite = !self.overwrite;
+                self.overwrite_changed = true;
+            }
+            EditorCommand::Speak => {
+                self.speak();
+            }
+        }
+        None
+    }
+
+    /// Returns the token that starts a line comment, if one is set.
+    pub fn line_comment(&self) -> Option<&str> {
+        self.line_comment.as_deref()
+    }
+
+    /// Sets the token that starts a line comment, e.g. `//` or `#`, which Ctrl+/ adds to or
+    /// removes from the selected lines. Without one Ctrl+/ does nothing.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The line-comment token, or `None`.
+    pub fn set_line_comment(&mut self, token: Option<String>) {
+        self.line_comment = token;
+    }
+
+    /// Returns the language of the content, if known.
+    pub fn language(&self) -> Option<&'static Language> {
+        self.language
+    }
+
+    /// Sets the language of the content, e.g. one found with [`Self::detect_language`].
+    ///
+    /// # Arguments
+    ///
+    /// * `language` - The language, or `None` if it is unknown.
+    pub fn set_language(&mut self, language: Option<&'static Language>) {
+        self.language = language;
+    }
+
+    /// Returns the language of the content as the file `file_name`: from the name if it
+    /// tells, otherwise from the first line, see [`Language::detect`].
+    pub fn detect_language(&self, file_name: Option<&str>) -> Option<&'static Language> {
+        file_name.and_then(Language::by_filename).or_else(|| {
+            self.editor.with_buffer(|buffer| {
+                Language::by_first_line(buffer.lines.first().map_or("", |line| line.text()))
+            })
+        })
+    }
+
+    /// Comments or uncomments every line touched by the selection, or the cursor line without
+    /// one, with the [line-comment token](Self::set_line_comment).
+    ///
+    /// The lines are uncommented if all of them that are not blank start with the token, and
+    /// commented otherwise. The change is a single undo step.
+    ///
+    /// Returns `true` if the content changed.
+    pub fn toggle_comment(&mut self) -> bool {
+        let Some(token) = self.line_comment.clone() else {
+            return false;
+        };
+        let cursor = self.editor.cursor();
+        let (start, last_line) = self.selected_lines();
+
+        let edits = self.editor.with_buffer(|buffer| {
+            let lines: Vec<&str> = buffer
+                .lines
+                .iter()
+                .skip(start)
+                .take(last_line - start + 1)
+                .map(|line| line.text())
+                .collect();
+            toggle_comment_edits(&lines, start, &token)
+        });
+        if edits.is_empty() {
+            return false;
+        }
+
+        let before = self.snapshot();
+        for edit in &edits {
+            let at = Cursor::new(edit.line, edit.index);
+            if edit.removed > 0 {
+                self.editor
+                    .delete_range(at, Cursor::new(edit.line, edit.index + edit.removed));
+            }
+            insert_text_at(&mut self.editor, at, &edit.inserted);
+        }
+
+        let shift = |c: Cursor| Cursor {
+            index: shift_index(c.line, c.index, &edits),
+            ..c
+        };
+        let new_cursor = shift(cursor);
+        let new_selection = match self.editor.selection() {
+            Selection::Normal(c) => Selection::Normal(shift(c)),
+            Selection::Line(c) => Selection::Line(shift(c)),
+            Selection::Word(c) => Selection::Word(shift(c)),
+            Selection::None => Selection::None,
+        };
+        self.editor.set_cursor(new_cursor);
+        self.editor.set_selection(new_selection);
+
+        self.mark_changed(&before.text);
+        self.record_change(before, new_cursor);
+        self.secondary_carets.clear();
+        self.refresh_search();
+        true
+    }
+
+    /// Indents or dedents every line touched by the selection, or the cursor line without one.
+    ///
+    /// A selection that ends at the start of a line does not touch that line. The change is a
+    /// single undo step, and the cursor and selection move with the text they were on.
+    ///
+    /// Returns `true` if the content changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dedent` - `true` to remove one indent level instead of adding one.
+    pub fn indent_lines(&mut self, dedent: bool) -> bool {
+        let cursor = self.editor.cursor();
+        let (start, last_line) = self.selected_lines();
+
+        let before = self.snapshot();
+        let unit = self.indent_unit();
+        // Bytes added (positive) or removed (negative) at the start of each line
+        let mut shifts = Vec::with_capacity(last_line - start + 1);
+        for line_i in start..=last_line {
+            let line = self
+                .editor
+                .with_buffer(|buffer| buffer.lines.get(line_i).map(|l| l.text().to_string()));
+            let Some(line) = line else {
+                break;
+            };
+            let line_start = Cursor::new(line_i, 0);
+            if dedent {
+                let len = dedent_len(&line, self.tab_width);
+                if len > 0 {
+                    self.editor
+                        .delete_range(line_start, Cursor::new(line_i, len));
+                }
+                shifts.push(-(len as isize));
+            } else if line.is_empty() && start != last_line {
+                // Blank lines inside a block stay blank
+                shifts.push(0);
+            } else {
+                insert_in_line(&mut self.editor, line_start, &unit);
+                shifts.push(unit.len() as isize);
+            }
+        }
+        if editor_content(&self.editor) == before.text {
+            return false;
+        }
+
+        let shift = |c: Cursor| -> Cursor {
+            let by = c
+                .line
+                .checked_sub(start)
+                .and_then(|i| shifts.get(i))
+                .copied()
+                .unwrap_or(0);
+            // Positions inside removed indentation collapse to the line start
+            let index = if by < 0 {
+                c.index.saturating_sub(by.unsigned_abs())
+            } else {
+                c.index + by as usize
+            };
+            Cursor { index, ..c }
+        };
+        let new_cursor = shift(cursor);
+        let new_selection = match self.editor.selection() {
+            Selection::Normal(c) => Selection::Normal(shift(c)),
+            Selection::Line(c) => Selection::Line(shift(c)),
+            Selection::Word(c) => Selection::Word(shift(c)),
+            Selection::None => Selection::None,
+        };
+        self.editor.set_cursor(new_cursor);
+        self.editor.set_selection(new_selection);
+
+        self.mark_changed(&before.text);
+        self.record_change(before, new_cursor);
+        self.secondary_carets.clear();
+        self.refresh_search();
+        true
+    }
+
+    /// Returns the auto-indent for a line break at the start of the selection or the cursor.
+    fn new_line_indent(&self) -> String {
+        let at = self
+            .editor
+            .selection_bounds()
+            .map_or(self.editor.cursor(), |(start, _)| start);
+        self.editor.with_buffer(|buffer| {
+            buffer.lines.get(at.line).map_or_else(String::new, |line| {
+                newline_indent(
+                    &line.text()[..at.index],
+                    &self.indent_after,
+                    &self.indent_unit(),
+                )
+            })
+        })
+    }
+
+    /// Returns the input mask applied to edits, if any.
+    pub fn input_mask(&self) -> Option<&InputMask> {
+        self.input_mask.as_ref()
+    }
+
+    /// Sets the input mask applied to edits. `None` removes formatting.
+    ///
+    /// The current content is not reformatted until the next edit.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - The new input mask.
+    pub fn set_input_mask(&mut self, mask: Option<InputMask>) {
+        self.input_mask = mask;
+    }
+
+    /// Returns `true` if the changes passed to `on_change` carry the whole content.
+    pub fn change_text(&self) -> bool {
+        self.change_text
+    }
+
+    /// Sets whether the changes passed to `on_change` carry the whole content in
+    /// [`TextChange::text`]. Off by default, as copying the content on every key press is
+    /// slow in large documents.
+    pub fn set_change_text(&mut self, change_text: bool) {
+        self.change_text = change_text;
+    }
+
+    /// Returns the content without the formatting added by the input mask.
+    ///
+    /// Without a mask this is the full content.
+    pub fn raw_text(&self) -> String {
+        let content = self.content();
+        match &self.input_mask {
+            Some(mask) => mask.raw(&content),
+            None => content,
+        }
+    }
+
+    /// Returns `true` if the content is empty.
+    pub fn is_empty(&self) -> bool {
+        self.editor.with_buffer(|buffer| {
+            buffer.lines.len() <= 1 && buffer.lines.iter().all(|line| line.text().is_empty())
+        })
+    }
+
+    /// Returns `true` if user input cannot change the content.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Makes the editor read-only or editable.
+    ///
+    /// A read-only editor still supports focus, selection, scrolling and copying, but ignores
+    /// typing, deletion, paste, undo/redo and IME input. Programmatic changes such as
+    /// [`Self::replace_all`] are not affected.
+    ///
+    /// # Arguments
+    ///
+    /// * `read_only` - Whether user input should be blocked.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Returns the carets in addition to the editor's own cursor.
+    ///
+    /// Edits and cursor movement from user input are applied at every caret.
+    pub fn secondary_carets(&self) -> &[Caret] {
+        &self.secondary_carets
+    }
+
+    /// Adds a caret at `cursor`, unless there already is one at that position.
+    ///
+    /// A position past the end of the content or of the line is moved back onto it like in
+    /// [`Self::set_cursor`].
+    ///
+    /// # Arguments
+    ///
+    /// * `cursor` - Position of the new caret.
+    pub fn add_caret(&mut self, cursor: Cursor) {
+        let caret = Caret {
+            cursor: self.clamped_cursor(cursor),
+            selection: Selection::None,
+        };
+        self.push_caret(caret);
+    }
+
+    /// Removes all secondary carets, keeping only the editor's own cursor.
+    pub fn clear_secondary_carets(&mut self) {
+        self.secondary_carets.clear();
+    }
+
+    /// Keeps the current primary cursor as a secondary caret, so the primary can move elsewhere.
+    pub(crate) fn keep_primary_as_caret(&mut self) {
+        let caret = Caret {
+            cursor: self.editor.cursor(),
+            selection: self.editor.selection(),
+        };
+        self.push_caret(caret);
+    }
+
+    /// Grows the selection to the smallest structure around it: the word, the contents of a
+    /// string, the string, the contents of the enclosing brackets, the brackets, the line
+    /// and finally the whole content. Returns `false` if it already covers everything.
+    ///
+    /// [`Self::shrink_selection`] steps back to the selections before.
+    pub fn expand_selection(&mut self) -> bool {
+        let current = self.selection_state();
+        if self.selection_history.last().map(|(_, expanded)| expanded) != Some(&current) {
+            self.selection_history.clear();
+        }
+        let (start, end) = self
+            .editor
+            .selection_bounds()
+            .unwrap_or((current.cursor, current.cursor));
+        let range = cursor_to_offset(&self.editor, start)..cursor_to_offset(&self.editor, end);
+        let content = editor_content(&self.editor);
+        let Some(expanded) = structure::expand_range(&content, range, &self.bracket_pairs) else {
+            return false;
+        };
+        self.select_range(
+            offset_to_cursor(&self.editor, expanded.start),
+            offset_to_cursor(&self.editor, expanded.end),
+        );
+        let expanded = self.selection_state();
+        self.selection_history.push((current, expanded));
+        true
+    }
+
+    /// Restores the selection before the last [`Self::expand_selection`]. Returns `false` if
+    /// the selection was not expanded or has changed since.
+    pub fn shrink_selection(&mut self) -> bool {
+        let current = self.selection_state();
+        match self.selection_history.pop() {
+            Some((previous, expanded)) if expanded == current => {
+                self.editor.set_selection(match previous.anchor {
+                    Some(anchor) => Selection::Normal(anchor),
+                    None => Selection::None,
+                });
+                self.editor.set_cursor(previous.cursor);
+                true
+            }
+            _ => {
+                self.selection_history.clear();
+                false
+            }
+        }
+    }
+
+    fn selection_state(&self) -> SelectionState {
+        SelectionState {
+            anchor: match self.editor.selection() {
+                Selection::Normal(anchor) => Some(anchor),
+                _ => None,
+            },
+            cursor: self.editor.cursor(),
+        }
+    }
+
+    /// Selects the next occurrence of the selected text with an additional caret.
+    ///
+    /// Without a selection, the word around the cursor is selected first. Returns `true` if the
+    /// selection changed or a caret was added.
+    pub fn add_next_occurrence(&mut self) -> bool {
+        let Some(selected) = self.editor.copy_selection().filter(|text| !text.is_empty()) else {
+            let cursor = self.editor.cursor();
+            let word = self.editor.with_buffer(|buffer| {
+                buffer
+                    .lines
+                    .get(cursor.line)
+                    .and_then(|line| word_at(line.text(), cursor.index))
+            });
+            let Some((start, end)) = word else {
+                return false;
+            };
+            self.editor
+                .set_selection(Selection::Normal(Cursor::new(cursor.line, start)));
+            self.editor.set_cursor(Cursor::new(cursor.line, end));
+            return true;
+        };
+        if selected.contains('\n') {
+            return false;
+        }
+
+        let matches = self.editor.with_buffer(|buffer| {
+            find_matches(buffer.lines.iter().map(|line| line.text()), &selected)
+        });
+        let occupied: Vec<(Cursor, Cursor)> = self
+            .secondary_carets
+            .iter()
+            .filter_map(Caret::selection_bounds)
+            .chain(self.editor.selection_bounds())
+            .collect();
+        // Continue after the furthest caret so repeated presses walk down the document
+        let furthest = occupied
+            .iter()
+            .map(|(_, end)| *end)
+            .max_by_key(|end| (end.line, end.index))
+            .unwrap_or(self.editor.cursor());
+        let free: Vec<TextRange> = matches
+            .into_iter()
+            .filter(|m| !occupied.contains(&(m.start, m.end)))
+            .collect();
+        let Some(found) = next_match(&free, furthest) else {
+            return false;
+        };
+
+        self.keep_primary_as_caret();
+        self.editor.set_selection(Selection::Normal(found.start));
+        self.editor.set_cursor(found.end);
+        true
+    }
+
+    fn push_caret(&mut self, caret: Caret) {
+        let taken = caret.cursor == self.editor.cursor()
+            || self
+                .secondary_carets
+                .iter()
+                .any(|c| c.cursor == caret.cursor);
+        if !taken {
+            self.secondary_carets.push(caret);
+        }
+    }
+
+    fn select_match(&mut self, found: TextRange) {
+        let from = self.editor.cursor();
+        self.reveal_line(found.start.line);
+        self.reveal_line(found.end.line);
+        self.secondary_carets.clear();
+        self.editor.set_selection(Selection::Normal(found.start));
+        self.editor.set_cursor(found.end);
+        self.record_jump_from(from, 1);
+    }
+
+    /// Replaces the content with `text` in the editor font, keeping the scroll position.
+    pub(crate) fn set_text_reactive(&mut self, text: &str) {
+        // The composition is dropped with the content it was shown in
+        self.preedit = None;
+        self.editor.set_text_reactive(
+            text,
+            &mut write_font_system(),
+            &editor_attrs(&self.font_family, &self.font_features),
+        );
+        // glyphon ends the single line of empty text with a line break
+        if text.is_empty() {
+            self.editor.with_buffer_mut(|buffer| {
+                if let [line] = buffer.lines.as_mut_slice() {
+                    line.set_ending(cosmic_text::LineEnding::None);
+                }
+            });
+        }
+    }
+
+    fn refresh_search(&mut self) {
+        self.search_matches = match &self.search_pattern {
+            Some(pattern) => self.editor.with_buffer(|buffer| {
+                find_matches(buffer.lines.iter().map(|line| line.text()), pattern)
+            }),
+            None => Vec::new(),
+        };
+    }
+
+    /// Applies `action` at every caret the way typed input is, with auto-closing brackets,
+    /// the input mask and an undo step. Edits are ignored while the editor is read-only.
+    pub fn apply_action(&mut self, action: glyphon::Action) {
+        if self.read_only && is_editing_action(&action) {
+            return;
+        }
+        input_handler::handle_action(self, action, Arc::new(|_| None));
+    }
+
+    /// Inserts `text` at every caret in place of its selection, the way pasted text is.
+    ///
+    /// Unlike typing it, the whole text is a single undo step and brackets in it are not
+    /// closed automatically. In single-line mode line breaks become spaces. Returns `true` if
+    /// the content changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to insert.
+    pub fn insert_text(&mut self, text: &str) -> bool {
+        self.clear_preedit();
+        let text = match self.single_line {
+            true => text.lines().collect::<Vec<_>>().join(" "),
+            false => text.to_string(),
+        };
+        let before = editor_content(&self.editor);
+        let carets = self.carets();
+        let lines = match self.input_mask {
+            Some(_) => 0..=usize::MAX,
+            None => self.caret_lines(),
+        };
+        let first_line = *lines.start();
+        let window = self
+            .editor
+            .with_buffer(|buffer| EditWindow::new(&buffer.lines, lines));
+        // The remembered closers only follow typing
+        self.auto_closers.clear();
+
+        let mut secondary = std::mem::take(&mut self.secondary_carets);
+        insert_at_carets(&mut self.editor, &mut secondary, &text);
+        self.secondary_carets = secondary;
+        let line_count = self.editor.with_buffer(|buffer| buffer.lines.len());
+        self.break_inserted_lines(first_line, line_count - 1);
+        self.apply_input_mask();
+
+        let edit = self.editor.with_buffer(|buffer| window.edit(&buffer.lines));
+        let changed = edit.is_some();
+        if changed {
+            self.mark_changed(&before);
+        }
+        self.record_edit(edit, carets, window.line_count(), EditKind::Other);
+        changed
+    }
+
+    /// Reformats the content through the input mask, if any, keeping the caret on the same
+    /// character.
+    pub(crate) fn apply_input_mask(&mut self) {
+        let Some(mask) = self.input_mask.clone() else {
+            return;
+        };
+        let content = editor_content(&self.editor);
+        let caret = cursor_to_offset(&self.editor, self.editor.cursor());
+        let (formatted, formatted_caret) = mask.format_with_caret(&content, caret);
+        if formatted != content {
+            self.set_text_reactive(&formatted);
+            let cursor = offset_to_cursor(&self.editor, formatted_caret);
+            self.editor.set_cursor(cursor);
+        }
+    }
+
+    /// Applies the IME composition or commit `event` the way the editor does while it has
+    /// focus. Ignored while the editor is read-only.
+    pub fn apply_ime(&mut self, event: winit::event::Ime) {
+        if !self.read_only {
+            input_handler::handle_ime(self, event, Arc::new(|_| None));
+        }
+    }
+
+    /// Map keyboard events to text editing actions
+    /// Maps a keyboard event to a list of text editing actions for the editor.
+    ///
+    /// This function translates keyboard input (including modifiers) into editing actions
+    /// such as character insertion, deletion, navigation, and clipboard operations.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_event` - The keyboard event to map.
+    /// * `key_modifiers` - The current keyboard modifier state.
+    /// * `clipboard` - Mutable reference to the clipboard for clipboard operations.
+    ///
+    /// # Returns
+    ///
+    /// An optional vector of `glyphon::Action` to be applied to the editor.
+    pub fn map_key_event_to_action(
+        &mut self,
+        key_event: winit::event::KeyEvent,
+        key_modifiers: winit::keyboard::ModifiersState,
+        clipboard: &mut Clipboard,
+    ) -> Option<Vec<glyphon::Action>> {
+        match key_event.state {
+            winit::event::ElementState::Pressed => {}
+            winit::event::ElementState::Released => return None,
+        }
+
+        match self.keymap.press(&key_event, key_modifiers) {
+            ChordMatch::Matched { action, repeat } => {
+                if repeat && !action.repeats() {
+                    return None;
+                }
+                return self.run_command(action, clipboard);
+            }
+            // The keys of an unfinished or abandoned sequence do nothing else
+            ChordMatch::Pending | ChordMatch::Cancelled => return None,
+            ChordMatch::Unmatched => {}
+        }
+        let editor = &mut self.editor;
+
+        // Shift turns every caret movement into extending the selection
+        if let winit::keyboard::Key::Named(named_key) = key_event.logical_key
+            && key_modifiers.shift_key()
+            && let Some(motion) = navigation_motion(named_key, key_modifiers.control_key())
+        {
+            self.anchor_selections(true);
+            let editor = &self.editor;
+            let last_line = editor.with_buffer(|buffer| buffer.lines.len().saturating_sub(1));
+            // Like without Shift, moving up on the first line goes to its start and moving
+            // down on the last line to its end
+            let motion = match motion {
+                cosmic_text::Motion::Up if editor.cursor().line == 0 => {
+                    cosmic_text::Motion::BufferStart
+                }
+                cosmic_text::Motion::Down if editor.cursor().line >= last_line => {
+                    cosmic_text::Motion::BufferEnd
+                }
+                motion => motion,
+            };
+            return Some(vec![glyphon::Action::Motion(motion)]);
+        }
+
+        match key_event.logical_key {
+            winit::keyboard::Key::Named(named_key) => match named_key {
+                NamedKey::Backspace => Some(vec![glyphon::Action::Backspace]),
+                NamedKey::Delete => Some(vec![glyphon::Action::Delete]),
+                NamedKey::Enter => {
+                    if self.single_line {
+                        self.submit_requested = true;
+                        return None;
+                    }
+                    let mut actions = vec![glyphon::Action::Enter];
+                    if self.auto_indent {
+                        actions.extend(self.new_line_indent().chars().map(glyphon::Action::Insert));
+                    }
+                    Some(actions)
+                }
+                NamedKey::Escape => {
+                    if !self.secondary_carets.is_empty() {
+                        self.secondary_carets.clear();
+                        return None;
+                    }
+                    Some(vec![glyphon::Action::Escape])
+                }
+                NamedKey::Tab => {
+                    if self.read_only || self.single_line {
+                        return None;
+                    }
+                    if key_modifiers.shift_key() || self.editor.selection_bounds().is_some() {
+                        self.indent_lines(key_modifiers.shift_key());
+                        return None;
+                    }
+                    if !self.insert_spaces {
+                        return Some(vec![glyphon::Action::Insert('\t')]);
+                    }
+                    let cursor = self.editor.cursor();
+                    let count = self.editor.with_buffer(|buffer| {
+                        buffer
+                            .lines
+                            .get(cursor.line)
+                            .map_or(self.tab_width, |line| {
+                                spaces_to_tab_stop(&line.text()[..cursor.index], self.tab_width)
+                            })
+                    });
+                    Some(vec![glyphon::Action::Insert(' '); count])
+                }
+                NamedKey::ArrowLeft => {
+                    if key_modifiers.control_key() {
+                        editor.set_selection(Selection::None);
+
+                        Some(vec![glyphon::Action::Motion(cosmic_text::Motion::LeftWord)])
+                    } else {
+                        // if we have selected text, we need to clear it and not perform any action
+                        if editor.selection_bounds().is_some() {
+                            editor.set_selection(Selection::None);
+
+                            return None;
+                        }
+
+                        Some(vec![glyphon::Action::Motion(cosmic_text::Motion::Left)])
+                    }
+                }
+                NamedKey::ArrowRight => {
+                    if key_modifiers.control_key() {
+                        editor.set_selection(Selection::None);
+
+                        Some(vec![glyphon::Action::Motion(
+                            cosmic_text::Motion::RightWord,
+                        )])
+                    } else {
+                        if editor.selection_bounds().is_some() {
+                            editor.set_selection(Selection::None);
+
+                            return None;
+                        }
+
+                        Some(vec![glyphon::Action::Motion(cosmic_text::Motion::Right)])
+                    }
+                }
+                NamedKey::ArrowUp => {
+                    // if we are on the first line, we move the cursor to the beginning of the line
+                    if editor.cursor().line == 0 {
+                        editor.set_cursor(Cursor::new(0, 0));
+
+                        return None;
+                    }
+
+                    Some(vec![glyphon::Action::Motion(cosmic_text::Motion::Up)])
+                }
+                NamedKey::ArrowDown => {
+                    let last_line_index =
+                        editor.with_buffer(|buffer| buffer.lines.len().saturating_sub(1));
+
+                    // if we are on the last line, we move the cursor to the end of the line
+                    if editor.cursor().line >= last_line_index {
+                        let last_col =
+                            editor.with_buffer(|buffer| buffer.lines[last_line_index].text().len());
+
+                        editor.set_cursor(Cursor::new(last_line_index, last_col));
+                        return None;
+                    }
+
+                    Some(vec![glyphon::Action::Motion(cosmic_text::Motion::Down)])
+                }
+                NamedKey::Home | NamedKey::End | NamedKey::PageUp | NamedKey::PageDown => {
+                    let motion = navigation_motion(named_key, key_modifiers.control_key())?;
+                    self.anchor_selections(false);
+                    Some(vec![glyphon::Action::Motion(motion)])
+                }
+                NamedKey::Space => Some(vec![glyphon::Action::Insert(' ')]),
+                _ => None,
+            },
+
+            winit::keyboard::Key::Character(s) => {
+                Some(s.chars().map(glyphon::Action::Insert).collect::<Vec<_>>())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the caret movement of a navigation key, with Ctrl held for `ctrl`.
+fn navigation_motion(key: NamedKey, ctrl: bool) -> Option<cosmic_text::Motion> {
+    use cosmic_text::Motion;
+    Some(match key {
+        NamedKey::ArrowLeft if ctrl => Motion::LeftWord,
+        NamedKey::ArrowLeft => Motion::Left,
+        NamedKey::ArrowRight if ctrl => Motion::RightWord,
+        NamedKey::ArrowRight => Motion::Right,
+        NamedKey::ArrowUp => Motion::Up,
+        NamedKey::ArrowDown => Motion::Down,
+        NamedKey::Home if ctrl => Motion::BufferStart,
+        NamedKey::Home => Motion::Home,
+        NamedKey::End if ctrl => Motion::BufferEnd,
+        NamedKey::End => Motion::End,
+        NamedKey::PageUp => Motion::PageUp,
+        NamedKey::PageDown => Motion::PageDown,
+        _ => return None,
+    })
+}
+
+impl ScrollTarget for RwLock<TextEditorState> {
+    fn target_position(&self) -> PxPosition {
+        let state = self.read();
+        PxPosition::new(-state.horizontal_scroll(), -state.scroll_offset())
+    }
+
+    fn set_target_position(&self, position: PxPosition) {
+        let mut state = self.write();
+        let vertical = -position.y - state.scroll_offset();
+        let horizontal = -position.x - state.horizontal_scroll();
+        if vertical != Px(0) {
+            // Scrolling by pixels would shape every line passed over, which makes dragging the
+            // scrollbar through a long document slow, so the target line is looked up in the
+            // heights of the last layout.
+            let (line, offset) =
+                viewport::line_at_offset(&state.line_heights, (-position.y).0.max(0) as f32);
+            state.editor.with_buffer_mut(|buffer| {
+                let mut scroll = buffer.scroll();
+                scroll.line = line;
+                scroll.vertical = offset;
+                buffer.set_scroll(scroll);
+            });
+            // Layout updates the offset only next frame, further moves this frame build on it
+            state.scroll_offset += vertical;
+        }
+        if horizontal != Px(0) {
+            state.scroll_horizontally(horizontal.to_f32());
+        }
+    }
+}
 
-            winit::keyboard::Key::Character(s) => {
-                let is_ctrl = key_modifiers.control_key() || key_modifiers.super_key();
-                if is_ctrl {
-                    match s.to_lowercase().as_str() {
-                        "c" => {
-                            if let Some(text) = editor.copy_selection() {
-                                clipboard.set_text(&text);
-                            }
-                            return None;
-                        }
-                        "v" => {
-                            if let Some(text) = clipboard.get_text() {
-                                return Some(text.chars().map(glyphon::Action::Insert).collect());
-                            }
+/// Returns the total height of all lines in `buffer` and its vertical scroll offset, in pixels,
+/// given the `heights` of its lines.
+fn content_metrics(buffer: &glyphon::Buffer, heights: &[f32]) -> (f32, f32) {
+    let scroll = buffer.scroll();
+    let height = heights.iter().sum::<f32>();
+    let scroll_offset = match heights.get(..scroll.line) {
+        Some(above) => above.iter().sum::<f32>() + scroll.vertical,
+        None => height,
+    };
+    (height, scroll_offset.max(0.0))
+}
 
-                            return None;
-                        }
-                        "x" => {
-                            if let Some(text) = editor.copy_selection() {
-                                clipboard.set_text(&text);
-                                // Use Backspace action to delete selection
-                                return Some(vec![glyphon::Action::Backspace]);
-                            }
-                            return None;
-                        }
-                        _ => {}
-                    }
-                }
-                Some(s.chars().map(glyphon::Action::Insert).collect::<Vec<_>>())
+/// Returns the full content of the editor, including line endings.
+pub(crate) fn editor_content(editor: &glyphon::Editor) -> String {
+    editor.with_buffer(|buffer| {
+        buffer
+            .lines
+            .iter()
+            .map(|line| line.text().to_string() + line.ending().as_str())
+            .collect::<String>()
+    })
+}
+
+/// Returns a copy of the buffer of `editor`.
+fn owned_buffer(editor: &glyphon::Editor) -> glyphon::Buffer {
+    match editor.buffer_ref() {
+        glyphon::cosmic_text::BufferRef::Owned(buffer) => buffer.clone(),
+        glyphon::cosmic_text::BufferRef::Borrowed(buffer) => (**buffer).to_owned(),
+        glyphon::cosmic_text::BufferRef::Arc(buffer) => (**buffer).clone(),
+    }
+}
+
+/// Inserts `text` at `cursor` like [`glyphon::Editor::insert_at`] and returns the cursor
+/// after it.
+///
+/// `insert_at` drops a control character at the end of the inserted text, meant for line
+/// breaks, so trailing tabs are put into the line after the rest.
+pub(crate) fn insert_text_at(editor: &mut glyphon::Editor, cursor: Cursor, text: &str) -> Cursor {
+    let rest = text.trim_end_matches('\t');
+    let end = editor.insert_at(cursor, rest, None);
+    match rest.len() < text.len() {
+        true => insert_in_line(editor, end, &text[rest.len()..]),
+        false => end,
+    }
+}
+
+/// Inserts `text`, which has no line breaks, at `cursor` and returns the cursor after it.
+///
+/// Unlike [`glyphon::Editor::insert_at`], which drops a control character at the end of the
+/// inserted text to strip line breaks, this keeps a trailing tab.
+pub(crate) fn insert_in_line(editor: &mut glyphon::Editor, cursor: Cursor, text: &str) -> Cursor {
+    editor.with_buffer_mut(|buffer| {
+        let Some(line) = buffer.lines.get_mut(cursor.line) else {
+            return;
+        };
+        let attrs = cosmic_text::AttrsList::new(
+            &line.attrs_list().get_span(cursor.index.saturating_sub(1)),
+        );
+        let after = line.split_off(cursor.index);
+        let inserted = cosmic_text::BufferLine::new(
+            text,
+            line.ending(),
+            attrs,
+            cosmic_text::Shaping::Advanced,
+        );
+        line.append(&inserted);
+        line.append(&after);
+    });
+    Cursor::new(cursor.line, cursor.index + text.len())
+}
+
+/// The selection of the primary caret: where it started, if anywhere, and the cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SelectionState {
+    anchor: Option<Cursor>,
+    cursor: Cursor,
+}
+
+/// Converts a cursor into a byte offset into [`editor_content`].
+pub(crate) fn cursor_to_offset(editor: &glyphon::Editor, cursor: Cursor) -> usize {
+    editor.with_buffer(|buffer| {
+        let preceding: usize = buffer
+            .lines
+            .iter()
+            .take(cursor.line)
+            .map(|line| line.text().len() + line.ending().as_str().len())
+            .sum();
+        preceding + cursor.index
+    })
+}
+
+/// Converts a byte offset into [`editor_content`] into a cursor, clamping it to the content.
+pub(crate) fn offset_to_cursor(editor: &glyphon::Editor, offset: usize) -> Cursor {
+    editor.with_buffer(|buffer| {
+        let mut remaining = offset;
+        for (line_i, line) in buffer.lines.iter().enumerate() {
+            let len = line.text().len();
+            if remaining <= len {
+                return Cursor::new(line_i, remaining);
             }
-            _ => None,
+            remaining = remaining.saturating_sub(len + line.ending().as_str().len());
         }
-    }
+        let last = buffer.lines.len().saturating_sub(1);
+        Cursor::new(
+            last,
+            buffer.lines.get(last).map_or(0, |line| line.text().len()),
+        )
+    })
+}
+
+/// Returns `true` if `action` may change the content rather than only the cursor, selection or scroll.
+pub(crate) fn is_editing_action(action: &glyphon::Action) -> bool {
+    matches!(
+        action,
+        glyphon::Action::Insert(_)
+            | glyphon::Action::Enter
+            | glyphon::Action::Backspace
+            | glyphon::Action::Delete
+            | glyphon::Action::Indent
+            | glyphon::Action::Unindent
+    )
+}
+
+/// Text attributes used when replacing the editor content.
+pub(crate) fn editor_attrs<'a>(
+    family: &'a FontFamily,
+    features: &FontFeatures,
+) -> glyphon::Attrs<'a> {
+    glyphon::Attrs::new()
+        .family(family.to_glyphon())
+        .font_features(features.to_glyphon())
+}
+
+/// Returns the metrics of a font of `size` with `line_height`, or 1.2x the size without one.
+fn font_metrics(size: Dp, line_height: Option<Dp>) -> glyphon::Metrics {
+    let line_height: Px = line_height.unwrap_or(Dp(size.0 * 1.2)).into();
+    glyphon::Metrics::new(size.to_pixels_f32(), line_height.to_f32())
+}
+
+/// Converts `color` to the color type of the text layout.
+fn glyphon_color(color: Color) -> glyphon::Color {
+    glyphon::Color::rgba(
+        (color.r * 255.0) as u8,
+        (color.g * 255.0) as u8,
+        (color.b * 255.0) as u8,
+        (color.a * 255.0) as u8,
+    )
+}
+
+/// Attributes of folded lines, which collapse them to zero height and draw nothing.
+fn folded_attrs() -> glyphon::Attrs<'static> {
+    glyphon::Attrs::new()
+        .metrics(glyphon::Metrics::new(1.0, 0.0))
+        .color(glyphon::Color::rgba(0, 0, 0, 0))
+}
+
+/// Computes the markers drawn after the header line of each visible fold.
+fn compute_fold_marker_rects(
+    editor: &glyphon::Editor,
+    folds: &[(usize, usize)],
+) -> Vec<(RectDef, usize)> {
+    let line_height = editor.with_buffer(|buffer| buffer.metrics().line_height);
+    let quarter = Px((line_height / 4.0).round() as i32);
+    folds
+        .iter()
+        .filter(|&&(header, _)| !fold::is_hidden(folds, header))
+        .filter_map(|&(header, _)| {
+            let end =
+                editor.with_buffer(|buffer| buffer.lines.get(header).map(|l| l.text().len()))?;
+            let (x, y) = cursor_pixel_position(editor, Cursor::new(header, end))?;
+            let rect = RectDef {
+                x: Px(x) + quarter,
+                y: Px(y) + quarter,
+                width: quarter * 4,
+                height: quarter * 2,
+            };
+            Some((rect, header))
+        })
+        .collect()
 }
 
-/// Compute selection rectangles for the given editor.
-fn compute_selection_rects(editor: &glyphon::Editor) -> Vec<RectDef> {
-    let mut selection_rects: Vec<RectDef> = Vec::new();
+/// Compute selection rectangles for the given editor and its secondary carets.
+pub(crate) fn compute_selection_rects(
+    editor: &glyphon::Editor,
+    secondary: &[Caret],
+) -> Vec<RectDef> {
     let (selection_start, selection_end) = editor.selection_bounds().unwrap_or_default();
+    let ranges: Vec<TextRange> = std::iter::once((selection_start, selection_end))
+        .chain(secondary.iter().filter_map(Caret::selection_bounds))
+        .map(|(start, end)| TextRange { start, end })
+        .collect();
+    compute_range_rects(editor, &ranges)
+}
+
+/// Returns the width of the character after `cursor` in the laid out buffer, or `None` at the
+/// end of a line.
+fn glyph_width_at(editor: &glyphon::Editor, cursor: Cursor) -> Option<Px> {
+    editor.with_buffer(|buffer| {
+        buffer
+            .layout_runs()
+            .filter(|run| run.line_i == cursor.line)
+            .flat_map(|run| run.glyphs.iter())
+            .find(|glyph| glyph.start <= cursor.index && cursor.index < glyph.end)
+            .map(|glyph| {
+                let chars = buffer.lines[cursor.line].text()[glyph.start..glyph.end]
+                    .chars()
+                    .count()
+                    .max(1);
+                Px((glyph.w / chars as f32).ceil() as i32)
+            })
+    })
+}
+
+/// Returns the pixel position of `cursor` in the laid out buffer, like
+/// `Edit::cursor_position` does for the editor's own cursor.
+pub(crate) fn cursor_pixel_position(
+    editor: &glyphon::Editor,
+    cursor: Cursor,
+) -> Option<(i32, i32)> {
+    editor.with_buffer(|buffer| {
+        let line_height = buffer.metrics().line_height;
+        buffer.layout_runs().find_map(|run| {
+            if run.line_i != cursor.line {
+                return None;
+            }
+            let y = lens_row::text_row(&run, line_height).0 as i32;
+            let Some(last) = run.glyphs.last() else {
+                return Some((0, y));
+            };
+            for glyph in run.glyphs.iter() {
+                if cursor.index == glyph.start {
+                    return Some((glyph.x as i32, y));
+                }
+                if cursor.index > glyph.start && cursor.index < glyph.end {
+                    let cluster = &run.text[glyph.start..glyph.end];
+                    let before = cluster[..cursor.index - glyph.start].chars().count();
+                    let total = cluster.chars().count().max(1);
+                    let offset = glyph.w * before as f32 / total as f32;
+                    return Some(((glyph.x + offset) as i32, y));
+                }
+            }
+            (cursor.index == last.end).then_some(((last.x + last.w) as i32, y))
+        })
+    })
+}
+
+/// Computes a rectangle of the given width covering every layout line of `cursor`'s line.
+fn compute_line_rects(editor: &glyphon::Editor, cursor: Cursor, width: Px) -> Vec<RectDef> {
+    editor.with_buffer(|buffer| {
+        let line_height = buffer.metrics().line_height;
+        let (top, bottom) = buffer
+            .layout_runs()
+            .filter(|run| run.line_i == cursor.line)
+            .fold((f32::MAX, f32::MIN), |(top, bottom), run| {
+                let (row_top, row_height) = lens_row::text_row(&run, line_height);
+                (top.min(row_top), bottom.max(row_top + row_height))
+            });
+        if top > bottom {
+            return Vec::new();
+        }
+        vec![RectDef {
+            x: Px(0),
+            y: Px(top as i32),
+            width,
+            height: Px((bottom - top).ceil() as i32),
+        }]
+    })
+}
+
+/// Compute highlight rectangles covering each of the given ranges.
+fn compute_range_rects(editor: &glyphon::Editor, ranges: &[TextRange]) -> Vec<RectDef> {
+    let mut rects: Vec<RectDef> = Vec::new();
+    if ranges.is_empty() {
+        return rects;
+    }
 
     editor.with_buffer(|buffer| {
+        let buffer_line_height = buffer.metrics().line_height;
         for run in buffer.layout_runs() {
-            let line_top = Px(run.line_top as i32);
-            let line_height = Px(run.line_height as i32);
-
-            if let Some((x, w)) = run.highlight(selection_start, selection_end) {
-                selection_rects.push(RectDef {
-                    x: Px(x as i32),
-                    y: line_top,
-                    width: Px(w as i32),
-                    height: line_height,
-                });
+            let (top, height) = lens_row::text_row(&run, buffer_line_height);
+            let (line_top, line_height) = (Px(top as i32), Px(height as i32));
+
+            for range in ranges
+                .iter()
+                .filter(|range| range.start.line <= run.line_i && run.line_i <= range.end.line)
+            {
+                if let Some((x, w)) = run.highlight(range.start, range.end) {
+                    rects.push(RectDef {
+                        x: Px(x as i32),
+                        y: line_top,
+                        width: Px(w as i32),
+                        height: line_height,
+                    });
+                }
             }
         }
     });
 
-    selection_rects
+    rects
+}
+
+/// Shift rects left by the horizontal scroll offset.
+fn scroll_rects(mut rects: Vec<RectDef>, scroll_x: Px) -> Vec<RectDef> {
+    for rect in rects.iter_mut() {
+        rect.x = rect.x - scroll_x;
+    }
+    rects
 }
 
 /// Clip rects to visible area and drop those fully outside.
@@ -508,33 +4869,347 @@ pub fn text_edit_core(state: Arc<RwLock<TextEditorState>>) {
             let text_data = state_clone.write().text_data(TextConstraint {
                 max_width: max_width_pixels.map(|px| px.to_f32()),
                 max_height: max_height_pixels.map(|px| px.to_f32()),
+                max_lines: None,
             });
 
+            let visible_x1 = max_width_pixels.unwrap_or(Px(i32::MAX));
+            let visible_y1 = max_height_pixels.unwrap_or(Px(i32::MAX));
+            // Layout positions ignore horizontal scroll, so everything is shifted here.
+            let scroll_x = state_clone.read().horizontal_scroll();
+
+            // The line a debugger stopped at and the line of the cursor are highlighted
+            // beneath everything else, across the whole width of the editor. The number of
+            // children of each kind below was decided from the rects stored last pass.
+            let line_children_len = state_clone.read().current_line_rects.len();
+            let line_rects: Vec<(RectDef, Color)> = {
+                let guard = state_clone.read();
+                let width = max_width_pixels
+                    .unwrap_or_else(|| Px::from(text_data.size[0]) + CURSOR_WIDRH.to_px());
+                let execution_line = guard.execution_line.map(|line| {
+                    let cursor = Cursor::new(line, 0);
+                    (
+                        compute_line_rects(guard.layout_editor(), cursor, width),
+                        guard.execution_line_color,
+                    )
+                });
+                let cursor_line = guard
+                    .current_line_color
+                    .filter(|_| guard.focus_handler().is_focused())
+                    .map(|color| {
+                        let cursor = guard.editor.cursor();
+                        (
+                            compute_line_rects(guard.layout_editor(), cursor, width),
+                            color,
+                        )
+                    });
+                execution_line
+                    .into_iter()
+                    .chain(cursor_line)
+                    .flat_map(|(rects, color)| {
+                        clip_and_take_visible(rects, visible_x1, visible_y1)
+                            .into_iter()
+                            .map(move |rect| (rect, color))
+                    })
+                    .collect()
+            };
+            for (i, (rect_def, _)) in line_rects.iter().take(line_children_len).enumerate() {
+                let rect_node_id = input.children_ids[i];
+                input.measure_child(rect_node_id, input.parent_constraint)?;
+                input.place_child(rect_node_id, PxPosition::new(rect_def.x, rect_def.y));
+            }
+            state_clone.write().current_line_rects = line_rects;
+
+            // Search match highlights come next so the selection is drawn on top of them.
+            let search_children_len = state_clone.read().current_search_rects.len();
+            let search_rects: Vec<(RectDef, Color)> = {
+                let guard = state_clone.read();
+                let current = guard.current_match();
+                guard
+                    .search_matches()
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, range)| {
+                        let color = if Some(i) == current {
+                            guard.current_match_color
+                        } else {
+                            guard.search_highlight_color
+                        };
+                        let range = guard.layout_range(*range);
+                        let rects = compute_range_rects(guard.layout_editor(), &[range]);
+                        clip_and_take_visible(scroll_rects(rects, scroll_x), visible_x1, visible_y1)
+                            .into_iter()
+                            .map(move |rect| (rect, color))
+                    })
+                    .collect()
+            };
+            for (i, (rect_def, _)) in search_rects.iter().take(search_children_len).enumerate() {
+                let rect_node_id = input.children_ids[line_children_len + i];
+                input.measure_child(rect_node_id, input.parent_constraint)?;
+                input.place_child(rect_node_id, PxPosition::new(rect_def.x, rect_def.y));
+            }
+            state_clone.write().current_search_rects = search_rects;
+
+            // Diagnostic underlines sit at the bottom of each line they cover, and misspelled
+            // words are underlined the same way.
+            state_clone.write().refresh_misspellings();
+            let diagnostic_children_len = state_clone.read().current_diagnostic_rects.len();
+            let diagnostic_rects: Vec<(RectDef, Color)> = {
+                let guard = state_clone.read();
+                guard
+                    .diagnostics()
+                    .iter()
+                    .map(|diagnostic| (diagnostic.range, diagnostic.severity.color()))
+                    .chain(
+                        guard
+                            .misspellings()
+                            .iter()
+                            .map(|&range| (range, SPELLING_COLOR)),
+                    )
+                    .flat_map(|(range, color)| {
+                        let range = guard.layout_range(range);
+                        let underlines = compute_range_rects(guard.layout_editor(), &[range])
+                            .into_iter()
+                            .map(|rect| RectDef {
+                                y: rect.y + rect.height - UNDERLINE_HEIGHT,
+                                height: UNDERLINE_HEIGHT,
+                                ..rect
+                            })
+                            .collect();
+                        clip_and_take_visible(
+                            scroll_rects(underlines, scroll_x),
+                            visible_x1,
+                            visible_y1,
+                        )
+                        .into_iter()
+                        .map(move |rect| (rect, color))
+                    })
+                    .collect()
+            };
+            for (i, (rect_def, _)) in diagnostic_rects
+                .iter()
+                .take(diagnostic_children_len)
+                .enumerate()
+            {
+                let rect_node_id = input.children_ids[line_children_len + search_children_len + i];
+                input.measure_child(rect_node_id, input.parent_constraint)?;
+                input.place_child(rect_node_id, PxPosition::new(rect_def.x, rect_def.y));
+            }
+            state_clone.write().current_diagnostic_rects = diagnostic_rects;
+            let diagnostic_children_end =
+                line_children_len + search_children_len + diagnostic_children_len;
+
+            // The link under the pointer is underlined while Ctrl is held.
+            let link_children_len = state_clone.read().current_link_rects.len();
+            let link_rects = {
+                let guard = state_clone.read();
+                let links: Vec<_> = guard
+                    .hovered_link
+                    .iter()
+                    .map(|&range| guard.layout_range(range))
+                    .collect();
+                let underlines = compute_range_rects(guard.layout_editor(), &links)
+                    .into_iter()
+                    .map(|rect| RectDef {
+                        y: rect.y + rect.height - LINK_UNDERLINE_HEIGHT,
+                        height: LINK_UNDERLINE_HEIGHT,
+                        ..rect
+                    })
+                    .collect();
+                clip_and_take_visible(scroll_rects(underlines, scroll_x), visible_x1, visible_y1)
+            };
+            for (i, rect_def) in link_rects.iter().take(link_children_len).enumerate() {
+                let rect_node_id = input.children_ids[diagnostic_children_end + i];
+                input.measure_child(rect_node_id, input.parent_constraint)?;
+                input.place_child(rect_node_id, PxPosition::new(rect_def.x, rect_def.y));
+            }
+            state_clone.write().current_link_rects = link_rects;
+            let link_children_end = diagnostic_children_end + link_children_len;
+
+            // Text being composed with an input method is underlined.
+            let preedit_children_len = state_clone.read().current_preedit_rects.len();
+            let preedit_rects = {
+                let guard = state_clone.read();
+                let ranges: Vec<_> = guard
+                    .composed
+                    .iter()
+                    .map(ComposedLayout::composition)
+                    .collect();
+                let underlines = compute_range_rects(guard.layout_editor(), &ranges)
+                    .into_iter()
+                    .map(|rect| RectDef {
+                        y: rect.y + rect.height - PREEDIT_UNDERLINE_HEIGHT,
+                        height: PREEDIT_UNDERLINE_HEIGHT,
+                        ..rect
+                    })
+                    .collect();
+                clip_and_take_visible(scroll_rects(underlines, scroll_x), visible_x1, visible_y1)
+            };
+            for (i, rect_def) in preedit_rects.iter().take(preedit_children_len).enumerate() {
+                let rect_node_id = input.children_ids[link_children_end + i];
+                input.measure_child(rect_node_id, input.parent_constraint)?;
+                input.place_child(rect_node_id, PxPosition::new(rect_def.x, rect_def.y));
+            }
+            state_clone.write().current_preedit_rects = preedit_rects;
+            let underline_children_len = link_children_end + preedit_children_len;
+
+            // Matching brackets are highlighted next, only while the editor has focus.
+            let bracket_children_len = state_clone.read().current_bracket_rects.len();
+            let bracket_rects = {
+                let guard = state_clone.read();
+                match guard.matching_brackets() {
+                    Some((at, other)) if guard.focus_handler().is_focused() => {
+                        let ranges = [guard.layout_range(at), guard.layout_range(other)];
+                        let rects = compute_range_rects(guard.layout_editor(), &ranges);
+                        clip_and_take_visible(scroll_rects(rects, scroll_x), visible_x1, visible_y1)
+                    }
+                    _ => Vec::new(),
+                }
+            };
+            for (i, rect_def) in bracket_rects.iter().take(bracket_children_len).enumerate() {
+                let rect_node_id = input.children_ids[underline_children_len + i];
+                input.measure_child(rect_node_id, input.parent_constraint)?;
+                input.place_child(rect_node_id, PxPosition::new(rect_def.x, rect_def.y));
+            }
+            state_clone.write().current_bracket_rects = bracket_rects;
+            let bracket_children_end = underline_children_len + bracket_children_len;
+
+            // Folded regions get a marker after their header line.
+            let fold_children_len = state_clone.read().current_fold_marker_rects.len();
+            let fold_rects: Vec<(RectDef, usize)> = {
+                let guard = state_clone.read();
+                compute_fold_marker_rects(guard.layout_editor(), guard.folds())
+                    .into_iter()
+                    .filter_map(|(rect, header)| {
+                        let rects = scroll_rects(vec![rect], scroll_x);
+                        let rect = clip_and_take_visible(rects, visible_x1, visible_y1).pop()?;
+                        Some((rect, header))
+                    })
+                    .collect()
+            };
+            for (i, (rect_def, _)) in fold_rects.iter().take(fold_children_len).enumerate() {
+                let rect_node_id = input.children_ids[bracket_children_end + i];
+                input.measure_child(rect_node_id, input.parent_constraint)?;
+                input.place_child(rect_node_id, PxPosition::new(rect_def.x, rect_def.y));
+            }
+            state_clone.write().current_fold_marker_rects = fold_rects;
+            let fold_children_end = bracket_children_end + fold_children_len;
+
+            // Whitespace marks are drawn beneath the selection, so selected whitespace
+            // stays visible.
+            let whitespace_children_len = state_clone.read().current_whitespace_rects.len();
+            let whitespace_rects = {
+                let guard = state_clone.read();
+                if guard.show_whitespace {
+                    let rects = guard
+                        .layout_editor()
+                        .with_buffer(|buffer| whitespace::whitespace_rects(buffer, guard.folds()));
+                    clip_and_take_visible(scroll_rects(rects, scroll_x), visible_x1, visible_y1)
+                } else {
+                    Vec::new()
+                }
+            };
+            for (i, rect_def) in whitespace_rects
+                .iter()
+                .take(whitespace_children_len)
+                .enumerate()
+            {
+                let rect_node_id = input.children_ids[fold_children_end + i];
+                input.measure_child(rect_node_id, input.parent_constraint)?;
+                input.place_child(rect_node_id, PxPosition::new(rect_def.x, rect_def.y));
+            }
+            state_clone.write().current_whitespace_rects = whitespace_rects;
+            let whitespace_children_end = fold_children_end + whitespace_children_len;
+
+            // Inlay hint labels are drawn in the room left for them in the text.
+            let inlay_children_len = state_clone.read().current_inlay_hint_rects.len();
+            let inlay_rects: Vec<(RectDef, String)> = {
+                let mut guard = state_clone.write();
+                let hints: Vec<_> = guard
+                    .inlay_hint_layout()
+                    .into_iter()
+                    .map(|(position, room)| (guard.layout_cursor(position, false), room))
+                    .collect();
+                compute_inlay_hint_rects(guard.layout_editor(), &hints)
+                    .into_iter()
+                    // Folded lines are not spaced for their hints
+                    .filter(|&(_, index)| !fold::is_hidden(guard.folds(), hints[index].0.line))
+                    .filter_map(|(rect, index)| {
+                        let rects = scroll_rects(vec![rect], scroll_x);
+                        let rect = clip_and_take_visible(rects, visible_x1, visible_y1).pop()?;
+                        Some((rect, guard.inlay_hints[index].label.clone()))
+                    })
+                    .collect()
+            };
+            for (i, (rect_def, _)) in inlay_rects.iter().take(inlay_children_len).enumerate() {
+                let label_node_id = input.children_ids[whitespace_children_end + i];
+                input.measure_child(label_node_id, input.parent_constraint)?;
+                input.place_child(label_node_id, PxPosition::new(rect_def.x, rect_def.y));
+            }
+            state_clone.write().current_inlay_hint_rects = inlay_rects;
+            let highlight_children_len = whitespace_children_end + inlay_children_len;
+
             // Simplified selection rectangle computation using helper functions to reduce complexity.
-            let mut selection_rects = compute_selection_rects(state_clone.read().editor());
+            let mut selection_rects = {
+                let guard = state_clone.read();
+                let secondary: Vec<_> = guard
+                    .secondary_carets()
+                    .iter()
+                    .map(|caret| Caret {
+                        cursor: guard.layout_cursor(caret.cursor, true),
+                        selection: match caret.selection {
+                            Selection::Normal(c) => Selection::Normal(guard.layout_cursor(c, true)),
+                            Selection::Line(c) => Selection::Line(guard.layout_cursor(c, true)),
+                            Selection::Word(c) => Selection::Word(guard.layout_cursor(c, true)),
+                            Selection::None => Selection::None,
+                        },
+                    })
+                    .collect();
+                let rects = compute_selection_rects(guard.layout_editor(), &secondary);
+                scroll_rects(rects, scroll_x)
+            };
 
             // Record length before moving (used to place cursor node after rects)
             let selection_rects_len = selection_rects.len();
 
             // Handle selection rectangle positioning
             for (i, rect_def) in selection_rects.iter().enumerate() {
-                if let Some(rect_node_id) = input.children_ids.get(i).copied() {
+                if let Some(rect_node_id) =
+                    input.children_ids.get(highlight_children_len + i).copied()
+                {
                     input.measure_child(rect_node_id, input.parent_constraint)?;
                     input.place_child(rect_node_id, PxPosition::new(rect_def.x, rect_def.y));
                 }
             }
 
             // Clip to visible area and write filtered rects to state
-            let visible_x1 = max_width_pixels.unwrap_or(Px(i32::MAX));
-            let visible_y1 = max_height_pixels.unwrap_or(Px(i32::MAX));
             selection_rects = clip_and_take_visible(selection_rects, visible_x1, visible_y1);
             state_clone.write().current_selection_rects = selection_rects;
 
-            // Handle cursor positioning (cursor comes after selection rects)
-            if let Some(cursor_pos_raw) = state_clone.read().editor.cursor_position() {
-                let cursor_pos = PxPosition::new(Px(cursor_pos_raw.0), Px(cursor_pos_raw.1));
-                let cursor_node_index = selection_rects_len;
-                if let Some(cursor_node_id) = input.children_ids.get(cursor_node_index).copied() {
+            // Handle cursor positioning (cursors come after search and selection rects,
+            // the primary cursor first, then one per secondary caret)
+            let cursor_positions: Vec<Option<(i32, i32)>> = {
+                let guard = state_clone.read();
+                let primary = match &guard.composed {
+                    Some(composed) => cursor_pixel_position(&composed.editor, composed.caret()),
+                    None => guard.caret_position(),
+                };
+                std::iter::once(primary)
+                    .chain(guard.secondary_carets().iter().map(|caret| {
+                        let cursor = guard.layout_cursor(caret.cursor, true);
+                        cursor_pixel_position(guard.layout_editor(), cursor)
+                    }))
+                    .collect()
+            };
+            let first_cursor_index = highlight_children_len + selection_rects_len;
+            for (i, cursor_pos_raw) in cursor_positions.into_iter().enumerate() {
+                let Some(cursor_pos_raw) = cursor_pos_raw else {
+                    continue;
+                };
+                let cursor_pos =
+                    PxPosition::new(Px(cursor_pos_raw.0) - scroll_x, Px(cursor_pos_raw.1));
+                if let Some(cursor_node_id) =
+                    input.children_ids.get(first_cursor_index + i).copied()
+                {
                     input.measure_child(cursor_node_id, input.parent_constraint)?;
                     input.place_child(cursor_node_id, cursor_pos);
                 }
@@ -552,13 +5227,130 @@ pub fn text_edit_core(state: Arc<RwLock<TextEditorState>>) {
                 text_data.size[1]
             };
 
+            let width = Px::from(text_data.size[0]) + CURSOR_WIDRH.to_px(); // Add padding for cursor
+            // Unwrapped lines can be wider than the editor, they are scrolled instead
+            let constrained_width = match max_width_pixels {
+                Some(max_w) => width.min(max_w),
+                None => width,
+            };
+
             Ok(ComputedData {
-                width: Px::from(text_data.size[0]) + CURSOR_WIDRH.to_px(), // Add padding for cursor
+                width: constrained_width,
                 height: constrained_height.into(),
             })
         }));
     }
 
+    // Execution line and current line highlighting
+    {
+        let rect_definitions = state.read().current_line_rects.clone();
+        for (def, color) in rect_definitions {
+            selection_highlight_rect(def.width, def.height, color);
+        }
+    }
+
+    // Search match highlighting
+    {
+        let rect_definitions = state.read().current_search_rects.clone();
+        for (def, color) in rect_definitions {
+            selection_highlight_rect(def.width, def.height, color);
+        }
+    }
+
+    // Diagnostic underlines
+    {
+        let underlines = state.read().current_diagnostic_rects.clone();
+        for (def, color) in underlines {
+            diagnostic_underline(def.width, color);
+        }
+    }
+
+    // Link underline
+    {
+        let (rect_definitions, color) = {
+            let guard = state.read();
+            (guard.current_link_rects.clone(), guard.link_color)
+        };
+        for def in rect_definitions {
+            selection_highlight_rect(def.width, def.height, color);
+        }
+    }
+
+    // Composition underline
+    {
+        let (rect_definitions, color) = {
+            let guard = state.read();
+            (
+                guard.current_preedit_rects.clone(),
+                guard.caret_color.unwrap_or(Color::BLACK),
+            )
+        };
+        for def in rect_definitions {
+            selection_highlight_rect(def.width, def.height, color);
+        }
+    }
+
+    // Bracket match highlighting
+    {
+        let (rect_definitions, color_for_brackets) = {
+            let guard = state.read();
+            (
+                guard.current_bracket_rects.clone(),
+                guard.bracket_highlight_color,
+            )
+        };
+
+        for def in rect_definitions {
+            selection_highlight_rect(def.width, def.height, color_for_brackets);
+        }
+    }
+
+    // Fold markers
+    {
+        let (rect_definitions, color_for_folds) = {
+            let guard = state.read();
+            (
+                guard.current_fold_marker_rects.clone(),
+                guard.fold_marker_color,
+            )
+        };
+
+        for (def, _) in rect_definitions {
+            selection_highlight_rect(def.width, def.height, color_for_folds);
+        }
+    }
+
+    // Whitespace marks
+    {
+        let (rect_definitions, color_for_whitespace) = {
+            let guard = state.read();
+            (
+                guard.current_whitespace_rects.clone(),
+                guard.whitespace_color,
+            )
+        };
+
+        for def in rect_definitions {
+            selection_highlight_rect(def.width, def.height, color_for_whitespace);
+        }
+    }
+
+    // Inlay hint labels
+    {
+        let (labels, color, metrics) = {
+            let guard = state.read();
+            (
+                guard.current_inlay_hint_rects.clone(),
+                guard.inlay_hint_color,
+                guard.editor().with_buffer(|buffer| buffer.metrics()),
+            )
+        };
+
+        for (_, label) in labels {
+            inlay_hint_label(label, color, metrics);
+        }
+    }
+
     // Selection highlighting
     {
         let (rect_definitions, color_for_selection) = {
@@ -573,6 +5365,406 @@ pub fn text_edit_core(state: Arc<RwLock<TextEditorState>>) {
 
     // Cursor rendering (only when focused)
     if state.read().focus_handler().is_focused() {
-        cursor::cursor(state.read().line_height(), state.read().blink_timer());
+        let (line_height, blink_timer, block_widths, caret_color) = {
+            let guard = state.read();
+            // In overwrite mode each caret covers the character it will replace
+            let block_widths: Vec<Option<Px>> = std::iter::once(guard.editor.cursor())
+                .chain(guard.secondary_carets().iter().map(|caret| caret.cursor))
+                .map(|cursor| {
+                    guard.overwrite().then(|| {
+                        glyph_width_at(guard.layout_editor(), guard.layout_cursor(cursor, true))
+                            .unwrap_or(Px(guard.line_height().0 / 2))
+                    })
+                })
+                .collect();
+            let caret_color = guard.caret_color.unwrap_or(Color::BLACK);
+            (
+                guard.line_height(),
+                guard.blink_timer(),
+                block_widths,
+                caret_color,
+            )
+        };
+        for block_width in block_widths {
+            cursor::cursor(line_height, blink_timer, block_width, caret_color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glyphon::Action;
+
+    use super::*;
+
+    /// Returns an editor showing `text` with the cursor at its start.
+    fn state_with(text: &str) -> TextEditorState {
+        let mut state = TextEditorState::new(Dp(14.0), None);
+        state.set_text_reactive(text);
+        state.editor_mut().set_cursor(Cursor::new(0, 0));
+        state
+    }
+
+    #[test]
+    fn test_enter_on_the_last_line_keeps_the_break() {
+        let mut state = state_with("ab");
+        state.set_cursor(0, 2);
+        state.apply_action(Action::Enter);
+        state.apply_action(Action::Insert('c'));
+        assert_eq!(state.raw_text(), "ab\nc");
+        while state.undo() {}
+        assert_eq!(state.raw_text(), "ab");
+    }
+
+    #[test]
+    fn test_move_lines_with_selection_ending_at_line_start() {
+        let mut state = state_with("ab");
+        state.set_cursor(0, 2);
+        state.apply_action(Action::Enter);
+        state.select_all();
+        assert!(state.move_lines(false));
+        assert_eq!(state.raw_text(), "\nab");
+        assert_eq!(state.editor().cursor(), Cursor::new(1, 2));
+        assert_eq!(state.selected_text().as_deref(), Some("ab"));
+
+        let mut state = state_with("ab\ncd\nef");
+        state.select_range(Cursor::new(0, 0), Cursor::new(1, 0));
+        assert!(state.move_lines(false));
+        assert_eq!(state.raw_text(), "cd\nab\nef");
+        assert_eq!(state.editor().cursor(), Cursor::new(2, 0));
+        assert_eq!(state.selected_text().as_deref(), Some("ab\n"));
+
+        assert!(state.move_lines(true));
+        assert_eq!(state.raw_text(), "ab\ncd\nef");
+        assert_eq!(state.editor().cursor(), Cursor::new(1, 0));
+        assert_eq!(state.selected_text().as_deref(), Some("ab\n"));
+    }
+
+    #[test]
+    fn test_move_lines_keeps_the_caret_on_the_moved_line() {
+        let mut state = state_with("ab\ncd");
+        state.set_cursor(1, 1);
+        assert!(state.move_lines(true));
+        assert_eq!(state.raw_text(), "cd\nab");
+        assert_eq!(state.editor().cursor(), Cursor::new(0, 1));
+        assert!(!state.move_lines(true));
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "ab\ncd");
+    }
+
+    #[test]
+    fn test_add_caret_is_clamped_onto_the_content() {
+        let mut state = state_with("ab\ncé");
+        state.add_caret(Cursor::new(9, 9));
+        state.add_caret(Cursor::new(1, 3));
+        // Byte 2 is within 'é'
+        state.add_caret(Cursor::new(1, 2));
+        let carets: Vec<_> = state.secondary_carets().iter().map(|c| c.cursor).collect();
+        assert_eq!(carets, [Cursor::new(1, 3), Cursor::new(1, 1)]);
+    }
+
+    #[test]
+    fn test_tab_inserts_a_tab_without_insert_spaces() {
+        let mut state = state_with("ab");
+        state.set_insert_spaces(false);
+        state.set_cursor(0, 1);
+        state.apply_action(Action::Insert('\t'));
+        assert_eq!(state.raw_text(), "a\tb");
+        assert_eq!(state.editor().cursor(), Cursor::new(0, 2));
+        state.set_cursor(0, 3);
+        state.apply_action(Action::Insert('\t'));
+        assert_eq!(state.raw_text(), "a\tb\t");
+
+        state.select_all();
+        assert!(state.indent_lines(false));
+        assert_eq!(state.raw_text(), "\ta\tb\t");
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "a\tb\t");
+    }
+
+    #[test]
+    fn test_insert_text_is_one_undo_step_without_auto_close() {
+        let mut state = state_with("x");
+        state.set_auto_close(true);
+        state.set_cursor(0, 1);
+        assert!(state.insert_text("f(a,\n b)"));
+        assert_eq!(state.raw_text(), "xf(a,\n b)");
+        assert_eq!(state.editor().cursor(), Cursor::new(1, 3));
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "x");
+        assert!(!state.can_undo());
+
+        let mut state = state_with("x\ny");
+        state.set_cursor(0, 1);
+        state.add_caret(Cursor::new(1, 1));
+        assert!(state.insert_text("()"));
+        assert_eq!(state.raw_text(), "x()\ny()");
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "x\ny");
+    }
+
+    #[test]
+    fn test_ime_commit_auto_closes_only_typed_characters() {
+        for (commit, expected) in [("g(", "xg("), ("(", "x()")] {
+            let mut state = state_with("x");
+            state.set_auto_close(true);
+            state.set_cursor(0, 1);
+            state.apply_ime(winit::event::Ime::Commit(commit.to_string()));
+            assert_eq!(state.raw_text(), expected);
+        }
+    }
+
+    #[test]
+    fn test_preedit_is_kept_out_of_the_content() {
+        let mut state = state_with("/");
+        state.set_line_comment(Some("//".to_string()));
+        state.select_all();
+        state.apply_ime(winit::event::Ime::Preedit(" 😀{".to_string(), None));
+        assert_eq!(state.raw_text(), "");
+        // Only blank lines, which stay uncommented
+        assert!(!state.toggle_comment());
+        assert_eq!(state.preedit(), Some(" 😀{"));
+
+        let mut state = state_with("/");
+        state.set_line_comment(Some("//".to_string()));
+        state.apply_ime(winit::event::Ime::Preedit("x{".to_string(), None));
+        assert!(state.toggle_comment());
+        assert_eq!(state.raw_text(), "// /");
+        assert!(state.expand_selection());
+        let selection = state.selection_range().unwrap();
+        assert!(selection.end.offset <= state.raw_text().len());
+
+        state.apply_ime(winit::event::Ime::Commit("x".to_string()));
+        assert_eq!(state.preedit(), None);
+        while state.undo() {}
+        assert_eq!(state.raw_text(), "/");
+    }
+
+    #[test]
+    fn test_preedit_is_laid_out_at_the_caret() {
+        let mut state = state_with("ab");
+        state.set_cursor(0, 1);
+        state.apply_ime(winit::event::Ime::Preedit("かな".to_string(), Some((0, 3))));
+        let constraint = TextConstraint {
+            max_width: Some(400.0),
+            max_height: None,
+            max_lines: None,
+        };
+        state.text_data(constraint.clone());
+        let composed = state.composed.as_ref().unwrap();
+        let line = composed
+            .editor
+            .with_buffer(|buffer| buffer.lines[0].text().to_string());
+        assert_eq!(line, "aかなb");
+        assert_eq!(composed.caret(), Cursor::new(0, 4));
+        assert_eq!(
+            state.layout_cursor(Cursor::new(0, 2), false),
+            Cursor::new(0, 8)
+        );
+        assert_eq!(state.raw_text(), "ab");
+
+        state.apply_ime(winit::event::Ime::Preedit(String::new(), None));
+        state.text_data(constraint);
+        assert!(state.composed.is_none());
+        assert_eq!(state.editor().cursor(), Cursor::new(0, 1));
+    }
+
+    #[test]
+    fn test_typing_is_undone_word_by_word() {
+        let mut state = state_with("x");
+        state.set_cursor(0, 1);
+        for c in "ab cd".chars() {
+            state.apply_action(Action::Insert(c));
+        }
+        state.apply_action(Action::Backspace);
+        state.apply_action(Action::Backspace);
+        assert_eq!(state.raw_text(), "xab ");
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "xab cd");
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "xab ");
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "x");
+        assert!(!state.undo());
+        assert!(state.redo());
+        assert_eq!(state.raw_text(), "xab ");
+    }
+
+    #[test]
+    fn test_duplicate_lines() {
+        let mut state = state_with("ab\ncd");
+        state.set_cursor(0, 1);
+        assert!(state.duplicate_lines());
+        assert_eq!(state.raw_text(), "ab\nab\ncd");
+        assert_eq!(state.editor().cursor(), Cursor::new(1, 1));
+
+        state.select_range(Cursor::new(2, 0), Cursor::new(2, 1));
+        assert!(state.duplicate_lines());
+        assert_eq!(state.raw_text(), "ab\nab\nccd");
+        assert_eq!(state.selected_text().as_deref(), Some("c"));
+        assert_eq!(state.editor().cursor(), Cursor::new(2, 2));
+
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "ab\nab\ncd");
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "ab\ncd");
+    }
+
+    #[test]
+    fn test_secondary_carets_edit_together() {
+        let mut state = state_with("ab\ncd");
+        state.add_caret(Cursor::new(1, 0));
+        state.apply_action(Action::Insert('x'));
+        assert_eq!(state.raw_text(), "xab\nxcd");
+        assert_eq!(state.editor().cursor(), Cursor::new(0, 1));
+        let carets: Vec<_> = state.secondary_carets().iter().map(|c| c.cursor).collect();
+        assert_eq!(carets, [Cursor::new(1, 1)]);
+
+        state.apply_action(Action::Backspace);
+        assert_eq!(state.raw_text(), "ab\ncd");
+        while state.undo() {}
+        assert_eq!(state.raw_text(), "ab\ncd");
+        assert!(state.secondary_carets().is_empty());
+
+        // A caret within the selection of another is merged into it
+        state.select_range(Cursor::new(0, 1), Cursor::new(1, 1));
+        state.add_caret(Cursor::new(1, 0));
+        state.apply_action(Action::Insert('-'));
+        assert_eq!(state.raw_text(), "a-d");
+        assert!(state.secondary_carets().is_empty());
+    }
+
+    #[test]
+    fn test_joining_the_last_line_adds_no_line_break() {
+        let mut state = state_with("ab\ncd");
+        state.set_cursor(1, 0);
+        state.apply_action(Action::Backspace);
+        assert_eq!(state.raw_text(), "abcd");
+
+        let mut state = state_with("ab\ncd");
+        state.select_range(Cursor::new(0, 1), Cursor::new(1, 1));
+        assert!(state.insert_text("x\ny"));
+        assert_eq!(state.raw_text(), "ax\nyd");
+    }
+
+    #[test]
+    fn test_undo_restores_the_carets() {
+        let mut state = state_with("ab\ncd");
+        state.select_range(Cursor::new(0, 0), Cursor::new(0, 2));
+        state.apply_action(Action::Insert('x'));
+        state.set_cursor(1, 2);
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "ab\ncd");
+        assert_eq!(state.selected_text().as_deref(), Some("ab"));
+        assert!(state.redo());
+        assert_eq!(state.editor().cursor(), Cursor::new(0, 1));
+        assert_eq!(state.editor().selection(), Selection::None);
+    }
+
+    /// Returns the next number of a xorshift sequence starting at `seed`.
+    fn next_random(seed: &mut u64) -> u64 {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+        *seed
+    }
+
+    /// Asserts that every caret sits on a character boundary of an existing line and that
+    /// the selection lies within the content.
+    fn assert_carets_on_content(state: &TextEditorState) {
+        let text = state.raw_text();
+        state.editor().with_buffer(|buffer| {
+            let carets = state.secondary_carets().iter().map(|caret| caret.cursor);
+            for cursor in carets.chain([state.editor().cursor()]) {
+                let line = buffer
+                    .lines
+                    .get(cursor.line)
+                    .expect("caret past the last line");
+                assert!(
+                    line.text().is_char_boundary(cursor.index),
+                    "{cursor:?} in {text:?}"
+                );
+            }
+        });
+        if let Some(selection) = state.selection_range() {
+            assert!(selection.start.offset <= selection.end.offset);
+            assert!(selection.end.offset <= text.len());
+        }
+    }
+
+    /// Runs the steps of the `editor_actions` fuzz target in a fixed pseudo-random order.
+    #[test]
+    fn test_random_edits_keep_carets_on_the_content() {
+        const SNIPPETS: [&str; 6] = ["a", "é(", " 😀{", "\t", "x\ny", "}\n"];
+        let constraint = TextConstraint {
+            max_width: Some(80.0),
+            max_height: None,
+            max_lines: None,
+        };
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..200 {
+            let mut state = state_with("fn f(a) {\n\tlet b = \"é\";\n}");
+            state.set_history_depth(usize::MAX);
+            state.set_line_comment(Some("//".to_string()));
+            state.set_auto_close(true);
+            let initial = state.raw_text();
+            for _ in 0..40 {
+                let n = next_random(&mut seed);
+                let small = (n >> 8) as usize % 8;
+                let snippet = SNIPPETS[(n >> 16) as usize % SNIPPETS.len()].to_string();
+                match n % 20 {
+                    0 => state.apply_action(Action::Insert(snippet.chars().next().unwrap())),
+                    1 => state.apply_action(Action::Enter),
+                    2 => state.apply_action(Action::Backspace),
+                    3 => state.apply_action(Action::Delete),
+                    4 => state.apply_action(Action::Indent),
+                    5 => state.apply_action(Action::Motion(cosmic_text::Motion::Down)),
+                    6 => state.apply_action(Action::Motion(cosmic_text::Motion::NextWord)),
+                    7 => state.select_range(Cursor::new(small / 2, small), Cursor::new(small, 1)),
+                    8 => state.add_caret(Cursor::new(small / 3, small * 2)),
+                    9 => state.select_all(),
+                    10 => {
+                        state.expand_selection();
+                    }
+                    11 => state.apply_ime(winit::event::Ime::Preedit(snippet, None)),
+                    12 => state.apply_ime(winit::event::Ime::Commit(snippet)),
+                    13 => {
+                        state.insert_text(&snippet);
+                    }
+                    14 => {
+                        state.duplicate_lines();
+                    }
+                    15 => {
+                        state.move_lines(small.is_multiple_of(2));
+                    }
+                    16 => {
+                        state.toggle_comment();
+                    }
+                    17 => {
+                        state.undo();
+                    }
+                    18 => {
+                        state.redo();
+                    }
+                    _ => {
+                        state.text_data(constraint.clone());
+                    }
+                }
+                assert_carets_on_content(&state);
+            }
+
+            while state.can_redo() {
+                state.redo();
+            }
+            let edited = state.raw_text();
+            while state.can_undo() {
+                state.undo();
+            }
+            assert_eq!(state.raw_text(), initial);
+            while state.can_redo() {
+                state.redo();
+            }
+            assert_eq!(state.raw_text(), edited);
+        }
     }
 }
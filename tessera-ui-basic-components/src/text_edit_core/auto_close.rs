@@ -0,0 +1,62 @@
+//! Insertion of the closing bracket or quote when an opening one is typed.
+
+use glyphon::Cursor;
+
+/// Pairs closed automatically: brackets and quotes.
+pub(crate) const AUTO_CLOSE_PAIRS: [(char, char); 5] =
+    [('(', ')'), ('[', ']'), ('{', '}'), ('"', '"'), ('\'', '\'')];
+
+/// Returns the character to insert after `typed` between `before` and `after`, if `typed` opens
+/// one of `pairs`.
+///
+/// Nothing is closed in front of a word, so wrapping existing text stays easy, and a quote is
+/// not closed after a word character, where it is more likely an apostrophe.
+pub(super) fn closer_for(
+    pairs: &[(char, char)],
+    typed: char,
+    before: Option<char>,
+    after: Option<char>,
+) -> Option<char> {
+    let &(open, close) = pairs.iter().find(|&&(open, _)| open == typed)?;
+    let closes_before = after.is_none_or(|c| {
+        c.is_whitespace() || pairs.iter().any(|&(o, close)| c == close && o != close)
+    });
+    let is_quote = open == close;
+    let after_word = before.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    (closes_before && !(is_quote && after_word)).then_some(close)
+}
+
+/// Moves the auto-inserted closers in `closers` along with the text when one character of
+/// `len` bytes is inserted at each of `carets`.
+pub(super) fn shift_closers(closers: &mut [Cursor], carets: &[Cursor], len: usize) {
+    for closer in closers {
+        let before = carets
+            .iter()
+            .filter(|caret| caret.line == closer.line && caret.index <= closer.index)
+            .count();
+        closer.index += before * len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_close_closer_for() {
+        let pairs = &AUTO_CLOSE_PAIRS;
+        assert_eq!(closer_for(pairs, '(', None, None), Some(')'));
+        assert_eq!(closer_for(pairs, '[', Some('a'), Some(' ')), Some(']'));
+        assert_eq!(closer_for(pairs, '{', Some('('), Some(')')), Some('}'));
+        // Not in front of a word, and not for characters that open nothing
+        assert_eq!(closer_for(pairs, '(', None, Some('x')), None);
+        assert_eq!(closer_for(pairs, ')', None, None), None);
+        // A quote after a letter is an apostrophe
+        assert_eq!(closer_for(pairs, '\'', Some('n'), None), None);
+        assert_eq!(closer_for(pairs, '"', Some(' '), None), Some('"'));
+
+        let mut closers = [Cursor::new(0, 4), Cursor::new(1, 2)];
+        shift_closers(&mut closers, &[Cursor::new(0, 1), Cursor::new(0, 4)], 1);
+        assert_eq!(closers, [Cursor::new(0, 6), Cursor::new(1, 2)]);
+    }
+}
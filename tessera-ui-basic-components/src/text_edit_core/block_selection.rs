@@ -0,0 +1,83 @@
+//! Block (column) selection, which selects the same horizontal span on a range of lines with
+//! one caret per line.
+
+use glyphon::{Cursor, cosmic_text::Selection};
+
+use super::{Caret, fold};
+
+/// Returns the carets of a block selection from `anchor` to `head`, both a line and an x
+/// position in the buffer: the caret on the line of `head` first, then one for each other
+/// line in between.
+///
+/// Lines hidden by `folds` or not laid out get no caret. Each caret selects from the anchor
+/// x to the head x, clamped to the end of its line, so shorter lines get a shorter selection.
+pub(super) fn block_carets(
+    buffer: &glyphon::Buffer,
+    anchor: (usize, f32),
+    head: (usize, f32),
+    folds: &[(usize, usize)],
+) -> Option<(Caret, Vec<Caret>)> {
+    let (first, last) = (anchor.0.min(head.0), anchor.0.max(head.0));
+    let mut primary = None;
+    let mut others = Vec::new();
+    let mut line_i = first;
+    for run in buffer.layout_runs() {
+        // Wrapped lines are selected in their first row
+        if run.line_i < line_i || run.line_i > last || fold::is_hidden(folds, run.line_i) {
+            continue;
+        }
+        line_i = run.line_i + 1;
+        let glyphs = run.glyphs.iter().map(|g| (g.start, g.end, g.x, g.w));
+        let from = index_at_x(glyphs.clone(), run.text.len(), anchor.1);
+        let to = index_at_x(glyphs, run.text.len(), head.1);
+        let caret = Caret {
+            cursor: Cursor::new(run.line_i, to),
+            selection: if from == to {
+                Selection::None
+            } else {
+                Selection::Normal(Cursor::new(run.line_i, from))
+            },
+        };
+        if run.line_i == head.0 {
+            primary = Some(caret);
+        } else {
+            others.push(caret);
+        }
+    }
+    Some((primary?, others))
+}
+
+/// Returns the byte index of the glyph edge closest to `x` in a row of `glyphs`, given as
+/// `(start, end, x, width)`, or `line_end` past the last glyph.
+fn index_at_x(
+    glyphs: impl IntoIterator<Item = (usize, usize, f32, f32)>,
+    line_end: usize,
+    x: f32,
+) -> usize {
+    for (start, end, glyph_x, width) in glyphs {
+        if x < glyph_x + width / 2.0 {
+            return start;
+        }
+        if x < glyph_x + width {
+            return end;
+        }
+    }
+    line_end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_selection_index_at_x() {
+        let glyphs = [(0, 1, 0.0, 10.0), (1, 2, 10.0, 10.0), (2, 3, 20.0, 10.0)];
+        assert_eq!(index_at_x(glyphs, 3, -5.0), 0);
+        assert_eq!(index_at_x(glyphs, 3, 4.0), 0);
+        assert_eq!(index_at_x(glyphs, 3, 6.0), 1);
+        assert_eq!(index_at_x(glyphs, 3, 21.0), 2);
+        // Past the end of a short line the selection stops at its end
+        assert_eq!(index_at_x(glyphs, 3, 100.0), 3);
+        assert_eq!(index_at_x([], 0, 50.0), 0);
+    }
+}
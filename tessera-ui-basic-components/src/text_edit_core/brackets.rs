@@ -0,0 +1,148 @@
+//! Matching of bracket pairs around the cursor.
+
+use glyphon::Cursor;
+
+use super::TextRange;
+
+/// Bracket pairs matched by default: `()`, `[]` and `{}`.
+pub(crate) const DEFAULT_BRACKET_PAIRS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+/// Finds the bracket next to `cursor` and its counterpart.
+///
+/// The character after the cursor is tried first, then the one before it. Returns the ranges
+/// of both brackets, the one next to the cursor first, or `None` if there is no bracket next to
+/// the cursor or it is unbalanced.
+pub(crate) fn find_bracket_pair(
+    lines: &[&str],
+    cursor: Cursor,
+    pairs: &[(char, char)],
+) -> Option<(TextRange, TextRange)> {
+    let line = lines.get(cursor.line)?;
+    let after = line[cursor.index..]
+        .chars()
+        .next()
+        .map(|c| (cursor.index, c));
+    let before = line[..cursor.index].char_indices().next_back();
+
+    after.into_iter().chain(before).find_map(|(index, c)| {
+        let at = Cursor::new(cursor.line, index);
+        let other = match_from(lines, at, c, pairs)?;
+        Some((
+            char_range(at, c),
+            char_range(other, bracket_partner(c, pairs)?),
+        ))
+    })
+}
+
+fn char_range(start: Cursor, c: char) -> TextRange {
+    TextRange {
+        start,
+        end: Cursor::new(start.line, start.index + c.len_utf8()),
+    }
+}
+
+fn bracket_partner(c: char, pairs: &[(char, char)]) -> Option<char> {
+    pairs.iter().find_map(|&(open, close)| {
+        if c == open {
+            Some(close)
+        } else if c == close {
+            Some(open)
+        } else {
+            None
+        }
+    })
+}
+
+/// Scans from the bracket `c` at `at` towards its partner, skipping nested pairs.
+fn match_from(lines: &[&str], at: Cursor, c: char, pairs: &[(char, char)]) -> Option<Cursor> {
+    let &(open, close) = pairs
+        .iter()
+        .find(|&&(open, close)| c == open || c == close)?;
+    let forward = c == open && open != close;
+    let mut depth = 0usize;
+
+    if forward {
+        for (line_i, line) in lines.iter().enumerate().skip(at.line) {
+            let start = if line_i == at.line { at.index } else { 0 };
+            for (index, ch) in line[start..].char_indices() {
+                if ch == open {
+                    depth += 1;
+                } else if ch == close {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(Cursor::new(line_i, start + index));
+                    }
+                }
+            }
+        }
+    } else {
+        for line_i in (0..=at.line).rev() {
+            let line = lines[line_i];
+            let end = if line_i == at.line {
+                at.index + c.len_utf8()
+            } else {
+                line.len()
+            };
+            for (index, ch) in line[..end].char_indices().rev() {
+                if ch == close {
+                    depth += 1;
+                } else if ch == open {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(Cursor::new(line_i, index));
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn starts(pair: Option<(TextRange, TextRange)>) -> Option<((usize, usize), (usize, usize))> {
+        pair.map(|(at, other)| {
+            (
+                (at.start.line, at.start.index),
+                (other.start.line, other.start.index),
+            )
+        })
+    }
+
+    #[test]
+    fn test_matches_nested_pairs_across_lines() {
+        let lines = ["fn f(a: [u8; 2]) {", "    g(a[0]);", "}"];
+
+        // Cursor before the opening brace
+        let pair = find_bracket_pair(&lines, Cursor::new(0, 17), &DEFAULT_BRACKET_PAIRS);
+        assert_eq!(starts(pair), Some(((0, 17), (2, 0))));
+
+        // Cursor after the closing parenthesis of the signature
+        let pair = find_bracket_pair(&lines, Cursor::new(0, 16), &DEFAULT_BRACKET_PAIRS);
+        assert_eq!(starts(pair), Some(((0, 15), (0, 4))));
+
+        // Between `]` and `)` the bracket after the cursor wins
+        let pair = find_bracket_pair(&lines, Cursor::new(1, 10), &DEFAULT_BRACKET_PAIRS);
+        assert_eq!(starts(pair), Some(((1, 10), (1, 5))));
+    }
+
+    #[test]
+    fn test_no_match_without_bracket_or_partner() {
+        let lines = ["(a", "b"];
+        assert_eq!(
+            find_bracket_pair(&lines, Cursor::new(1, 1), &DEFAULT_BRACKET_PAIRS),
+            None
+        );
+        assert_eq!(
+            find_bracket_pair(&lines, Cursor::new(0, 0), &DEFAULT_BRACKET_PAIRS),
+            None
+        );
+        // Custom pairs replace the defaults
+        let lines = ["<a>"];
+        let pair = find_bracket_pair(&lines, Cursor::new(0, 0), &[('<', '>')]);
+        assert_eq!(starts(pair), Some(((0, 0), (0, 2))));
+    }
+}
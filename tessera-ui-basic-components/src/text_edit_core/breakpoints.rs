@@ -0,0 +1,145 @@
+//! Breakpoints toggled in the gutter, and the line a debugger stopped at.
+
+use tessera_ui::Color;
+
+use super::{ContextMenuChoice, ContextMenuEntry, GutterAnnotation};
+
+/// Alpha of the marks of disabled breakpoints, relative to enabled ones.
+const DISABLED_ALPHA: f32 = 0.35;
+
+/// A breakpoint of a debugger frontend, drawn as a dot in the gutter of its line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Breakpoint {
+    /// Index of the line.
+    pub line: usize,
+    /// Disabled breakpoints are kept, drawn faded, but the debugger should not stop at them.
+    pub enabled: bool,
+    /// Expression the debugger should stop on only when it holds, or `None` to always stop.
+    pub condition: Option<String>,
+}
+
+impl Breakpoint {
+    /// Creates an enabled breakpoint without a condition.
+    pub fn new(line: usize) -> Self {
+        Self {
+            line,
+            enabled: true,
+            condition: None,
+        }
+    }
+
+    /// Sets the condition of the breakpoint.
+    pub fn with_condition(mut self, condition: impl Into<String>) -> Self {
+        self.condition = Some(condition.into());
+        self
+    }
+}
+
+/// A command of the menu opened by right-clicking the gutter in breakpoint mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BreakpointCommand {
+    Add,
+    AddConditional,
+    Remove,
+    ToggleEnabled,
+    EditCondition,
+}
+
+/// Returns the entries of the gutter menu of a line with `breakpoint`, offering the
+/// condition entries only if the application can edit `conditions`.
+pub(crate) fn breakpoint_menu_entries(
+    breakpoint: Option<&Breakpoint>,
+    conditions: bool,
+) -> Vec<ContextMenuEntry> {
+    let commands: &[(BreakpointCommand, &str)] = match breakpoint {
+        None => &[
+            (BreakpointCommand::Add, "Add Breakpoint"),
+            (
+                BreakpointCommand::AddConditional,
+                "Add Conditional Breakpoint…",
+            ),
+        ],
+        Some(breakpoint) => &[
+            (BreakpointCommand::Remove, "Remove Breakpoint"),
+            (
+                BreakpointCommand::ToggleEnabled,
+                if breakpoint.enabled {
+                    "Disable Breakpoint"
+                } else {
+                    "Enable Breakpoint"
+                },
+            ),
+            (BreakpointCommand::EditCondition, "Edit Condition…"),
+        ],
+    };
+    commands
+        .iter()
+        .filter(|(command, _)| {
+            conditions
+                || !matches!(
+                    command,
+                    BreakpointCommand::AddConditional | BreakpointCommand::EditCondition
+                )
+        })
+        .map(|&(command, label)| ContextMenuEntry {
+            label: label.to_string(),
+            enabled: true,
+            choice: ContextMenuChoice::Breakpoint(command),
+        })
+        .collect()
+}
+
+/// Returns the gutter marks of `breakpoints`: dots in `color`, or `conditional_color` for
+/// breakpoints with a condition, faded while disabled.
+pub(super) fn breakpoint_annotations(
+    breakpoints: &[Breakpoint],
+    color: Color,
+    conditional_color: Color,
+) -> impl Iterator<Item = GutterAnnotation> + '_ {
+    breakpoints.iter().map(move |breakpoint| {
+        let color = if breakpoint.condition.is_some() {
+            conditional_color
+        } else {
+            color
+        };
+        let color = if breakpoint.enabled {
+            color
+        } else {
+            color.with_alpha(color.a * DISABLED_ALPHA)
+        };
+        GutterAnnotation::dot(breakpoint.line, color)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakpoint_menu_entries() {
+        let labels = |breakpoint: Option<&Breakpoint>, conditions| -> Vec<String> {
+            breakpoint_menu_entries(breakpoint, conditions)
+                .into_iter()
+                .map(|entry| entry.label)
+                .collect()
+        };
+        assert_eq!(
+            labels(None, true),
+            ["Add Breakpoint", "Add Conditional Breakpoint…"]
+        );
+        assert_eq!(labels(None, false), ["Add Breakpoint"]);
+        let mut breakpoint = Breakpoint::new(3).with_condition("i > 2");
+        breakpoint.enabled = false;
+        assert_eq!(
+            labels(Some(&breakpoint), true),
+            ["Remove Breakpoint", "Enable Breakpoint", "Edit Condition…"]
+        );
+
+        let red = Color::new(1.0, 0.0, 0.0, 1.0);
+        let orange = Color::new(1.0, 0.5, 0.0, 1.0);
+        let marks: Vec<_> =
+            breakpoint_annotations(&[Breakpoint::new(1), breakpoint], red, orange).collect();
+        assert_eq!(marks[0], GutterAnnotation::dot(1, red));
+        assert_eq!(marks[1], GutterAnnotation::dot(3, orange.with_alpha(0.35)));
+    }
+}
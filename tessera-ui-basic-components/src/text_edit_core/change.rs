@@ -0,0 +1,188 @@
+//! Change events passed to the `on_change` callback of the editor.
+
+use std::ops::{Range, RangeInclusive};
+
+use glyphon::BufferLine;
+
+use super::history::EditDelta;
+
+/// An edit of the editor content, described as one replaced range.
+///
+/// Edits at several carets are merged into the range spanning all of them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextChange {
+    /// Byte range of the previous content that was replaced.
+    pub range: Range<usize>,
+    /// Text that now stands in place of `range`.
+    pub inserted: String,
+    /// Byte offset of the cursor in the new content.
+    pub cursor: usize,
+    /// The whole content after the change. Only filled in when the editor is asked for it
+    /// with [`TextEditorArgs::change_text`](crate::text_editor::TextEditorArgs::change_text),
+    /// as copying it on every key press is slow in large documents.
+    pub text: Option<String>,
+}
+
+impl TextChange {
+    /// Describes `edit`, after which the cursor is at byte offset `cursor`.
+    pub(crate) fn from_edit(edit: &EditDelta, cursor: usize) -> Self {
+        Self {
+            range: edit.start..edit.start + edit.removed.len(),
+            inserted: edit.inserted.clone(),
+            cursor,
+            text: None,
+        }
+    }
+}
+
+/// The lines an action may edit, copied before it runs so that its edit can be found
+/// afterwards without copying the whole content.
+pub(super) struct EditWindow {
+    /// Index of the first line.
+    first: usize,
+    /// Number of lines.
+    len: usize,
+    /// Byte offset of the first line in the content.
+    start: usize,
+    /// The lines with their endings.
+    text: String,
+    /// Number of lines of the whole content.
+    line_count: usize,
+}
+
+impl EditWindow {
+    /// Copies `range` of the content `lines`, widened by the line on either side that
+    /// Backspace and Delete join them with.
+    pub(super) fn new(lines: &[BufferLine], range: RangeInclusive<usize>) -> Self {
+        let line_count = lines.len();
+        let first = range.start().saturating_sub(1).min(line_count);
+        let end = range.end().saturating_add(2).min(line_count).max(first);
+        Self {
+            first,
+            len: end - first,
+            start: lines[..first].iter().map(line_len).sum(),
+            text: lines_text(&lines[first..end]),
+            line_count,
+        }
+    }
+
+    /// Returns the number of lines the content had when the window was copied.
+    pub(super) fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Returns the edit that turned the content into `lines` since the window was copied,
+    /// or `None` if it is unchanged. Lines outside of the window must not have been edited.
+    pub(super) fn edit(&self, lines: &[BufferLine]) -> Option<EditDelta> {
+        // Lines added or removed by the edit were added or removed in the window
+        let len = (self.len + lines.len()).saturating_sub(self.line_count);
+        let end = (self.first + len).min(lines.len());
+        let text = lines_text(&lines[self.first.min(end)..end]);
+        let mut edit = EditDelta::between(&self.text, &text)?;
+        edit.start += self.start;
+        Some(edit)
+    }
+}
+
+/// Returns the text of `lines` with their endings.
+fn lines_text(lines: &[BufferLine]) -> String {
+    lines
+        .iter()
+        .map(|line| line.text().to_string() + line.ending().as_str())
+        .collect()
+}
+
+/// Returns the byte length of `line` with its ending.
+fn line_len(line: &BufferLine) -> usize {
+    line.text().len() + line.ending().as_str().len()
+}
+
+/// Returns the byte range of `before` that differs from `after` and the range of `after` that
+/// replaces it, leaving out the longest common prefix and suffix.
+pub(super) fn changed_ranges(before: &str, after: &str) -> (Range<usize>, Range<usize>) {
+    let on_boundary = |len: usize, from_end: bool| {
+        let (a, b) = if from_end {
+            (before.len() - len, after.len() - len)
+        } else {
+            (len, len)
+        };
+        before.is_char_boundary(a) && after.is_char_boundary(b)
+    };
+
+    let mut prefix = before
+        .bytes()
+        .zip(after.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !on_boundary(prefix, false) {
+        prefix -= 1;
+    }
+    let mut suffix = before[prefix..]
+        .bytes()
+        .rev()
+        .zip(after[prefix..].bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !on_boundary(suffix, true) {
+        suffix -= 1;
+    }
+    (prefix..before.len() - suffix, prefix..after.len() - suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(before: &str, after: &str) -> (Range<usize>, String) {
+        let change = TextChange::from_edit(&EditDelta::between(before, after).unwrap(), 0);
+        (change.range, change.inserted)
+    }
+
+    fn lines(text: &str) -> Vec<BufferLine> {
+        let attrs = glyphon::AttrsList::new(&glyphon::Attrs::new());
+        glyphon::cosmic_text::LineIter::new(text)
+            .map(|(range, ending)| {
+                let shaping = glyphon::Shaping::Advanced;
+                BufferLine::new(&text[range], ending, attrs.clone(), shaping)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_text_change_between() {
+        assert_eq!(change("hello", "hello!"), (5..5, "!".to_string()));
+        assert_eq!(change("hello", "hllo"), (1..2, String::new()));
+        assert_eq!(change("aaa", "aaaa"), (3..3, "a".to_string()));
+        assert_eq!(change("a b c", "a X c"), (2..3, "X".to_string()));
+        // Multi-caret edits become one range
+        assert_eq!(change("x1 x2", "y1 y2"), (0..4, "y1 y".to_string()));
+        // 'é' and 'ê' share their first byte, the range still holds whole characters
+        assert_eq!(change("café", "cafê"), (3..5, "ê".to_string()));
+        assert_eq!(EditDelta::between("same", "same"), None);
+    }
+
+    #[test]
+    fn test_edit_window() {
+        let before = lines("zero\none\ntwo\nthree\nfour");
+        let window = EditWindow::new(&before, 2..=2);
+        assert_eq!(window.line_count(), 5);
+        assert_eq!(window.edit(&before), None);
+
+        // Splitting the line adds one to the window
+        let edit = window
+            .edit(&lines("zero\none\ntw\no\nthree\nfour"))
+            .unwrap();
+        assert_eq!((edit.start, edit.removed.as_str()), (11, ""));
+        assert_eq!(edit.inserted, "\n");
+
+        // Backspace at the start of the line joins it with the one before
+        let edit = window.edit(&lines("zero\nonetwo\nthree\nfour")).unwrap();
+        assert_eq!((edit.start, edit.removed.as_str()), (8, "\n"));
+        assert_eq!(edit.inserted, "");
+
+        // The window stops at the ends of the content
+        let window = EditWindow::new(&before, 0..=4);
+        let edit = window.edit(&lines("zero\none\ntwo\nthree\nfour!")).unwrap();
+        assert_eq!((edit.start, edit.inserted.as_str()), (23, "!"));
+    }
+}
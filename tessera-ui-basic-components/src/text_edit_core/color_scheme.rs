@@ -0,0 +1,92 @@
+//! The colors of the text and the chrome around it, the way editor themes define them.
+
+use tessera_ui::{Color, theme};
+
+/// Relative luminance below which a background counts as dark, about a middle gray.
+const DARK_LUMINANCE: f32 = 0.18;
+
+/// The colors of a [`text_editor`](crate::text_editor::text_editor) from a light or dark
+/// theme. They fill in the colors its [`TextEditorArgs`](crate::text_editor::TextEditorArgs)
+/// leave unset, so a dark theme does not get the light default chrome.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EditorColorScheme {
+    /// Background of the editor, focused or not.
+    pub background: Color,
+    /// Color of the text.
+    pub foreground: Color,
+    /// Color of the caret.
+    pub caret: Color,
+    /// Highlight of the selected text.
+    pub selection: Color,
+    /// Highlight of the line with the caret, or `None` to leave it off.
+    pub current_line: Option<Color>,
+}
+
+impl EditorColorScheme {
+    /// Dark text on a white background.
+    pub fn light() -> Self {
+        Self::from_background(Color::WHITE)
+    }
+
+    /// Light text on a dark gray background.
+    pub fn dark() -> Self {
+        Self::from_background(Color::from_srgb(0.12, 0.12, 0.13, 1.0))
+    }
+
+    /// Derives a scheme from `background`: dark text on a light background and light text on
+    /// a dark one, with a caret, selection and current line that show on it.
+    pub fn from_background(background: Color) -> Self {
+        let dark = is_dark(background);
+        let foreground = if dark {
+            Color::from_srgb(0.85, 0.85, 0.85, 1.0)
+        } else {
+            Color::from_srgb(0.1, 0.1, 0.1, 1.0)
+        };
+        let selection = theme::accent_color().map_or(
+            if dark {
+                Color::from_srgb(0.25, 0.4, 0.65, 0.6)
+            } else {
+                Color::new(0.5, 0.7, 1.0, 0.4)
+            },
+            |accent| accent.with_alpha(if dark { 0.5 } else { 0.4 }),
+        );
+        Self {
+            background,
+            foreground,
+            caret: foreground,
+            selection,
+            current_line: Some(foreground.with_alpha(if dark { 0.08 } else { 0.05 })),
+        }
+    }
+
+    /// Returns `true` if the background is dark.
+    pub fn is_dark(&self) -> bool {
+        is_dark(self.background)
+    }
+}
+
+/// Returns `true` if the relative luminance of `color` is below a middle gray.
+fn is_dark(color: Color) -> bool {
+    0.2126 * color.r + 0.7152 * color.g + 0.0722 * color.b < DARK_LUMINANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_background() {
+        let dark = EditorColorScheme::dark();
+        assert!(dark.is_dark());
+        assert!(dark.foreground.r > 0.5);
+        assert_eq!(dark.caret, dark.foreground);
+
+        let light = EditorColorScheme::light();
+        assert!(!light.is_dark());
+        assert!(light.foreground.r < 0.1);
+
+        // Saturated colors count by how bright they look, not by their channels
+        assert!(EditorColorScheme::from_background(Color::new(0.0, 0.0, 1.0, 1.0)).is_dark());
+        assert!(!EditorColorScheme::from_background(Color::new(0.0, 1.0, 0.0, 1.0)).is_dark());
+    }
+}
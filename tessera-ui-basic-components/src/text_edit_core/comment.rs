@@ -0,0 +1,102 @@
+//! Commenting and uncommenting lines with a line-comment token like `//`.
+
+/// An edit of one line: `removed` bytes at `index` replaced with `inserted`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct LineEdit {
+    pub(super) line: usize,
+    pub(super) index: usize,
+    pub(super) removed: usize,
+    pub(super) inserted: String,
+}
+
+/// Returns the edits that toggle the comment `token` on `lines`, starting at line `first`.
+///
+/// If every non-blank line starts with the token after its indentation, the token and one
+/// space after it are removed. Otherwise the token and a space are inserted in all of them,
+/// at the smallest indentation so the comment markers line up. Blank lines are left alone.
+pub(super) fn toggle_comment_edits(lines: &[&str], first: usize, token: &str) -> Vec<LineEdit> {
+    if token.is_empty() {
+        return Vec::new();
+    }
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let code: Vec<(usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (first + i, *line))
+        .collect();
+
+    let commented = code
+        .iter()
+        .all(|(_, line)| line.trim_start().starts_with(token));
+    if commented {
+        return code
+            .into_iter()
+            .map(|(line, text)| {
+                let after = &text[indent(text) + token.len()..];
+                LineEdit {
+                    line,
+                    index: indent(text),
+                    removed: token.len() + usize::from(after.starts_with(' ')),
+                    inserted: String::new(),
+                }
+            })
+            .collect();
+    }
+
+    let column = code.iter().map(|(_, line)| indent(line)).min().unwrap_or(0);
+    code.into_iter()
+        .map(|(line, _)| LineEdit {
+            line,
+            index: column,
+            removed: 0,
+            inserted: format!("{token} "),
+        })
+        .collect()
+}
+
+/// Returns where the byte `index` on `line` is after `edits`, collapsing positions inside
+/// removed text to its start.
+pub(super) fn shift_index(line: usize, index: usize, edits: &[LineEdit]) -> usize {
+    let Some(edit) = edits.iter().find(|edit| edit.line == line) else {
+        return index;
+    };
+    if index <= edit.index && (edit.removed > 0 || index < edit.index) {
+        index
+    } else if index < edit.index + edit.removed {
+        edit.index + edit.inserted.len()
+    } else {
+        index - edit.removed + edit.inserted.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_comment_edits() {
+        let edit = |line, index, removed, inserted: &str| LineEdit {
+            line,
+            index,
+            removed,
+            inserted: inserted.to_string(),
+        };
+        let lines = ["    let a = 1;", "", "  if a {"];
+        let comment = toggle_comment_edits(&lines, 3, "//");
+        assert_eq!(comment, [edit(3, 2, 0, "// "), edit(5, 2, 0, "// ")]);
+        // The caret follows the text after the inserted token
+        assert_eq!(shift_index(3, 4, &comment), 7);
+        assert_eq!(shift_index(3, 2, &comment), 5);
+        assert_eq!(shift_index(3, 0, &comment), 0);
+        assert_eq!(shift_index(4, 0, &comment), 0);
+
+        let lines = ["  // let a = 1;", "  //if a {"];
+        let uncomment = toggle_comment_edits(&lines, 0, "//");
+        assert_eq!(uncomment, [edit(0, 2, 3, ""), edit(1, 2, 2, "")]);
+        assert_eq!(shift_index(0, 3, &uncomment), 2);
+        assert_eq!(shift_index(0, 9, &uncomment), 6);
+
+        assert!(toggle_comment_edits(&["# x"], 0, "").is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+//! Types for the completion popup of the editor.
+
+use glyphon::Cursor;
+
+/// An entry offered in the completion popup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompletionItem {
+    /// Text shown in the popup.
+    pub label: String,
+    /// Text that replaces the typed prefix when the item is chosen.
+    pub insert_text: String,
+}
+
+impl CompletionItem {
+    /// Creates an item that inserts its own label.
+    pub fn new(label: impl Into<String>) -> Self {
+        let label = label.into();
+        Self {
+            insert_text: label.clone(),
+            label,
+        }
+    }
+}
+
+impl From<&str> for CompletionItem {
+    fn from(label: &str) -> Self {
+        Self::new(label)
+    }
+}
+
+impl From<String> for CompletionItem {
+    fn from(label: String) -> Self {
+        Self::new(label)
+    }
+}
+
+/// What the user has typed when completions are requested.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompletionRequest {
+    /// The word characters directly before the cursor.
+    pub prefix: String,
+    /// The cursor position, at the end of `prefix`.
+    pub cursor: Cursor,
+}
+
+/// An open completion popup.
+#[derive(Clone, Debug)]
+pub(crate) struct ActiveCompletion {
+    pub(crate) items: Vec<CompletionItem>,
+    pub(crate) selected: usize,
+    /// Start of the prefix that a chosen item replaces.
+    pub(crate) start: Cursor,
+}
+
+/// Returns `true` for characters that are part of a completion prefix.
+pub(crate) fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Returns the byte index where the run of word characters ending at `index` starts.
+pub(crate) fn prefix_start(line: &str, index: usize) -> usize {
+    line[..index]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| is_word_char(c))
+        .last()
+        .map_or(index, |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_start() {
+        assert_eq!(prefix_start("let foo_bar", 11), 4);
+        assert_eq!(prefix_start("let foo_bar", 7), 4);
+        assert_eq!(prefix_start("x.", 2), 2);
+        assert_eq!(prefix_start("größe", "größe".len()), 0);
+        assert_eq!(prefix_start("", 0), 0);
+    }
+}
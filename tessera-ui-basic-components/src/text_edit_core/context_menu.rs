@@ -0,0 +1,176 @@
+//! Entries of the context menu opened by right-clicking the editor.
+
+use std::sync::Arc;
+
+use super::breakpoints::BreakpointCommand;
+
+/// An editing command offered by the context menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EditCommand {
+    Cut,
+    Copy,
+    Paste,
+    SelectAll,
+}
+
+impl EditCommand {
+    const ALL: [Self; 4] = [Self::Cut, Self::Copy, Self::Paste, Self::SelectAll];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Cut => "Cut",
+            Self::Copy => "Copy",
+            Self::Paste => "Paste",
+            Self::SelectAll => "Select All",
+        }
+    }
+
+    /// Returns `true` if the command does anything, e.g. Copy needs a selection.
+    fn is_enabled(self, has_selection: bool, read_only: bool) -> bool {
+        match self {
+            Self::Cut => has_selection && !read_only,
+            Self::Copy => has_selection,
+            Self::Paste => !read_only,
+            Self::SelectAll => true,
+        }
+    }
+}
+
+/// An entry of the context menu added by the application, listed after Cut, Copy, Paste and
+/// Select All.
+#[derive(Clone)]
+pub struct ContextMenuItem {
+    /// Text shown in the menu.
+    pub label: String,
+    /// Called when the entry is clicked, after the menu closed.
+    pub on_select: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl ContextMenuItem {
+    /// Creates an entry that calls `on_select` when it is clicked.
+    pub fn new(label: impl Into<String>, on_select: impl Fn() + Send + Sync + 'static) -> Self {
+        Self {
+            label: label.into(),
+            on_select: Arc::new(on_select),
+        }
+    }
+}
+
+/// What clicking an entry of the context menu does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ContextMenuChoice {
+    Command(EditCommand),
+    /// The spelling suggestion at this index.
+    Suggestion(usize),
+    /// A command of the gutter menu on the line it was opened on.
+    Breakpoint(BreakpointCommand),
+    /// The [`ContextMenuItem`] at this index.
+    Custom(usize),
+}
+
+/// An entry as shown in the context menu.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ContextMenuEntry {
+    pub(crate) label: String,
+    /// Disabled entries are greyed out and cannot be clicked.
+    pub(crate) enabled: bool,
+    pub(crate) choice: ContextMenuChoice,
+}
+
+/// Returns the entries of the context menu: the spelling `suggestions` when it was opened on
+/// a misspelled word, then the editing commands, then `custom`.
+pub(crate) fn menu_entries(
+    has_selection: bool,
+    read_only: bool,
+    suggestions: Option<&[String]>,
+    custom: &[ContextMenuItem],
+) -> Vec<ContextMenuEntry> {
+    let suggestions = suggestions.into_iter().flat_map(|suggestions| {
+        if suggestions.is_empty() {
+            return vec![ContextMenuEntry {
+                label: "No Suggestions".to_string(),
+                enabled: false,
+                choice: ContextMenuChoice::Suggestion(0),
+            }];
+        }
+        suggestions
+            .iter()
+            .enumerate()
+            .map(|(index, suggestion)| ContextMenuEntry {
+                label: suggestion.clone(),
+                enabled: !read_only,
+                choice: ContextMenuChoice::Suggestion(index),
+            })
+            .collect()
+    });
+    let commands = EditCommand::ALL
+        .into_iter()
+        .map(|command| ContextMenuEntry {
+            label: command.label().to_string(),
+            enabled: command.is_enabled(has_selection, read_only),
+            choice: ContextMenuChoice::Command(command),
+        });
+    let custom = custom
+        .iter()
+        .enumerate()
+        .map(|(index, item)| ContextMenuEntry {
+            label: item.label.clone(),
+            enabled: true,
+            choice: ContextMenuChoice::Custom(index),
+        });
+    suggestions.chain(commands).chain(custom).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(has_selection: bool, read_only: bool) -> Vec<(String, bool)> {
+        let custom = [ContextMenuItem::new("Look Up", || {})];
+        menu_entries(has_selection, read_only, None, &custom)
+            .into_iter()
+            .map(|entry| (entry.label, entry.enabled))
+            .collect()
+    }
+
+    #[test]
+    fn test_context_menu_entries() {
+        let entry = |label: &str, enabled| (label.to_string(), enabled);
+        assert_eq!(
+            enabled(false, false),
+            [
+                entry("Cut", false),
+                entry("Copy", false),
+                entry("Paste", true),
+                entry("Select All", true),
+                entry("Look Up", true),
+            ]
+        );
+        // A read-only editor only copies
+        assert_eq!(
+            enabled(true, true)[..3],
+            [
+                entry("Cut", false),
+                entry("Copy", true),
+                entry("Paste", false)
+            ]
+        );
+        let custom = [ContextMenuItem::new("Look Up", || {})];
+        assert_eq!(
+            menu_entries(true, false, None, &custom)[4].choice,
+            ContextMenuChoice::Custom(0)
+        );
+
+        // Suggestions for a misspelled word come first
+        let suggestions = ["quick".to_string(), "quirk".to_string()];
+        let entries = menu_entries(false, false, Some(&suggestions), &custom);
+        assert_eq!(entries[1].label, "quirk");
+        assert_eq!(entries[1].choice, ContextMenuChoice::Suggestion(1));
+        assert_eq!(entries[2].label, "Cut");
+        let entries = menu_entries(false, false, Some(&[]), &custom);
+        assert_eq!(
+            (entries[0].label.as_str(), entries[0].enabled),
+            ("No Suggestions", false)
+        );
+    }
+}
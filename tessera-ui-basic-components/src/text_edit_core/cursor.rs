@@ -6,7 +6,7 @@
 
 use std::time::Instant;
 
-use tessera_ui::{Color, ComputedData, Dp, Px, tessera};
+use tessera_ui::{Color, ComputedData, Dp, Px, clock, tessera};
 
 use crate::pipelines::ShapeCommand;
 
@@ -23,6 +23,9 @@ pub(crate) const CURSOR_WIDRH: Dp = Dp(2.5);
 ///
 /// * `height_px` - The height of the cursor in pixels, typically matching the line height
 /// * `bink_timer` - Timer used to control the blinking animation cycle
+/// * `block_width` - Draws a translucent block of this width instead of a line, used in
+///   overwrite mode
+/// * `color` - The color of the line, or of the block at a third of its opacity
 ///
 /// # Blinking Behavior
 ///
@@ -38,28 +41,32 @@ pub(crate) const CURSOR_WIDRH: Dp = Dp(2.5);
 /// use tessera_ui::Px;
 ///
 /// // Create a cursor with line height and current time
-/// cursor(Px(20.0), Instant::now());
+/// cursor(Px(20.0), Instant::now(), None, Color::BLACK);
 /// ```
 ///
 /// # Rendering
 ///
-/// The cursor is rendered as a solid black rectangle with:
-/// - Fixed width of 2.5 device-independent pixels
+/// The cursor is rendered as a solid rectangle of `color` with:
+/// - Fixed width of 2.5 device-independent pixels, or a translucent block of `block_width`
 /// - Variable height matching the text line height
 /// - No corner radius (sharp rectangular appearance)
 /// - No shadow effects
 #[tessera]
-pub(super) fn cursor(height_px: Px, bink_timer: Instant) {
+pub(super) fn cursor(height_px: Px, bink_timer: Instant, block_width: Option<Px>, color: Color) {
     // Skip rendering the cursor during the "off" phase of the blink cycle
     // to create the blinking effect (visible for 500ms, hidden for 500ms)
-    if bink_timer.elapsed().as_millis() % 1000 < 500 {
+    if clock::elapsed(bink_timer).as_millis() % 1000 < 500 {
         return;
     }
 
     measure(Box::new(move |input| {
         // Create a rectangular cursor shape with fixed width and variable height
         let drawable = ShapeCommand::Rect {
-            color: Color::BLACK,
+            color: if block_width.is_some() {
+                color.with_alpha(color.a * 0.35)
+            } else {
+                color
+            },
             corner_radii: glam::Vec4::ZERO.into(),
             g2_k_value: 3.0, // Use G2-like corners
             shadow: None,
@@ -70,7 +77,7 @@ pub(super) fn cursor(height_px: Px, bink_timer: Instant) {
 
         // Return the computed dimensions for layout calculation
         Ok(ComputedData {
-            width: CURSOR_WIDRH.into(),
+            width: block_width.unwrap_or(CURSOR_WIDRH.into()),
             height: height_px,
         })
     }));
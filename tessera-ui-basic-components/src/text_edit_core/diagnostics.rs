@@ -0,0 +1,129 @@
+//! Diagnostics attached to ranges of the editor content, e.g. from a language server.
+
+use glyphon::Cursor;
+use tessera_ui::{Color, ComputedData, Px, PxPosition, tessera};
+
+use super::TextRange;
+use crate::selection_highlight_rect::selection_highlight_rect;
+
+/// How serious a [`Diagnostic`] is. Mirrors the severities of the Language Server Protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DiagnosticSeverity {
+    /// Code that is wrong, e.g. a compile error.
+    Error,
+    /// Code that is likely wrong.
+    Warning,
+    /// Something worth knowing about the code.
+    Information,
+    /// A suggestion, e.g. a possible simplification.
+    Hint,
+}
+
+impl DiagnosticSeverity {
+    /// Returns the color used to underline diagnostics of this severity.
+    pub fn color(self) -> Color {
+        match self {
+            DiagnosticSeverity::Error => Color::new(0.9, 0.2, 0.2, 1.0),
+            DiagnosticSeverity::Warning => Color::new(0.95, 0.65, 0.1, 1.0),
+            DiagnosticSeverity::Information => Color::new(0.2, 0.5, 0.95, 1.0),
+            DiagnosticSeverity::Hint => Color::new(0.55, 0.55, 0.55, 1.0),
+        }
+    }
+}
+
+/// A message about a range of the editor content, drawn as a squiggly underline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// The text the diagnostic is about.
+    pub range: TextRange,
+    /// How serious the diagnostic is, which decides the underline color.
+    pub severity: DiagnosticSeverity,
+    /// The message, e.g. for a tooltip shown on hover.
+    pub message: String,
+}
+
+/// Returns the index of the diagnostic whose range contains `cursor`.
+///
+/// When ranges overlap, the most severe diagnostic wins, then the first one.
+pub(crate) fn diagnostic_index_at(diagnostics: &[Diagnostic], cursor: Cursor) -> Option<usize> {
+    let at = (cursor.line, cursor.index);
+    diagnostics
+        .iter()
+        .enumerate()
+        .filter(|(_, d)| {
+            (d.range.start.line, d.range.start.index) <= at
+                && at < (d.range.end.line, d.range.end.index)
+        })
+        .min_by_key(|(i, d)| (d.severity as u8, *i))
+        .map(|(i, _)| i)
+}
+
+/// Thickness of one stroke of the underline.
+const STROKE: Px = Px(2);
+/// Length of one segment of the underline wave.
+const SEGMENT: Px = Px(3);
+/// Height of a [`diagnostic_underline`].
+pub(super) const UNDERLINE_HEIGHT: Px = Px(STROKE.0 * 2);
+
+/// A squiggly underline of the given width, built from short segments alternating between
+/// two heights.
+#[tessera]
+pub(super) fn diagnostic_underline(width: Px, color: Color) {
+    let segments = (width.0 + SEGMENT.0 - 1) / SEGMENT.0;
+    for _ in 0..segments {
+        selection_highlight_rect(SEGMENT, STROKE, color);
+    }
+
+    measure(Box::new(move |input| {
+        input.enable_clipping();
+        for (i, &child) in input.children_ids.iter().enumerate() {
+            input.measure_child(child, input.parent_constraint)?;
+            let y = if i % 2 == 0 { Px(0) } else { STROKE };
+            input.place_child(child, PxPosition::new(SEGMENT * i as i32, y));
+        }
+        Ok(ComputedData {
+            width,
+            height: UNDERLINE_HEIGHT,
+        })
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(
+        start: (usize, usize),
+        end: (usize, usize),
+        severity: DiagnosticSeverity,
+    ) -> Diagnostic {
+        Diagnostic {
+            range: TextRange {
+                start: Cursor::new(start.0, start.1),
+                end: Cursor::new(end.0, end.1),
+            },
+            severity,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_diagnostic_index_at() {
+        let diagnostics = [
+            diagnostic((0, 2), (1, 3), DiagnosticSeverity::Warning),
+            diagnostic((1, 0), (1, 5), DiagnosticSeverity::Error),
+        ];
+        assert_eq!(diagnostic_index_at(&diagnostics, Cursor::new(0, 1)), None);
+        assert_eq!(
+            diagnostic_index_at(&diagnostics, Cursor::new(0, 2)),
+            Some(0)
+        );
+        // Overlap: the error is more severe
+        assert_eq!(
+            diagnostic_index_at(&diagnostics, Cursor::new(1, 1)),
+            Some(1)
+        );
+        // End is exclusive
+        assert_eq!(diagnostic_index_at(&diagnostics, Cursor::new(1, 5)), None);
+    }
+}
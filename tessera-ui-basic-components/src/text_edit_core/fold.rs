@@ -0,0 +1,104 @@
+//! Helpers for collapsing regions of lines.
+
+/// Returns the indentation width of `line` in columns, or `None` if the line is blank.
+fn indent_columns(line: &str, tab_width: usize) -> Option<usize> {
+    let body = line.trim_start_matches([' ', '\t']);
+    if body.trim().is_empty() {
+        return None;
+    }
+    let tab_width = tab_width.max(1);
+    Some(
+        line[..line.len() - body.len()]
+            .chars()
+            .fold(0, |column, c| match c {
+                '\t' => column + tab_width - column % tab_width,
+                _ => column + 1,
+            }),
+    )
+}
+
+/// Returns the last line of the block indented deeper than `lines[header]`.
+///
+/// Blank lines belong to the block only when a deeper indented line follows them. Returns
+/// `None` if the header is blank or the next non-blank line is not indented deeper.
+pub(crate) fn indented_block_end(lines: &[&str], header: usize, tab_width: usize) -> Option<usize> {
+    let header_indent = indent_columns(lines.get(header)?, tab_width)?;
+    let mut end = None;
+    for (i, line) in lines.iter().enumerate().skip(header + 1) {
+        match indent_columns(line, tab_width) {
+            None => {}
+            Some(indent) if indent > header_indent => end = Some(i),
+            Some(_) => break,
+        }
+    }
+    end
+}
+
+/// Returns `true` if `line` is hidden by one of `folds`, i.e. lies after a fold's header.
+pub(crate) fn is_hidden(folds: &[(usize, usize)], line: usize) -> bool {
+    folds
+        .iter()
+        .any(|&(header, last)| header < line && line <= last)
+}
+
+/// Updates `folds` after an edit of the lines `first..=last` changed the number of lines by
+/// `delta`.
+///
+/// Folds before the edit are kept, folds after it are moved, and folds the edit touched are
+/// opened since their lines can no longer be told apart.
+pub(crate) fn shift_folds(
+    folds: &mut Vec<(usize, usize)>,
+    first: usize,
+    last: usize,
+    delta: isize,
+) {
+    folds.retain_mut(|(header, end)| {
+        if *end < first {
+            return true;
+        }
+        if *header > last {
+            *header = header.saturating_add_signed(delta);
+            *end = end.saturating_add_signed(delta);
+            return true;
+        }
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indented_block_end() {
+        let lines = [
+            "fn main() {",
+            "    let x = 1;",
+            "",
+            "    if x > 0 {",
+            "\t\tprint(x);",
+            "    }",
+            "}",
+            "",
+        ];
+        assert_eq!(indented_block_end(&lines, 0, 4), Some(5));
+        assert_eq!(indented_block_end(&lines, 3, 4), Some(4));
+        assert_eq!(indented_block_end(&lines, 1, 4), None);
+        assert_eq!(indented_block_end(&lines, 2, 4), None);
+        // Trailing blank lines are not part of the block
+        assert_eq!(indented_block_end(&["a", "  b", "", ""], 0, 4), Some(1));
+    }
+
+    #[test]
+    fn test_shift_folds() {
+        let mut folds = vec![(0, 2), (4, 6), (10, 12)];
+        // Two lines inserted at line 8
+        shift_folds(&mut folds, 7, 8, 2);
+        assert_eq!(folds, vec![(0, 2), (4, 6), (12, 14)]);
+        // A line removed inside the second fold
+        shift_folds(&mut folds, 4, 5, -1);
+        assert_eq!(folds, vec![(0, 2), (11, 13)]);
+        assert!(is_hidden(&folds, 12));
+        assert!(!is_hidden(&folds, 11));
+    }
+}
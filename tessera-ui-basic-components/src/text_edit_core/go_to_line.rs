@@ -0,0 +1,47 @@
+//! Parsing the `line[:column]` typed into the go-to-line popup.
+
+/// Returns the line and the optional column of `input`, both counted from one as shown to
+/// users, converted to zero-based indices. Surrounding whitespace is ignored; anything else
+/// than one or two numbers separated by `:` gives `None`.
+pub(super) fn parse_target(input: &str) -> Option<(usize, Option<usize>)> {
+    let (line, column) = match input.trim().split_once(':') {
+        Some((line, column)) => (line, Some(column)),
+        None => (input.trim(), None),
+    };
+    let number = |text: &str| {
+        text.trim()
+            .parse::<usize>()
+            .ok()
+            .map(|n| n.saturating_sub(1))
+    };
+    let column = match column {
+        Some(column) => Some(number(column)?),
+        None => None,
+    };
+    Some((number(line)?, column))
+}
+
+/// Returns the byte index of the character at `column` in `text`, or its length past the
+/// end.
+pub(super) fn char_column_index(text: &str, column: usize) -> usize {
+    text.char_indices()
+        .nth(column)
+        .map_or(text.len(), |(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(parse_target("12"), Some((11, None)));
+        assert_eq!(parse_target(" 3:7 "), Some((2, Some(6))));
+        assert_eq!(parse_target("0"), Some((0, None)));
+        assert_eq!(parse_target("3:"), None);
+        assert_eq!(parse_target("x"), None);
+        assert_eq!(parse_target(""), None);
+        assert_eq!(char_column_index("aé b", 2), 3);
+        assert_eq!(char_column_index("ab", 9), 2);
+    }
+}
@@ -0,0 +1,222 @@
+//! Per-line marks in a strip left of the text, such as breakpoints or changed lines, and
+//! the line numbers.
+
+use tessera_ui::{Color, Px};
+
+use super::{RectDef, fold, lens_row};
+
+/// Width of a [`GutterMark::Bar`].
+const BAR_WIDTH: Px = Px(3);
+/// Space between the line numbers and the marks or the text.
+pub(crate) const LINE_NUMBER_GAP: Px = Px(8);
+/// Width and height of the dash marking the continuation rows of a wrapped line.
+pub(super) const WRAP_INDICATOR_SIZE: (Px, Px) = (Px(6), Px(2));
+
+/// A laid out row of the text. A line wider than the editor is broken into several rows
+/// when wrapping is on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VisualRow {
+    /// Index of the line the row belongs to.
+    pub line: usize,
+    /// Top of the row relative to the top of the text area, in pixels, below the lens row
+    /// above it, if any.
+    pub top: f32,
+    /// Height of the row in pixels.
+    pub height: f32,
+    /// `true` for the rows after the first one of a wrapped line.
+    pub continuation: bool,
+}
+
+/// How a [`GutterAnnotation`] is drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GutterMark {
+    /// A filled circle in the first row of the line, e.g. a breakpoint.
+    #[default]
+    Dot,
+    /// A thin bar next to the text along all rows of the line, e.g. an added or changed line.
+    Bar,
+}
+
+/// A mark drawn in the gutter of a [`text_editor`](crate::text_editor::text_editor) next to
+/// one line.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GutterAnnotation {
+    /// Index of the line.
+    pub line: usize,
+    /// Shape of the mark.
+    pub mark: GutterMark,
+    /// Color of the mark.
+    pub color: Color,
+}
+
+impl GutterAnnotation {
+    /// Creates a dot next to `line`.
+    pub fn dot(line: usize, color: Color) -> Self {
+        Self {
+            line,
+            mark: GutterMark::Dot,
+            color,
+        }
+    }
+
+    /// Creates a bar next to `line`.
+    pub fn bar(line: usize, color: Color) -> Self {
+        Self {
+            line,
+            mark: GutterMark::Bar,
+            color,
+        }
+    }
+}
+
+/// Returns the laid out rows of `buffer`, relative to the top of the text area.
+pub(super) fn layout_rows(buffer: &glyphon::Buffer) -> Vec<VisualRow> {
+    let line_height = buffer.metrics().line_height;
+    buffer
+        .layout_runs()
+        .map(|run| (lens_row::text_row(&run, line_height), run))
+        .map(|((top, height), run)| VisualRow {
+            line: run.line_i,
+            top,
+            height,
+            // Scrolling can cut off the first row of a line, but not the text before the row
+            continuation: run.glyphs.iter().map(|glyph| glyph.start).min() > Some(0),
+        })
+        .collect()
+}
+
+/// Returns the rects of the `annotations` on the laid out `rows` in a gutter of `width`,
+/// with the annotation each one draws. Lines hidden by `folds` or not laid out get none.
+pub(super) fn gutter_rects(
+    rows: &[VisualRow],
+    annotations: &[GutterAnnotation],
+    width: Px,
+    folds: &[(usize, usize)],
+) -> Vec<(RectDef, GutterAnnotation)> {
+    annotations
+        .iter()
+        .filter(|annotation| !fold::is_hidden(folds, annotation.line))
+        .filter_map(|annotation| {
+            let mut line_rows = rows.iter().filter(|row| row.line == annotation.line);
+            let &VisualRow { top, height, .. } = line_rows.next()?;
+            let rect = match annotation.mark {
+                GutterMark::Dot => {
+                    let size = Px((width.to_f32().min(height) * 0.6).round() as i32);
+                    RectDef {
+                        x: (width - size) / 2,
+                        y: Px((top + (height - size.to_f32()) / 2.0).round() as i32),
+                        width: size,
+                        height: size,
+                    }
+                }
+                GutterMark::Bar => {
+                    let bottom = line_rows
+                        .next_back()
+                        .map_or(top + height, |row| row.top + row.height);
+                    RectDef {
+                        x: (width - BAR_WIDTH * 2).max(Px(0)),
+                        y: Px(top.round() as i32),
+                        width: BAR_WIDTH,
+                        height: Px((bottom - top).round() as i32),
+                    }
+                }
+            };
+            Some((rect, *annotation))
+        })
+        .collect()
+}
+
+/// Returns the line whose rows cover `y` among the laid out `rows`, skipping lines hidden by
+/// `folds`, or `None` below the last line.
+pub(super) fn line_at_y(rows: &[VisualRow], y: f32, folds: &[(usize, usize)]) -> Option<usize> {
+    rows.iter()
+        .find(|row| {
+            !fold::is_hidden(folds, row.line) && (row.top..row.top + row.height).contains(&y)
+        })
+        .map(|row| row.line)
+}
+
+/// Returns the rows that show the number of their line: the first row of every line not
+/// hidden by `folds`.
+pub(super) fn line_number_rows(rows: &[VisualRow], folds: &[(usize, usize)]) -> Vec<VisualRow> {
+    rows.iter()
+        .filter(|row| !row.continuation && !fold::is_hidden(folds, row.line))
+        .copied()
+        .collect()
+}
+
+/// Returns the rects of the wrap indicators of the continuation `rows` at `x`, vertically
+/// centered on their row.
+pub(super) fn wrap_indicator_rects(
+    rows: &[VisualRow],
+    x: Px,
+    folds: &[(usize, usize)],
+) -> Vec<RectDef> {
+    let (width, height) = WRAP_INDICATOR_SIZE;
+    rows.iter()
+        .filter(|row| row.continuation && !fold::is_hidden(folds, row.line))
+        .map(|row| RectDef {
+            x,
+            y: Px((row.top + (row.height - height.to_f32()) / 2.0).round() as i32),
+            width,
+            height,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gutter_rects() {
+        // Line 1 wraps onto two rows, line 3 is folded into line 2
+        let row = |line, top, height, continuation| VisualRow {
+            line,
+            top,
+            height,
+            continuation,
+        };
+        let rows = [
+            row(0, 0.0, 20.0, false),
+            row(1, 20.0, 20.0, false),
+            row(1, 40.0, 20.0, true),
+            row(2, 60.0, 20.0, false),
+            row(3, 80.0, 1.0, false),
+        ];
+        let folds = [(2, 3)];
+        let red = Color::new(1.0, 0.0, 0.0, 1.0);
+        let annotations = [
+            GutterAnnotation::dot(0, red),
+            GutterAnnotation::bar(1, red),
+            GutterAnnotation::dot(3, red),
+            GutterAnnotation::dot(9, red),
+        ];
+        let rects: Vec<RectDef> = gutter_rects(&rows, &annotations, Px(20), &folds)
+            .into_iter()
+            .map(|(rect, _)| rect)
+            .collect();
+        let rect = |x, y, width, height| RectDef {
+            x: Px(x),
+            y: Px(y),
+            width: Px(width),
+            height: Px(height),
+        };
+        assert_eq!(rects, [rect(4, 4, 12, 12), rect(14, 20, 3, 40)]);
+
+        assert_eq!(line_at_y(&rows, 45.0, &folds), Some(1));
+        assert_eq!(line_at_y(&rows, 80.5, &folds), None);
+        assert_eq!(line_at_y(&rows, 200.0, &folds), None);
+
+        // Only the first row of a line is numbered, the second one of line 1 is marked
+        let numbered: Vec<usize> = line_number_rows(&rows, &folds)
+            .iter()
+            .map(|row| row.line)
+            .collect();
+        assert_eq!(numbered, [0, 1, 2]);
+        assert_eq!(
+            wrap_indicator_rects(&rows, Px(10), &folds),
+            [rect(10, 49, 6, 2)]
+        );
+    }
+}
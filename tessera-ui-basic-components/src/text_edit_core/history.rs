@@ -0,0 +1,410 @@
+//! Undo/redo history for [`super::TextEditorState`].
+//!
+//! Every step stores its edits as replaced ranges of the content, together with the cursor
+//! and selection before and after it, so a step takes memory for what it changed rather than
+//! for the whole content. Consecutive insertions (typing a word) and consecutive deletions
+//! are grouped into a single step so undo behaves like it does in common editors.
+
+use std::collections::VecDeque;
+
+use glyphon::{Cursor, cosmic_text::Selection};
+
+use super::change::changed_ranges;
+
+/// Default number of undo steps kept by an editor.
+pub(crate) const DEFAULT_HISTORY_DEPTH: usize = 100;
+
+/// Content, cursor and selection of the editor at a point in time, taken before edits that
+/// rewrite much of the content and only kept until their [`EditDelta`] is known.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct EditSnapshot {
+    pub(crate) text: String,
+    pub(crate) cursor: Cursor,
+    pub(crate) selection: Selection,
+}
+
+impl EditSnapshot {
+    pub(crate) fn carets(&self) -> Carets {
+        Carets {
+            cursor: self.cursor,
+            selection: self.selection,
+        }
+    }
+}
+
+/// Cursor and selection of the editor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Carets {
+    pub(crate) cursor: Cursor,
+    pub(crate) selection: Selection,
+}
+
+/// A replacement in the content: `removed` at byte offset `start` became `inserted`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct EditDelta {
+    pub(crate) start: usize,
+    pub(crate) removed: String,
+    pub(crate) inserted: String,
+}
+
+impl EditDelta {
+    /// Returns the delta turning `before` into `after`, leaving out what they have in common
+    /// at both ends, or `None` if they are equal.
+    pub(crate) fn between(before: &str, after: &str) -> Option<Self> {
+        if before == after {
+            return None;
+        }
+        let (removed, inserted) = changed_ranges(before, after);
+        Some(Self {
+            start: removed.start,
+            removed: before[removed].to_string(),
+            inserted: after[inserted].to_string(),
+        })
+    }
+
+    /// Makes the edit in `text`, which holds the content from before it.
+    pub(crate) fn apply(&self, text: &mut String) {
+        text.replace_range(self.start..self.start + self.removed.len(), &self.inserted);
+    }
+
+    /// Takes the edit back in `text`, which holds the content from after it.
+    pub(crate) fn revert(&self, text: &mut String) {
+        text.replace_range(self.start..self.start + self.inserted.len(), &self.removed);
+    }
+}
+
+/// One undo step: its edits in the order they were made, and the carets around them.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct EditStep {
+    deltas: Vec<EditDelta>,
+    pub(crate) before: Carets,
+    pub(crate) after: Carets,
+}
+
+impl EditStep {
+    /// Makes the edits of the step in `text`.
+    pub(crate) fn apply(&self, text: &mut String) {
+        for delta in &self.deltas {
+            delta.apply(text);
+        }
+    }
+
+    /// Takes the edits of the step back in `text`, last first.
+    pub(crate) fn revert(&self, text: &mut String) {
+        for delta in self.deltas.iter().rev() {
+            delta.revert(text);
+        }
+    }
+}
+
+/// Coarse classification of an edit, used to decide whether it can be merged
+/// into the previous undo step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EditKind {
+    /// Insertion of a single character. `whitespace` marks word boundaries.
+    Insert { whitespace: bool },
+    /// Backspace or delete of a single character.
+    Delete,
+    /// A line break. Indentation typed right after it belongs to the same step.
+    NewLine,
+    /// Anything else, never merged.
+    Other,
+}
+
+impl EditKind {
+    pub(crate) fn from_action(action: &glyphon::Action) -> Self {
+        match action {
+            glyphon::Action::Insert(c) => EditKind::Insert {
+                whitespace: c.is_whitespace(),
+            },
+            glyphon::Action::Backspace | glyphon::Action::Delete => EditKind::Delete,
+            glyphon::Action::Enter => EditKind::NewLine,
+            _ => EditKind::Other,
+        }
+    }
+
+    /// Returns `true` if an edit of kind `self` may be appended to a group whose last edit was `previous`.
+    fn continues(self, previous: EditKind) -> bool {
+        match (previous, self) {
+            // A space ends the word; the next word starts a new undo step.
+            (EditKind::Insert { whitespace: true }, EditKind::Insert { whitespace: false }) => {
+                false
+            }
+            (EditKind::Insert { .. }, EditKind::Insert { .. }) => true,
+            (EditKind::NewLine, EditKind::Insert { whitespace: true }) => true,
+            (EditKind::Delete, EditKind::Delete) => true,
+            _ => false,
+        }
+    }
+}
+
+/// The open group of edits, i.e. the step that the next edit may be merged into.
+#[derive(Clone, Copy, Debug)]
+struct OpenGroup {
+    kind: EditKind,
+    cursor_after: Cursor,
+}
+
+/// Bounded undo/redo stacks of [`EditStep`]s.
+#[derive(Debug)]
+pub(crate) struct EditHistory {
+    undo_stack: VecDeque<EditStep>,
+    redo_stack: Vec<EditStep>,
+    max_depth: usize,
+    open_group: Option<OpenGroup>,
+}
+
+impl EditHistory {
+    pub(crate) fn new(max_depth: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            max_depth,
+            open_group: None,
+        }
+    }
+
+    pub(crate) fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Changes the depth, dropping the oldest steps if the history is now too long.
+    pub(crate) fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+        self.trim();
+    }
+
+    /// Records an edit that changed the content by `delta`.
+    ///
+    /// `before` are the carets prior to the edit and `cursor_after` is where the cursor ended
+    /// up. The edit is merged into the previous step when it directly continues it.
+    pub(crate) fn record(
+        &mut self,
+        delta: EditDelta,
+        before: Carets,
+        kind: EditKind,
+        cursor_after: Cursor,
+    ) {
+        self.redo_stack.clear();
+
+        let merges = self.open_group.is_some_and(|group| {
+            kind.continues(group.kind)
+                && group.cursor_after == before.cursor
+                && before.selection == Selection::None
+        });
+        let after = Carets {
+            cursor: cursor_after,
+            selection: Selection::None,
+        };
+
+        match self.undo_stack.back_mut() {
+            Some(step) if merges => {
+                step.deltas.push(delta);
+                step.after = after;
+            }
+            _ => {
+                self.undo_stack.push_back(EditStep {
+                    deltas: vec![delta],
+                    before,
+                    after,
+                });
+                self.trim();
+            }
+        }
+
+        self.open_group = if kind == EditKind::Other {
+            None
+        } else {
+            Some(OpenGroup { kind, cursor_after })
+        };
+    }
+
+    /// Moves the last step onto the redo stack and returns it, to be reverted.
+    pub(crate) fn undo(&mut self) -> Option<EditStep> {
+        let step = self.undo_stack.pop_back()?;
+        self.redo_stack.push(step.clone());
+        self.open_group = None;
+        Some(step)
+    }
+
+    /// Moves the last undone step back onto the undo stack and returns it, to be made
+    /// again.
+    pub(crate) fn redo(&mut self) -> Option<EditStep> {
+        let step = self.redo_stack.pop()?;
+        self.undo_stack.push_back(step.clone());
+        self.trim();
+        self.open_group = None;
+        Some(step)
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Stops merging, so the next edit starts a new undo step.
+    pub(crate) fn break_group(&mut self) {
+        self.open_group = None;
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.open_group = None;
+    }
+
+    fn trim(&mut self) {
+        while self.undo_stack.len() > self.max_depth {
+            self.undo_stack.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn carets(index: usize) -> Carets {
+        Carets {
+            cursor: Cursor::new(0, index),
+            selection: Selection::None,
+        }
+    }
+
+    fn insert(start: usize, inserted: &str) -> EditDelta {
+        EditDelta {
+            start,
+            removed: String::new(),
+            inserted: inserted.to_string(),
+        }
+    }
+
+    /// Makes `delta` in `text` and records it, with the cursor before it at its start and
+    /// after it past what it inserted.
+    fn record(history: &mut EditHistory, text: &mut String, delta: EditDelta, kind: EditKind) {
+        let (start, end) = (delta.start, delta.start + delta.inserted.len());
+        delta.apply(text);
+        history.record(delta, carets(start), kind, Cursor::new(0, end));
+    }
+
+    fn type_str(history: &mut EditHistory, text: &mut String, typed: &str) {
+        for c in typed.chars() {
+            let kind = EditKind::Insert {
+                whitespace: c.is_whitespace(),
+            };
+            record(history, text, insert(text.len(), &c.to_string()), kind);
+        }
+    }
+
+    fn undo(history: &mut EditHistory, text: &mut String) -> Option<Carets> {
+        let step = history.undo()?;
+        step.revert(text);
+        Some(step.before)
+    }
+
+    #[test]
+    fn test_delta_between() {
+        let delta = EditDelta::between("let x = 1;", "let xy = 12;").unwrap();
+        assert_eq!(delta.start, 5);
+        assert_eq!(
+            (delta.removed.as_str(), delta.inserted.as_str()),
+            (" = 1", "y = 12")
+        );
+        let mut text = "let x = 1;".to_string();
+        delta.apply(&mut text);
+        assert_eq!(text, "let xy = 12;");
+        delta.revert(&mut text);
+        assert_eq!(text, "let x = 1;");
+        assert_eq!(EditDelta::between("same", "same"), None);
+    }
+
+    #[test]
+    fn test_consecutive_insertions_are_grouped_by_word() {
+        let mut history = EditHistory::new(DEFAULT_HISTORY_DEPTH);
+        let mut text = String::new();
+        type_str(&mut history, &mut text, "hello world");
+
+        assert_eq!(undo(&mut history, &mut text), Some(carets(6)));
+        assert_eq!(text, "hello ");
+        assert_eq!(undo(&mut history, &mut text), Some(carets(0)));
+        assert_eq!(text, "");
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_indentation_after_newline_is_one_step() {
+        let mut history = EditHistory::new(DEFAULT_HISTORY_DEPTH);
+        let mut text = "a\n ".to_string();
+        history.record(
+            insert(1, "\n"),
+            carets(1),
+            EditKind::NewLine,
+            Cursor::new(1, 0),
+        );
+        let before = Carets {
+            cursor: Cursor::new(1, 0),
+            selection: Selection::None,
+        };
+        let indent = EditKind::Insert { whitespace: true };
+        history.record(insert(2, " "), before, indent, Cursor::new(1, 1));
+
+        undo(&mut history, &mut text);
+        assert_eq!(text, "a");
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_redo_restores_undone_step_and_new_edit_clears_it() {
+        let mut history = EditHistory::new(DEFAULT_HISTORY_DEPTH);
+        let mut text = String::new();
+        type_str(&mut history, &mut text, "abc");
+
+        undo(&mut history, &mut text);
+        let step = history.redo().unwrap();
+        step.apply(&mut text);
+        assert_eq!(text, "abc");
+        assert_eq!(step.after, carets(3));
+
+        undo(&mut history, &mut text);
+        record(&mut history, &mut text, insert(0, "x"), EditKind::Other);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_cursor_jump_breaks_group() {
+        let mut history = EditHistory::new(DEFAULT_HISTORY_DEPTH);
+        let mut text = String::new();
+        type_str(&mut history, &mut text, "ab");
+        // Continue typing at the start of the line rather than where the last edit ended.
+        let kind = EditKind::Insert { whitespace: false };
+        record(&mut history, &mut text, insert(0, "x"), kind);
+
+        undo(&mut history, &mut text);
+        assert_eq!(text, "ab");
+        assert!(history.can_undo());
+    }
+
+    #[test]
+    fn test_depth_limit_drops_oldest_steps() {
+        let mut history = EditHistory::new(2);
+        let mut text = String::new();
+        for i in 0..5 {
+            let delta = insert(i, &i.to_string());
+            record(&mut history, &mut text, delta, EditKind::Other);
+        }
+
+        undo(&mut history, &mut text);
+        assert_eq!(text, "0123");
+        undo(&mut history, &mut text);
+        assert_eq!(text, "012");
+        assert!(undo(&mut history, &mut text).is_none());
+
+        record(&mut history, &mut text, insert(3, "3"), EditKind::Other);
+        assert!(history.can_undo());
+        history.set_max_depth(0);
+        assert!(!history.can_undo());
+    }
+}
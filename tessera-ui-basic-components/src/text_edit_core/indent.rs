@@ -0,0 +1,71 @@
+//! Indentation helpers for line-oriented editing.
+
+/// Default number of columns per indent level.
+pub(crate) const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// Returns the indentation for a new line split off at the end of `before_cursor`.
+///
+/// The leading whitespace of the line is kept, and one `unit` is added when the last
+/// non-whitespace character before the cursor is one of `indent_after`.
+pub(crate) fn newline_indent(before_cursor: &str, indent_after: &[char], unit: &str) -> String {
+    let leading_len = before_cursor.len() - before_cursor.trim_start().len();
+    let mut indent = before_cursor[..leading_len].to_string();
+    if before_cursor
+        .trim_end()
+        .chars()
+        .next_back()
+        .is_some_and(|c| indent_after.contains(&c))
+    {
+        indent.push_str(unit);
+    }
+    indent
+}
+
+/// Returns the number of spaces that move `before_cursor` to the next tab stop.
+pub(crate) fn spaces_to_tab_stop(before_cursor: &str, tab_width: usize) -> usize {
+    let tab_width = tab_width.max(1);
+    tab_width - before_cursor.chars().count() % tab_width
+}
+
+/// Returns the byte length of the indentation one dedent removes from the start of `line`:
+/// a single tab, or up to `tab_width` spaces.
+pub(crate) fn dedent_len(line: &str, tab_width: usize) -> usize {
+    if line.starts_with('\t') {
+        return 1;
+    }
+    line.chars()
+        .take(tab_width)
+        .take_while(|&c| c == ' ')
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newline_indent() {
+        let triggers = ['{', ':'];
+        assert_eq!(newline_indent("    let x = 1;", &triggers, "    "), "    ");
+        assert_eq!(
+            newline_indent("\tfn main() { ", &triggers, "    "),
+            "\t    "
+        );
+        assert_eq!(newline_indent("if x:", &triggers, "\t"), "\t");
+        assert_eq!(newline_indent("  ", &triggers, "    "), "  ");
+        assert_eq!(newline_indent("", &[], "    "), "");
+    }
+
+    #[test]
+    fn test_tab_stops_and_dedent() {
+        assert_eq!(spaces_to_tab_stop("", 4), 4);
+        assert_eq!(spaces_to_tab_stop("ab", 4), 2);
+        assert_eq!(spaces_to_tab_stop("abcd", 4), 4);
+        assert_eq!(spaces_to_tab_stop("x", 0), 1);
+
+        assert_eq!(dedent_len("      x", 4), 4);
+        assert_eq!(dedent_len("  x", 4), 2);
+        assert_eq!(dedent_len("\t  x", 4), 1);
+        assert_eq!(dedent_len("x", 4), 0);
+    }
+}
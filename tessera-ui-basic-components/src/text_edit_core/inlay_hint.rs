@@ -0,0 +1,244 @@
+//! Inlay hints: labels such as inferred types or parameter names drawn between the
+//! characters of a line without being part of the content.
+//!
+//! The room of a hint is made by spacing the character before it wider, so the line is laid
+//! out, wrapped and hit-tested with the hint in place while the text stays untouched. The
+//! label is then drawn in that room.
+
+use glyphon::{Cursor, Edit};
+use tessera_ui::{Color, ComputedData, Px, tessera};
+
+use super::{RectDef, lens_row};
+use crate::pipelines::{TextCommand, TextConstraint, TextData};
+
+/// Size of the label text relative to the editor font.
+pub(super) const INLAY_HINT_SCALE: f32 = 0.9;
+
+/// Room left on a padded side of a label, relative to the editor font size.
+const PADDING: f32 = 0.3;
+
+/// Default color of inlay hint labels.
+pub(super) const DEFAULT_INLAY_HINT_COLOR: Color = Color::new(0.55, 0.55, 0.55, 1.0);
+
+/// A label shown inline in the content, e.g. the type a language server inferred for a
+/// variable or the name of the parameter an argument is passed to.
+///
+/// Hints cannot be edited, selected or copied, and the caret steps over them. A hint needs a
+/// character before it on its line, so hints at the start of a line are not shown.
+///
+/// # Example
+///
+/// ```
+/// use glyphon::Cursor;
+/// use tessera_ui::Dp;
+/// use tessera_ui_basic_components::text_editor::{InlayHint, TextEditorState};
+///
+/// // For `let x = compute(2);`
+/// let mut state = TextEditorState::new(Dp(14.0), None);
+/// state.set_inlay_hints(vec![
+///     InlayHint::new(Cursor::new(0, 5), ": i32").with_padding(false, true),
+///     InlayHint::new(Cursor::new(0, 16), "count:").with_padding(false, true),
+/// ]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlayHint {
+    /// Where the hint is shown: after the character before this position.
+    pub position: Cursor,
+    /// The text of the label.
+    pub label: String,
+    /// Whether to leave a gap between the text before the hint and the label.
+    pub padding_left: bool,
+    /// Whether to leave a gap between the label and the text after the hint.
+    pub padding_right: bool,
+}
+
+impl InlayHint {
+    /// Creates a hint with `label` at `position`, without padding.
+    pub fn new(position: Cursor, label: impl Into<String>) -> Self {
+        Self {
+            position,
+            label: label.into(),
+            padding_left: false,
+            padding_right: false,
+        }
+    }
+
+    /// Sets whether gaps are left before and after the label.
+    pub fn with_padding(mut self, left: bool, right: bool) -> Self {
+        self.padding_left = left;
+        self.padding_right = right;
+        self
+    }
+}
+
+/// Returns the room taken by `hint` in an editor with the given font metrics, and the left
+/// padding of its label within that room.
+pub(super) fn hint_width(hint: &InlayHint, metrics: glyphon::Metrics) -> (f32, f32) {
+    let label = TextData::new(
+        hint.label.clone(),
+        Color::BLACK,
+        metrics.font_size * INLAY_HINT_SCALE,
+        metrics.line_height,
+        TextConstraint::NONE,
+    );
+    let padding = |padded: bool| {
+        if padded {
+            metrics.font_size * PADDING
+        } else {
+            0.0
+        }
+    };
+    let left = padding(hint.padding_left);
+    (
+        left + label.size[0] as f32 + padding(hint.padding_right),
+        left,
+    )
+}
+
+/// Returns the byte range of the character of `line` before `index`, which is widened to
+/// make room for the hints at `index`, or `None` at the start of the line or if `index` is
+/// not a character boundary.
+pub(super) fn spaced_char(line: &str, index: usize) -> Option<std::ops::Range<usize>> {
+    if !line.is_char_boundary(index) {
+        return None;
+    }
+    let (start, _) = line[..index].char_indices().next_back()?;
+    Some(start..index)
+}
+
+/// Returns where the labels of `hints` are drawn, with the index of their hint. Each hint
+/// is given with its position in the buffer and its room and left padding from
+/// [`hint_width`]; hints at the same position follow each other in order.
+pub(super) fn compute_inlay_hint_rects(
+    editor: &glyphon::Editor,
+    hints: &[(Cursor, (f32, f32))],
+) -> Vec<(RectDef, usize)> {
+    let mut rects = Vec::new();
+    if hints.is_empty() {
+        return rects;
+    }
+    editor.with_buffer(|buffer| {
+        let line_height = buffer.metrics().line_height;
+        for run in buffer.layout_runs() {
+            let (top, height) = lens_row::text_row(&run, line_height);
+            let mut i = 0;
+            while i < hints.len() {
+                let position = hints[i].0;
+                let group = hints[i..]
+                    .iter()
+                    .take_while(|(other, _)| *other == position)
+                    .count();
+                let widened = (position.line == run.line_i)
+                    .then(|| run.glyphs.iter().find(|glyph| glyph.end == position.index))
+                    .flatten();
+                if let Some(glyph) = widened {
+                    // The room of the whole group is at the end of the widened glyph
+                    let room: f32 = hints[i..i + group].iter().map(|(_, (w, _))| w).sum();
+                    let mut x = glyph.x + glyph.w - room;
+                    for (index, (_, (width, left))) in hints.iter().enumerate().skip(i).take(group)
+                    {
+                        rects.push((
+                            RectDef {
+                                x: Px((x + left) as i32),
+                                y: Px(top as i32),
+                                width: Px((width - left).ceil() as i32),
+                                height: Px(height as i32),
+                            },
+                            index,
+                        ));
+                        x += width;
+                    }
+                }
+                i += group;
+            }
+        }
+    });
+    rects
+}
+
+/// The label of an inlay hint, drawn smaller than the text around it and centered on its
+/// line.
+#[tessera]
+pub(super) fn inlay_hint_label(label: String, color: Color, metrics: glyphon::Metrics) {
+    measure(Box::new(move |input| {
+        let text_data = TextData::new(
+            label.clone(),
+            color,
+            metrics.font_size * INLAY_HINT_SCALE,
+            metrics.line_height,
+            TextConstraint::NONE,
+        );
+        let size = ComputedData {
+            width: Px(text_data.size[0] as i32),
+            height: Px(text_data.size[1] as i32),
+        };
+        input
+            .metadata_mut()
+            .push_draw_command(TextCommand { data: text_data });
+        Ok(size)
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spaced_char() {
+        assert_eq!(spaced_char("let x = 1;", 5), Some(4..5));
+        assert_eq!(spaced_char("let x = 1;", 0), None);
+        // 'é' is two bytes
+        assert_eq!(spaced_char("é(", 2), Some(0..2));
+        assert_eq!(spaced_char("é(", 1), None);
+    }
+
+    #[test]
+    fn test_inlay_hint_rects() {
+        let font_system = &mut crate::pipelines::write_font_system();
+        let mut buffer = glyphon::Buffer::new(font_system, glyphon::Metrics::new(14.0, 18.0));
+        buffer.set_text(
+            font_system,
+            "let x = 1;\nfoo(2);",
+            &glyphon::Attrs::new(),
+            glyphon::Shaping::Advanced,
+            None,
+        );
+        let editor = glyphon::Editor::new(buffer);
+        // The end of the glyph before each hint
+        let glyph_end = |line: usize, index: usize| {
+            editor.with_buffer(|buffer| {
+                let run = buffer.layout_runs().find(|run| run.line_i == line).unwrap();
+                let glyph = run.glyphs.iter().find(|glyph| glyph.end == index).unwrap();
+                (glyph.x + glyph.w, run.line_top)
+            })
+        };
+
+        let rects = compute_inlay_hint_rects(
+            &editor,
+            &[
+                // Not shown without a character before it
+                (Cursor::new(0, 0), (8.0, 0.0)),
+                (Cursor::new(0, 5), (10.0, 0.0)),
+                (Cursor::new(0, 5), (20.0, 4.0)),
+                (Cursor::new(1, 4), (12.0, 2.0)),
+            ],
+        );
+        let (x_end, _) = glyph_end(0, 5);
+        let (y_end, line_top) = glyph_end(1, 4);
+        let rect = |x: f32, y: f32, width: i32| RectDef {
+            x: Px(x as i32),
+            y: Px(y as i32),
+            width: Px(width),
+            height: Px(18),
+        };
+        assert_eq!(
+            rects,
+            vec![
+                // A group of hints shares the room at the end of the glyph, in order
+                (rect(x_end - 30.0, 0.0, 10), 1),
+                (rect(x_end - 30.0 + 10.0 + 4.0, 0.0, 16), 2),
+                (rect(y_end - 12.0 + 2.0, line_top, 10), 3),
+            ]
+        );
+    }
+}
@@ -0,0 +1,924 @@
+//! The pointer, keyboard and IME handling of an editor component.
+//!
+//! [`editor_input_handler`] is the input handler of
+//! [`text_editor`](crate::text_editor::text_editor). Other editor components built on
+//! [`TextEditorState`] can use it as well and adjust it with the hooks of
+//! [`EditorInputConfig`] instead of repeating it.
+
+use std::sync::Arc;
+
+use derive_builder::Builder;
+use glyphon::{Action, Edit, cosmic_text::Motion};
+use parking_lot::RwLock;
+use tessera_ui::{
+    CursorEventContent, ImeRequest, InputHandlerFn, InputHandlerInput, PressKeyEventType, Px,
+    clock, winit,
+};
+
+use super::{
+    Breakpoint, BreakpointCommand, ClickType, CompletionItem, CompletionRequest, ContextMenuChoice,
+    ContextMenuItem, Diagnostic, EditDelta, EditKind, EditWindow, TextChange, TextEditorState,
+    TextPosition, WrapMode, ZOOM_STEP, apply_to_carets, editor_content, is_editing_action,
+    is_word_char,
+};
+use crate::{
+    pipelines::write_font_system, pos_misc::is_position_in_component, scrollable::ScrollBarBehavior,
+};
+
+/// Lines a click must move the caret to count as a jump, see
+/// [`TextEditorState::jump_back`].
+const FAR_CLICK_LINES: usize = 10;
+
+/// Key press hook of an [`EditorInputConfig`], returning `true` to consume the key.
+pub type EditorKeyHook =
+    Arc<dyn Fn(&winit::event::KeyEvent, &mut TextEditorState) -> bool + Send + Sync>;
+
+/// Hook of an [`EditorInputConfig`] that sees the input before the editor does.
+pub type EditorInputHook =
+    Arc<dyn Fn(&mut InputHandlerInput<'_>, &Arc<RwLock<TextEditorState>>) + Send + Sync>;
+
+/// The behavior of an [`editor_input_handler`].
+///
+/// The fields mirror the ones of the same name in
+/// [`TextEditorArgs`](crate::text_editor::TextEditorArgs), which converts into this with
+/// `From`. The hooks let another editor component change the handling without forking it.
+#[derive(Builder, Clone)]
+#[builder(pattern = "owned")]
+pub struct EditorInputConfig {
+    /// Called with every change of the content, see
+    /// [`TextEditorArgs::on_change`](crate::text_editor::TextEditorArgs::on_change).
+    #[builder(default = "Arc::new(|_| None)")]
+    pub on_change: Arc<dyn Fn(TextChange) -> Option<String> + Send + Sync>,
+    /// Enter submits instead of breaking the line.
+    #[builder(default = "false")]
+    pub single_line: bool,
+    /// Tab passes the focus on in single-line mode.
+    #[builder(default = "false")]
+    pub tab_moves_focus: bool,
+    /// Right-clicking opens the context menu.
+    #[builder(default = "true")]
+    pub context_menu: bool,
+    /// Entries of the context menu after the editing commands.
+    #[builder(default)]
+    pub context_menu_items: Vec<ContextMenuItem>,
+    /// When the scrollbars are shown, which decides whether scrolling reveals them.
+    #[builder(default = "ScrollBarBehavior::Hidden")]
+    pub scrollbar_behavior: ScrollBarBehavior,
+    /// Called with the content when Enter is pressed in single-line mode.
+    #[builder(default, setter(strip_option))]
+    pub on_submit: Option<Arc<dyn Fn(String) + Send + Sync>>,
+    /// Called with the new zoom factor when user input zooms the editor.
+    #[builder(default, setter(strip_option))]
+    pub on_zoom_changed: Option<Arc<dyn Fn(f32) + Send + Sync>>,
+    /// Called when the Insert key switches overwrite mode.
+    #[builder(default, setter(strip_option))]
+    pub on_overwrite_changed: Option<Arc<dyn Fn(bool) + Send + Sync>>,
+    /// Called when the primary caret moves.
+    #[builder(default, setter(strip_option))]
+    pub on_caret_move: Option<Arc<dyn Fn(TextPosition) + Send + Sync>>,
+    /// Called when the pointer moves onto or off a diagnostic.
+    #[builder(default, setter(strip_option))]
+    pub on_diagnostic_hover: Option<Arc<dyn Fn(Option<Diagnostic>) + Send + Sync>>,
+    /// Fills the completion popup.
+    #[builder(default, setter(strip_option))]
+    pub on_complete: Option<Arc<dyn Fn(CompletionRequest) -> Vec<CompletionItem> + Send + Sync>>,
+    /// Called with the link under a Ctrl+click.
+    #[builder(default, setter(strip_option))]
+    pub on_link_activate: Option<Arc<dyn Fn(String) + Send + Sync>>,
+    /// Called with the line next to a click in the gutter.
+    #[builder(default, setter(strip_option))]
+    pub on_gutter_click: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    /// Clicking the gutter toggles breakpoints and right-clicking it opens the breakpoint
+    /// menu.
+    #[builder(default = "false")]
+    pub breakpoints: bool,
+    /// Called with all breakpoints after the gutter changed them.
+    #[builder(default, setter(strip_option))]
+    pub on_breakpoints_changed: Option<Arc<dyn Fn(Vec<Breakpoint>) + Send + Sync>>,
+    /// Called with the line whose breakpoint condition the gutter menu asks to edit.
+    #[builder(default, setter(strip_option))]
+    pub on_edit_breakpoint_condition: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    /// Called with every key press while the editor has the focus, before the editor maps it
+    /// to an edit. Returning `true` consumes the key, e.g. for keys a terminal sends to its
+    /// process instead.
+    #[builder(default, setter(strip_option))]
+    pub on_key: Option<EditorKeyHook>,
+    /// Called with the input of every frame before the editor handles it, e.g. to take
+    /// pointer events for an inline widget out of it.
+    #[builder(default, setter(strip_option))]
+    pub on_input: Option<EditorInputHook>,
+}
+
+impl Default for EditorInputConfig {
+    fn default() -> Self {
+        EditorInputConfigBuilder::default().build().unwrap()
+    }
+}
+
+/// Returns the input handler of an editor component showing `state`, which handles clicks,
+/// drags, scrolling, key presses and IME composition as `config` says.
+///
+/// Positions are taken relative to the component, with the text area at
+/// [`TextEditorState::hit_test`] offsets, so the component must place the text the same way
+/// [`text_editor`](crate::text_editor::text_editor) does.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use tessera_ui::winit::keyboard::{Key, NamedKey};
+/// use tessera_ui_basic_components::text_editor::{
+///     EditorInputConfigBuilder, TextEditorState, editor_input_handler,
+/// };
+/// use tessera_ui::Dp;
+/// use parking_lot::RwLock;
+///
+/// // A field that leaves Escape to the components around it
+/// let config = EditorInputConfigBuilder::default()
+///     .single_line(true)
+///     .on_key(Arc::new(|event, _state| event.logical_key == Key::Named(NamedKey::Escape)))
+///     .build()
+///     .unwrap();
+/// let state = Arc::new(RwLock::new(TextEditorState::new(Dp(14.0), None)));
+/// let handler = editor_input_handler(state, config);
+/// ```
+pub fn editor_input_handler(
+    state: Arc<RwLock<TextEditorState>>,
+    config: EditorInputConfig,
+) -> Box<InputHandlerFn> {
+    let state_for_handler = state;
+    let on_change = config.on_change.clone();
+    Box::new(move |mut input| {
+        state_for_handler.read().focus_handler().join_traversal();
+        if let Some(on_input) = &config.on_input {
+            on_input(&mut input, &state_for_handler);
+        }
+        let size = input.computed_data; // This is the full surface size
+        let cursor_pos_option = input.cursor_position_rel;
+        let is_cursor_in_editor = cursor_pos_option
+            .map(|pos| is_position_in_component(size, pos))
+            .unwrap_or(false);
+
+        // Set text input cursor when hovering
+        if is_cursor_in_editor {
+            input.requests.cursor_icon = winit::window::CursorIcon::Text;
+        }
+
+        // Pointer position relative to the text area
+        let text_pos = cursor_pos_option
+            .filter(|_| is_cursor_in_editor)
+            .map(|pos| state_for_handler.read().text_area_position(pos));
+        state_for_handler.write().update_hovered_fold(text_pos);
+        let link_pos = text_pos
+            .filter(|_| config.on_link_activate.is_some())
+            .filter(|_| is_ctrl_pressed(input.key_modifiers));
+        state_for_handler.write().update_hovered_link(link_pos);
+        {
+            let state = state_for_handler.read();
+            if state.hovered_fold().is_some() || state.hovered_link().is_some() {
+                input.requests.cursor_icon = winit::window::CursorIcon::Pointer;
+            }
+        }
+
+        if let Some(on_diagnostic_hover) = &config.on_diagnostic_hover {
+            let mut state = state_for_handler.write();
+            if state.update_hovered_diagnostic(text_pos) {
+                on_diagnostic_hover(state.hovered_diagnostic().cloned());
+            }
+        }
+
+        // Clicks on the context menu were taken by its entries, any other click closes it
+        let chosen = state_for_handler.write().take_context_menu_choice();
+        match chosen {
+            Some(ContextMenuChoice::Command(command)) => {
+                let mut state = state_for_handler.write();
+                let read_only = state.is_read_only();
+                let actions = state
+                    .clipboard_actions(command, input.clipboard)
+                    .unwrap_or_default();
+                for action in actions {
+                    if read_only && is_editing_action(&action) {
+                        continue;
+                    }
+                    handle_action(&mut state, action, on_change.clone());
+                }
+            }
+            Some(ContextMenuChoice::Suggestion(index)) => {
+                state_for_handler.write().accept_spelling_suggestion(index);
+            }
+            Some(ContextMenuChoice::Breakpoint(command)) => {
+                let line = state_for_handler.read().breakpoint_menu_line();
+                if let Some(line) = line {
+                    run_breakpoint_command(&state_for_handler, &config, command, line);
+                }
+            }
+            Some(ContextMenuChoice::Custom(index)) => {
+                if let Some(item) = config.context_menu_items.get(index) {
+                    (item.on_select)();
+                }
+            }
+            None => {}
+        }
+        if input
+            .cursor_events
+            .iter()
+            .any(|event| matches!(event.content, CursorEventContent::Pressed(_)))
+        {
+            state_for_handler.write().close_context_menu();
+        }
+
+        // A click in the editor or losing the focus ends the composition without committing it
+        {
+            let mut state = state_for_handler.write();
+            let clicked = is_cursor_in_editor
+                && input
+                    .cursor_events
+                    .iter()
+                    .any(|event| matches!(event.content, CursorEventContent::Pressed(_)));
+            if state.preedit().is_some() && (clicked || !state.focus_handler().is_focused()) {
+                state.clear_preedit();
+            }
+        }
+
+        // Handle click events - now we have a full clickable area from surface
+        if is_cursor_in_editor {
+            // Handle mouse pressed events, right-clicks open the context menu instead
+            let click_events: Vec<_> = input
+                .cursor_events
+                .iter()
+                .filter(|event| {
+                    matches!(
+                        event.content,
+                        CursorEventContent::Pressed(
+                            PressKeyEventType::Left | PressKeyEventType::Middle
+                        )
+                    )
+                })
+                .collect();
+            let right_clicked = input.cursor_events.iter().any(|event| {
+                matches!(
+                    event.content,
+                    CursorEventContent::Pressed(PressKeyEventType::Right)
+                )
+            });
+
+            // Handle mouse released events (end of drag)
+            let release_events: Vec<_> = input
+                .cursor_events
+                .iter()
+                .filter(|event| matches!(event.content, CursorEventContent::Released(_)))
+                .collect();
+
+            let right_clicked_gutter = cursor_pos_option
+                .filter(|_| right_clicked && config.breakpoints)
+                .and_then(|pos| state_for_handler.read().gutter_line_at(pos));
+            if let Some(line) = right_clicked_gutter
+                && let Some(pos) = text_pos
+            {
+                let conditions = config.on_edit_breakpoint_condition.is_some();
+                state_for_handler
+                    .write()
+                    .open_breakpoint_menu(pos, line, conditions);
+            } else if right_clicked
+                && config.context_menu
+                && let Some(pos) = text_pos.filter(|pos| pos.x >= Px(0) && pos.y >= Px(0))
+            {
+                let mut state = state_for_handler.write();
+                if !state.focus_handler().is_focused() {
+                    state.focus_handler_mut().request_focus();
+                }
+                // Without a selection the commands apply at the pointer
+                if state.editor().selection_bounds().is_none() {
+                    let x = (pos.x + state.horizontal_scroll()).0;
+                    state.clear_secondary_carets();
+                    state
+                        .editor_mut()
+                        .action(&mut write_font_system(), Action::Click { x, y: pos.y.0 });
+                }
+                state.open_context_menu(pos);
+            }
+
+            if !click_events.is_empty() {
+                state_for_handler.write().close_completion();
+                // Request focus if not already focused
+                if !state_for_handler.read().focus_handler().is_focused() {
+                    state_for_handler
+                        .write()
+                        .focus_handler_mut()
+                        .request_focus();
+                }
+
+                // A click in the gutter goes to its callback, a click on a fold marker opens
+                // the fold and a Ctrl+click on a link activates it, other clicks position the
+                // cursor
+                let clicked_fold =
+                    text_pos.and_then(|pos| state_for_handler.read().fold_marker_at(pos));
+                let clicked_link = state_for_handler.read().hovered_link();
+                let clicked_gutter =
+                    cursor_pos_option.and_then(|pos| state_for_handler.read().gutter_line_at(pos));
+                if let Some(line) = clicked_gutter {
+                    if config.breakpoints {
+                        state_for_handler.write().toggle_breakpoint(line);
+                        notify_breakpoints_changed(&state_for_handler, &config);
+                    }
+                    if let Some(on_gutter_click) = &config.on_gutter_click {
+                        on_gutter_click(line);
+                    }
+                } else if let Some(header) = clicked_fold {
+                    state_for_handler.write().unfold(header);
+                } else if let Some(target) = clicked_link
+                    && let Some(on_link_activate) = &config.on_link_activate
+                {
+                    on_link_activate(target);
+                } else if let Some(cursor_pos) = cursor_pos_option {
+                    // Calculate the relative position within the text area
+                    let text_relative_pos = state_for_handler.read().text_area_position(cursor_pos);
+
+                    // Only process if the click is within the text area (non-negative relative coords)
+                    if text_relative_pos.x >= Px(0) && text_relative_pos.y >= Px(0) {
+                        // Determine click type and handle accordingly
+                        let click_type = state_for_handler
+                            .write()
+                            .handle_click(text_relative_pos, click_events[0].timestamp);
+                        let buffer_x =
+                            (text_relative_pos.x + state_for_handler.read().horizontal_scroll()).0;
+
+                        // Ctrl+click adds a caret, any other click goes back to a single one
+                        if is_ctrl_pressed(input.key_modifiers) {
+                            state_for_handler.write().keep_primary_as_caret();
+                        } else {
+                            state_for_handler.write().clear_secondary_carets();
+                        }
+
+                        match click_type {
+                            // Alt+click starts a block selection
+                            ClickType::Single if input.key_modifiers.alt_key() => {
+                                state_for_handler
+                                    .write()
+                                    .start_block_selection(buffer_x, text_relative_pos.y.0);
+                            }
+                            // Shift+click extends the selection to the click
+                            ClickType::Single if input.key_modifiers.shift_key() => {
+                                state_for_handler.write().editor_mut().action(
+                                    &mut write_font_system(),
+                                    Action::Drag {
+                                        x: buffer_x,
+                                        y: text_relative_pos.y.0,
+                                    },
+                                );
+                            }
+                            ClickType::Single => {
+                                // Single click: position cursor
+                                let mut state = state_for_handler.write();
+                                let from = state.editor().cursor();
+                                state.editor_mut().action(
+                                    &mut write_font_system(),
+                                    Action::Click {
+                                        x: buffer_x,
+                                        y: text_relative_pos.y.0,
+                                    },
+                                );
+                                state.record_jump_from(from, FAR_CLICK_LINES);
+                            }
+                            ClickType::Double => {
+                                // Double click: select word
+                                state_for_handler.write().editor_mut().action(
+                                    &mut write_font_system(),
+                                    Action::DoubleClick {
+                                        x: buffer_x,
+                                        y: text_relative_pos.y.0,
+                                    },
+                                );
+                            }
+                            ClickType::Triple => {
+                                // Triple click: select line
+                                state_for_handler.write().editor_mut().action(
+                                    &mut write_font_system(),
+                                    Action::TripleClick {
+                                        x: buffer_x,
+                                        y: text_relative_pos.y.0,
+                                    },
+                                );
+                            }
+                        }
+
+                        // Start potential drag operation
+                        state_for_handler.write().start_drag();
+                    }
+                }
+            }
+
+            // Handle drag events (mouse move while dragging)
+            // This happens every frame when cursor position changes during drag
+            if state_for_handler.read().is_dragging()
+                && let Some(cursor_pos) = cursor_pos_option
+            {
+                let current_pos_px = state_for_handler.read().text_area_position(cursor_pos);
+                if current_pos_px.x >= Px(0) && current_pos_px.y >= Px(0) {
+                    let last_pos_px = state_for_handler.read().last_click_position();
+
+                    if last_pos_px != Some(current_pos_px) {
+                        // Extend selection by dragging
+                        let buffer_x =
+                            (current_pos_px.x + state_for_handler.read().horizontal_scroll()).0;
+                        let mut state = state_for_handler.write();
+                        if state.is_block_selecting() {
+                            state.extend_block_selection(buffer_x, current_pos_px.y.0);
+                        } else {
+                            state.editor_mut().action(
+                                &mut write_font_system(),
+                                Action::Drag {
+                                    x: buffer_x,
+                                    y: current_pos_px.y.0,
+                                },
+                            );
+                        }
+                        drop(state);
+
+                        // Update last position to current position
+                        state_for_handler
+                            .write()
+                            .update_last_click_position(current_pos_px);
+                    }
+                }
+            }
+
+            // Handle mouse release events (end drag)
+            if !release_events.is_empty() {
+                state_for_handler.write().stop_drag();
+            }
+
+            let scroll_events: Vec<_> = input
+                .cursor_events
+                .iter()
+                .filter_map(|event| match &event.content {
+                    CursorEventContent::Scroll(scroll_event) => Some(scroll_event),
+                    _ => None,
+                })
+                .collect();
+
+            // Handle scroll events (only when focused and cursor is in editor)
+            if state_for_handler.read().focus_handler().is_focused() {
+                for scroll_event in &scroll_events {
+                    if is_ctrl_pressed(input.key_modifiers) {
+                        if scroll_event.delta_y != 0.0 {
+                            let anchor_y = cursor_pos_option
+                                .map_or(Px(0), |pos| {
+                                    state_for_handler.read().text_area_position(pos).y
+                                })
+                                .max(Px(0));
+                            let mut state = state_for_handler.write();
+                            let zoom = if scroll_event.delta_y > 0.0 {
+                                state.zoom() * ZOOM_STEP
+                            } else {
+                                state.zoom() / ZOOM_STEP
+                            };
+                            state.zoom_at(zoom, anchor_y.to_f32());
+                        }
+                        continue;
+                    }
+
+                    // Without wrapping, Shift turns the vertical wheel into horizontal scrolling
+                    let shift_scrolls = input.key_modifiers.shift_key()
+                        && state_for_handler.read().wrap_mode() == WrapMode::None;
+                    let horizontal = if shift_scrolls {
+                        -scroll_event.delta_y
+                    } else {
+                        -scroll_event.delta_x
+                    };
+                    if horizontal != 0.0 {
+                        state_for_handler.write().scroll_horizontally(horizontal);
+                    }
+                    if shift_scrolls {
+                        continue;
+                    }
+
+                    // Convert scroll delta to lines
+                    let scroll = -scroll_event.delta_y;
+
+                    // Scroll up for positive, down for negative
+                    let action = glyphon::Action::Scroll { pixels: scroll };
+                    state_for_handler
+                        .write()
+                        .editor_mut()
+                        .action(&mut write_font_system(), action);
+                }
+                if matches!(config.scrollbar_behavior, ScrollBarBehavior::AutoHide)
+                    && !scroll_events.is_empty()
+                {
+                    let state = state_for_handler.read();
+                    for bar_state in [&state.scrollbar_state_v, &state.scrollbar_state_h] {
+                        let mut bar_state = bar_state.write();
+                        bar_state.last_scroll_activity = Some(clock::now());
+                        bar_state.should_be_visible = true;
+                    }
+                }
+            }
+
+            // Only block cursor events when focused to prevent propagation
+            if state_for_handler.read().focus_handler().is_focused() {
+                input.cursor_events.clear();
+            }
+        }
+
+        // Handle keyboard events (only when focused)
+        if state_for_handler.read().focus_handler().is_focused() {
+            // Handle keyboard events
+            // Any key closes the context menu, Escape does nothing else
+            if state_for_handler.read().is_context_menu_open()
+                && input
+                    .keyboard_events
+                    .iter()
+                    .any(|key_event| key_event.state == winit::event::ElementState::Pressed)
+            {
+                state_for_handler.write().close_context_menu();
+                input.keyboard_events.retain(|key_event| {
+                    key_event.logical_key
+                        != winit::keyboard::Key::Named(winit::keyboard::NamedKey::Escape)
+                });
+            }
+
+            let submit_requested;
+            let mut all_actions = Vec::new();
+            {
+                let mut state = state_for_handler.write();
+                for key_event in input.keyboard_events.iter().cloned() {
+                    if key_event.state == winit::event::ElementState::Pressed
+                        && let Some(on_key) = &config.on_key
+                        && on_key(&key_event, &mut state)
+                    {
+                        continue;
+                    }
+                    if state.handle_go_to_line_key(&key_event)
+                        || state.handle_completion_key(&key_event)
+                    {
+                        continue;
+                    }
+                    if let Some(actions) = state.map_key_event_to_action(
+                        key_event,
+                        input.key_modifiers,
+                        input.clipboard,
+                    ) {
+                        all_actions.extend(actions);
+                    }
+                }
+                submit_requested = state.take_submit_requested();
+            }
+
+            if !all_actions.is_empty() || submit_requested {
+                let mut state = state_for_handler.write();
+                let read_only = state.is_read_only();
+                // Typing a word character asks for completions, deleting refines an open popup
+                let completes = !read_only
+                    && all_actions.iter().any(|action| match action {
+                        Action::Insert(c) => is_word_char(*c),
+                        Action::Backspace | Action::Delete => !state.completion_items().is_empty(),
+                        _ => false,
+                    });
+                let moved = !all_actions.is_empty();
+                for action in all_actions {
+                    if read_only && is_editing_action(&action) {
+                        continue;
+                    }
+                    handle_action(&mut state, action, on_change.clone());
+                }
+                match &config.on_complete {
+                    Some(on_complete) if completes => state.update_completion(on_complete.as_ref()),
+                    _ if moved => state.close_completion(),
+                    _ => {}
+                }
+                if submit_requested && let Some(on_submit) = &config.on_submit {
+                    on_submit(state.raw_text());
+                }
+            }
+
+            // Block all keyboard events to prevent propagation, except Tab when a single-line
+            // field hands the focus on
+            let passed_on: Vec<_> = input
+                .keyboard_events
+                .iter()
+                .filter(|key_event| {
+                    config.single_line
+                        && config.tab_moves_focus
+                        && key_event.logical_key
+                            == winit::keyboard::Key::Named(winit::keyboard::NamedKey::Tab)
+                })
+                .cloned()
+                .collect();
+            input.keyboard_events.clear();
+            if passed_on
+                .iter()
+                .any(|key_event| key_event.state == winit::event::ElementState::Pressed)
+            {
+                state_for_handler.write().focus_handler_mut().unfocus();
+            }
+            input.keyboard_events.extend(passed_on);
+
+            // Handle IME events, a read-only editor only swallows them
+            let ime_events: Vec<_> = input.ime_events.drain(..).collect();
+            let read_only = state_for_handler.read().is_read_only();
+            for event in ime_events.into_iter().filter(|_| !read_only) {
+                handle_ime(&mut state_for_handler.write(), event, on_change.clone());
+            }
+
+            // Request IME window next to the caret, which moves along during composition, or
+            // along the editor while the caret is scrolled out of view
+            if !read_only {
+                let text_origin = state_for_handler.read().text_origin();
+                let caret_area = state_for_handler.read().ime_cursor_area();
+                let request = match caret_area {
+                    Some((caret, caret_size))
+                        if caret.x >= Px(0)
+                            && caret.y >= Px(0)
+                            && caret.x + text_origin.x <= size.width
+                            && caret.y + caret_size.height + text_origin.y <= size.height =>
+                    {
+                        ImeRequest::at(caret.offset(text_origin.x, text_origin.y), caret_size)
+                    }
+                    _ => ImeRequest::new(size.into()),
+                };
+                input.requests.ime_request = Some(request);
+            }
+        }
+
+        // Undo/redo, find/replace and paste change the content directly, so the owner still has
+        // to be told, with one change for the whole edit.
+        let reports_caret = config.on_caret_move.is_some();
+        if !state_for_handler.read().has_pending_changes(reports_caret) {
+            return;
+        }
+        let mut state = state_for_handler.write();
+        if let Some(before) = state.take_content_changed() {
+            notify_content_change(&mut state, &before, on_change.clone());
+        }
+        if state.take_zoom_changed()
+            && let Some(on_zoom_changed) = &config.on_zoom_changed
+        {
+            on_zoom_changed(state.zoom());
+        }
+        if state.take_overwrite_changed()
+            && let Some(on_overwrite_changed) = &config.on_overwrite_changed
+        {
+            on_overwrite_changed(state.overwrite());
+        }
+        if let Some(on_caret_move) = &config.on_caret_move
+            && let Some(caret) = state.take_caret_moved()
+        {
+            on_caret_move(caret);
+        }
+    })
+}
+
+/// Runs `command` of the gutter menu on the breakpoint of `line`.
+fn run_breakpoint_command(
+    state: &Arc<RwLock<TextEditorState>>,
+    config: &EditorInputConfig,
+    command: BreakpointCommand,
+    line: usize,
+) {
+    match command {
+        BreakpointCommand::Add | BreakpointCommand::AddConditional => {
+            if state.read().breakpoint_at(line).is_none() {
+                state.write().toggle_breakpoint(line);
+            }
+        }
+        BreakpointCommand::Remove => {
+            if state.read().breakpoint_at(line).is_some() {
+                state.write().toggle_breakpoint(line);
+            }
+        }
+        BreakpointCommand::ToggleEnabled => {
+            let mut state = state.write();
+            let enabled = state.breakpoint_at(line).is_some_and(|b| b.enabled);
+            state.set_breakpoint_enabled(line, !enabled);
+        }
+        BreakpointCommand::EditCondition => {}
+    }
+    if command != BreakpointCommand::EditCondition {
+        notify_breakpoints_changed(state, config);
+    }
+    // The application asks for the condition, e.g. in a dialog, and sets it afterwards
+    if matches!(
+        command,
+        BreakpointCommand::AddConditional | BreakpointCommand::EditCondition
+    ) && let Some(on_edit_breakpoint_condition) = &config.on_edit_breakpoint_condition
+    {
+        on_edit_breakpoint_condition(line);
+    }
+}
+
+/// Passes the breakpoints to the `on_breakpoints_changed` callback, if any.
+fn notify_breakpoints_changed(state: &Arc<RwLock<TextEditorState>>, config: &EditorInputConfig) {
+    if let Some(on_breakpoints_changed) = &config.on_breakpoints_changed {
+        let breakpoints = state.read().breakpoints().to_vec();
+        on_breakpoints_changed(breakpoints);
+    }
+}
+
+/// Shows the composition of `event` at the caret, or applies its commit at every caret.
+pub(super) fn handle_ime(
+    state: &mut TextEditorState,
+    event: winit::event::Ime,
+    on_change: Arc<dyn Fn(TextChange) -> Option<String> + Send + Sync>,
+) {
+    match event {
+        winit::event::Ime::Commit(text) => {
+            state.clear_preedit();
+            // Input methods that commit every key type single characters, longer commits are
+            // inserted like pasted text
+            let mut chars = text.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                // Composed text is always inserted
+                let overwrite = state.overwrite();
+                state.set_overwrite(false);
+                handle_action(state, Action::Insert(c), on_change);
+                state.set_overwrite(overwrite);
+            } else {
+                state.insert_text(&text);
+            }
+        }
+        winit::event::Ime::Preedit(text, cursor) => {
+            // Starting a composition replaces the selection, like typing does
+            if state.preedit().is_none()
+                && !text.is_empty()
+                && state.editor().selection_bounds().is_some()
+            {
+                handle_action(state, Action::Delete, on_change.clone());
+            }
+            state.set_preedit(&text, cursor);
+        }
+        winit::event::Ime::Disabled => state.clear_preedit(),
+        winit::event::Ime::Enabled => {}
+    }
+}
+
+pub(super) fn handle_action(
+    state: &mut TextEditorState,
+    action: Action,
+    on_change: Arc<dyn Fn(TextChange) -> Option<String> + Send + Sync>,
+) {
+    state.clear_preedit();
+    let carets = state.carets();
+    let cursor_before = carets.cursor;
+    // A mask may reformat the whole content, other edits stay on the lines of the carets
+    let lines = match state.input_mask() {
+        Some(_) => 0..=usize::MAX,
+        None => state.caret_lines(),
+    };
+    let first_line = *lines.start();
+    let window = state
+        .editor()
+        .with_buffer(|buffer| EditWindow::new(&buffer.lines, lines));
+    let (action, closer) = state.auto_close_action(action);
+    state.select_overwritten(&action);
+
+    let mut secondary = std::mem::take(&mut state.secondary_carets);
+    apply_to_carets(
+        state.editor_mut(),
+        &mut write_font_system(),
+        &mut secondary,
+        action,
+    );
+    // The closer goes after every caret, which stays between the pair
+    if let Some(closer) = closer {
+        for action in [Action::Insert(closer), Action::Motion(Motion::Left)] {
+            apply_to_carets(
+                state.editor_mut(),
+                &mut write_font_system(),
+                &mut secondary,
+                action,
+            );
+        }
+    }
+    state.secondary_carets = secondary;
+    if let Some(closer) = closer {
+        state.remember_auto_closers(closer);
+    }
+    // Lines broken off the last line copy its missing line ending
+    let line_count = state.editor().with_buffer(|buffer| buffer.lines.len());
+    state.break_inserted_lines(first_line, line_count - 1);
+
+    let mut edit = None;
+    if is_editing_action(&action) {
+        state.apply_input_mask();
+        edit = state
+            .editor()
+            .with_buffer(|buffer| window.edit(&buffer.lines));
+        // The buffer is left as edited unless on_change rewrites the content
+        if let Some(delta) = &edit
+            && let Some(replacement) = on_change(state.text_change(delta))
+        {
+            let mut before = editor_content(state.editor());
+            delta.revert(&mut before);
+            state.set_text_reactive(&replacement);
+            edit = EditDelta::between(&before, &replacement);
+        }
+    }
+
+    state.record_edit(
+        edit,
+        carets,
+        window.line_count(),
+        EditKind::from_action(&action),
+    );
+    state.skip_folded_lines(cursor_before, &action);
+}
+
+/// Ctrl on most platforms, Cmd on macOS.
+fn is_ctrl_pressed(modifiers: winit::keyboard::ModifiersState) -> bool {
+    modifiers.control_key() || modifiers.super_key()
+}
+
+/// Passes content that was replaced outside of `handle_action`, starting from `before`,
+/// through `on_change`.
+fn notify_content_change(
+    state: &mut TextEditorState,
+    before: &str,
+    on_change: Arc<dyn Fn(TextChange) -> Option<String> + Send + Sync>,
+) {
+    if let Some(edit) = EditDelta::between(before, &editor_content(state.editor()))
+        && let Some(replacement) = on_change(state.text_change(&edit))
+    {
+        state.set_text_reactive(&replacement);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+    use tessera_ui::Dp;
+
+    use super::*;
+
+    #[test]
+    fn test_inserted_text_is_reported_as_one_change() {
+        let mut state = TextEditorState::new(Dp(14.0), None);
+        state.set_text_reactive("ab");
+        state.editor_mut().set_cursor(glyphon::Cursor::new(0, 1));
+        state.insert_text("x\ny");
+        let before = state.take_content_changed().unwrap();
+
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = changes.clone();
+        notify_content_change(
+            &mut state,
+            &before,
+            Arc::new(move |change| {
+                recorded.lock().push(change);
+                None
+            }),
+        );
+        assert_eq!(
+            *changes.lock(),
+            [TextChange {
+                range: 1..1,
+                inserted: "x\ny".to_string(),
+                cursor: 4,
+                text: None,
+            }]
+        );
+        assert!(state.take_content_changed().is_none());
+    }
+
+    #[test]
+    fn test_preedit_replaces_the_selection() {
+        let mut state = TextEditorState::new(Dp(14.0), None);
+        state.set_text_reactive("abc");
+        state.select_range(glyphon::Cursor::new(0, 0), glyphon::Cursor::new(0, 2));
+        let on_change: Arc<dyn Fn(TextChange) -> Option<String> + Send + Sync> = Arc::new(|_| None);
+        handle_ime(
+            &mut state,
+            winit::event::Ime::Preedit("か".to_string(), None),
+            on_change.clone(),
+        );
+        assert_eq!(state.raw_text(), "c");
+        assert_eq!(state.preedit(), Some("か"));
+
+        handle_ime(
+            &mut state,
+            winit::event::Ime::Commit("仮名".to_string()),
+            on_change,
+        );
+        assert_eq!(state.raw_text(), "仮名c");
+        assert_eq!(state.preedit(), None);
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "c");
+    }
+
+    #[test]
+    fn test_on_change_rewrites_the_edit() {
+        let mut state = TextEditorState::new(Dp(14.0), None);
+        state.set_text_reactive("x");
+        state.set_cursor(0, 1);
+        handle_action(
+            &mut state,
+            Action::Insert('y'),
+            Arc::new(|_| Some("XY".to_string())),
+        );
+        assert_eq!(state.raw_text(), "XY");
+        assert!(state.undo());
+        assert_eq!(state.raw_text(), "x");
+        assert!(state.redo());
+        assert_eq!(state.raw_text(), "XY");
+    }
+}
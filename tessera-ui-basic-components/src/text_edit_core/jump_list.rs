@@ -0,0 +1,88 @@
+//! The positions the caret jumped away from, to go back and forward through them like in a
+//! browser.
+
+use glyphon::Cursor;
+
+/// Number of positions kept; older ones are dropped.
+const MAX_JUMPS: usize = 100;
+
+/// Positions the caret jumped away from, oldest first, and where going back and forward
+/// currently is among them.
+#[derive(Debug, Default)]
+pub(super) struct JumpList {
+    entries: Vec<Cursor>,
+    /// Index of the entry last gone back or forward to, `entries.len()` while not navigating.
+    index: usize,
+}
+
+impl JumpList {
+    /// Records a jump away from `from`. Positions gone back past are dropped, like the
+    /// forward history of a browser, and a jump from the same line as the last one replaces
+    /// it.
+    pub(super) fn push(&mut self, from: Cursor) {
+        self.entries.truncate(self.index);
+        if self
+            .entries
+            .last()
+            .is_some_and(|last| last.line == from.line)
+        {
+            self.entries.pop();
+        }
+        self.entries.push(from);
+        if self.entries.len() > MAX_JUMPS {
+            self.entries.remove(0);
+        }
+        self.index = self.entries.len();
+    }
+
+    /// Returns the position before the current one, remembering `current` to come back to
+    /// it, or `None` at the oldest position.
+    pub(super) fn back(&mut self, current: Cursor) -> Option<Cursor> {
+        if self.index == 0 {
+            return None;
+        }
+        if self.index == self.entries.len() {
+            self.entries.push(current);
+        }
+        self.index -= 1;
+        Some(self.entries[self.index])
+    }
+
+    /// Returns the position after the current one, or `None` at the newest position.
+    pub(super) fn forward(&mut self) -> Option<Cursor> {
+        if self.index + 1 >= self.entries.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.entries[self.index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jump_list_back_and_forward() {
+        let at = |line| Cursor::new(line, 0);
+        let mut jumps = JumpList::default();
+        assert_eq!(jumps.back(at(0)), None);
+        jumps.push(at(1));
+        jumps.push(at(20));
+        assert_eq!(jumps.back(at(40)), Some(at(20)));
+        assert_eq!(jumps.back(at(20)), Some(at(1)));
+        assert_eq!(jumps.back(at(1)), None);
+        assert_eq!(jumps.forward(), Some(at(20)));
+        assert_eq!(jumps.forward(), Some(at(40)));
+        assert_eq!(jumps.forward(), None);
+
+        // A new jump after going back forgets the positions ahead
+        jumps.back(at(40));
+        jumps.back(at(20));
+        jumps.push(at(1));
+        jumps.push(at(1));
+        assert_eq!(jumps.forward(), None);
+        assert_eq!(jumps.back(at(7)), Some(at(1)));
+        assert_eq!(jumps.back(at(1)), None);
+    }
+}
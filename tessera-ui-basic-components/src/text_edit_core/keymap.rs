@@ -0,0 +1,439 @@
+//! Key bindings of the editor commands, with the usual shortcuts of the platform by default.
+
+use std::time::Duration;
+
+use tessera_ui::{
+    clock,
+    key_chord::{ChordMatch, ChordMatcher, KeyChord},
+    winit::{
+        event::KeyEvent,
+        keyboard::{ModifiersState, NamedKey},
+    },
+};
+
+/// An editor command that can be bound to a key chord in an [`EditorKeymap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EditorCommand {
+    /// Copies the selection to the clipboard.
+    Copy,
+    /// Cuts the selection to the clipboard.
+    Cut,
+    /// Pastes the clipboard at the cursor.
+    Paste,
+    /// Selects the whole text.
+    SelectAll,
+    /// Reverts the last edit.
+    Undo,
+    /// Reapplies the last reverted edit.
+    Redo,
+    /// Adds a caret at the next occurrence of the selection or the word at the cursor.
+    AddNextOccurrence,
+    /// Makes the text larger by one zoom step.
+    ZoomIn,
+    /// Makes the text smaller by one zoom step.
+    ZoomOut,
+    /// Resets the zoom to 100%.
+    ZoomReset,
+    /// Folds the indented block below the cursor line.
+    Fold,
+    /// Unfolds the fold at the cursor line.
+    Unfold,
+    /// Comments or uncomments the selected lines.
+    ToggleComment,
+    /// Duplicates the selection, or the cursor line without one.
+    DuplicateLines,
+    /// Moves the selected lines up by one line.
+    MoveLinesUp,
+    /// Moves the selected lines down by one line.
+    MoveLinesDown,
+    /// Selects the next match of the search.
+    FindNext,
+    /// Selects the previous match of the search.
+    FindPrevious,
+    /// Switches between inserting and overwriting typed text.
+    ToggleOverwrite,
+    /// Grows the selection to the word, string, brackets or line around it.
+    ExpandSelection,
+    /// Steps back to the selection before the last expansion.
+    ShrinkSelection,
+    /// Opens the go-to-line popup, if the editor has one.
+    GoToLine,
+    /// Moves the caret back to the position it last jumped away from.
+    NavigateBack,
+    /// Moves the caret forward again after going back.
+    NavigateForward,
+    /// Reads the selection aloud, or the cursor line without one, see
+    /// [`TextEditorState::speak`](super::TextEditorState::speak). Not bound by default.
+    Speak,
+}
+
+impl EditorCommand {
+    /// Returns `true` if holding the key of the command runs it again.
+    pub(crate) fn repeats(self) -> bool {
+        !matches!(
+            self,
+            Self::SelectAll
+                | Self::ToggleComment
+                | Self::ToggleOverwrite
+                | Self::Fold
+                | Self::Unfold
+                | Self::GoToLine
+                | Self::Speak
+        )
+    }
+}
+
+/// Maps key chords, or sequences of them, to [`EditorCommand`]s.
+///
+/// [`EditorKeymap::default`] has the shortcuts of the platform the application runs on:
+/// [`EditorKeymap::macos`] on macOS and [`EditorKeymap::standard`] everywhere else. Bindings
+/// can be added, replaced or removed on top of either one. Keys without a binding keep their
+/// fixed meaning, e.g. the arrow keys move the cursor and characters are typed.
+///
+/// ```
+/// use tessera_ui::key_chord::KeyChord;
+/// use tessera_ui::winit::keyboard::ModifiersState;
+/// use tessera_ui_basic_components::text_editor::{EditorCommand, EditorKeymap};
+///
+/// let ctrl = |c| KeyChord::character(c, ModifiersState::CONTROL);
+/// let mut keymap = EditorKeymap::standard()
+///     .with_binding([ctrl('k'), ctrl('c')], EditorCommand::ToggleComment);
+/// assert!(keymap.unbind(&[ctrl('d')]));
+/// assert_eq!(keymap.command_for(&[ctrl('k'), ctrl('c')]), Some(EditorCommand::ToggleComment));
+/// ```
+#[derive(Clone, Debug)]
+pub struct EditorKeymap {
+    matcher: ChordMatcher<EditorCommand>,
+}
+
+impl Default for EditorKeymap {
+    fn default() -> Self {
+        Self::native()
+    }
+}
+
+impl PartialEq for EditorKeymap {
+    fn eq(&self, other: &Self) -> bool {
+        self.matcher.bindings() == other.matcher.bindings()
+            && self.matcher.timeout() == other.matcher.timeout()
+    }
+}
+
+impl EditorKeymap {
+    /// Creates a keymap without bindings.
+    pub fn empty() -> Self {
+        Self {
+            matcher: ChordMatcher::new(),
+        }
+    }
+
+    /// Returns the shortcuts of Windows and Linux, built on Ctrl.
+    pub fn standard() -> Self {
+        Self::with_shortcut_modifier(ModifiersState::CONTROL)
+            .with_binding(
+                [KeyChord::character('g', ModifiersState::CONTROL)],
+                EditorCommand::GoToLine,
+            )
+            .with_binding(
+                [KeyChord::character('y', ModifiersState::CONTROL)],
+                EditorCommand::Redo,
+            )
+            .with_binding(
+                [KeyChord::named(NamedKey::F3, ModifiersState::empty())],
+                EditorCommand::FindNext,
+            )
+            .with_binding(
+                [KeyChord::named(NamedKey::F3, ModifiersState::SHIFT)],
+                EditorCommand::FindPrevious,
+            )
+            .with_binding([KeyChord::character('[', CTRL_SHIFT)], EditorCommand::Fold)
+            .with_binding([KeyChord::character('{', CTRL_SHIFT)], EditorCommand::Fold)
+            .with_binding(
+                [KeyChord::character(']', CTRL_SHIFT)],
+                EditorCommand::Unfold,
+            )
+            .with_binding(
+                [KeyChord::character('}', CTRL_SHIFT)],
+                EditorCommand::Unfold,
+            )
+            .with_binding(
+                [KeyChord::named(NamedKey::ArrowRight, ALT_SHIFT)],
+                EditorCommand::ExpandSelection,
+            )
+            .with_binding(
+                [KeyChord::named(NamedKey::ArrowLeft, ALT_SHIFT)],
+                EditorCommand::ShrinkSelection,
+            )
+            .with_binding(
+                [KeyChord::named(NamedKey::ArrowLeft, ModifiersState::ALT)],
+                EditorCommand::NavigateBack,
+            )
+            .with_binding(
+                [KeyChord::named(NamedKey::ArrowRight, ModifiersState::ALT)],
+                EditorCommand::NavigateForward,
+            )
+    }
+
+    /// Returns the shortcuts of macOS, built on Cmd.
+    ///
+    /// Besides the Cmd versions of [`Self::standard`], Cmd+G and Cmd+Shift+G step through the
+    /// search matches, Cmd+Option+[ and Cmd+Option+] fold and unfold, Ctrl+Shift+Cmd+Right
+    /// and Ctrl+Shift+Cmd+Left expand and shrink the selection, Ctrl+G goes to a line and
+    /// Ctrl+- and Ctrl+Shift+- go back and forward through the jumps, since Option+arrows
+    /// move by words.
+    pub fn macos() -> Self {
+        let cmd_opt = ModifiersState::SUPER | ModifiersState::ALT;
+        let ctrl_shift_cmd = CTRL_SHIFT | ModifiersState::SUPER;
+        Self::with_shortcut_modifier(ModifiersState::SUPER)
+            .with_binding(
+                [KeyChord::character('g', ModifiersState::SUPER)],
+                EditorCommand::FindNext,
+            )
+            .with_binding(
+                [KeyChord::character(
+                    'g',
+                    ModifiersState::SUPER | ModifiersState::SHIFT,
+                )],
+                EditorCommand::FindPrevious,
+            )
+            .with_binding([KeyChord::character('[', cmd_opt)], EditorCommand::Fold)
+            .with_binding([KeyChord::character(']', cmd_opt)], EditorCommand::Unfold)
+            .with_binding(
+                [KeyChord::character('g', ModifiersState::CONTROL)],
+                EditorCommand::GoToLine,
+            )
+            .with_binding(
+                [KeyChord::character('-', ModifiersState::CONTROL)],
+                EditorCommand::NavigateBack,
+            )
+            .with_binding(
+                [KeyChord::character('_', CTRL_SHIFT)],
+                EditorCommand::NavigateForward,
+            )
+            .with_binding(
+                [KeyChord::named(NamedKey::ArrowRight, ctrl_shift_cmd)],
+                EditorCommand::ExpandSelection,
+            )
+            .with_binding(
+                [KeyChord::named(NamedKey::ArrowLeft, ctrl_shift_cmd)],
+                EditorCommand::ShrinkSelection,
+            )
+    }
+
+    /// Returns [`Self::macos`] on macOS and [`Self::standard`] on other platforms.
+    pub fn native() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::macos()
+        } else {
+            Self::standard()
+        }
+    }
+
+    /// The bindings shared by all platforms, with `modifier` as Ctrl or Cmd.
+    fn with_shortcut_modifier(modifier: ModifiersState) -> Self {
+        let key = |c| KeyChord::character(c, modifier);
+        let shift = |c| KeyChord::character(c, modifier | ModifiersState::SHIFT);
+        let alt = |key| KeyChord::named(key, ModifiersState::ALT);
+        Self::empty()
+            .with_binding([key('c')], EditorCommand::Copy)
+            .with_binding([key('x')], EditorCommand::Cut)
+            .with_binding([key('v')], EditorCommand::Paste)
+            .with_binding([key('a')], EditorCommand::SelectAll)
+            .with_binding([key('z')], EditorCommand::Undo)
+            .with_binding([shift('z')], EditorCommand::Redo)
+            .with_binding([key('d')], EditorCommand::AddNextOccurrence)
+            .with_binding([shift('d')], EditorCommand::DuplicateLines)
+            .with_binding([key('/')], EditorCommand::ToggleComment)
+            // Plus is typed with or without Shift depending on the layout and the keypad
+            .with_binding([key('=')], EditorCommand::ZoomIn)
+            .with_binding([key('+')], EditorCommand::ZoomIn)
+            .with_binding([shift('+')], EditorCommand::ZoomIn)
+            .with_binding([key('-')], EditorCommand::ZoomOut)
+            .with_binding([key('0')], EditorCommand::ZoomReset)
+            .with_binding([alt(NamedKey::ArrowUp)], EditorCommand::MoveLinesUp)
+            .with_binding([alt(NamedKey::ArrowDown)], EditorCommand::MoveLinesDown)
+            .with_binding(
+                [KeyChord::named(NamedKey::Insert, ModifiersState::empty())],
+                EditorCommand::ToggleOverwrite,
+            )
+    }
+
+    /// Binds the chords of `sequence`, pressed one after the other, to `command`, replacing
+    /// the command it was bound to before.
+    pub fn bind(&mut self, sequence: impl Into<Vec<KeyChord>>, command: EditorCommand) {
+        self.matcher.bind(sequence, command);
+    }
+
+    /// Returns the keymap with `sequence` bound to `command`, see [`Self::bind`].
+    pub fn with_binding(
+        mut self,
+        sequence: impl Into<Vec<KeyChord>>,
+        command: EditorCommand,
+    ) -> Self {
+        self.bind(sequence, command);
+        self
+    }
+
+    /// Removes the binding of `sequence`, returning `true` if there was one.
+    ///
+    /// An unbound printable chord types its character again.
+    pub fn unbind(&mut self, sequence: &[KeyChord]) -> bool {
+        self.matcher.unbind(sequence)
+    }
+
+    /// Returns the keymap waiting `timeout` for the next chord of a sequence.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.matcher = self.matcher.with_timeout(timeout);
+        self
+    }
+
+    /// Returns the keymap reporting the chords of an unfinished sequence to `on_status`, e.g.
+    /// to show them in a status bar.
+    pub fn with_status_callback(
+        mut self,
+        on_status: impl Fn(&[KeyChord]) + Send + Sync + 'static,
+    ) -> Self {
+        self.matcher = self.matcher.with_status_callback(on_status);
+        self
+    }
+
+    /// Returns the bound sequences and their commands.
+    pub fn bindings(&self) -> &[(Vec<KeyChord>, EditorCommand)] {
+        self.matcher.bindings()
+    }
+
+    /// Returns the command bound to `sequence`, if any.
+    pub fn command_for(&self, sequence: &[KeyChord]) -> Option<EditorCommand> {
+        self.bindings()
+            .iter()
+            .find(|(bound, _)| bound == sequence)
+            .map(|&(_, command)| command)
+    }
+
+    /// Returns the sequences bound to `command`.
+    pub fn sequences_for(&self, command: EditorCommand) -> Vec<&[KeyChord]> {
+        self.bindings()
+            .iter()
+            .filter(|&&(_, bound)| bound == command)
+            .map(|(sequence, _)| sequence.as_slice())
+            .collect()
+    }
+
+    /// Returns the bindings that clash with binding `sequence`: one of the same sequence, and
+    /// those that start with it or that it starts with, e.g. Ctrl+K and Ctrl+K Ctrl+C, since
+    /// the shorter one would run before the longer one could be pressed.
+    pub fn conflicts(&self, sequence: &[KeyChord]) -> Vec<(&[KeyChord], EditorCommand)> {
+        self.bindings()
+            .iter()
+            .filter(|(bound, _)| bound.starts_with(sequence) || sequence.starts_with(bound))
+            .map(|(bound, command)| (bound.as_slice(), *command))
+            .collect()
+    }
+
+    /// Binds `sequence` to `command` in place of the sequences it was bound to, removing the
+    /// bindings of other commands it [conflicts](Self::conflicts) with.
+    pub fn rebind(&mut self, command: EditorCommand, sequence: impl Into<Vec<KeyChord>>) {
+        let sequence = sequence.into();
+        let replaced: Vec<Vec<KeyChord>> = self
+            .sequences_for(command)
+            .into_iter()
+            .chain(
+                self.conflicts(&sequence)
+                    .into_iter()
+                    .map(|(bound, _)| bound),
+            )
+            .map(<[KeyChord]>::to_vec)
+            .collect();
+        for bound in replaced {
+            self.unbind(&bound);
+        }
+        self.bind(sequence, command);
+    }
+
+    /// Returns the chords of the sequence waiting for its next chord.
+    pub fn pending(&self) -> &[KeyChord] {
+        self.matcher.pending()
+    }
+
+    /// Matches a keyboard event pressed with `modifiers`.
+    pub(crate) fn press(
+        &mut self,
+        event: &KeyEvent,
+        modifiers: ModifiersState,
+    ) -> ChordMatch<EditorCommand> {
+        self.matcher.press(event, modifiers, clock::now())
+    }
+}
+
+const CTRL_SHIFT: ModifiersState = ModifiersState::CONTROL.union(ModifiersState::SHIFT);
+const ALT_SHIFT: ModifiersState = ModifiersState::ALT.union(ModifiersState::SHIFT);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_keymap_platform_bindings() {
+        let standard = EditorKeymap::standard();
+        let macos = EditorKeymap::macos();
+        let ctrl = |c| KeyChord::character(c, ModifiersState::CONTROL);
+        let cmd = |c| KeyChord::character(c, ModifiersState::SUPER);
+        assert_eq!(
+            standard.command_for(&[ctrl('c')]),
+            Some(EditorCommand::Copy)
+        );
+        assert_eq!(standard.command_for(&[cmd('c')]), None);
+        assert_eq!(macos.command_for(&[cmd('c')]), Some(EditorCommand::Copy));
+        assert_eq!(macos.command_for(&[ctrl('c')]), None);
+        assert_eq!(
+            standard.command_for(&[ctrl('y')]),
+            Some(EditorCommand::Redo)
+        );
+        assert_eq!(
+            macos.command_for(&[cmd('g')]),
+            Some(EditorCommand::FindNext)
+        );
+        // Shifted characters arrive as the character Shift produces
+        let fold = KeyChord::character('{', CTRL_SHIFT);
+        assert_eq!(standard.command_for(&[fold]), Some(EditorCommand::Fold));
+
+        // A user binding replaces the default one and removed bindings are gone
+        let mut keymap = standard
+            .clone()
+            .with_binding([ctrl('d')], EditorCommand::Cut);
+        assert_eq!(keymap.command_for(&[ctrl('d')]), Some(EditorCommand::Cut));
+        assert_ne!(keymap, standard);
+        assert!(keymap.unbind(&[ctrl('d')]));
+        assert!(!keymap.unbind(&[ctrl('d')]));
+        assert_eq!(keymap.command_for(&[ctrl('d')]), None);
+    }
+
+    #[test]
+    fn test_editor_keymap_rebind() {
+        let ctrl = |c| KeyChord::character(c, ModifiersState::CONTROL);
+        let mut keymap = EditorKeymap::empty()
+            .with_binding([ctrl('c')], EditorCommand::Copy)
+            .with_binding([ctrl('k'), ctrl('c')], EditorCommand::ToggleComment)
+            .with_binding([ctrl('/')], EditorCommand::ToggleComment);
+
+        // A prefix of a sequence clashes with it, and so does a longer sequence
+        assert_eq!(
+            keymap.conflicts(&[ctrl('k')]),
+            vec![(&[ctrl('k'), ctrl('c')][..], EditorCommand::ToggleComment)]
+        );
+        assert_eq!(
+            keymap.conflicts(&[ctrl('c'), ctrl('v')]),
+            vec![(&[ctrl('c')][..], EditorCommand::Copy)]
+        );
+        assert!(keymap.conflicts(&[ctrl('v')]).is_empty());
+
+        // Rebinding replaces both sequences of the command and the binding it clashes with
+        keymap.rebind(EditorCommand::ToggleComment, [ctrl('c')]);
+        assert_eq!(
+            keymap.sequences_for(EditorCommand::ToggleComment),
+            vec![&[ctrl('c')][..]]
+        );
+        assert!(keymap.sequences_for(EditorCommand::Copy).is_empty());
+        assert_eq!(keymap.bindings().len(), 1);
+    }
+}
@@ -0,0 +1,298 @@
+//! Detecting the language of the content from the file name or, without a telling one, from
+//! the first line: a shebang, an XML or HTML prolog or an editor modeline.
+
+/// A language the editor knows how to detect, with the settings it implies.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    /// Display name, e.g. `"Python"`.
+    pub name: &'static str,
+    /// Other names it goes by, e.g. the filetype of Vim or the mode of Emacs, lowercase.
+    pub aliases: &'static [&'static str],
+    /// File extensions without the dot, lowercase.
+    pub extensions: &'static [&'static str],
+    /// Whole file names, e.g. `Makefile`.
+    pub file_names: &'static [&'static str],
+    /// Interpreters named in a shebang, without version numbers.
+    pub interpreters: &'static [&'static str],
+    /// Token that starts a line comment, if the language has one.
+    pub line_comment: Option<&'static str>,
+}
+
+/// Shorthand for the entries of [`LANGUAGES`].
+const fn language(
+    name: &'static str,
+    aliases: &'static [&'static str],
+    extensions: &'static [&'static str],
+    file_names: &'static [&'static str],
+    interpreters: &'static [&'static str],
+    line_comment: Option<&'static str>,
+) -> Language {
+    Language {
+        name,
+        aliases,
+        extensions,
+        file_names,
+        interpreters,
+        line_comment,
+    }
+}
+
+/// The detected languages.
+pub const LANGUAGES: &[Language] = &[
+    language("Rust", &["rust"], &["rs"], &[], &[], Some("//")),
+    language("C", &["c"], &["c", "h"], &[], &[], Some("//")),
+    language(
+        "C++",
+        &["cpp", "c++"],
+        &["cpp", "cc", "cxx", "hpp", "hh", "hxx"],
+        &[],
+        &[],
+        Some("//"),
+    ),
+    language("Go", &["go"], &["go"], &[], &[], Some("//")),
+    language("Java", &["java"], &["java"], &[], &[], Some("//")),
+    language(
+        "JavaScript",
+        &["javascript", "js"],
+        &["js", "mjs", "cjs", "jsx"],
+        &[],
+        &["node"],
+        Some("//"),
+    ),
+    language(
+        "TypeScript",
+        &["typescript", "ts"],
+        &["ts", "tsx", "mts", "cts"],
+        &[],
+        &["deno", "ts-node"],
+        Some("//"),
+    ),
+    language(
+        "Python",
+        &["python", "py"],
+        &["py", "pyw", "pyi"],
+        &["SConstruct"],
+        &["python", "pypy"],
+        Some("#"),
+    ),
+    language(
+        "Ruby",
+        &["ruby", "rb"],
+        &["rb"],
+        &["Rakefile", "Gemfile"],
+        &["ruby"],
+        Some("#"),
+    ),
+    language("Perl", &["perl"], &["pl", "pm"], &[], &["perl"], Some("#")),
+    language(
+        "Lua",
+        &["lua"],
+        &["lua"],
+        &[],
+        &["lua", "luajit"],
+        Some("--"),
+    ),
+    language("PHP", &["php"], &["php"], &[], &["php"], Some("//")),
+    language(
+        "Shell",
+        &["sh", "bash", "zsh", "shell-script"],
+        &["sh", "bash", "zsh", "ksh"],
+        &[".bashrc", ".bash_profile", ".zshrc", ".profile"],
+        &["sh", "bash", "zsh", "dash", "ksh"],
+        Some("#"),
+    ),
+    language(
+        "Makefile",
+        &["make", "makefile"],
+        &["mk"],
+        &["Makefile", "makefile", "GNUmakefile"],
+        &["make"],
+        Some("#"),
+    ),
+    language(
+        "Dockerfile",
+        &["dockerfile"],
+        &["dockerfile"],
+        &["Dockerfile", "Containerfile"],
+        &[],
+        Some("#"),
+    ),
+    language(
+        "TOML",
+        &["toml"],
+        &["toml"],
+        &["Cargo.lock"],
+        &[],
+        Some("#"),
+    ),
+    language("YAML", &["yaml"], &["yaml", "yml"], &[], &[], Some("#")),
+    language("JSON", &["json"], &["json"], &[], &[], None),
+    language(
+        "XML",
+        &["xml"],
+        &["xml", "svg", "xsd", "xsl"],
+        &[],
+        &[],
+        None,
+    ),
+    language("HTML", &["html"], &["html", "htm"], &[], &[], None),
+    language("CSS", &["css"], &["css"], &[], &[], None),
+    language(
+        "Markdown",
+        &["markdown", "md"],
+        &["md", "markdown"],
+        &[],
+        &[],
+        None,
+    ),
+    language("SQL", &["sql"], &["sql"], &[], &[], Some("--")),
+];
+
+impl Language {
+    /// Returns the language called `name` or one of its aliases, ignoring case.
+    pub fn by_name(name: &str) -> Option<&'static Language> {
+        let name = name.trim().to_lowercase();
+        LANGUAGES.iter().find(|language| {
+            language.name.to_lowercase() == name || language.aliases.contains(&name.as_str())
+        })
+    }
+
+    /// Returns the language of the file called `file_name`, which may be a path, from its
+    /// whole name like `Makefile` or its extension.
+    pub fn by_filename(file_name: &str) -> Option<&'static Language> {
+        let name = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+        LANGUAGES
+            .iter()
+            .find(|language| language.file_names.contains(&name))
+            .or_else(|| {
+                let (stem, extension) = name.rsplit_once('.')?;
+                if stem.is_empty() {
+                    return None;
+                }
+                let extension = extension.to_lowercase();
+                LANGUAGES
+                    .iter()
+                    .find(|language| language.extensions.contains(&extension.as_str()))
+            })
+    }
+
+    /// Returns the language announced by the first `line` of the content: a shebang like
+    /// `#!/usr/bin/env python3`, an `<?xml` or HTML prolog, or a Vim or Emacs modeline.
+    pub fn by_first_line(line: &str) -> Option<&'static Language> {
+        let line = line.trim();
+        if let Some(command) = line.strip_prefix("#!") {
+            return shebang_interpreter(command).and_then(|interpreter| {
+                LANGUAGES
+                    .iter()
+                    .find(|language| language.interpreters.contains(&interpreter))
+            });
+        }
+        let lower = line.to_lowercase();
+        if lower.starts_with("<?xml") {
+            return Self::by_name("xml");
+        }
+        if lower.starts_with("<!doctype html") || lower.starts_with("<html") {
+            return Self::by_name("html");
+        }
+        modeline_language(line).and_then(Self::by_name)
+    }
+
+    /// Returns the language of a file called `file_name` with `content`: from the name if it
+    /// tells, otherwise from the first line.
+    pub fn detect(file_name: Option<&str>, content: &str) -> Option<&'static Language> {
+        file_name
+            .and_then(Self::by_filename)
+            .or_else(|| Self::by_first_line(content.lines().next().unwrap_or("")))
+    }
+}
+
+/// Returns the interpreter run by the shebang `command`, without its directory and version,
+/// looking through `env` and its options.
+fn shebang_interpreter(command: &str) -> Option<&str> {
+    let mut words = command.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+    Some(program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.'))
+}
+
+/// Returns the language named in a Vim modeline (`vim: set ft=python:`) or an Emacs one
+/// (`-*- mode: python -*-` or `-*- python -*-`) in `line`.
+fn modeline_language(line: &str) -> Option<&str> {
+    if let Some(start) = line.find("-*-") {
+        let rest = &line[start + 3..];
+        let variables = &rest[..rest.find("-*-")?];
+        let mode = variables
+            .split(';')
+            .find_map(|variable| {
+                let (name, value) = variable.split_once(':')?;
+                (name.trim().eq_ignore_ascii_case("mode")).then_some(value)
+            })
+            .unwrap_or(variables);
+        return Some(mode.trim()).filter(|mode| !mode.is_empty() && !mode.contains(':'));
+    }
+    let start = ["vim:", "vi:", "ex:"]
+        .iter()
+        .find_map(|marker| line.find(marker).map(|index| index + marker.len()))?;
+    line[start..].split([' ', ':', '\t']).find_map(|option| {
+        let (name, value) = option.split_once('=')?;
+        matches!(name, "ft" | "filetype" | "syntax" | "syn").then_some(value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        let name = |language: Option<&Language>| language.map(|language| language.name);
+        assert_eq!(name(Language::by_filename("src/main.RS")), Some("Rust"));
+        assert_eq!(
+            name(Language::by_filename("/work/Makefile")),
+            Some("Makefile")
+        );
+        assert_eq!(name(Language::by_filename(".bashrc")), Some("Shell"));
+        assert_eq!(name(Language::by_filename(".gitignore")), None);
+        assert_eq!(
+            name(Language::by_first_line("#!/usr/bin/env -S python3.11 -u")),
+            Some("Python")
+        );
+        assert_eq!(name(Language::by_first_line("#!/bin/bash")), Some("Shell"));
+        assert_eq!(
+            name(Language::by_first_line("<?xml version=\"1.0\"?>")),
+            Some("XML")
+        );
+        assert_eq!(
+            name(Language::by_first_line("<!DOCTYPE html>")),
+            Some("HTML")
+        );
+        assert_eq!(
+            name(Language::by_first_line("# vim: set ft=ruby ts=2:")),
+            Some("Ruby")
+        );
+        assert_eq!(
+            name(Language::by_first_line(
+                "// -*- mode: c++; indent-tabs-mode: nil -*-"
+            )),
+            Some("C++")
+        );
+        assert_eq!(name(Language::by_first_line(";; -*- lua -*-")), Some("Lua"));
+        assert_eq!(name(Language::by_first_line("fn main() {}")), None);
+
+        // The file name wins over the first line, which helps without one
+        assert_eq!(
+            name(Language::detect(Some("run.rb"), "#!/bin/sh")),
+            Some("Ruby")
+        );
+        assert_eq!(
+            name(Language::detect(None, "#!/bin/sh\necho")),
+            Some("Shell")
+        );
+        assert_eq!(
+            Language::by_name("Shell-Script").and_then(|l| l.line_comment),
+            Some("#")
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Lens rows: app components shown between the lines of the content, e.g. "Run | Debug"
+//! actions above a test or the blame of a line.
+//!
+//! The room of a row is made by raising the line height of the first character of the line
+//! below it, so the row is laid out, scrolled and hit-tested with the content while the text
+//! stays untouched. cosmic-text centers the text of a taller row, so that row is drawn lower
+//! by half of the room, and everything placed along the text uses [`text_row`] to skip the
+//! room above it.
+
+use std::sync::Arc;
+
+use glyphon::cosmic_text::{self, LayoutRun};
+
+/// A row of components shown above a line of a [`text_editor`](crate::text_editor::text_editor),
+/// pushing that line and the ones after it down.
+///
+/// # Example
+///
+/// ```
+/// use tessera_ui_basic_components::text_editor::{LensRow, TextEditorArgs};
+///
+/// // "Run | Debug" above a test function on line 12
+/// let args = TextEditorArgs::simple().with_lens_rows(vec![LensRow::new(12, || {
+///     // Compose the buttons of the row here
+/// })]);
+/// assert_eq!(args.lens_rows[0].line, 12);
+/// ```
+#[derive(Clone)]
+pub struct LensRow {
+    /// Index of the line the row is shown above.
+    pub line: usize,
+    /// Composes the components of the row, which are laid out side by side.
+    pub content: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl LensRow {
+    /// Creates a row above `line` composed by `content`.
+    pub fn new(line: usize, content: impl Fn() + Send + Sync + 'static) -> Self {
+        Self {
+            line,
+            content: Arc::new(content),
+        }
+    }
+}
+
+/// Makes room for a row `height` pixels high above `text`, a line styled with `attrs_list`
+/// in an editor with the given font metrics.
+pub(super) fn make_room(
+    attrs_list: &mut cosmic_text::AttrsList,
+    text: &str,
+    metrics: glyphon::Metrics,
+    height: f32,
+) {
+    let room = glyphon::Metrics::new(metrics.font_size, metrics.line_height + height);
+    match text.chars().next() {
+        // Only the row holding the first character of a wrapped line gets taller
+        Some(first) => {
+            let attrs = cosmic_text::AttrsOwned::new(&attrs_list.get_span(0).metrics(room));
+            attrs_list.add_span(0..first.len_utf8(), &attrs.as_attrs());
+        }
+        None => {
+            let attrs = cosmic_text::AttrsOwned::new(&attrs_list.defaults().metrics(room));
+            *attrs_list = cosmic_text::AttrsList::new(&attrs.as_attrs());
+        }
+    }
+}
+
+/// Returns the room above the text of `run` in a buffer with rows `line_height` high.
+///
+/// Rows only get taller than the buffer's line height for a lens row, and folded lines are
+/// lower.
+fn lens_gap(run: &LayoutRun, line_height: f32) -> f32 {
+    (run.line_height - line_height).max(0.0)
+}
+
+/// Returns the top and height of the text of `run`, below the lens row above it, if any.
+pub(super) fn text_row(run: &LayoutRun, line_height: f32) -> (f32, f32) {
+    let gap = lens_gap(run, line_height);
+    (run.line_top + gap, run.line_height - gap)
+}
+
+/// Returns the top of each lens row laid out in `buffer`, with the line it is above.
+pub(super) fn lens_row_tops(buffer: &glyphon::Buffer) -> Vec<(usize, f32)> {
+    let line_height = buffer.metrics().line_height;
+    buffer
+        .layout_runs()
+        .filter(|run| lens_gap(run, line_height) > 0.0)
+        .map(|run| (run.line_i, run.line_top))
+        .collect()
+}
+
+/// Returns the rows of `buffer` below a lens row, which are drawn lower than they are laid
+/// out, with their top and bottom and how much lower, for [`TextData::with_row_offsets`].
+///
+/// [`TextData::with_row_offsets`]: crate::pipelines::TextData::with_row_offsets
+pub(super) fn row_offsets(buffer: &glyphon::Buffer) -> Vec<(f32, f32, f32)> {
+    let line_height = buffer.metrics().line_height;
+    buffer
+        .layout_runs()
+        .filter_map(|run| {
+            let gap = lens_gap(&run, line_height);
+            (gap > 0.0).then(|| (run.line_top, run.line_top + run.line_height, gap / 2.0))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipelines::write_font_system;
+
+    const METRICS: glyphon::Metrics = glyphon::Metrics::new(14.0, 20.0);
+
+    /// Lays out `lines` with a row `height` high above the line at `above`.
+    fn layout(lines: &str, above: usize, height: f32) -> glyphon::Buffer {
+        let font_system = &mut write_font_system();
+        let mut buffer = glyphon::Buffer::new(font_system, METRICS);
+        buffer.set_text(
+            font_system,
+            lines,
+            &glyphon::Attrs::new(),
+            glyphon::Shaping::Advanced,
+            None,
+        );
+        let line = &mut buffer.lines[above];
+        let mut attrs_list = line.attrs_list().clone();
+        make_room(&mut attrs_list, line.text(), METRICS, height);
+        line.set_attrs_list(attrs_list);
+        buffer.shape_until_scroll(font_system, false);
+        buffer
+    }
+
+    #[test]
+    fn test_room_pushes_later_lines_down() {
+        let buffer = layout("first\nsecond\nthird", 1, 30.0);
+        let rows: Vec<(f32, f32)> = buffer
+            .layout_runs()
+            .map(|run| text_row(&run, METRICS.line_height))
+            .collect();
+        assert_eq!(rows, vec![(0.0, 20.0), (50.0, 20.0), (70.0, 20.0)]);
+        assert_eq!(lens_row_tops(&buffer), vec![(1, 20.0)]);
+        assert_eq!(row_offsets(&buffer), vec![(20.0, 70.0, 15.0)]);
+
+        // Drawn lower by the offset, the text sits on the row as if it started below the room
+        let baselines: Vec<f32> = buffer.layout_runs().map(|run| run.line_y).collect();
+        assert_eq!(baselines[1] + 15.0 - 50.0, baselines[0]);
+    }
+
+    #[test]
+    fn test_room_above_an_empty_line() {
+        let buffer = layout("first\n\nthird", 1, 30.0);
+        assert_eq!(lens_row_tops(&buffer), vec![(1, 20.0)]);
+        let third = buffer.layout_runs().last().unwrap();
+        assert_eq!(third.line_top, 70.0);
+    }
+
+    #[test]
+    fn test_only_the_first_row_of_a_wrapped_line_gets_taller() {
+        let font_system = &mut write_font_system();
+        let mut buffer = glyphon::Buffer::new(font_system, METRICS);
+        buffer.set_size(font_system, Some(60.0), None);
+        buffer.set_text(
+            font_system,
+            "a line much wider than the buffer",
+            &glyphon::Attrs::new(),
+            glyphon::Shaping::Advanced,
+            None,
+        );
+        let mut attrs_list = buffer.lines[0].attrs_list().clone();
+        make_room(&mut attrs_list, "a", METRICS, 30.0);
+        buffer.lines[0].set_attrs_list(attrs_list);
+        buffer.shape_until_scroll(font_system, false);
+
+        let heights: Vec<f32> = buffer.layout_runs().map(|run| run.line_height).collect();
+        assert!(heights.len() > 1);
+        assert_eq!(heights[0], 50.0);
+        assert!(heights[1..].iter().all(|&height| height == 20.0));
+    }
+}
@@ -0,0 +1,166 @@
+//! Detection of URLs, file paths and configured patterns in the editor content.
+
+use std::ops::Range;
+
+use tessera_ui::Px;
+
+use super::is_word_char;
+
+/// Thickness of the underline of a hovered link.
+pub(super) const LINK_UNDERLINE_HEIGHT: Px = Px(1);
+
+/// Schemes that start a URL.
+const SCHEMES: [&str; 5] = ["https://", "http://", "ftp://", "file://", "mailto:"];
+
+/// Characters that end a link, on top of whitespace.
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            '"' | '\'' | '`' | '<' | '>' | '(' | ')' | '[' | ']' | '{' | '}'
+        )
+}
+
+/// Returns `true` if a path could start at byte `start` of `line`: a `/`, `./`, `../` or `~/`,
+/// or a drive letter followed by `:\` or `:/`.
+fn starts_path(line: &str, start: usize) -> bool {
+    let rest = &line[start..];
+    let drive = rest.as_bytes();
+    rest.starts_with('/')
+        || rest.starts_with("./")
+        || rest.starts_with("../")
+        || rest.starts_with("~/")
+        || (drive.len() >= 3
+            && drive[0].is_ascii_alphabetic()
+            && drive[1] == b':'
+            && (drive[2] == b'\\' || drive[2] == b'/'))
+}
+
+/// Returns the byte ranges of the URLs and file paths in `line`.
+///
+/// A link starts at the beginning of the line or after a delimiter and runs to the next
+/// whitespace, quote or bracket. Trailing punctuation such as the full stop of a sentence is
+/// left out. A path must contain a letter or digit, so comment markers like `//` are skipped.
+pub(crate) fn find_links(line: &str) -> Vec<Range<usize>> {
+    let mut links = Vec::new();
+    let mut previous = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let at_boundary = previous.is_none_or(is_delimiter);
+        previous = Some(c);
+        if !at_boundary || is_delimiter(c) {
+            continue;
+        }
+        let rest = &line[start..];
+        let is_url = SCHEMES.iter().any(|scheme| {
+            rest.len() > scheme.len()
+                && rest.is_char_boundary(scheme.len())
+                && rest[..scheme.len()].eq_ignore_ascii_case(scheme)
+        });
+        if !is_url && !starts_path(line, start) {
+            continue;
+        }
+        let len = rest.find(is_delimiter).unwrap_or(rest.len());
+        let link = rest[..len].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        let has_name = is_url || link.chars().any(char::is_alphanumeric);
+        if has_name && !SCHEMES.contains(&link) {
+            links.push(start..start + link.len());
+        }
+        // Skip the rest of the token, a link never starts inside another one
+        while chars.next_if(|&(i, _)| i < start + len).is_some() {}
+        previous = rest[..len].chars().last();
+    }
+    links
+}
+
+/// A kind of link detected on top of URLs and file paths, e.g. issue numbers.
+///
+/// A pattern starts at the beginning of a word, so `#12` is found in `fixes #12` but not in
+/// `a#12`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkPattern {
+    /// The prefix followed by one or more ASCII digits, e.g. `#` for `#123` or `JIRA-` for
+    /// `JIRA-42`.
+    Numbered(String),
+    /// The prefix followed by one or more word characters, e.g. `@` for `@alice`.
+    Word(String),
+}
+
+impl LinkPattern {
+    /// Returns the length of the link at the start of `rest`, if one starts there.
+    fn match_len(&self, rest: &str) -> Option<usize> {
+        let (prefix, is_body): (_, fn(char) -> bool) = match self {
+            Self::Numbered(prefix) => (prefix, |c| c.is_ascii_digit()),
+            Self::Word(prefix) => (prefix, is_word_char),
+        };
+        let body = rest.strip_prefix(prefix.as_str())?;
+        let len = body.find(|c| !is_body(c)).unwrap_or(body.len());
+        (len > 0).then_some(prefix.len() + len)
+    }
+}
+
+/// Returns the byte ranges of the matches of `patterns` in `line`, the first pattern winning
+/// where several match at the same place.
+pub(crate) fn find_pattern_links(line: &str, patterns: &[LinkPattern]) -> Vec<Range<usize>> {
+    let mut links = Vec::new();
+    let mut previous = None;
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let at_boundary = previous.is_none_or(|previous| !is_word_char(previous));
+        previous = Some(c);
+        if !at_boundary {
+            continue;
+        }
+        let rest = &line[start..];
+        let Some(len) = patterns.iter().find_map(|pattern| pattern.match_len(rest)) else {
+            continue;
+        };
+        links.push(start..start + len);
+        while chars.next_if(|&(i, _)| i < start + len).is_some() {}
+        previous = rest[..len].chars().last();
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(line: &str) -> Vec<&str> {
+        find_links(line).into_iter().map(|r| &line[r]).collect()
+    }
+
+    #[test]
+    fn test_find_links() {
+        assert_eq!(
+            links("see https://example.com/a?b=1. (or http://x.org)"),
+            ["https://example.com/a?b=1", "http://x.org"]
+        );
+        assert_eq!(
+            links("error at /src/main.rs:12:5: oops"),
+            ["/src/main.rs:12:5"]
+        );
+        assert_eq!(
+            links("open ./a.txt, ~/b or C:\\tmp\\c.log"),
+            ["./a.txt", "~/b", "C:\\tmp\\c.log"]
+        );
+        // Comment markers, divisions and schemes on their own are not links
+        assert!(links("// a/b 1 / 2 https://").is_empty());
+        assert!(links("xhttps://example.com").is_empty());
+    }
+
+    #[test]
+    fn test_find_pattern_links() {
+        let patterns = [
+            LinkPattern::Numbered("#".to_string()),
+            LinkPattern::Numbered("JIRA-".to_string()),
+            LinkPattern::Word("@".to_string()),
+        ];
+        let line = "fixes #12, JIRA-7 (@ann_b) not a#3, # or JIRA-x";
+        let found: Vec<_> = find_pattern_links(line, &patterns)
+            .into_iter()
+            .map(|r| &line[r])
+            .collect();
+        assert_eq!(found, ["#12", "JIRA-7", "@ann_b"]);
+    }
+}
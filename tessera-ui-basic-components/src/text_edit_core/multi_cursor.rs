@@ -0,0 +1,288 @@
+//! Secondary carets for multi-cursor editing.
+//!
+//! The editor's own cursor and selection form the primary caret. Additional carets are kept
+//! next to it and every action is replayed at each of them, bottom-most first, so that an
+//! edit never shifts the position of a caret that still has to be processed.
+
+use glyphon::{
+    Cursor, Edit, FontSystem,
+    cosmic_text::{LineEnding, Selection},
+};
+
+use super::{cursor_to_offset, editor_content, insert_text_at, offset_to_cursor};
+
+/// A caret in addition to the editor's primary cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Caret {
+    /// Position of the caret.
+    pub cursor: Cursor,
+    /// Selection anchored at this caret, in the same form as [`glyphon::Edit::selection`].
+    pub selection: Selection,
+}
+
+impl Caret {
+    /// Returns the ordered bounds of the caret's selection, if it selects anything.
+    pub(crate) fn selection_bounds(&self) -> Option<(Cursor, Cursor)> {
+        let anchor = selection_anchor(self.selection)?;
+        let (start, end) = if (anchor.line, anchor.index) <= (self.cursor.line, self.cursor.index) {
+            (anchor, self.cursor)
+        } else {
+            (self.cursor, anchor)
+        };
+        (start != end).then_some((start, end))
+    }
+}
+
+fn selection_anchor(selection: Selection) -> Option<Cursor> {
+    match selection {
+        Selection::None => None,
+        Selection::Normal(anchor) | Selection::Line(anchor) | Selection::Word(anchor) => {
+            Some(anchor)
+        }
+    }
+}
+
+fn with_anchor(selection: Selection, anchor: Cursor) -> Selection {
+    match selection {
+        Selection::None => Selection::None,
+        Selection::Normal(_) => Selection::Normal(anchor),
+        Selection::Line(_) => Selection::Line(anchor),
+        Selection::Word(_) => Selection::Word(anchor),
+    }
+}
+
+/// A caret flattened to byte offsets into the content, which survive edits elsewhere.
+#[derive(Clone, Copy)]
+struct FlatCaret {
+    cursor: usize,
+    anchor: Option<usize>,
+    selection: Selection,
+}
+
+impl FlatCaret {
+    fn from_caret(editor: &glyphon::Editor, caret: Caret) -> Self {
+        Self {
+            cursor: cursor_to_offset(editor, caret.cursor),
+            anchor: selection_anchor(caret.selection).map(|a| cursor_to_offset(editor, a)),
+            selection: caret.selection,
+        }
+    }
+
+    fn to_caret(self, editor: &glyphon::Editor) -> Caret {
+        let cursor = offset_to_cursor(editor, self.cursor);
+        let selection = match self.anchor {
+            Some(anchor) => with_anchor(self.selection, offset_to_cursor(editor, anchor)),
+            None => Selection::None,
+        };
+        Caret { cursor, selection }
+    }
+
+    fn start(&self) -> usize {
+        self.cursor.min(self.anchor.unwrap_or(self.cursor))
+    }
+
+    fn end(&self) -> usize {
+        self.cursor.max(self.anchor.unwrap_or(0))
+    }
+
+    /// Returns whether editing at `self` would touch `other`, which ends no later than it.
+    fn overlaps(&self, other: &Self) -> bool {
+        other.end() > self.start() || (other.end() == self.start() && other.start() == self.end())
+    }
+
+    fn shift(&mut self, delta: isize) {
+        self.cursor = self.cursor.saturating_add_signed(delta);
+        self.anchor = self.anchor.map(|a| a.saturating_add_signed(delta));
+    }
+}
+
+/// Applies `action` at the primary caret and at every caret in `secondary`.
+///
+/// Afterwards the editor's cursor and selection hold the moved primary caret and `secondary`
+/// holds the moved secondary carets. Carets that end up on the same position are merged.
+pub(crate) fn apply_to_carets(
+    editor: &mut glyphon::Editor<'static>,
+    font_system: &mut FontSystem,
+    secondary: &mut Vec<Caret>,
+    action: glyphon::Action,
+) {
+    edit_carets(editor, secondary, |editor| {
+        editor_action(editor, font_system, action);
+    });
+}
+
+/// Inserts `text` in place of the selection of the primary caret and of every caret in
+/// `secondary`, moving the carets like [`apply_to_carets`].
+pub(crate) fn insert_at_carets(
+    editor: &mut glyphon::Editor<'static>,
+    secondary: &mut Vec<Caret>,
+    text: &str,
+) {
+    edit_carets(editor, secondary, |editor| insert_string(editor, text));
+}
+
+/// Runs `edit` with the editor's own caret at the primary caret and at every caret in
+/// `secondary` in turn.
+fn edit_carets(
+    editor: &mut glyphon::Editor<'static>,
+    secondary: &mut Vec<Caret>,
+    mut edit: impl FnMut(&mut glyphon::Editor<'static>),
+) {
+    if secondary.is_empty() {
+        keep_open_end(editor, &mut edit);
+        return;
+    }
+
+    let primary = Caret {
+        cursor: editor.cursor(),
+        selection: editor.selection(),
+    };
+    let mut carets: Vec<FlatCaret> = std::iter::once(primary)
+        .chain(secondary.iter().copied())
+        .map(|caret| FlatCaret::from_caret(editor, caret))
+        .collect();
+
+    // Bottom-most first, so earlier positions stay valid while later ones are edited
+    let mut order: Vec<usize> = (0..carets.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(carets[i].end()));
+    // A caret inside the selection of another would be edited twice; the primary caret wins
+    let mut kept: Vec<usize> = Vec::with_capacity(order.len());
+    for i in order {
+        match kept.last() {
+            Some(&k) if carets[k].overlaps(&carets[i]) => {
+                if i == 0 {
+                    kept.pop();
+                    kept.push(i);
+                }
+            }
+            _ => kept.push(i),
+        }
+    }
+    let order = kept;
+
+    for (processed, &i) in order.iter().enumerate() {
+        let caret = carets[i].to_caret(editor);
+        editor.set_cursor(caret.cursor);
+        editor.set_selection(caret.selection);
+
+        let len_before = editor_content(editor).len();
+        keep_open_end(editor, &mut edit);
+        let delta = editor_content(editor).len() as isize - len_before as isize;
+
+        carets[i] = FlatCaret::from_caret(
+            editor,
+            Caret {
+                cursor: editor.cursor(),
+                selection: editor.selection(),
+            },
+        );
+        // Carets below this one have already been edited and move with the text
+        if delta != 0 {
+            for &j in &order[..processed] {
+                carets[j].shift(delta);
+            }
+        }
+    }
+
+    let primary = carets[0].to_caret(editor);
+    editor.set_cursor(primary.cursor);
+    editor.set_selection(primary.selection);
+
+    secondary.clear();
+    let edited = (1..carets.len()).filter(|i| order.contains(i));
+    for caret in edited.map(|i| carets[i].to_caret(editor)) {
+        if caret.cursor != primary.cursor && !secondary.iter().any(|c| c.cursor == caret.cursor) {
+            secondary.push(caret);
+        }
+    }
+}
+
+/// Runs `edit`, leaving the last line without a line ending if it had none.
+///
+/// glyphon joins lines with the ending of the first one, so deleting up to the end of the
+/// content would otherwise leave a line break after it.
+fn keep_open_end(
+    editor: &mut glyphon::Editor<'static>,
+    edit: &mut impl FnMut(&mut glyphon::Editor<'static>),
+) {
+    let last_ending = |editor: &glyphon::Editor<'static>| {
+        editor.with_buffer(|buffer| buffer.lines.last().map(|line| line.ending()))
+    };
+    let open = last_ending(editor) == Some(LineEnding::None);
+    edit(editor);
+    if open && last_ending(editor) != Some(LineEnding::None) {
+        editor.with_buffer_mut(|buffer| {
+            if let Some(line) = buffer.lines.last_mut() {
+                line.set_ending(LineEnding::None);
+            }
+        });
+    }
+}
+
+/// Applies `action` at the editor's own caret.
+fn editor_action(
+    editor: &mut glyphon::Editor<'static>,
+    font_system: &mut FontSystem,
+    action: glyphon::Action,
+) {
+    match action {
+        glyphon::Action::Insert('\t') => insert_string(editor, "\t"),
+        action => editor.action(font_system, action),
+    }
+}
+
+/// Inserts `text` in place of the selection of the editor's own caret.
+fn insert_string(editor: &mut glyphon::Editor<'static>, text: &str) {
+    editor.delete_selection();
+    let at = editor.cursor();
+    let cursor = insert_text_at(editor, at, text);
+    editor.set_cursor(cursor);
+}
+
+/// Returns the byte range of the word around `index` in `line`, if the index touches a word.
+pub(crate) fn word_at(line: &str, index: usize) -> Option<(usize, usize)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let start = line[..index]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word(*c))
+        .last()
+        .map_or(index, |(i, _)| i);
+    let end = line[index..]
+        .char_indices()
+        .find(|(_, c)| !is_word(*c))
+        .map_or(line.len(), |(i, _)| index + i);
+    (start < end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_at() {
+        let line = "let foo_bar = 1;";
+        assert_eq!(word_at(line, 4), Some((4, 11)));
+        assert_eq!(word_at(line, 11), Some((4, 11)));
+        assert_eq!(word_at(line, 3), Some((0, 3)));
+        assert_eq!(word_at(line, 12), None);
+    }
+
+    #[test]
+    fn test_caret_selection_bounds_are_ordered() {
+        let caret = Caret {
+            cursor: Cursor::new(0, 2),
+            selection: Selection::Normal(Cursor::new(0, 7)),
+        };
+        assert_eq!(
+            caret.selection_bounds(),
+            Some((Cursor::new(0, 2), Cursor::new(0, 7)))
+        );
+
+        let empty = Caret {
+            cursor: Cursor::new(1, 0),
+            selection: Selection::Normal(Cursor::new(1, 0)),
+        };
+        assert_eq!(empty.selection_bounds(), None);
+    }
+}
@@ -0,0 +1,65 @@
+//! Positions in the content as lines and columns, for bookmarks, jump lists and language
+//! servers.
+
+use glyphon::Cursor;
+
+/// A position in the content of a [`TextEditorState`](super::TextEditorState), independent
+/// of how the text is laid out.
+///
+/// The three fields describe the same place: `column` is a byte index within `line` and
+/// `offset` the byte index into the whole content, with line breaks counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextPosition {
+    /// Index of the line.
+    pub line: usize,
+    /// Byte index within the line.
+    pub column: usize,
+    /// Byte index into the content.
+    pub offset: usize,
+}
+
+impl From<TextPosition> for Cursor {
+    fn from(position: TextPosition) -> Self {
+        Cursor::new(position.line, position.column)
+    }
+}
+
+/// Returns the number of UTF-16 code units in `text` before the byte `column`.
+pub(super) fn utf16_column(text: &str, column: usize) -> usize {
+    text[..column.min(text.len())]
+        .chars()
+        .map(char::len_utf16)
+        .sum()
+}
+
+/// Returns the byte index in `text` after `utf16_column` UTF-16 code units, moved back to
+/// the start of a character split by it and clamped to the end of `text`.
+pub(super) fn byte_column(text: &str, utf16_column: usize) -> usize {
+    let mut units = 0;
+    for (index, c) in text.char_indices() {
+        units += c.len_utf16();
+        if units > utf16_column {
+            return index;
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf16_columns() {
+        // 'é' is two bytes and one unit, '𝄞' four bytes and two units
+        let text = "aé𝄞b";
+        assert_eq!(utf16_column(text, 0), 0);
+        assert_eq!(utf16_column(text, 3), 2);
+        assert_eq!(utf16_column(text, 7), 4);
+        assert_eq!(utf16_column(text, 99), 5);
+        assert_eq!(byte_column(text, 2), 3);
+        assert_eq!(byte_column(text, 3), 3);
+        assert_eq!(byte_column(text, 4), 7);
+        assert_eq!(byte_column(text, 99), 8);
+    }
+}
@@ -0,0 +1,159 @@
+//! Text being composed with an input method, shown at the caret but kept out of the content
+//! until it is committed.
+//!
+//! The composition is never inserted into the editor's buffer. Each layout inserts it into a
+//! copy of the buffer instead, which the text and everything drawn over it is placed by
+//! while composing.
+
+use glyphon::{Cursor, FontSystem, cosmic_text};
+use tessera_ui::Px;
+
+use super::TextRange;
+
+/// Thickness of the underline of the composed text.
+pub(super) const PREEDIT_UNDERLINE_HEIGHT: Px = Px(1);
+
+/// A composition shown at the primary caret.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Preedit {
+    /// The composed text, on a single line.
+    pub(crate) text: String,
+    /// Byte index in `text` of the caret.
+    pub(crate) caret: usize,
+}
+
+/// The layout of the content with a composition inserted at the caret.
+pub(crate) struct ComposedLayout {
+    /// An editor over the copy of the buffer holding the composition.
+    pub(crate) editor: glyphon::Editor<'static>,
+    /// Where the composition is inserted, the primary caret of the content.
+    at: Cursor,
+    /// Length of the composition in bytes.
+    len: usize,
+    /// Byte index of the caret within the composition.
+    caret: usize,
+}
+
+impl ComposedLayout {
+    /// Lays out a copy of `buffer` with `preedit` inserted at `at`, with the default
+    /// attributes of that line.
+    pub(crate) fn new(
+        mut buffer: glyphon::Buffer,
+        at: Cursor,
+        preedit: &Preedit,
+        font_system: &mut FontSystem,
+    ) -> Self {
+        if let Some(line) = buffer.lines.get_mut(at.line) {
+            let attrs = cosmic_text::AttrsList::new(&line.attrs_list().defaults());
+            let after = line.split_off(at.index);
+            let composed = cosmic_text::BufferLine::new(
+                &preedit.text,
+                line.ending(),
+                attrs,
+                cosmic_text::Shaping::Advanced,
+            );
+            line.append(&composed);
+            line.append(&after);
+        }
+        buffer.shape_until_scroll(font_system, false);
+        Self {
+            editor: glyphon::Editor::new(buffer),
+            at,
+            len: preedit.text.len(),
+            caret: preedit.caret,
+        }
+    }
+
+    /// Returns where `cursor` of the content is in the layout. See [`composed_cursor`].
+    pub(crate) fn cursor(&self, cursor: Cursor, after: bool) -> Cursor {
+        composed_cursor(self.at, self.len, cursor, after)
+    }
+
+    /// Returns where `range` of the content is in the layout, leaving out the composition.
+    pub(crate) fn range(&self, range: TextRange) -> TextRange {
+        TextRange {
+            start: self.cursor(range.start, true),
+            end: self.cursor(range.end, false),
+        }
+    }
+
+    /// Returns the range of the composition in the layout.
+    pub(crate) fn composition(&self) -> TextRange {
+        TextRange {
+            start: self.at,
+            end: Cursor::new(self.at.line, self.at.index + self.len),
+        }
+    }
+
+    /// Returns the position of the primary caret in the layout, within the composition.
+    pub(crate) fn caret(&self) -> Cursor {
+        Cursor::new(self.at.line, self.at.index + self.caret)
+    }
+}
+
+/// Returns where `cursor` of the content is in a layout with `len` bytes inserted at `at`.
+///
+/// Positions after `at` on its line move right by `len`, and so does `at` itself if `after`,
+/// e.g. for the start of a range rather than its end.
+pub(super) fn composed_cursor(at: Cursor, len: usize, cursor: Cursor, after: bool) -> Cursor {
+    let moves =
+        cursor.line == at.line && (cursor.index > at.index || (after && cursor.index == at.index));
+    match moves {
+        true => Cursor::new(cursor.line, cursor.index + len),
+        false => cursor,
+    }
+}
+
+/// Returns the byte index in `text` to put the caret at, given the cursor range the input
+/// method reported: its end, or the end of `text` without one. An index splitting a
+/// character is moved back to its start.
+pub(super) fn preedit_caret(text: &str, cursor: Option<(usize, usize)>) -> usize {
+    let mut index = cursor.map_or(text.len(), |(_, end)| end.min(text.len()));
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preedit_caret() {
+        // 'か' is three bytes
+        assert_eq!(preedit_caret("かな", None), 6);
+        assert_eq!(preedit_caret("かな", Some((0, 3))), 3);
+        assert_eq!(preedit_caret("かな", Some((0, 4))), 3);
+        assert_eq!(preedit_caret("かな", Some((0, 99))), 6);
+    }
+
+    #[test]
+    fn test_composed_cursor() {
+        let at = Cursor::new(1, 4);
+        assert_eq!(
+            composed_cursor(at, 6, Cursor::new(0, 9), true),
+            Cursor::new(0, 9)
+        );
+        assert_eq!(
+            composed_cursor(at, 6, Cursor::new(1, 2), true),
+            Cursor::new(1, 2)
+        );
+        assert_eq!(
+            composed_cursor(at, 6, Cursor::new(1, 4), false),
+            Cursor::new(1, 4)
+        );
+        assert_eq!(
+            composed_cursor(at, 6, Cursor::new(1, 4), true),
+            Cursor::new(1, 10)
+        );
+        assert_eq!(
+            composed_cursor(at, 6, Cursor::new(1, 5), false),
+            Cursor::new(1, 11)
+        );
+        assert_eq!(
+            composed_cursor(at, 6, Cursor::new(2, 0), true),
+            Cursor::new(2, 0)
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! Copying the selection as HTML, keeping the colors the text is drawn in.
+
+use std::ops::Range;
+
+/// Returns the pieces of `range` of `line` with the color each one is drawn in, `None` for
+/// the default color.
+pub(super) fn colored_segments(
+    line: &glyphon::BufferLine,
+    range: Range<usize>,
+) -> Vec<(&str, Option<glyphon::Color>)> {
+    let text = line.text();
+    let attrs = line.attrs_list();
+    let mut bounds: Vec<usize> = attrs
+        .spans_iter()
+        .flat_map(|(span, _)| [span.start, span.end])
+        .filter(|&index| range.start < index && index < range.end)
+        .chain([range.start, range.end])
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+    bounds
+        .windows(2)
+        .map(|pair| (&text[pair[0]..pair[1]], attrs.get_span(pair[0]).color_opt))
+        .collect()
+}
+
+/// Returns an HTML fragment of `lines`, each split into colored pieces, in a monospace `pre`
+/// block. Pieces in the default color are left unstyled so they take the color of the target.
+pub(super) fn html_fragment(lines: &[Vec<(&str, Option<glyphon::Color>)>]) -> String {
+    let mut html = String::from("<pre style=\"font-family: monospace\">");
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            html.push('\n');
+        }
+        for &(text, color) in line {
+            match color {
+                Some(color) => {
+                    html.push_str(&format!("<span style=\"color: {}\">", css_color(color)));
+                    push_escaped(&mut html, text);
+                    html.push_str("</span>");
+                }
+                None => push_escaped(&mut html, text),
+            }
+        }
+    }
+    html.push_str("</pre>");
+    html
+}
+
+fn css_color(color: glyphon::Color) -> String {
+    if color.a() == u8::MAX {
+        format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+    } else {
+        let alpha = f32::from(color.a()) / 255.0;
+        format!(
+            "rgba({}, {}, {}, {alpha:.2})",
+            color.r(),
+            color.g(),
+            color.b()
+        )
+    }
+}
+
+fn push_escaped(html: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            _ => html.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_fragment() {
+        let red = glyphon::Color::rgb(255, 0, 0);
+        let faint = glyphon::Color::rgba(0, 0, 255, 128);
+        let lines = [
+            vec![("let ", Some(red)), ("a = b < c;", None)],
+            vec![],
+            vec![("\"x\" & y", Some(faint))],
+        ];
+        assert_eq!(
+            html_fragment(&lines),
+            "<pre style=\"font-family: monospace\"><span style=\"color: #ff0000\">let </span>\
+             a = b &lt; c;\n\n<span style=\"color: rgba(0, 0, 255, 0.50)\">&quot;x&quot; &amp; \
+             y</span></pre>"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Column rulers marking a maximum line length.
+
+use tessera_ui::Px;
+
+use super::{FontFamily, editor_attrs};
+use crate::pipelines::{FontFeatures, write_font_system};
+
+/// Width of a ruler line.
+pub(super) const RULER_WIDTH: Px = Px(1);
+
+/// Returns the advance width of one column for `metrics` in `family` shaped with `features`,
+/// taken from the digit `0`.
+pub(super) fn measure_column_width(
+    metrics: glyphon::Metrics,
+    family: &FontFamily,
+    features: &FontFeatures,
+) -> f32 {
+    let font_system = &mut write_font_system();
+    let mut buffer = glyphon::Buffer::new(font_system, metrics);
+    buffer.set_text(
+        font_system,
+        "0",
+        &editor_attrs(family, features),
+        glyphon::Shaping::Advanced,
+        None,
+    );
+    buffer.shape_until_scroll(font_system, false);
+    buffer
+        .layout_runs()
+        .next()
+        .map_or(metrics.font_size / 2.0, |run| run.line_w)
+}
+
+/// Returns the byte index of the first character of `line` that starts at or after column
+/// `limit`, with tabs advancing to the next multiple of `tab_width`.
+pub(super) fn overflow_start(line: &str, limit: usize, tab_width: usize) -> Option<usize> {
+    let tab_width = tab_width.max(1);
+    let mut column = 0;
+    for (i, c) in line.char_indices() {
+        if column >= limit {
+            return Some(i);
+        }
+        column = match c {
+            '\t' => column + tab_width - column % tab_width,
+            _ => column + 1,
+        };
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflow_start() {
+        assert_eq!(overflow_start("abcdef", 4, 4), Some(4));
+        assert_eq!(overflow_start("abcd", 4, 4), None);
+        assert_eq!(overflow_start("\tab", 4, 4), Some(1));
+        assert_eq!(overflow_start("a\tb", 2, 4), Some(2));
+        assert_eq!(overflow_start("äöüß!", 4, 4), Some("äöüß".len()));
+    }
+}
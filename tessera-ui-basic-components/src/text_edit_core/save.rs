@@ -0,0 +1,92 @@
+//! Whitespace clean-up applied to the content before it is saved.
+
+/// Clean-up steps applied by [`super::TextEditorState::prepare_save`]. All are off by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SaveOptions {
+    /// Removes spaces and tabs at the end of every line.
+    pub trim_trailing_whitespace: bool,
+    /// Ends non-empty content with a line break.
+    pub ensure_final_newline: bool,
+    /// Rewrites the indentation of every line with the editor's indent unit: spaces when it
+    /// inserts spaces, otherwise tabs followed by the spaces that don't fill a whole tab.
+    pub normalize_indentation: bool,
+}
+
+/// Applies `options` to a single line without its line ending.
+pub(crate) fn normalize_line(
+    line: &str,
+    options: &SaveOptions,
+    tab_width: usize,
+    insert_spaces: bool,
+) -> String {
+    let line = if options.trim_trailing_whitespace {
+        line.trim_end_matches([' ', '\t'])
+    } else {
+        line
+    };
+    if !options.normalize_indentation {
+        return line.to_string();
+    }
+
+    let tab_width = tab_width.max(1);
+    let body = line.trim_start_matches([' ', '\t']);
+    let columns = line[..line.len() - body.len()]
+        .chars()
+        .fold(0, |column, c| match c {
+            '\t' => column + tab_width - column % tab_width,
+            _ => column + 1,
+        });
+    let indent = if insert_spaces {
+        " ".repeat(columns)
+    } else {
+        "\t".repeat(columns / tab_width) + &" ".repeat(columns % tab_width)
+    };
+    indent + body
+}
+
+/// Maps a byte index in `old` to the same character position in its normalized form `new`.
+///
+/// Positions after the indentation keep their distance from it, positions inside it stay
+/// inside, and positions in removed trailing whitespace move to the end of the line.
+pub(crate) fn map_index(old: &str, new: &str, index: usize) -> usize {
+    let indent_len = |s: &str| s.len() - s.trim_start_matches([' ', '\t']).len();
+    let (old_indent, new_indent) = (indent_len(old), indent_len(new));
+    let index = if index >= old_indent {
+        index - old_indent + new_indent
+    } else {
+        index.min(new_indent)
+    };
+    index.min(new.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_line() {
+        let trim = SaveOptions {
+            trim_trailing_whitespace: true,
+            ..SaveOptions::default()
+        };
+        assert_eq!(normalize_line("  a = 1; \t", &trim, 4, true), "  a = 1;");
+
+        let indent = SaveOptions {
+            normalize_indentation: true,
+            ..SaveOptions::default()
+        };
+        assert_eq!(normalize_line("\t  x ", &indent, 4, true), "      x ");
+        assert_eq!(normalize_line("      x", &indent, 4, false), "\t  x");
+        // A tab after two spaces only advances to the next tab stop
+        assert_eq!(normalize_line("  \tx", &indent, 4, true), "    x");
+        assert_eq!(normalize_line("   ", &indent, 4, false), "   ");
+    }
+
+    #[test]
+    fn test_map_index() {
+        assert_eq!(map_index("\tfoo", "    foo", 2), 5);
+        assert_eq!(map_index("    foo", "\tfoo", 2), 1);
+        assert_eq!(map_index("foo   ", "foo", 5), 3);
+        assert_eq!(map_index("foo", "foo", 1), 1);
+    }
+}
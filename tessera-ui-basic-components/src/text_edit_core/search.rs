@@ -0,0 +1,107 @@
+//! Literal text search over the lines of an editor buffer.
+
+use glyphon::Cursor;
+
+/// A range of text in the editor, from `start` (inclusive) to `end` (exclusive).
+///
+/// Both ends are glyphon cursors, i.e. a line index and a byte index within that line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextRange {
+    /// Start of the range.
+    pub start: Cursor,
+    /// End of the range.
+    pub end: Cursor,
+}
+
+/// Finds all non-overlapping, case-sensitive occurrences of `pattern` in `lines`.
+///
+/// Matches never span multiple lines. An empty pattern matches nothing.
+pub(crate) fn find_matches<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+    pattern: &str,
+) -> Vec<TextRange> {
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+
+    lines
+        .into_iter()
+        .enumerate()
+        .flat_map(|(line, text)| {
+            text.match_indices(pattern)
+                .map(move |(index, found)| TextRange {
+                    start: Cursor::new(line, index),
+                    end: Cursor::new(line, index + found.len()),
+                })
+        })
+        .collect()
+}
+
+/// Returns the first match starting at or after `cursor`, wrapping around to the first match.
+pub(crate) fn next_match(matches: &[TextRange], cursor: Cursor) -> Option<TextRange> {
+    matches
+        .iter()
+        .find(|m| (m.start.line, m.start.index) >= (cursor.line, cursor.index))
+        .or_else(|| matches.first())
+        .copied()
+}
+
+/// Returns the last match ending before `cursor`, wrapping around to the last match.
+pub(crate) fn previous_match(matches: &[TextRange], cursor: Cursor) -> Option<TextRange> {
+    matches
+        .iter()
+        .rev()
+        .find(|m| (m.end.line, m.end.index) < (cursor.line, cursor.index))
+        .or_else(|| matches.last())
+        .copied()
+}
+
+/// Returns the text of the search counter, e.g. `3 of 12`, for the match at `current` out of
+/// `total` matches.
+pub(crate) fn counter_label(current: Option<usize>, total: usize) -> String {
+    match (current, total) {
+        (_, 0) => "No results".to_string(),
+        (Some(current), total) => format!("{} of {total}", current + 1),
+        (None, 1) => "1 match".to_string(),
+        (None, total) => format!("{total} matches"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matches_per_line() {
+        let matches = find_matches(["foo bar foo", "", "xfoo"], "foo");
+        let starts: Vec<_> = matches
+            .iter()
+            .map(|m| (m.start.line, m.start.index, m.end.index))
+            .collect();
+        assert_eq!(starts, vec![(0, 0, 3), (0, 8, 11), (2, 1, 4)]);
+        assert!(find_matches(["foo"], "").is_empty());
+    }
+
+    #[test]
+    fn test_next_and_previous_wrap_around() {
+        let matches = find_matches(["ab ab", "ab"], "ab");
+
+        let next = next_match(&matches, Cursor::new(0, 1)).unwrap();
+        assert_eq!(next.start, Cursor::new(0, 3));
+        let next = next_match(&matches, Cursor::new(1, 1)).unwrap();
+        assert_eq!(next.start, Cursor::new(0, 0));
+
+        let previous = previous_match(&matches, Cursor::new(0, 3)).unwrap();
+        assert_eq!(previous.start, Cursor::new(0, 0));
+        let previous = previous_match(&matches, Cursor::new(0, 1)).unwrap();
+        assert_eq!(previous.start, Cursor::new(1, 0));
+    }
+
+    #[test]
+    fn test_search_counter_label() {
+        assert_eq!(counter_label(Some(2), 12), "3 of 12");
+        assert_eq!(counter_label(None, 12), "12 matches");
+        assert_eq!(counter_label(None, 1), "1 match");
+        assert_eq!(counter_label(None, 0), "No results");
+    }
+}
@@ -0,0 +1,246 @@
+//! Spell checking of the words shown in the editor through a [`SpellProvider`].
+
+use std::{num::NonZero, ops::Range, sync::Arc};
+
+use glyphon::Cursor;
+use lru::LruCache;
+use tessera_ui::Color;
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::TextRange;
+
+/// Color of the underline of misspelled words.
+pub(super) const SPELLING_COLOR: Color = Color::new(0.9, 0.2, 0.2, 1.0);
+
+/// Most suggestions offered in the context menu for one word.
+pub(super) const MAX_SUGGESTIONS: usize = 5;
+
+/// Number of words whose spelling is remembered instead of asking the provider again.
+const CACHE_SIZE: usize = 4096;
+
+/// Checks the spelling of words, e.g. with a dictionary or a spell-check service.
+///
+/// The editor calls it with the words of the visible lines, remembering the answers, and
+/// underlines the misspelled ones. Right-clicking a misspelled word offers the suggestions
+/// in the context menu.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use tessera_ui::Dp;
+/// use tessera_ui_basic_components::text_editor::{SpellProvider, TextEditorState};
+///
+/// struct Words(Vec<&'static str>);
+///
+/// impl SpellProvider for Words {
+///     fn check(&self, word: &str) -> bool {
+///         self.0.contains(&word.to_lowercase().as_str())
+///     }
+///
+///     fn suggest(&self, word: &str) -> Vec<String> {
+///         let first = word.chars().next();
+///         self.0
+///             .iter()
+///             .filter(|known| known.chars().next() == first)
+///             .map(|known| known.to_string())
+///             .collect()
+///     }
+/// }
+///
+/// let mut state = TextEditorState::new(Dp(14.0), None);
+/// state.set_spell_provider(Some(Arc::new(Words(vec!["hello", "world"]))));
+/// ```
+pub trait SpellProvider: Send + Sync {
+    /// Returns `true` if `word` is spelled correctly.
+    fn check(&self, word: &str) -> bool;
+
+    /// Returns replacements for the misspelled `word`, best first.
+    fn suggest(&self, word: &str) -> Vec<String>;
+}
+
+/// A [`SpellProvider`] with the answers it gave before.
+pub(super) struct SpellChecker {
+    provider: Arc<dyn SpellProvider>,
+    known: LruCache<String, bool>,
+}
+
+impl SpellChecker {
+    pub(super) fn new(provider: Arc<dyn SpellProvider>) -> Self {
+        Self {
+            provider,
+            known: LruCache::new(NonZero::new(CACHE_SIZE).unwrap()),
+        }
+    }
+
+    pub(super) fn provider(&self) -> &Arc<dyn SpellProvider> {
+        &self.provider
+    }
+
+    /// Returns the misspelled words of `lines`, given with their line index.
+    pub(super) fn misspellings<'a>(
+        &mut self,
+        lines: impl IntoIterator<Item = (usize, &'a str)>,
+    ) -> Vec<TextRange> {
+        let mut misspellings = Vec::new();
+        for (line, text) in lines {
+            for range in words(text) {
+                let word = &text[range.clone()];
+                let correct = match self.known.get(word) {
+                    Some(&correct) => correct,
+                    None => {
+                        let correct = self.provider.check(word);
+                        self.known.put(word.to_string(), correct);
+                        correct
+                    }
+                };
+                if !correct {
+                    misspellings.push(TextRange {
+                        start: Cursor::new(line, range.start),
+                        end: Cursor::new(line, range.end),
+                    });
+                }
+            }
+        }
+        misspellings
+    }
+
+    /// Returns at most [`MAX_SUGGESTIONS`] replacements for `word`.
+    pub(super) fn suggest(&self, word: &str) -> Vec<String> {
+        let mut suggestions = self.provider.suggest(word);
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+}
+
+/// Returns the byte ranges of the words of `line` worth checking: words of letters and
+/// apostrophes, skipping identifiers such as `snake_case`, `camelCase` and `HTTP`.
+pub(super) fn words(line: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    line.unicode_word_indices()
+        .filter(|(_, word)| {
+            word.chars()
+                .all(|c| c.is_alphabetic() || c == '\'' || c == '’')
+                && !word.chars().skip(1).any(char::is_uppercase)
+        })
+        .map(|(start, word)| start..start + word.len())
+}
+
+#[cfg(feature = "hunspell")]
+mod hunspell {
+    use std::{io, path::Path};
+
+    use parking_lot::Mutex;
+
+    use super::SpellProvider;
+
+    /// A [`SpellProvider`] checking words with Hunspell dictionaries, as shipped with
+    /// LibreOffice and most Linux distributions.
+    pub struct HunspellProvider {
+        hunspell: Mutex<SendHunspell>,
+    }
+
+    /// A Hunspell instance that can move to the thread checking the spelling.
+    struct SendHunspell(hunspell_rs::Hunspell);
+
+    // SAFETY: `Hunspell` is only not `Send` because it holds a raw pointer to the C++
+    // instance, which has no ties to the thread that created it. The mutex of
+    // `HunspellProvider` makes sure only one thread calls into it at a time.
+    unsafe impl Send for SendHunspell {}
+
+    impl HunspellProvider {
+        /// Loads the dictionary with the affix file at `aff_path` and the word list at
+        /// `dic_path`, e.g. `/usr/share/hunspell/en_US.aff` and `en_US.dic`.
+        ///
+        /// Fails with [`io::ErrorKind::NotFound`] if either file does not exist, which
+        /// Hunspell itself would not report.
+        pub fn new(aff_path: &str, dic_path: &str) -> io::Result<Self> {
+            for path in [aff_path, dic_path] {
+                if !Path::new(path).is_file() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Hunspell dictionary file not found: {path}"),
+                    ));
+                }
+            }
+            let hunspell = hunspell_rs::Hunspell::new(aff_path, dic_path);
+            Ok(Self {
+                hunspell: Mutex::new(SendHunspell(hunspell)),
+            })
+        }
+    }
+
+    impl SpellProvider for HunspellProvider {
+        fn check(&self, word: &str) -> bool {
+            matches!(
+                self.hunspell.lock().0.check(word),
+                hunspell_rs::CheckResult::FoundInDictionary
+            )
+        }
+
+        fn suggest(&self, word: &str) -> Vec<String> {
+            self.hunspell.lock().0.suggest(word)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_missing_dictionary() {
+            let err = HunspellProvider::new("/nonexistent/en_US.aff", "/nonexistent/en_US.dic")
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+            assert!(err.to_string().contains("/nonexistent/en_US.aff"));
+        }
+    }
+}
+
+#[cfg(feature = "hunspell")]
+pub use hunspell::HunspellProvider;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Words;
+
+    impl SpellProvider for Words {
+        fn check(&self, word: &str) -> bool {
+            ["the", "quick", "fox", "isn't"].contains(&word.to_lowercase().as_str())
+        }
+
+        fn suggest(&self, word: &str) -> Vec<String> {
+            vec![word.replace("kk", "ck"); 8]
+        }
+    }
+
+    #[test]
+    fn test_words_skip_identifiers() {
+        let line = "The quikk_fox isn't parseJson HTTP 42 Fox";
+        let found: Vec<_> = words(line).map(|range| &line[range]).collect();
+        assert_eq!(found, ["The", "isn't", "Fox"]);
+    }
+
+    #[test]
+    fn test_misspellings() {
+        let mut checker = SpellChecker::new(Arc::new(Words));
+        let misspellings = checker.misspellings([(0, "the quikk fox"), (2, "Quikk")]);
+        assert_eq!(
+            misspellings,
+            [
+                TextRange {
+                    start: Cursor::new(0, 4),
+                    end: Cursor::new(0, 9),
+                },
+                TextRange {
+                    start: Cursor::new(2, 0),
+                    end: Cursor::new(2, 5),
+                },
+            ]
+        );
+        assert_eq!(checker.suggest("quikk").len(), MAX_SUGGESTIONS);
+        assert_eq!(checker.suggest("quikk")[0], "quick");
+    }
+}
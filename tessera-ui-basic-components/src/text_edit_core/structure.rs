@@ -0,0 +1,161 @@
+//! Growing a selection step by step over the structure around it: the word, the string, the
+//! contents of the enclosing brackets, the brackets themselves, the line and the whole text.
+
+use std::ops::Range;
+
+use super::is_word_char;
+
+/// Quote characters that start and end a string.
+const QUOTES: [char; 3] = ['"', '\'', '`'];
+
+/// Returns the smallest structural range of `text` that contains the byte `range` and is
+/// larger than it, or `None` if it already covers the whole text. Brackets are matched with
+/// `pairs`.
+pub(super) fn expand_range(
+    text: &str,
+    range: Range<usize>,
+    pairs: &[(char, char)],
+) -> Option<Range<usize>> {
+    let line = line_range(text, &range);
+    let strings = line
+        .as_ref()
+        .map(|line| string_ranges(text, line.clone(), &range))
+        .unwrap_or_default();
+    [word_range(text, &range), line]
+        .into_iter()
+        .flatten()
+        .chain(strings)
+        .chain(bracket_ranges(text, &range, pairs))
+        .chain(std::iter::once(0..text.len()))
+        .filter(|candidate| {
+            candidate.start <= range.start
+                && range.end <= candidate.end
+                && candidate.len() > range.len()
+        })
+        .min_by_key(|candidate| candidate.len())
+}
+
+/// The run of word characters around `range`, if it holds nothing else.
+fn word_range(text: &str, range: &Range<usize>) -> Option<Range<usize>> {
+    if !text[range.clone()].chars().all(is_word_char) {
+        return None;
+    }
+    let start = text[..range.start]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| is_word_char(c))
+        .last()
+        .map_or(range.start, |(index, _)| index);
+    let end = text[range.end..]
+        .char_indices()
+        .find(|&(_, c)| !is_word_char(c))
+        .map_or(text.len(), |(index, _)| range.end + index);
+    (start < end).then_some(start..end)
+}
+
+/// The line `range` is on without its indentation and line break, if it does not span lines.
+fn line_range(text: &str, range: &Range<usize>) -> Option<Range<usize>> {
+    if text[range.clone()].contains('\n') {
+        return None;
+    }
+    let start = text[..range.start].rfind('\n').map_or(0, |index| index + 1);
+    let end = text[range.end..]
+        .find('\n')
+        .map_or(text.len(), |index| range.end + index);
+    let line = text[start..end].trim_end_matches('\r');
+    let indent = line.len() - line.trim_start().len();
+    Some(start + indent..start + line.len())
+}
+
+/// The contents and the whole of the string on `line` that holds `range`, if any.
+fn string_ranges(text: &str, line: Range<usize>, range: &Range<usize>) -> Vec<Range<usize>> {
+    let mut open: Option<(usize, char)> = None;
+    let mut escaped = false;
+    for (index, c) in text[line.clone()].char_indices() {
+        let index = line.start + index;
+        match open {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some((start, quote)) if c == quote => {
+                if start < range.start && range.end <= index {
+                    return vec![start + 1..index, start..index + 1];
+                }
+                open = None;
+            }
+            None if QUOTES.contains(&c) => open = Some((index, c)),
+            _ => {}
+        }
+    }
+    Vec::new()
+}
+
+/// The contents and the whole of the innermost bracket pair around `range`, if any.
+fn bracket_ranges(text: &str, range: &Range<usize>, pairs: &[(char, char)]) -> Vec<Range<usize>> {
+    // Closers after an opener before the range belong to pairs next to the range
+    let mut closers = Vec::new();
+    for (index, c) in text[..range.start].char_indices().rev() {
+        if pairs.iter().any(|&(_, close)| close == c) {
+            closers.push(c);
+        } else if let Some(&(open, close)) = pairs.iter().find(|&&(open, _)| open == c) {
+            if closers.last() == Some(&close) {
+                closers.pop();
+                continue;
+            }
+            if let Some(end) = matching_close(text, index + open.len_utf8(), open, close)
+                && range.end <= end
+            {
+                return vec![index + open.len_utf8()..end, index..end + close.len_utf8()];
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// Returns the index of the `close` that ends the pair opened just before `from`.
+fn matching_close(text: &str, from: usize, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    for (index, c) in text[from..].char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                return Some(from + index);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_edit_core::DEFAULT_BRACKET_PAIRS;
+
+    #[test]
+    fn test_expand_range_steps() {
+        let text = "fn f() {\n    g(a, \"x y\");\n}";
+        let pairs = DEFAULT_BRACKET_PAIRS;
+        let at = |s: &str| text.find(s).unwrap();
+        let mut range = at("x")..at("x");
+        let mut steps = Vec::new();
+        while let Some(next) = expand_range(text, range.clone(), &pairs) {
+            steps.push(&text[next.clone()]);
+            range = next;
+        }
+        assert_eq!(
+            steps,
+            [
+                "x",
+                "x y",
+                "\"x y\"",
+                "a, \"x y\"",
+                "(a, \"x y\")",
+                "g(a, \"x y\");",
+                "\n    g(a, \"x y\");\n",
+                "{\n    g(a, \"x y\");\n}",
+                text,
+            ]
+        );
+    }
+}
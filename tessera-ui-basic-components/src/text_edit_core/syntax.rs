@@ -0,0 +1,241 @@
+//! Syntax highlighting with tree-sitter grammars.
+
+use std::{fmt, ops::Range, sync::Arc};
+
+use tessera_ui::Color;
+use tree_sitter::{InputEdit, Parser, Point, Query, QueryCursor, StreamingIterator, Tree};
+
+use super::change::changed_ranges;
+
+/// A tree-sitter grammar with its highlights query and the colors of the query's captures.
+///
+/// A capture takes the color of its whole name or, failing that, of its longest dotted
+/// prefix, so `"string"` also colors `@string.special`. Captures without a color are left
+/// in the text color. A node captured by several patterns takes the color of the first one,
+/// as the queries shipped with grammars expect, and nested nodes are drawn over their
+/// parents.
+///
+/// ```
+/// use tessera_ui::Color;
+/// use tessera_ui_basic_components::text_editor::SyntaxHighlighting;
+///
+/// let highlighting = SyntaxHighlighting::new(
+///     tree_sitter_json::LANGUAGE,
+///     tree_sitter_json::HIGHLIGHTS_QUERY,
+///     &[
+///         ("string", Color::from_rgb_u8(10, 120, 40)),
+///         ("number", Color::from_rgb_u8(20, 80, 200)),
+///     ],
+/// )
+/// .unwrap();
+/// ```
+pub struct SyntaxHighlighting {
+    language: tree_sitter::Language,
+    query: Query,
+    colors: Vec<Option<Color>>,
+}
+
+impl SyntaxHighlighting {
+    /// Compiles `highlights_query` for `language`, coloring its captures from `theme`.
+    pub fn new(
+        language: impl Into<tree_sitter::Language>,
+        highlights_query: &str,
+        theme: &[(&str, Color)],
+    ) -> Result<Self, tree_sitter::QueryError> {
+        let language = language.into();
+        let query = Query::new(&language, highlights_query)?;
+        let colors = query
+            .capture_names()
+            .iter()
+            .map(|name| capture_color(name, theme))
+            .collect();
+        Ok(Self {
+            language,
+            query,
+            colors,
+        })
+    }
+}
+
+impl fmt::Debug for SyntaxHighlighting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyntaxHighlighting")
+            .field("language", &self.language.name())
+            .field("captures", &self.query.capture_names())
+            .finish()
+    }
+}
+
+/// Returns the color of the capture `name`, trying shorter dotted prefixes until one is in
+/// `theme`.
+fn capture_color(name: &str, theme: &[(&str, Color)]) -> Option<Color> {
+    let mut name = name;
+    loop {
+        if let Some(&(_, color)) = theme.iter().find(|(key, _)| *key == name) {
+            return Some(color);
+        }
+        name = &name[..name.rfind('.')?];
+    }
+}
+
+/// The parse tree of the editor content, updated incrementally as it is edited.
+pub(crate) struct SyntaxTree {
+    highlighting: Arc<SyntaxHighlighting>,
+    parser: Parser,
+    tree: Option<Tree>,
+    /// The content that was parsed.
+    text: String,
+    /// Byte offset of the start of each buffer line in `text`.
+    line_starts: Vec<usize>,
+}
+
+impl SyntaxTree {
+    pub(crate) fn new(highlighting: Arc<SyntaxHighlighting>) -> Self {
+        let mut parser = Parser::new();
+        if let Err(err) = parser.set_language(&highlighting.language) {
+            tracing::warn!("Cannot highlight with this grammar: {err}");
+        }
+        Self {
+            highlighting,
+            parser,
+            tree: None,
+            text: String::new(),
+            line_starts: Vec::new(),
+        }
+    }
+
+    pub(crate) fn highlighting(&self) -> &Arc<SyntaxHighlighting> {
+        &self.highlighting
+    }
+
+    /// Parses the content of `buffer` if it changed, reusing the unchanged parts of the
+    /// previous tree.
+    pub(crate) fn update(&mut self, buffer: &glyphon::Buffer) {
+        let mut text = String::with_capacity(self.text.len());
+        let mut line_starts = Vec::with_capacity(buffer.lines.len());
+        for line in &buffer.lines {
+            line_starts.push(text.len());
+            text.push_str(line.text());
+            text.push_str(line.ending().as_str());
+        }
+        self.parse(text, line_starts);
+    }
+
+    /// Parses `text` if it changed, taking each of its `\n` separated lines as a buffer
+    /// line, for content that is not edited in a buffer.
+    pub(crate) fn update_text(&mut self, text: &str) {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        self.parse(text.to_string(), line_starts);
+    }
+
+    fn parse(&mut self, text: String, line_starts: Vec<usize>) {
+        if self.tree.is_some() && text == self.text {
+            return;
+        }
+        if let Some(tree) = &mut self.tree {
+            let (old, new) = changed_ranges(&self.text, &text);
+            tree.edit(&InputEdit {
+                start_byte: old.start,
+                old_end_byte: old.end,
+                new_end_byte: new.end,
+                start_position: point_at(&self.text, old.start),
+                old_end_position: point_at(&self.text, old.end),
+                new_end_position: point_at(&text, new.end),
+            });
+        }
+        self.tree = self.parser.parse(&text, self.tree.as_ref());
+        self.text = text;
+        self.line_starts = line_starts;
+    }
+
+    /// Returns the colored byte ranges of buffer line `line`, relative to its start.
+    pub(crate) fn line_spans(&self, line: usize) -> Vec<(Range<usize>, Color)> {
+        let (Some(tree), Some(&start)) = (&self.tree, self.line_starts.get(line)) else {
+            return Vec::new();
+        };
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        let text = &self.text[start..end];
+        let len = text.trim_end_matches(['\r', '\n']).len();
+
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(start..end);
+        let query = &self.highlighting.query;
+        let mut captures = cursor.captures(query, tree.root_node(), self.text.as_bytes());
+        let mut spans = Vec::new();
+        let mut colored_node = None;
+        while let Some((found, index)) = captures.next() {
+            let capture = found.captures[*index];
+            let Some(color) = self.highlighting.colors[capture.index as usize] else {
+                continue;
+            };
+            if colored_node.replace(capture.node.id()) == Some(capture.node.id()) {
+                continue;
+            }
+            let range = capture.node.byte_range();
+            let range = range.start.max(start) - start..range.end.min(start + len) - start;
+            if range.start < range.end {
+                spans.push((range, color));
+            }
+        }
+        spans
+    }
+}
+
+/// Returns the row and byte column of `offset` in `text`.
+fn point_at(text: &str, offset: usize) -> Point {
+    let before = &text[..offset];
+    let row = before.matches('\n').count();
+    let column = before.rfind('\n').map_or(offset, |i| offset - i - 1);
+    Point { row, column }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipelines::write_font_system;
+
+    #[test]
+    fn test_line_spans() {
+        let number = Color::from_rgb_u8(0, 0, 255);
+        let key = Color::from_rgb_u8(255, 0, 0);
+        let highlighting = SyntaxHighlighting::new(
+            tree_sitter_json::LANGUAGE,
+            tree_sitter_json::HIGHLIGHTS_QUERY,
+            &[
+                ("number", number),
+                ("string.special", key),
+                ("string", Color::BLACK),
+            ],
+        )
+        .unwrap();
+        let mut syntax = SyntaxTree::new(Arc::new(highlighting));
+        let font_system = &mut write_font_system();
+        let mut buffer = glyphon::Buffer::new(font_system, glyphon::Metrics::new(14.0, 18.0));
+        let attrs = glyphon::Attrs::new();
+        let shaping = glyphon::Shaping::Advanced;
+
+        buffer.set_text(font_system, "{\n  \"a\": 1\n}", &attrs, shaping, None);
+        syntax.update(&buffer);
+        assert_eq!(syntax.line_spans(0), vec![]);
+        assert_eq!(syntax.line_spans(1), vec![(2..5, key), (7..8, number)]);
+
+        // An edit in the middle is applied to the old tree before reparsing
+        buffer.set_text(
+            font_system,
+            "{\n  \"a\": 12,\n  \"b\": 3\n}",
+            &attrs,
+            shaping,
+            None,
+        );
+        syntax.update(&buffer);
+        assert_eq!(syntax.line_spans(1), vec![(2..5, key), (7..9, number)]);
+        assert_eq!(syntax.line_spans(2), vec![(2..5, key), (7..8, number)]);
+        assert_eq!(point_at("ab\ncd", 4), Point { row: 1, column: 1 });
+    }
+}
@@ -0,0 +1,210 @@
+//! Layout of long documents, where only the lines around the visible area are shaped.
+
+use std::ops::Range;
+
+use crate::{pipelines::write_font_system, text_edit_core::fold};
+
+/// Lines above and below the visible area that are styled and shaped ahead of scrolling.
+const SHAPING_MARGIN: usize = 32;
+
+/// Returns the lines around the visible area of `buffer` that are kept styled and shaped.
+///
+/// An editor without a height limit shows all of its lines, so they are all in the window.
+pub(super) fn shaped_window(buffer: &glyphon::Buffer) -> Range<usize> {
+    let line_height = buffer.metrics().line_height;
+    let rows = buffer.size().1.map_or(buffer.lines.len(), |height| {
+        (height / line_height).ceil() as usize
+    });
+    let scroll_line = buffer.scroll().line;
+    let first = scroll_line.saturating_sub(SHAPING_MARGIN);
+    let end = scroll_line
+        .saturating_add(rows + 1 + SHAPING_MARGIN)
+        .min(buffer.lines.len());
+    first..end.max(first)
+}
+
+/// Returns the height of every line of `buffer`, in pixels.
+///
+/// Lines in `window` are laid out. The others keep the height of their last layout, or get an
+/// estimate from their length if they never had one, so opening a long document does not
+/// shape all of it. Lines hidden by `folds` take no space.
+pub(super) fn line_heights(
+    buffer: &mut glyphon::Buffer,
+    window: Range<usize>,
+    folds: &[(usize, usize)],
+) -> Vec<f32> {
+    let font_system = &mut write_font_system();
+    let line_height = buffer.metrics().line_height;
+    let layout_height = |layout: &[glyphon::cosmic_text::LayoutLine]| -> f32 {
+        layout
+            .iter()
+            .map(|line| line.line_height_opt.unwrap_or(line_height))
+            .sum()
+    };
+
+    let mut heights = vec![0.0; buffer.lines.len()];
+    let (mut width, mut glyphs) = (0.0, 0);
+    for line_i in window.clone() {
+        if let Some(layout) = buffer.line_layout(font_system, line_i) {
+            heights[line_i] = layout_height(layout);
+            width += layout.iter().map(|line| line.w).sum::<f32>();
+            glyphs += layout.iter().map(|line| line.glyphs.len()).sum::<usize>();
+        }
+    }
+
+    // Lines wrap after about as many characters as fit the width, at the average glyph width
+    // of the shaped lines
+    let chars_per_row = match (buffer.wrap(), buffer.size().0) {
+        (glyphon::Wrap::None, _) | (_, None) => None,
+        (_, Some(max_width)) => {
+            let average = if glyphs > 0 {
+                width / glyphs as f32
+            } else {
+                buffer.metrics().font_size / 2.0
+            };
+            Some((max_width / average.max(1.0)).max(1.0))
+        }
+    };
+    for (line_i, line) in buffer.lines.iter().enumerate() {
+        if window.contains(&line_i) {
+            continue;
+        }
+        heights[line_i] = if fold::is_hidden(folds, line_i) {
+            0.0
+        } else if let Some(layout) = line.layout_opt() {
+            layout_height(layout)
+        } else {
+            estimated_rows(line.text().chars().count(), chars_per_row) * line_height
+        };
+    }
+    heights
+}
+
+/// Returns the number of rows a line of `chars` characters wraps into, at `chars_per_row`
+/// characters per row or without wrapping.
+fn estimated_rows(chars: usize, chars_per_row: Option<f32>) -> f32 {
+    chars_per_row.map_or(1.0, |per_row| (chars as f32 / per_row).ceil().max(1.0))
+}
+
+/// Returns the line containing `offset` pixels from the top of the content and how far into
+/// that line it is.
+pub(super) fn line_at_offset(heights: &[f32], offset: f32) -> (usize, f32) {
+    let mut top = 0.0;
+    for (line_i, &height) in heights.iter().enumerate() {
+        if offset < top + height {
+            return (line_i, offset - top);
+        }
+        top += height;
+    }
+    // Past the end the last line is scrolled to its bottom
+    let last = heights.len().saturating_sub(1);
+    (last, heights.last().copied().unwrap_or(0.0))
+}
+
+/// Scrolls `buffer` the least needed to show the row of `cursor` at least `margin` lines
+/// away from the top and bottom of the view, or centered if the view is too short for that.
+pub(super) fn follow_cursor(buffer: &mut glyphon::Buffer, cursor: glyphon::Cursor, margin: usize) {
+    // Without a height limit every line is shown
+    let Some(view_height) = buffer.size().1 else {
+        return;
+    };
+    let font_system = &mut write_font_system();
+    let line_height = buffer.metrics().line_height;
+    let Some(layout_cursor) = buffer.layout_cursor(font_system, cursor) else {
+        return;
+    };
+    let row_heights: Vec<f32> = buffer
+        .line_layout(font_system, cursor.line)
+        .unwrap_or_default()
+        .iter()
+        .map(|row| row.line_height_opt.unwrap_or(line_height))
+        .collect();
+    let row_y: f32 = row_heights.iter().take(layout_cursor.layout).sum();
+    let row_height = row_heights
+        .get(layout_cursor.layout)
+        .copied()
+        .unwrap_or(line_height);
+
+    let mut scroll = buffer.scroll();
+    let top = if cursor.line < scroll.line {
+        f32::NEG_INFINITY
+    } else {
+        let mut top = row_y - scroll.vertical;
+        for line_i in scroll.line..cursor.line {
+            // Further down only the direction matters, so far jumps do not shape every line
+            if top > view_height {
+                break;
+            }
+            top += buffer
+                .line_layout(font_system, line_i)
+                .unwrap_or_default()
+                .iter()
+                .map(|row| row.line_height_opt.unwrap_or(line_height))
+                .sum::<f32>();
+        }
+        top
+    };
+    let margin = margin as f32 * line_height;
+    if let Some(offset) = follow_offset(row_y, top, row_height, view_height, margin) {
+        // Shaping moves the scroll back into the content and onto the line it lands on
+        scroll.line = cursor.line;
+        scroll.vertical = offset;
+        buffer.set_scroll(scroll);
+    }
+}
+
+/// Returns the scroll offset into its line that brings a row `row_y` pixels into the line,
+/// `top` pixels below the top of the view and `row_height` high, `margin` pixels inside a view
+/// of `view_height`, or `None` if it already is.
+fn follow_offset(
+    row_y: f32,
+    top: f32,
+    row_height: f32,
+    view_height: f32,
+    margin: f32,
+) -> Option<f32> {
+    let margin = margin.min(((view_height - row_height) / 2.0).max(0.0));
+    if top < margin {
+        Some(row_y - margin)
+    } else if top + row_height > view_height - margin {
+        Some(row_y + row_height + margin - view_height)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_layout_estimates() {
+        assert_eq!(estimated_rows(500, None), 1.0);
+        assert_eq!(estimated_rows(0, Some(80.0)), 1.0);
+        assert_eq!(estimated_rows(161, Some(80.0)), 3.0);
+
+        // Folded lines have no height and are skipped over
+        let heights = [20.0, 0.0, 0.0, 40.0, 20.0];
+        assert_eq!(line_at_offset(&heights, 0.0), (0, 0.0));
+        assert_eq!(line_at_offset(&heights, 25.0), (3, 5.0));
+        assert_eq!(line_at_offset(&heights, 75.0), (4, 15.0));
+        assert_eq!(line_at_offset(&heights, 500.0), (4, 20.0));
+        assert_eq!(line_at_offset(&[], 10.0), (0, 0.0));
+    }
+
+    #[test]
+    fn test_follow_offset() {
+        // A 100 pixel view with rows of 10 pixels and a margin of 2 rows
+        assert_eq!(follow_offset(0.0, 40.0, 10.0, 100.0, 20.0), None);
+        assert_eq!(follow_offset(0.0, 20.0, 10.0, 100.0, 20.0), None);
+        assert_eq!(follow_offset(30.0, 10.0, 10.0, 100.0, 20.0), Some(10.0));
+        assert_eq!(
+            follow_offset(0.0, f32::NEG_INFINITY, 10.0, 100.0, 20.0),
+            Some(-20.0)
+        );
+        assert_eq!(follow_offset(10.0, 75.0, 10.0, 100.0, 20.0), Some(-60.0));
+        // A margin larger than half the view centers the row
+        assert_eq!(follow_offset(0.0, 0.0, 10.0, 100.0, 90.0), Some(-45.0));
+        assert_eq!(follow_offset(0.0, 45.0, 10.0, 100.0, 90.0), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Marks drawn over whitespace: dots for spaces, arrows for tabs and pilcrows at line ends.
+
+use tessera_ui::Px;
+
+use super::{RectDef, fold, lens_row};
+
+/// A kind of whitespace that gets a mark.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mark {
+    Space,
+    Tab,
+    LineEnd,
+}
+
+/// Returns the rects of every whitespace mark in the laid out `buffer`, except on lines hidden
+/// by `folds`.
+pub(super) fn whitespace_rects(buffer: &glyphon::Buffer, folds: &[(usize, usize)]) -> Vec<RectDef> {
+    let mut rects = Vec::new();
+    let buffer_line_height = buffer.metrics().line_height;
+    let mut runs = buffer.layout_runs().peekable();
+    while let Some(run) = runs.next() {
+        if fold::is_hidden(folds, run.line_i) {
+            continue;
+        }
+        let (line_top, line_height) = lens_row::text_row(&run, buffer_line_height);
+        for glyph in run.glyphs {
+            let cluster = &run.text[glyph.start..glyph.end];
+            let chars = cluster.chars().count().max(1);
+            // A cluster of several spaces is shaped as one glyph, each of them gets a share
+            let advance = glyph.w / chars as f32;
+            for (i, c) in cluster.chars().enumerate() {
+                let mark = match c {
+                    ' ' => Mark::Space,
+                    '\t' => Mark::Tab,
+                    _ => continue,
+                };
+                let x = glyph.x + advance * i as f32;
+                rects.extend(mark_rects(mark, x, advance, line_top, line_height));
+            }
+        }
+        // Only the last row of a wrapped line ends it
+        let last_row = runs.peek().is_none_or(|next| next.line_i != run.line_i);
+        let has_ending =
+            buffer.lines[run.line_i].ending() != glyphon::cosmic_text::LineEnding::None;
+        if last_row && has_ending {
+            rects.extend(mark_rects(
+                Mark::LineEnd,
+                run.line_w,
+                0.0,
+                line_top,
+                line_height,
+            ));
+        }
+    }
+    rects
+}
+
+/// Returns the rects drawing `mark` over the whitespace at `x` that is `width` wide, on the
+/// row at `line_top`.
+fn mark_rects(mark: Mark, x: f32, width: f32, line_top: f32, line_height: f32) -> Vec<RectDef> {
+    // The marks are built from squares of a size that grows with the font
+    let dot = (line_height / 12.0).round().max(1.0);
+    let middle = line_top + line_height / 2.0;
+    let rect = |x: f32, y: f32, width: f32, height: f32| RectDef {
+        x: Px(x.round() as i32),
+        y: Px(y.round() as i32),
+        width: Px(width.round() as i32),
+        height: Px(height.round() as i32),
+    };
+    match mark {
+        Mark::Space => vec![rect(x + (width - dot) / 2.0, middle - dot / 2.0, dot, dot)],
+        Mark::Tab => {
+            // A shaft ending in a bar, like ⇥
+            let (start, end) = (x + dot, x + (width - 2.0 * dot).max(2.0 * dot));
+            vec![
+                rect(start, middle - dot / 2.0, end - start, dot),
+                rect(end, middle - 1.5 * dot, dot, 3.0 * dot),
+            ]
+        }
+        Mark::LineEnd => {
+            // A bowl on the left of a stem, like ¶
+            let (left, top) = (x + dot, middle - 3.0 * dot);
+            vec![
+                rect(left, top, 2.0 * dot, 2.0 * dot),
+                rect(left + 2.0 * dot, top, dot, 6.0 * dot),
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> RectDef {
+        RectDef {
+            x: Px(x),
+            y: Px(y),
+            width: Px(width),
+            height: Px(height),
+        }
+    }
+
+    #[test]
+    fn test_whitespace_mark_rects() {
+        let space = mark_rects(Mark::Space, 10.0, 8.0, 0.0, 24.0);
+        assert_eq!(space, [rect(13, 11, 2, 2)]);
+
+        let tab = mark_rects(Mark::Tab, 0.0, 32.0, 24.0, 24.0);
+        assert_eq!(tab, [rect(2, 35, 26, 2), rect(28, 33, 2, 6)]);
+        // A narrow tab still gets an arrow
+        let tab = mark_rects(Mark::Tab, 0.0, 2.0, 0.0, 24.0);
+        assert!(tab[0].width > Px(0));
+
+        let line_end = mark_rects(Mark::LineEnd, 40.0, 0.0, 0.0, 24.0);
+        assert_eq!(line_end, [rect(42, 6, 4, 4), rect(46, 6, 2, 12)]);
+    }
+}
@@ -6,31 +6,109 @@
 //! # Features
 //! - Unicode multi-line text editing
 //! - Full cursor and selection management (mouse, keyboard, drag, double/triple click)
-//! - IME/preedit support for CJK and complex input
+//! - IME support for CJK and complex input, with the composed text underlined at the caret
+//!   until it is committed
 //! - Customizable appearance (background, border, shape, padding, selection color)
+//! - Placeholder text shown while the editor is empty and unfocused
+//! - A single-line text field mode that submits on Enter via [`TextEditorArgs::on_submit`]
 //! - Focus management and event handling
-//! - Scroll support via mouse wheel or keyboard
+//! - Shift+click and Shift with the arrow keys, Home/End or PageUp/PageDown extend the
+//!   selection
+//! - Scroll support via mouse wheel or keyboard, with optional draggable scrollbars
+//! - Undo/redo history (Ctrl+Z, Ctrl+Shift+Z / Ctrl+Y)
+//! - Find/replace with highlighted matches via [`TextEditorState::find`], a match counter and
+//!   F3/Shift+F3 to step through the matches
+//! - Moving the cursor, selecting and scrolling from code, e.g.
+//!   [`TextEditorState::select_range`] and [`TextEditorState::scroll_to_line`]
+//! - Multiple carets (Ctrl+click to add a caret, Ctrl+D to add the next occurrence)
+//! - Block selection with Alt+drag, typing on every selected row
+//! - Glyph or word wrapping, or no wrapping with horizontal scrolling
+//! - Highlighting of the bracket matching the one next to the cursor
+//! - Optional highlight of the line containing the cursor
+//! - Optional auto-indent of new lines
+//! - Optional auto-closing of brackets and quotes via [`TextEditorArgs::auto_close`]
+//! - Tab/Shift+Tab indent and dedent the selected lines, with configurable tab width
+//! - Ctrl+Shift+D duplicates the line or selection, Alt+Up/Alt+Down move the selected lines
+//! - Ctrl+/ comments or uncomments the selected lines via [`TextEditorArgs::line_comment`],
+//!   by default with the comment token of the language detected from
+//!   [`TextEditorArgs::file_name`] or the first line
+//! - Zoom with Ctrl+wheel or Ctrl+=/Ctrl+-, reset with Ctrl+0
+//! - Overwrite mode toggled with the Insert key, shown as a block cursor
+//! - Squiggly underlines for diagnostics via [`TextEditorState::set_diagnostics`], and for
+//!   misspelled words via [`TextEditorState::set_spell_provider`] with suggestions in the
+//!   right-click menu
+//! - Inlay hints such as inferred types or parameter names via
+//!   [`TextEditorState::set_inlay_hints`], drawn inline but never part of the content
+//! - Rows of app components between the lines, such as code lens actions or inline blame,
+//!   via [`TextEditorArgs::lens_rows`]
+//! - A completion popup filled by [`TextEditorArgs::on_complete`]
+//! - A right-click menu with Cut/Copy/Paste/Select All and
+//!   [`TextEditorArgs::context_menu_items`]
+//! - Ctrl+click on URLs and file paths via [`TextEditorArgs::on_link_activate`], and on
+//!   issue numbers or other [`TextEditorArgs::link_patterns`]
+//! - Column rulers, optionally recoloring the text past the first one
+//! - Optional marks for spaces, tabs and line endings via [`TextEditorArgs::show_whitespace`]
+//! - Folding of indented blocks (Ctrl+Shift+[ / Ctrl+Shift+]) or any line range via
+//!   [`TextEditorState::fold_lines`], previewing the hidden lines when hovering a fold marker
+//! - A gutter with per-line marks and clicks via [`TextEditorArgs::annotations`]
+//! - Breakpoints toggled in the gutter via [`TextEditorArgs::breakpoints`], and the line a
+//!   debugger stopped at via [`TextEditorState::set_execution_line`]
+//! - Line numbers via [`TextEditorArgs::line_numbers`], with the continuation rows of
+//!   wrapped lines marked in the gutter instead of numbered
+//! - Growing the selection over the word, string, brackets and line around it and back
+//!   (Alt+Shift+Right / Alt+Shift+Left)
+//! - A Ctrl+G popup to jump to a `line[:column]` via [`TextEditorArgs::go_to_line`], or
+//!   [`TextEditorState::scroll_and_place_cursor_at_line`] from code
+//! - The view following the caret with a margin of lines around it via
+//!   [`TextEditorArgs::scroll_margin`]
+//! - Going back and forward through the caret jumps (Alt+Left / Alt+Right) via
+//!   [`TextEditorState::jump_back`] and [`TextEditorState::record_jump`]
+//! - Reading the selection or the cursor line aloud with [`EditorCommand::Speak`], bound in
+//!   [`TextEditorArgs::keymap`], with the `tts` feature
+//! - Copying with the text colors kept for rich text targets via
+//!   [`TextEditorArgs::copy_as_html`]
+//! - Syntax highlighting with tree-sitter grammars via [`TextEditorArgs::syntax_highlighting`],
+//!   with the `tree-sitter` feature
+//! - Light and dark color schemes via [`TextEditorArgs::color_scheme`], filling in the
+//!   colors left unset
+//! - Configurable shortcuts via [`TextEditorArgs::keymap`], with the Cmd-based macOS ones by
+//!   default on macOS
 //!
 //! # Usage
 //! The editor state is managed externally via [`TextEditorState`] (typically wrapped in `Arc<RwLock<...>>`).
 //! The [`text_editor`] component can be configured using [`TextEditorArgs`] for layout and appearance customization.
 //!
 //! Typical use cases include form inputs, code editors, chat boxes, and any scenario requiring rich text input within a Tessera UI application.
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use derive_builder::Builder;
-use glyphon::{Action, Edit};
+use glyphon::Edit;
 use parking_lot::RwLock;
 use tessera_ui::{
-    Color, CursorEventContent, DimensionValue, Dp, ImeRequest, Px, PxPosition, tessera, winit,
+    Color, ComputedData, Constraint, DimensionValue, Dp, InputHandlerInput, Px, PxPosition,
+    tessera, theme,
 };
 
 use crate::{
-    pipelines::write_font_system,
+    code_block::{CodeBlockArgsBuilder, code_block},
+    column::{ColumnArgs, column},
+    input_mask::InputMask,
+    pipelines::ShadowProps,
+    pipelines::{ShapeCommand, TextCommand, TextConstraint, TextData},
     pos_misc::is_position_in_component,
+    scrollable::{
+        ScrollBarBehavior,
+        scrollbar::{ScrollBarArgs, ScrollBarState, scrollbar_h, scrollbar_v},
+    },
+    selection_highlight_rect::selection_highlight_rect,
     shape_def::Shape,
-    surface::{SurfaceArgsBuilder, surface},
-    text_edit_core::{ClickType, text_edit_core},
+    surface::{SurfaceArgsBuilder, SurfaceStyle, surface},
+    text::{TextArgsBuilder, text},
+    text_edit_core::{
+        ContextMenuEntry, DEFAULT_BRACKET_PAIRS, DEFAULT_BREAKPOINT_COLOR,
+        DEFAULT_CONDITIONAL_BREAKPOINT_COLOR, DEFAULT_INDENT_AFTER, LINE_NUMBER_GAP,
+        default_selection_color, text_edit_core,
+    },
 };
 
 /// State structure for the text editor, managing text content, cursor, selection, and editing logic.
@@ -48,6 +126,67 @@ use crate::{
 /// ```
 pub use crate::text_edit_core::TextEditorState;
 
+/// OpenType features of a [`text_editor`], see [`TextEditorArgs::font_features`].
+pub use crate::pipelines::FontFeatures;
+/// A breakpoint in the gutter, see [`TextEditorArgs::breakpoints`].
+pub use crate::text_edit_core::Breakpoint;
+/// A caret in addition to the primary cursor of a [`TextEditorState`].
+pub use crate::text_edit_core::Caret;
+/// An entry of the completion popup, see [`TextEditorArgs::on_complete`].
+pub use crate::text_edit_core::CompletionItem;
+/// The typed prefix passed to [`TextEditorArgs::on_complete`].
+pub use crate::text_edit_core::CompletionRequest;
+/// An entry added to the right-click menu, see [`TextEditorArgs::context_menu_items`].
+pub use crate::text_edit_core::ContextMenuItem;
+/// A message underlined in a [`TextEditorState`], see [`TextEditorState::set_diagnostics`].
+pub use crate::text_edit_core::Diagnostic;
+/// How serious a [`Diagnostic`] is.
+pub use crate::text_edit_core::DiagnosticSeverity;
+/// The colors of a [`text_editor`] from a light or dark theme, see
+/// [`TextEditorArgs::color_scheme`].
+pub use crate::text_edit_core::EditorColorScheme;
+/// A command bound in an [`EditorKeymap`].
+pub use crate::text_edit_core::EditorCommand;
+/// The key bindings of a [`text_editor`], see [`TextEditorArgs::keymap`].
+pub use crate::text_edit_core::EditorKeymap;
+/// The font family of a [`text_editor`], see [`TextEditorArgs::font_family`].
+pub use crate::text_edit_core::FontFamily;
+/// A [`SpellProvider`] backed by Hunspell dictionaries.
+#[cfg(feature = "hunspell")]
+pub use crate::text_edit_core::HunspellProvider;
+/// A label shown inline in the content, see [`TextEditorState::set_inlay_hints`].
+pub use crate::text_edit_core::InlayHint;
+/// A row of components shown above a line, see [`TextEditorArgs::lens_rows`].
+pub use crate::text_edit_core::LensRow;
+/// A kind of link such as issue numbers, see [`TextEditorArgs::link_patterns`].
+pub use crate::text_edit_core::LinkPattern;
+/// Clean-up applied by [`TextEditorState::prepare_save`].
+pub use crate::text_edit_core::SaveOptions;
+/// Checks the spelling of the visible words, see [`TextEditorState::set_spell_provider`].
+pub use crate::text_edit_core::SpellProvider;
+/// A grammar and colors for [`TextEditorArgs::syntax_highlighting`].
+#[cfg(feature = "tree-sitter")]
+pub use crate::text_edit_core::SyntaxHighlighting;
+/// An edit of the content passed to [`TextEditorArgs::on_change`].
+pub use crate::text_edit_core::TextChange;
+/// A line and column in a [`TextEditorState`], as returned by [`TextEditorState::caret`].
+pub use crate::text_edit_core::TextPosition;
+/// A range of text in a [`TextEditorState`], as returned by [`TextEditorState::find`].
+pub use crate::text_edit_core::TextRange;
+/// A laid out row of a [`text_editor`], see [`TextEditorState::visual_rows`].
+pub use crate::text_edit_core::VisualRow;
+/// How a [`text_editor`] breaks long lines, see [`TextEditorArgs::wrap`].
+pub use crate::text_edit_core::WrapMode;
+/// The input handling of a [`text_editor`], for other editor components.
+pub use crate::text_edit_core::{
+    EditorInputConfig, EditorInputConfigBuilder, EditorInputHook, EditorKeyHook,
+    editor_input_handler,
+};
+/// A mark in the gutter, see [`TextEditorArgs::annotations`].
+pub use crate::text_edit_core::{GutterAnnotation, GutterMark};
+/// A language detected from a file name or first line, see [`TextEditorArgs::file_name`].
+pub use crate::text_edit_core::{LANGUAGES, Language};
+
 /// Arguments for configuring the [`text_editor`] component.
 ///
 /// `TextEditorArgs` provides flexible options for layout, appearance, and interaction of the text editor.
@@ -86,18 +225,25 @@ pub struct TextEditorArgs {
     /// Height constraint for the text editor. Defaults to `Wrap`.
     #[builder(default = "DimensionValue::WRAP", setter(into))]
     pub height: DimensionValue,
-    /// Called when the text content changes. The closure receives the new text content and returns the updated content.
+    /// Called when the text content changes, with the replaced range and the inserted text.
+    /// Return `None` to accept the change, or the content to show instead, e.g. with rejected
+    /// characters removed. Only a returned content resets the text buffer.
     ///
-    /// For default, it is a no-op that returns an empty string. Which means the text editor will not accept any input.
-    #[builder(default = "Arc::new(|_| { String::new() })")]
-    pub on_change: Arc<dyn Fn(String) -> String + Send + Sync>,
+    /// Defaults to accepting every change.
+    #[builder(default = "Arc::new(|_| None)")]
+    pub on_change: Arc<dyn Fn(TextChange) -> Option<String> + Send + Sync>,
+    /// Fills [`TextChange::text`] with the whole content for [`Self::on_change`], which
+    /// copies the content on every edit. Defaults to `false`.
+    #[builder(default = "false")]
+    pub change_text: bool,
     /// Minimum width in density-independent pixels. Defaults to 120dp if not specified.
     #[builder(default = "None")]
     pub min_width: Option<Dp>,
     /// Minimum height in density-independent pixels. Defaults to line height + padding if not specified.
     #[builder(default = "None")]
     pub min_height: Option<Dp>,
-    /// Background color of the text editor (RGBA). Defaults to light gray.
+    /// Background color of the text editor (RGBA). Defaults to the background of
+    /// [`Self::color_scheme`], or light gray without one.
     #[builder(default = "None")]
     pub background_color: Option<Color>,
     /// Border width in Dp. Defaults to 1.0 Dp.
@@ -118,15 +264,290 @@ pub struct TextEditorArgs {
     /// Padding inside the text editor. Defaults to 5.0 Dp.
     #[builder(default = "Dp(5.0)")]
     pub padding: Dp,
-    /// Border color when focused (RGBA). Defaults to blue.
+    /// Border color when focused (RGBA). Defaults to the
+    /// [accent color](tessera_ui::theme::accent_color), or blue without one.
     #[builder(default = "None")]
     pub focus_border_color: Option<Color>,
-    /// Background color when focused (RGBA). Defaults to white.
+    /// Background color when focused (RGBA). Defaults to [`Self::background_color`], then
+    /// the background of [`Self::color_scheme`], or white without either.
     #[builder(default = "None")]
     pub focus_background_color: Option<Color>,
-    /// Color for text selection highlight (RGBA). Defaults to light blue with transparency.
-    #[builder(default = "Some(Color::new(0.5, 0.7, 1.0, 0.4))")]
+    /// Time the border and background take to change when the editor gains or loses focus.
+    /// Defaults to `None`, which switches at once.
+    #[builder(default = "None")]
+    pub focus_transition: Option<Duration>,
+    /// Color for text selection highlight (RGBA). Defaults to the selection of
+    /// [`Self::color_scheme`], or the translucent
+    /// [accent color](tessera_ui::theme::accent_color) without one.
+    #[builder(default = "None")]
     pub selection_color: Option<Color>,
+    /// Colors for the background, text, caret, selection and current line that the other
+    /// color arguments leave unset, e.g. [`EditorColorScheme::dark`] for a dark theme.
+    /// Defaults to `None`, which keeps black text on a light background.
+    #[builder(default = "None")]
+    pub color_scheme: Option<EditorColorScheme>,
+    /// Color for highlighting search matches (RGBA). Defaults to translucent yellow.
+    #[builder(default = "Some(Color::new(1.0, 0.8, 0.0, 0.35))")]
+    pub search_highlight_color: Option<Color>,
+    /// Color for highlighting the selected search match (RGBA). Defaults to translucent
+    /// orange.
+    #[builder(default = "Some(Color::new(1.0, 0.55, 0.0, 0.5))")]
+    pub current_match_color: Option<Color>,
+    /// Shows the position of the selected match among all matches, e.g. `3 of 12`, in the top
+    /// right corner while a search is active. Defaults to `true`.
+    #[builder(default = "true")]
+    pub show_search_counter: bool,
+    /// Formats the content as the user types, e.g. [`InputMask::phone`]. Intended for single-line input.
+    /// The unformatted value is available via [`TextEditorState::raw_text`]. Defaults to no mask.
+    #[builder(default = "None")]
+    pub input_mask: Option<InputMask>,
+    /// Hint shown in place of the content while the editor is empty and not focused. It is
+    /// only drawn, never part of the content. Defaults to `None`.
+    #[builder(default = "None")]
+    pub placeholder: Option<String>,
+    /// Color of the placeholder. Defaults to gray.
+    #[builder(default = "Color::new(0.55, 0.55, 0.55, 1.0)")]
+    pub placeholder_color: Color,
+    /// Blocks all edits from user input while keeping selection, scrolling and copy working.
+    /// Useful for viewers of text that should not be modified. Defaults to `false`.
+    #[builder(default = "false")]
+    pub read_only: bool,
+    /// How lines wider than the editor are broken. With [`WrapMode::None`] the content
+    /// scrolls horizontally instead. Defaults to [`WrapMode::Glyph`].
+    #[builder(default = "WrapMode::Glyph")]
+    pub wrap: WrapMode,
+    /// Font family of the content. Defaults to [`FontFamily::SansSerif`].
+    #[builder(default)]
+    pub font_family: FontFamily,
+    /// OpenType features of the content, e.g. ligatures for a code font. Defaults to none
+    /// set, which keeps the defaults of the font.
+    #[builder(default)]
+    pub font_features: FontFeatures,
+    /// Font size of the content, before zooming. Defaults to `None`, which keeps the size
+    /// the [`TextEditorState`] was created with.
+    #[builder(default = "None")]
+    pub font_size: Option<Dp>,
+    /// Line height of the content, before zooming. Defaults to `None`, which is 1.2x the
+    /// font size if [`Self::font_size`] is set and otherwise the line height the
+    /// [`TextEditorState`] was created with.
+    #[builder(default = "None")]
+    pub line_height: Option<Dp>,
+    /// Color for highlighting the bracket next to the cursor and its match (RGBA).
+    /// Defaults to translucent gray.
+    #[builder(default = "Some(Color::new(0.5, 0.5, 0.5, 0.35))")]
+    pub bracket_highlight_color: Option<Color>,
+    /// Bracket pairs matched around the cursor, as `(open, close)`. An empty list disables
+    /// bracket matching. Defaults to `()`, `[]` and `{}`.
+    #[builder(default = "DEFAULT_BRACKET_PAIRS.to_vec()")]
+    pub bracket_pairs: Vec<(char, char)>,
+    /// Color of a full-width highlight behind the line of the cursor, shown while the editor
+    /// has focus. Defaults to `None`, which turns the highlight off.
+    #[builder(default = "None")]
+    pub current_line_color: Option<Color>,
+    /// Copies the indentation of the current line when Enter is pressed. Defaults to `false`.
+    #[builder(default = "false")]
+    pub auto_indent: bool,
+    /// Inserts the closing bracket or quote when `(`, `[`, `{`, `"` or `'` is typed, and moves
+    /// over it when it is typed next. Defaults to `false`.
+    #[builder(default = "false")]
+    pub auto_close: bool,
+    /// Characters after which auto-indent adds one more indent level, e.g. `{` or `:`.
+    /// Defaults to `{`, `[` and `(`.
+    #[builder(default = "DEFAULT_INDENT_AFTER.to_vec()")]
+    pub indent_after: Vec<char>,
+    /// Columns per indent level, also used to draw tab characters. Defaults to `4`.
+    #[builder(default = "4")]
+    pub tab_width: usize,
+    /// Tab inserts spaces up to the next tab stop instead of a tab character.
+    /// Defaults to `true`.
+    #[builder(default = "true")]
+    pub insert_spaces: bool,
+    /// Token that starts a line comment, e.g. `//` or `#`, toggled on the selected lines with
+    /// Ctrl+/. Defaults to `None`, which leaves Ctrl+/ unbound.
+    #[builder(default, setter(strip_option))]
+    pub line_comment: Option<String>,
+    /// Name or path of the edited file, used to detect the language of the content, which
+    /// sets the default of [`Self::line_comment`]. Without a telling name the first line is
+    /// used, e.g. a shebang or modeline. Defaults to `None`.
+    #[builder(default, setter(strip_option, into))]
+    pub file_name: Option<String>,
+    /// Name of the language of the content, e.g. `"python"`, instead of detecting it. See
+    /// [`Language::by_name`]. Defaults to `None`.
+    #[builder(default, setter(strip_option, into))]
+    pub language_name: Option<String>,
+    /// Tree-sitter grammar and colors the content is highlighted with. Edits only reparse
+    /// the part of the content they changed. Defaults to `None`, which draws all text in the
+    /// text color.
+    #[cfg(feature = "tree-sitter")]
+    #[builder(default)]
+    pub syntax_highlighting: Option<Arc<SyntaxHighlighting>>,
+    /// Key bindings of the editor commands like copy, undo or zoom. Defaults to
+    /// [`EditorKeymap::default`], the usual shortcuts of the platform.
+    #[builder(default)]
+    pub keymap: EditorKeymap,
+    /// Columns at which vertical ruler lines are drawn behind the text, e.g. `vec![80, 100]`.
+    /// Defaults to no rulers.
+    #[builder(default = "Vec::new()")]
+    pub rulers: Vec<usize>,
+    /// Color of the ruler lines (RGBA). Defaults to translucent gray.
+    #[builder(default = "Some(Color::new(0.5, 0.5, 0.5, 0.25))")]
+    pub ruler_color: Option<Color>,
+    /// Color of text beyond the first ruler, to point out lines that are too long.
+    /// Defaults to `None`, which draws it like other text.
+    #[builder(default = "None")]
+    pub overflow_color: Option<Color>,
+    /// Marks spaces with faint dots, tabs with arrows and line endings with pilcrows.
+    /// Defaults to `false`.
+    #[builder(default = "false")]
+    pub show_whitespace: bool,
+    /// Color of the whitespace marks (RGBA). Defaults to translucent gray.
+    #[builder(default = "None")]
+    pub whitespace_color: Option<Color>,
+    /// When the scrollbars are shown. The vertical one appears when the content is taller than
+    /// the editor, the horizontal one when wrapping is off and lines are wider than it.
+    /// Both can be dragged. Defaults to [`ScrollBarBehavior::Hidden`].
+    #[builder(default = "ScrollBarBehavior::Hidden")]
+    pub scrollbar_behavior: ScrollBarBehavior,
+    /// Thickness of the scrollbars. Defaults to `Dp(8.0)`.
+    #[builder(default = "Dp(8.0)")]
+    pub scrollbar_thickness: Dp,
+    /// Color of the scrollbar tracks.
+    #[builder(default = "Color::new(0.0, 0.0, 0.0, 0.1)")]
+    pub scrollbar_track_color: Color,
+    /// Color of the scrollbar thumbs.
+    #[builder(default = "Color::new(0.0, 0.0, 0.0, 0.3)")]
+    pub scrollbar_thumb_color: Color,
+    /// Color of a scrollbar thumb under the mouse pointer.
+    #[builder(default = "Color::new(0.0, 0.0, 0.0, 0.5)")]
+    pub scrollbar_thumb_hover_color: Color,
+    /// Smallest and largest zoom factor reachable with Ctrl+wheel and Ctrl+=/Ctrl+-.
+    /// Defaults to `(0.5, 3.0)`.
+    #[builder(default = "(0.5, 3.0)")]
+    pub zoom_range: (f32, f32),
+    /// Called with the new zoom factor when user input zooms the editor.
+    #[builder(default, setter(strip_option))]
+    pub on_zoom_changed: Option<Arc<dyn Fn(f32) + Send + Sync>>,
+    /// Called with `true` when the Insert key switches to overwrite mode and `false` when it
+    /// switches back, e.g. to update an "INS"/"OVR" indicator.
+    #[builder(default, setter(strip_option))]
+    pub on_overwrite_changed: Option<Arc<dyn Fn(bool) + Send + Sync>>,
+    /// Called with the position of the primary caret when it moves, by user input or by the
+    /// application, e.g. to highlight the current symbol in an
+    /// [`outline`](crate::outline::outline).
+    #[builder(default, setter(strip_option))]
+    pub on_caret_move: Option<Arc<dyn Fn(TextPosition) + Send + Sync>>,
+    /// Called when the mouse pointer moves onto a diagnostic, with that diagnostic, and with
+    /// `None` when it leaves, e.g. to show the message in a tooltip.
+    #[builder(default, setter(strip_option))]
+    pub on_diagnostic_hover: Option<Arc<dyn Fn(Option<Diagnostic>) + Send + Sync>>,
+    /// Called after typing a word character, and after deleting while the popup is open, with
+    /// the word before the cursor. The returned items are shown in a popup below the cursor;
+    /// an empty list closes it. Arrow keys move the highlight, Enter or Tab inserts the
+    /// highlighted item and Escape closes the popup.
+    #[builder(default, setter(strip_option))]
+    pub on_complete: Option<Arc<dyn Fn(CompletionRequest) -> Vec<CompletionItem> + Send + Sync>>,
+    /// Called with the URL, file path or [link pattern](Self::link_patterns) match under the
+    /// pointer when it is clicked with Ctrl held.
+    /// While set, holding Ctrl underlines the link under the pointer; without it Ctrl+click
+    /// adds a caret as usual.
+    #[builder(default, setter(strip_option))]
+    pub on_link_activate: Option<Arc<dyn Fn(String) + Send + Sync>>,
+    /// Patterns detected as links on top of URLs and file paths, e.g.
+    /// `LinkPattern::Numbered("#".into())` for issue numbers. The matched text, such as
+    /// `#123`, is passed to [`Self::on_link_activate`]. Defaults to none.
+    #[builder(default)]
+    pub link_patterns: Vec<LinkPattern>,
+    /// Marks drawn in a gutter left of the text, e.g. breakpoints or changed lines. The gutter
+    /// is shown while there are annotations or [`Self::on_gutter_click`] is set. Defaults to
+    /// none.
+    #[builder(default)]
+    pub annotations: Vec<GutterAnnotation>,
+    /// Rows of components shown between the lines, e.g. "Run | Debug" actions above a test
+    /// or the blame of a line. Each row pushes its line and the ones after it down, and only
+    /// the first row of a line is shown. Defaults to none.
+    #[builder(default)]
+    pub lens_rows: Vec<LensRow>,
+    /// Called with the index of the line next to a click in the gutter, e.g. to toggle a
+    /// breakpoint. Defaults to `None`.
+    #[builder(default, setter(strip_option))]
+    pub on_gutter_click: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    /// Turns the gutter into a breakpoint margin for debugger frontends: clicking a line
+    /// toggles its [`Breakpoint`], and right-clicking it opens a menu to remove, disable or
+    /// enable it, or to edit its condition. The breakpoints are kept in the
+    /// [`TextEditorState`]. Defaults to `false`.
+    #[builder(default = "false")]
+    pub breakpoints: bool,
+    /// Called with all breakpoints after clicks in the gutter changed them, e.g. to send them
+    /// to the debugger.
+    #[builder(default, setter(strip_option))]
+    pub on_breakpoints_changed: Option<Arc<dyn Fn(Vec<Breakpoint>) + Send + Sync>>,
+    /// Called with the line of a breakpoint whose condition the gutter menu asks to add or
+    /// edit. The application asks for it, e.g. in a dialog, and sets it with
+    /// [`TextEditorState::set_breakpoint_condition`]. Without it the menu offers no
+    /// conditions.
+    #[builder(default, setter(strip_option))]
+    pub on_edit_breakpoint_condition: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    /// Color of the breakpoints without a condition. Defaults to red.
+    #[builder(default, setter(strip_option))]
+    pub breakpoint_color: Option<Color>,
+    /// Color of the breakpoints with a condition. Defaults to orange.
+    #[builder(default, setter(strip_option))]
+    pub conditional_breakpoint_color: Option<Color>,
+    /// Color of the highlight of the line a debugger stopped at, see
+    /// [`TextEditorState::set_execution_line`]. Defaults to a translucent yellow.
+    #[builder(default, setter(strip_option))]
+    pub execution_line_color: Option<Color>,
+    /// Width of the gutter. Defaults to `Dp(16.0)`.
+    #[builder(default = "Dp(16.0)")]
+    pub gutter_width: Dp,
+    /// Whether the gutter shows the line numbers, left of the marks. A wrapped line is
+    /// numbered on its first row and its other rows get a small dash. Defaults to `false`.
+    #[builder(default = "false")]
+    pub line_numbers: bool,
+    /// Color of the line numbers and the wrap dashes. Defaults to gray.
+    #[builder(default = "Color::new(0.55, 0.55, 0.55, 1.0)")]
+    pub line_number_color: Color,
+    /// Color of the underline of a hovered link. Defaults to blue.
+    #[builder(default = "None")]
+    pub link_color: Option<Color>,
+    /// Color of the labels of inlay hints, see [`TextEditorState::set_inlay_hints`].
+    /// Defaults to gray.
+    #[builder(default = "None")]
+    pub inlay_hint_color: Option<Color>,
+    /// Whether copying and cutting also put the selection as HTML on the clipboard, in the
+    /// colors it is drawn in, so pasting into a word processor or mail keeps them. Defaults
+    /// to `false`.
+    #[builder(default = "false")]
+    pub copy_as_html: bool,
+    /// Whether Ctrl+G opens a popup at the top of the editor that jumps to the typed
+    /// `line[:column]`, centering it in the view. Defaults to `false`.
+    #[builder(default = "false")]
+    pub go_to_line: bool,
+    /// Lines kept visible above and below the caret as the view follows it after edits,
+    /// caret moves and focus changes. Defaults to `0`.
+    #[builder(default = "0")]
+    pub scroll_margin: usize,
+    /// Makes the editor a single-line text field: Enter calls [`Self::on_submit`] instead of
+    /// breaking the line, pasted line breaks become spaces and long content scrolls
+    /// horizontally instead of wrapping. Defaults to `false`.
+    #[builder(default = "false")]
+    pub single_line: bool,
+    /// Called with the content, without input mask formatting, when Enter is pressed in
+    /// single-line mode.
+    #[builder(default, setter(strip_option))]
+    pub on_submit: Option<Arc<dyn Fn(String) + Send + Sync>>,
+    /// In single-line mode, Tab and Shift+Tab unfocus the editor and are passed on to the
+    /// components around it, e.g. to focus the next field of a form. Otherwise Tab is ignored.
+    /// Defaults to `false`.
+    #[builder(default = "false")]
+    pub tab_moves_focus: bool,
+    /// Whether right-clicking opens a menu with Cut, Copy, Paste and Select All. A right-click
+    /// without a selection moves the cursor first. Defaults to `true`.
+    #[builder(default = "true")]
+    pub context_menu: bool,
+    /// Entries listed in the right-click menu after the editing commands. Defaults to none.
+    #[builder(default)]
+    pub context_menu_items: Vec<ContextMenuItem>,
 }
 
 impl Default for TextEditorArgs {
@@ -195,326 +616,897 @@ impl Default for TextEditorArgs {
 #[tessera]
 pub fn text_editor(args: impl Into<TextEditorArgs>, state: Arc<RwLock<TextEditorState>>) {
     let editor_args: TextEditorArgs = args.into();
-    let on_change = editor_args.on_change.clone();
 
+    let scheme = editor_args.color_scheme;
     // Update the state with the selection color from args
-    if let Some(selection_color) = editor_args.selection_color {
+    if let Some(selection_color) = editor_args.selection_color.or(scheme.map(|s| s.selection)) {
         state.write().set_selection_color(selection_color);
     }
+    let text_color = scheme.map(|s| s.foreground);
+    if state.read().text_color() != text_color {
+        state.write().set_text_color(text_color);
+    }
+    let caret_color = scheme.map(|s| s.caret);
+    if state.read().caret_color() != caret_color {
+        state.write().set_caret_color(caret_color);
+    }
+    if state.read().is_read_only() != editor_args.read_only {
+        state.write().set_read_only(editor_args.read_only);
+    }
+    if let Some(bracket_highlight_color) = editor_args.bracket_highlight_color {
+        state
+            .write()
+            .set_bracket_highlight_color(bracket_highlight_color);
+    }
+    if state.read().bracket_pairs() != editor_args.bracket_pairs.as_slice() {
+        state
+            .write()
+            .set_bracket_pairs(editor_args.bracket_pairs.clone());
+    }
+    let current_line_color = editor_args
+        .current_line_color
+        .or(scheme.and_then(|s| s.current_line));
+    if state.read().current_line_color() != current_line_color {
+        state.write().set_current_line_color(current_line_color);
+    }
+    if state.read().auto_indent() != editor_args.auto_indent {
+        state.write().set_auto_indent(editor_args.auto_indent);
+    }
+    if state.read().auto_close() != editor_args.auto_close {
+        state.write().set_auto_close(editor_args.auto_close);
+    }
+    if state.read().indent_after() != editor_args.indent_after.as_slice() {
+        state
+            .write()
+            .set_indent_after(editor_args.indent_after.clone());
+    }
+    if state.read().tab_width() != editor_args.tab_width {
+        state.write().set_tab_width(editor_args.tab_width);
+    }
+    if state.read().insert_spaces() != editor_args.insert_spaces {
+        state.write().set_insert_spaces(editor_args.insert_spaces);
+    }
+    let language = match &editor_args.language_name {
+        Some(name) => Language::by_name(name),
+        None => state
+            .read()
+            .detect_language(editor_args.file_name.as_deref()),
+    };
+    if state.read().language() != language {
+        state.write().set_language(language);
+    }
+    #[cfg(feature = "tree-sitter")]
+    if state.read().syntax_highlighting().map(Arc::as_ptr)
+        != editor_args.syntax_highlighting.as_ref().map(Arc::as_ptr)
+    {
+        state
+            .write()
+            .set_syntax_highlighting(editor_args.syntax_highlighting.clone());
+    }
+    let line_comment = editor_args
+        .line_comment
+        .as_deref()
+        .or(language.and_then(|language| language.line_comment));
+    if state.read().line_comment() != line_comment {
+        state
+            .write()
+            .set_line_comment(line_comment.map(str::to_string));
+    }
+    let text_offset =
+        Px::from(editor_args.padding) + Px(editor_args.border_width.to_pixels_u32() as i32);
+    if state.read().text_offset() != text_offset {
+        state.write().set_text_offset(text_offset);
+    }
+    if state.read().keymap() != &editor_args.keymap {
+        state.write().set_keymap(editor_args.keymap.clone());
+    }
+    if state.read().gutter_annotations() != editor_args.annotations.as_slice() {
+        state
+            .write()
+            .set_gutter_annotations(editor_args.annotations.clone());
+    }
+    let line_number_width = if editor_args.line_numbers {
+        line_number_width(&state.read())
+    } else {
+        Px(0)
+    };
+    if state.read().line_number_width() != line_number_width {
+        state.write().set_line_number_width(line_number_width);
+    }
+    let breakpoint_colors = (
+        editor_args
+            .breakpoint_color
+            .unwrap_or(DEFAULT_BREAKPOINT_COLOR),
+        editor_args
+            .conditional_breakpoint_color
+            .unwrap_or(DEFAULT_CONDITIONAL_BREAKPOINT_COLOR),
+    );
+    if state.read().breakpoint_colors() != breakpoint_colors {
+        state.write().set_breakpoint_colors(breakpoint_colors);
+    }
+    if let Some(execution_line_color) = editor_args.execution_line_color {
+        state.write().set_execution_line_color(execution_line_color);
+    }
+    let marks_width = if editor_args.annotations.is_empty()
+        && editor_args.on_gutter_click.is_none()
+        && !editor_args.breakpoints
+        && state.read().breakpoints().is_empty()
+        && state.read().execution_line().is_none()
+    {
+        Px(0)
+    } else {
+        editor_args.gutter_width.into()
+    };
+    let gutter_width = line_number_width + marks_width;
+    if state.read().gutter_width() != gutter_width {
+        state.write().set_gutter_width(gutter_width);
+    }
+    if state.read().rulers() != editor_args.rulers.as_slice() {
+        state.write().set_rulers(editor_args.rulers.clone());
+    }
+    if let Some(ruler_color) = editor_args.ruler_color {
+        state.write().set_ruler_color(ruler_color);
+    }
+    if state.read().show_whitespace() != editor_args.show_whitespace {
+        state
+            .write()
+            .set_show_whitespace(editor_args.show_whitespace);
+    }
+    if let Some(whitespace_color) = editor_args.whitespace_color {
+        state.write().set_whitespace_color(whitespace_color);
+    }
+    if state.read().link_patterns() != editor_args.link_patterns.as_slice() {
+        state
+            .write()
+            .set_link_patterns(editor_args.link_patterns.clone());
+    }
+    if let Some(link_color) = editor_args.link_color {
+        state.write().set_link_color(link_color);
+    }
+    if let Some(inlay_hint_color) = editor_args.inlay_hint_color {
+        state.write().set_inlay_hint_color(inlay_hint_color);
+    }
+    if state.read().overflow_color() != editor_args.overflow_color {
+        state.write().set_overflow_color(editor_args.overflow_color);
+    }
+    if state.read().zoom_range() != editor_args.zoom_range {
+        let (min, max) = editor_args.zoom_range;
+        state.write().set_zoom_range(min, max);
+    }
+    if state.read().copy_as_html() != editor_args.copy_as_html {
+        state.write().set_copy_as_html(editor_args.copy_as_html);
+    }
+    if state.read().go_to_line_enabled() != editor_args.go_to_line {
+        state.write().set_go_to_line_enabled(editor_args.go_to_line);
+    }
+    if state.read().scroll_margin() != editor_args.scroll_margin {
+        state.write().set_scroll_margin(editor_args.scroll_margin);
+    }
+    if state.read().is_single_line() != editor_args.single_line {
+        state.write().set_single_line(editor_args.single_line);
+    }
+    // A single-line field never wraps
+    let wrap = if editor_args.single_line {
+        WrapMode::None
+    } else {
+        editor_args.wrap
+    };
+    if state.read().wrap_mode() != wrap {
+        state.write().set_wrap_mode(wrap);
+    }
+    if state.read().font_family() != &editor_args.font_family {
+        state
+            .write()
+            .set_font_family(editor_args.font_family.clone());
+    }
+    if state.read().font_features() != &editor_args.font_features {
+        state
+            .write()
+            .set_font_features(editor_args.font_features.clone());
+    }
+    if editor_args.font_size.is_some() || editor_args.line_height.is_some() {
+        let size = editor_args
+            .font_size
+            .unwrap_or_else(|| state.read().font_size());
+        state.write().set_font_size(size, editor_args.line_height);
+    }
+    if state.read().input_mask() != editor_args.input_mask.as_ref() {
+        state.write().set_input_mask(editor_args.input_mask.clone());
+    }
+    if state.read().change_text() != editor_args.change_text {
+        state.write().set_change_text(editor_args.change_text);
+    }
+    if let Some(search_highlight_color) = editor_args.search_highlight_color {
+        state
+            .write()
+            .set_search_highlight_color(search_highlight_color);
+    }
+    if let Some(current_match_color) = editor_args.current_match_color {
+        state.write().set_current_match_color(current_match_color);
+    }
+
+    if !state.read().focus_handler().is_focused() {
+        state.write().close_completion();
+        state.write().close_context_menu();
+    }
 
     // surface layer - provides visual container and minimum size guarantee
     {
         let state_for_surface = state.clone();
         let args_for_surface = editor_args.clone();
+        let (scrollbar_v, scrollbar_h) = editor_scrollbar_args(&editor_args, &state);
+        let placeholder = editor_args
+            .placeholder
+            .clone()
+            .filter(|_| {
+                let guard = state.read();
+                guard.is_empty() && !guard.focus_handler().is_focused()
+            })
+            .map(|placeholder| (placeholder, editor_args.placeholder_color));
+        let search_counter = editor_args
+            .show_search_counter
+            .then(|| state.read().search_counter())
+            .flatten()
+            .map(|counter| (counter, editor_args.placeholder_color));
+        let line_number_color = editor_args.line_number_color;
+        let lens_rows = editor_args.lens_rows.clone();
         surface(
             create_surface_args(&args_for_surface, &state_for_surface),
             None, // text editors are not interactive at surface level
             move || {
                 // Core layer - handles text rendering and editing logic
-                text_edit_core(state_for_surface.clone());
+                editor_text_area(
+                    state_for_surface.clone(),
+                    placeholder,
+                    scrollbar_v,
+                    scrollbar_h,
+                    search_counter,
+                    line_number_color,
+                    lens_rows.clone(),
+                );
             },
         );
     }
 
+    // The popups hang below the cursor, pointer or fold marker and do not count towards the
+    // editor size
+    let has_completion = !state.read().completion_items().is_empty();
+    if has_completion {
+        completion_popup(state.clone());
+    }
+    let has_context_menu = state.read().is_context_menu_open();
+    if has_context_menu {
+        let entries = state
+            .read()
+            .context_menu_entries(&editor_args.context_menu_items);
+        context_menu_popup(state.clone(), entries);
+    }
+    let fold_preview = state.read().fold_preview(FOLD_PREVIEW_LINES);
+    if let Some(code) = fold_preview {
+        fold_preview_popup(code);
+    }
+    let go_to_line = state.read().go_to_line_input().map(str::to_string);
+    let has_go_to_line = go_to_line.is_some();
+    if let Some(input) = go_to_line {
+        go_to_line_popup(input);
+    }
+    {
+        let state_for_measure = state.clone();
+        measure(Box::new(move |input| {
+            let surface_id = input.children_ids[0];
+            let size = input.measure_child(surface_id, input.parent_constraint)?;
+            input.place_child(surface_id, PxPosition::new(Px(0), Px(0)));
+
+            let wrap = DimensionValue::Wrap {
+                min: None,
+                max: None,
+            };
+            let (text_origin, completion_anchor, context_menu_anchor, fold_preview_anchor) = {
+                let guard = state_for_measure.read();
+                (
+                    guard.text_origin(),
+                    guard.completion_anchor(),
+                    guard.context_menu_anchor(),
+                    guard.fold_preview_anchor(),
+                )
+            };
+            let anchors = has_completion
+                .then_some(completion_anchor)
+                .into_iter()
+                .chain(has_context_menu.then_some(context_menu_anchor))
+                .chain(std::iter::once(fold_preview_anchor));
+            let popup_ids = &input.children_ids[1..];
+            let (popup_ids, go_to_line_id) =
+                popup_ids.split_at(popup_ids.len() - usize::from(has_go_to_line));
+            for (&popup_id, anchor) in popup_ids.iter().zip(anchors) {
+                input.measure_child(popup_id, &Constraint::new(wrap, wrap))?;
+                let anchor = anchor.unwrap_or(PxPosition::new(Px(0), Px(0)));
+                input.place_child(
+                    popup_id,
+                    PxPosition::new(anchor.x + text_origin.x, anchor.y + text_origin.y),
+                );
+            }
+            // The go-to-line popup is centered at the top
+            if let Some(&popup_id) = go_to_line_id.first() {
+                let popup = input.measure_child(popup_id, &Constraint::new(wrap, wrap))?;
+                let x = ((size.width - popup.width) / 2).max(Px(0));
+                input.place_child(popup_id, PxPosition::new(x, text_origin.y));
+            }
+
+            Ok(ComputedData {
+                width: size.width,
+                height: size.height,
+            })
+        }));
+    }
+
     // Event handling at the outermost layer - can access full surface area
+    input_handler(editor_input_handler(
+        state.clone(),
+        EditorInputConfig::from(&editor_args),
+    ));
+}
 
-    let state_for_handler = state.clone();
-    input_handler(Box::new(move |input| {
-        let size = input.computed_data; // This is the full surface size
-        let cursor_pos_option = input.cursor_position_rel;
-        let is_cursor_in_editor = cursor_pos_option
-            .map(|pos| is_position_in_component(size, pos))
-            .unwrap_or(false);
-
-        // Set text input cursor when hovering
-        if is_cursor_in_editor {
-            input.requests.cursor_icon = winit::window::CursorIcon::Text;
+/// The core layer of a [`text_editor`]: the gutter, the text with the column rulers drawn
+/// before it so they appear behind it, the lens rows over the room the text leaves for them,
+/// then the placeholder, if any, the scrollbars on top
+/// along the right and bottom edges and the search counter in the top right corner.
+#[tessera]
+fn editor_text_area(
+    state: Arc<RwLock<TextEditorState>>,
+    placeholder: Option<(String, Color)>,
+    scrollbar_v: Option<ScrollBarArgs>,
+    scrollbar_h: Option<ScrollBarArgs>,
+    search_counter: Option<(String, Color)>,
+    line_number_color: Color,
+    lens_rows: Vec<LensRow>,
+) {
+    let (gutter_rects, wrap_indicators, line_numbers, ruler_rects, ruler_color, metrics) = {
+        let guard = state.read();
+        (
+            guard.current_gutter_rects.clone(),
+            guard.current_wrap_indicators.clone(),
+            guard.current_line_numbers.clone(),
+            guard.current_ruler_rects.clone(),
+            guard.ruler_color(),
+            guard.editor().with_buffer(|buffer| buffer.metrics()),
+        )
+    };
+    let gutter_count = gutter_rects.len();
+    for (def, annotation) in gutter_rects {
+        match annotation.mark {
+            GutterMark::Dot => gutter_dot(def.width, def.height, annotation.color),
+            GutterMark::Bar => selection_highlight_rect(def.width, def.height, annotation.color),
         }
+    }
+    let wrap_count = wrap_indicators.len();
+    let wrap_color = line_number_color.with_alpha(line_number_color.a * 0.5);
+    for def in wrap_indicators {
+        selection_highlight_rect(def.width, def.height, wrap_color);
+    }
+    let number_count = line_numbers.len();
+    for row in line_numbers {
+        placeholder_text((row.line + 1).to_string(), line_number_color, metrics);
+    }
+    for def in ruler_rects {
+        selection_highlight_rect(def.width, def.height, ruler_color);
+    }
+    text_edit_core(state.clone());
+    let lens_lines: Vec<usize> = lens_rows.iter().map(|row| row.line).collect();
+    for row in lens_rows {
+        lens_row_content(row.content);
+    }
 
-        // Handle click events - now we have a full clickable area from surface
-        if is_cursor_in_editor {
-            // Handle mouse pressed events
-            let click_events: Vec<_> = input
-                .cursor_events
-                .iter()
-                .filter(|event| matches!(event.content, CursorEventContent::Pressed(_)))
-                .collect();
+    let has_placeholder = placeholder.is_some();
+    if let Some((placeholder, color)) = placeholder {
+        let metrics = state.read().editor().with_buffer(|buffer| buffer.metrics());
+        placeholder_text(placeholder, color, metrics);
+    }
 
-            // Handle mouse released events (end of drag)
-            let release_events: Vec<_> = input
-                .cursor_events
-                .iter()
-                .filter(|event| matches!(event.content, CursorEventContent::Released(_)))
-                .collect();
-
-            if !click_events.is_empty() {
-                // Request focus if not already focused
-                if !state_for_handler.read().focus_handler().is_focused() {
-                    state_for_handler
-                        .write()
-                        .focus_handler_mut()
-                        .request_focus();
-                }
-
-                // Handle cursor positioning for clicks
-                if let Some(cursor_pos) = cursor_pos_option {
-                    // Calculate the relative position within the text area
-                    let padding_px: Px = editor_args.padding.into();
-                    let border_width_px = Px(editor_args.border_width.to_pixels_u32() as i32); // Assuming border_width is integer pixels
-
-                    let text_relative_x_px = cursor_pos.x - padding_px - border_width_px;
-                    let text_relative_y_px = cursor_pos.y - padding_px - border_width_px;
-
-                    // Only process if the click is within the text area (non-negative relative coords)
-                    if text_relative_x_px >= Px(0) && text_relative_y_px >= Px(0) {
-                        let text_relative_pos =
-                            PxPosition::new(text_relative_x_px, text_relative_y_px);
-                        // Determine click type and handle accordingly
-                        let click_type = state_for_handler
-                            .write()
-                            .handle_click(text_relative_pos, click_events[0].timestamp);
-
-                        match click_type {
-                            ClickType::Single => {
-                                // Single click: position cursor
-                                state_for_handler.write().editor_mut().action(
-                                    &mut write_font_system(),
-                                    Action::Click {
-                                        x: text_relative_pos.x.0,
-                                        y: text_relative_pos.y.0,
-                                    },
-                                );
-                            }
-                            ClickType::Double => {
-                                // Double click: select word
-                                state_for_handler.write().editor_mut().action(
-                                    &mut write_font_system(),
-                                    Action::DoubleClick {
-                                        x: text_relative_pos.x.0,
-                                        y: text_relative_pos.y.0,
-                                    },
-                                );
-                            }
-                            ClickType::Triple => {
-                                // Triple click: select line
-                                state_for_handler.write().editor_mut().action(
-                                    &mut write_font_system(),
-                                    Action::TripleClick {
-                                        x: text_relative_pos.x.0,
-                                        y: text_relative_pos.y.0,
-                                    },
-                                );
-                            }
-                        }
-
-                        // Start potential drag operation
-                        state_for_handler.write().start_drag();
-                    }
-                }
-            }
+    let (has_v, has_h) = (scrollbar_v.is_some(), scrollbar_h.is_some());
+    if let Some(args) = scrollbar_v {
+        editor_scrollbar(args, state.read().scrollbar_state_v.clone(), true);
+    }
+    if let Some(args) = scrollbar_h {
+        editor_scrollbar(args, state.read().scrollbar_state_h.clone(), false);
+    }
+    let has_counter = search_counter.is_some();
+    if let Some((counter, color)) = search_counter {
+        let metrics = state.read().editor().with_buffer(|buffer| buffer.metrics());
+        placeholder_text(counter, color, metrics);
+    }
 
-            // Handle drag events (mouse move while dragging)
-            // This happens every frame when cursor position changes during drag
-            if state_for_handler.read().is_dragging()
-                && let Some(cursor_pos) = cursor_pos_option
-            {
-                let padding_px: Px = editor_args.padding.into();
-                let border_width_px = Px(editor_args.border_width.to_pixels_u32() as i32);
-
-                let text_relative_x_px = cursor_pos.x - padding_px - border_width_px;
-                let text_relative_y_px = cursor_pos.y - padding_px - border_width_px;
-
-                if text_relative_x_px >= Px(0) && text_relative_y_px >= Px(0) {
-                    let current_pos_px = PxPosition::new(text_relative_x_px, text_relative_y_px);
-                    let last_pos_px = state_for_handler.read().last_click_position();
-
-                    if last_pos_px != Some(current_pos_px) {
-                        // Extend selection by dragging
-                        state_for_handler.write().editor_mut().action(
-                            &mut write_font_system(),
-                            Action::Drag {
-                                x: current_pos_px.x.0,
-                                y: current_pos_px.y.0,
-                            },
-                        );
-
-                        // Update last position to current position
-                        state_for_handler
-                            .write()
-                            .update_last_click_position(current_pos_px);
-                    }
-                }
+    measure(Box::new(move |input| {
+        let overlay_count = usize::from(has_placeholder)
+            + usize::from(has_v)
+            + usize::from(has_h)
+            + usize::from(has_counter);
+        let (gutter_ids, rest) = input.children_ids.split_at(gutter_count);
+        let (wrap_ids, rest) = rest.split_at(wrap_count);
+        let (number_ids, rest) = rest.split_at(number_count);
+        let (ruler_ids, rest) = rest.split_at(rest.len() - 1 - lens_lines.len() - overlay_count);
+        let (&core_id, rest) = rest.split_first().expect("the text core is always a child");
+        let (lens_ids, bar_ids) = rest.split_at(lens_lines.len());
+        // The text sits right of the gutter
+        let gutter = state.read().gutter_width();
+        let text_constraint = Constraint::new(
+            without_width(input.parent_constraint.width, gutter),
+            input.parent_constraint.height,
+        );
+        // The lens rows are measured first, so the text makes room for them
+        let row_constraint = Constraint::new(
+            DimensionValue::Wrap {
+                min: None,
+                max: text_constraint.width.get_max(),
+            },
+            DimensionValue::Wrap {
+                min: None,
+                max: None,
+            },
+        );
+        let mut lens_heights = Vec::with_capacity(lens_ids.len());
+        for &row_id in lens_ids {
+            lens_heights.push(input.measure_child(row_id, &row_constraint)?.height);
+        }
+        state.write().set_lens_rows(
+            lens_lines
+                .iter()
+                .zip(&lens_heights)
+                .map(|(&line, height)| (line, height.to_f32()))
+                .collect(),
+        );
+        let mut size = input.measure_child(core_id, &text_constraint)?;
+        input.place_child(core_id, PxPosition::new(gutter, Px(0)));
+        let (placeholder_id, bar_ids) = bar_ids.split_at(usize::from(has_placeholder));
+        if let Some(&placeholder_id) = placeholder_id.first() {
+            // The empty editor grows to fit the placeholder instead of cutting it off
+            let hint = input.measure_child(placeholder_id, &text_constraint)?;
+            input.place_child(placeholder_id, PxPosition::new(gutter, Px(0)));
+            size.width = size.width.max(hint.width);
+            size.height = size.height.max(hint.height);
+        }
+        let in_view = |top: Px, height: Px| top + height > Px(0) && top < size.height;
+        // Rows of lines that were not laid out, or of a line that already has one, are not
+        // placed and so not drawn
+        let mut lens_tops = state.read().lens_row_tops();
+        for ((&row_id, line), &height) in lens_ids.iter().zip(&lens_lines).zip(&lens_heights) {
+            let Some(i) = lens_tops.iter().position(|(other, _)| other == line) else {
+                continue;
+            };
+            let top = Px(lens_tops.swap_remove(i).1.round() as i32);
+            if in_view(top, height) {
+                input.place_child(row_id, PxPosition::new(gutter, top));
             }
+        }
 
-            // Handle mouse release events (end drag)
-            if !release_events.is_empty() {
-                state_for_handler.write().stop_drag();
-            }
+        // The number of ruler and gutter children was decided from the rows stored last pass
+        let ruler_rects = state.read().ruler_rects(size.width, size.height);
+        for (&ruler_id, rect_def) in ruler_ids.iter().zip(&ruler_rects) {
+            input.measure_child(ruler_id, input.parent_constraint)?;
+            input.place_child(ruler_id, PxPosition::new(rect_def.x + gutter, rect_def.y));
+        }
+        state.write().current_ruler_rects = ruler_rects;
+        let gutter_rects: Vec<_> = state
+            .read()
+            .gutter_rects()
+            .into_iter()
+            .filter(|(rect, _)| rect.y + rect.height > Px(0) && rect.y < size.height)
+            .collect();
+        for (&mark_id, (rect_def, _)) in gutter_ids.iter().zip(&gutter_rects) {
+            input.measure_child(mark_id, input.parent_constraint)?;
+            input.place_child(mark_id, PxPosition::new(rect_def.x, rect_def.y));
+        }
+        state.write().current_gutter_rects = gutter_rects;
+        let wrap_indicators: Vec<_> = state
+            .read()
+            .wrap_indicator_rects()
+            .into_iter()
+            .filter(|rect| in_view(rect.y, rect.height))
+            .collect();
+        for (&dash_id, rect_def) in wrap_ids.iter().zip(&wrap_indicators) {
+            input.measure_child(dash_id, input.parent_constraint)?;
+            input.place_child(dash_id, PxPosition::new(rect_def.x, rect_def.y));
+        }
+        state.write().current_wrap_indicators = wrap_indicators;
+        // The numbers are right-aligned before the gap
+        let number_right = state.read().line_number_width() - LINE_NUMBER_GAP;
+        let line_numbers: Vec<_> = state
+            .read()
+            .line_number_rows()
+            .into_iter()
+            .filter(|row| in_view(Px(row.top as i32), Px(row.height.ceil() as i32)))
+            .collect();
+        for (&number_id, row) in number_ids.iter().zip(&line_numbers) {
+            let number = input.measure_child(number_id, &Constraint::NONE)?;
+            let x = (number_right - number.width).max(Px(0));
+            input.place_child(number_id, PxPosition::new(x, Px(row.top.round() as i32)));
+        }
+        state.write().current_line_numbers = line_numbers;
+        size.width += gutter;
 
-            let scroll_events: Vec<_> = input
-                .cursor_events
-                .iter()
-                .filter_map(|event| match &event.content {
-                    CursorEventContent::Scroll(scroll_event) => Some(scroll_event),
-                    _ => None,
-                })
-                .collect();
-
-            // Handle scroll events (only when focused and cursor is in editor)
-            if state_for_handler.read().focus_handler().is_focused() {
-                for scroll_event in scroll_events {
-                    // Convert scroll delta to lines
-                    let scroll = -scroll_event.delta_y;
-
-                    // Scroll up for positive, down for negative
-                    let action = glyphon::Action::Scroll { pixels: scroll };
-                    state_for_handler
-                        .write()
-                        .editor_mut()
-                        .action(&mut write_font_system(), action);
-                }
-            }
+        let mut bar_ids = bar_ids.iter().copied();
+        let mut bar_width = Px(0);
+        if has_v && let Some(bar_id) = bar_ids.next() {
+            let bar = input.measure_child(bar_id, &Constraint::NONE)?;
+            input.place_child(bar_id, PxPosition::new(size.width - bar.width, Px(0)));
+            bar_width = bar.width;
+        }
+        if has_h && let Some(bar_id) = bar_ids.next() {
+            let bar = input.measure_child(bar_id, &Constraint::NONE)?;
+            input.place_child(bar_id, PxPosition::new(gutter, size.height - bar.height));
+        }
+        // The counter keeps clear of the vertical scrollbar
+        if has_counter && let Some(counter_id) = bar_ids.next() {
+            let counter = input.measure_child(counter_id, &Constraint::NONE)?;
+            let x = (size.width - bar_width - counter.width).max(Px(0));
+            input.place_child(counter_id, PxPosition::new(x, Px(0)));
+        }
+        Ok(size)
+    }));
+}
 
-            // Only block cursor events when focused to prevent propagation
-            if state_for_handler.read().focus_handler().is_focused() {
-                input.cursor_events.clear();
-            }
+/// The components of a lens row, side by side.
+#[tessera]
+fn lens_row_content(content: Arc<dyn Fn() + Send + Sync>) {
+    content();
+    measure(Box::new(|input| {
+        let mut size = ComputedData::ZERO;
+        for &child_id in input.children_ids.iter() {
+            let child = input.measure_child(child_id, input.parent_constraint)?;
+            input.place_child(child_id, PxPosition::new(size.width, Px(0)));
+            size.width += child.width;
+            size.height = size.height.max(child.height);
         }
+        Ok(size)
+    }));
+}
 
-        // Handle keyboard events (only when focused)
-        if state_for_handler.read().focus_handler().is_focused() {
-            // Handle keyboard events
-            let is_ctrl = input.key_modifiers.control_key() || input.key_modifiers.super_key();
-
-            // Custom handling for Ctrl+A (Select All)
-            let select_all_event_index = input.keyboard_events.iter().position(|key_event| {
-                if let winit::keyboard::Key::Character(s) = &key_event.logical_key {
-                    is_ctrl
-                        && s.to_lowercase() == "a"
-                        && key_event.state == winit::event::ElementState::Pressed
-                } else {
-                    false
-                }
-            });
+/// Returns the width of the line numbers of `state` in its gutter: room for the digits of
+/// the last line, at least two, and the gap after them.
+fn line_number_width(state: &TextEditorState) -> Px {
+    let (line_count, metrics) = state
+        .editor()
+        .with_buffer(|buffer| (buffer.lines.len(), buffer.metrics()));
+    let digits = line_count.to_string().len().max(2);
+    let text_data = TextData::new(
+        "0".repeat(digits),
+        Color::BLACK,
+        metrics.font_size,
+        metrics.line_height,
+        TextConstraint::NONE,
+    );
+    Px(text_data.size[0] as i32) + LINE_NUMBER_GAP
+}
 
-            if let Some(_index) = select_all_event_index {
-                let mut state = state_for_handler.write();
-                let editor = state.editor_mut();
-                // Set cursor to the beginning of the document
-                editor.set_cursor(glyphon::Cursor::new(0, 0));
-                // Set selection to start from the beginning
-                editor.set_selection(glyphon::cosmic_text::Selection::Normal(
-                    glyphon::Cursor::new(0, 0),
-                ));
-                // Move cursor to the end, which extends the selection (use BufferEnd for full document)
-                editor.action(
-                    &mut write_font_system(),
-                    glyphon::Action::Motion(glyphon::cosmic_text::Motion::BufferEnd),
-                );
-            } else {
-                // Original logic for other keys
-                let mut all_actions = Vec::new();
-                {
-                    let mut state = state_for_handler.write();
-                    for key_event in input.keyboard_events.iter().cloned() {
-                        if let Some(actions) = state.map_key_event_to_action(
-                            key_event,
-                            input.key_modifiers,
-                            input.clipboard,
-                        ) {
-                            all_actions.extend(actions);
-                        }
-                    }
-                }
-
-                if !all_actions.is_empty() {
-                    let mut state = state_for_handler.write();
-                    for action in all_actions {
-                        handle_action(&mut state, action, on_change.clone());
-                    }
-                }
-            }
+/// Returns `dimension` narrowed by `width`, the room taken by the gutter.
+fn without_width(dimension: DimensionValue, width: Px) -> DimensionValue {
+    let narrow = |value: Px| (value - width).max(Px(0));
+    match dimension {
+        DimensionValue::Fixed(value) => DimensionValue::Fixed(narrow(value)),
+        DimensionValue::Wrap { min, max } => DimensionValue::Wrap {
+            min: min.map(narrow),
+            max: max.map(narrow),
+        },
+        DimensionValue::Fill { min, max } => DimensionValue::Fill {
+            min: min.map(narrow),
+            max: max.map(narrow),
+        },
+    }
+}
 
-            // Block all keyboard events to prevent propagation
-            input.keyboard_events.clear();
-
-            // Handle IME events
-            let ime_events: Vec<_> = input.ime_events.drain(..).collect();
-            for event in ime_events {
-                let mut state = state_for_handler.write();
-                match event {
-                    winit::event::Ime::Commit(text) => {
-                        // Clear preedit string if it exists
-                        if let Some(preedit_text) = state.preedit_string.take() {
-                            for _ in 0..preedit_text.chars().count() {
-                                handle_action(&mut state, Action::Backspace, on_change.clone());
-                            }
-                        }
-                        // Insert the committed text
-                        for c in text.chars() {
-                            handle_action(&mut state, Action::Insert(c), on_change.clone());
-                        }
-                    }
-                    winit::event::Ime::Preedit(text, _cursor_offset) => {
-                        // Remove the old preedit text if it exists
-                        if let Some(old_preedit) = state.preedit_string.take() {
-                            for _ in 0..old_preedit.chars().count() {
-                                handle_action(&mut state, Action::Backspace, on_change.clone());
-                            }
-                        }
-                        // Insert the new preedit text
-                        for c in text.chars() {
-                            handle_action(&mut state, Action::Insert(c), on_change.clone());
-                        }
-                        state.preedit_string = Some(text.to_string());
-                    }
-                    _ => {}
-                }
-            }
+/// A [`GutterMark::Dot`] in the gutter of a [`text_editor`].
+#[tessera]
+fn gutter_dot(width: Px, height: Px, color: Color) {
+    measure(Box::new(move |input| {
+        input
+            .metadata_mut()
+            .push_draw_command(ShapeCommand::Ellipse {
+                color,
+                shadow: None,
+            });
+        Ok(ComputedData { width, height })
+    }));
+}
 
-            // Request IME window
-            input.requests.ime_request = Some(ImeRequest::new(size.into()));
-        }
+/// The placeholder of an empty [`text_editor`], its search counter or a line number, drawn
+/// with the font metrics of its content.
+#[tessera]
+fn placeholder_text(text: String, color: Color, metrics: glyphon::Metrics) {
+    measure(Box::new(move |input| {
+        let max_width = match input.parent_constraint.width {
+            DimensionValue::Fixed(w) => Some(w),
+            DimensionValue::Wrap { max, .. } => max,
+            DimensionValue::Fill { max, .. } => max,
+        };
+        let text_data = TextData::new(
+            text.clone(),
+            color,
+            metrics.font_size,
+            metrics.line_height,
+            TextConstraint {
+                max_width: max_width.map(|px| px.to_f32()),
+                max_height: None,
+                max_lines: None,
+            },
+        );
+        let size = ComputedData {
+            width: Px(text_data.size[0] as i32),
+            height: Px(text_data.size[1] as i32),
+        };
+        input
+            .metadata_mut()
+            .push_draw_command(TextCommand { data: text_data });
+        Ok(size)
     }));
 }
 
-fn handle_action(
-    state: &mut TextEditorState,
-    action: Action,
-    on_change: Arc<dyn Fn(String) -> String + Send + Sync>,
-) {
-    // Clone a temporary editor and apply action, waiting for on_change to confirm
-    let mut new_editor = state.editor().clone();
-
-    // Make sure new editor own a isolated buffer
-    let mut new_buffer = None;
-    match new_editor.buffer_ref_mut() {
-        glyphon::cosmic_text::BufferRef::Owned(_) => { /* Already owned */ }
-        glyphon::cosmic_text::BufferRef::Borrowed(buffer) => {
-            new_buffer = Some(buffer.clone());
-        }
-        glyphon::cosmic_text::BufferRef::Arc(buffer) => {
-            new_buffer = Some((**buffer).clone());
-        }
+/// Returns the arguments of the vertical and horizontal scrollbars of a [`text_editor`], or
+/// `None` for a scrollbar that is turned off or has nothing to scroll.
+fn editor_scrollbar_args(
+    args: &TextEditorArgs,
+    state: &Arc<RwLock<TextEditorState>>,
+) -> (Option<ScrollBarArgs>, Option<ScrollBarArgs>) {
+    if matches!(args.scrollbar_behavior, ScrollBarBehavior::Hidden) {
+        return (None, None);
     }
-    if let Some(buffer) = new_buffer {
-        *new_editor.buffer_ref_mut() = glyphon::cosmic_text::BufferRef::Owned(buffer);
+    let guard = state.read();
+    let bar = |total: Px, visible: Px, offset: Px| {
+        (total > visible).then(|| ScrollBarArgs {
+            total,
+            visible,
+            offset,
+            thickness: args.scrollbar_thickness,
+            state: state.clone(),
+            scrollbar_behavior: args.scrollbar_behavior.clone(),
+            track_color: args.scrollbar_track_color,
+            thumb_color: args.scrollbar_thumb_color,
+            thumb_hover_color: args.scrollbar_thumb_hover_color,
+        })
+    };
+    let vertical = bar(
+        guard.content_height(),
+        guard.viewport_height(),
+        -guard.scroll_offset(),
+    );
+    let horizontal = if guard.wrap_mode() == WrapMode::None {
+        let (widest, visible) = guard.horizontal_extent();
+        bar(widest, visible, -guard.horizontal_scroll())
+    } else {
+        None
+    };
+    (vertical, horizontal)
+}
+
+/// A scrollbar of a [`text_editor`]. Presses and drags on it do not reach the text.
+#[tessera]
+fn editor_scrollbar(args: ScrollBarArgs, bar_state: Arc<RwLock<ScrollBarState>>, vertical: bool) {
+    if vertical {
+        scrollbar_v(args, bar_state.clone());
+    } else {
+        scrollbar_h(args, bar_state.clone());
     }
 
-    new_editor.action(&mut write_font_system(), action);
-    let content_after_action = get_editor_content(&new_editor);
+    input_handler(Box::new(move |input| {
+        let size = input.computed_data;
+        let on_bar = input
+            .cursor_position_rel
+            .is_some_and(|pos| is_position_in_component(size, pos));
+        if on_bar || bar_state.read().is_dragging {
+            input.cursor_events.clear();
+        }
+    }));
+}
+
+/// Surface arguments shared by the popups of a [`text_editor`].
+fn popup_surface_args(padding: Dp) -> crate::surface::SurfaceArgs {
+    SurfaceArgsBuilder::default()
+        .style(Color::WHITE.into())
+        .shadow(ShadowProps {
+            color: Color::BLACK.with_alpha(0.25),
+            ..Default::default()
+        })
+        .shape(Shape::RoundedRectangle {
+            top_left: Dp(4.0),
+            top_right: Dp(4.0),
+            bottom_right: Dp(4.0),
+            bottom_left: Dp(4.0),
+            g2_k_value: 3.0,
+        })
+        .padding(padding)
+        .build()
+        .unwrap()
+}
+
+/// The go-to-line popup of a [`text_editor`], showing the typed `input`.
+#[tessera]
+fn go_to_line_popup(input: String) {
+    surface(popup_surface_args(Dp(6.0)), None, move || {
+        text(
+            TextArgsBuilder::default()
+                .text(format!("Go to line: {input}|"))
+                .color(Color::BLACK)
+                .size(Dp(14.0))
+                .build()
+                .unwrap(),
+        );
+    });
+}
+
+/// Number of hidden lines shown in the preview of a fold.
+const FOLD_PREVIEW_LINES: usize = 12;
+
+/// Shows the hidden lines of the fold marker under the mouse pointer.
+#[tessera]
+fn fold_preview_popup(code: String) {
+    surface(popup_surface_args(Dp(0.0)), None, move || {
+        code_block(
+            CodeBlockArgsBuilder::default()
+                .code(code)
+                .line_numbers(false)
+                .copy_button(false)
+                .build()
+                .unwrap(),
+        );
+    });
+}
+
+/// Number of completion items shown at once; the list scrolls to keep the highlight visible.
+const COMPLETION_VISIBLE_ITEMS: usize = 8;
+
+/// The completion popup of a [`text_editor`], listing the items of `state` with the
+/// highlighted one on an accent background. Clicking an item inserts it.
+#[tessera]
+fn completion_popup(state: Arc<RwLock<TextEditorState>>) {
+    let (items, selected) = {
+        let guard = state.read();
+        (
+            guard.completion_items().to_vec(),
+            guard.selected_completion().unwrap_or(0),
+        )
+    };
+    let first = selected.saturating_sub(COMPLETION_VISIBLE_ITEMS - 1);
+
+    surface(popup_surface_args(Dp(4.0)), None, move || {
+        column(ColumnArgs::default(), |scope| {
+            for (index, item) in items
+                .into_iter()
+                .enumerate()
+                .skip(first)
+                .take(COMPLETION_VISIBLE_ITEMS)
+            {
+                let state = state.clone();
+                scope.child(move || {
+                    completion_row(item.label, index == selected, index, state);
+                });
+            }
+        });
+    });
 
-    state.editor_mut().action(&mut write_font_system(), action);
-    let new_content = on_change(content_after_action);
+    input_handler(Box::new(block_popup_clicks));
+}
+
+/// Keeps clicks on a popup from reaching the editor underneath.
+fn block_popup_clicks(input: InputHandlerInput) {
+    let size = input.computed_data;
+    if input
+        .cursor_position_rel
+        .is_some_and(|pos| is_position_in_component(size, pos))
+    {
+        input.cursor_events.clear();
+    }
+}
 
-    // Update editor content
-    state.editor_mut().set_text_reactive(
-        &new_content,
-        &mut write_font_system(),
-        &glyphon::Attrs::new().family(glyphon::fontdb::Family::SansSerif),
+/// One entry of the [`completion_popup`].
+#[tessera]
+fn completion_row(
+    label: String,
+    highlighted: bool,
+    index: usize,
+    state: Arc<RwLock<TextEditorState>>,
+) {
+    let background = if highlighted {
+        default_selection_color()
+    } else {
+        Color::TRANSPARENT
+    };
+    surface(
+        SurfaceArgsBuilder::default()
+            .style(SurfaceStyle::Filled { color: background })
+            .shape(Shape::RECTANGLE)
+            .padding(Dp(4.0))
+            .on_click(Arc::new(move || {
+                state.write().accept_completion(index);
+            }))
+            .build()
+            .unwrap(),
+        None,
+        move || {
+            text(
+                TextArgsBuilder::default()
+                    .text(label)
+                    .size(Dp(14.0))
+                    .build()
+                    .unwrap(),
+            );
+        },
     );
 }
 
+/// The right-click menu of a [`text_editor`]. Clicking an enabled entry closes it and leaves
+/// the entry to be run by the editor, which has access to the clipboard.
+#[tessera]
+fn context_menu_popup(state: Arc<RwLock<TextEditorState>>, entries: Vec<ContextMenuEntry>) {
+    surface(popup_surface_args(Dp(4.0)), None, move || {
+        column(ColumnArgs::default(), |scope| {
+            for entry in entries {
+                let state = state.clone();
+                scope.child(move || context_menu_row(entry, state));
+            }
+        });
+    });
+    input_handler(Box::new(block_popup_clicks));
+}
+
+/// One entry of the [`context_menu_popup`], greyed out while it is disabled.
+#[tessera]
+fn context_menu_row(entry: ContextMenuEntry, state: Arc<RwLock<TextEditorState>>) {
+    let mut args = SurfaceArgsBuilder::default()
+        .style(SurfaceStyle::Filled {
+            color: Color::TRANSPARENT,
+        })
+        .shape(Shape::RECTANGLE)
+        .padding(Dp(4.0));
+    let color = if entry.enabled {
+        let choice = entry.choice;
+        args = args
+            .hover_style(Some(SurfaceStyle::Filled {
+                color: default_selection_color(),
+            }))
+            .on_click(Arc::new(move || {
+                state.write().choose_context_menu_entry(choice);
+            }));
+        Color::BLACK
+    } else {
+        Color::BLACK.with_alpha(0.38)
+    };
+    surface(args.build().unwrap(), None, move || {
+        text(
+            TextArgsBuilder::default()
+                .text(entry.label)
+                .color(color)
+                .size(Dp(14.0))
+                .build()
+                .unwrap(),
+        );
+    });
+}
+
+impl From<&TextEditorArgs> for EditorInputConfig {
+    fn from(args: &TextEditorArgs) -> Self {
+        Self {
+            on_change: args.on_change.clone(),
+            single_line: args.single_line,
+            tab_moves_focus: args.tab_moves_focus,
+            context_menu: args.context_menu,
+            context_menu_items: args.context_menu_items.clone(),
+            scrollbar_behavior: args.scrollbar_behavior.clone(),
+            on_submit: args.on_submit.clone(),
+            on_zoom_changed: args.on_zoom_changed.clone(),
+            on_overwrite_changed: args.on_overwrite_changed.clone(),
+            on_caret_move: args.on_caret_move.clone(),
+            on_diagnostic_hover: args.on_diagnostic_hover.clone(),
+            on_complete: args.on_complete.clone(),
+            on_link_activate: args.on_link_activate.clone(),
+            on_gutter_click: args.on_gutter_click.clone(),
+            breakpoints: args.breakpoints,
+            on_breakpoints_changed: args.on_breakpoints_changed.clone(),
+            on_edit_breakpoint_condition: args.on_edit_breakpoint_condition.clone(),
+            on_key: None,
+            on_input: None,
+        }
+    }
+}
+
 /// Create surface arguments based on editor configuration and state
 fn create_surface_args(
     args: &TextEditorArgs,
@@ -532,14 +1524,16 @@ fn create_surface_args(
         }
     };
 
-    SurfaceArgsBuilder::default()
+    let mut builder = SurfaceArgsBuilder::default()
         .style(style)
         .shape(args.shape)
         .padding(args.padding)
         .width(args.width)
-        .height(args.height)
-        .build()
-        .unwrap()
+        .height(args.height);
+    if let Some(duration) = args.focus_transition {
+        builder = builder.style_transition(state.write().focus_transition(duration));
+    }
+    builder.build().unwrap()
 }
 
 /// Determine background color based on focus state
@@ -547,12 +1541,15 @@ fn determine_background_color(
     args: &TextEditorArgs,
     state: &Arc<RwLock<TextEditorState>>,
 ) -> Color {
+    let scheme_background = args.color_scheme.map(|s| s.background);
     if state.read().focus_handler().is_focused() {
         args.focus_background_color
             .or(args.background_color)
+            .or(scheme_background)
             .unwrap_or(Color::WHITE) // Default white when focused
     } else {
         args.background_color
+            .or(scheme_background)
             .unwrap_or(Color::new(0.95, 0.95, 0.95, 1.0)) // Default light gray when not focused
     }
 }
@@ -565,7 +1562,7 @@ fn determine_border_color(
     if state.read().focus_handler().is_focused() {
         args.focus_border_color
             .or(args.border_color)
-            .or(Some(Color::new(0.0, 0.5, 1.0, 1.0))) // Default blue focus border
+            .or(Some(theme::accent_or(Color::new(0.0, 0.5, 1.0, 1.0)))) // Accent or blue
     } else {
         args.border_color.or(Some(Color::new(0.7, 0.7, 0.7, 1.0))) // Default gray border
     }
@@ -604,7 +1601,7 @@ impl TextEditorArgs {
 
     /// Creates a text editor with an emphasized border for better visibility.
     ///
-    /// - Border: 2px, blue focus border
+    /// - Border: 2px, focus border in the accent color or blue
     ///
     /// # Example
     /// ```
@@ -614,7 +1611,7 @@ impl TextEditorArgs {
     pub fn outlined() -> Self {
         Self::simple()
             .with_border_width(Dp(1.0))
-            .with_focus_border_color(Color::new(0.0, 0.5, 1.0, 1.0))
+            .with_focus_border_color(theme::accent_or(Color::new(0.0, 0.5, 1.0, 1.0)))
     }
 
     /// Creates a text editor with no border (minimal style).
@@ -799,6 +1796,21 @@ impl TextEditorArgs {
         self
     }
 
+    /// Animates the border and background over `duration` when the editor gains or loses
+    /// focus.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::outlined().with_focus_transition(Duration::from_millis(150));
+    /// ```
+    pub fn with_focus_transition(mut self, duration: Duration) -> Self {
+        self.focus_transition = Some(duration);
+        self
+    }
+
     /// Sets the selection highlight color.
     ///
     /// # Example
@@ -812,14 +1824,1009 @@ impl TextEditorArgs {
         self.selection_color = Some(color);
         self
     }
-}
 
-fn get_editor_content(editor: &glyphon::Editor) -> String {
-    editor.with_buffer(|buffer| {
-        buffer
-            .lines
-            .iter()
-            .map(|line| line.text().to_string() + line.ending().as_str())
-            .collect::<String>()
-    })
+    /// Sets the search match highlight color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// use tessera_ui::Color;
+    /// let args = TextEditorArgs::simple().with_search_highlight_color(Color::new(1.0, 0.6, 0.0, 0.4));
+    /// ```
+    pub fn with_search_highlight_color(mut self, color: Color) -> Self {
+        self.search_highlight_color = Some(color);
+        self
+    }
+
+    /// Sets the highlight color of the selected search match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_current_match_color(Color::new(1.0, 0.3, 0.0, 0.5));
+    /// ```
+    pub fn with_current_match_color(mut self, color: Color) -> Self {
+        self.current_match_color = Some(color);
+        self
+    }
+
+    /// Sets whether the search counter is shown while a search is active.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// // The application shows the counter in its own find bar
+    /// let args = TextEditorArgs::simple().with_show_search_counter(false);
+    /// ```
+    pub fn with_show_search_counter(mut self, show: bool) -> Self {
+        self.show_search_counter = show;
+        self
+    }
+
+    /// Sets the input mask used to format the content while typing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::{input_mask::InputMask, text_editor::TextEditorArgs};
+    /// let args = TextEditorArgs::simple().with_input_mask(InputMask::credit_card());
+    /// ```
+    pub fn with_input_mask(mut self, mask: InputMask) -> Self {
+        self.input_mask = Some(mask);
+        self
+    }
+
+    /// Sets the hint shown while the editor is empty and not focused.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_placeholder("Search…");
+    /// ```
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    /// Sets the color of the placeholder.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple()
+    ///     .with_placeholder("Name")
+    ///     .with_placeholder_color(Color::new(0.4, 0.4, 0.6, 1.0));
+    /// ```
+    pub fn with_placeholder_color(mut self, color: Color) -> Self {
+        self.placeholder_color = color;
+        self
+    }
+
+    /// Makes the editor read-only.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_read_only(true);
+    /// ```
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Sets how lines wider than the editor are broken.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::{TextEditorArgs, WrapMode};
+    /// let args = TextEditorArgs::simple().with_wrap(WrapMode::None);
+    /// ```
+    pub fn with_wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Sets the font family of the content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::{FontFamily, TextEditorArgs};
+    /// let args = TextEditorArgs::simple().with_font_family(FontFamily::Monospace);
+    /// ```
+    pub fn with_font_family(mut self, family: FontFamily) -> Self {
+        self.font_family = family;
+        self
+    }
+
+    /// Sets the OpenType features of the content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::{FontFamily, FontFeatures, TextEditorArgs};
+    /// let args = TextEditorArgs::simple()
+    ///     .with_font_family(FontFamily::Name("Fira Code".to_string()))
+    ///     .with_font_features(FontFeatures::new().with_ligatures(true).with_slashed_zero(true));
+    /// ```
+    pub fn with_font_features(mut self, features: FontFeatures) -> Self {
+        self.font_features = features;
+        self
+    }
+
+    /// Sets the font size of the content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Dp;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_font_size(Dp(13.0));
+    /// ```
+    pub fn with_font_size(mut self, size: Dp) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Sets the line height of the content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Dp;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple()
+    ///     .with_font_size(Dp(13.0))
+    ///     .with_line_height(Dp(20.0));
+    /// ```
+    pub fn with_line_height(mut self, line_height: Dp) -> Self {
+        self.line_height = Some(line_height);
+        self
+    }
+
+    /// Sets the color used to highlight matching brackets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// use tessera_ui::Color;
+    /// let args = TextEditorArgs::simple().with_bracket_highlight_color(Color::new(0.2, 0.8, 0.2, 0.4));
+    /// ```
+    pub fn with_bracket_highlight_color(mut self, color: Color) -> Self {
+        self.bracket_highlight_color = Some(color);
+        self
+    }
+
+    /// Sets the bracket pairs that are matched, replacing the default `()`, `[]` and `{}`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_bracket_pairs(vec![('(', ')'), ('<', '>')]);
+    /// ```
+    pub fn with_bracket_pairs(mut self, pairs: Vec<(char, char)>) -> Self {
+        self.bracket_pairs = pairs;
+        self
+    }
+
+    /// Highlights the line of the cursor with the given color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_current_line_color(Color::new(0.5, 0.5, 0.5, 0.1));
+    /// ```
+    pub fn with_current_line_color(mut self, color: Color) -> Self {
+        self.current_line_color = Some(color);
+        self
+    }
+
+    /// Enables or disables auto-indent of new lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_auto_indent(true);
+    /// ```
+    pub fn with_auto_indent(mut self, auto_indent: bool) -> Self {
+        self.auto_indent = auto_indent;
+        self
+    }
+
+    /// Enables or disables auto-closing of brackets and quotes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_auto_close(true);
+    /// ```
+    pub fn with_auto_close(mut self, auto_close: bool) -> Self {
+        self.auto_close = auto_close;
+        self
+    }
+
+    /// Sets the characters after which auto-indent adds an extra indent level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// // Python-style blocks
+    /// let args = TextEditorArgs::simple()
+    ///     .with_auto_indent(true)
+    ///     .with_indent_after(vec![':']);
+    /// ```
+    pub fn with_indent_after(mut self, chars: Vec<char>) -> Self {
+        self.indent_after = chars;
+        self
+    }
+
+    /// Sets the number of columns per indent level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_tab_width(2);
+    /// assert_eq!(args.tab_width, 2);
+    /// ```
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    /// Chooses between spaces and tab characters for Tab and auto-indent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// // Indent with real tabs, drawn 8 columns wide
+    /// let args = TextEditorArgs::simple()
+    ///     .with_insert_spaces(false)
+    ///     .with_tab_width(8);
+    /// ```
+    pub fn with_insert_spaces(mut self, insert_spaces: bool) -> Self {
+        self.insert_spaces = insert_spaces;
+        self
+    }
+
+    /// Sets the line-comment token that Ctrl+/ toggles on the selected lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_line_comment("#");
+    /// assert_eq!(args.line_comment.as_deref(), Some("#"));
+    /// ```
+    pub fn with_line_comment(mut self, token: impl Into<String>) -> Self {
+        self.line_comment = Some(token.into());
+        self
+    }
+
+    /// Sets the name or path of the edited file, which the language is detected from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_file_name("scripts/build.py");
+    /// assert_eq!(args.file_name.as_deref(), Some("scripts/build.py"));
+    /// ```
+    pub fn with_file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Sets the tree-sitter grammar and colors the content is highlighted with.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::{SyntaxHighlighting, TextEditorArgs};
+    /// let highlighting = SyntaxHighlighting::new(
+    ///     tree_sitter_json::LANGUAGE,
+    ///     tree_sitter_json::HIGHLIGHTS_QUERY,
+    ///     &[("string", Color::from_rgb_u8(10, 120, 40))],
+    /// )
+    /// .unwrap();
+    /// let args = TextEditorArgs::simple().with_syntax_highlighting(Arc::new(highlighting));
+    /// assert!(args.syntax_highlighting.is_some());
+    /// ```
+    #[cfg(feature = "tree-sitter")]
+    pub fn with_syntax_highlighting(mut self, highlighting: Arc<SyntaxHighlighting>) -> Self {
+        self.syntax_highlighting = Some(highlighting);
+        self
+    }
+
+    /// Sets the language of the content instead of detecting it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::{Language, TextEditorArgs};
+    /// let args = TextEditorArgs::simple().with_language_name("bash");
+    /// let language = Language::by_name(args.language_name.as_deref().unwrap());
+    /// assert_eq!(language.and_then(|language| language.line_comment), Some("#"));
+    /// ```
+    pub fn with_language_name(mut self, name: impl Into<String>) -> Self {
+        self.language_name = Some(name.into());
+        self
+    }
+
+    /// Sets the key bindings of the editor commands.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::key_chord::KeyChord;
+    /// use tessera_ui::winit::keyboard::ModifiersState;
+    /// use tessera_ui_basic_components::text_editor::{EditorCommand, EditorKeymap, TextEditorArgs};
+    /// let save_as_undo = KeyChord::character('s', ModifiersState::CONTROL);
+    /// let keymap = EditorKeymap::standard().with_binding([save_as_undo.clone()], EditorCommand::Undo);
+    /// let args = TextEditorArgs::simple().with_keymap(keymap);
+    /// assert_eq!(args.keymap.command_for(&[save_as_undo]), Some(EditorCommand::Undo));
+    /// ```
+    pub fn with_keymap(mut self, keymap: EditorKeymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Sets the columns at which ruler lines are drawn behind the text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_rulers(vec![80, 100]);
+    /// ```
+    pub fn with_rulers(mut self, columns: Vec<usize>) -> Self {
+        self.rulers = columns;
+        self
+    }
+
+    /// Sets the color of the ruler lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple()
+    ///     .with_rulers(vec![80])
+    ///     .with_ruler_color(Color::new(0.8, 0.2, 0.2, 0.3));
+    /// ```
+    pub fn with_ruler_color(mut self, color: Color) -> Self {
+        self.ruler_color = Some(color);
+        self
+    }
+
+    /// Recolors the text beyond the first ruler.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// // Gray out everything past column 100
+    /// let args = TextEditorArgs::simple()
+    ///     .with_rulers(vec![100])
+    ///     .with_overflow_color(Color::new(0.6, 0.6, 0.6, 1.0));
+    /// ```
+    pub fn with_overflow_color(mut self, color: Color) -> Self {
+        self.overflow_color = Some(color);
+        self
+    }
+
+    /// Marks spaces, tabs and line endings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_show_whitespace(true);
+    /// ```
+    pub fn with_show_whitespace(mut self, show: bool) -> Self {
+        self.show_whitespace = show;
+        self
+    }
+
+    /// Sets the color of the whitespace marks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple()
+    ///     .with_show_whitespace(true)
+    ///     .with_whitespace_color(Color::new(0.4, 0.6, 0.4, 0.6));
+    /// ```
+    pub fn with_whitespace_color(mut self, color: Color) -> Self {
+        self.whitespace_color = Some(color);
+        self
+    }
+
+    /// Sets when the scrollbars are shown.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::{scrollable::ScrollBarBehavior, text_editor::TextEditorArgs};
+    /// let args = TextEditorArgs::simple().with_scrollbar_behavior(ScrollBarBehavior::AutoHide);
+    /// ```
+    pub fn with_scrollbar_behavior(mut self, behavior: ScrollBarBehavior) -> Self {
+        self.scrollbar_behavior = behavior;
+        self
+    }
+
+    /// Sets the thickness of the scrollbars.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Dp;
+    /// use tessera_ui_basic_components::{scrollable::ScrollBarBehavior, text_editor::TextEditorArgs};
+    /// let args = TextEditorArgs::simple()
+    ///     .with_scrollbar_behavior(ScrollBarBehavior::AlwaysVisible)
+    ///     .with_scrollbar_thickness(Dp(12.0));
+    /// ```
+    pub fn with_scrollbar_thickness(mut self, thickness: Dp) -> Self {
+        self.scrollbar_thickness = thickness;
+        self
+    }
+
+    /// Sets the color of the scrollbar tracks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_scrollbar_track_color(Color::TRANSPARENT);
+    /// ```
+    pub fn with_scrollbar_track_color(mut self, color: Color) -> Self {
+        self.scrollbar_track_color = color;
+        self
+    }
+
+    /// Sets the color of the scrollbar thumbs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_scrollbar_thumb_color(Color::new(0.2, 0.4, 0.8, 0.5));
+    /// ```
+    pub fn with_scrollbar_thumb_color(mut self, color: Color) -> Self {
+        self.scrollbar_thumb_color = color;
+        self
+    }
+
+    /// Sets the color of a scrollbar thumb under the mouse pointer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args =
+    ///     TextEditorArgs::simple().with_scrollbar_thumb_hover_color(Color::new(0.2, 0.4, 0.8, 0.8));
+    /// ```
+    pub fn with_scrollbar_thumb_hover_color(mut self, color: Color) -> Self {
+        self.scrollbar_thumb_hover_color = color;
+        self
+    }
+
+    /// Sets the smallest and largest zoom factor reachable by user input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_zoom_range(0.8, 2.0);
+    /// assert_eq!(args.zoom_range, (0.8, 2.0));
+    /// ```
+    pub fn with_zoom_range(mut self, min: f32, max: f32) -> Self {
+        self.zoom_range = (min, max);
+        self
+    }
+
+    /// Sets the callback invoked with the new zoom factor when user input zooms the editor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_on_zoom_changed(Arc::new(|zoom| {
+    ///     println!("zoom: {:.0}%", zoom * 100.0);
+    /// }));
+    /// ```
+    pub fn with_on_zoom_changed(mut self, on_zoom_changed: Arc<dyn Fn(f32) + Send + Sync>) -> Self {
+        self.on_zoom_changed = Some(on_zoom_changed);
+        self
+    }
+
+    /// Sets the callback invoked when the Insert key toggles overwrite mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_on_overwrite_changed(Arc::new(|overwrite| {
+    ///     println!("{}", if overwrite { "OVR" } else { "INS" });
+    /// }));
+    /// ```
+    pub fn with_on_overwrite_changed(
+        mut self,
+        on_overwrite_changed: Arc<dyn Fn(bool) + Send + Sync>,
+    ) -> Self {
+        self.on_overwrite_changed = Some(on_overwrite_changed);
+        self
+    }
+
+    /// Sets the callback invoked when the primary caret moves.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_on_caret_move(Arc::new(|caret| {
+    ///     println!("Ln {}, Col {}", caret.line + 1, caret.column + 1);
+    /// }));
+    /// ```
+    pub fn with_on_caret_move(
+        mut self,
+        on_caret_move: Arc<dyn Fn(TextPosition) + Send + Sync>,
+    ) -> Self {
+        self.on_caret_move = Some(on_caret_move);
+        self
+    }
+
+    /// Sets the callback invoked when the diagnostic under the mouse pointer changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_on_diagnostic_hover(Arc::new(|diagnostic| {
+    ///     if let Some(diagnostic) = diagnostic {
+    ///         println!("{:?}: {}", diagnostic.severity, diagnostic.message);
+    ///     }
+    /// }));
+    /// ```
+    pub fn with_on_diagnostic_hover(
+        mut self,
+        on_diagnostic_hover: Arc<dyn Fn(Option<Diagnostic>) + Send + Sync>,
+    ) -> Self {
+        self.on_diagnostic_hover = Some(on_diagnostic_hover);
+        self
+    }
+
+    /// Sets the provider of completion items for the word before the cursor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::{CompletionItem, TextEditorArgs};
+    ///
+    /// const KEYWORDS: [&str; 4] = ["fn", "for", "let", "loop"];
+    /// let args = TextEditorArgs::simple().with_on_complete(Arc::new(|request| {
+    ///     KEYWORDS
+    ///         .iter()
+    ///         .filter(|keyword| keyword.starts_with(&request.prefix))
+    ///         .map(|&keyword| CompletionItem::new(keyword))
+    ///         .collect()
+    /// }));
+    /// ```
+    pub fn with_on_complete(
+        mut self,
+        on_complete: Arc<dyn Fn(CompletionRequest) -> Vec<CompletionItem> + Send + Sync>,
+    ) -> Self {
+        self.on_complete = Some(on_complete);
+        self
+    }
+
+    /// Sets the callback for Ctrl+clicks on URLs and file paths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_on_link_activate(Arc::new(|target| {
+    ///     println!("open {target}");
+    /// }));
+    /// ```
+    pub fn with_on_link_activate(
+        mut self,
+        on_link_activate: Arc<dyn Fn(String) + Send + Sync>,
+    ) -> Self {
+        self.on_link_activate = Some(on_link_activate);
+        self
+    }
+
+    /// Sets the patterns detected as links on top of URLs and file paths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::{LinkPattern, TextEditorArgs};
+    /// let args = TextEditorArgs::simple()
+    ///     .with_link_patterns(vec![LinkPattern::Numbered("#".to_string())]);
+    /// ```
+    pub fn with_link_patterns(mut self, patterns: Vec<LinkPattern>) -> Self {
+        self.link_patterns = patterns;
+        self
+    }
+
+    /// Sets the marks drawn in the gutter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::{GutterAnnotation, TextEditorArgs};
+    /// let breakpoint = GutterAnnotation::dot(4, Color::new(0.9, 0.2, 0.2, 1.0));
+    /// let changed = GutterAnnotation::bar(7, Color::new(0.2, 0.6, 0.9, 1.0));
+    /// let args = TextEditorArgs::simple().with_annotations(vec![breakpoint, changed]);
+    /// assert_eq!(args.annotations.len(), 2);
+    /// ```
+    pub fn with_annotations(mut self, annotations: Vec<GutterAnnotation>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Sets the rows of components shown between the lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::{LensRow, TextEditorArgs};
+    /// let args = TextEditorArgs::simple().with_lens_rows(vec![LensRow::new(3, || {})]);
+    /// assert_eq!(args.lens_rows[0].line, 3);
+    /// ```
+    pub fn with_lens_rows(mut self, lens_rows: Vec<LensRow>) -> Self {
+        self.lens_rows = lens_rows;
+        self
+    }
+
+    /// Sets the callback for clicks in the gutter, which shows the gutter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_on_gutter_click(Arc::new(|line| {
+    ///     println!("toggle breakpoint on line {}", line + 1);
+    /// }));
+    /// assert!(args.on_gutter_click.is_some());
+    /// ```
+    pub fn with_on_gutter_click(
+        mut self,
+        on_gutter_click: Arc<dyn Fn(usize) + Send + Sync>,
+    ) -> Self {
+        self.on_gutter_click = Some(on_gutter_click);
+        self
+    }
+
+    /// Turns on the breakpoint margin and sets the callback invoked with all breakpoints
+    /// after clicks in the gutter changed them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_breakpoints(Arc::new(|breakpoints| {
+    ///     for breakpoint in breakpoints.iter().filter(|b| b.enabled) {
+    ///         println!("break at line {}", breakpoint.line + 1);
+    ///     }
+    /// }));
+    /// assert!(args.breakpoints);
+    /// ```
+    pub fn with_breakpoints(
+        mut self,
+        on_breakpoints_changed: Arc<dyn Fn(Vec<Breakpoint>) + Send + Sync>,
+    ) -> Self {
+        self.breakpoints = true;
+        self.on_breakpoints_changed = Some(on_breakpoints_changed);
+        self
+    }
+
+    /// Sets the callback invoked with the line of a breakpoint whose condition the gutter
+    /// menu asks to add or edit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_on_edit_breakpoint_condition(Arc::new(|line| {
+    ///     println!("ask for the condition of line {}", line + 1);
+    /// }));
+    /// assert!(args.on_edit_breakpoint_condition.is_some());
+    /// ```
+    pub fn with_on_edit_breakpoint_condition(
+        mut self,
+        on_edit_breakpoint_condition: Arc<dyn Fn(usize) + Send + Sync>,
+    ) -> Self {
+        self.on_edit_breakpoint_condition = Some(on_edit_breakpoint_condition);
+        self
+    }
+
+    /// Sets the width of the gutter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Dp;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_gutter_width(Dp(24.0));
+    /// assert_eq!(args.gutter_width, Dp(24.0));
+    /// ```
+    pub fn with_gutter_width(mut self, width: Dp) -> Self {
+        self.gutter_width = width;
+        self
+    }
+
+    /// Sets whether the gutter shows the line numbers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_line_numbers(true);
+    /// assert!(args.line_numbers);
+    /// ```
+    pub fn with_line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    /// Sets the color of the line numbers and the wrap dashes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_line_number_color(Color::new(0.4, 0.4, 0.4, 1.0));
+    /// assert_eq!(args.line_number_color, Color::new(0.4, 0.4, 0.4, 1.0));
+    /// ```
+    pub fn with_line_number_color(mut self, color: Color) -> Self {
+        self.line_number_color = color;
+        self
+    }
+
+    /// Sets the color of the underline of a hovered link.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_link_color(Color::new(0.1, 0.6, 0.3, 1.0));
+    /// ```
+    pub fn with_link_color(mut self, color: Color) -> Self {
+        self.link_color = Some(color);
+        self
+    }
+
+    /// Sets the color of the labels of inlay hints.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_inlay_hint_color(Color::new(0.5, 0.5, 0.6, 1.0));
+    /// ```
+    pub fn with_inlay_hint_color(mut self, color: Color) -> Self {
+        self.inlay_hint_color = Some(color);
+        self
+    }
+
+    /// Sets whether Ctrl+G opens the go-to-line popup.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_go_to_line(true);
+    /// assert!(args.go_to_line);
+    /// ```
+    pub fn with_go_to_line(mut self, go_to_line: bool) -> Self {
+        self.go_to_line = go_to_line;
+        self
+    }
+
+    /// Sets how many lines stay visible above and below the caret.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_scroll_margin(3);
+    /// assert_eq!(args.scroll_margin, 3);
+    /// ```
+    pub fn with_scroll_margin(mut self, lines: usize) -> Self {
+        self.scroll_margin = lines;
+        self
+    }
+
+    /// Sets the color scheme that fills in the colors left unset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::{EditorColorScheme, TextEditorArgs};
+    /// let args = TextEditorArgs::default().with_color_scheme(EditorColorScheme::dark());
+    /// assert!(args.color_scheme.unwrap().is_dark());
+    /// ```
+    pub fn with_color_scheme(mut self, scheme: EditorColorScheme) -> Self {
+        self.color_scheme = Some(scheme);
+        self
+    }
+
+    /// Sets whether copying also puts the selection as HTML on the clipboard.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_copy_as_html(true);
+    /// assert!(args.copy_as_html);
+    /// ```
+    pub fn with_copy_as_html(mut self, copy_as_html: bool) -> Self {
+        self.copy_as_html = copy_as_html;
+        self
+    }
+
+    /// Makes the editor a single-line text field.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_single_line(true);
+    /// ```
+    pub fn with_single_line(mut self, single_line: bool) -> Self {
+        self.single_line = single_line;
+        self
+    }
+
+    /// Sets the callback for Enter in single-line mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple()
+    ///     .with_single_line(true)
+    ///     .with_on_submit(Arc::new(|query| println!("search for {query}")));
+    /// ```
+    pub fn with_on_submit(mut self, on_submit: Arc<dyn Fn(String) + Send + Sync>) -> Self {
+        self.on_submit = Some(on_submit);
+        self
+    }
+
+    /// Makes Tab unfocus a single-line editor and pass the key on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple()
+    ///     .with_single_line(true)
+    ///     .with_tab_moves_focus(true);
+    /// ```
+    pub fn with_tab_moves_focus(mut self, tab_moves_focus: bool) -> Self {
+        self.tab_moves_focus = tab_moves_focus;
+        self
+    }
+
+    /// Sets whether right-clicking opens the context menu.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::TextEditorArgs;
+    /// let args = TextEditorArgs::simple().with_context_menu(false);
+    /// ```
+    pub fn with_context_menu(mut self, context_menu: bool) -> Self {
+        self.context_menu = context_menu;
+        self
+    }
+
+    /// Sets the entries added to the context menu after the editing commands.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui_basic_components::text_editor::{ContextMenuItem, TextEditorArgs};
+    /// let args = TextEditorArgs::simple().with_context_menu_items(vec![ContextMenuItem::new(
+    ///     "Look Up",
+    ///     || println!("look up the selection"),
+    /// )]);
+    /// ```
+    pub fn with_context_menu_items(mut self, items: Vec<ContextMenuItem>) -> Self {
+        self.context_menu_items = items;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_width() {
+        assert_eq!(
+            without_width(DimensionValue::Fixed(Px(100)), Px(30)),
+            DimensionValue::Fixed(Px(70))
+        );
+        assert_eq!(
+            without_width(
+                DimensionValue::Fill {
+                    min: Some(Px(20)),
+                    max: None
+                },
+                Px(30)
+            ),
+            DimensionValue::Fill {
+                min: Some(Px(0)),
+                max: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_input_config_follows_the_args() {
+        let args = TextEditorArgsBuilder::default()
+            .single_line(true)
+            .tab_moves_focus(true)
+            .build()
+            .unwrap();
+        let config = EditorInputConfig::from(&args);
+        assert!(config.single_line);
+        assert!(config.tab_moves_focus);
+        assert!(!config.breakpoints);
+        assert!(config.on_key.is_none());
+    }
+
+    #[test]
+    fn test_line_number_width_grows_with_the_line_count() {
+        let mut state = TextEditorState::new(Dp(14.0), None);
+        state.set_text_reactive("a\nb");
+        let narrow = line_number_width(&state);
+        state.set_text_reactive(&"a\n".repeat(1000));
+        assert!(line_number_width(&state) > narrow);
+    }
 }
@@ -50,6 +50,7 @@ fn register_node_tokens(crate_path: &syn::Path, fn_name: &syn::Ident) -> proc_ma
                         fn_name: stringify!(#fn_name).to_string(),
                         measure_fn: None,
                         input_handler_fn: None,
+                        test_id: None,
                     }
                 )
             });
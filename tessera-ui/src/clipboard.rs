@@ -8,6 +8,8 @@
 //!
 //! - **Set Text**: Place a string onto the system clipboard.
 //! - **Get Text**: Retrieve the current text content from the system clipboard.
+//! - **Set HTML**: Place formatted text onto the clipboard, with a plain text version for
+//!   targets that do not accept HTML.
 //! - **Cross-platform**: Uses `arboard` for broad platform support (Windows, macOS, Linux).
 //! - **Graceful Fallback**: On unsupported platforms like Android, operations are no-ops
 //!   that log a warning, preventing crashes.
@@ -137,6 +139,36 @@ impl Clipboard {
         }
     }
 
+    /// Sets the clipboard to `html`, overwriting any previous content. Rich text targets
+    /// paste the formatted version and others paste `alt_text`.
+    ///
+    /// Only the plain text is stored on Android.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - An HTML fragment, e.g. `<b>Hello</b>, world!`.
+    /// * `alt_text` - The same content as plain text.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use tessera_ui::clipboard::Clipboard;
+    ///
+    /// let mut clipboard = Clipboard::new();
+    /// clipboard.set_html("<b>Hello</b>, world!", "Hello, world!");
+    /// ```
+    pub fn set_html(&mut self, html: &str, alt_text: &str) {
+        #[cfg(not(target_os = "android"))]
+        {
+            let _ = self.manager.set_html(html, Some(alt_text));
+        }
+        #[cfg(target_os = "android")]
+        {
+            let _ = html;
+            set_clipboard_text(&self.android_app, alt_text);
+        }
+    }
+
     /// Gets the current text content from the clipboard.
     ///
     /// This method retrieves text from the clipboard. If the clipboard is empty, contains
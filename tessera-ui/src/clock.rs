@@ -0,0 +1,145 @@
+//! # Frame Clock
+//!
+//! The time components animate and time their input against, e.g. the blinking of a text
+//! cursor, the progress of a transition, the interval of a double click or the delay of a
+//! debounce.
+//!
+//! The renderer samples the system clock once at the start of every frame, so all components
+//! of a frame see the same [`now`], however long the frame takes to build. Outside of a frame
+//! [`now`] is the time of the last one. Input events are stamped with [`input_time`] as they
+//! arrive.
+//!
+//! Tests and replays take over the clock with [`manual`], after which time only moves when
+//! they advance it, one [frame](FRAME_DURATION) or any duration at a time. That makes
+//! animations and timing-dependent input reproducible, e.g. a test can press twice with
+//! [`input_time`] stamps a few frames apart to double click:
+//!
+//! ```
+//! use std::time::Duration;
+//! use tessera_ui::clock;
+//!
+//! let clock = clock::manual();
+//! let start = clock::now();
+//! for _ in 0..3 {
+//!     clock.advance_frame();
+//! }
+//! assert_eq!(clock::now() - start, Duration::from_millis(48));
+//!
+//! // Dropping the manual clock goes back to the system clock
+//! drop(clock);
+//! ```
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use parking_lot::{Mutex, MutexGuard, RwLock};
+
+/// Time a frame advances a [`ManualClock`] in [`ManualClock::advance_frame`], about 60 frames
+/// per second.
+pub const FRAME_DURATION: Duration = Duration::from_millis(16);
+
+/// The time of the current frame, `None` until the first one.
+static FRAME_TIME: RwLock<Option<Instant>> = RwLock::new(None);
+
+/// Held by the [`ManualClock`] in use, so tests running in parallel take turns with it.
+static MANUAL: Mutex<()> = Mutex::new(());
+
+/// Whether a [`ManualClock`] is in use, which stops the renderer from sampling the system clock.
+static IS_MANUAL: AtomicBool = AtomicBool::new(false);
+
+/// Returns the time of the current frame.
+///
+/// Use it instead of [`Instant::now`] for anything that should follow a [`manual`] clock.
+/// Before the first frame it is the system time.
+pub fn now() -> Instant {
+    FRAME_TIME.read().unwrap_or_else(Instant::now)
+}
+
+/// Returns the time passed since `earlier` by the frame clock, zero if `earlier` is later.
+///
+/// It replaces [`Instant::elapsed`], which always reads the system clock.
+pub fn elapsed(earlier: Instant) -> Duration {
+    now().saturating_duration_since(earlier)
+}
+
+/// Returns the time to stamp an input event with when it arrives: the system time, as events
+/// come in between frames, or the time of a [`ManualClock`] while one is in use.
+pub fn input_time() -> Instant {
+    if IS_MANUAL.load(Ordering::Acquire) {
+        now()
+    } else {
+        Instant::now()
+    }
+}
+
+/// Samples the system clock as the time of the frame about to be built, unless a
+/// [`ManualClock`] is in use.
+pub(crate) fn begin_frame() {
+    if !IS_MANUAL.load(Ordering::Acquire) {
+        *FRAME_TIME.write() = Some(Instant::now());
+    }
+}
+
+/// Takes over the frame clock until the returned [`ManualClock`] is dropped. The time stands
+/// still at the current [`now`] until it is advanced.
+///
+/// Only one manual clock exists at a time: this blocks until the previous one is dropped.
+pub fn manual() -> ManualClock {
+    let guard = MANUAL.lock();
+    let mut frame_time = FRAME_TIME.write();
+    frame_time.get_or_insert_with(Instant::now);
+    IS_MANUAL.store(true, Ordering::Release);
+    ManualClock { _guard: guard }
+}
+
+/// A frame clock that only moves when it is advanced, see [`manual`].
+pub struct ManualClock {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl ManualClock {
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        let mut frame_time = FRAME_TIME.write();
+        *frame_time = frame_time.map(|time| time + duration);
+    }
+
+    /// Moves the clock forward by one [frame](FRAME_DURATION).
+    pub fn advance_frame(&self) {
+        self.advance(FRAME_DURATION);
+    }
+}
+
+impl Drop for ManualClock {
+    fn drop(&mut self) {
+        IS_MANUAL.store(false, Ordering::Release);
+        *FRAME_TIME.write() = Some(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = manual();
+        let start = now();
+        // Frames built meanwhile do not move it
+        begin_frame();
+        assert_eq!(now(), start);
+        assert_eq!(input_time(), start);
+
+        clock.advance_frame();
+        clock.advance(Duration::from_millis(4));
+        assert_eq!(now() - start, Duration::from_millis(20));
+        assert_eq!(elapsed(start), Duration::from_millis(20));
+        assert_eq!(elapsed(start + Duration::from_secs(1)), Duration::ZERO);
+
+        drop(clock);
+        begin_frame();
+        assert!(now() >= start);
+    }
+}
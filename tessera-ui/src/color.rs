@@ -10,6 +10,14 @@
 //! modern graphics rendering. This ensures consistent color reproduction across different
 //! devices and platforms.
 //!
+//! # Color Models and Palettes
+//!
+//! Colors convert to and from HSL, HSV and the perceptually uniform OKLCH. Shades are
+//! derived in OKLCH with [`Color::lighten`], [`Color::darken`] and [`Color::mix`], and a
+//! [`TonalPalette`] gives the shades of one hue from black to white. For text and icons,
+//! [`Color::contrast_ratio`] measures the WCAG contrast with a background and
+//! [`Color::on_color`] picks black or white to draw on it.
+//!
 //! # Usage
 //!
 //! ```
@@ -27,6 +35,9 @@
 //! let from_bytes = Color::from_rgba_u8(128, 64, 192, 255);
 //! let from_rgb_bytes = Color::from_rgb_u8(100, 150, 200);
 //!
+//! // Create colors from sRGB-encoded values, e.g. taken from CSS or a design tool
+//! let from_css = Color::from_srgb(0.9, 0.4, 0.1, 1.0);
+//!
 //! // Convert from arrays
 //! let from_array: Color = [0.1, 0.2, 0.3, 0.4].into();
 //! let to_array: [f32; 4] = custom_color.into();
@@ -228,6 +239,71 @@ impl Color {
         Self::from_rgba_u8(r, g, b, 255)
     }
 
+    /// Creates a new `Color` from sRGB-encoded components, as found in CSS, design tools and
+    /// color pickers, converting them to linear sRGB.
+    ///
+    /// Components outside `[0.0, 1.0]` are converted with the extended sRGB curve, which is
+    /// mirrored below zero, so colors outside of the sRGB gamut survive the round trip.
+    /// Alpha is not encoded and is kept as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// // CSS `rgb(50% 50% 50%)` is a lot darker than half of white in linear light
+    /// let gray = Color::from_srgb(0.5, 0.5, 0.5, 1.0);
+    /// assert!((gray.r - 0.214).abs() < 0.001);
+    /// assert_eq!(Color::from_srgb(1.0, 0.0, 0.0, 0.5), Color::new(1.0, 0.0, 0.0, 0.5));
+    /// ```
+    #[must_use]
+    pub fn from_srgb(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self {
+            r: srgb_to_linear(r),
+            g: srgb_to_linear(g),
+            b: srgb_to_linear(b),
+            a,
+        }
+    }
+
+    /// Creates a new `Color` from linear sRGB components, the space `Color` is stored in.
+    ///
+    /// The same as [`Color::new`], spelled out for code that also uses [`Color::from_srgb`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// assert_eq!(Color::from_linear(0.2, 0.4, 0.6, 1.0), Color::new(0.2, 0.4, 0.6, 1.0));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn from_linear(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::new(r, g, b, a)
+    }
+
+    /// Converts the color to sRGB-encoded `[r, g, b, a]`, the inverse of [`Color::from_srgb`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// let [r, g, b, a] = Color::from_srgb(0.25, 0.5, 0.75, 1.0).to_srgb();
+    /// assert!((r - 0.25).abs() < 1e-5 && (g - 0.5).abs() < 1e-5 && (b - 0.75).abs() < 1e-5);
+    /// assert_eq!(a, 1.0);
+    /// ```
+    #[must_use]
+    pub fn to_srgb(self) -> [f32; 4] {
+        [
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+            self.a,
+        ]
+    }
+
     /// Converts the color to an array of `[f32; 4]`.
     ///
     /// This is useful for interfacing with graphics APIs and shaders that
@@ -311,6 +387,428 @@ impl Color {
             a: (other.a - self.a).mul_add(t, self.a),
         }
     }
+
+    // --- Color Models ---
+
+    /// Creates a color from hue, saturation and lightness, as CSS `hsl()` does.
+    ///
+    /// `hue` is in degrees and wraps around; `saturation` and `lightness` are in
+    /// `[0.0, 1.0]`. Like in CSS, the model describes sRGB-encoded values, which are
+    /// converted to linear sRGB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// assert_eq!(Color::from_hsl(0.0, 1.0, 0.5, 1.0), Color::RED);
+    /// assert_eq!(Color::from_hsl(240.0, 1.0, 0.5, 1.0), Color::BLUE);
+    /// assert_eq!(Color::from_hsl(120.0, 0.0, 1.0, 1.0), Color::WHITE);
+    /// ```
+    #[must_use]
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Self {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let [r, g, b] = from_hue_chroma(hue, chroma, lightness - chroma / 2.0);
+        Self::from_srgb(r, g, b, alpha)
+    }
+
+    /// Converts the color to `[hue, saturation, lightness, alpha]`, the inverse of
+    /// [`Color::from_hsl`]. The hue of grays is `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// let [h, s, l, a] = Color::from_hsl(200.0, 0.6, 0.3, 1.0).to_hsl();
+    /// assert!((h - 200.0).abs() < 0.01 && (s - 0.6).abs() < 1e-4 && (l - 0.3).abs() < 1e-4);
+    /// assert_eq!(a, 1.0);
+    /// ```
+    #[must_use]
+    pub fn to_hsl(self) -> [f32; 4] {
+        let [r, g, b, a] = self.to_srgb();
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+        let saturation = if delta == 0.0 {
+            0.0
+        } else {
+            delta / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        [hue_of(r, g, b, max, delta), saturation, lightness, a]
+    }
+
+    /// Creates a color from hue, saturation and value, as color pickers commonly show them.
+    ///
+    /// `hue` is in degrees and wraps around; `saturation` and `value` are in `[0.0, 1.0]`.
+    /// The model describes sRGB-encoded values, which are converted to linear sRGB.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// assert_eq!(Color::from_hsv(120.0, 1.0, 1.0, 1.0), Color::GREEN);
+    /// assert_eq!(Color::from_hsv(0.0, 0.0, 0.0, 1.0), Color::BLACK);
+    /// ```
+    #[must_use]
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Self {
+        let chroma = value * saturation;
+        let [r, g, b] = from_hue_chroma(hue, chroma, value - chroma);
+        Self::from_srgb(r, g, b, alpha)
+    }
+
+    /// Converts the color to `[hue, saturation, value, alpha]`, the inverse of
+    /// [`Color::from_hsv`]. The hue of grays is `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// let [h, s, v, _] = Color::from_srgb(1.0, 0.5, 0.0, 1.0).to_hsv();
+    /// assert!((h - 30.0).abs() < 0.01 && (s - 1.0).abs() < 1e-4 && (v - 1.0).abs() < 1e-4);
+    /// ```
+    #[must_use]
+    pub fn to_hsv(self) -> [f32; 4] {
+        let [r, g, b, a] = self.to_srgb();
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let delta = max - min;
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+        [hue_of(r, g, b, max, delta), saturation, max, a]
+    }
+
+    /// Creates a color from OKLCH lightness, chroma and hue, as CSS `oklch()` does.
+    ///
+    /// OKLCH is perceptually uniform: colors of the same lightness look equally light
+    /// whatever their hue, which makes it the model to derive shades and palettes in.
+    /// `lightness` is in `[0.0, 1.0]`, `chroma` starts at `0.0` for grays and stays below
+    /// about `0.37` for colors in sRGB, and `hue` is in degrees.
+    ///
+    /// Colors outside of the sRGB gamut keep components outside of `[0.0, 1.0]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// let white = Color::from_oklch(1.0, 0.0, 0.0, 1.0);
+    /// assert!((white.r - 1.0).abs() < 1e-4 && (white.b - 1.0).abs() < 1e-4);
+    /// ```
+    #[must_use]
+    pub fn from_oklch(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Self {
+        let (sin, cos) = hue.to_radians().sin_cos();
+        let [r, g, b] = oklab_to_linear(lightness, chroma * cos, chroma * sin);
+        Self::new(r, g, b, alpha)
+    }
+
+    /// Converts the color to `[lightness, chroma, hue, alpha]` in OKLCH, the inverse of
+    /// [`Color::from_oklch`]. The hue is in `[0.0, 360.0)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// let [l, c, h, _] = Color::from_oklch(0.6, 0.15, 250.0, 1.0).to_oklch();
+    /// assert!((l - 0.6).abs() < 1e-4 && (c - 0.15).abs() < 1e-4 && (h - 250.0).abs() < 0.01);
+    /// ```
+    #[must_use]
+    pub fn to_oklch(self) -> [f32; 4] {
+        let [lightness, a, b] = linear_to_oklab(self.r, self.g, self.b);
+        let hue = b.atan2(a).to_degrees().rem_euclid(360.0);
+        [lightness, a.hypot(b), hue, self.a]
+    }
+
+    // --- Adjustments ---
+
+    /// Returns the color made lighter by `amount` of OKLCH lightness, e.g. `0.1` for a
+    /// shade a tenth of the way from black to white lighter. The hue is kept, and the
+    /// chroma as far as sRGB can show it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// let blue = Color::from_srgb(0.2, 0.4, 0.8, 1.0);
+    /// assert!(blue.lighten(0.1).to_oklch()[0] > blue.to_oklch()[0]);
+    /// // Lightness stops at white
+    /// assert!(Color::WHITE.lighten(0.2).r > 0.999);
+    /// ```
+    #[must_use]
+    pub fn lighten(self, amount: f32) -> Self {
+        let [lightness, chroma, hue, alpha] = self.to_oklch();
+        oklch_in_gamut(lightness + amount, chroma, hue, alpha)
+    }
+
+    /// Returns the color made darker by `amount` of OKLCH lightness, see
+    /// [`Color::lighten`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// let blue = Color::from_srgb(0.2, 0.4, 0.8, 1.0);
+    /// assert!(blue.darken(0.08).relative_luminance() < blue.relative_luminance());
+    /// assert_eq!(Color::BLACK.darken(0.5), Color::BLACK);
+    /// ```
+    #[must_use]
+    pub fn darken(self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// Mixes the color with `other` in OKLab, `t` of the way to `other`.
+    ///
+    /// Unlike [`Color::lerp`], which blends linear light, the mix goes through evenly
+    /// spaced perceived colors, like CSS `color-mix(in oklab, ...)`: halfway between blue
+    /// and white is a light blue rather than a washed-out lavender.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// let gray = Color::BLACK.mix(Color::WHITE, 0.5);
+    /// assert!((gray.to_oklch()[0] - 0.5).abs() < 1e-4);
+    /// assert_eq!(Color::RED.mix(Color::BLUE, 0.0), Color::RED);
+    /// ```
+    #[must_use]
+    pub fn mix(self, other: Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        if t == 0.0 {
+            return self;
+        }
+        if t == 1.0 {
+            return other;
+        }
+        let from = linear_to_oklab(self.r, self.g, self.b);
+        let to = linear_to_oklab(other.r, other.g, other.b);
+        let [l, a, b] = std::array::from_fn(|i| (to[i] - from[i]).mul_add(t, from[i]));
+        let [r, g, b] = oklab_to_linear(l, a, b);
+        Self::new(r, g, b, (other.a - self.a).mul_add(t, self.a))
+    }
+
+    // --- Contrast ---
+
+    /// Returns the relative luminance of the color as defined by WCAG, from `0.0` for
+    /// black to `1.0` for white. Alpha is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// assert!((Color::WHITE.relative_luminance() - 1.0).abs() < 1e-6);
+    /// assert!(Color::GREEN.relative_luminance() > Color::RED.relative_luminance());
+    /// ```
+    #[must_use]
+    pub fn relative_luminance(self) -> f32 {
+        let clamp = |c: f32| c.clamp(0.0, 1.0);
+        0.2126 * clamp(self.r) + 0.7152 * clamp(self.g) + 0.0722 * clamp(self.b)
+    }
+
+    /// Returns the WCAG contrast ratio between the color and `other`, from `1.0` for the
+    /// same luminance to `21.0` for black on white. The order of the colors does not
+    /// matter, and alpha is ignored.
+    ///
+    /// WCAG asks for at least 4.5 between body text and its background, and 3.0 for large
+    /// text and user interface elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// assert!((Color::BLACK.contrast_ratio(Color::WHITE) - 21.0).abs() < 1e-4);
+    /// let gray = Color::from_srgb(0.45, 0.45, 0.45, 1.0);
+    /// assert!(gray.contrast_ratio(Color::WHITE) > 4.5);
+    /// ```
+    #[must_use]
+    pub fn contrast_ratio(self, other: Self) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Returns black or white, whichever contrasts more with the color, for text and icons
+    /// drawn on it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tessera_ui::Color;
+    ///
+    /// assert_eq!(Color::from_srgb(0.1, 0.2, 0.5, 1.0).on_color(), Color::WHITE);
+    /// assert_eq!(Color::from_srgb(1.0, 0.85, 0.2, 1.0).on_color(), Color::BLACK);
+    /// ```
+    #[must_use]
+    pub fn on_color(self) -> Self {
+        if self.contrast_ratio(Self::WHITE) >= self.contrast_ratio(Self::BLACK) {
+            Self::WHITE
+        } else {
+            Self::BLACK
+        }
+    }
+}
+
+/// The shades of one hue from black to white, to derive the colors of a theme from a single
+/// seed color, e.g. the [accent color](crate::theme::accent_palette).
+///
+/// A tone is an OKLCH lightness from `0.0` (black) to `100.0` (white), so tones the same
+/// distance apart look the same steps apart for every hue. All tones keep the hue and chroma
+/// of the seed, with the chroma lowered where sRGB cannot show it at that lightness.
+///
+/// # Examples
+///
+/// ```
+/// use tessera_ui::{Color, color::TonalPalette};
+///
+/// let palette = TonalPalette::new(Color::from_srgb(0.0, 0.48, 1.0, 1.0));
+/// let container = palette.tone(90.0);
+/// let on_container = palette.tone(10.0);
+/// assert!(container.contrast_ratio(on_container) > 7.0);
+/// assert_eq!(palette.tone(0.0), Color::BLACK);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TonalPalette {
+    hue: f32,
+    chroma: f32,
+}
+
+impl TonalPalette {
+    /// Creates the palette of the hue and chroma of `seed`; its lightness and alpha do not
+    /// matter.
+    #[must_use]
+    pub fn new(seed: Color) -> Self {
+        let [_, chroma, hue, _] = seed.to_oklch();
+        Self { hue, chroma }
+    }
+
+    /// Returns the opaque color of `tone`, clamped to `[0.0, 100.0]`.
+    #[must_use]
+    pub fn tone(&self, tone: f32) -> Color {
+        oklch_in_gamut(tone.clamp(0.0, 100.0) / 100.0, self.chroma, self.hue, 1.0)
+    }
+}
+
+/// Decodes an sRGB-encoded component to linear light, mirrored for negative values.
+fn srgb_to_linear(c: f32) -> f32 {
+    let magnitude = c.abs();
+    let linear = if magnitude <= 0.04045 {
+        magnitude / 12.92
+    } else {
+        ((magnitude + 0.055) / 1.055).powf(2.4)
+    };
+    linear.copysign(c)
+}
+
+/// Encodes a linear component with the sRGB curve, mirrored for negative values.
+fn linear_to_srgb(c: f32) -> f32 {
+    let magnitude = c.abs();
+    let encoded = if magnitude <= 0.0031308 {
+        magnitude * 12.92
+    } else {
+        1.055 * magnitude.powf(1.0 / 2.4) - 0.055
+    };
+    encoded.copysign(c)
+}
+
+/// Returns the sRGB-encoded `[r, g, b]` with `hue` in degrees, `chroma` the difference
+/// between the largest and smallest component and `min` the smallest one.
+fn from_hue_chroma(hue: f32, chroma: f32, min: f32) -> [f32; 3] {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let second = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let [r, g, b] = match sector as u32 {
+        0 => [chroma, second, 0.0],
+        1 => [second, chroma, 0.0],
+        2 => [0.0, chroma, second],
+        3 => [0.0, second, chroma],
+        4 => [second, 0.0, chroma],
+        _ => [chroma, 0.0, second],
+    };
+    [r + min, g + min, b + min]
+}
+
+/// Returns the hue in degrees of the sRGB-encoded `r`, `g` and `b`, whose largest component
+/// is `max` and whose largest and smallest ones are `delta` apart.
+fn hue_of(r: f32, g: f32, b: f32, max: f32, delta: f32) -> f32 {
+    if delta == 0.0 {
+        return 0.0;
+    }
+    let sector = if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    sector * 60.0
+}
+
+/// Converts linear sRGB to OKLab `[l, a, b]`.
+fn linear_to_oklab(r: f32, g: f32, b: f32) -> [f32; 3] {
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+/// Converts OKLab to linear sRGB `[r, g, b]`.
+fn oklab_to_linear(l: f32, a: f32, b: f32) -> [f32; 3] {
+    let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+    let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+    let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+    [
+        4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_,
+        -1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_,
+        -0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_,
+    ]
+}
+
+/// Creates the color of OKLCH `lightness`, `chroma` and `hue` with the chroma lowered as far
+/// as needed to fit into sRGB, and the lightness clamped to `[0.0, 1.0]`.
+fn oklch_in_gamut(lightness: f32, chroma: f32, hue: f32, alpha: f32) -> Color {
+    const EPSILON: f32 = 1e-4;
+    let lightness = lightness.clamp(0.0, 1.0);
+    // Black and white have no hue
+    if lightness == 0.0 || lightness == 1.0 {
+        return Color::new(lightness, lightness, lightness, alpha);
+    }
+    let in_gamut = |color: Color| {
+        [color.r, color.g, color.b]
+            .iter()
+            .all(|c| (-EPSILON..=1.0 + EPSILON).contains(c))
+    };
+    let color = Color::from_oklch(lightness, chroma, hue, alpha);
+    if in_gamut(color) {
+        return clamp_components(color);
+    }
+    // Bisect the largest chroma that fits
+    let (mut low, mut high) = (0.0, chroma);
+    for _ in 0..16 {
+        let mid = (low + high) / 2.0;
+        if in_gamut(Color::from_oklch(lightness, mid, hue, alpha)) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    clamp_components(Color::from_oklch(lightness, low, hue, alpha))
+}
+
+/// Clamps the color components to `[0.0, 1.0]`, dropping rounding errors of conversions.
+fn clamp_components(color: Color) -> Color {
+    Color::new(
+        color.r.clamp(0.0, 1.0),
+        color.g.clamp(0.0, 1.0),
+        color.b.clamp(0.0, 1.0),
+        color.a,
+    )
 }
 
 /// The default color is fully transparent.
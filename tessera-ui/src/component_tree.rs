@@ -42,6 +42,8 @@ pub struct ComponentTree {
     metadatas: ComponentNodeMetaDatas,
     /// Used to remember the current node
     node_queue: Vec<indextree::NodeId>,
+    /// Test id given to the next node added
+    next_test_id: Option<String>,
 }
 
 impl Default for ComponentTree {
@@ -60,6 +62,7 @@ impl ComponentTree {
             tree,
             node_queue,
             metadatas,
+            next_test_id: None,
         }
     }
 
@@ -68,6 +71,7 @@ impl ComponentTree {
         self.tree.clear();
         self.metadatas.clear();
         self.node_queue.clear();
+        self.next_test_id = None;
     }
 
     /// Get node by NodeId
@@ -96,7 +100,10 @@ impl ComponentTree {
     /// Add a new node to the tree
     /// Nodes now store their intrinsic constraints in their metadata.
     /// The `node_component` itself primarily holds the measure_fn.
-    pub fn add_node(&mut self, node_component: ComponentNode) {
+    pub fn add_node(&mut self, mut node_component: ComponentNode) {
+        if let Some(test_id) = self.next_test_id.take() {
+            node_component.test_id = Some(test_id);
+        }
         let new_node_id = self.tree.new_node(node_component);
         if let Some(current_node_id) = self.node_queue.last_mut() {
             current_node_id.append(new_node_id, &mut self.tree);
@@ -109,6 +116,50 @@ impl ComponentTree {
     /// Pop the last node from the queue
     pub fn pop_node(&mut self) {
         self.node_queue.pop();
+        // A test id is only for the children of the node that set it
+        self.next_test_id = None;
+    }
+
+    /// Gives the next node added the test id `test_id`
+    pub fn set_next_test_id(&mut self, test_id: String) {
+        self.next_test_id = Some(test_id);
+    }
+
+    /// Find the first node, in build order, with the test id `test_id`
+    pub fn find_by_test_id(&self, test_id: &str) -> Option<indextree::NodeId> {
+        self.node_ids()
+            .find(|&id| self.get(id).unwrap().test_id.as_deref() == Some(test_id))
+    }
+
+    /// Find all nodes, in build order, of the component function named `role`
+    pub fn find_all_by_role(&self, role: &str) -> Vec<indextree::NodeId> {
+        self.node_ids()
+            .filter(|&id| self.get(id).unwrap().fn_name == role)
+            .collect()
+    }
+
+    /// Iterate over the ids of the nodes from the root down, in build order
+    fn node_ids(&self) -> impl Iterator<Item = indextree::NodeId> + '_ {
+        self.root()
+            .into_iter()
+            .flat_map(|root| root.descendants(&self.tree))
+    }
+
+    /// Get the id of the root node, if the tree is not empty
+    pub(crate) fn root(&self) -> Option<indextree::NodeId> {
+        self.tree.get_node_id_at(NonZero::new(1).unwrap())
+    }
+
+    /// Get the underlying tree, e.g. to measure it
+    #[cfg(feature = "proptest")]
+    pub(crate) fn nodes(&self) -> &ComponentNodeTree {
+        &self.tree
+    }
+
+    /// Get the metadatas of the nodes, filled in by measuring
+    #[cfg(feature = "proptest")]
+    pub(crate) fn metadatas(&self) -> &ComponentNodeMetaDatas {
+        &self.metadatas
     }
 
     /// Compute the ComponentTree into a list of rendering commands
@@ -136,7 +187,7 @@ impl ComponentTree {
             gpu,
             clipboard,
         } = params;
-        let Some(root_node) = self.tree.get_node_id_at(NonZero::new(1).unwrap()) else {
+        let Some(root_node) = self.root() else {
             return (vec![], WindowRequests::default());
         };
         let screen_constraint = Constraint::new(
@@ -187,6 +238,7 @@ impl ComponentTree {
         let input_handler_timer = Instant::now();
         let mut window_requests = WindowRequests::default();
         debug!("Start executing input handlers...");
+        crate::focus_state::begin_traversal_frame();
 
         for node_id in root_node
             .reverse_traverse(&self.tree)
@@ -196,6 +248,9 @@ impl ComponentTree {
             })
         {
             let metadata = self.metadatas.get(&node_id).unwrap();
+            if metadata.input_disabled {
+                continue;
+            }
 
             let Some(input_handler) = self
                 .tree
@@ -240,7 +295,7 @@ impl ComponentTree {
                 if let Some(ref mut ime_request) = window_requests.ime_request
                     && ime_request.position.is_none()
                 {
-                    ime_request.position = Some(abs_pos);
+                    ime_request.position = Some(abs_pos + ime_request.offset);
                 }
             } else {
                 warn!(
@@ -283,6 +338,7 @@ fn compute_draw_commands_parallel(
         screen_width,
         screen_height,
         None,
+        false,
     )
 }
 
@@ -296,6 +352,7 @@ fn compute_draw_commands_inner_parallel(
     screen_width: i32,
     screen_height: i32,
     clip_rect: Option<PxRect>,
+    input_disabled: bool,
 ) -> Vec<(Command, TypeId, PxSize, PxPosition)> {
     let mut local_commands = Vec::new();
 
@@ -328,6 +385,8 @@ fn compute_draw_commands_inner_parallel(
     if let Some(clip_rect) = clip_rect {
         metadata.event_clip_rect = Some(clip_rect);
     }
+    metadata.input_disabled = input_disabled;
+    let children_input_disabled = input_disabled || metadata.disables_input;
 
     let clips_children = metadata.clips_children;
     // Add Clip command if the node clips its children
@@ -383,6 +442,7 @@ fn compute_draw_commands_inner_parallel(
                 screen_width,
                 screen_height,
                 clip_rect,
+                children_input_disabled,
             )
         })
         .collect();
@@ -398,3 +458,50 @@ fn compute_draw_commands_inner_parallel(
 
     local_commands
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{tessera, testing};
+
+    use super::*;
+
+    #[tessera(crate)]
+    fn group(content: impl FnOnce()) {
+        content();
+    }
+
+    #[tessera(crate)]
+    fn leaf() {}
+
+    #[test]
+    fn test_disabled_input_reaches_every_descendant() {
+        let tree = testing::compose(|| {
+            group(|| {
+                testing::set_test_id("disabled");
+                group(|| {
+                    group(leaf);
+                });
+                leaf();
+            })
+        });
+        let disabled = tree.find_by_test_id("disabled").unwrap();
+        for node_id in tree.node_ids() {
+            let mut metadata = tree.metadatas.get_mut(&node_id).unwrap();
+            metadata.rel_position = Some(PxPosition::ZERO);
+            metadata.computed_data = Some(ComputedData {
+                width: Px(10),
+                height: Px(10),
+            });
+            metadata.disables_input = node_id == disabled;
+        }
+        compute_draw_commands_parallel(tree.root().unwrap(), &tree.tree, &tree.metadatas, 100, 100);
+
+        let input_disabled = |id| tree.metadatas.get(&id).unwrap().input_disabled;
+        // The node disabling input still handles it, e.g. to block the cursor
+        assert!(!input_disabled(disabled));
+        assert!(disabled.descendants(&tree.tree).skip(1).all(input_disabled));
+        let leaves = tree.find_all_by_role("leaf");
+        assert!(input_disabled(leaves[0]));
+        assert!(!input_disabled(leaves[1]));
+    }
+}
@@ -34,6 +34,8 @@ pub struct ComponentNode {
     /// Describes the input handler for the component.
     /// This is used to handle state changes.
     pub input_handler_fn: Option<Box<InputHandlerFn>>,
+    /// Identifies the component in tests, see [`crate::testing`].
+    pub test_id: Option<String>,
 }
 
 /// Contains metadata of the component node.
@@ -61,6 +63,11 @@ pub struct ComponentNodeMetaData {
     pub(crate) commands: Vec<(Command, TypeId)>,
     /// Whether this node clips its children.
     pub clips_children: bool,
+    /// Whether the input handlers of this node's descendants are skipped.
+    pub disables_input: bool,
+    /// Whether an ancestor disabled input to this node.
+    /// This is calculated once per frame before event handling.
+    pub input_disabled: bool,
 }
 
 impl ComponentNodeMetaData {
@@ -73,6 +80,8 @@ impl ComponentNodeMetaData {
             event_clip_rect: None,
             commands: Vec::new(),
             clips_children: false,
+            disables_input: false,
+            input_disabled: false,
         }
     }
 
@@ -232,6 +241,15 @@ impl<'a> MeasureInput<'a> {
         // Set the clipping flag to false for this node.
         self.metadata_mut().clips_children = false;
     }
+
+    /// Skips the input handlers of all descendants of the current node this frame, so the
+    /// subtree cannot be clicked, typed into or hovered.
+    ///
+    /// The input handler of the current node still runs, e.g. to block the cursor from the
+    /// components behind it.
+    pub fn disable_input(&self) {
+        self.metadata_mut().disables_input = true;
+    }
 }
 
 /// A `InputHandlerFn` is a function that handles state changes for a component.
@@ -262,6 +280,9 @@ pub struct InputHandlerInput<'a> {
     /// Cursor events from the event loop, if any.
     pub cursor_events: &'a mut Vec<CursorEvent>,
     /// Keyboard events from the event loop, if any.
+    ///
+    /// Events of a key held down have `repeat` set, see [`crate::key_chord`] for matching
+    /// them against shortcuts.
     pub keyboard_events: &'a mut Vec<winit::event::KeyEvent>,
     /// IME events from the event loop, if any.
     pub ime_events: &'a mut Vec<winit::event::Ime>,
@@ -322,6 +343,8 @@ pub struct WindowRequests {
 pub struct ImeRequest {
     /// The size of the area where the IME is requested.
     pub size: PxSize,
+    /// The position of that area relative to the component.
+    pub offset: PxPosition,
     /// The absolute position where the IME should be placed.
     /// This is set internally by the component tree during the compute pass.
     pub(crate) position: Option<PxPosition>, // should be setted in tessera node tree compute
@@ -329,8 +352,15 @@ pub struct ImeRequest {
 
 impl ImeRequest {
     pub fn new(size: PxSize) -> Self {
+        Self::at(PxPosition::ZERO, size)
+    }
+
+    /// Creates a request for the area of `size` at `offset` from the top left of the component,
+    /// e.g. the caret of a text field, so the candidate window opens next to it.
+    pub fn at(offset: PxPosition, size: PxSize) -> Self {
         Self {
             size,
+            offset,
             position: None, // Position will be set during the compute phase
         }
     }
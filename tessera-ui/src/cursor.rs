@@ -43,7 +43,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use crate::PxPosition;
+use crate::{PxPosition, clock};
 
 /// Maximum number of events to keep in the queue to prevent memory issues during UI jank.
 const KEEP_EVENTS_COUNT: usize = 10;
@@ -305,7 +305,7 @@ impl CursorState {
     fn process_and_queue_inertial_scroll(&mut self) {
         // Handle active inertia with clear, small responsibilities.
         if let Some(mut inertia) = self.active_inertia.take() {
-            let now = Instant::now();
+            let now = clock::now();
             let delta_time = now.duration_since(inertia.last_tick_time).as_secs_f32();
 
             if delta_time <= 0.0 {
@@ -471,7 +471,7 @@ impl CursorState {
     /// ```
     pub fn handle_touch_start(&mut self, touch_id: u64, position: PxPosition) {
         self.active_inertia = None; // Stop any existing inertia on new touch
-        let now = Instant::now();
+        let now = clock::input_time();
 
         self.touch_points.insert(
             touch_id,
@@ -526,7 +526,7 @@ impl CursorState {
         touch_id: u64,
         current_position: PxPosition,
     ) -> Option<CursorEvent> {
-        let now = Instant::now();
+        let now = clock::input_time();
         self.update_position(current_position);
 
         if !self.touch_scroll_config.enabled {
@@ -600,7 +600,7 @@ impl CursorState {
     /// // Events may include scroll events from inertia
     /// ```
     pub fn handle_touch_end(&mut self, touch_id: u64) {
-        let now = Instant::now();
+        let now = clock::input_time();
         let mut was_drag = false;
 
         if let Some(touch_state) = self.touch_points.get_mut(&touch_id) {
@@ -956,3 +956,58 @@ pub enum PressKeyEventType {
     /// The middle mouse button (typically scroll wheel click).
     Middle,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Px;
+
+    fn scroll_deltas(events: &[CursorEvent]) -> Vec<(f32, f32)> {
+        events
+            .iter()
+            .filter_map(|event| match &event.content {
+                CursorEventContent::Scroll(scroll) => Some((scroll.delta_x, scroll.delta_y)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_touch_fling_inertia() {
+        let clock = clock::manual();
+        let at = |y| PxPosition::new(Px(100), Px(y));
+        let mut state = CursorState::default();
+
+        state.handle_touch_start(0, at(200));
+        clock.advance_frame();
+        // 32 pixels in one frame is 2000 pixels per second
+        let moved = state.handle_touch_move(0, at(232)).unwrap();
+        assert_eq!(moved.timestamp, clock::now());
+        state.handle_touch_end(0);
+        let events = state.take_events();
+        assert_eq!(events.len(), 2);
+        assert!(scroll_deltas(&events).is_empty());
+
+        clock.advance_frame();
+        let events = state.take_events();
+        let [(dx, dy)] = scroll_deltas(&events)[..] else {
+            panic!("expected one inertial scroll, got {events:?}");
+        };
+        assert_eq!(events[0].timestamp, clock::now());
+        assert_eq!(dx, 0.0);
+        assert!((dy - 32.0).abs() < 0.01, "{dy}");
+
+        // Slower every frame
+        clock.advance_frame();
+        let [(_, next)] = scroll_deltas(&state.take_events())[..] else {
+            panic!("expected the inertia to go on");
+        };
+        assert!(next < dy);
+
+        // Until it stops
+        clock.advance(Duration::from_secs(10));
+        state.take_events();
+        clock.advance_frame();
+        assert!(state.take_events().is_empty());
+    }
+}
@@ -37,6 +37,12 @@
 //! focus.unfocus();
 //! ```
 //!
+//! ## Traversal
+//!
+//! Components that call [`Focus::join_traversal`] from their input handler can be reached
+//! with [`focus_next`] and [`focus_previous`] in layout order, e.g. from the D-pad of a
+//! gamepad, and learn about [`activate_focused`] through [`Focus::take_activation`].
+//!
 //! ## Thread Safety
 //!
 //! The focus state is managed through a global static variable protected by
@@ -63,6 +69,14 @@ static FOCUS_STATE: OnceLock<RwLock<FocusState>> = OnceLock::new();
 struct FocusState {
     /// The UUID of the currently focused component, or `None` if no component has focus.
     focused: Option<Uuid>,
+    /// Components that joined the focus traversal in the frame being processed, in the order
+    /// their input handlers ran, which is the reverse of the layout order.
+    traversal: Vec<Uuid>,
+    /// The traversal order of the last complete frame, in layout order.
+    previous_traversal: Vec<Uuid>,
+    /// The focused component activated with [`activate_focused`], until it takes the
+    /// activation.
+    activated: Option<Uuid>,
 }
 
 /// Acquires a read lock on the global focus state.
@@ -267,6 +281,93 @@ impl Focus {
             focus_state.focused = None;
         }
     }
+
+    /// Makes this component reachable with [`focus_next`] and [`focus_previous`] in the
+    /// current frame.
+    ///
+    /// Components call this from their input handler every frame they can be focused, so
+    /// the traversal follows the layout order and skips components that are not shown or are
+    /// disabled.
+    pub fn join_traversal(&self) {
+        let mut focus_state = write_focus_state();
+        if !focus_state.traversal.contains(&self.id) {
+            focus_state.traversal.push(self.id);
+        }
+    }
+
+    /// Returns `true` once after [`activate_focused`] was called while this component had
+    /// focus, e.g. to press a focused button.
+    pub fn take_activation(&self) -> bool {
+        let mut focus_state = write_focus_state();
+        if focus_state.activated == Some(self.id) {
+            focus_state.activated = None;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Moves the focus to the next component of the traversal, wrapping around after the last
+/// one, or to the first one if none has focus.
+///
+/// The traversal contains the components that called [`Focus::join_traversal`] in the last
+/// frame, in layout order. Returns `true` if the focus moved.
+pub fn focus_next() -> bool {
+    move_focus(true)
+}
+
+/// Moves the focus to the previous component of the traversal, like [`focus_next`] does
+/// the other way.
+pub fn focus_previous() -> bool {
+    move_focus(false)
+}
+
+/// Activates the focused component, which learns about it from [`Focus::take_activation`].
+///
+/// Returns `true` if a component has focus.
+pub fn activate_focused() -> bool {
+    let mut focus_state = write_focus_state();
+    focus_state.activated = focus_state.focused;
+    focus_state.activated.is_some()
+}
+
+fn move_focus(forward: bool) -> bool {
+    let mut focus_state = write_focus_state();
+    let next = step_focus(
+        &focus_state.previous_traversal,
+        focus_state.focused,
+        forward,
+    );
+    if next.is_some() && next != focus_state.focused {
+        focus_state.focused = next;
+        focus_state.activated = None;
+        true
+    } else {
+        false
+    }
+}
+
+/// Returns the component after `focused` in `order`, or before it if not `forward`.
+fn step_focus(order: &[Uuid], focused: Option<Uuid>, forward: bool) -> Option<Uuid> {
+    let len = order.len();
+    let current = focused.and_then(|id| order.iter().position(|&other| other == id));
+    let next = match current {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None if forward => 0,
+        None => len.checked_sub(1)?,
+    };
+    order.get(next).copied()
+}
+
+/// Starts collecting the focus traversal of a new frame, keeping the one of the last frame
+/// for [`focus_next`] and [`focus_previous`].
+pub(crate) fn begin_traversal_frame() {
+    let mut focus_state = write_focus_state();
+    let mut order = std::mem::take(&mut focus_state.traversal);
+    order.reverse();
+    focus_state.previous_traversal = order;
 }
 
 impl Drop for Focus {
@@ -300,3 +401,25 @@ impl Drop for Focus {
         self.unfocus(); // Ensure focus is cleared when the Focus instance is dropped
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_focus() {
+        let order = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        assert_eq!(step_focus(&order, None, true), Some(order[0]));
+        assert_eq!(step_focus(&order, None, false), Some(order[2]));
+        assert_eq!(step_focus(&order, Some(order[0]), true), Some(order[1]));
+        // The traversal wraps around at both ends
+        assert_eq!(step_focus(&order, Some(order[2]), true), Some(order[0]));
+        assert_eq!(step_focus(&order, Some(order[0]), false), Some(order[2]));
+        // A focused component outside the traversal counts as none
+        assert_eq!(
+            step_focus(&order, Some(Uuid::new_v4()), true),
+            Some(order[0])
+        );
+        assert_eq!(step_focus(&[], None, false), None);
+    }
+}
@@ -0,0 +1,152 @@
+//! # GPU Memory
+//!
+//! Accounting of the GPU memory held by the renderer and the pipelines, by category, so
+//! long-running applications can monitor it and bound it with budgets.
+//!
+//! Pipelines record what they hold with [`record_usage`]. Caches read [`budget`] and evict
+//! their least recently used entries to stay within it. A category that is still over budget,
+//! or has nothing to evict, is reported to the
+//! [`TesseraRuntime::on_gpu_memory_exceeded`](crate::TesseraRuntime::on_gpu_memory_exceeded)
+//! callbacks at the end of the frame it went over.
+//!
+//! ```
+//! use tessera_ui::gpu_memory::{self, GpuMemoryCategory};
+//!
+//! gpu_memory::set_budget(GpuMemoryCategory::Images, Some(64 << 20));
+//! let report = gpu_memory::report();
+//! println!(
+//!     "images: {} of {} bytes",
+//!     report.bytes(GpuMemoryCategory::Images),
+//!     report.total()
+//! );
+//! ```
+
+use std::{collections::HashMap, sync::OnceLock};
+
+use parking_lot::RwLock;
+
+/// What a GPU allocation is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GpuMemoryCategory {
+    /// Textures holding rasterized glyphs.
+    GlyphAtlas,
+    /// Textures of decoded images.
+    Images,
+    /// Shapes rendered once and reused while they do not change.
+    ShapeCache,
+    /// Frame-sized render targets, e.g. for multisampling and backdrop effects.
+    OffscreenTargets,
+}
+
+impl GpuMemoryCategory {
+    /// All categories, in the order they are reported.
+    pub const ALL: [Self; 4] = [
+        Self::GlyphAtlas,
+        Self::Images,
+        Self::ShapeCache,
+        Self::OffscreenTargets,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// GPU memory in use per [`GpuMemoryCategory`], in bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GpuMemoryReport {
+    bytes: [u64; 4],
+}
+
+impl GpuMemoryReport {
+    /// Returns the bytes used by `category`.
+    pub fn bytes(&self, category: GpuMemoryCategory) -> u64 {
+        self.bytes[category.index()]
+    }
+
+    /// Returns the bytes used by all categories together.
+    pub fn total(&self) -> u64 {
+        self.bytes.iter().sum()
+    }
+
+    /// Returns the categories above their budget in `budgets`.
+    pub(crate) fn over_budget(&self, budgets: [Option<u64>; 4]) -> [bool; 4] {
+        std::array::from_fn(|i| budgets[i].is_some_and(|budget| self.bytes[i] > budget))
+    }
+}
+
+#[derive(Default)]
+struct GpuMemory {
+    /// Bytes held per category and owner, e.g. `(Images, "image pipeline")`.
+    usage: HashMap<(GpuMemoryCategory, &'static str), u64>,
+    budgets: [Option<u64>; 4],
+}
+
+static GPU_MEMORY: OnceLock<RwLock<GpuMemory>> = OnceLock::new();
+
+fn gpu_memory() -> &'static RwLock<GpuMemory> {
+    GPU_MEMORY.get_or_init(Default::default)
+}
+
+/// Returns the GPU memory currently in use.
+pub fn report() -> GpuMemoryReport {
+    let mut report = GpuMemoryReport::default();
+    for (&(category, _), &bytes) in &gpu_memory().read().usage {
+        report.bytes[category.index()] += bytes;
+    }
+    report
+}
+
+/// Limits the memory `category` may use, or removes the limit with `None`.
+///
+/// Caches evict entries once they go over it. Memory that cannot be evicted, such as the
+/// render targets of the window, is only reported.
+pub fn set_budget(category: GpuMemoryCategory, budget: Option<u64>) {
+    gpu_memory().write().budgets[category.index()] = budget;
+}
+
+/// Returns the budget of `category`, if one was set.
+pub fn budget(category: GpuMemoryCategory) -> Option<u64> {
+    gpu_memory().read().budgets[category.index()]
+}
+
+/// Returns the budgets of all categories, indexed like [`GpuMemoryCategory::ALL`].
+pub(crate) fn budgets() -> [Option<u64>; 4] {
+    gpu_memory().read().budgets
+}
+
+/// Records that `owner` now holds `bytes` of GPU memory for `category`, replacing what it
+/// recorded before.
+///
+/// Called by pipelines whenever they allocate or free resources they keep across frames.
+pub fn record_usage(category: GpuMemoryCategory, owner: &'static str, bytes: u64) {
+    gpu_memory().write().usage.insert((category, owner), bytes);
+}
+
+/// Returns the number of bytes `texture` takes, including all multisamples.
+pub fn texture_bytes(texture: &wgpu::Texture) -> u64 {
+    let size = texture.size();
+    let texel = u64::from(texture.format().block_copy_size(None).unwrap_or(4));
+    u64::from(size.width)
+        * u64::from(size.height)
+        * u64::from(size.depth_or_array_layers)
+        * u64::from(texture.sample_count())
+        * texel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpu_memory_report() {
+        let mut report = GpuMemoryReport::default();
+        report.bytes[GpuMemoryCategory::Images.index()] = 300;
+        report.bytes[GpuMemoryCategory::OffscreenTargets.index()] = 50;
+        assert_eq!(report.bytes(GpuMemoryCategory::Images), 300);
+        assert_eq!(report.total(), 350);
+
+        let budgets = [Some(0), Some(200), None, Some(50)];
+        assert_eq!(report.over_budget(budgets), [false, true, false, false]);
+    }
+}
@@ -0,0 +1,202 @@
+//! # Haptics
+//!
+//! Short vibrations that confirm an interaction on devices that have a vibration motor, e.g.
+//! a switch being toggled or a slider reaching its end.
+//!
+//! Components call [`perform`] at those moments. It goes to the [`HapticsBackend`] of the
+//! platform: the vibrator service on Android, nothing on desktop platforms. An application
+//! can turn the feedback off with [`set_enabled`], e.g. from its settings, or replace the
+//! backend with [`set_backend`], e.g. to drive a gamepad's rumble or to log the feedback in
+//! tests.
+//!
+//! On Android the predefined vibration effects need API level 29 and the `VIBRATE`
+//! permission in the manifest. Without them the feedback is skipped.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use tessera_ui::haptics::{self, HapticFeedback, HapticsBackend};
+//!
+//! struct Log;
+//!
+//! impl HapticsBackend for Log {
+//!     fn perform(&self, feedback: HapticFeedback) {
+//!         println!("haptic {feedback:?}");
+//!     }
+//! }
+//!
+//! haptics::set_backend(Some(Arc::new(Log)));
+//! haptics::perform(HapticFeedback::Click);
+//! haptics::set_enabled(false);
+//! assert!(!haptics::is_enabled());
+//! ```
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use parking_lot::RwLock;
+
+/// The moments components give haptic feedback for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HapticFeedback {
+    /// A light tick, e.g. a slider snapping to a step or reaching its end.
+    Tick,
+    /// A control changed its state, e.g. a switch or checkbox was toggled.
+    Click,
+    /// A long press picked something up, e.g. an item lifted to reorder a list.
+    LongPress,
+    /// A gesture crossed the point where it takes effect, e.g. a swipe far enough to dismiss.
+    Threshold,
+}
+
+/// Plays [`HapticFeedback`] on a device.
+pub trait HapticsBackend: Send + Sync {
+    /// Plays `feedback`, without waiting for it to finish.
+    fn perform(&self, feedback: HapticFeedback);
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static BACKEND: RwLock<Option<Arc<dyn HapticsBackend>>> = RwLock::new(None);
+
+/// Plays `feedback` with the current backend, unless haptics are turned off.
+pub fn perform(feedback: HapticFeedback) {
+    if !is_enabled() {
+        return;
+    }
+    match BACKEND.read().as_ref() {
+        Some(backend) => backend.perform(feedback),
+        None => platform_perform(feedback),
+    }
+}
+
+/// Returns `true` if [`perform`] plays feedback, which is the default.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Turns haptic feedback on or off for the whole application.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Replaces the backend of the platform, or goes back to it with `None`.
+pub fn set_backend(backend: Option<Arc<dyn HapticsBackend>>) {
+    *BACKEND.write() = backend;
+}
+
+/// Returns the `VibrationEffect.EFFECT_*` constant Android plays for `feedback`.
+#[cfg(any(target_os = "android", test))]
+fn predefined_effect(feedback: HapticFeedback) -> i32 {
+    match feedback {
+        // EFFECT_TICK
+        HapticFeedback::Tick => 2,
+        // EFFECT_CLICK
+        HapticFeedback::Click => 0,
+        // EFFECT_HEAVY_CLICK
+        HapticFeedback::LongPress => 5,
+        // EFFECT_DOUBLE_CLICK
+        HapticFeedback::Threshold => 1,
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+fn platform_perform(_feedback: HapticFeedback) {}
+
+#[cfg(target_os = "android")]
+fn platform_perform(feedback: HapticFeedback) {
+    use jni::objects::{JObject, JValue};
+
+    let effect_id = predefined_effect(feedback);
+    let context = ndk_context::android_context();
+    let Ok(vm) = (unsafe { jni::JavaVM::from_raw(context.vm().cast()) }) else {
+        return;
+    };
+    let Ok(mut env) = vm.attach_current_thread() else {
+        return;
+    };
+    let activity = unsafe { JObject::from_raw(context.context().cast()) };
+
+    let result = (|| -> jni::errors::Result<()> {
+        let service_name = env.new_string("vibrator")?;
+        let vibrator = env
+            .call_method(
+                &activity,
+                "getSystemService",
+                "(Ljava/lang/String;)Ljava/lang/Object;",
+                &[JValue::from(&service_name)],
+            )?
+            .l()?;
+        if vibrator.is_null() {
+            return Ok(());
+        }
+        let effect = env
+            .call_static_method(
+                "android/os/VibrationEffect",
+                "createPredefined",
+                "(I)Landroid/os/VibrationEffect;",
+                &[JValue::Int(effect_id)],
+            )?
+            .l()?;
+        env.call_method(
+            &vibrator,
+            "vibrate",
+            "(Landroid/os/VibrationEffect;)V",
+            &[JValue::from(&effect)],
+        )?;
+        Ok(())
+    })();
+    // Older devices lack the predefined effects, and apps without the permission throw
+    if result.is_err() {
+        let _ = env.exception_clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    /// Serializes the tests, which share the global backend and switch.
+    static GLOBAL: Mutex<()> = Mutex::new(());
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<HapticFeedback>>);
+
+    impl HapticsBackend for Recorder {
+        fn perform(&self, feedback: HapticFeedback) {
+            self.0.lock().push(feedback);
+        }
+    }
+
+    #[test]
+    fn test_predefined_effects() {
+        assert_eq!(predefined_effect(HapticFeedback::Click), 0);
+        assert_eq!(predefined_effect(HapticFeedback::Threshold), 1);
+        assert_eq!(predefined_effect(HapticFeedback::Tick), 2);
+        assert_eq!(predefined_effect(HapticFeedback::LongPress), 5);
+    }
+
+    #[test]
+    fn test_backend_receives_feedback_while_enabled() {
+        let _guard = GLOBAL.lock();
+        let recorder = Arc::new(Recorder::default());
+        set_backend(Some(recorder.clone()));
+
+        perform(HapticFeedback::Tick);
+        set_enabled(false);
+        perform(HapticFeedback::Click);
+        set_enabled(true);
+        perform(HapticFeedback::LongPress);
+        assert_eq!(
+            *recorder.0.lock(),
+            [HapticFeedback::Tick, HapticFeedback::LongPress]
+        );
+
+        // Without a backend the platform plays it, which does nothing on desktop
+        set_backend(None);
+        perform(HapticFeedback::Threshold);
+        assert_eq!(recorder.0.lock().len(), 2);
+    }
+}
@@ -0,0 +1,82 @@
+//! Detection of idle periods without user input, during which frames are throttled.
+
+use std::time::{Duration, Instant};
+
+/// Tracks the time since the last user input and when the next throttled frame is due.
+pub(crate) struct IdleTracker {
+    /// Time without input after which the window is idle, `None` to never go idle.
+    timeout: Option<Duration>,
+    /// Time between frames while idle.
+    frame_interval: Duration,
+    last_activity: Instant,
+    idle: bool,
+    next_frame: Option<Instant>,
+}
+
+impl IdleTracker {
+    pub(crate) fn new(timeout: Option<Duration>, frame_interval: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            frame_interval,
+            last_activity: now,
+            idle: false,
+            next_frame: None,
+        }
+    }
+
+    /// Records user input at `now`. Returns `true` if it ended an idle period.
+    pub(crate) fn record_activity(&mut self, now: Instant) -> bool {
+        self.last_activity = now;
+        self.next_frame = None;
+        std::mem::take(&mut self.idle)
+    }
+
+    /// Called after every frame. Returns `true` if the window became idle, after which
+    /// [`Self::next_frame`] says when to draw again.
+    pub(crate) fn end_frame(&mut self, now: Instant) -> bool {
+        let became_idle = !self.idle
+            && self
+                .timeout
+                .is_some_and(|timeout| now.duration_since(self.last_activity) >= timeout);
+        self.idle |= became_idle;
+        self.next_frame = self.idle.then(|| now + self.frame_interval);
+        became_idle
+    }
+
+    /// Returns when the next frame is due while idle, `None` while frames run freely.
+    pub(crate) fn next_frame(&self) -> Option<Instant> {
+        self.next_frame
+    }
+
+    /// Forgets the due frame once it was requested.
+    pub(crate) fn take_next_frame(&mut self) -> Option<Instant> {
+        self.next_frame.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_tracker() {
+        let start = Instant::now();
+        let second = Duration::from_secs(1);
+        let mut idle = IdleTracker::new(Some(10 * second), second / 4, start);
+        assert!(!idle.end_frame(start + 9 * second));
+        assert_eq!(idle.next_frame(), None);
+
+        assert!(idle.end_frame(start + 10 * second));
+        assert_eq!(idle.next_frame(), Some(start + 10 * second + second / 4));
+        // Staying idle does not report it again
+        assert!(!idle.end_frame(start + 11 * second));
+
+        assert!(idle.record_activity(start + 12 * second));
+        assert_eq!(idle.next_frame(), None);
+        assert!(!idle.record_activity(start + 13 * second));
+        assert!(!idle.end_frame(start + 22 * second));
+
+        let mut never = IdleTracker::new(None, second, start);
+        assert!(!never.end_frame(start + 1000 * second));
+    }
+}
@@ -0,0 +1,448 @@
+//! # Key chords
+//!
+//! Matching of keyboard shortcuts against the keyboard events of an input handler, including
+//! sequences of several chords like Ctrl+K Ctrl+C.
+//!
+//! A [`ChordMatcher`] holds the bindings of a component. After the first chord of a sequence it
+//! waits for the next one, until the sequence is complete, a chord that continues no binding is
+//! pressed or [`ChordMatcher::timeout`] passes. The chords pressed so far are reported to the
+//! status callback, so an application can show them while the rest is awaited.
+//!
+//! ```
+//! use tessera_ui::clock;
+//! use tessera_ui::key_chord::{ChordMatch, ChordMatcher, KeyChord};
+//! use tessera_ui::winit::keyboard::ModifiersState;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! enum Command {
+//!     Comment,
+//!     Save,
+//! }
+//!
+//! let ctrl = |c| KeyChord::character(c, ModifiersState::CONTROL);
+//! let mut matcher = ChordMatcher::new()
+//!     .with_binding([ctrl('s')], Command::Save)
+//!     .with_binding([ctrl('k'), ctrl('c')], Command::Comment)
+//!     .with_status_callback(|pending| println!("{pending:?}"));
+//!
+//! let now = clock::now();
+//! assert_eq!(matcher.press_chord(ctrl('k'), false, now), ChordMatch::Pending);
+//! assert_eq!(matcher.pending()[0].to_string(), "Ctrl+K");
+//! assert_eq!(
+//!     matcher.press_chord(ctrl('c'), false, now),
+//!     ChordMatch::Matched {
+//!         action: Command::Comment,
+//!         repeat: false
+//!     }
+//! );
+//! ```
+
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use winit::{
+    event::KeyEvent,
+    keyboard::{Key, ModifiersState, NamedKey, SmolStr},
+};
+
+use crate::clock;
+
+/// How long a [`ChordMatcher`] waits for the next chord of a sequence by default.
+pub const DEFAULT_CHORD_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A key pressed together with modifiers, e.g. Ctrl+K.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    /// The logical key, with characters in lower case.
+    pub key: Key,
+    /// The modifiers held with the key.
+    pub modifiers: ModifiersState,
+}
+
+impl KeyChord {
+    /// Creates a chord of `key` and `modifiers`.
+    ///
+    /// Characters are stored in lower case, so a chord matches whether or not Shift changed the
+    /// character the key produced.
+    pub fn new(key: Key, modifiers: ModifiersState) -> Self {
+        let key = match key {
+            Key::Character(c) => Key::Character(SmolStr::new(c.to_lowercase())),
+            key => key,
+        };
+        Self { key, modifiers }
+    }
+
+    /// Creates a chord of the character key `c` and `modifiers`.
+    pub fn character(c: char, modifiers: ModifiersState) -> Self {
+        Self::new(Key::Character(SmolStr::new(c.to_string())), modifiers)
+    }
+
+    /// Creates a chord of the named key `key` and `modifiers`, e.g. F3.
+    pub fn named(key: NamedKey, modifiers: ModifiersState) -> Self {
+        Self::new(Key::Named(key), modifiers)
+    }
+
+    /// Returns the chord `event` pressed with `modifiers`, or `None` for releases and for lone
+    /// modifier keys.
+    pub fn from_event(event: &KeyEvent, modifiers: ModifiersState) -> Option<Self> {
+        let is_modifier = matches!(
+            event.logical_key,
+            Key::Named(NamedKey::Control | NamedKey::Shift | NamedKey::Alt | NamedKey::Super)
+        );
+        (event.state.is_pressed() && !is_modifier)
+            .then(|| Self::new(event.logical_key.clone(), modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    /// Formats the chord like `Ctrl+Shift+K`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_modifiers(self.modifiers))?;
+        match &self.key {
+            Key::Character(c) => write!(f, "{}", c.to_uppercase()),
+            Key::Named(key) => write!(f, "{key:?}"),
+            key => write!(f, "{key:?}"),
+        }
+    }
+}
+
+/// Formats `modifiers` like the start of a [`KeyChord`], e.g. `Ctrl+Shift+`, to show the
+/// modifiers held before the key of a chord is pressed.
+pub fn format_modifiers(modifiers: ModifiersState) -> String {
+    [
+        (ModifiersState::CONTROL, "Ctrl+"),
+        (ModifiersState::ALT, "Alt+"),
+        (ModifiersState::SHIFT, "Shift+"),
+        (ModifiersState::SUPER, "Super+"),
+    ]
+    .into_iter()
+    .filter(|&(modifier, _)| modifiers.contains(modifier))
+    .map(|(_, name)| name)
+    .collect()
+}
+
+/// The outcome of pressing a chord in a [`ChordMatcher`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChordMatch<A> {
+    /// The chord completed the sequence of `action`.
+    ///
+    /// `repeat` is set when the last chord is held down and the key repeats, so that an action
+    /// like undo can run again while one like toggling a panel can ignore the repeats.
+    Matched { action: A, repeat: bool },
+    /// The chord started or continued a sequence, the matcher waits for the next chord.
+    Pending,
+    /// The chord does not continue the pending sequence, which is dropped. The chord should not
+    /// be handled otherwise, e.g. as typed text.
+    Cancelled,
+    /// The chord is not part of a binding.
+    Unmatched,
+}
+
+/// Callback receiving the chords of the pending sequence whenever they change, with an empty
+/// slice once the sequence is matched, cancelled or timed out.
+pub type ChordStatusFn = Arc<dyn Fn(&[KeyChord]) + Send + Sync>;
+
+/// Matches pressed chords against sequences bound to actions of type `A`.
+///
+/// When a binding is a prefix of another one, the shorter one matches as soon as it is pressed.
+#[derive(Clone)]
+pub struct ChordMatcher<A> {
+    bindings: Vec<(Vec<KeyChord>, A)>,
+    pending: Vec<KeyChord>,
+    pending_since: Option<Instant>,
+    /// The last chord of the last matched sequence, which key repeats run again.
+    last_match: Option<(KeyChord, A)>,
+    timeout: Duration,
+    on_status: Option<ChordStatusFn>,
+}
+
+impl<A: Clone> Default for ChordMatcher<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Clone> ChordMatcher<A> {
+    /// Creates a matcher without bindings that waits [`DEFAULT_CHORD_TIMEOUT`] for the next
+    /// chord of a sequence.
+    pub fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+            pending: Vec::new(),
+            pending_since: None,
+            last_match: None,
+            timeout: DEFAULT_CHORD_TIMEOUT,
+            on_status: None,
+        }
+    }
+
+    /// Binds the chords of `sequence`, pressed one after the other, to `action`.
+    ///
+    /// A sequence bound before is bound to the new action instead.
+    pub fn bind(&mut self, sequence: impl Into<Vec<KeyChord>>, action: A) {
+        let sequence = sequence.into();
+        self.bindings.retain(|(bound, _)| *bound != sequence);
+        self.bindings.push((sequence, action));
+    }
+
+    /// Returns the matcher with `sequence` bound to `action`, see [`Self::bind`].
+    pub fn with_binding(mut self, sequence: impl Into<Vec<KeyChord>>, action: A) -> Self {
+        self.bind(sequence, action);
+        self
+    }
+
+    /// Removes the binding of `sequence`, returning `true` if there was one.
+    pub fn unbind(&mut self, sequence: &[KeyChord]) -> bool {
+        let len = self.bindings.len();
+        self.bindings.retain(|(bound, _)| bound != sequence);
+        self.bindings.len() != len
+    }
+
+    /// Returns the matcher waiting `timeout` for the next chord of a sequence.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the matcher reporting the pending chords to `on_status`.
+    pub fn with_status_callback(
+        mut self,
+        on_status: impl Fn(&[KeyChord]) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_status = Some(Arc::new(on_status));
+        self
+    }
+
+    /// Returns the bound sequences and their actions.
+    pub fn bindings(&self) -> &[(Vec<KeyChord>, A)] {
+        &self.bindings
+    }
+
+    /// Returns how long the matcher waits for the next chord of a sequence.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns the chords of the sequence waiting for its next chord.
+    pub fn pending(&self) -> &[KeyChord] {
+        &self.pending
+    }
+
+    /// Matches a keyboard event pressed with `modifiers` at `now`.
+    ///
+    /// Releases and lone modifier keys are [`ChordMatch::Unmatched`].
+    pub fn press(
+        &mut self,
+        event: &KeyEvent,
+        modifiers: ModifiersState,
+        now: Instant,
+    ) -> ChordMatch<A> {
+        match KeyChord::from_event(event, modifiers) {
+            Some(chord) => self.press_chord(chord, event.repeat, now),
+            None => ChordMatch::Unmatched,
+        }
+    }
+
+    /// Matches `chord`, pressed at `now` and repeating if `repeat` is set.
+    pub fn press_chord(&mut self, chord: KeyChord, repeat: bool, now: Instant) -> ChordMatch<A> {
+        self.expire(now);
+        if repeat {
+            // Holding a key runs the action it completed again, holding the start of a
+            // sequence keeps waiting for the rest
+            return match &self.last_match {
+                _ if !self.pending.is_empty() => ChordMatch::Pending,
+                Some((last, action)) if *last == chord => ChordMatch::Matched {
+                    action: action.clone(),
+                    repeat: true,
+                },
+                _ => ChordMatch::Unmatched,
+            };
+        }
+
+        let was_pending = !self.pending.is_empty();
+        let mut pressed = self.pending.clone();
+        pressed.push(chord.clone());
+        let matched = self
+            .bindings
+            .iter()
+            .find(|(sequence, _)| *sequence == pressed)
+            .map(|(_, action)| action.clone());
+        if let Some(action) = matched {
+            self.set_pending(Vec::new(), now);
+            self.last_match = Some((chord, action.clone()));
+            return ChordMatch::Matched {
+                action,
+                repeat: false,
+            };
+        }
+
+        self.last_match = None;
+        let continues = self
+            .bindings
+            .iter()
+            .any(|(sequence, _)| sequence.starts_with(&pressed));
+        if continues {
+            self.set_pending(pressed, now);
+            ChordMatch::Pending
+        } else {
+            self.set_pending(Vec::new(), now);
+            if was_pending {
+                ChordMatch::Cancelled
+            } else {
+                ChordMatch::Unmatched
+            }
+        }
+    }
+
+    /// Drops the pending sequence if [`Self::timeout`] passed since its last chord at `now`.
+    ///
+    /// Pressing a chord checks the timeout as well, calling this e.g. every frame only makes the
+    /// status callback learn about it without waiting for the next key.
+    ///
+    /// Returns `true` if a pending sequence was dropped.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let expired = self
+            .pending_since
+            .is_some_and(|since| now.saturating_duration_since(since) >= self.timeout);
+        if expired {
+            self.set_pending(Vec::new(), now);
+        }
+        expired
+    }
+
+    /// Drops the pending sequence, e.g. when the component loses focus.
+    pub fn cancel(&mut self) {
+        if !self.pending.is_empty() {
+            self.set_pending(Vec::new(), clock::now());
+        }
+    }
+
+    fn set_pending(&mut self, pending: Vec<KeyChord>, now: Instant) {
+        let changed = pending != self.pending;
+        self.pending = pending;
+        self.pending_since = (!self.pending.is_empty()).then_some(now);
+        if changed && let Some(on_status) = &self.on_status {
+            on_status(&self.pending);
+        }
+    }
+}
+
+impl<A> fmt::Debug for ChordMatcher<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChordMatcher")
+            .field("bindings", &self.bindings.len())
+            .field("pending", &self.pending)
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_format_modifiers() {
+        let modifiers = ModifiersState::SHIFT | ModifiersState::CONTROL;
+        assert_eq!(format_modifiers(modifiers), "Ctrl+Shift+");
+        assert_eq!(format_modifiers(ModifiersState::empty()), "");
+        assert_eq!(
+            KeyChord::named(NamedKey::F3, modifiers).to_string(),
+            "Ctrl+Shift+F3"
+        );
+    }
+
+    #[test]
+    fn test_chord_matcher_sequences() {
+        let ctrl = |c| KeyChord::character(c, ModifiersState::CONTROL);
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let statuses_in_callback = statuses.clone();
+        let mut matcher = ChordMatcher::new()
+            .with_binding([ctrl('z')], "undo")
+            .with_binding([ctrl('k'), ctrl('c')], "comment")
+            .with_status_callback(move |pending| {
+                statuses_in_callback.lock().unwrap().push(pending.len())
+            });
+        let start = Instant::now();
+
+        let matched = |action| ChordMatch::Matched {
+            action,
+            repeat: false,
+        };
+        assert_eq!(
+            matcher.press_chord(ctrl('z'), false, start),
+            matched("undo")
+        );
+        let repeated = ChordMatch::Matched {
+            action: "undo",
+            repeat: true,
+        };
+        assert_eq!(matcher.press_chord(ctrl('z'), true, start), repeated);
+        assert_eq!(
+            matcher.press_chord(
+                KeyChord::character('z', ModifiersState::empty()),
+                false,
+                start
+            ),
+            ChordMatch::Unmatched
+        );
+
+        assert_eq!(
+            matcher.press_chord(ctrl('k'), false, start),
+            ChordMatch::Pending
+        );
+        assert_eq!(
+            matcher.press_chord(ctrl('k'), true, start),
+            ChordMatch::Pending
+        );
+        assert_eq!(
+            matcher.press_chord(ctrl('c'), false, start),
+            matched("comment")
+        );
+        // Another chord cancels the sequence, so does the timeout
+        assert_eq!(
+            matcher.press_chord(ctrl('k'), false, start),
+            ChordMatch::Pending
+        );
+        assert_eq!(
+            matcher.press_chord(ctrl('x'), false, start),
+            ChordMatch::Cancelled
+        );
+        assert_eq!(
+            matcher.press_chord(ctrl('k'), false, start),
+            ChordMatch::Pending
+        );
+        assert!(matcher.expire(start + DEFAULT_CHORD_TIMEOUT));
+        assert!(matcher.pending().is_empty());
+        assert_eq!(*statuses.lock().unwrap(), [1, 0, 1, 0, 1, 0]);
+
+        let shifted = KeyChord::new(Key::Character("K".into()), ModifiersState::SHIFT);
+        assert_eq!(shifted, KeyChord::character('k', ModifiersState::SHIFT));
+        assert_eq!(shifted.to_string(), "Shift+K");
+    }
+
+    #[test]
+    fn test_chord_timeout_follows_clock() {
+        let ctrl = |c| KeyChord::character(c, ModifiersState::CONTROL);
+        let clock = clock::manual();
+        let mut matcher = ChordMatcher::new().with_binding([ctrl('k'), ctrl('c')], "comment");
+
+        assert_eq!(
+            matcher.press_chord(ctrl('k'), false, clock::now()),
+            ChordMatch::Pending
+        );
+        clock.advance(DEFAULT_CHORD_TIMEOUT - clock::FRAME_DURATION);
+        assert!(!matcher.expire(clock::now()));
+        clock.advance_frame();
+        assert!(matcher.expire(clock::now()));
+        assert_eq!(
+            matcher.press_chord(ctrl('c'), false, clock::now()),
+            ChordMatch::Unmatched
+        );
+    }
+}
@@ -129,6 +129,7 @@
 //! - Optimized component tree traversal
 
 pub mod clipboard;
+pub mod clock;
 pub mod color;
 mod component_tree;
 mod cursor;
@@ -136,12 +137,23 @@ pub mod dp;
 pub mod dyn_eq;
 pub mod dyn_eq_compute;
 pub mod focus_state;
+pub mod gpu_memory;
+pub mod haptics;
+mod idle;
 mod ime_state;
+pub mod key_chord;
 mod keyboard_state;
+pub mod navigation;
 pub mod px;
+pub mod raw_event;
 pub mod renderer;
 pub mod runtime;
+pub mod sound;
+pub mod speech;
+pub mod testing;
+pub mod theme;
 mod thread_utils;
+pub mod window;
 
 #[cfg(feature = "shard")]
 pub mod router;
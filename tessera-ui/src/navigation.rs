@@ -0,0 +1,191 @@
+//! # Navigation
+//!
+//! Moving through an application without a pointer, e.g. in couch and kiosk applications
+//! driven by a gamepad or a remote control.
+//!
+//! [`navigate`] maps a [`NavigationAction`] onto the [focus traversal](crate::focus_state) and
+//! the router, so any input source can drive it. With the `gamepad` feature the renderer reads
+//! connected gamepads itself: the D-pad moves the focus, A (the bottom face button) activates
+//! the focused component and B (the right face button) goes back.
+//!
+//! ```
+//! use tessera_ui::navigation::{NavigationAction, navigate};
+//!
+//! // E.g. from the arrow keys of a remote control that arrive as keyboard events
+//! navigate(NavigationAction::Next);
+//! ```
+
+/// A step of pointer-free navigation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NavigationAction {
+    /// Moves the focus to the next component, see [`crate::focus_state::focus_next`].
+    Next,
+    /// Moves the focus to the previous component.
+    Previous,
+    /// Activates the focused component, see [`crate::Focus::take_activation`].
+    Activate,
+    /// Goes back to the previous route of the router, if there is one.
+    Back,
+}
+
+/// Performs `action`, returning `true` if it changed anything.
+pub fn navigate(action: NavigationAction) -> bool {
+    match action {
+        NavigationAction::Next => crate::focus_state::focus_next(),
+        NavigationAction::Previous => crate::focus_state::focus_previous(),
+        NavigationAction::Activate => crate::focus_state::activate_focused(),
+        NavigationAction::Back => go_back(),
+    }
+}
+
+#[cfg(feature = "shard")]
+fn go_back() -> bool {
+    // The root route stays, with nothing to show below it
+    crate::router::Router::with_mut(|router| router.len() > 1 && router.pop().is_some())
+}
+
+#[cfg(not(feature = "shard"))]
+fn go_back() -> bool {
+    false
+}
+
+/// Reads the connected gamepads and navigates with their buttons.
+#[cfg(feature = "gamepad")]
+pub(crate) struct GamepadInput {
+    gilrs: gilrs::Gilrs,
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadInput {
+    /// Connects to the gamepad backend of the platform, or returns `None` without one.
+    pub(crate) fn new() -> Option<Self> {
+        match gilrs::Gilrs::new() {
+            Ok(gilrs) => Some(Self { gilrs }),
+            Err(err) => {
+                tracing::warn!("Gamepad input is unavailable: {err}");
+                None
+            }
+        }
+    }
+
+    /// Performs the navigation of the button presses since the last call.
+    ///
+    /// Returns `true` if any of them changed anything.
+    pub(crate) fn poll(&mut self) -> bool {
+        let mut navigated = false;
+        while let Some(gilrs::Event { event, .. }) = self.gilrs.next_event() {
+            if let gilrs::EventType::ButtonPressed(button, _) = event
+                && let Some(action) = button_action(button)
+            {
+                navigated |= navigate(action);
+            }
+        }
+        navigated
+    }
+
+    /// Returns `true` while a gamepad is connected, which the renderer then polls.
+    pub(crate) fn is_connected(&self) -> bool {
+        self.gilrs.gamepads().next().is_some()
+    }
+}
+
+/// Returns the navigation of a gamepad `button`.
+#[cfg(feature = "gamepad")]
+fn button_action(button: gilrs::Button) -> Option<NavigationAction> {
+    use gilrs::Button;
+
+    match button {
+        Button::DPadDown | Button::DPadRight => Some(NavigationAction::Next),
+        Button::DPadUp | Button::DPadLeft => Some(NavigationAction::Previous),
+        Button::South => Some(NavigationAction::Activate),
+        Button::East => Some(NavigationAction::Back),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Focus, focus_state::begin_traversal_frame};
+
+    use super::*;
+
+    #[test]
+    fn test_navigate_wraps_through_the_traversal() {
+        let order = [Focus::new(), Focus::new(), Focus::new()];
+        // Input handlers run in reverse layout order
+        for focus in order.iter().rev() {
+            focus.join_traversal();
+        }
+        begin_traversal_frame();
+
+        assert!(navigate(NavigationAction::Next));
+        assert!(order[0].is_focused());
+        assert!(navigate(NavigationAction::Next));
+        assert!(navigate(NavigationAction::Next));
+        assert!(order[2].is_focused());
+        assert!(navigate(NavigationAction::Next));
+        assert!(order[0].is_focused());
+        assert!(navigate(NavigationAction::Previous));
+        assert!(order[2].is_focused());
+        assert!(navigate(NavigationAction::Previous));
+        assert!(order[1].is_focused());
+
+        assert!(navigate(NavigationAction::Activate));
+        assert!(order[1].take_activation());
+        assert!(!order[0].take_activation());
+    }
+
+    #[cfg(feature = "shard")]
+    #[test]
+    fn test_back_keeps_the_root_route() {
+        use crate::router::{Router, RouterDestination};
+
+        struct Screen;
+
+        impl RouterDestination for Screen {
+            fn exec_component(&self) {}
+
+            fn shard_id(&self) -> &'static str {
+                "navigation_test_screen"
+            }
+        }
+
+        Router::with_mut(|router| {
+            router.reset_with(Screen);
+            router.push(Screen);
+        });
+        assert!(navigate(NavigationAction::Back));
+        assert_eq!(Router::with(|router| router.len()), 1);
+        assert!(!navigate(NavigationAction::Back));
+        assert_eq!(Router::with(|router| router.len()), 1);
+    }
+
+    #[cfg(feature = "gamepad")]
+    #[test]
+    fn test_button_action() {
+        use gilrs::Button;
+
+        assert_eq!(
+            button_action(Button::DPadDown),
+            Some(NavigationAction::Next)
+        );
+        assert_eq!(
+            button_action(Button::DPadRight),
+            Some(NavigationAction::Next)
+        );
+        assert_eq!(
+            button_action(Button::DPadUp),
+            Some(NavigationAction::Previous)
+        );
+        assert_eq!(
+            button_action(Button::DPadLeft),
+            Some(NavigationAction::Previous)
+        );
+        assert_eq!(
+            button_action(Button::South),
+            Some(NavigationAction::Activate)
+        );
+        assert_eq!(button_action(Button::East), Some(NavigationAction::Back));
+        assert_eq!(button_action(Button::Start), None);
+    }
+}
@@ -0,0 +1,42 @@
+//! # Raw Events
+//!
+//! Access to the winit events of the window as they arrive, before Tessera turns them into the
+//! cursor, keyboard and IME events of the input handlers.
+//!
+//! The cooked events cover what components need, but leave out e.g. the physical key codes
+//! and scancodes of key presses, raw mouse motion for camera control, gamepad-like devices
+//! and window moves. Callbacks registered with
+//! [`TesseraRuntime::on_raw_event`](crate::TesseraRuntime::on_raw_event) see every
+//! [`RawEvent`] next to the normal pipeline, which handles the events as usual.
+//!
+//! Device events are reported while the window has the focus by default. A global
+//! push-to-talk key needs them from the background too, see
+//! [`TesseraConfig::device_events`](crate::renderer::TesseraConfig::device_events).
+//!
+//! ```
+//! use tessera_ui::{TesseraRuntime, raw_event::RawEvent, winit::event::WindowEvent};
+//!
+//! TesseraRuntime::with_mut(|rt| {
+//!     rt.on_raw_event(|event| {
+//!         if let RawEvent::Window(WindowEvent::KeyboardInput { event, .. }) = event {
+//!             println!("{:?} {:?}", event.physical_key, event.state);
+//!         }
+//!     })
+//! });
+//! ```
+
+use winit::event::{DeviceEvent, DeviceId, WindowEvent};
+
+/// An event reported by winit, see the [module docs](self).
+#[derive(Debug)]
+pub enum RawEvent<'a> {
+    /// An event of the application window.
+    Window(&'a WindowEvent),
+    /// An event of an input device, not tied to the window, e.g. raw mouse motion.
+    Device {
+        /// The device that reported the event.
+        device_id: DeviceId,
+        /// The event.
+        event: &'a DeviceEvent,
+    },
+}
@@ -186,40 +186,60 @@
 //! ```
 
 pub mod app;
+mod color_output;
 pub mod command;
 pub mod compute;
 pub mod drawer;
+mod render_scale;
 pub mod reorder;
 
-use std::{any::TypeId, sync::Arc, thread, time::Instant};
+use std::{
+    any::TypeId,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
 
 use tessera_ui_macros::tessera;
 use tracing::{debug, error, instrument, warn};
 use winit::{
     application::ApplicationHandler,
     error::EventLoopError,
-    event::WindowEvent,
-    event_loop::{ActiveEventLoop, EventLoop},
+    event::{DeviceEvent, DeviceId, StartCause, WindowEvent},
+    event_loop::{ActiveEventLoop, ControlFlow, DeviceEvents, EventLoop},
+    keyboard::{Key, NamedKey},
     window::{Window, WindowId},
 };
 
+#[cfg(feature = "gamepad")]
+use crate::navigation::GamepadInput;
 use crate::{
-    Clipboard, ImeState, PxPosition,
+    Clipboard, ImeState, PxPosition, clock,
     component_tree::WindowRequests,
     cursor::{CursorEvent, CursorEventContent, CursorState, GestureState},
     dp::SCALE_FACTOR,
+    gpu_memory,
+    idle::IdleTracker,
     keyboard_state::KeyboardState,
     px::PxSize,
+    raw_event::RawEvent,
     runtime::TesseraRuntime,
     thread_utils,
+    window::{self, WindowPlacement},
 };
 
+/// How often connected gamepads are read while nothing else wakes the event loop.
+#[cfg(feature = "gamepad")]
+const GAMEPAD_POLL_INTERVAL: Duration = Duration::from_millis(30);
+
 pub use app::WgpuApp;
+pub use color_output::ColorOutput;
 pub use command::{BarrierRequirement, Command};
 pub use compute::{
     ComputablePipeline, ComputeBatchItem, ComputePipelineRegistry, ErasedComputeBatchItem,
 };
 pub use drawer::{DrawCommand, DrawablePipeline, PipelineRegistry};
+pub use render_scale::RenderScale;
 
 #[cfg(target_os = "android")]
 use winit::platform::android::{
@@ -272,6 +292,30 @@ pub struct TesseraConfig {
     /// The title of the application window.
     /// Defaults to "Tessera" if not specified.
     pub window_title: String,
+    /// Where the window opens, e.g. a [`WindowGeometry`](crate::window::WindowGeometry)
+    /// saved in the previous run. Defaults to `None`, which leaves it to the platform.
+    pub window_placement: Option<WindowPlacement>,
+    /// Key that toggles borderless fullscreen. Its presses are not passed on to components.
+    /// Defaults to F11, `None` leaves fullscreen to
+    /// [`TesseraRuntime::set_fullscreen`](crate::TesseraRuntime::set_fullscreen).
+    pub fullscreen_key: Option<NamedKey>,
+    /// Time without user input after which the window is idle and frames are throttled to
+    /// [`Self::idle_frame_interval`], to save power in apps that stay open all day.
+    /// Defaults to one minute, `None` keeps the full frame rate.
+    pub idle_timeout: Option<Duration>,
+    /// Time between frames while idle. Defaults to 250 ms.
+    pub idle_frame_interval: Duration,
+    /// Resolution the UI is rendered at, relative to the window. Defaults to full resolution,
+    /// see [`RenderScale`] for a lower or load-dependent one.
+    pub render_scale: RenderScale,
+    /// How colors are stored for the display. Defaults to sRGB, see [`ColorOutput`] for
+    /// wide-gamut and HDR output.
+    pub color_output: ColorOutput,
+    /// When input devices report the raw events passed to
+    /// [`TesseraRuntime::on_raw_event`](crate::TesseraRuntime::on_raw_event). Defaults to
+    /// while the window has the focus, [`DeviceEvents::Always`] also reports them while
+    /// another application is in front, e.g. for a global push-to-talk key.
+    pub device_events: DeviceEvents,
 }
 
 impl Default for TesseraConfig {
@@ -280,6 +324,13 @@ impl Default for TesseraConfig {
         Self {
             sample_count: 1,
             window_title: "Tessera".to_string(),
+            window_placement: None,
+            fullscreen_key: Some(NamedKey::F11),
+            idle_timeout: Some(Duration::from_secs(60)),
+            idle_frame_interval: Duration::from_millis(250),
+            render_scale: RenderScale::default(),
+            color_output: ColorOutput::default(),
+            device_events: DeviceEvents::WhenFocused,
         }
     }
 }
@@ -334,6 +385,11 @@ pub struct Renderer<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> {
     clipboard: Clipboard,
     /// Commands from the previous frame, for dirty rectangle optimization
     previous_commands: Vec<(Command, TypeId, PxSize, PxPosition)>,
+    /// Tracks user input to throttle frames while idle
+    idle: IdleTracker,
+    #[cfg(feature = "gamepad")]
+    /// Connected gamepads, which navigate the focus
+    gamepad: Option<GamepadInput>,
     #[cfg(target_os = "android")]
     /// Android-specific state tracking whether the soft keyboard is currently open
     android_ime_opened: bool,
@@ -438,9 +494,16 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> Renderer<F, R> {
             keyboard_state,
             register_pipelines_fn,
             ime_state,
+            idle: IdleTracker::new(
+                config.idle_timeout,
+                config.idle_frame_interval,
+                clock::now(),
+            ),
             config,
             clipboard,
             previous_commands: Vec::new(),
+            #[cfg(feature = "gamepad")]
+            gamepad: GamepadInput::new(),
         };
         thread_utils::set_thread_name("Tessera Renderer");
         event_loop.run_app(&mut renderer)
@@ -559,9 +622,16 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> Renderer<F, R> {
             register_pipelines_fn,
             ime_state,
             android_ime_opened: false,
+            idle: IdleTracker::new(
+                config.idle_timeout,
+                config.idle_frame_interval,
+                clock::now(),
+            ),
             config,
             clipboard,
             previous_commands: Vec::new(),
+            #[cfg(feature = "gamepad")]
+            gamepad: GamepadInput::new(),
         };
         thread_utils::set_thread_name("Tessera Renderer");
         event_loop.run_app(&mut renderer)
@@ -581,6 +651,7 @@ struct RenderFrameArgs<'a> {
     #[cfg(target_os = "android")]
     pub event_loop: &'a ActiveEventLoop,
     pub clipboard: &'a mut Clipboard,
+    pub idle: &'a mut IdleTracker,
 }
 
 impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> Renderer<F, R> {
@@ -760,6 +831,13 @@ Fps: {:.2}
         // Clear any registered callbacks
         TesseraRuntime::with_mut(|rt| rt.clear_frame_callbacks());
 
+        if let Some(render_scale) = TesseraRuntime::with_mut(|rt| rt.render_scale_request.take()) {
+            args.app.render_scale.set_mode(render_scale);
+        }
+
+        // Every component of the frame sees the same time
+        clock::begin_frame();
+
         // Build the component tree and measure time
         let build_tree_cost = Self::build_component_tree(entry_point);
 
@@ -770,7 +848,10 @@ Fps: {:.2}
 
         // --- Dirty Rectangle Logic ---
         let mut dirty = false;
-        if args.resized || new_commands.len() != previous_commands.len() {
+        if args.resized
+            || args.app.render_scale.take_changed()
+            || new_commands.len() != previous_commands.len()
+        {
             dirty = true;
         } else {
             for (new_cmd_tuple, old_cmd_tuple) in new_commands.iter().zip(previous_commands.iter())
@@ -804,6 +885,10 @@ Fps: {:.2}
             let render_cost = Self::perform_render(args, new_commands.clone());
             // Log frame statistics
             Self::log_frame_stats(build_tree_cost, draw_cost, render_cost);
+            let render_scale = &mut args.app.render_scale;
+            render_scale.record_frame(build_tree_cost + draw_cost + render_cost);
+            let scale = render_scale.scale();
+            TesseraRuntime::with_mut(|rt| rt.render_scale = Some(scale));
         } else {
             thread::sleep(std::time::Duration::from_millis(4)); // Sleep briefly to avoid busy-waiting
         }
@@ -858,15 +943,37 @@ Fps: {:.2}
             }
         }
 
+        if let Some(placement) = TesseraRuntime::with_mut(|rt| rt.window_placement_request.take()) {
+            window::apply_placement(&args.app.window, placement);
+            let monitors = window::monitors(&args.app.window);
+            TesseraRuntime::with_mut(|rt| rt.monitors = monitors);
+        }
+        if let Some(fullscreen) = TesseraRuntime::with_mut(|rt| rt.fullscreen_request.take()) {
+            window::set_fullscreen(&args.app.window, fullscreen);
+            TesseraRuntime::with_mut(|rt| rt.update_fullscreen(fullscreen));
+        }
+
         // End of frame cleanup
         args.cursor_state.frame_cleanup();
 
         // Store the commands for the next frame's comparison
         *previous_commands = new_commands;
 
+        let now = clock::now();
+        if TesseraRuntime::with(|rt| rt.keep_awake_request) {
+            args.idle.record_activity(now);
+        }
+        if args.idle.end_frame(now) {
+            TesseraRuntime::with_mut(|rt| rt.update_idle(true));
+        }
+        let (gpu_memory, budgets) = (gpu_memory::report(), gpu_memory::budgets());
+        TesseraRuntime::with_mut(|rt| rt.update_gpu_memory(gpu_memory, budgets));
         // Currently we render every frame, but with dirty checking, this could be conditional.
         // For now, we still request a redraw to keep the event loop spinning for animations.
-        args.app.window.request_redraw();
+        // While idle the next frame waits for `about_to_wait` to schedule it instead.
+        if args.idle.next_frame().is_none() {
+            args.app.window.request_redraw();
+        }
     }
 }
 
@@ -878,6 +985,26 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> Renderer<F, R> {
         event_loop.exit();
     }
 
+    /// Stores the geometry of the window in the runtime, and the connected monitors too when
+    /// the window may have moved to another one.
+    fn record_window_geometry(&self, moved: bool) {
+        let Some(app) = self.app.as_ref() else {
+            return;
+        };
+        let monitors = moved.then(|| window::monitors(&app.window));
+        TesseraRuntime::with_mut(|rt| {
+            if rt.window_minimized {
+                return;
+            }
+            rt.window_geometry = window::current_geometry(&app.window, rt.window_geometry);
+            // Fullscreen may also be left through the platform, e.g. with the title bar button
+            rt.update_fullscreen(app.window.fullscreen().is_some());
+            if let Some(monitors) = monitors {
+                rt.monitors = monitors;
+            }
+        });
+    }
+
     fn handle_resized(&mut self, size: winit::dpi::PhysicalSize<u32>) {
         // Obtain the app inside the method to avoid holding a mutable borrow across other
         // borrows of `self`.
@@ -929,7 +1056,7 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> Renderer<F, R> {
             return; // Ignore unsupported buttons
         };
         let event = CursorEvent {
-            timestamp: Instant::now(),
+            timestamp: clock::input_time(),
             content: event_content,
             gesture_state: GestureState::TapCandidate,
         };
@@ -940,7 +1067,7 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> Renderer<F, R> {
     fn handle_mouse_wheel(&mut self, delta: winit::event::MouseScrollDelta) {
         let event_content = CursorEventContent::from_scroll_event(delta);
         let event = CursorEvent {
-            timestamp: Instant::now(),
+            timestamp: clock::input_time(),
             content: event_content,
             gesture_state: GestureState::Dragged,
         };
@@ -974,8 +1101,27 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> Renderer<F, R> {
         }
     }
 
+    /// Ends an idle period and restarts the frame loop that was throttled during it.
+    fn handle_user_activity(&mut self) {
+        if !self.idle.record_activity(clock::input_time()) {
+            return;
+        }
+        TesseraRuntime::with_mut(|rt| rt.update_idle(false));
+        if let Some(app) = self.app.as_ref() {
+            app.window.request_redraw();
+        }
+    }
+
     fn handle_keyboard_input(&mut self, event: winit::event::KeyEvent) {
         debug!("Keyboard input: {event:?}");
+        if let Some(key) = self.config.fullscreen_key
+            && event.logical_key == Key::Named(key)
+        {
+            if event.state.is_pressed() && !event.repeat {
+                TesseraRuntime::with_mut(|rt| rt.toggle_fullscreen());
+            }
+            return;
+        }
         self.keyboard_state.push_event(event);
     }
 
@@ -1001,6 +1147,7 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> Renderer<F, R> {
             #[cfg(target_os = "android")]
             event_loop,
             clipboard: &mut self.clipboard,
+            idle: &mut self.idle,
         };
         Self::execute_render_frame(&self.entry_point, &mut args, &mut self.previous_commands);
     }
@@ -1043,14 +1190,30 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> ApplicationHandler for Rend
             return;
         }
 
+        event_loop.listen_device_events(self.config.device_events);
+
         // Create a new window
         let window_attributes = Window::default_attributes()
             .with_title(&self.config.window_title)
             .with_transparent(true);
         let window = Arc::new(event_loop.create_window(window_attributes).unwrap());
+        if let Some(placement) = self.config.window_placement {
+            window::apply_placement(&window, placement);
+        }
+        TesseraRuntime::with_mut(|rt| {
+            rt.monitors = window::monitors(&window);
+            rt.window_geometry = window::current_geometry(&window, None);
+        });
         let register_pipelines_fn = self.register_pipelines_fn.clone();
 
-        let mut wgpu_app = pollster::block_on(WgpuApp::new(window, self.config.sample_count));
+        let mut wgpu_app = pollster::block_on(WgpuApp::new(
+            window,
+            self.config.sample_count,
+            self.config.render_scale,
+            self.config.color_output,
+        ));
+        let color_output = color_output::color_output(wgpu_app.config.format);
+        TesseraRuntime::with_mut(|rt| rt.color_output = color_output);
 
         // Register pipelines
         wgpu_app.register_pipelines(register_pipelines_fn);
@@ -1110,6 +1273,58 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> ApplicationHandler for Rend
         });
     }
 
+    /// Wakes the event loop up for the next throttled frame while idle.
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let next_frame = self.idle.next_frame();
+        // Gamepads do not wake the event loop, so it polls them while one is connected
+        #[cfg(feature = "gamepad")]
+        let next_frame = match self.gamepad.as_mut() {
+            Some(gamepad) => {
+                if gamepad.poll() {
+                    TesseraRuntime::with_mut(|rt| rt.update_idle(false));
+                    if let Some(app) = self.app.as_ref() {
+                        app.window.request_redraw();
+                    }
+                }
+                let poll = gamepad
+                    .is_connected()
+                    .then(|| Instant::now() + GAMEPAD_POLL_INTERVAL);
+                next_frame.into_iter().chain(poll).min()
+            }
+            None => next_frame,
+        };
+        event_loop.set_control_flow(match next_frame {
+            Some(deadline) => ControlFlow::WaitUntil(deadline),
+            None => ControlFlow::Wait,
+        });
+    }
+
+    /// Passes the events of input devices on to the raw event callbacks, see
+    /// [`crate::raw_event`]. Tessera itself takes its input from the window events.
+    fn device_event(
+        &mut self,
+        _event_loop: &ActiveEventLoop,
+        device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        TesseraRuntime::with(|rt| {
+            rt.trigger_raw_event_callbacks(&RawEvent::Device {
+                device_id,
+                event: &event,
+            })
+        });
+    }
+
+    /// Draws the throttled frame that [`Self::about_to_wait`] waited for.
+    fn new_events(&mut self, _event_loop: &ActiveEventLoop, cause: StartCause) {
+        if let StartCause::ResumeTimeReached { .. } = cause
+            && self.idle.take_next_frame().is_some()
+            && let Some(app) = self.app.as_ref()
+        {
+            app.window.request_redraw();
+        }
+    }
+
     /// Handles window-specific events from the windowing system.
     ///
     /// This method processes all window events including user input, window state changes,
@@ -1154,6 +1369,20 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> ApplicationHandler for Rend
         _window_id: WindowId,
         event: WindowEvent,
     ) {
+        TesseraRuntime::with(|rt| rt.trigger_raw_event_callbacks(&RawEvent::Window(&event)));
+
+        if matches!(
+            event,
+            WindowEvent::CursorMoved { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::Touch(_)
+                | WindowEvent::KeyboardInput { .. }
+                | WindowEvent::Ime(_)
+        ) {
+            self.handle_user_activity();
+        }
+
         // Defer borrowing `app` into specific event handlers to avoid overlapping mutable borrows.
         // Handlers will obtain a mutable reference to `self.app` as needed.
 
@@ -1164,6 +1393,10 @@ impl<F: Fn(), R: Fn(&mut WgpuApp) + Clone + 'static> ApplicationHandler for Rend
             }
             WindowEvent::Resized(size) => {
                 self.handle_resized(size);
+                self.record_window_geometry(false);
+            }
+            WindowEvent::Moved(_) => {
+                self.record_window_geometry(true);
             }
             WindowEvent::CursorMoved {
                 device_id: _,
@@ -9,13 +9,16 @@ use crate::{
     ComputablePipeline, ComputeCommand, DrawCommand, DrawablePipeline, Px, PxPosition,
     compute::resource::ComputeResourceManager,
     dp::SCALE_FACTOR,
+    gpu_memory::{self, GpuMemoryCategory},
     px::{PxRect, PxSize},
     renderer::command::{AsAny, BarrierRequirement, Command},
 };
 
 use super::{
+    color_output::{self, ColorOutput},
     compute::{ComputePipelineRegistry, ErasedComputeBatchItem},
     drawer::Drawer,
+    render_scale::{RenderScale, RenderScaleController, scaled_size},
 };
 
 // WGPU context for ping-pong operations
@@ -39,6 +42,8 @@ struct RenderCurrentPassParams<'a> {
     queue: &'a wgpu::Queue,
     config: &'a wgpu::SurfaceConfiguration,
     clip_stack: &'a mut Vec<PxRect>,
+    /// Render scale of the frame, applied to scissor rects.
+    scale: f32,
 }
 
 // Parameters for do_compute function
@@ -58,6 +63,14 @@ struct DoComputeParams<'a> {
     compute_blit_pipeline: &'a wgpu::RenderPipeline,
 }
 
+// Texture a frame is rendered into below the window resolution
+struct ScaledTarget {
+    size: (u32, u32),
+    view: wgpu::TextureView,
+    msaa_texture: Option<wgpu::Texture>,
+    msaa_view: Option<wgpu::TextureView>,
+}
+
 // Compute resources for ping-pong operations
 struct ComputeResources<'a> {
     compute_commands: &'a mut Vec<(Box<dyn ComputeCommand>, PxSize, PxPosition)>,
@@ -107,6 +120,11 @@ pub struct WgpuApp {
     blit_bind_group_layout: wgpu::BindGroupLayout,
     blit_sampler: wgpu::Sampler,
     compute_blit_pipeline: wgpu::RenderPipeline,
+
+    // Render scale resources
+    pub(crate) render_scale: RenderScaleController,
+    scaled_target: Option<ScaledTarget>,
+    upscale_sampler: wgpu::Sampler,
 }
 
 impl WgpuApp {
@@ -187,7 +205,12 @@ impl WgpuApp {
     }
 
     /// Create a new WGPU app, as the root of Tessera
-    pub(crate) async fn new(window: Arc<Window>, sample_count: u32) -> Self {
+    pub(crate) async fn new(
+        window: Arc<Window>,
+        sample_count: u32,
+        render_scale: RenderScale,
+        color_output: ColorOutput,
+    ) -> Self {
         // Looking for gpus
         let instance: wgpu::Instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
             backends: wgpu::Backends::all(),
@@ -217,9 +240,11 @@ impl WgpuApp {
             wgpu::PresentMode::Immediate
         };
         info!("Using present mode: {present_mode:?}");
+        let format = color_output::surface_format(&caps.formats, color_output);
+        info!("Using surface format: {format:?}");
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
-            format: caps.formats[0],
+            format,
             width: size.width,
             height: size.height,
             present_mode,
@@ -249,6 +274,12 @@ impl WgpuApp {
         // Create blit pipeline resources
         let blit_shader = gpu.create_shader_module(wgpu::include_wgsl!("shaders/blit.wgsl"));
         let blit_sampler = gpu.create_sampler(&wgpu::SamplerDescriptor::default());
+        let upscale_sampler = gpu.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Upscale Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
         let blit_bind_group_layout =
             gpu.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("Blit Bind Group Layout"),
@@ -322,7 +353,7 @@ impl WgpuApp {
             cache: None,
         });
 
-        Self {
+        let app = Self {
             window,
             gpu,
             surface,
@@ -344,7 +375,31 @@ impl WgpuApp {
             blit_bind_group_layout,
             blit_sampler,
             compute_blit_pipeline,
-        }
+            render_scale: RenderScaleController::new(render_scale),
+            scaled_target: None,
+            upscale_sampler,
+        };
+        app.record_target_memory();
+        app
+    }
+
+    /// Reports the memory of the frame-sized render targets to [`gpu_memory`].
+    fn record_target_memory(&self) {
+        let scaled = self.scaled_target.iter().flat_map(|target| {
+            std::iter::once(target.view.texture()).chain(target.msaa_texture.as_ref())
+        });
+        let bytes = [
+            &self.offscreen_texture,
+            &self.compute_target_a,
+            &self.compute_target_b,
+        ]
+        .into_iter()
+        .map(|view| view.texture())
+        .chain(self.msaa_texture.as_ref())
+        .chain(scaled)
+        .map(gpu_memory::texture_bytes)
+        .sum();
+        gpu_memory::record_usage(GpuMemoryCategory::OffscreenTargets, "renderer", bytes);
     }
 
     /// Registers a new drawable pipeline for a specific command type.
@@ -482,6 +537,42 @@ impl WgpuApp {
             self.msaa_texture = msaa_texture;
             self.msaa_view = msaa_view;
         }
+        self.destroy_scaled_target();
+        self.record_target_memory();
+    }
+
+    fn destroy_scaled_target(&mut self) {
+        if let Some(target) = self.scaled_target.take() {
+            target.view.texture().destroy();
+            if let Some(texture) = target.msaa_texture {
+                texture.destroy();
+            }
+        }
+    }
+
+    /// Returns the views to render a frame into at `scale`, creating them if the size changed.
+    fn scaled_target(&mut self, scale: f32) -> (wgpu::TextureView, Option<wgpu::TextureView>) {
+        let size = scaled_size(self.config.width, self.config.height, scale);
+        if self.scaled_target.as_ref().map(|target| target.size) != Some(size) {
+            self.destroy_scaled_target();
+            let config = wgpu::SurfaceConfiguration {
+                width: size.0,
+                height: size.1,
+                ..self.config.clone()
+            };
+            let view = Self::create_pass_target(&self.gpu, &config, "Scaled");
+            let (msaa_texture, msaa_view) =
+                Self::make_msaa_resources(&self.gpu, self.sample_count, &config);
+            self.scaled_target = Some(ScaledTarget {
+                size,
+                view,
+                msaa_texture,
+                msaa_view,
+            });
+            self.record_target_memory();
+        }
+        let target = self.scaled_target.as_ref().unwrap();
+        (target.view.clone(), target.msaa_view.clone())
     }
 
     /// Resize the surface if needed.
@@ -597,6 +688,17 @@ impl WgpuApp {
     ) -> Result<(), wgpu::SurfaceError> {
         // Collect commands into a Vec to allow reordering
         let commands: Vec<_> = commands.into_iter().collect();
+        // Backdrop effects read the scene at full resolution, so their frames are not scaled
+        let samples_scene = commands.iter().any(|(command, ..)| match command {
+            Command::Draw(cmd) => cmd.barrier().is_some(),
+            Command::Compute(_) => true,
+            Command::ClipPush(_) | Command::ClipPop => false,
+        });
+        let scale = if samples_scene {
+            1.0
+        } else {
+            self.render_scale.scale()
+        };
         // Reorder instructions based on dependencies for better batching optimization
         let commands = super::reorder::reorder_instructions(commands);
 
@@ -633,9 +735,15 @@ impl WgpuApp {
         let mut barrier_draw_rects_in_pass: Vec<PxRect> = Vec::new();
         let mut clip_stack: Vec<PxRect> = Vec::new();
 
-        let mut output_view = output_frame
+        let surface_view = output_frame
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
+        let (mut output_view, msaa_view) = if scale < 1.0 {
+            let (view, msaa_view) = self.scaled_target(scale);
+            (view, msaa_view)
+        } else {
+            (surface_view.clone(), self.msaa_view.clone())
+        };
 
         for (command, command_type_id, size, start_pos) in commands {
             let need_new_pass = commands_in_pass
@@ -701,7 +809,7 @@ impl WgpuApp {
                 }
 
                 render_current_pass(RenderCurrentPassParams {
-                    msaa_view: &self.msaa_view,
+                    msaa_view: &msaa_view,
                     is_first_pass: &mut is_first_pass,
                     encoder: &mut encoder,
                     write_target: &output_view,
@@ -712,6 +820,7 @@ impl WgpuApp {
                     queue: &self.queue,
                     config: &self.config,
                     clip_stack: &mut clip_stack,
+                    scale,
                 });
                 commands_in_pass.clear();
                 barrier_draw_rects_in_pass.clear();
@@ -793,7 +902,7 @@ impl WgpuApp {
 
             // Render the current pass before starting a new one
             render_current_pass(RenderCurrentPassParams {
-                msaa_view: &self.msaa_view,
+                msaa_view: &msaa_view,
                 is_first_pass: &mut is_first_pass,
                 encoder: &mut encoder,
                 write_target: &output_view,
@@ -804,6 +913,7 @@ impl WgpuApp {
                 queue: &self.queue,
                 config: &self.config,
                 clip_stack: &mut clip_stack,
+                scale,
             });
             commands_in_pass.clear();
             barrier_draw_rects_in_pass.clear();
@@ -814,6 +924,19 @@ impl WgpuApp {
             .pipeline_registry
             .end_all_frames(&self.gpu, &self.queue, &self.config);
 
+        // Stretch a frame rendered below the window resolution over the surface
+        if scale < 1.0 {
+            Self::blit_to_view(
+                &mut encoder,
+                &self.gpu,
+                &output_view,
+                &surface_view,
+                &self.blit_bind_group_layout,
+                &self.upscale_sampler,
+                &self.blit_pipeline,
+            );
+        }
+
         self.queue.submit(Some(encoder.finish()));
         output_frame.present();
 
@@ -1122,6 +1245,7 @@ fn render_current_pass(params: RenderCurrentPassParams<'_>) {
                         params.scene_texture_view,
                         params.clip_stack,
                         &mut current_batch_draw_rect,
+                        params.scale,
                     );
                     last_command_type_id = None; // Reset batch type after flush
                 }
@@ -1152,6 +1276,7 @@ fn render_current_pass(params: RenderCurrentPassParams<'_>) {
                 params.scene_texture_view,
                 params.clip_stack,
                 &mut current_batch_draw_rect,
+                params.scale,
             );
         }
 
@@ -1173,6 +1298,7 @@ fn render_current_pass(params: RenderCurrentPassParams<'_>) {
             params.scene_texture_view,
             params.clip_stack,
             &mut current_batch_draw_rect,
+            params.scale,
         );
     }
 
@@ -1195,6 +1321,7 @@ fn submit_buffered_commands(
     scene_texture_view: &wgpu::TextureView,
     clip_stack: &mut [PxRect],
     current_batch_draw_rect: &mut Option<PxRect>,
+    scale: f32,
 ) {
     // Take the buffered commands and convert to the transient representation expected by drawer.submit
     let commands = mem::take(buffer);
@@ -1211,7 +1338,7 @@ fn submit_buffered_commands(
     }
 
     let rect = current_batch_draw_rect.unwrap();
-    set_scissor_rect_from_pxrect(rpass, rect);
+    set_scissor_rect_from_pxrect(rpass, rect, scale, config);
 
     drawer.submit(
         gpu,
@@ -1225,13 +1352,22 @@ fn submit_buffered_commands(
     *current_batch_draw_rect = None;
 }
 
-fn set_scissor_rect_from_pxrect(rpass: &mut wgpu::RenderPass<'_>, rect: PxRect) {
-    rpass.set_scissor_rect(
-        rect.x.positive(),
-        rect.y.positive(),
-        rect.width.positive(),
-        rect.height.positive(),
-    );
+/// Sets the scissor rect to `rect`, scaled down to the render target of a frame drawn at
+/// `scale` and clamped to it.
+fn set_scissor_rect_from_pxrect(
+    rpass: &mut wgpu::RenderPass<'_>,
+    rect: PxRect,
+    scale: f32,
+    config: &wgpu::SurfaceConfiguration,
+) {
+    let (width, height) = scaled_size(config.width, config.height, scale);
+    let left = ((rect.x.positive() as f32 * scale).floor() as u32).min(width);
+    let top = ((rect.y.positive() as f32 * scale).floor() as u32).min(height);
+    let right = (((rect.x.positive() + rect.width.positive()) as f32 * scale).ceil() as u32)
+        .clamp(left, width);
+    let bottom = (((rect.y.positive() + rect.height.positive()) as f32 * scale).ceil() as u32)
+        .clamp(top, height);
+    rpass.set_scissor_rect(left, top, right - left, bottom - top);
 }
 
 /// Apply clip_stack to current_batch_draw_rect. Returns false if intersection yields nothing
@@ -0,0 +1,78 @@
+//! Choice of the surface format, which decides how colors are encoded for the display.
+
+use wgpu::TextureFormat;
+
+/// How the window surface stores the colors it shows.
+///
+/// [`Color`](crate::Color) values are linear sRGB. With an sRGB surface format the GPU blends
+/// them in linear space and encodes the result for the display, so gradients and translucent
+/// layers come out the same on every platform.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorOutput {
+    /// 8-bit sRGB, the format every display supports.
+    #[default]
+    Srgb,
+    /// 16-bit float, which compositors that support it (e.g. Windows and macOS) treat as
+    /// extended-range linear sRGB: components below `0.0` reach the wider gamut of
+    /// Display-P3 screens and components above `1.0` are shown brighter on HDR screens.
+    ///
+    /// Falls back to [`Self::Srgb`] where the surface does not offer a float format.
+    Extended,
+}
+
+/// Returns the format the surface is configured with for `output`, out of the `formats` it
+/// supports, most preferred first.
+///
+/// Falls back to an sRGB format, and then to the preferred one.
+pub(crate) fn surface_format(formats: &[TextureFormat], output: ColorOutput) -> TextureFormat {
+    let extended = (output == ColorOutput::Extended)
+        .then(|| formats.iter().find(|&&f| f == TextureFormat::Rgba16Float))
+        .flatten();
+    extended
+        .or_else(|| formats.iter().find(|f| f.is_srgb()))
+        .or(formats.first())
+        .copied()
+        .unwrap_or(TextureFormat::Bgra8UnormSrgb)
+}
+
+/// Returns the color output a surface configured with `format` shows.
+pub(crate) fn color_output(format: TextureFormat) -> ColorOutput {
+    if format == TextureFormat::Rgba16Float {
+        ColorOutput::Extended
+    } else {
+        ColorOutput::Srgb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_format() {
+        let formats = [
+            TextureFormat::Bgra8Unorm,
+            TextureFormat::Bgra8UnormSrgb,
+            TextureFormat::Rgba16Float,
+        ];
+        assert_eq!(
+            surface_format(&formats, ColorOutput::Srgb),
+            TextureFormat::Bgra8UnormSrgb
+        );
+        assert_eq!(
+            surface_format(&formats, ColorOutput::Extended),
+            TextureFormat::Rgba16Float
+        );
+        // Without a float format the surface stays sRGB, without an sRGB one it keeps the
+        // preferred format
+        assert_eq!(
+            surface_format(&formats[..2], ColorOutput::Extended),
+            TextureFormat::Bgra8UnormSrgb
+        );
+        assert_eq!(
+            surface_format(&formats[..1], ColorOutput::Srgb),
+            TextureFormat::Bgra8Unorm
+        );
+        assert_eq!(color_output(formats[1]), ColorOutput::Srgb);
+    }
+}
@@ -0,0 +1,160 @@
+//! Rendering the UI below the window resolution to keep the frame rate on weak GPUs.
+
+use std::time::Duration;
+
+/// Lowest fraction of the window resolution the UI is rendered at.
+const MIN_RENDER_SCALE: f32 = 0.25;
+/// Change of the automatic render scale per adjustment.
+const AUTO_STEP: f32 = 0.125;
+/// Consecutive frames over budget before the automatic scale is lowered.
+const SLOW_FRAMES: u32 = 10;
+/// Consecutive frames within budget before the automatic scale is raised again.
+const FAST_FRAMES: u32 = 300;
+
+/// Resolution the UI is rendered at, relative to the window.
+///
+/// Below `1.0` the frame is drawn into a smaller texture and stretched over the window, trading
+/// sharpness for fill rate. Layout and input still use the full window size. Frames that
+/// contain backdrop effects such as blur or fluid glass are always drawn at full resolution,
+/// since those sample the rendered scene pixel by pixel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RenderScale {
+    /// Renders at the given fraction of the window resolution, clamped to `0.25..=1.0`.
+    Fixed(f32),
+    /// Starts at full resolution and lowers it step by step, down to `min`, while frames take
+    /// longer than `frame_budget`. Once frames meet the budget for a few seconds the
+    /// resolution is raised again.
+    ///
+    /// With vsync frames never take less than the refresh interval, so the budget should be
+    /// somewhat above it, e.g. 20 ms at 60 Hz.
+    Auto {
+        /// Lowest scale to drop to.
+        min: f32,
+        /// Frame time that counts as too slow.
+        frame_budget: Duration,
+    },
+}
+
+impl Default for RenderScale {
+    fn default() -> Self {
+        Self::Fixed(1.0)
+    }
+}
+
+/// Picks the render scale of each frame from a [`RenderScale`] and recent frame times.
+pub(crate) struct RenderScaleController {
+    mode: RenderScale,
+    scale: f32,
+    slow_frames: u32,
+    fast_frames: u32,
+    changed: bool,
+}
+
+impl RenderScaleController {
+    pub(crate) fn new(mode: RenderScale) -> Self {
+        let mut controller = Self {
+            mode,
+            scale: 1.0,
+            slow_frames: 0,
+            fast_frames: 0,
+            changed: false,
+        };
+        controller.set_mode(mode);
+        controller
+    }
+
+    /// Switches to `mode`, starting an automatic scale over at full resolution.
+    pub(crate) fn set_mode(&mut self, mode: RenderScale) {
+        self.mode = mode;
+        self.slow_frames = 0;
+        self.fast_frames = 0;
+        self.set_scale(match mode {
+            RenderScale::Fixed(scale) => scale,
+            RenderScale::Auto { .. } => 1.0,
+        });
+    }
+
+    /// Returns the fraction of the window resolution to render at.
+    pub(crate) fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Returns `true` once after the scale changed, as the frame must be drawn again.
+    pub(crate) fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Adjusts an automatic scale after a frame that took `frame_time`.
+    pub(crate) fn record_frame(&mut self, frame_time: Duration) {
+        let RenderScale::Auto { min, frame_budget } = self.mode else {
+            return;
+        };
+        if frame_time > frame_budget {
+            self.fast_frames = 0;
+            self.slow_frames += 1;
+            if self.slow_frames >= SLOW_FRAMES {
+                self.slow_frames = 0;
+                self.set_scale((self.scale - AUTO_STEP).max(min));
+            }
+        } else {
+            self.slow_frames = 0;
+            self.fast_frames += 1;
+            if self.fast_frames >= FAST_FRAMES {
+                self.fast_frames = 0;
+                self.set_scale(self.scale + AUTO_STEP);
+            }
+        }
+    }
+
+    fn set_scale(&mut self, scale: f32) {
+        let scale = if scale.is_finite() {
+            scale.clamp(MIN_RENDER_SCALE, 1.0)
+        } else {
+            1.0
+        };
+        if scale != self.scale {
+            self.scale = scale;
+            self.changed = true;
+        }
+    }
+}
+
+/// Returns the size of the texture a `width`×`height` frame is rendered into at `scale`.
+pub(crate) fn scaled_size(width: u32, height: u32, scale: f32) -> (u32, u32) {
+    let scaled = |v: u32| ((v as f32 * scale).ceil() as u32).clamp(1, v.max(1));
+    (scaled(width), scaled(height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_render_scale() {
+        let budget = Duration::from_millis(20);
+        let mut controller = RenderScaleController::new(RenderScale::Auto {
+            min: 0.5,
+            frame_budget: budget,
+        });
+        assert_eq!(controller.scale(), 1.0);
+
+        let slow = Duration::from_millis(33);
+        for _ in 0..SLOW_FRAMES * 10 {
+            controller.record_frame(slow);
+        }
+        assert_eq!(controller.scale(), 0.5);
+        assert!(controller.take_changed());
+
+        for _ in 0..FAST_FRAMES {
+            controller.record_frame(Duration::from_millis(16));
+        }
+        assert_eq!(controller.scale(), 0.5 + AUTO_STEP);
+
+        controller.set_mode(RenderScale::Fixed(4.0));
+        assert_eq!(controller.scale(), 1.0);
+        controller.set_mode(RenderScale::Fixed(0.1));
+        assert_eq!(controller.scale(), MIN_RENDER_SCALE);
+        assert_eq!(scaled_size(1920, 1080, 0.5), (960, 540));
+        assert_eq!(scaled_size(3, 0, 0.5), (2, 1));
+    }
+}
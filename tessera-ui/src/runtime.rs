@@ -49,7 +49,13 @@ use std::sync::OnceLock;
 
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-use crate::component_tree::ComponentTree;
+use crate::{
+    component_tree::ComponentTree,
+    gpu_memory::{GpuMemoryCategory, GpuMemoryReport},
+    raw_event::RawEvent,
+    renderer::{ColorOutput, RenderScale},
+    window::{MonitorInfo, WindowGeometry, WindowPlacement},
+};
 
 /// Global singleton instance of the Tessera runtime.
 ///
@@ -58,6 +64,9 @@ use crate::component_tree::ComponentTree;
 /// without the overhead of synchronization after the first initialization.
 static TESSERA_RUNTIME: OnceLock<RwLock<TesseraRuntime>> = OnceLock::new();
 
+/// A callback registered with [`TesseraRuntime::on_raw_event`].
+type RawEventHook = Box<dyn Fn(&RawEvent<'_>) + Send + Sync>;
+
 /// Central runtime state container for the Tessera UI framework.
 ///
 /// The `TesseraRuntime` holds all global state and side effects that need to be shared
@@ -135,6 +144,37 @@ pub struct TesseraRuntime {
     on_close_callbacks: Vec<Box<dyn Fn() + Send + Sync>>,
     /// Whether the window is currently minimized.
     pub(crate) window_minimized: bool,
+    /// Monitors connected when the window was created or last moved.
+    pub(crate) monitors: Vec<MonitorInfo>,
+    /// Size, position and maximized state of the window, once it is known.
+    pub(crate) window_geometry: Option<WindowGeometry>,
+    /// Placement to apply at the end of the current frame.
+    pub(crate) window_placement_request: Option<WindowPlacement>,
+    /// Whether the window is currently in borderless fullscreen.
+    fullscreen: bool,
+    /// Fullscreen state to switch to at the end of the current frame.
+    pub(crate) fullscreen_request: Option<bool>,
+    /// Called when the window enters or leaves fullscreen.
+    on_fullscreen_callbacks: Vec<Box<dyn Fn(bool) + Send + Sync>>,
+    /// Whether there has been no user input for the configured idle timeout.
+    idle: bool,
+    /// Called when the window becomes idle or input resumes.
+    on_idle_callbacks: Vec<Box<dyn Fn(bool) + Send + Sync>>,
+    /// Set by components that need full frame rate even without input this frame.
+    pub(crate) keep_awake_request: bool,
+    /// Fraction of the window resolution the last frame was rendered at, once there was one.
+    pub(crate) render_scale: Option<f32>,
+    /// Render scale to switch to at the start of the next frame.
+    pub(crate) render_scale_request: Option<RenderScale>,
+    /// How the window surface stores colors, once it was created.
+    pub(crate) color_output: ColorOutput,
+    /// Which GPU memory categories were over budget at the end of the last frame.
+    gpu_memory_over_budget: [bool; 4],
+    /// Called when a GPU memory category goes over its budget.
+    on_gpu_memory_exceeded_callbacks:
+        Vec<Box<dyn Fn(GpuMemoryCategory, GpuMemoryReport) + Send + Sync>>,
+    /// Called with every winit event of the window and its input devices.
+    on_raw_event_callbacks: Vec<RawEventHook>,
 }
 
 impl TesseraRuntime {
@@ -289,6 +329,198 @@ impl TesseraRuntime {
         self.window_size
     }
 
+    /// Returns the monitors connected when the window was created or last moved. Their
+    /// indices are the ones used by [`WindowPlacement`].
+    pub fn monitors(&self) -> &[MonitorInfo] {
+        &self.monitors
+    }
+
+    /// Returns the size, position and maximized state of the window, e.g. to save it for the
+    /// next run. `None` before the window exists or where the platform cannot report it.
+    pub fn window_geometry(&self) -> Option<WindowGeometry> {
+        self.window_geometry
+    }
+
+    /// Moves the window, e.g. onto another monitor, at the end of the current frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::{TesseraRuntime, window::WindowPlacement};
+    ///
+    /// TesseraRuntime::with_mut(|rt| {
+    ///     if let Some(index) = rt.monitors().iter().position(|monitor| !monitor.is_primary) {
+    ///         rt.place_window(WindowPlacement::Maximized(index));
+    ///     }
+    /// });
+    /// ```
+    pub fn place_window(&mut self, placement: WindowPlacement) {
+        self.window_placement_request = Some(placement);
+    }
+
+    /// Returns `true` if the window is in borderless fullscreen.
+    pub fn is_fullscreen(&self) -> bool {
+        self.fullscreen
+    }
+
+    /// Enters or leaves borderless fullscreen at the end of the current frame.
+    ///
+    /// By default F11 toggles it too, see
+    /// [`TesseraConfig::fullscreen_key`](crate::renderer::TesseraConfig::fullscreen_key).
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.fullscreen_request = Some(fullscreen);
+    }
+
+    /// Switches between fullscreen and windowed mode at the end of the current frame.
+    pub fn toggle_fullscreen(&mut self) {
+        let fullscreen = self.fullscreen_request.unwrap_or(self.fullscreen);
+        self.fullscreen_request = Some(!fullscreen);
+    }
+
+    /// Registers a per-frame callback for entering (`true`) and leaving (`false`) fullscreen,
+    /// e.g. to hide toolbars and status bars in a presentation mode.
+    /// Components should call this every frame they wish to be notified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::{
+    ///     Arc,
+    ///     atomic::{AtomicBool, Ordering},
+    /// };
+    /// use tessera_ui::TesseraRuntime;
+    ///
+    /// let show_toolbar = Arc::new(AtomicBool::new(true));
+    /// let flag = show_toolbar.clone();
+    /// TesseraRuntime::with_mut(|rt| {
+    ///     rt.on_fullscreen(move |fullscreen| flag.store(!fullscreen, Ordering::Relaxed))
+    /// });
+    /// ```
+    pub fn on_fullscreen(&mut self, callback: impl Fn(bool) + Send + Sync + 'static) {
+        self.on_fullscreen_callbacks.push(Box::new(callback));
+    }
+
+    /// Returns `true` while there has been no user input for
+    /// [`TesseraConfig::idle_timeout`](crate::renderer::TesseraConfig::idle_timeout).
+    ///
+    /// While idle, frames are drawn only every
+    /// [`TesseraConfig::idle_frame_interval`](crate::renderer::TesseraConfig::idle_frame_interval),
+    /// so animations keep running at a lower rate. The next input resumes the full frame rate.
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    /// Registers a per-frame callback for the window becoming idle (`true`) and input
+    /// resuming (`false`), e.g. to pause polling or autosave.
+    /// Components should call this every frame they wish to be notified.
+    pub fn on_idle(&mut self, callback: impl Fn(bool) + Send + Sync + 'static) {
+        self.on_idle_callbacks.push(Box::new(callback));
+    }
+
+    /// Keeps the full frame rate this frame, as if there had been input, e.g. while playing a
+    /// video. Components should call this every frame they need it.
+    pub fn keep_awake(&mut self) {
+        self.keep_awake_request = true;
+    }
+
+    /// Returns the fraction of the window resolution the UI is currently rendered at, `1.0`
+    /// for full resolution.
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale.unwrap_or(1.0)
+    }
+
+    /// Changes the resolution the UI is rendered at from the next frame on, e.g. from a
+    /// "performance mode" setting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use tessera_ui::{TesseraRuntime, renderer::RenderScale};
+    ///
+    /// TesseraRuntime::with_mut(|rt| {
+    ///     rt.set_render_scale(RenderScale::Auto {
+    ///         min: 0.5,
+    ///         frame_budget: Duration::from_millis(20),
+    ///     })
+    /// });
+    /// ```
+    pub fn set_render_scale(&mut self, render_scale: RenderScale) {
+        self.render_scale_request = Some(render_scale);
+    }
+
+    /// Returns how the window surface stores colors. [`ColorOutput::Extended`] was asked for
+    /// in the [`TesseraConfig`](crate::renderer::TesseraConfig) if it is not supported, so
+    /// this tells whether colors outside of sRGB can be shown.
+    pub fn color_output(&self) -> ColorOutput {
+        self.color_output
+    }
+
+    /// Registers a per-frame callback for a GPU memory category going over its
+    /// [budget](crate::gpu_memory::set_budget) even after its caches evicted what they could,
+    /// called with the category and the memory in use.
+    /// Components should call this every frame they wish to be notified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tessera_ui::{TesseraRuntime, gpu_memory::GpuMemoryCategory};
+    ///
+    /// TesseraRuntime::with_mut(|rt| {
+    ///     rt.on_gpu_memory_exceeded(|category, report| {
+    ///         if category == GpuMemoryCategory::Images {
+    ///             println!("images use {} bytes, dropping thumbnails", report.bytes(category));
+    ///         }
+    ///     })
+    /// });
+    /// ```
+    pub fn on_gpu_memory_exceeded(
+        &mut self,
+        callback: impl Fn(GpuMemoryCategory, GpuMemoryReport) + Send + Sync + 'static,
+    ) {
+        self.on_gpu_memory_exceeded_callbacks
+            .push(Box::new(callback));
+    }
+
+    /// Records the GPU memory in use after a frame, notifying the callbacks of the categories
+    /// that went over their budget.
+    pub(crate) fn update_gpu_memory(&mut self, report: GpuMemoryReport, budgets: [Option<u64>; 4]) {
+        let over_budget = report.over_budget(budgets);
+        for (category, (&over, was_over)) in GpuMemoryCategory::ALL
+            .into_iter()
+            .zip(over_budget.iter().zip(self.gpu_memory_over_budget))
+        {
+            if over && !was_over {
+                for callback in &self.on_gpu_memory_exceeded_callbacks {
+                    callback(category, report);
+                }
+            }
+        }
+        self.gpu_memory_over_budget = over_budget;
+    }
+
+    /// Records whether the window is idle, notifying the callbacks when it changed.
+    pub(crate) fn update_idle(&mut self, idle: bool) {
+        if self.idle == idle {
+            return;
+        }
+        self.idle = idle;
+        for callback in &self.on_idle_callbacks {
+            callback(idle);
+        }
+    }
+
+    /// Records the fullscreen state of the window, notifying the callbacks when it changed.
+    pub(crate) fn update_fullscreen(&mut self, fullscreen: bool) {
+        if self.fullscreen == fullscreen {
+            return;
+        }
+        self.fullscreen = fullscreen;
+        for callback in &self.on_fullscreen_callbacks {
+            callback(fullscreen);
+        }
+    }
+
     /// Registers a per-frame callback for minimize state changes.
     /// Components should call this every frame they wish to be notified.
     pub fn on_minimize(&mut self, callback: impl Fn(bool) + Send + Sync + 'static) {
@@ -301,11 +533,32 @@ impl TesseraRuntime {
         self.on_close_callbacks.push(Box::new(callback));
     }
 
+    /// Registers a per-frame callback for the unprocessed winit events, see
+    /// [`crate::raw_event`]. Components should call this every frame they wish to be notified.
+    ///
+    /// The events reach the callbacks registered in the last frame, in addition to the normal
+    /// input handling.
+    pub fn on_raw_event(&mut self, callback: impl Fn(&RawEvent<'_>) + Send + Sync + 'static) {
+        self.on_raw_event_callbacks.push(Box::new(callback));
+    }
+
+    /// Passes `event` to the callbacks registered with [`Self::on_raw_event`].
+    pub(crate) fn trigger_raw_event_callbacks(&self, event: &RawEvent<'_>) {
+        for callback in &self.on_raw_event_callbacks {
+            callback(event);
+        }
+    }
+
     /// Clears all per-frame registered callbacks.
     /// Must be called by the event loop at the beginning of each frame.
     pub fn clear_frame_callbacks(&mut self) {
         self.on_minimize_callbacks.clear();
         self.on_close_callbacks.clear();
+        self.on_fullscreen_callbacks.clear();
+        self.on_idle_callbacks.clear();
+        self.on_gpu_memory_exceeded_callbacks.clear();
+        self.on_raw_event_callbacks.clear();
+        self.keep_awake_request = false;
     }
 
     /// Triggers all registered callbacks (global and per-frame).
@@ -324,3 +577,33 @@ impl TesseraRuntime {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use winit::event::WindowEvent;
+
+    use super::*;
+
+    #[test]
+    fn test_raw_event_hooks() {
+        let mut runtime = TesseraRuntime::default();
+        let focus_changes = Arc::new(Mutex::new(Vec::new()));
+        let focus_changes_in_hook = focus_changes.clone();
+        runtime.on_raw_event(move |event| {
+            if let RawEvent::Window(WindowEvent::Focused(focused)) = event {
+                focus_changes_in_hook.lock().unwrap().push(*focused);
+            }
+        });
+
+        runtime.trigger_raw_event_callbacks(&RawEvent::Window(&WindowEvent::Focused(false)));
+        runtime.trigger_raw_event_callbacks(&RawEvent::Window(&WindowEvent::Focused(true)));
+        assert_eq!(*focus_changes.lock().unwrap(), [false, true]);
+
+        // Hooks only last for the frame they were registered in
+        runtime.clear_frame_callbacks();
+        runtime.trigger_raw_event_callbacks(&RawEvent::Window(&WindowEvent::Focused(false)));
+        assert_eq!(*focus_changes.lock().unwrap(), [false, true]);
+    }
+}
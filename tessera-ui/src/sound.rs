@@ -0,0 +1,253 @@
+//! # Sound
+//!
+//! Short audio cues for UI events, such as a switch being toggled or an error being shown,
+//! which make an application easier to follow without looking at it.
+//!
+//! The standard components play [`Cue::Toggle`] themselves. Applications play the other cues,
+//! or their own [`Cue::Custom`] ones, with [`play`]. Every cue can be given the encoded audio
+//! of a sound file with [`set_cue_sound`], e.g. a WAV or Ogg Vorbis file; the built-in cues
+//! otherwise play a short tone. The volume and muting apply to all cues.
+//!
+//! Sound is only played with the `audio` feature, which opens the default output device in
+//! the background on the first cue and plays the cues from until then once it is open.
+//! Without it, or without an output device, the functions keep the settings but play
+//! nothing, so components can play cues unconditionally.
+//!
+//! ```
+//! use tessera_ui::sound::{self, Cue};
+//!
+//! // E.g. read from a file shipped with the application
+//! let chime: Vec<u8> = Vec::new();
+//! sound::set_cue_sound(Cue::Custom("saved"), Some(chime.into()));
+//! sound::set_volume(0.5);
+//! sound::play(Cue::Error);
+//! sound::set_muted(true);
+//! assert_eq!(sound::gain(), 0.0);
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
+
+use parking_lot::RwLock;
+
+/// An audio cue for a UI event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Cue {
+    /// A switch, checkbox or similar control changed its state.
+    Toggle,
+    /// An error was shown, e.g. in a banner or a dialog.
+    Error,
+    /// A notification arrived.
+    Notification,
+    /// A cue of the application, which only plays once given a sound with
+    /// [`set_cue_sound`].
+    Custom(&'static str),
+}
+
+struct Settings {
+    volume: f32,
+    muted: bool,
+}
+
+static SETTINGS: RwLock<Settings> = RwLock::new(Settings {
+    volume: 0.5,
+    muted: false,
+});
+static CUE_SOUNDS: LazyLock<RwLock<HashMap<Cue, Arc<[u8]>>>> = LazyLock::new(Default::default);
+
+/// Plays `cue` at the current [gain](gain), without waiting for it to finish.
+pub fn play(cue: Cue) {
+    let gain = gain();
+    if gain <= 0.0 {
+        return;
+    }
+    let sound = CUE_SOUNDS.read().get(&cue).cloned();
+    #[cfg(feature = "audio")]
+    match sound {
+        Some(data) => output::play_encoded(data, gain),
+        None => output::play_tones(cue, gain),
+    }
+    #[cfg(not(feature = "audio"))]
+    let _ = sound;
+}
+
+/// Sets the encoded audio `cue` plays, or goes back to its built-in tone with `None`.
+///
+/// The data can be in any format the audio backend decodes, such as WAV, FLAC, MP3 or
+/// Ogg Vorbis. It is decoded each time the cue plays, so a cue that fails to decode is
+/// reported then.
+pub fn set_cue_sound(cue: Cue, sound: Option<Arc<[u8]>>) {
+    let mut sounds = CUE_SOUNDS.write();
+    match sound {
+        Some(sound) => sounds.insert(cue, sound),
+        None => sounds.remove(&cue),
+    };
+}
+
+/// Returns the volume of the cues, between `0.0` and `1.0`.
+pub fn volume() -> f32 {
+    SETTINGS.read().volume
+}
+
+/// Sets the volume of the cues, clamped between `0.0` and `1.0`. Defaults to `0.5`.
+///
+/// A NaN volume is ignored.
+pub fn set_volume(volume: f32) {
+    if volume.is_nan() {
+        return;
+    }
+    SETTINGS.write().volume = volume.clamp(0.0, 1.0);
+}
+
+/// Returns `true` if the cues are muted.
+pub fn is_muted() -> bool {
+    SETTINGS.read().muted
+}
+
+/// Mutes or unmutes the cues, keeping the volume.
+pub fn set_muted(muted: bool) {
+    SETTINGS.write().muted = muted;
+}
+
+/// Returns the factor the cues are played at: the volume, or `0.0` while muted.
+pub fn gain() -> f32 {
+    let settings = SETTINGS.read();
+    if settings.muted { 0.0 } else { settings.volume }
+}
+
+#[cfg(feature = "audio")]
+mod output {
+    use std::{io::Cursor, sync::Arc, thread, time::Duration};
+
+    use parking_lot::Mutex;
+    use rodio::{Decoder, OutputStreamBuilder, Source, mixer::Mixer, source::SineWave};
+
+    use super::Cue;
+
+    /// Length of the fade-in of a tone, which keeps it from clicking.
+    const FADE: Duration = Duration::from_millis(5);
+
+    /// Adds a cue to the mixer.
+    type Play = Box<dyn FnOnce(&Mixer) + Send>;
+
+    /// The default output device.
+    enum Device {
+        /// Not opened yet, no cue was played.
+        Closed,
+        /// Being opened, with the cues played meanwhile.
+        Opening(Vec<Play>),
+        /// Open, cues are added to its mixer.
+        Open(Mixer),
+        /// Failed to open, cues are not played.
+        Unavailable,
+    }
+
+    static DEVICE: Mutex<Device> = Mutex::new(Device::Closed);
+
+    /// Runs `play` with the mixer of the default output device.
+    ///
+    /// The first call starts opening the device on a thread of its own, since that can take
+    /// a while, and cues played until it is open are queued for it.
+    fn with_mixer(play: impl FnOnce(&Mixer) + Send + 'static) {
+        let mut device = DEVICE.lock();
+        match &mut *device {
+            Device::Open(mixer) => play(mixer),
+            Device::Opening(queued) => queued.push(Box::new(play)),
+            Device::Unavailable => {}
+            Device::Closed => {
+                *device = match open() {
+                    Ok(()) => Device::Opening(vec![Box::new(play)]),
+                    Err(err) => {
+                        tracing::warn!("Audio cues are unavailable: {err}");
+                        Device::Unavailable
+                    }
+                };
+            }
+        }
+    }
+
+    /// Opens the default output device on a new thread, which then plays the queued cues.
+    fn open() -> std::io::Result<()> {
+        // rodio stops playing when the OutputStream is dropped, and the cpal stream inside
+        // it is not Send, so it can't be kept in the static. The thread owns it for the
+        // rest of the process and stores its Mixer, which is a Send handle cues are added
+        // to from any thread.
+        thread::Builder::new()
+            .name("tessera-audio".to_string())
+            .spawn(|| match OutputStreamBuilder::open_default_stream() {
+                Ok(stream) => {
+                    let mixer = stream.mixer().clone();
+                    let opening =
+                        std::mem::replace(&mut *DEVICE.lock(), Device::Open(mixer.clone()));
+                    if let Device::Opening(queued) = opening {
+                        for play in queued {
+                            play(&mixer);
+                        }
+                    }
+                    loop {
+                        thread::park();
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("Audio cues are unavailable: {err}");
+                    *DEVICE.lock() = Device::Unavailable;
+                }
+            })
+            .map(drop)
+    }
+
+    /// Returns the built-in tones of `cue` as frequencies in Hz and lengths in milliseconds.
+    fn tones(cue: Cue) -> &'static [(f32, u64)] {
+        match cue {
+            Cue::Toggle => &[(880.0, 40)],
+            Cue::Error => &[(330.0, 90), (220.0, 160)],
+            Cue::Notification => &[(660.0, 80), (990.0, 120)],
+            Cue::Custom(_) => &[],
+        }
+    }
+
+    pub(super) fn play_tones(cue: Cue, gain: f32) {
+        with_mixer(move |mixer| {
+            let mut start = Duration::ZERO;
+            for &(frequency, millis) in tones(cue) {
+                let length = Duration::from_millis(millis);
+                let tone = SineWave::new(frequency)
+                    .take_duration(length - FADE)
+                    .fade_in(FADE)
+                    .amplify(gain * 0.3)
+                    .delay(start);
+                mixer.add(tone);
+                start += length;
+            }
+        });
+    }
+
+    pub(super) fn play_encoded(data: Arc<[u8]>, gain: f32) {
+        with_mixer(move |mixer| match Decoder::new(Cursor::new(data)) {
+            Ok(source) => mixer.add(source.amplify(gain)),
+            Err(err) => tracing::warn!("Failed to decode an audio cue: {err}"),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gain() {
+        set_volume(1.5);
+        assert_eq!(volume(), 1.0);
+        set_volume(0.25);
+        assert_eq!(gain(), 0.25);
+        set_muted(true);
+        assert_eq!(gain(), 0.0);
+        assert_eq!(volume(), 0.25);
+        set_muted(false);
+        assert_eq!(gain(), 0.25);
+        set_volume(f32::NAN);
+        assert_eq!(volume(), 0.25);
+    }
+}
@@ -0,0 +1,170 @@
+//! # Speech
+//!
+//! Reading text aloud, e.g. an editor line for proofreading or a message for users who
+//! cannot see the screen.
+//!
+//! [`speak`] hands the text to the [`SpeechBackend`], interrupting what was being read.
+//! With the `tts` feature the backend is the speech synthesizer of the platform, started on
+//! the first call; without it, or where the platform has none, nothing is read. An
+//! application can replace the backend with [`set_backend`], e.g. to use its own voices or
+//! to log the text in tests.
+//!
+//! ```
+//! use std::sync::Arc;
+//! use tessera_ui::speech::{self, SpeechBackend};
+//!
+//! struct Log;
+//!
+//! impl SpeechBackend for Log {
+//!     fn speak(&self, text: &str) {
+//!         println!("speak {text:?}");
+//!     }
+//!
+//!     fn stop(&self) {}
+//! }
+//!
+//! speech::set_backend(Some(Arc::new(Log)));
+//! speech::speak("Hello");
+//! speech::stop();
+//! ```
+
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+/// Reads text aloud.
+pub trait SpeechBackend: Send + Sync {
+    /// Starts reading `text`, stopping what was being read, without waiting for it to
+    /// finish.
+    fn speak(&self, text: &str);
+
+    /// Stops reading.
+    fn stop(&self);
+}
+
+static BACKEND: RwLock<Option<Arc<dyn SpeechBackend>>> = RwLock::new(None);
+
+/// Reads `text` aloud with the current backend, interrupting what was being read. Blank
+/// text only stops it.
+pub fn speak(text: &str) {
+    if text.trim().is_empty() {
+        stop();
+        return;
+    }
+    match BACKEND.read().as_ref() {
+        Some(backend) => backend.speak(text),
+        None => platform::speak(text),
+    }
+}
+
+/// Stops reading with the current backend.
+pub fn stop() {
+    match BACKEND.read().as_ref() {
+        Some(backend) => backend.stop(),
+        None => platform::stop(),
+    }
+}
+
+/// Replaces the speech synthesizer of the platform, or goes back to it with `None`.
+pub fn set_backend(backend: Option<Arc<dyn SpeechBackend>>) {
+    *BACKEND.write() = backend;
+}
+
+#[cfg(not(feature = "tts"))]
+mod platform {
+    pub(super) fn speak(_text: &str) {}
+
+    pub(super) fn stop() {}
+}
+
+#[cfg(feature = "tts")]
+mod platform {
+    use std::{
+        sync::{
+            OnceLock,
+            mpsc::{self, Sender},
+        },
+        thread,
+    };
+
+    enum Request {
+        Speak(String),
+        Stop,
+    }
+
+    static REQUESTS: OnceLock<Option<Sender<Request>>> = OnceLock::new();
+
+    /// Sends `request` to the synthesizer, starting it on the first call.
+    fn send(request: Request) {
+        let requests = REQUESTS.get_or_init(|| {
+            let (sender, receiver) = mpsc::channel();
+            // tts::Tts shares its backend through an Rc, so it is neither Send nor Sync and
+            // can't be kept in the static. The thread that creates it owns it and takes the
+            // requests over a channel, which also keeps slow backend calls, like connecting
+            // to speech-dispatcher on Linux, off the UI thread.
+            thread::Builder::new()
+                .name("tessera-speech".to_string())
+                .spawn(move || {
+                    let mut tts = match tts::Tts::default() {
+                        Ok(tts) => tts,
+                        Err(err) => {
+                            tracing::warn!("Speech is unavailable: {err}");
+                            return;
+                        }
+                    };
+                    for request in receiver {
+                        let result = match request {
+                            Request::Speak(text) => tts.speak(text, true).map(drop),
+                            Request::Stop => tts.stop().map(drop),
+                        };
+                        if let Err(err) = result {
+                            tracing::warn!("Failed to speak: {err}");
+                        }
+                    }
+                })
+                .ok()?;
+            Some(sender)
+        });
+        if let Some(sender) = requests {
+            let _ = sender.send(request);
+        }
+    }
+
+    pub(super) fn speak(text: &str) {
+        send(Request::Speak(text.to_string()));
+    }
+
+    pub(super) fn stop() {
+        send(Request::Stop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Log(parking_lot::Mutex<Vec<String>>);
+
+    impl SpeechBackend for Log {
+        fn speak(&self, text: &str) {
+            self.0.lock().push(text.to_string());
+        }
+
+        fn stop(&self) {
+            self.0.lock().push("<stop>".to_string());
+        }
+    }
+
+    #[test]
+    fn test_speak_with_backend() {
+        let log = Arc::new(Log::default());
+        set_backend(Some(log.clone()));
+        speak("first line");
+        speak("  \n");
+        stop();
+        set_backend(None);
+        speak("not logged");
+        assert_eq!(*log.0.lock(), ["first line", "<stop>", "<stop>"]);
+    }
+}
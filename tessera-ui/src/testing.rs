@@ -0,0 +1,102 @@
+//! # Testing
+//!
+//! Helpers for tests that build a UI and look for the components in it, without opening a
+//! window.
+//!
+//! A component is found by the test id given to it with [`set_test_id`] just before it is
+//! called, or by its role: the name of its component function, e.g. `"button"` for every
+//! button. [`compose`] builds the UI once and returns its [`ComponentTree`], whose
+//! [`find_by_test_id`](ComponentTree::find_by_test_id) and
+//! [`find_all_by_role`](ComponentTree::find_all_by_role) return the nodes to check.
+//!
+//! Composing only builds the tree: the components register their layout and input handlers
+//! but are not measured, as that needs a GPU device. With the `proptest` feature,
+//! `layout` measures components under random constraints and checks their layout.
+//!
+//! ```
+//! use tessera_ui::{tessera, testing};
+//!
+//! #[tessera]
+//! fn toolbar() {
+//!     testing::set_test_id("save");
+//!     tool();
+//!     tool();
+//! }
+//!
+//! #[tessera]
+//! fn tool() {}
+//!
+//! let tree = testing::compose(toolbar);
+//! let save = tree.find_by_test_id("save").unwrap();
+//! assert_eq!(tree.find_all_by_role("tool")[0], save);
+//! assert_eq!(tree.find_all_by_role("tool").len(), 2);
+//! ```
+
+#[cfg(feature = "proptest")]
+pub mod layout;
+
+use parking_lot::Mutex;
+
+use crate::{ComponentTree, TesseraRuntime};
+
+/// Keeps tests running in parallel from building into the same tree.
+static COMPOSE: Mutex<()> = Mutex::new(());
+
+/// Gives the next component called the test id `test_id`.
+///
+/// The id is dropped if the component calling this returns before calling another one.
+pub fn set_test_id(test_id: impl Into<String>) {
+    TesseraRuntime::with_mut(|runtime| runtime.component_tree.set_next_test_id(test_id.into()));
+}
+
+/// Builds the UI of `ui` and returns its component tree.
+///
+/// This replaces the tree of the current frame, so it is meant for tests rather than a
+/// running application.
+pub fn compose(ui: impl FnOnce()) -> ComponentTree {
+    let _guard = COMPOSE.lock();
+    TesseraRuntime::with_mut(|runtime| runtime.component_tree.clear());
+    ui();
+    TesseraRuntime::with_mut(|runtime| std::mem::take(&mut runtime.component_tree))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tessera;
+
+    use super::*;
+
+    #[tessera(crate)]
+    fn row(content: impl FnOnce()) {
+        content();
+    }
+
+    #[tessera(crate)]
+    fn cell() {
+        set_test_id("unused");
+    }
+
+    #[tessera(crate)]
+    fn label() {}
+
+    #[test]
+    fn test_find_nodes() {
+        let tree = compose(|| {
+            set_test_id("outer");
+            row(|| {
+                label();
+                set_test_id("given");
+                label();
+                cell();
+                label();
+            })
+        });
+        let outer = tree.find_by_test_id("outer").unwrap();
+        assert_eq!(tree.get(outer).unwrap().fn_name, "row");
+        let labels = tree.find_all_by_role("label");
+        assert_eq!(labels.len(), 3);
+        assert_eq!(tree.find_by_test_id("given"), Some(labels[1]));
+        assert!(tree.find_by_test_id("unused").is_none());
+        assert!(tree.find_all_by_role("button").is_empty());
+    }
+}
@@ -0,0 +1,365 @@
+//! # Layout checks
+//!
+//! Property-based helpers that measure a component under random constraints, with random
+//! children, and check the layout invariants every component should keep:
+//!
+//! - Measuring the same tree again gives the same sizes and positions.
+//! - Under a `Wrap` or `Fill` constraint the component is no larger than its `max`, and no
+//!   smaller than its `min`.
+//! - Children are placed within their parent, unless the parent declares the overflow by
+//!   [clipping](crate::MeasureInput::enable_clipping) its children.
+//!
+//! Measuring needs a GPU device, see [`test_device`]. The strategies generate
+//! [`Constraint`]s and trees of [`LayoutNode`] boxes to use as children of the component
+//! under test:
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use tessera_ui::{tessera, testing::layout};
+//!
+//! #[tessera]
+//! fn stack(content: impl FnOnce()) {
+//!     content();
+//! }
+//!
+//! proptest! {
+//!     #[test]
+//!     fn stack_keeps_its_bounds(
+//!         children in proptest::collection::vec(layout::layout_node(200, 2, 3), 0..4),
+//!         constraint in layout::constraint(400),
+//!     ) {
+//!         let Some(gpu) = layout::test_device() else {
+//!             return Ok(());
+//!         };
+//!         layout::check_layout(
+//!             || stack(|| children.iter().for_each(layout::LayoutNode::compose)),
+//!             constraint,
+//!             gpu,
+//!         )?;
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::RwLock;
+use proptest::{collection::vec, option, prelude::*, test_runner::TestCaseError};
+
+use crate::{
+    ComponentTree, ComputeResourceManager, ComputedData, Constraint, DimensionValue,
+    MeasurementError, Px, PxPosition, measure_node, tessera, testing::compose,
+};
+
+/// The measured layout of a component and its descendants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutSnapshot {
+    /// Name of the component function.
+    pub role: String,
+    /// Measured size.
+    pub size: ComputedData,
+    /// Position relative to the parent, `None` if the parent did not place it.
+    pub position: Option<PxPosition>,
+    /// Whether the component clips its children, allowing them to overflow it.
+    pub clips_children: bool,
+    /// Snapshots of the children, in build order.
+    pub children: Vec<LayoutSnapshot>,
+}
+
+/// A box in a random component tree, see [`layout_node`].
+///
+/// Composed, the box measures its children under its own size merged with the parent
+/// constraint, places them at its top left corner and wraps the largest of them within that
+/// constraint. It clips the children that do not fit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutNode {
+    /// Width of the box.
+    pub width: DimensionValue,
+    /// Height of the box.
+    pub height: DimensionValue,
+    /// Boxes inside this one.
+    pub children: Vec<LayoutNode>,
+}
+
+impl LayoutNode {
+    /// Composes the box and its children as `layout_box` components.
+    pub fn compose(&self) {
+        layout_box(self.clone());
+    }
+}
+
+#[tessera(crate)]
+fn layout_box(node: LayoutNode) {
+    let (width, height) = (node.width, node.height);
+    measure(Box::new(move |input| {
+        let constraint = Constraint::new(width, height).merge(input.parent_constraint);
+        let children = input
+            .children_ids
+            .iter()
+            .map(|&child| (child, constraint))
+            .collect();
+        let sizes = input.measure_children(children)?;
+        let content = sizes
+            .values()
+            .fold(ComputedData::ZERO, |content, &size| content.max(size));
+        let size = ComputedData {
+            width: resolve(constraint.width, content.width),
+            height: resolve(constraint.height, content.height),
+        };
+        for &child in input.children_ids {
+            input.place_child(child, PxPosition::ZERO);
+        }
+        if sizes
+            .values()
+            .any(|child| child.width > size.width || child.height > size.height)
+        {
+            input.enable_clipping();
+        }
+        Ok(size)
+    }));
+    for child in &node.children {
+        child.compose();
+    }
+}
+
+/// Returns the size of a box along `dimension` with content `content` long.
+fn resolve(dimension: DimensionValue, content: Px) -> Px {
+    let (size, min, max) = match dimension {
+        DimensionValue::Fixed(value) => return value,
+        DimensionValue::Wrap { min, max } => (content, min, max),
+        // A box fills up to its max, or wraps without one
+        DimensionValue::Fill { min, max } => (max.unwrap_or(content), min, max),
+    };
+    let size = size.max(min.unwrap_or(Px(0)));
+    max.map_or(size, |max| size.min(max))
+}
+
+/// Generates a length from zero to `max` pixels.
+pub fn px(max: i32) -> impl Strategy<Value = Px> + Clone {
+    (0..=max).prop_map(Px)
+}
+
+/// Generates a fixed, wrapping or filling length of at most `max` pixels, with optional
+/// bounds where `min` is never larger than `max`.
+pub fn dimension_value(max: i32) -> impl Strategy<Value = DimensionValue> + Clone {
+    let bounds =
+        (option::of(px(max)), option::of(px(max))).prop_map(|(min, max)| match (min, max) {
+            (Some(min), Some(max)) if min > max => (Some(max), Some(min)),
+            bounds => bounds,
+        });
+    prop_oneof![
+        px(max).prop_map(DimensionValue::Fixed),
+        bounds
+            .clone()
+            .prop_map(|(min, max)| DimensionValue::Wrap { min, max }),
+        bounds.prop_map(|(min, max)| DimensionValue::Fill { min, max }),
+    ]
+}
+
+/// Generates a constraint of [`dimension_value`]s of at most `max` pixels.
+pub fn constraint(max: i32) -> impl Strategy<Value = Constraint> + Clone {
+    (dimension_value(max), dimension_value(max))
+        .prop_map(|(width, height)| Constraint::new(width, height))
+}
+
+/// Generates a tree of boxes at most `max` pixels large, `depth` levels deep, each with up to
+/// `breadth` children.
+pub fn layout_node(max: i32, depth: u32, breadth: usize) -> impl Strategy<Value = LayoutNode> {
+    let leaf =
+        (dimension_value(max), dimension_value(max)).prop_map(|(width, height)| LayoutNode {
+            width,
+            height,
+            children: Vec::new(),
+        });
+    leaf.prop_recursive(depth, 64, breadth as u32, move |inner| {
+        (
+            dimension_value(max),
+            dimension_value(max),
+            vec(inner, 0..=breadth),
+        )
+            .prop_map(|(width, height, children)| LayoutNode {
+                width,
+                height,
+                children,
+            })
+    })
+}
+
+/// Returns a GPU device to measure with, or `None` where no adapter is available, e.g. on a
+/// CI runner without a software renderer. Tests should then pass without checking.
+pub fn test_device() -> Option<&'static wgpu::Device> {
+    static DEVICE: OnceLock<Option<wgpu::Device>> = OnceLock::new();
+    DEVICE
+        .get_or_init(|| {
+            let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+                backends: wgpu::Backends::all(),
+                ..Default::default()
+            });
+            let adapter = pollster::block_on(instance.request_adapter(&Default::default())).ok()?;
+            let (gpu, _queue) =
+                pollster::block_on(adapter.request_device(&Default::default())).ok()?;
+            Some(gpu)
+        })
+        .as_ref()
+}
+
+/// Builds the UI of `ui` and measures it under `constraint`.
+pub fn measure(
+    ui: impl FnOnce(),
+    constraint: Constraint,
+    gpu: &wgpu::Device,
+) -> Result<LayoutSnapshot, MeasurementError> {
+    measure_tree(&compose(ui), constraint, gpu)
+}
+
+/// Builds the UI of `ui`, measures it twice under `constraint` and checks the layout
+/// invariants listed in the [module documentation](self). Returns the measured layout.
+pub fn check_layout(
+    ui: impl FnOnce(),
+    constraint: Constraint,
+    gpu: &wgpu::Device,
+) -> Result<LayoutSnapshot, TestCaseError> {
+    let tree = compose(ui);
+    let fail = |e: MeasurementError| TestCaseError::fail(format!("measuring failed: {e:?}"));
+    let first = measure_tree(&tree, constraint, gpu).map_err(fail)?;
+    let second = measure_tree(&tree, constraint, gpu).map_err(fail)?;
+    prop_assert_eq!(&first, &second, "measuring again changed the layout");
+    check_bounds(first.size.width, constraint.width, "width")?;
+    check_bounds(first.size.height, constraint.height, "height")?;
+    check_children(&first)?;
+    Ok(first)
+}
+
+/// Measures the root of `tree` under `constraint` and snapshots the result.
+fn measure_tree(
+    tree: &ComponentTree,
+    constraint: Constraint,
+    gpu: &wgpu::Device,
+) -> Result<LayoutSnapshot, MeasurementError> {
+    let root = tree.root().ok_or(MeasurementError::NodeNotFoundInTree)?;
+    let compute_resource_manager = Arc::new(RwLock::new(ComputeResourceManager::new()));
+    measure_node(
+        root,
+        &constraint,
+        tree.nodes(),
+        tree.metadatas(),
+        compute_resource_manager,
+        gpu,
+    )?;
+    snapshot(tree, root).ok_or(MeasurementError::NodeNotFoundInMeta)
+}
+
+/// Snapshots the measured layout of `node_id` and its descendants.
+fn snapshot(tree: &ComponentTree, node_id: indextree::NodeId) -> Option<LayoutSnapshot> {
+    let (size, position, clips_children) = {
+        let metadata = tree.metadatas().get(&node_id)?;
+        (
+            metadata.computed_data?,
+            metadata.rel_position,
+            metadata.clips_children,
+        )
+    };
+    let children = node_id
+        .children(tree.nodes())
+        .map(|child| snapshot(tree, child))
+        .collect::<Option<_>>()?;
+    Some(LayoutSnapshot {
+        role: tree.get(node_id)?.fn_name.clone(),
+        size,
+        position,
+        clips_children,
+        children,
+    })
+}
+
+/// Checks that `size` is within the bounds of a `Wrap` or `Fill` `dimension`.
+fn check_bounds(size: Px, dimension: DimensionValue, axis: &str) -> Result<(), TestCaseError> {
+    let (min, max) = match dimension {
+        DimensionValue::Fixed(_) => return Ok(()),
+        DimensionValue::Wrap { min, max } | DimensionValue::Fill { min, max } => (min, max),
+    };
+    if let Some(max) = max {
+        prop_assert!(
+            size <= max,
+            "{axis} {size:?} is over the max of {dimension:?}"
+        );
+    }
+    if let Some(min) = min.filter(|&min| max.is_none_or(|max| min <= max)) {
+        prop_assert!(
+            size >= min,
+            "{axis} {size:?} is under the min of {dimension:?}"
+        );
+    }
+    Ok(())
+}
+
+/// Checks that the placed children of `node` and its descendants are within their parent,
+/// unless the parent clips them.
+fn check_children(node: &LayoutSnapshot) -> Result<(), TestCaseError> {
+    for child in &node.children {
+        if let Some(position) = child.position
+            && !node.clips_children
+        {
+            prop_assert!(
+                position.x >= Px(0)
+                    && position.y >= Px(0)
+                    && position.x + child.size.width <= node.size.width
+                    && position.y + child.size.height <= node.size.height,
+                "{} at {position:?} of size {:?} overflows {} of size {:?}",
+                child.role,
+                child.size,
+                node.role,
+                node.size
+            );
+        }
+        check_children(child)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn test_layout_boxes(
+            children in vec(layout_node(200, 2, 3), 0..4),
+            constraint in constraint(400),
+        ) {
+            let Some(gpu) = test_device() else {
+                return Ok(());
+            };
+            let root = LayoutNode {
+                width: DimensionValue::WRAP,
+                height: DimensionValue::WRAP,
+                children,
+            };
+            let layout = check_layout(|| root.compose(), constraint, gpu)?;
+            prop_assert_eq!(layout.role, "layout_box");
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let content = Px(50);
+        assert_eq!(resolve(DimensionValue::Fixed(Px(10)), content), Px(10));
+        assert_eq!(resolve(DimensionValue::WRAP, content), Px(50));
+        let wrap = DimensionValue::Wrap {
+            min: Some(Px(60)),
+            max: Some(Px(80)),
+        };
+        assert_eq!(resolve(wrap, content), Px(60));
+        let fill = DimensionValue::Fill {
+            min: None,
+            max: Some(Px(40)),
+        };
+        assert_eq!(resolve(fill, content), Px(40));
+        // A max under the min wins
+        let wrap = DimensionValue::Wrap {
+            min: Some(Px(90)),
+            max: Some(Px(30)),
+        };
+        assert_eq!(resolve(wrap, content), Px(30));
+    }
+}
@@ -0,0 +1,139 @@
+//! # Theme
+//!
+//! The accent color of the platform, which components use for their default selection
+//! colors, focus borders and checked states, so an application matches the platform look
+//! without configuring each of them.
+//!
+//! It is read from the system on Windows and macOS. Elsewhere, or to give the application a
+//! brand color, it can be set with [`set_accent_color`]. Components keep their own default
+//! colors while there is no accent color.
+//!
+//! Colors to draw on the accent and lighter or darker shades of it are derived with
+//! [`on_accent_color`] and [`accent_palette`].
+//!
+//! ```
+//! use tessera_ui::{Color, theme};
+//!
+//! theme::set_accent_color(Some(Color::from_srgb(0.9, 0.3, 0.1, 1.0)));
+//! let focus_border = theme::accent_or(Color::new(0.0, 0.5, 1.0, 1.0));
+//! assert_eq!(focus_border, theme::accent_color().unwrap());
+//! ```
+
+use std::sync::OnceLock;
+
+use parking_lot::RwLock;
+
+use crate::{Color, color::TonalPalette};
+
+static ACCENT_OVERRIDE: RwLock<Option<Color>> = RwLock::new(None);
+static SYSTEM_ACCENT: OnceLock<Option<Color>> = OnceLock::new();
+
+/// Returns the accent color set with [`set_accent_color`], or else the one of the platform.
+pub fn accent_color() -> Option<Color> {
+    ACCENT_OVERRIDE.read().or_else(system_accent_color)
+}
+
+/// Returns the [accent color](accent_color), or `fallback` if there is none.
+pub fn accent_or(fallback: Color) -> Color {
+    accent_color().unwrap_or(fallback)
+}
+
+/// Returns black or white, whichever contrasts more with the [accent color](accent_color),
+/// for check marks, text and icons drawn on it.
+pub fn on_accent_color() -> Option<Color> {
+    accent_color().map(Color::on_color)
+}
+
+/// Returns the [tonal palette](TonalPalette) of the [accent color](accent_color), for tinted
+/// containers and surfaces in the hue of the platform.
+pub fn accent_palette() -> Option<TonalPalette> {
+    accent_color().map(TonalPalette::new)
+}
+
+/// Overrides the accent color of the platform, or goes back to it with `None`.
+pub fn set_accent_color(accent: Option<Color>) {
+    *ACCENT_OVERRIDE.write() = accent;
+}
+
+/// Returns the accent color the user chose in the system settings, if the platform has one.
+///
+/// It is read once, on the first call.
+pub fn system_accent_color() -> Option<Color> {
+    *SYSTEM_ACCENT.get_or_init(detect_accent_color)
+}
+
+#[cfg(target_os = "windows")]
+fn detect_accent_color() -> Option<Color> {
+    use windows::{Win32::Graphics::Dwm::DwmGetColorizationColor, core::BOOL};
+
+    let mut colorization = 0u32;
+    let mut opaque = BOOL::default();
+    // The colorization color follows the accent color, as 0xAARRGGBB
+    unsafe { DwmGetColorizationColor(&mut colorization, &mut opaque) }.ok()?;
+    let channel = |shift: u32| ((colorization >> shift) & 0xff) as f32 / 255.0;
+    Some(Color::from_srgb(channel(16), channel(8), channel(0), 1.0))
+}
+
+#[cfg(target_os = "macos")]
+fn detect_accent_color() -> Option<Color> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleAccentColor"])
+        .output()
+        .ok()?;
+    // The key is missing while the default, multicolor accent is selected, which is blue
+    let accent = if output.status.success() {
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .ok()?
+    } else {
+        4
+    };
+    macos_accent_color(accent)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn detect_accent_color() -> Option<Color> {
+    None
+}
+
+/// Returns the color of an `AppleAccentColor` setting.
+#[cfg(any(target_os = "macos", test))]
+fn macos_accent_color(accent: i32) -> Option<Color> {
+    let [r, g, b] = match accent {
+        -1 => [0x98, 0x98, 0x98],
+        0 => [0xe0, 0x38, 0x3e],
+        1 => [0xf7, 0x82, 0x1b],
+        2 => [0xfc, 0xb8, 0x27],
+        3 => [0x62, 0xba, 0x46],
+        4 => [0x00, 0x7a, 0xff],
+        5 => [0x95, 0x3d, 0x96],
+        6 => [0xf7, 0x4f, 0x9e],
+        _ => return None,
+    };
+    let channel = |c: u8| f32::from(c) / 255.0;
+    Some(Color::from_srgb(channel(r), channel(g), channel(b), 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accent_color_override() {
+        let system = system_accent_color();
+        let accent = Color::new(0.2, 0.4, 0.6, 1.0);
+        set_accent_color(Some(accent));
+        assert_eq!(accent_color(), Some(accent));
+        assert_eq!(on_accent_color(), Some(Color::BLACK));
+        assert_eq!(accent_palette(), Some(TonalPalette::new(accent)));
+        set_accent_color(None);
+        assert_eq!(accent_color(), system);
+
+        assert_eq!(
+            macos_accent_color(4),
+            Some(Color::from_srgb(0.0, 122.0 / 255.0, 1.0, 1.0))
+        );
+        assert_eq!(macos_accent_color(7), None);
+    }
+}
@@ -0,0 +1,247 @@
+//! # Window Placement
+//!
+//! Types for choosing the monitor a window opens on and for restoring its size, position and
+//! maximized state across runs.
+//!
+//! Monitors are listed by [`TesseraRuntime::monitors`](crate::TesseraRuntime::monitors). A
+//! [`WindowPlacement`] is applied when the window is created, via
+//! [`TesseraConfig::window_placement`](crate::renderer::TesseraConfig::window_placement), or
+//! at any later frame via [`TesseraRuntime::place_window`](crate::TesseraRuntime::place_window).
+//!
+//! Borderless fullscreen is switched with
+//! [`TesseraRuntime::set_fullscreen`](crate::TesseraRuntime::set_fullscreen), or F11 by default.
+//!
+//! [`TesseraRuntime::window_geometry`](crate::TesseraRuntime::window_geometry) reports the
+//! current geometry. It converts to and from a short string, so it can be written wherever the
+//! application keeps its settings, typically from an
+//! [`on_close`](crate::TesseraRuntime::on_close) callback:
+//!
+//! ```
+//! use tessera_ui::{TesseraRuntime, window::WindowGeometry};
+//!
+//! let saved = "120,80,1280,720,0"; // e.g. read from a settings file
+//! let geometry: WindowGeometry = saved.parse().unwrap();
+//! assert_eq!(geometry.to_string(), saved);
+//!
+//! TesseraRuntime::with_mut(|rt| {
+//!     rt.on_close(|| {
+//!         if let Some(geometry) = TesseraRuntime::with(|rt| rt.window_geometry()) {
+//!             println!("save {geometry}");
+//!         }
+//!     })
+//! });
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    monitor::MonitorHandle,
+    window::{Fullscreen, Window},
+};
+
+/// A monitor connected to the system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorInfo {
+    /// Human-readable name of the monitor, if the platform provides one.
+    pub name: Option<String>,
+    /// Top-left corner of the monitor on the virtual desktop, in physical pixels.
+    pub position: [i32; 2],
+    /// Resolution of the monitor in physical pixels.
+    pub size: [u32; 2],
+    /// Scale factor of the monitor.
+    pub scale_factor: f64,
+    /// Whether this is the primary monitor.
+    pub is_primary: bool,
+}
+
+impl MonitorInfo {
+    fn new(handle: &MonitorHandle, primary: Option<&MonitorHandle>) -> Self {
+        let position = handle.position();
+        let size = handle.size();
+        Self {
+            name: handle.name(),
+            position: [position.x, position.y],
+            size: [size.width, size.height],
+            scale_factor: handle.scale_factor(),
+            is_primary: primary == Some(handle),
+        }
+    }
+
+    /// Returns `true` if the point `[x, y]` of the virtual desktop lies on this monitor.
+    pub fn contains(&self, [x, y]: [i32; 2]) -> bool {
+        let [left, top] = self.position;
+        x >= left
+            && y >= top
+            && i64::from(x) < i64::from(left) + i64::from(self.size[0])
+            && i64::from(y) < i64::from(top) + i64::from(self.size[1])
+    }
+}
+
+/// The size, position and maximized state of a window.
+///
+/// While the window is maximized or fullscreen, `position` and `size` keep the values it had
+/// before, so a
+/// restored window is maximized on the right monitor and returns to its old size when the
+/// user un-maximizes it.
+///
+/// It is written as `x,y,width,height,maximized`, e.g. `120,80,1280,720,0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowGeometry {
+    /// Top-left corner of the window frame on the virtual desktop, in physical pixels.
+    pub position: [i32; 2],
+    /// Size of the window contents in physical pixels.
+    pub size: [u32; 2],
+    /// Whether the window is maximized.
+    pub maximized: bool,
+}
+
+impl fmt::Display for WindowGeometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x, y] = self.position;
+        let [width, height] = self.size;
+        write!(f, "{x},{y},{width},{height},{}", u8::from(self.maximized))
+    }
+}
+
+/// Error returned when a string is not a valid [`WindowGeometry`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseWindowGeometryError;
+
+impl fmt::Display for ParseWindowGeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("expected window geometry as `x,y,width,height,maximized`")
+    }
+}
+
+impl std::error::Error for ParseWindowGeometryError {}
+
+impl FromStr for WindowGeometry {
+    type Err = ParseWindowGeometryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.trim().split(',').map(str::trim).collect();
+        let [x, y, width, height, maximized] = fields[..] else {
+            return Err(ParseWindowGeometryError);
+        };
+        let int = |v: &str| v.parse::<i32>().map_err(|_| ParseWindowGeometryError);
+        let uint = |v: &str| v.parse::<u32>().map_err(|_| ParseWindowGeometryError);
+        Ok(Self {
+            position: [int(x)?, int(y)?],
+            size: [uint(width)?, uint(height)?],
+            maximized: match maximized {
+                "0" => false,
+                "1" => true,
+                _ => return Err(ParseWindowGeometryError),
+            },
+        })
+    }
+}
+
+/// Where a window is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowPlacement {
+    /// Centered on the monitor with the given index in
+    /// [`TesseraRuntime::monitors`](crate::TesseraRuntime::monitors), keeping its size.
+    Monitor(usize),
+    /// Maximized on the monitor with the given index.
+    Maximized(usize),
+    /// A geometry saved earlier. A position that is on none of the connected monitors, e.g.
+    /// because the monitor was unplugged, is ignored so the window stays reachable.
+    Geometry(WindowGeometry),
+}
+
+/// Lists the monitors the window can be placed on.
+pub(crate) fn monitors(window: &Window) -> Vec<MonitorInfo> {
+    let primary = window.primary_monitor();
+    window
+        .available_monitors()
+        .map(|handle| MonitorInfo::new(&handle, primary.as_ref()))
+        .collect()
+}
+
+/// Moves and resizes `window` as `placement` says. Indices of monitors that are not connected
+/// leave the window where it is.
+pub(crate) fn apply_placement(window: &Window, placement: WindowPlacement) {
+    let monitors = monitors(window);
+    match placement {
+        WindowPlacement::Monitor(index) | WindowPlacement::Maximized(index) => {
+            let Some(monitor) = monitors.get(index) else {
+                return;
+            };
+            let maximized = matches!(placement, WindowPlacement::Maximized(_));
+            // A maximized window only moves between monitors while it is restored
+            window.set_maximized(false);
+            let outer = window.outer_size();
+            let [left, top] = monitor.position;
+            let offset = |monitor: u32, window: u32| (monitor.saturating_sub(window) / 2) as i32;
+            window.set_outer_position(PhysicalPosition::new(
+                left + offset(monitor.size[0], outer.width),
+                top + offset(monitor.size[1], outer.height),
+            ));
+            window.set_maximized(maximized);
+        }
+        WindowPlacement::Geometry(geometry) => {
+            window.set_maximized(false);
+            let [width, height] = geometry.size;
+            let _ = window.request_inner_size(PhysicalSize::new(width.max(1), height.max(1)));
+            if monitors
+                .iter()
+                .any(|monitor| monitor.contains(geometry.position))
+            {
+                let [x, y] = geometry.position;
+                window.set_outer_position(PhysicalPosition::new(x, y));
+            }
+            window.set_maximized(geometry.maximized);
+        }
+    }
+}
+
+/// Puts `window` into borderless fullscreen on its current monitor, or back into a window.
+pub(crate) fn set_fullscreen(window: &Window, fullscreen: bool) {
+    window.set_fullscreen(fullscreen.then_some(Fullscreen::Borderless(None)));
+}
+
+/// Returns the geometry of `window`, with the restored position and size taken from
+/// `previous` while it is maximized or fullscreen.
+pub(crate) fn current_geometry(
+    window: &Window,
+    previous: Option<WindowGeometry>,
+) -> Option<WindowGeometry> {
+    let maximized = window.is_maximized();
+    if window.fullscreen().is_some() {
+        return previous;
+    }
+    if maximized {
+        return previous.map(|geometry| WindowGeometry {
+            maximized,
+            ..geometry
+        });
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size();
+    Some(WindowGeometry {
+        position: [position.x, position.y],
+        size: [size.width, size.height],
+        maximized,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_geometry_round_trip() {
+        let geometry = WindowGeometry {
+            position: [-1920, 40],
+            size: [1280, 720],
+            maximized: true,
+        };
+        assert_eq!(geometry.to_string(), "-1920,40,1280,720,1");
+        assert_eq!("-1920, 40, 1280, 720, 1".parse(), Ok(geometry));
+        assert!("1,2,3,4".parse::<WindowGeometry>().is_err());
+        assert!("1,2,-3,4,0".parse::<WindowGeometry>().is_err());
+        assert!("1,2,3,4,yes".parse::<WindowGeometry>().is_err());
+    }
+}